@@ -3,7 +3,7 @@
 
 mod fake_context;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use aptos_api::{get_api_service, spec::get_spec};
 use clap::{Parser, ValueEnum};
 use fake_context::get_fake_context;
@@ -25,11 +25,29 @@ pub struct OutputArgs {
     /// What format to output the spec in.
     #[clap(short, long, ignore_case = true, value_enum, default_value_t = OutputFormat::Yaml)]
     pub format: OutputFormat,
+
+    /// Instead of writing the spec, verify that the spec at `output_path` is
+    /// identical to the generated spec. This is used to make sure the spec that
+    /// is checked in (and that the SDKs are generated from) is kept up to date.
+    #[clap(long, requires = "output_path")]
+    pub check: bool,
 }
 
 impl OutputArgs {
     pub fn write(&self, output: &str) -> Result<()> {
         match &self.output_path {
+            Some(path) if self.check => {
+                let existing = std::fs::read_to_string(path)?;
+                if existing.trim_end() != output.trim_end() {
+                    bail!(
+                        "The spec at {} is out of date, regenerate it with: \
+                        cargo run -p aptos-openapi-spec-generator -- -f {:?} -o {}",
+                        path.display(),
+                        self.format,
+                        path.display(),
+                    );
+                }
+            },
             Some(path) => std::fs::write(path, output)?,
             None => println!("{}", output),
         }
@@ -61,3 +79,25 @@ fn verify_tool() {
     use clap::CommandFactory;
     Args::command().debug_assert()
 }
+
+#[test]
+fn verify_examples() {
+    let api_service = get_api_service(Arc::new(get_fake_context()));
+    let spec: serde_json::Value = serde_json::from_str(&get_spec(&api_service, false)).unwrap();
+
+    // Verify that the schemas used by the mempool status endpoint carry examples
+    let schemas = &spec["components"]["schemas"];
+    for schema in ["HashValue", "TransactionMempoolStatus"] {
+        assert!(
+            !schemas[schema]["example"].is_null(),
+            "Schema {} has no example",
+            schema
+        );
+    }
+
+    // Verify that the hash example is a valid hash literal (i.e., 0x and 64 hex digits)
+    let hash_example = schemas["HashValue"]["example"].as_str().unwrap();
+    let hash_digits = hash_example.strip_prefix("0x").unwrap();
+    assert_eq!(hash_digits.len(), 64);
+    assert!(hash_digits.chars().all(|digit| digit.is_ascii_hexdigit()));
+}
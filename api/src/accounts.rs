@@ -8,15 +8,16 @@ use crate::{
     failpoint::fail_point_poem,
     page::determine_limit,
     response::{
-        account_not_found, resource_not_found, struct_field_not_found, BadRequestError,
-        BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404, InternalError,
+        account_not_found, api_disabled, resource_not_found, struct_field_not_found,
+        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
+        InternalError,
     },
     ApiTags,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    AccountData, Address, AptosErrorCode, AsConverter, LedgerInfo, MoveModuleBytecode,
-    MoveModuleId, MoveResource, MoveStructTag, StateKeyWrapper, U64,
+    AccountData, AccountStorageUsage, Address, AptosErrorCode, AsConverter, LedgerInfo,
+    MoveModuleBytecode, MoveModuleId, MoveResource, MoveStructTag, StateKeyWrapper, U64,
 };
 use aptos_types::{
     account_config::{AccountResource, ObjectGroupResource},
@@ -177,6 +178,50 @@ impl AccountsApi {
         })
         .await
     }
+
+    /// Get account storage usage
+    ///
+    /// Retrieves the number of resources and modules stored under an account, their total
+    /// size in bytes, and the storage deposits paid for them, at a specific ledger version.
+    /// If the ledger version is not specified in the request, the latest ledger version is used.
+    ///
+    /// The usage is maintained by the node's internal indexer, so this endpoint is only
+    /// available if the storage usage index is enabled (`indexer_db_config.enable_storage_usage`).
+    #[oai(
+        path = "/accounts/:address/storage_usage",
+        method = "get",
+        operation_id = "get_account_storage_usage",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_storage_usage(
+        &self,
+        accept_type: AcceptType,
+        /// Address of account with or without a `0x` prefix
+        address: Path<Address>,
+        /// Ledger version to get the storage usage of the account
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<AccountStorageUsage> {
+        fail_point_poem("endpoint_get_account_storage_usage")?;
+        self.context
+            .check_api_output_enabled("Get account storage usage", &accept_type)?;
+        if !self
+            .context
+            .node_config
+            .indexer_db_config
+            .enable_storage_usage()
+        {
+            return Err(api_disabled("Get account storage usage"));
+        }
+
+        let context = self.context.clone();
+        api_spawn_blocking(move || {
+            let account = Account::new(context, address.0, ledger_version.0, None, None, true)?;
+            account.storage_usage(&accept_type)
+        })
+        .await
+    }
 }
 
 /// A struct representing Account related lookups for resources and modules
@@ -314,6 +359,42 @@ impl Account {
         }
     }
 
+    /// Retrieves the storage usage of the account
+    ///
+    /// * JSON: Return a JSON encoded version of [`AccountStorageUsage`]
+    /// * BCS: Return a BCS encoded version of [`AccountStorageUsage`]
+    pub fn storage_usage(
+        self,
+        accept_type: &AcceptType,
+    ) -> BasicResultWith404<AccountStorageUsage> {
+        // check account exists
+        self.verify_account_or_object_resource()?;
+        let storage_usage = self
+            .context
+            .get_account_storage_usage(self.address.into(), self.ledger_version)
+            .context("Failed to retrieve the account storage usage")
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &self.latest_ledger_info,
+                )
+            })?;
+
+        match accept_type {
+            AcceptType::Json => BasicResponse::try_from_json((
+                storage_usage.into(),
+                &self.latest_ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+            AcceptType::Bcs => BasicResponse::try_from_bcs((
+                storage_usage,
+                &self.latest_ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+        }
+    }
+
     /// Retrieves the move resources associated with the account
     ///
     /// * JSON: Return a JSON encoded version of [`Vec<MoveResource>`]
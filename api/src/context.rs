@@ -16,11 +16,14 @@ use aptos_api_types::{
     AptosErrorCode, AsConverter, BcsBlock, GasEstimation, LedgerInfo, ResourceGroup,
     TransactionOnChainData,
 };
-use aptos_config::config::{NodeConfig, RoleType};
+use aptos_config::config::{ClientApiKey, NodeConfig, RoleType};
 use aptos_crypto::HashValue;
 use aptos_gas_schedule::{AptosGasParameters, FromOnChainGasSchedule};
 use aptos_logger::{error, info, Schema};
-use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use aptos_mempool::{
+    MempoolClientRequest, MempoolClientSender, MempoolLoadMonitor, MempoolLoadState,
+    MempoolTransactionStatus, SubmissionStatus,
+};
 use aptos_storage_interface::{
     state_view::{DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView},
     AptosDbError, DbReader, Order, MAX_REQUEST_LIMIT,
@@ -37,6 +40,7 @@ use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     on_chain_config::{GasSchedule, GasScheduleV2, OnChainConfig, OnChainExecutionConfig},
     state_store::{
+        account_storage_usage::AccountStorageUsage,
         state_key::{inner::StateKeyInner, prefix::StateKeyPrefix, StateKey},
         state_value::StateValue,
         TStateView,
@@ -70,6 +74,7 @@ pub struct Context {
     chain_id: ChainId,
     pub db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
+    mempool_load_monitor: MempoolLoadMonitor,
     pub node_config: Arc<NodeConfig>,
     gas_schedule_cache: Arc<RwLock<GasScheduleCache>>,
     gas_estimation_cache: Arc<RwLock<GasEstimationCache>>,
@@ -111,6 +116,7 @@ impl Context {
             chain_id,
             db,
             mp_sender,
+            mempool_load_monitor: MempoolLoadMonitor::default(),
             node_config: Arc::new(node_config),
             gas_schedule_cache: Arc::new(RwLock::new(GasScheduleCache {
                 last_updated_epoch: None,
@@ -134,6 +140,24 @@ impl Context {
         }
     }
 
+    /// Sets the handle used to query the mempool load state (for submission backpressure)
+    pub fn with_mempool_load_monitor(self, mempool_load_monitor: MempoolLoadMonitor) -> Self {
+        Self {
+            mempool_load_monitor,
+            ..self
+        }
+    }
+
+    /// Returns the latest load state of mempool
+    pub fn mempool_load_state(&self) -> MempoolLoadState {
+        self.mempool_load_monitor.get_load_state()
+    }
+
+    /// Returns the handle used to query the mempool load state
+    pub fn mempool_load_monitor(&self) -> &MempoolLoadMonitor {
+        &self.mempool_load_monitor
+    }
+
     pub fn max_transactions_page_size(&self) -> u16 {
         self.node_config.api.max_transactions_page_size
     }
@@ -204,6 +228,12 @@ impl Context {
         self.node_config.api.max_submit_transaction_batch_size
     }
 
+    pub fn max_transaction_mempool_status_batch_size(&self) -> usize {
+        self.node_config
+            .api
+            .max_transaction_mempool_status_batch_size
+    }
+
     pub async fn submit_transaction(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
         let (req_sender, callback) = oneshot::channel();
         self.mp_sender
@@ -214,6 +244,40 @@ impl Context {
         callback.await?
     }
 
+    /// Submits the transaction on behalf of the authenticated client with the given
+    /// identity (the submission is subject to the mempool quotas of the client).
+    pub async fn submit_transaction_from_client(
+        &self,
+        txn: SignedTransaction,
+        client_identity: String,
+    ) -> Result<SubmissionStatus> {
+        let (req_sender, callback) = oneshot::channel();
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::SubmitTransactionFromClient(
+                txn,
+                client_identity,
+                req_sender,
+            ))
+            .await?;
+
+        callback.await?
+    }
+
+    /// Returns the identity of the client that owns the given API key (if any).
+    /// The key hash is compared against every configured key hash in constant time,
+    /// so the response time doesn't reveal how much of a key matched.
+    pub fn client_identity(&self, api_key: &str) -> Option<String> {
+        let api_key_hash = ClientApiKey::hash_api_key(api_key);
+        let mut client_identity = None;
+        for client_api_key in &self.node_config.api.client_api_keys {
+            if constant_time_eq(&api_key_hash, &client_api_key.api_key_hash) {
+                client_identity = Some(client_api_key.identity.clone());
+            }
+        }
+        client_identity
+    }
+
     // For use from external crates where they don't want to handle
     // the API response error types.
     pub fn get_latest_ledger_info_wrapped(&self) -> anyhow::Result<LedgerInfo> {
@@ -348,6 +412,17 @@ impl Context {
             .map_err(|e| E::internal_with_code(e, AptosErrorCode::InternalError, ledger_info))
     }
 
+    pub fn get_account_storage_usage(
+        &self,
+        address: AccountAddress,
+        version: Version,
+    ) -> Result<AccountStorageUsage> {
+        self.indexer_reader
+            .as_ref()
+            .ok_or_else(|| format_err!("Indexer reader doesn't exist"))?
+            .get_account_storage_usage(address, version)
+    }
+
     pub fn get_resource<T: MoveResource>(
         &self,
         address: AccountAddress,
@@ -703,7 +778,9 @@ impl Context {
         }
 
         let state_view = self.latest_state_view_poem(ledger_info)?;
-        let converter = state_view.as_converter(self.db.clone(), self.indexer_reader.clone());
+        let converter = state_view
+            .as_converter(self.db.clone(), self.indexer_reader.clone())
+            .with_detailed_vm_status(self.node_config.api.detailed_vm_status_enabled);
         let txns: Vec<aptos_api_types::Transaction> = data
             .into_iter()
             .map(|t| {
@@ -735,7 +812,9 @@ impl Context {
         }
 
         let state_view = self.latest_state_view_poem(ledger_info)?;
-        let converter = state_view.as_converter(self.db.clone(), self.indexer_reader.clone());
+        let converter = state_view
+            .as_converter(self.db.clone(), self.indexer_reader.clone())
+            .with_detailed_vm_status(self.node_config.api.detailed_vm_status_enabled);
         let txns: Vec<aptos_api_types::Transaction> = data
             .into_iter()
             .map(|t| {
@@ -878,6 +957,23 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
+    pub async fn get_pending_transaction_statuses_by_hash(
+        &self,
+        hashes: Vec<HashValue>,
+    ) -> Result<Vec<MempoolTransactionStatus>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetTransactionStatusesByHash(
+                hashes, req_sender,
+            ))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
     pub fn get_transaction_by_version(
         &self,
         version: u64,
@@ -1546,3 +1642,13 @@ impl FunctionStats {
 fn db_sharding_enabled(node_config: &NodeConfig) -> bool {
     node_config.storage.rocksdb_configs.enable_storage_sharding
 }
+
+/// Compares the hashes without short-circuiting on the first differing byte
+fn constant_time_eq(hash: &HashValue, other_hash: &HashValue) -> bool {
+    hash.iter()
+        .zip(other_hash.iter())
+        .fold(0, |difference, (byte, other_byte)| {
+            difference | (byte ^ other_byte)
+        })
+        == 0
+}
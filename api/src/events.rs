@@ -16,8 +16,8 @@ use crate::{
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    verify_field_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper, LedgerInfo,
-    MoveStructTag, VerifyInputWithRecursion, VersionedEvent, U64,
+    verify_field_identifier, Address, AptosErrorCode, AsConverter, EventPayloadEncoding,
+    IdentifierWrapper, LedgerInfo, MoveStructTag, VerifyInputWithRecursion, VersionedEvent, U64,
 };
 use aptos_types::event::EventKey;
 use poem_openapi::{
@@ -64,6 +64,11 @@ impl EventsApi {
         ///
         /// If unspecified, defaults to default page size
         limit: Query<Option<u16>>,
+        /// How the event payloads are rendered in JSON responses: `decoded` (JSON
+        /// in `data`), `raw` (BCS bytes in `raw_data`, skipping the decoding) or `both`.
+        ///
+        /// If unspecified, defaults to `decoded`
+        event_payload: Query<Option<EventPayloadEncoding>>,
     ) -> BasicResultWith404<Vec<VersionedEvent>> {
         fail_point_poem("endpoint_get_events_by_event_key")?;
         self.context
@@ -84,6 +89,7 @@ impl EventsApi {
                 accept_type,
                 page,
                 EventKey::new(creation_number.0 .0, address.0.into()),
+                event_payload.0.unwrap_or_default(),
             )
         })
         .await
@@ -120,6 +126,11 @@ impl EventsApi {
         ///
         /// If unspecified, defaults to default page size
         limit: Query<Option<u16>>,
+        /// How the event payloads are rendered in JSON responses: `decoded` (JSON
+        /// in `data`), `raw` (BCS bytes in `raw_data`, skipping the decoding) or `both`.
+        ///
+        /// If unspecified, defaults to `decoded`
+        event_payload: Query<Option<EventPayloadEncoding>>,
     ) -> BasicResultWith404<Vec<VersionedEvent>> {
         event_handle
             .0
@@ -146,7 +157,13 @@ impl EventsApi {
         api_spawn_blocking(move || {
             let account = Account::new(api.context.clone(), address.0, None, None, None, true)?;
             let key = account.find_event_key(event_handle.0, field_name.0.into())?;
-            api.list(account.latest_ledger_info, accept_type, page, key)
+            api.list(
+                account.latest_ledger_info,
+                accept_type,
+                page,
+                key,
+                event_payload.0.unwrap_or_default(),
+            )
         })
         .await
     }
@@ -160,6 +177,7 @@ impl EventsApi {
         accept_type: AcceptType,
         page: Page,
         event_key: EventKey,
+        event_payload: EventPayloadEncoding,
     ) -> BasicResultWith404<Vec<VersionedEvent>> {
         let ledger_version = latest_ledger_info.version();
         let events = self
@@ -181,7 +199,17 @@ impl EventsApi {
 
         match accept_type {
             AcceptType::Json => {
-                let events = self
+                // Raw payloads don't need to be decoded, so skip the conversion entirely
+                if event_payload == EventPayloadEncoding::Raw {
+                    let events: Vec<_> = events.iter().map(VersionedEvent::new_raw).collect();
+                    return BasicResponse::try_from_json((
+                        events,
+                        &latest_ledger_info,
+                        BasicResponseStatus::Ok,
+                    ));
+                }
+
+                let mut versioned_events = self
                     .context
                     .latest_state_view_poem(&latest_ledger_info)?
                     .as_converter(self.context.db.clone(), self.context.indexer_reader.clone())
@@ -194,8 +222,19 @@ impl EventsApi {
                             &latest_ledger_info,
                         )
                     })?;
+                if event_payload == EventPayloadEncoding::Both {
+                    versioned_events = versioned_events
+                        .into_iter()
+                        .zip(events.iter())
+                        .map(|(versioned_event, event)| versioned_event.with_raw_data(event))
+                        .collect();
+                }
 
-                BasicResponse::try_from_json((events, &latest_ledger_info, BasicResponseStatus::Ok))
+                BasicResponse::try_from_json((
+                    versioned_events,
+                    &latest_ledger_info,
+                    BasicResponseStatus::Ok,
+                ))
             },
             AcceptType::Bcs => {
                 BasicResponse::try_from_bcs((events, &latest_ledger_info, BasicResponseStatus::Ok))
@@ -18,6 +18,7 @@ mod index;
 mod log;
 pub mod metrics;
 mod page;
+mod rate_limit;
 mod response;
 mod runtime;
 mod set_failpoints;
@@ -105,3 +105,12 @@ pub static WAIT_TRANSACTION_POLL_TIME: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static RATE_LIMITED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_api_rate_limited_requests",
+        "Number of API requests rejected by the rate limiter, grouped by client type",
+        &["client_type"]
+    )
+    .unwrap()
+});
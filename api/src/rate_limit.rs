@@ -0,0 +1,536 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-client request rate limiting. Clients that provide a valid API key are limited
+//! per key, and all other clients are limited per IP address. Requests are counted in
+//! fixed windows, and every response reports the remaining budget of the client (via
+//! the `X-RateLimit-*` headers), so that clients can back off before being rejected.
+//! Requests from trusted proxies (e.g., load balancers) are attributed to the client IP
+//! address in the `X-Forwarded-For` header.
+
+use crate::{context::Context, error_converter::convert_error, metrics};
+use aptos_api_types::{
+    AptosError, AptosErrorCode, X_APTOS_API_KEY, X_RATELIMIT_LIMIT, X_RATELIMIT_REMAINING,
+    X_RATELIMIT_RESET,
+};
+use aptos_config::config::ApiRateLimitConfig;
+use aptos_infallible::Mutex;
+use mini_moka::sync::Cache;
+use poem::{
+    handler,
+    http::{header::RETRY_AFTER, HeaderMap, HeaderValue, StatusCode},
+    web::{Data, Query},
+    Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+use poem_openapi::payload::Json;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The maximum number of rate limit windows held (beyond which windows are evicted)
+const MAX_TRACKED_WINDOWS: u64 = 100_000;
+
+/// The header used by proxies to forward the address of the client
+const X_FORWARDED_FOR: &str = "X-Forwarded-For";
+
+/// The client that a request is attributed to (for rate limiting)
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum RateLimitedClient {
+    /// A client authenticated by API key (with the given identity)
+    ApiKey(String),
+    /// An anonymous client (with the given IP address)
+    Ip(IpAddr),
+}
+
+impl RateLimitedClient {
+    /// Identifies the client of the given request. Returns None if the client
+    /// didn't provide a valid API key, and has no IP address (e.g., in tests).
+    fn from_request(
+        context: &Context,
+        request: &Request,
+        trusted_proxies: &[IpAddr],
+    ) -> Option<Self> {
+        let client_identity = request
+            .headers()
+            .get(X_APTOS_API_KEY)
+            .and_then(|api_key| api_key.to_str().ok())
+            .and_then(|api_key| context.client_identity(api_key));
+        match client_identity {
+            Some(client_identity) => Some(Self::ApiKey(client_identity)),
+            None => request.remote_addr().as_socket_addr().map(|address| {
+                Self::Ip(get_client_ip(
+                    address.ip(),
+                    request.headers(),
+                    trusted_proxies,
+                ))
+            }),
+        }
+    }
+
+    /// Returns the client type (used for metrics)
+    fn get_type_label(&self) -> &'static str {
+        match self {
+            Self::ApiKey(_) => "api_key",
+            Self::Ip(_) => "ip",
+        }
+    }
+}
+
+impl Display for RateLimitedClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ApiKey(client_identity) => write!(f, "api_key:{}", client_identity),
+            Self::Ip(ip_address) => write!(f, "ip:{}", ip_address),
+        }
+    }
+}
+
+/// Returns the IP address of the client of a request (from the given remote address).
+/// If the request is from a trusted proxy, the forwarded addresses are walked from the
+/// closest hop, and the first address that isn't a trusted proxy is the client address.
+fn get_client_ip(remote_ip: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> IpAddr {
+    let mut client_ip = remote_ip;
+    if !trusted_proxies.contains(&client_ip) {
+        return client_ip;
+    }
+
+    let forwarded_ips: Vec<&str> = headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|header| header.split(','))
+        .collect();
+    for forwarded_ip in forwarded_ips.into_iter().rev() {
+        match forwarded_ip.trim().parse::<IpAddr>() {
+            Ok(forwarded_ip) => {
+                client_ip = forwarded_ip;
+                if !trusted_proxies.contains(&client_ip) {
+                    break;
+                }
+            },
+            Err(_) => break, // The remaining addresses can't be trusted
+        }
+    }
+    client_ip
+}
+
+/// The rate limit budget of a client (in the current window)
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RateLimitStatus {
+    /// The maximum number of requests in the window
+    pub limit: u64,
+    /// The number of requests remaining in the window
+    pub remaining: u64,
+    /// The number of seconds until the window resets
+    pub reset_secs: u64,
+}
+
+/// The number of requests made by a client in the current window
+#[derive(Debug)]
+struct RateLimitWindow {
+    start_time: Instant,
+    num_requests: u64,
+}
+
+/// The key of a rate limit window (i.e., the client and route override)
+type RateLimitWindowKey = (RateLimitedClient, Option<usize>);
+
+/// Tracks the requests of each client (per route override), and enforces the rate limits.
+/// The windows are held in a bounded, concurrent cache, and expire with the window duration.
+pub struct RateLimiter {
+    config: ApiRateLimitConfig,
+    windows: Cache<RateLimitWindowKey, Arc<Mutex<RateLimitWindow>>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: ApiRateLimitConfig) -> Self {
+        let windows = Cache::builder()
+            .max_capacity(MAX_TRACKED_WINDOWS)
+            .time_to_live(Duration::from_secs(config.window_secs))
+            .build();
+        Self { config, windows }
+    }
+
+    /// Returns true iff the rate limits are enforced
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Records a request from the client to the given path, and returns the remaining
+    /// budget of the client. If the client exceeded its limit, the request is not
+    /// counted and the (exhausted) budget is returned as an error.
+    pub fn record_request(
+        &self,
+        client: &RateLimitedClient,
+        path: &str,
+    ) -> Result<RateLimitStatus, RateLimitStatus> {
+        self.record_request_at(client, path, Instant::now())
+    }
+
+    /// Returns the remaining budget of the client for the given path
+    /// (without counting a request).
+    pub fn get_status(&self, client: &RateLimitedClient, path: &str) -> RateLimitStatus {
+        self.get_status_at(client, path, Instant::now())
+    }
+
+    fn record_request_at(
+        &self,
+        client: &RateLimitedClient,
+        path: &str,
+        now: Instant,
+    ) -> Result<RateLimitStatus, RateLimitStatus> {
+        let route_override = self.get_route_override(path);
+        let limit = self.get_limit(client, route_override);
+        let window_duration = self.get_window_duration();
+
+        // Fetch the window of the client (starting a new window if the previous one expired)
+        let window = self.get_or_create_window((client.clone(), route_override), now);
+        let mut window = window.lock();
+        if now.duration_since(window.start_time) >= window_duration {
+            *window = RateLimitWindow {
+                start_time: now,
+                num_requests: 0,
+            };
+        }
+
+        // Count the request (if the client is within its limit)
+        let within_limit = window.num_requests < limit;
+        if within_limit {
+            window.num_requests += 1;
+        }
+        let status = self.create_status(&window, limit, now);
+        if within_limit {
+            Ok(status)
+        } else {
+            Err(status)
+        }
+    }
+
+    fn get_status_at(
+        &self,
+        client: &RateLimitedClient,
+        path: &str,
+        now: Instant,
+    ) -> RateLimitStatus {
+        let route_override = self.get_route_override(path);
+        let limit = self.get_limit(client, route_override);
+
+        let window = self.windows.get(&(client.clone(), route_override));
+        match window.as_ref().map(|window| window.lock()) {
+            Some(window) if now.duration_since(window.start_time) < self.get_window_duration() => {
+                self.create_status(&window, limit, now)
+            },
+            _ => RateLimitStatus {
+                limit,
+                remaining: limit,
+                reset_secs: self.config.window_secs,
+            },
+        }
+    }
+
+    /// Returns the window with the given key, or a new window (starting now). Note:
+    /// concurrent first requests of a client may each create a window (and only the
+    /// last one is kept), so at most a few requests of the client go uncounted.
+    fn get_or_create_window(
+        &self,
+        window_key: RateLimitWindowKey,
+        now: Instant,
+    ) -> Arc<Mutex<RateLimitWindow>> {
+        if let Some(window) = self.windows.get(&window_key) {
+            return window;
+        }
+        let window = Arc::new(Mutex::new(RateLimitWindow {
+            start_time: now,
+            num_requests: 0,
+        }));
+        self.windows.insert(window_key, window.clone());
+        window
+    }
+
+    /// Returns the addresses of the trusted proxies
+    fn get_trusted_proxies(&self) -> &[IpAddr] {
+        &self.config.trusted_proxies
+    }
+
+    /// Returns the budget of the given window
+    fn create_status(&self, window: &RateLimitWindow, limit: u64, now: Instant) -> RateLimitStatus {
+        let time_until_reset = self
+            .get_window_duration()
+            .saturating_sub(now.duration_since(window.start_time));
+        RateLimitStatus {
+            limit,
+            remaining: limit.saturating_sub(window.num_requests),
+            reset_secs: time_until_reset.as_secs_f64().ceil() as u64,
+        }
+    }
+
+    /// Returns the limit of the client (for the given route override, if any)
+    fn get_limit(&self, client: &RateLimitedClient, route_override: Option<usize>) -> u64 {
+        let route_override =
+            route_override.and_then(|index| self.config.route_overrides.get(index));
+        match (client, route_override) {
+            (RateLimitedClient::ApiKey(_), Some(route_override)) => {
+                route_override.max_requests_per_api_key
+            },
+            (RateLimitedClient::Ip(_), Some(route_override)) => route_override.max_requests_per_ip,
+            (RateLimitedClient::ApiKey(_), None) => self.config.max_requests_per_api_key,
+            (RateLimitedClient::Ip(_), None) => self.config.max_requests_per_ip,
+        }
+    }
+
+    /// Returns the index of the route override that applies to the given
+    /// path (i.e., the override with the longest matching prefix), if any.
+    fn get_route_override(&self, path: &str) -> Option<usize> {
+        self.config
+            .route_overrides
+            .iter()
+            .enumerate()
+            .filter(|(_, route_override)| path.starts_with(&route_override.path_prefix))
+            .max_by_key(|(_, route_override)| route_override.path_prefix.len())
+            .map(|(index, _)| index)
+    }
+
+    fn get_window_duration(&self) -> Duration {
+        Duration::from_secs(self.config.window_secs)
+    }
+}
+
+/// This middleware enforces the rate limits of each client, and adds the
+/// remaining budget of the client to the response headers.
+pub struct RateLimit {
+    context: Arc<Context>,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl RateLimit {
+    pub fn new(context: Arc<Context>, rate_limiter: Arc<RateLimiter>) -> Self {
+        Self {
+            context,
+            rate_limiter,
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for RateLimit {
+    type Output = RateLimitEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RateLimitEndpoint {
+            inner: ep,
+            context: self.context.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+        }
+    }
+}
+
+/// Endpoint for RateLimit middleware.
+pub struct RateLimitEndpoint<E> {
+    inner: E,
+    context: Arc<Context>,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl<E: Endpoint> Endpoint for RateLimitEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        // Identify the client (if rate limiting is enabled)
+        let client = if self.rate_limiter.is_enabled() {
+            RateLimitedClient::from_request(
+                &self.context,
+                &req,
+                self.rate_limiter.get_trusted_proxies(),
+            )
+        } else {
+            None
+        };
+        let Some(client) = client else {
+            return Ok(self.inner.call(req).await?.into_response());
+        };
+
+        // Count the request, and reject it if the client exceeded its limit
+        let status = match self.rate_limiter.record_request(&client, req.uri().path()) {
+            Ok(status) => status,
+            Err(status) => {
+                metrics::RATE_LIMITED_REQUESTS
+                    .with_label_values(&[client.get_type_label()])
+                    .inc();
+                let error = AptosError::new_with_error_code(
+                    format!(
+                        "Rate limit exceeded! The limit resets in {} seconds.",
+                        status.reset_secs
+                    ),
+                    AptosErrorCode::RateLimited,
+                );
+                let mut response = Json(error)
+                    .with_status(StatusCode::TOO_MANY_REQUESTS)
+                    .into_response();
+                response
+                    .headers_mut()
+                    .insert(RETRY_AFTER, HeaderValue::from(status.reset_secs));
+                add_rate_limit_headers(&mut response, &status);
+                return Ok(response);
+            },
+        };
+
+        // Convert any errors into responses (so that the headers are added to them too)
+        let mut response = match self.inner.call(req).await {
+            Ok(response) => response.into_response(),
+            Err(error) => convert_error(error).await.into_response(),
+        };
+        add_rate_limit_headers(&mut response, &status);
+        Ok(response)
+    }
+}
+
+/// Adds the rate limit budget of the client to the response headers
+fn add_rate_limit_headers(response: &mut Response, status: &RateLimitStatus) {
+    let headers = response.headers_mut();
+    headers.insert(X_RATELIMIT_LIMIT, HeaderValue::from(status.limit));
+    headers.insert(X_RATELIMIT_REMAINING, HeaderValue::from(status.remaining));
+    headers.insert(X_RATELIMIT_RESET, HeaderValue::from(status.reset_secs));
+}
+
+#[derive(Deserialize)]
+pub struct ClientQuotaQuery {
+    /// The route to report the budget for (defaults to the routes without overrides)
+    route: Option<String>,
+}
+
+/// The rate limit budget of the calling client
+#[derive(Serialize)]
+pub struct ClientQuota {
+    /// Whether rate limits are enforced
+    enabled: bool,
+    /// The client the request was attributed to (e.g., "ip:127.0.0.1")
+    client: Option<String>,
+    /// The route the budget applies to
+    route: String,
+    /// The current budget of the client (if rate limits are enforced)
+    budget: Option<RateLimitStatus>,
+}
+
+/// Returns the current rate limit budget of the caller (for the given route)
+#[handler]
+pub fn client_quota_poem(
+    req: &Request,
+    context: Data<&Arc<Context>>,
+    rate_limiter: Data<&Arc<RateLimiter>>,
+    Query(query): Query<ClientQuotaQuery>,
+) -> poem::web::Json<ClientQuota> {
+    let client = RateLimitedClient::from_request(&context, req, rate_limiter.get_trusted_proxies());
+    let route = query.route.unwrap_or_else(|| "/v1".into());
+    let budget = match &client {
+        Some(client) if rate_limiter.is_enabled() => Some(rate_limiter.get_status(client, &route)),
+        _ => None,
+    };
+    poem::web::Json(ClientQuota {
+        enabled: rate_limiter.is_enabled(),
+        client: client.map(|client| client.to_string()),
+        route,
+        budget,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_client_ip, RateLimitedClient, RateLimiter, X_FORWARDED_FOR};
+    use aptos_config::config::{ApiRateLimitConfig, RouteRateLimitConfig};
+    use poem::http::{HeaderMap, HeaderValue};
+    use std::{
+        net::IpAddr,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn test_get_client_ip() {
+        let proxy_ip = IpAddr::from([10, 0, 0, 1]);
+        let client_ip = IpAddr::from([1, 2, 3, 4]);
+        let spoofed_ip = IpAddr::from([5, 6, 7, 8]);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            X_FORWARDED_FOR,
+            HeaderValue::from_str(&format!("{}, {}, {}", spoofed_ip, client_ip, proxy_ip)).unwrap(),
+        );
+
+        // Verify that forwarded addresses are ignored for untrusted remote addresses
+        assert_eq!(get_client_ip(client_ip, &headers, &[proxy_ip]), client_ip);
+        assert_eq!(get_client_ip(proxy_ip, &headers, &[]), proxy_ip);
+
+        // Verify that the first untrusted forwarded address (from the closest hop) is used
+        assert_eq!(get_client_ip(proxy_ip, &headers, &[proxy_ip]), client_ip);
+
+        // Verify that invalid forwarded addresses stop the walk
+        headers.insert(
+            X_FORWARDED_FOR,
+            HeaderValue::from_str(&format!("{}, invalid, {}", client_ip, proxy_ip)).unwrap(),
+        );
+        assert_eq!(get_client_ip(proxy_ip, &headers, &[proxy_ip]), proxy_ip);
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        // Create a rate limiter with a route override
+        let rate_limiter = RateLimiter::new(ApiRateLimitConfig {
+            enabled: true,
+            window_secs: 10,
+            max_requests_per_ip: 2,
+            max_requests_per_api_key: 3,
+            route_overrides: vec![RouteRateLimitConfig {
+                path_prefix: "/v1/transactions".into(),
+                max_requests_per_ip: 1,
+                max_requests_per_api_key: 1,
+            }],
+            trusted_proxies: vec![],
+        });
+        let ip_client = RateLimitedClient::Ip(IpAddr::from([127, 0, 0, 1]));
+        let api_key_client = RateLimitedClient::ApiKey("client".into());
+
+        // Exhaust the budget of the IP client and verify further requests are rejected
+        let now = Instant::now();
+        let status = rate_limiter
+            .record_request_at(&ip_client, "/v1/accounts", now)
+            .unwrap();
+        assert_eq!(
+            (status.limit, status.remaining, status.reset_secs),
+            (2, 1, 10)
+        );
+        rate_limiter
+            .record_request_at(&ip_client, "/v1", now)
+            .unwrap();
+        let status = rate_limiter
+            .record_request_at(&ip_client, "/v1", now)
+            .unwrap_err();
+        assert_eq!(status.remaining, 0);
+
+        // Verify the route override and API key client have separate budgets
+        let status = rate_limiter
+            .record_request_at(&ip_client, "/v1/transactions/batch", now)
+            .unwrap();
+        assert_eq!((status.limit, status.remaining), (1, 0));
+        let status = rate_limiter
+            .record_request_at(&api_key_client, "/v1", now)
+            .unwrap();
+        assert_eq!((status.limit, status.remaining), (3, 2));
+
+        // Verify that fetching the status doesn't count a request
+        let status = rate_limiter.get_status_at(&api_key_client, "/v1", now);
+        assert_eq!(status.remaining, 2);
+
+        // Elapse part of the window and verify the reset time
+        let now = now + Duration::from_millis(2_500);
+        let status = rate_limiter.get_status_at(&ip_client, "/v1", now);
+        assert_eq!((status.remaining, status.reset_secs), (0, 8));
+
+        // Elapse the window and verify the budget is reset
+        let now = now + Duration::from_secs(10);
+        let status = rate_limiter
+            .record_request_at(&ip_client, "/v1", now)
+            .unwrap();
+        assert_eq!((status.remaining, status.reset_secs), (1, 10));
+    }
+}
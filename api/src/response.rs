@@ -149,6 +149,8 @@ macro_rules! generate_error_response {
                 #[oai(header = "X-Aptos-Oldest-Block-Height")] Option<u64>,
                 /// The cost of the call in terms of gas
                 #[oai(header = "X-Aptos-Gas-Used")] Option<u64>,
+                /// The suggested delay (in seconds) before retrying the request
+                #[oai(header = "Retry-After")] Option<u64>,
             ),
             )*
         }
@@ -177,6 +179,7 @@ macro_rules! generate_error_response {
                     Some(ledger_info.block_height.into()),
                     Some(ledger_info.oldest_block_height.into()),
                     None,
+                    None,
                 ))
             }
 
@@ -197,6 +200,7 @@ macro_rules! generate_error_response {
                     None,
                     None,
                     None,
+                    None,
                 ))
             }
 
@@ -218,6 +222,7 @@ macro_rules! generate_error_response {
                     Some(ledger_info.block_height.into()),
                     Some(ledger_info.oldest_block_height.into()),
                     None,
+                    None,
                 ))
             }
 
@@ -236,6 +241,7 @@ macro_rules! generate_error_response {
                     Some(ledger_info.block_height.into()),
                     Some(ledger_info.oldest_block_height.into()),
                     None,
+                    None,
                 ))
             }
         }
@@ -256,12 +262,26 @@ macro_rules! generate_error_response {
                         _block_height,
                         _oldest_block_height,
                         _gas_used,
+                        _retry_after,
                     ) => &mut *inner,
                     )*
                 }
             }
         }
 
+        impl $enum_name {
+            /// Sets the suggested delay (in seconds) before retrying the request
+            #[allow(unused)]
+            pub fn with_retry_after(mut self, retry_after_secs: u64) -> Self {
+                match &mut self {
+                    $(
+                    $enum_name::$name(.., retry_after) => *retry_after = Some(retry_after_secs),
+                    )*
+                }
+                self
+            }
+        }
+
         impl std::error::Error for $enum_name {}
 
         impl std::fmt::Display for $enum_name {
@@ -514,7 +534,8 @@ generate_error_traits!(
     PayloadTooLarge,
     Internal,
     InsufficientStorage,
-    ServiceUnavailable
+    ServiceUnavailable,
+    TooManyRequests
 );
 
 // Group these common errors together
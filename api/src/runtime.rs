@@ -12,6 +12,7 @@ use crate::{
     events::EventsApi,
     index::IndexApi,
     log::middleware_log,
+    rate_limit::{self, RateLimit, RateLimiter},
     set_failpoints,
     spec::{spec_endpoint_json, spec_endpoint_yaml},
     state::StateApi,
@@ -21,7 +22,7 @@ use crate::{
 use anyhow::Context as AnyhowContext;
 use aptos_config::config::{ApiConfig, NodeConfig};
 use aptos_logger::info;
-use aptos_mempool::MempoolClientSender;
+use aptos_mempool::{MempoolClientSender, MempoolLoadMonitor};
 use aptos_storage_interface::DbReader;
 use aptos_types::{chain_id::ChainId, indexer::indexer_db_reader::IndexerReader};
 use poem::{
@@ -45,52 +46,66 @@ pub fn bootstrap(
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     indexer_reader: Option<Arc<dyn IndexerReader>>,
+    mempool_load_monitor: MempoolLoadMonitor,
 ) -> anyhow::Result<Runtime> {
     let max_runtime_workers = get_max_runtime_workers(&config.api);
-    let runtime = aptos_runtimes::spawn_named_runtime("api".into(), Some(max_runtime_workers));
+    // The API is not safety-critical, so panics are contained by the supervisor
+    let runtime =
+        aptos_runtimes::spawn_supervised_runtime("api".into(), "api", Some(max_runtime_workers));
 
-    let context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader);
+    let context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader)
+        .with_mempool_load_monitor(mempool_load_monitor);
 
     attach_poem_to_runtime(runtime.handle(), context.clone(), config, false)
         .context("Failed to attach poem to runtime")?;
 
     let context_cloned = context.clone();
     if let Some(period_ms) = config.api.periodic_gas_estimation_ms {
-        runtime.spawn(async move {
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(period_ms));
-            loop {
-                interval.tick().await;
-                let context_cloned = context_cloned.clone();
-                tokio::task::spawn_blocking(move || {
-                    if let Ok(latest_ledger_info) =
-                        context_cloned.get_latest_ledger_info::<crate::response::BasicError>()
-                    {
-                        if let Ok(gas_estimation) = context_cloned
-                            .estimate_gas_price::<crate::response::BasicError>(&latest_ledger_info)
+        aptos_runtimes::spawn_supervised(runtime.handle(), "api", move || {
+            let context_cloned = context_cloned.clone();
+            async move {
+                let mut interval =
+                    tokio::time::interval(tokio::time::Duration::from_millis(period_ms));
+                loop {
+                    interval.tick().await;
+                    let context_cloned = context_cloned.clone();
+                    tokio::task::spawn_blocking(move || {
+                        if let Ok(latest_ledger_info) =
+                            context_cloned.get_latest_ledger_info::<crate::response::BasicError>()
                         {
-                            TransactionsApi::log_gas_estimation(&gas_estimation);
+                            if let Ok(gas_estimation) = context_cloned
+                                .estimate_gas_price::<crate::response::BasicError>(
+                                    &latest_ledger_info,
+                                )
+                            {
+                                TransactionsApi::log_gas_estimation(&gas_estimation);
+                            }
                         }
-                    }
-                })
-                .await
-                .unwrap_or(());
+                    })
+                    .await
+                    .unwrap_or(());
+                }
             }
         });
     }
 
     let context_cloned = context.clone();
     if let Some(period_sec) = config.api.periodic_function_stats_sec {
-        runtime.spawn(async move {
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(period_sec));
-            loop {
-                interval.tick().await;
-                let context_cloned = context_cloned.clone();
-                tokio::task::spawn_blocking(move || {
-                    context_cloned.view_function_stats().log_and_clear();
-                    context_cloned.simulate_txn_stats().log_and_clear();
-                })
-                .await
-                .unwrap_or(());
+        aptos_runtimes::spawn_supervised(runtime.handle(), "api", move || {
+            let context_cloned = context_cloned.clone();
+            async move {
+                let mut interval =
+                    tokio::time::interval(tokio::time::Duration::from_secs(period_sec));
+                loop {
+                    interval.tick().await;
+                    let context_cloned = context_cloned.clone();
+                    tokio::task::spawn_blocking(move || {
+                        context_cloned.view_function_stats().log_and_clear();
+                        context_cloned.simulate_txn_stats().log_and_clear();
+                    })
+                    .await
+                    .unwrap_or(());
+                }
             }
         });
     }
@@ -171,6 +186,7 @@ pub fn attach_poem_to_runtime(
     let context = Arc::new(context);
 
     let size_limit = context.content_length_limit();
+    let rate_limiter = Arc::new(RateLimiter::new(config.api.rate_limit.clone()));
 
     let api_service = get_api_service(context.clone());
 
@@ -197,7 +213,15 @@ pub fn attach_poem_to_runtime(
                 tls_key_path
             ))?;
             let rustls_certificate = RustlsCertificate::new().cert(cert).key(key);
-            let rustls_config = RustlsConfig::new().fallback(rustls_certificate);
+            let mut rustls_config = RustlsConfig::new().fallback(rustls_certificate);
+            if let Some(tls_client_ca_path) = &config.api.tls_client_ca_path {
+                info!("Using mutual TLS for API");
+                let client_ca = std::fs::read(tls_client_ca_path).context(format!(
+                    "Failed to read TLS client CA from path: {}",
+                    tls_client_ca_path
+                ))?;
+                rustls_config = rustls_config.client_auth_required(client_ca);
+            }
             TcpListener::bind(address).rustls(rustls_config).boxed()
         },
         _ => {
@@ -238,8 +262,17 @@ pub fn attach_poem_to_runtime(
                     .at(
                         "/set_failpoint",
                         poem::get(set_failpoints::set_failpoint_poem).data(context.clone()),
+                    )
+                    .at(
+                        "/client/quota",
+                        poem::get(rate_limit::client_quota_poem)
+                            .data(context.clone())
+                            .data(rate_limiter.clone()),
                     ),
             )
+            // The rate limit is applied inside CORS (so that rejected requests
+            // carry the CORS headers). It converts errors into responses itself.
+            .with(RateLimit::new(context.clone(), rate_limiter))
             .with(cors)
             .with(PostSizeLimit::new(size_limit))
             // NOTE: Make sure to keep this after all the `with` middleware.
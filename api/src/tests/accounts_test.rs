@@ -406,6 +406,59 @@ async fn test_get_account_items_limit_params() {
     assert_eq!(resp.status(), 400);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_storage_usage() {
+    // Verify the storage usage isn't available without the internal indexer
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(403)
+        .get(&account_storage_usage("0x1"))
+        .await;
+
+    // Create a new account
+    let mut context =
+        new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn.clone()]).await;
+    if let Some(indexer_reader) = context.context.indexer_reader.as_ref() {
+        indexer_reader.wait_for_internal_indexer(2).unwrap();
+    }
+
+    // Verify the storage usage of the framework account
+    let storage_usage = context.get(&account_storage_usage("0x1")).await;
+    assert!(parse_u64(&storage_usage["resource_count"]) > 0);
+    assert!(parse_u64(&storage_usage["module_count"]) > 0);
+    assert!(parse_u64(&storage_usage["total_bytes"]) > 0);
+
+    // Verify the storage usage of the new account
+    let address = account.address().to_hex_literal();
+    let storage_usage = context.get(&account_storage_usage(&address)).await;
+    assert!(parse_u64(&storage_usage["resource_count"]) > 0);
+    assert_eq!(parse_u64(&storage_usage["module_count"]), 0);
+    assert_eq!(
+        parse_u64(&storage_usage["total_deposit"]),
+        parse_u64(&storage_usage["slot_deposit"]) + parse_u64(&storage_usage["bytes_deposit"])
+    );
+
+    // Verify the new account didn't exist at genesis
+    context
+        .expect_status_code(404)
+        .get(&format!(
+            "{}?ledger_version=0",
+            account_storage_usage(&address)
+        ))
+        .await;
+}
+
+fn parse_u64(value: &serde_json::Value) -> u64 {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+fn account_storage_usage(address: &str) -> String {
+    format!("/accounts/{}/storage_usage", address)
+}
+
 fn account_resources(address: &str) -> String {
     format!("/accounts/{}/resources", address)
 }
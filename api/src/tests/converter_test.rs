@@ -3,10 +3,21 @@
 
 use super::new_test_context;
 use aptos_api_test_context::current_function_name;
-use aptos_api_types::{new_vm_utf8_string, AsConverter, HexEncodedBytes, MoveConverter, MoveType};
-use aptos_types::state_store::StateView;
+use aptos_api_types::{
+    new_vm_utf8_string, AsConverter, HexEncodedBytes, MoveConverter, MoveType,
+    TransactionOnChainData, UserTransaction,
+};
+use aptos_crypto::HashValue;
+use aptos_types::{
+    state_store::StateView,
+    transaction::{ExecutionStatus, Transaction, TransactionInfo},
+    vm_status::AbortLocation,
+    write_set::WriteSet,
+};
 use move_core_types::{
     account_address::AccountAddress,
+    ident_str,
+    language_storage::ModuleId,
     value::{MoveStruct, MoveValue as VmMoveValue},
 };
 use serde::Serialize;
@@ -56,6 +67,82 @@ async fn test_value_conversion() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_detailed_vm_status() {
+    let mut context = new_test_context(current_function_name!());
+    let state_view = context.latest_state_view();
+
+    // Create a transfer that aborted in 0x1::coin (with EINSUFFICIENT_BALANCE)
+    let mut sender = context.gen_account();
+    let receiver = context.gen_account();
+    let txn = context.account_transfer(&mut sender, &receiver, 1);
+    let status = ExecutionStatus::MoveAbort {
+        location: AbortLocation::Module(ModuleId::new(
+            AccountAddress::ONE,
+            ident_str!("coin").to_owned(),
+        )),
+        code: 0x10006,
+        info: None,
+    };
+    let data = TransactionOnChainData {
+        version: context.get_latest_ledger_info().version(),
+        transaction: Transaction::UserTransaction(txn),
+        info: TransactionInfo::new(
+            HashValue::zero(),
+            HashValue::zero(),
+            HashValue::zero(),
+            None,
+            0,
+            status,
+        ),
+        events: vec![],
+        accumulator_root_hash: HashValue::zero(),
+        changes: WriteSet::default(),
+    };
+
+    // Verify there are no VM status details by default
+    let converter = state_view.as_converter(context.db.clone(), None);
+    let user_txn = into_user_transaction(&converter, data.clone());
+    assert_eq!(user_txn.info.vm_status, "Move abort in 0x1::coin: 0x10006");
+    assert_eq!(user_txn.vm_status_details, None);
+
+    // Verify the VM status details resolve the abort code (without changing the VM status)
+    let converter = state_view
+        .as_converter(context.db.clone(), None)
+        .with_detailed_vm_status(true);
+    let user_txn = into_user_transaction(&converter, data);
+    assert_eq!(user_txn.info.vm_status, "Move abort in 0x1::coin: 0x10006");
+    let vm_status_details = user_txn.vm_status_details.unwrap();
+    assert_eq!(
+        vm_status_details.entry_function.unwrap().to_string(),
+        "0x1::aptos_account::transfer"
+    );
+    assert_eq!(
+        vm_status_details.failure_module.unwrap().to_string(),
+        "0x1::coin"
+    );
+    assert_eq!(vm_status_details.failure_function, None);
+    let abort_code = vm_status_details.abort_code.unwrap();
+    assert_eq!(abort_code.code, 0x10006.into());
+    assert_eq!(abort_code.category.as_deref(), Some("INVALID_ARGUMENT"));
+    assert_eq!(abort_code.reason, Some(0x6.into()));
+    assert_eq!(
+        abort_code.reason_name.as_deref(),
+        Some("EINSUFFICIENT_BALANCE")
+    );
+    assert!(abort_code.description.is_some());
+}
+
+fn into_user_transaction<S: StateView>(
+    converter: &MoveConverter<'_, S>,
+    data: TransactionOnChainData,
+) -> UserTransaction {
+    match converter.try_into_onchain_transaction(0, data).unwrap() {
+        aptos_api_types::Transaction::UserTransaction(user_txn) => user_txn,
+        txn => panic!("Expected a user transaction, but got: {:?}", txn),
+    }
+}
+
 fn assert_value_conversion<S: StateView, V: Serialize>(
     converter: &MoveConverter<'_, S>,
     json_move_type: &str,
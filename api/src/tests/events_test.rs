@@ -109,6 +109,41 @@ async fn test_get_events_by_account_event_handle() {
     assert_eq!(resp, new_resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_with_event_payload_encoding() {
+    let context = new_test_context(current_function_name!());
+    let path = "/accounts/0x1/events/0x1::reconfiguration::Configuration/events";
+
+    // Get the events with the payload decoded (the default)
+    let decoded_resp = context.get(path).await;
+    assert_eq!(
+        decoded_resp,
+        context
+            .get(&format!("{}?event_payload=decoded", path))
+            .await
+    );
+    let decoded_event = &decoded_resp.as_array().unwrap()[0];
+    assert!(decoded_event["data"].is_object());
+    assert!(decoded_event.get("raw_data").is_none());
+
+    // Get the events with only the raw payload, and verify the payload isn't decoded
+    let raw_resp = context.get(&format!("{}?event_payload=raw", path)).await;
+    let raw_event = &raw_resp.as_array().unwrap()[0];
+    assert!(raw_event["data"].is_null());
+    assert!(raw_event["raw_data"].as_str().unwrap().starts_with("0x"));
+    assert_eq!(raw_event["type"], decoded_event["type"]);
+    assert_eq!(
+        raw_event["sequence_number"],
+        decoded_event["sequence_number"]
+    );
+
+    // Get the events with both payloads
+    let both_resp = context.get(&format!("{}?event_payload=both", path)).await;
+    let both_event = &both_resp.as_array().unwrap()[0];
+    assert_eq!(both_event["data"], decoded_event["data"]);
+    assert_eq!(both_event["raw_data"], raw_event["raw_data"]);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_events_by_invalid_account_event_handle_struct_address() {
     let mut context = new_test_context(current_function_name!());
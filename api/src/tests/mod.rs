@@ -11,6 +11,7 @@ mod invalid_post_request_test;
 mod modules;
 mod multisig_transactions_test;
 mod objects;
+mod rate_limit_test;
 mod resource_groups;
 mod secp256k1_ecdsa;
 mod simulation_test;
@@ -35,6 +36,6 @@ fn new_test_context_with_config(test_name: String, node_config: NodeConfig) -> T
 fn new_test_context_with_db_sharding_and_internal_indexer(test_name: String) -> TestContext {
     let mut node_config = NodeConfig::default();
     node_config.storage.rocksdb_configs.enable_storage_sharding = true;
-    node_config.indexer_db_config = InternalIndexerDBConfig::new(true, true, true, 10_000);
+    node_config.indexer_db_config = InternalIndexerDBConfig::new(true, true, true, true, 10_000);
     super_new_test_context(test_name, node_config, true)
 }
@@ -0,0 +1,64 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context_with_config;
+use aptos_api_test_context::current_function_name;
+use aptos_api_types::{X_RATELIMIT_LIMIT, X_RATELIMIT_REMAINING};
+use aptos_config::config::{ApiRateLimitConfig, NodeConfig};
+use std::net::IpAddr;
+
+/// Creates a request (from the given client, forwarded by the test proxy)
+fn create_forwarded_request(path: &str, client_ip: &str) -> warp::test::RequestBuilder {
+    warp::test::request()
+        .method("GET")
+        .header("origin", "test")
+        .header("X-Forwarded-For", client_ip)
+        .path(path)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_rate_limit() {
+    // Create a test context that limits each client to 2 requests (and trusts the test proxy)
+    let mut node_config = NodeConfig::default();
+    node_config.api.rate_limit = ApiRateLimitConfig {
+        enabled: true,
+        max_requests_per_ip: 2,
+        trusted_proxies: vec![IpAddr::from([127, 0, 0, 1])],
+        ..Default::default()
+    };
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+
+    // Send a successful request and verify the budget is reported
+    let resp = context
+        .reply(create_forwarded_request("/v1", "1.2.3.4"))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()[X_RATELIMIT_LIMIT], "2");
+    assert_eq!(resp.headers()[X_RATELIMIT_REMAINING], "1");
+
+    // Send a failing request and verify the budget is also reported
+    let resp = context
+        .reply(create_forwarded_request(
+            "/v1/accounts/nope/resources",
+            "1.2.3.4",
+        ))
+        .await;
+    assert_eq!(resp.status(), 400);
+    assert_eq!(resp.headers()[X_RATELIMIT_REMAINING], "0");
+
+    // Verify the next request is rejected (with the CORS headers)
+    let resp = context
+        .reply(create_forwarded_request("/v1", "1.2.3.4"))
+        .await;
+    assert_eq!(resp.status(), 429);
+    assert_eq!(resp.headers()[X_RATELIMIT_REMAINING], "0");
+    assert!(resp.headers().contains_key("retry-after"));
+    assert_eq!(resp.headers()["access-control-allow-origin"], "test");
+
+    // Verify that requests forwarded for another client have a separate budget
+    let resp = context
+        .reply(create_forwarded_request("/v1", "5.6.7.8"))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()[X_RATELIMIT_REMAINING], "1");
+}
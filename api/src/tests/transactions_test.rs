@@ -7,19 +7,21 @@ use crate::tests::{
     new_test_context_with_config, new_test_context_with_db_sharding_and_internal_indexer,
 };
 use aptos_api_test_context::{assert_json, current_function_name, pretty, TestContext};
+use aptos_api_types::{mime_types, AptosError, AptosErrorCode};
 use aptos_config::config::{GasEstimationStaticOverride, NodeConfig};
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519Signature},
     multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
-    PrivateKey, SigningKey, Uniform,
+    signing_message, PrivateKey, SigningKey, Uniform,
 };
+use aptos_mempool::{MempoolLoadLevel, MempoolLoadState};
 use aptos_sdk::types::{AccountKey, LocalAccount};
 use aptos_types::{
     account_address::AccountAddress,
     account_config::aptos_test_root_address,
     transaction::{
         authenticator::{AuthenticationKey, TransactionAuthenticator},
-        EntryFunction, Script, SignedTransaction,
+        EntryFunction, RawTransactionWithData, Script, SignedTransaction,
     },
     utility_coin::APTOS_COIN_TYPE,
 };
@@ -32,6 +34,7 @@ use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use serde_json::json;
 use std::{path::PathBuf, time::Duration};
 use tokio::time::sleep;
+use warp::http::header::CONTENT_TYPE;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_deserialize_genesis_transaction() {
@@ -189,6 +192,48 @@ async fn test_post_transaction_rejected_by_mempool() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_post_transaction_rejected_when_mempool_is_full() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    let body = bcs::to_bytes(&txn).unwrap();
+
+    // Mark mempool as full (with a suggested retry delay)
+    context
+        .context
+        .mempool_load_monitor()
+        .set_load_state_for_testing(MempoolLoadState {
+            level: MempoolLoadLevel::Full,
+            num_transactions: 100,
+            capacity: 100,
+            retry_after: Some(Duration::from_secs(7)),
+            ..Default::default()
+        });
+
+    // Verify that the submission is rejected with a 429 and a retry delay
+    let req = warp::test::request()
+        .method("POST")
+        .path("/v1/transactions")
+        .header(CONTENT_TYPE, mime_types::BCS_SIGNED_TRANSACTION)
+        .body(body.clone());
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 429);
+    assert_eq!(resp.headers().get("Retry-After").unwrap(), "7");
+    let error: AptosError = serde_json::from_slice(resp.body()).unwrap();
+    assert!(matches!(error.error_code, AptosErrorCode::MempoolIsFull));
+
+    // Verify that the submission is accepted once mempool is healthy again
+    context
+        .context
+        .mempool_load_monitor()
+        .set_load_state_for_testing(MempoolLoadState::default());
+    context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", body)
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_multi_agent_signed_transaction() {
     let mut context = new_test_context(current_function_name!());
@@ -685,6 +730,48 @@ async fn test_signing_message_with_entry_function_payload() {
     test_signing_message_with_payload(context, txn, payload).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_signing_message_with_fee_payer() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let fee_payer = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    let sender = context.root_account().await;
+    let body = json!({
+        "sender": sender.address().to_hex_literal(),
+        "sequence_number": sender.sequence_number().to_string(),
+        "gas_unit_price": txn.gas_unit_price().to_string(),
+        "max_gas_amount": txn.max_gas_amount().to_string(),
+        "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+        "payload": {
+            "type": "entry_function_payload",
+            "function": "0x1::aptos_account::create_account",
+            "type_arguments": [],
+            "arguments": [
+                account.address().to_hex_literal(), // new_account_address
+            ]
+        },
+        "fee_payer": fee_payer.address().to_hex_literal(),
+    });
+
+    let resp = context.post("/transactions/encode_submission", body).await;
+
+    // Verify that the signing message covers the fee payer
+    let signing_msg = context
+        .api_specific_config
+        .unwrap_signing_message_response(resp);
+    let expected_msg = signing_message(&RawTransactionWithData::new_fee_payer(
+        txn.into_raw_transaction(),
+        vec![],
+        fee_payer.address(),
+    ))
+    .unwrap();
+    assert_eq!(
+        signing_msg.to_string(),
+        format!("0x{}", hex::encode(expected_msg))
+    );
+}
+
 async fn test_signing_message_with_payload(
     mut context: TestContext,
     txn: SignedTransaction,
@@ -15,7 +15,7 @@ use crate::{
         api_disabled, api_forbidden, transaction_not_found_by_hash,
         transaction_not_found_by_version, version_pruned, BadRequestError, BasicError,
         BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResult, BasicResultWith404,
-        ForbiddenError, InsufficientStorageError, InternalError,
+        ForbiddenError, InsufficientStorageError, InternalError, TooManyRequestsError,
     },
     ApiTags,
 };
@@ -23,12 +23,14 @@ use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
     verify_function_identifier, verify_module_identifier, Address, AptosError, AptosErrorCode,
     AsConverter, EncodeSubmissionRequest, GasEstimation, GasEstimationBcs, HashValue,
-    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
-    Transaction, TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
+    HexEncodedBytes, LedgerInfo, MempoolTransactionState, MoveType, PendingTransaction,
+    SubmitTransactionRequest, Transaction, TransactionData, TransactionMempoolStatus,
+    TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
     TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion,
     MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use aptos_crypto::{hash::CryptoHash, signing_message};
+use aptos_mempool::MempoolTransactionStatus;
 use aptos_types::{
     account_address::AccountAddress,
     mempool_status::MempoolStatusCode,
@@ -42,7 +44,7 @@ use aptos_types::{
 use aptos_vm::{AptosSimulationVM, AptosVM};
 use move_core_types::{ident_str, language_storage::ModuleId, vm_status::VMStatus};
 use poem_openapi::{
-    param::{Path, Query},
+    param::{Header, Path, Query},
     payload::Json,
     ApiRequest, OpenApi,
 };
@@ -56,6 +58,7 @@ generate_error_response!(
     (403, Forbidden),
     (404, NotFound),
     (413, PayloadTooLarge),
+    (429, TooManyRequests),
     (500, Internal),
     (503, ServiceUnavailable),
     (507, InsufficientStorage)
@@ -362,6 +365,9 @@ impl TransactionsApi {
     /// To submit a transaction as BCS, you must submit a SignedTransaction
     /// encoded as BCS. See SignedTransaction in types/src/transaction/mod.rs.
     /// Make sure to use the `application/x.aptos.signed_transaction+bcs` Content-Type.
+    ///
+    /// Clients configured with an API key may provide it in the `x-aptos-api-key`
+    /// header, in which case the submission is subject to the client's mempool quotas.
     // TODO: Point to examples of both of these flows, in multiple languages.
     #[oai(
         path = "/transactions",
@@ -372,6 +378,12 @@ impl TransactionsApi {
     async fn submit_transaction(
         &self,
         accept_type: AcceptType,
+        /// API key of the client (if configured on the node)
+        ///
+        /// If provided, the submission is attributed to the client, and is
+        /// subject to the client's mempool quotas
+        #[oai(name = "x-aptos-api-key")]
+        api_key: Header<Option<String>>,
         data: SubmitTransactionPost,
     ) -> SubmitTransactionResult<PendingTransaction> {
         data.verify()
@@ -388,10 +400,17 @@ impl TransactionsApi {
         }
         self.context
             .check_api_output_enabled("Submit transaction", &accept_type)?;
+        let client_identity = self.authenticate_client(api_key.0.as_deref())?;
         let ledger_info = self.context.get_latest_ledger_info()?;
+        self.check_mempool_load(&ledger_info)?;
         let signed_transaction = self.get_signed_transaction(&ledger_info, data)?;
-        self.create(&accept_type, &ledger_info, signed_transaction)
-            .await
+        self.create(
+            &accept_type,
+            &ledger_info,
+            signed_transaction,
+            client_identity,
+        )
+        .await
     }
 
     /// Submit batch transactions
@@ -416,6 +435,9 @@ impl TransactionsApi {
     /// To submit a transaction as BCS, you must submit a SignedTransaction
     /// encoded as BCS. See SignedTransaction in types/src/transaction/mod.rs.
     /// Make sure to use the `application/x.aptos.signed_transaction+bcs` Content-Type.
+    ///
+    /// Clients configured with an API key may provide it in the `x-aptos-api-key`
+    /// header, in which case the submissions are subject to the client's mempool quotas.
     #[oai(
         path = "/transactions/batch",
         method = "post",
@@ -425,6 +447,12 @@ impl TransactionsApi {
     async fn submit_transactions_batch(
         &self,
         accept_type: AcceptType,
+        /// API key of the client (if configured on the node)
+        ///
+        /// If provided, the submission is attributed to the client, and is
+        /// subject to the client's mempool quotas
+        #[oai(name = "x-aptos-api-key")]
+        api_key: Header<Option<String>>,
         data: SubmitTransactionsBatchPost,
     ) -> SubmitTransactionsBatchResult<TransactionsBatchSubmissionResult> {
         data.verify()
@@ -441,7 +469,9 @@ impl TransactionsApi {
         }
         self.context
             .check_api_output_enabled("Submit batch transactions", &accept_type)?;
+        let client_identity = self.authenticate_client(api_key.0.as_deref())?;
         let ledger_info = self.context.get_latest_ledger_info()?;
+        self.check_mempool_load(&ledger_info)?;
         let signed_transactions_batch = self.get_signed_transactions_batch(&ledger_info, data)?;
         if self.context.max_submit_transaction_batch_size() < signed_transactions_batch.len() {
             return Err(SubmitTransactionError::bad_request_with_code(
@@ -454,8 +484,13 @@ impl TransactionsApi {
                 &ledger_info,
             ));
         }
-        self.create_batch(&accept_type, &ledger_info, signed_transactions_batch)
-            .await
+        self.create_batch(
+            &accept_type,
+            &ledger_info,
+            signed_transactions_batch,
+            client_identity,
+        )
+        .await
     }
 
     /// Simulate transaction
@@ -688,6 +723,69 @@ impl TransactionsApi {
         }
     }
 
+    /// Get mempool status of transactions
+    ///
+    /// Look up the state of multiple transactions in this node's mempool by hash. The
+    /// statuses are returned in the same order as the requested hashes. A transaction is
+    /// `pending` if it is ready to be included in a block, `parked` if it is waiting on an
+    /// earlier sequence number, `rejected` if it was recently rejected during block execution
+    /// (in which case the VM status code it was rejected with is included), and `unknown` if
+    /// this node's mempool does not hold it (e.g. it was already committed, expired, or never
+    /// submitted to this node).
+    #[oai(
+        path = "/transactions/mempool_status",
+        method = "post",
+        operation_id = "get_transactions_mempool_status",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn get_transactions_mempool_status(
+        &self,
+        accept_type: AcceptType,
+        hashes: Json<Vec<HashValue>>,
+    ) -> BasicResult<Vec<TransactionMempoolStatus>> {
+        fail_point_poem("endpoint_get_transactions_mempool_status")?;
+        self.context
+            .check_api_output_enabled("Get transactions mempool status", &accept_type)?;
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let hashes = hashes.0;
+        if self.context.max_transaction_mempool_status_batch_size() < hashes.len() {
+            return Err(BasicError::bad_request_with_code(
+                format!(
+                    "Requested too many transaction hashes: {}, while limit is {}",
+                    hashes.len(),
+                    self.context.max_transaction_mempool_status_batch_size(),
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+
+        let statuses = self
+            .context
+            .get_pending_transaction_statuses_by_hash(
+                hashes.iter().map(|hash| (*hash).into()).collect(),
+            )
+            .await
+            .context("Failed to get transaction statuses from mempool")
+            .map_err(|err| {
+                BasicError::internal_with_code(err, AptosErrorCode::InternalError, &ledger_info)
+            })?;
+        let statuses: Vec<_> = hashes
+            .into_iter()
+            .zip(statuses)
+            .map(|(hash, status)| Self::to_transaction_mempool_status(hash, status))
+            .collect();
+
+        match accept_type {
+            AcceptType::Json => {
+                BasicResponse::try_from_json((statuses, &ledger_info, BasicResponseStatus::Ok))
+            },
+            AcceptType::Bcs => {
+                BasicResponse::try_from_bcs((statuses, &ledger_info, BasicResponseStatus::Ok))
+            },
+        }
+    }
+
     /// Estimate gas price
     ///
     /// Gives an estimate of the gas unit price required to get a transaction on chain in a
@@ -741,6 +839,34 @@ impl TransactionsApi {
 }
 
 impl TransactionsApi {
+    /// Converts a mempool transaction status into its API representation
+    fn to_transaction_mempool_status(
+        hash: HashValue,
+        status: MempoolTransactionStatus,
+    ) -> TransactionMempoolStatus {
+        let (state, info, rejection_status_code) = match status {
+            MempoolTransactionStatus::Pending(info) => {
+                (MempoolTransactionState::Pending, Some(info), None)
+            },
+            MempoolTransactionStatus::Parked(info) => {
+                (MempoolTransactionState::Parked, Some(info), None)
+            },
+            MempoolTransactionStatus::Rejected(reason) => (
+                MempoolTransactionState::Rejected,
+                None,
+                Some(U64::from(u64::from(reason))),
+            ),
+            MempoolTransactionStatus::Unknown => (MempoolTransactionState::Unknown, None, None),
+        };
+        TransactionMempoolStatus {
+            hash,
+            state,
+            ranking_score: info.as_ref().map(|info| info.ranking_score.into()),
+            time_in_mempool_ms: info.map(|info| (info.time_in_mempool.as_millis() as u64).into()),
+            rejection_status_code,
+        }
+    }
+
     /// List all transactions paging by ledger version
     fn list(&self, accept_type: &AcceptType, page: Page) -> BasicResultWith404<Vec<Transaction>> {
         let latest_ledger_info = self.context.get_latest_ledger_info()?;
@@ -900,6 +1026,9 @@ impl TransactionsApi {
                                 self.context.db.clone(),
                                 self.context.indexer_reader.clone(),
                             )
+                            .with_detailed_vm_status(
+                                self.context.node_config.api.detailed_vm_status_enabled,
+                            )
                             .try_into_onchain_transaction(timestamp, txn)
                             .context("Failed to convert on chain transaction to Transaction")
                             .map_err(|err| {
@@ -1182,12 +1311,59 @@ impl TransactionsApi {
         }
     }
 
+    /// Authenticates the client using the API key header (if any), and returns
+    /// the identity of the client. Unknown API keys are rejected.
+    fn authenticate_client(
+        &self,
+        api_key: Option<&str>,
+    ) -> Result<Option<String>, SubmitTransactionError> {
+        let Some(api_key) = api_key else {
+            return Ok(None); // The client is not authenticated
+        };
+        self.context
+            .client_identity(api_key)
+            .map(Some)
+            .ok_or_else(|| {
+                api_forbidden(
+                    "Submitting transactions with an unknown API key",
+                    "Please check the x-aptos-api-key header.",
+                )
+            })
+    }
+
+    /// Rejects submissions while mempool is full (with a suggested retry delay),
+    /// instead of accepting transactions that are likely to be evicted.
+    fn check_mempool_load(&self, ledger_info: &LedgerInfo) -> Result<(), SubmitTransactionError> {
+        let load_state = self.context.mempool_load_state();
+        if let (true, Some(retry_after)) = (load_state.is_full(), load_state.retry_after) {
+            return Err(SubmitTransactionError::too_many_requests_with_code(
+                format!(
+                    "Mempool is full ({}% utilized), please retry later",
+                    load_state.utilization_percent()
+                ),
+                AptosErrorCode::MempoolIsFull,
+                ledger_info,
+            )
+            .with_retry_after(retry_after.as_secs()));
+        }
+        Ok(())
+    }
+
     /// Submits a single transaction, and converts mempool codes to errors
-    async fn create_internal(&self, txn: SignedTransaction) -> Result<(), AptosError> {
-        let (mempool_status, vm_status_opt) = self
-            .context
-            .submit_transaction(txn)
-            .await
+    async fn create_internal(
+        &self,
+        txn: SignedTransaction,
+        client_identity: Option<String>,
+    ) -> Result<(), AptosError> {
+        let submission_status = match client_identity {
+            Some(client_identity) => {
+                self.context
+                    .submit_transaction_from_client(txn, client_identity)
+                    .await
+            },
+            None => self.context.submit_transaction(txn).await,
+        };
+        let (mempool_status, vm_status_opt) = submission_status
             .context("Mempool failed to initially evaluate submitted transaction")
             .map_err(|err| {
                 aptos_api_types::AptosError::new_with_error_code(err, AptosErrorCode::InternalError)
@@ -1240,8 +1416,9 @@ impl TransactionsApi {
         accept_type: &AcceptType,
         ledger_info: &LedgerInfo,
         txn: SignedTransaction,
+        client_identity: Option<String>,
     ) -> SubmitTransactionResult<PendingTransaction> {
-        match self.create_internal(txn.clone()).await {
+        match self.create_internal(txn.clone(), client_identity).await {
             Ok(()) => match accept_type {
                 AcceptType::Json => {
                     let state_view = self
@@ -1310,11 +1487,15 @@ impl TransactionsApi {
         accept_type: &AcceptType,
         ledger_info: &LedgerInfo,
         txns: Vec<SignedTransaction>,
+        client_identity: Option<String>,
     ) -> SubmitTransactionsBatchResult<TransactionsBatchSubmissionResult> {
         // Iterate through transactions keeping track of failures
         let mut txn_failures = Vec::new();
         for (idx, txn) in txns.iter().enumerate() {
-            if let Err(error) = self.create_internal(txn.clone()).await {
+            if let Err(error) = self
+                .create_internal(txn.clone(), client_identity.clone())
+                .await
+            {
                 txn_failures.push(TransactionsBatchSingleSubmissionFailure {
                     error,
                     transaction_index: idx,
@@ -1498,8 +1679,23 @@ impl TransactionsApi {
                 BasicError::bad_request_with_code(err, AptosErrorCode::InvalidInput, &ledger_info)
             })?;
 
-        let raw_message = match request.secondary_signers {
-            Some(secondary_signer_addresses) => signing_message(
+        let raw_message = match (request.secondary_signers, request.fee_payer) {
+            (secondary_signer_addresses, Some(fee_payer_address)) => signing_message(
+                &RawTransactionWithData::new_fee_payer(
+                    raw_txn,
+                    secondary_signer_addresses
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|v| v.into())
+                        .collect(),
+                    fee_payer_address.into(),
+                ),
+            )
+            .context("Invalid transaction to generate signing message")
+            .map_err(|err| {
+                BasicError::bad_request_with_code(err, AptosErrorCode::InvalidInput, &ledger_info)
+            })?,
+            (Some(secondary_signer_addresses), None) => signing_message(
                 &RawTransactionWithData::new_multi_agent(
                     raw_txn,
                     secondary_signer_addresses
@@ -1512,7 +1708,7 @@ impl TransactionsApi {
             .map_err(|err| {
                 BasicError::bad_request_with_code(err, AptosErrorCode::InvalidInput, &ledger_info)
             })?,
-            None => raw_txn
+            (None, None) => raw_txn
                 .signing_message()
                 .context("Invalid transaction to generate signing message")
                 .map_err(|err| {
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{HexEncodedBytes, U64};
-use aptos_types::account_config::AccountResource;
+use aptos_types::{account_config::AccountResource, state_store::account_storage_usage};
 use poem_openapi::Object;
 use serde::{Deserialize, Serialize};
 
@@ -25,3 +25,37 @@ impl From<AccountResource> for AccountData {
         }
     }
 }
+
+/// Account storage usage
+///
+/// The storage used by the resources and modules stored under an account, along
+/// with the storage deposits paid for them (which are refunded when the resources
+/// and modules are deleted). Table items are not included.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct AccountStorageUsage {
+    /// Number of resources (and resource groups) stored under the account
+    pub resource_count: U64,
+    /// Number of modules stored under the account
+    pub module_count: U64,
+    /// Total size (in bytes) of the resources and modules, including their keys
+    pub total_bytes: U64,
+    /// Storage deposit paid for the state slots (in octas)
+    pub slot_deposit: U64,
+    /// Storage deposit paid for the bytes of the state items (in octas)
+    pub bytes_deposit: U64,
+    /// Total storage deposit (in octas)
+    pub total_deposit: U64,
+}
+
+impl From<account_storage_usage::AccountStorageUsage> for AccountStorageUsage {
+    fn from(usage: account_storage_usage::AccountStorageUsage) -> Self {
+        Self {
+            resource_count: usage.resource_count.into(),
+            module_count: usage.module_count.into(),
+            total_bytes: usage.bytes.into(),
+            slot_deposit: usage.slot_deposit.into(),
+            bytes_deposit: usage.bytes_deposit.into(),
+            total_deposit: usage.total_deposit().into(),
+        }
+    }
+}
@@ -9,18 +9,19 @@ use crate::{
         UserTransactionRequestInner, WriteModule, WriteResource, WriteTableItem,
     },
     view::{ViewFunction, ViewRequest},
-    Address, Bytecode, DirectWriteSet, EntryFunctionId, EntryFunctionPayload, Event,
-    HexEncodedBytes, MoveFunction, MoveModuleBytecode, MoveResource, MoveScriptBytecode, MoveType,
-    MoveValue, PendingTransaction, ResourceGroup, ScriptPayload, ScriptWriteSet,
-    SubmitTransactionRequest, Transaction, TransactionInfo, TransactionOnChainData,
-    TransactionPayload, UserTransactionRequest, VersionedEvent, WriteSet, WriteSetChange,
-    WriteSetPayload,
+    AbortCodeDetails, Address, Bytecode, DirectWriteSet, EntryFunctionId, EntryFunctionPayload,
+    Event, HexEncodedBytes, IdentifierWrapper, MoveFunction, MoveModuleBytecode, MoveResource,
+    MoveScriptBytecode, MoveType, MoveValue, PendingTransaction, ResourceGroup, ScriptPayload,
+    ScriptWriteSet, SubmitTransactionRequest, Transaction, TransactionInfo, TransactionOnChainData,
+    TransactionPayload, UserTransactionRequest, VersionedEvent, VmStatusDetails, WriteSet,
+    WriteSetChange, WriteSetPayload,
 };
 use anyhow::{bail, ensure, format_err, Context as AnyhowContext, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_framework::get_metadata_from_compiled_module;
 use aptos_logger::{sample, sample::SampleRate};
 use aptos_resource_viewer::AptosValueAnnotator;
-use aptos_storage_interface::DbReader;
+use aptos_storage_interface::{state_view::DbStateViewAtVersion, DbReader};
 use aptos_types::{
     access_path::{AccessPath, Path},
     chain_id::ChainId,
@@ -29,7 +30,7 @@ use aptos_types::{
     state_store::{
         state_key::{inner::StateKeyInner, StateKey},
         table::{TableHandle, TableInfo},
-        StateView,
+        StateView, TStateView,
     },
     transaction::{
         BlockEndInfo, BlockEpiloguePayload, EntryFunction, ExecutionStatus, Multisig,
@@ -39,7 +40,7 @@ use aptos_types::{
     write_set::WriteOp,
 };
 use bytes::Bytes;
-use move_binary_format::file_format::FunctionHandleIndex;
+use move_binary_format::{file_format::FunctionHandleIndex, CompiledModule};
 use move_core_types::{
     account_address::AccountAddress,
     ident_str,
@@ -68,6 +69,7 @@ pub struct MoveConverter<'a, S> {
     inner: AptosValueAnnotator<'a, S>,
     db: Arc<dyn DbReader>,
     indexer_reader: Option<Arc<dyn IndexerReader>>,
+    detailed_vm_status: bool,
 }
 
 impl<'a, S: StateView> MoveConverter<'a, S> {
@@ -80,9 +82,18 @@ impl<'a, S: StateView> MoveConverter<'a, S> {
             inner: AptosValueAnnotator::new(inner),
             db,
             indexer_reader,
+            detailed_vm_status: false,
         }
     }
 
+    /// Enables (or disables) detailed VM statuses for failed user transactions. If enabled,
+    /// the transactions include structured VM status details (e.g., the abort code resolved
+    /// from the error map of the failing module, and the entry function that was called).
+    pub fn with_detailed_vm_status(mut self, detailed_vm_status: bool) -> Self {
+        self.detailed_vm_status = detailed_vm_status;
+        self
+    }
+
     pub fn try_into_resources<'b>(
         &self,
         data: impl Iterator<Item = (StructTag, &'b [u8])>,
@@ -198,7 +209,13 @@ impl<'a, S: StateView> MoveConverter<'a, S> {
         Ok(match data.transaction {
             UserTransaction(txn) => {
                 let payload = self.try_into_transaction_payload(txn.payload().clone())?;
-                (&txn, info, payload, events, timestamp).into()
+                let vm_status_details =
+                    self.explain_vm_status_details(data.version, &txn, data.info.status());
+                let mut transaction: Transaction = (&txn, info, payload, events, timestamp).into();
+                if let Transaction::UserTransaction(user_transaction) = &mut transaction {
+                    user_transaction.vm_status_details = vm_status_details;
+                }
+                transaction
             },
             GenesisTransaction(write_set) => {
                 let payload = self.try_into_write_set_payload(write_set)?;
@@ -1100,6 +1117,98 @@ impl<'a, S: StateView> MoveConverter<'a, S> {
         let id = code.identifier_at(func.name);
         Ok(id.to_string())
     }
+
+    /// Returns the structured details of the VM status of a failed user transaction
+    /// (if detailed VM statuses are enabled). Modules are resolved at the version of
+    /// the transaction, so that abort codes and function indices are explained using
+    /// the code that actually failed (rather than the latest code).
+    fn explain_vm_status_details(
+        &self,
+        version: u64,
+        txn: &SignedTransaction,
+        status: &ExecutionStatus,
+    ) -> Option<VmStatusDetails> {
+        if !self.detailed_vm_status {
+            return None;
+        }
+
+        // Identify the failing module (and function, or abort code)
+        let (failure_module, failure_function, abort_code) = match status {
+            ExecutionStatus::MoveAbort {
+                location: AbortLocation::Module(module_id),
+                code,
+                info,
+            } => {
+                // The abort info isn't stored on-chain, so resolve it from the error map
+                let info = info.clone().or_else(|| {
+                    self.view_module_at_version(module_id, version)
+                        .and_then(|module| {
+                            get_metadata_from_compiled_module(&module)?.extract_abort_info(*code)
+                        })
+                });
+                let category = abort_code_category(*code);
+                let abort_code = AbortCodeDetails {
+                    code: (*code).into(),
+                    category: category.map(|(category, _)| category.to_owned()),
+                    reason: category.map(|(_, reason)| reason.into()),
+                    reason_name: info.as_ref().map(|info| info.reason_name.clone()),
+                    description: info.map(|info| info.description),
+                };
+                (Some(module_id.clone()), None, Some(abort_code))
+            },
+            ExecutionStatus::ExecutionFailure {
+                location: AbortLocation::Module(module_id),
+                function,
+                ..
+            } => {
+                let failure_function =
+                    self.view_module_at_version(module_id, version)
+                        .map(|module| {
+                            let handle =
+                                module.function_handle_at(FunctionHandleIndex::new(*function));
+                            IdentifierWrapper(module.identifier_at(handle.name).to_owned())
+                        });
+                (Some(module_id.clone()), failure_function, None)
+            },
+            ExecutionStatus::Success => return None,
+            _ => (None, None, None),
+        };
+
+        // Identify the entry function
+        let entry_function = match txn.payload() {
+            aptos_types::transaction::TransactionPayload::EntryFunction(entry_function) => {
+                Some(entry_function)
+            },
+            aptos_types::transaction::TransactionPayload::Multisig(Multisig {
+                transaction_payload:
+                    Some(aptos_types::transaction::MultisigTransactionPayload::EntryFunction(
+                        entry_function,
+                    )),
+                ..
+            }) => Some(entry_function),
+            _ => None,
+        };
+
+        Some(VmStatusDetails {
+            entry_function: entry_function.map(|entry_function| EntryFunctionId {
+                module: entry_function.module().clone().into(),
+                name: entry_function.function().to_owned().into(),
+            }),
+            failure_module: failure_module.map(Into::into),
+            failure_function,
+            abort_code,
+        })
+    }
+
+    /// Returns the given module as of the given version (if it exists, and the
+    /// state at the version hasn't been pruned)
+    fn view_module_at_version(&self, module_id: &ModuleId, version: u64) -> Option<CompiledModule> {
+        let state_view = self.db.state_view_at_version(Some(version)).ok()?;
+        let bytes = state_view
+            .get_state_value_bytes(&StateKey::module_id(module_id))
+            .ok()??;
+        CompiledModule::deserialize(&bytes).ok()
+    }
 }
 
 fn log_missing_table_info(handle: TableHandle) {
@@ -1144,6 +1253,33 @@ pub fn new_vm_utf8_string(string: &str) -> move_core_types::value::MoveValue {
     MoveValue::Struct(move_string)
 }
 
+/// Decomposes the given abort code into its (named) error category and reason,
+/// using the canonical error code layout (i.e., `std::error`). Returns None if the
+/// abort code doesn't use the canonical layout.
+fn abort_code_category(code: u64) -> Option<(&'static str, u64)> {
+    if code >> 24 != 0 {
+        return None;
+    }
+
+    let category = match code >> 16 {
+        0x1 => "INVALID_ARGUMENT",
+        0x2 => "OUT_OF_RANGE",
+        0x3 => "INVALID_STATE",
+        0x4 => "UNAUTHENTICATED",
+        0x5 => "PERMISSION_DENIED",
+        0x6 => "NOT_FOUND",
+        0x7 => "ABORTED",
+        0x8 => "ALREADY_EXISTS",
+        0x9 => "RESOURCE_EXHAUSTED",
+        0xA => "CANCELLED",
+        0xB => "INTERNAL",
+        0xC => "NOT_IMPLEMENTED",
+        0xD => "UNAVAILABLE",
+        _ => return None,
+    };
+    Some((category, code & 0xFFFF))
+}
+
 fn abort_location_to_str(loc: &AbortLocation) -> String {
     match loc {
         AbortLocation::Module(mid) => {
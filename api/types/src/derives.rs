@@ -63,7 +63,16 @@ impl_poem_type!(
     )
 );
 
-impl_poem_type!(HashValue, "string", ());
+impl_poem_type!(
+    HashValue,
+    "string",
+    (
+        example = Some(serde_json::Value::String(
+            "0x88fbd33f54e1126269769780feb24480428179f552e2313fbe571b72e62a1ca1".to_string()
+        )),
+        description = Some("A hex encoded 32 byte hash, with a leading 0x")
+    )
+);
 
 impl_poem_type!(
     HexEncodedBytes,
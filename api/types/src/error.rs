@@ -110,6 +110,8 @@ pub enum AptosErrorCode {
     BcsNotSupported = 602,
     /// API Disabled
     ApiDisabled = 603,
+    /// The client exceeded its request rate limit
+    RateLimited = 604,
 }
 
 impl AptosErrorCode {
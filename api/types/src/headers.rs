@@ -22,3 +22,11 @@ pub const X_APTOS_CURSOR: &str = "X-Aptos-Cursor";
 pub const X_APTOS_GAS_USED: &str = "X-Aptos-Gas-Used";
 /// Provided by the client to identify what client it is.
 pub const X_APTOS_CLIENT: &str = "x-aptos-client";
+/// Provided by the client to authenticate itself when submitting transactions.
+pub const X_APTOS_API_KEY: &str = "x-aptos-api-key";
+/// The maximum number of requests allowed in the current rate limit window
+pub const X_RATELIMIT_LIMIT: &str = "X-RateLimit-Limit";
+/// The number of requests remaining in the current rate limit window
+pub const X_RATELIMIT_REMAINING: &str = "X-RateLimit-Remaining";
+/// The number of seconds until the current rate limit window resets
+pub const X_RATELIMIT_RESET: &str = "X-RateLimit-Reset";
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    Address, AptosError, EntryFunctionId, EventGuid, HashValue, HexEncodedBytes,
+    Address, AptosError, EntryFunctionId, EventGuid, HashValue, HexEncodedBytes, IdentifierWrapper,
     MoveModuleBytecode, MoveModuleId, MoveResource, MoveScriptBytecode, MoveStructTag, MoveType,
     MoveValue, VerifyInput, VerifyInputWithRecursion, U64,
 };
@@ -34,7 +34,7 @@ use aptos_types::{
     },
 };
 use once_cell::sync::Lazy;
-use poem_openapi::{Object, Union};
+use poem_openapi::{types::Example, Enum, Object, Union};
 use serde::{Deserialize, Serialize};
 use std::{
     boxed::Box,
@@ -297,6 +297,7 @@ impl
             request: (txn, payload).into(),
             events,
             timestamp: timestamp.into(),
+            vm_status_details: None,
         })
     }
 }
@@ -413,6 +414,46 @@ pub struct UserTransaction {
     /// Events generated by the transaction
     pub events: Vec<Event>,
     pub timestamp: U64,
+    /// Structured details of the VM status of a failed transaction
+    ///
+    /// This is only present if detailed VM statuses are enabled on the node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(skip_serializing_if_is_none)]
+    pub vm_status_details: Option<VmStatusDetails>,
+}
+
+/// Structured details of the VM status of a failed user transaction
+///
+/// This contains the entry function called by the transaction (if the payload is
+/// an entry function), the module in which the transaction failed, and the function
+/// in which it failed (only known for execution failures). Only the entry function
+/// and the failing location are known: the intermediate frames of the failure are
+/// not recorded on-chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct VmStatusDetails {
+    pub entry_function: Option<EntryFunctionId>,
+    pub failure_module: Option<MoveModuleId>,
+    pub failure_function: Option<IdentifierWrapper>,
+    /// The details of the abort code (only present for Move aborts in modules)
+    pub abort_code: Option<AbortCodeDetails>,
+}
+
+/// The details of a Move abort code
+///
+/// If the abort code uses the canonical `std::error` layout, the reason is the
+/// abort code within its category.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct AbortCodeDetails {
+    pub code: U64,
+    /// The error category of the abort code (if it uses the canonical `std::error` layout)
+    pub category: Option<String>,
+    pub reason: Option<U64>,
+    /// The name of the abort code, resolved from the error map of the module at the
+    /// version of the transaction
+    pub reason_name: Option<String>,
+    /// The description of the abort code, resolved from the error map of the module at
+    /// the version of the transaction
+    pub description: Option<String>,
 }
 
 /// A state checkpoint transaction
@@ -477,6 +518,53 @@ pub struct TransactionsBatchSingleSubmissionFailure {
     pub transaction_index: usize,
 }
 
+/// State of a transaction in the node's mempool
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum MempoolTransactionState {
+    /// The transaction is ready to be pulled into a block
+    Pending,
+    /// The transaction is waiting on an earlier sequence number
+    Parked,
+    /// The transaction was recently rejected during block execution, and removed from mempool
+    Rejected,
+    /// The transaction is not in mempool
+    Unknown,
+}
+
+/// Mempool status of a single transaction
+///
+/// The ranking score and time in mempool are only present when the
+/// transaction is in mempool. The rejection status code is only present when
+/// the transaction was rejected.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[oai(example)]
+pub struct TransactionMempoolStatus {
+    pub hash: HashValue,
+    pub state: MempoolTransactionState,
+    pub ranking_score: Option<U64>,
+    pub time_in_mempool_ms: Option<U64>,
+    /// The VM status code the transaction was rejected with (e.g., 5 for
+    /// `INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE`)
+    pub rejection_status_code: Option<U64>,
+}
+
+impl Example for TransactionMempoolStatus {
+    fn example() -> Self {
+        Self {
+            hash: HashValue::from_str(
+                "0x88fbd33f54e1126269769780feb24480428179f552e2313fbe571b72e62a1ca1",
+            )
+            .unwrap(),
+            state: MempoolTransactionState::Pending,
+            ranking_score: Some(U64::from(100u64)),
+            time_in_mempool_ms: Some(U64::from(1500u64)),
+            rejection_status_code: None,
+        }
+    }
+}
+
 // TODO: Rename this to remove the Inner when we cut over.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
 pub struct UserTransactionRequestInner {
@@ -536,6 +624,9 @@ pub struct EncodeSubmissionRequest {
     /// Secondary signer accounts of the request for Multi-agent
     #[serde(skip_serializing_if = "Option::is_none")]
     pub secondary_signers: Option<Vec<Address>>,
+    /// Fee payer account of the request (if the transaction is sponsored)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_payer: Option<Address>,
 }
 
 impl VerifyInput for EncodeSubmissionRequest {
@@ -804,6 +895,20 @@ impl From<(&ContractEvent, serde_json::Value)> for Event {
     }
 }
 
+/// How the payload of an event is rendered in a JSON response
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum EventPayloadEncoding {
+    /// The payload is decoded into JSON (in `data`)
+    #[default]
+    Decoded,
+    /// The payload is returned as raw BCS bytes (in `raw_data`), without decoding
+    Raw,
+    /// The payload is returned both decoded into JSON and as raw BCS bytes
+    Both,
+}
+
 /// An event from a transaction with a version
 #[derive(Clone, Debug, Deserialize, Eq, Object, PartialEq, Serialize)]
 pub struct VersionedEvent {
@@ -816,7 +921,28 @@ pub struct VersionedEvent {
     #[oai(rename = "type")]
     pub typ: MoveType,
     /// The JSON representation of the event
+    ///
+    /// This is null if only the raw payload was requested
     pub data: serde_json::Value,
+    /// The raw BCS bytes of the event payload
+    ///
+    /// This is only present if the raw payload was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(skip_serializing_if_is_none)]
+    pub raw_data: Option<HexEncodedBytes>,
+}
+
+impl VersionedEvent {
+    /// Creates a versioned event with only the raw payload, skipping the JSON decoding
+    pub fn new_raw(event: &EventWithVersion) -> Self {
+        Self::from((event, serde_json::Value::Null)).with_raw_data(event)
+    }
+
+    /// Attaches the raw payload of the given event
+    pub fn with_raw_data(mut self, event: &EventWithVersion) -> Self {
+        self.raw_data = Some(event.event.event_data().to_vec().into());
+        self
+    }
 }
 
 impl From<(&EventWithVersion, serde_json::Value)> for VersionedEvent {
@@ -828,6 +954,7 @@ impl From<(&EventWithVersion, serde_json::Value)> for VersionedEvent {
                 sequence_number: v1.sequence_number().into(),
                 typ: v1.type_tag().clone().into(),
                 data,
+                raw_data: None,
             },
             ContractEvent::V2(v2) => Self {
                 version: event.transaction_version.into(),
@@ -835,6 +962,7 @@ impl From<(&EventWithVersion, serde_json::Value)> for VersionedEvent {
                 sequence_number: *DUMMY_SEQUENCE_NUMBER,
                 typ: v2.type_tag().clone().into(),
                 data,
+                raw_data: None,
             },
         }
     }
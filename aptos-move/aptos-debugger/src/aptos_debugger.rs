@@ -10,7 +10,7 @@ use aptos_types::{
     block_executor::config::{
         BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig,
     },
-    state_store::TStateView,
+    state_store::{state_key::StateKey, state_value::StateValue, TStateView},
     transaction::{
         signature_verified_transaction::SignatureVerifiedTransaction, BlockOutput,
         SignedTransaction, Transaction, TransactionInfo, TransactionOutput, TransactionPayload,
@@ -29,15 +29,25 @@ use aptos_vm::{
 use aptos_vm_logging::log_schema::AdapterLogSchema;
 use aptos_vm_types::output::VMOutput;
 use itertools::Itertools;
-use std::{path::Path, sync::Arc, time::Instant};
+use std::{collections::HashMap, path::Path, sync::Arc, time::Instant};
 
 pub struct AptosDebugger {
     debugger: Arc<dyn AptosValidatorInterface + Send>,
+    /// State values that override the ones read from the DB, in every state view
+    state_overrides: HashMap<StateKey, StateValue>,
 }
 
 impl AptosDebugger {
     pub fn new(debugger: Arc<dyn AptosValidatorInterface + Send>) -> Self {
-        Self { debugger }
+        Self {
+            debugger,
+            state_overrides: HashMap::new(),
+        }
+    }
+
+    /// Overrides the value of the given state key for all subsequent executions
+    pub fn set_state_override(&mut self, state_key: StateKey, state_value: StateValue) {
+        self.state_overrides.insert(state_key, state_value);
     }
 
     pub fn rest_client(rest_client: Client) -> Result<Self> {
@@ -59,7 +69,7 @@ impl AptosDebugger {
     ) -> Result<Vec<TransactionOutput>> {
         let sig_verified_txns: Vec<SignatureVerifiedTransaction> =
             txns.into_iter().map(|x| x.into()).collect::<Vec<_>>();
-        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        let state_view = self.state_view_at_version(version);
 
         print_transaction_stats(&sig_verified_txns, version);
 
@@ -107,7 +117,7 @@ impl AptosDebugger {
         version: Version,
         txn: SignedTransaction,
     ) -> Result<(VMStatus, VMOutput, TransactionGasLog)> {
-        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        let state_view = self.state_view_at_version(version);
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
         let txn = txn
             .check_signature()
@@ -321,6 +331,10 @@ impl AptosDebugger {
         Ok(ret)
     }
 
+    pub async fn get_latest_version(&self) -> Result<Version> {
+        self.debugger.get_latest_ledger_info_version().await
+    }
+
     pub async fn get_version_by_account_sequence(
         &self,
         account: AccountAddress,
@@ -347,6 +361,7 @@ impl AptosDebugger {
 
     pub fn state_view_at_version(&self, version: Version) -> DebuggerStateView {
         DebuggerStateView::new(self.debugger.clone(), version)
+            .with_overrides(self.state_overrides.clone())
     }
 }
 
@@ -432,6 +447,7 @@ fn execute_block_no_limit(
                 concurrency_level,
                 allow_fallback: true,
                 discard_failed_blocks: false,
+                speculative_write_budget_bytes: None,
             },
             onchain: BlockExecutorConfigFromOnchain::new_no_block_limit(),
         },
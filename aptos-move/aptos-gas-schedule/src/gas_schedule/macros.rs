@@ -21,12 +21,14 @@ macro_rules! define_gas_parameters {
         $prefix: literal,
         $env: ty => $(.$field: ident)*,
         [$(
-            [$name: ident: $ty: ident, $key_bindings: tt, $initial: expr $(, $tn: ident)? $(,)?]
+            since $since: expr => [$(
+                [$name: ident: $ty: ident, $key_bindings: tt, $initial: expr $(, $tn: ident)? $(,)?]
+            ),* $(,)?]
         ),* $(,)?]
     ) => {
         #[derive(Debug, Clone)]
         pub struct $params_name {
-            $(pub $name : $ty),*
+            $($(pub $name : $ty),*),*
         }
 
         impl $crate::traits::FromOnChainGasSchedule for $params_name {
@@ -34,12 +36,12 @@ macro_rules! define_gas_parameters {
             fn from_on_chain_gas_schedule(gas_schedule: &std::collections::BTreeMap<String, u64>, feature_version: u64) -> Result<Self, String> {
                 let mut params = $params_name::zeros();
 
-                $(
+                $($(
                     if let Some(key) = $crate::gas_schedule::macros::define_gas_parameters_extract_key_at_version!($key_bindings, feature_version) {
                         let name = format!("{}.{}", $prefix, key);
                         params.$name = gas_schedule.get(&name).cloned().ok_or_else(|| format!("Gas parameter {} does not exist. Feature version: {}.", name, feature_version))?.into();
                     }
-                )*
+                )*)*
 
                 Ok(params)
             }
@@ -50,27 +52,48 @@ macro_rules! define_gas_parameters {
             fn to_on_chain_gas_schedule(&self, feature_version: u64) -> Vec<(String, u64)> {
                 let mut output = vec![];
 
-                $(
+                $($(
                     if let Some(key) = $crate::gas_schedule::macros::define_gas_parameters_extract_key_at_version!($key_bindings, feature_version) {
                         output.push((format!("{}.{}", $prefix, key), self.$name.into()))
                     }
-                )*
+                )*)*
 
                 output
             }
         }
 
+        impl $crate::traits::GasParameterNamespaces for $params_name {
+            #[allow(unused)]
+            fn namespaces(feature_version: u64) -> Vec<(u64, Vec<String>)> {
+                let mut namespaces = vec![];
+
+                $(
+                    let mut keys = vec![];
+                    $(
+                        if let Some(key) = $crate::gas_schedule::macros::define_gas_parameters_extract_key_at_version!($key_bindings, feature_version) {
+                            keys.push(format!("{}.{}", $prefix, key));
+                        }
+                    )*
+                    if !keys.is_empty() {
+                        namespaces.push(($since, keys));
+                    }
+                )*
+
+                namespaces
+            }
+        }
+
         impl $params_name {
             pub fn zeros() -> Self {
                 Self {
-                    $($name: 0.into()),*
+                    $($($name: 0.into()),*),*
                 }
             }
 
             #[cfg(feature = "testing")]
             pub fn random() -> Self {
                 Self {
-                    $($name: rand::random::<u64>().into()),*
+                    $($($name: rand::random::<u64>().into()),*),*
                 }
             }
         }
@@ -78,7 +101,7 @@ macro_rules! define_gas_parameters {
         impl $crate::traits::InitialGasSchedule for $params_name {
             fn initial() -> Self {
                 Self {
-                    $($name: $initial.into()),*
+                    $($($name: $initial.into()),*),*
                 }
             }
         }
@@ -99,7 +122,7 @@ macro_rules! define_gas_parameters {
                 }
             }
 
-            $(
+            $($(
                 paste::paste! {
                     #[derive(Debug)]
                     #[doc = "Type representing the `" $name "` gas parameter."]
@@ -162,7 +185,7 @@ macro_rules! define_gas_parameters {
                         }
                     }
                 }
-            )*
+            )*)*
         }
 
         #[test]
@@ -170,15 +193,30 @@ macro_rules! define_gas_parameters {
             for ver in 0..=$crate::LATEST_GAS_FEATURE_VERSION {
                 let mut map = std::collections::BTreeMap::<&str, ()>::new();
 
-                $(
+                $($(
                     if let Some(key) = $crate::gas_schedule::macros::define_gas_parameters_extract_key_at_version!($key_bindings, ver) {
                         if map.insert(key, ()).is_some() {
                             panic!("duplicated key {} at version {}", key, ver);
                         }
                     }
-                )*
+                )*)*
             }
         }
+
+        #[test]
+        fn parameters_should_be_introduced_at_their_namespace_version() {
+            $(
+                let since: u64 = $since;
+                $(
+                    if $crate::gas_schedule::macros::define_gas_parameters_extract_key_at_version!($key_bindings, since).is_none() {
+                        panic!("gas parameter {} does not exist at version {} (of its namespace)", stringify!($name), since);
+                    }
+                    if since > 0 && $crate::gas_schedule::macros::define_gas_parameters_extract_key_at_version!($key_bindings, since - 1).is_some() {
+                        panic!("gas parameter {} exists before version {} (of its namespace)", stringify!($name), since);
+                    }
+                )*
+            )*
+        }
     };
 }
 
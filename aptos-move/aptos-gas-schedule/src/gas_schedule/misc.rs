@@ -6,7 +6,9 @@
 
 use crate::{
     gas_schedule::VMGasParameters,
-    traits::{FromOnChainGasSchedule, InitialGasSchedule, ToOnChainGasSchedule},
+    traits::{
+        FromOnChainGasSchedule, GasParameterNamespaces, InitialGasSchedule, ToOnChainGasSchedule,
+    },
 };
 use aptos_gas_algebra::{AbstractValueSize, AbstractValueSizePerArg};
 use move_core_types::{account_address::AccountAddress, gas_algebra::NumArgs, u256::U256};
@@ -21,37 +23,41 @@ crate::gas_schedule::macros::define_gas_parameters!(
     "misc.abs_val",
     VMGasParameters => .misc.abs_val,
     [
-        // abstract value size
-        [u8: AbstractValueSize, "u8", 40],
-        [u16: AbstractValueSize, { 5.. => "u16" }, 40],
-        [u32: AbstractValueSize, { 5.. => "u32" }, 40],
-        [u64: AbstractValueSize, "u64", 40],
-        [u128: AbstractValueSize, "u128", 40],
-        [u256: AbstractValueSize, { 5.. => "u256" }, 40],
-        [bool: AbstractValueSize, "bool", 40],
-        [address: AbstractValueSize, "address", 40],
-        [struct_: AbstractValueSize, "struct", 40],
-        [vector: AbstractValueSize, "vector", 40],
-        [reference: AbstractValueSize, "reference", 40],
-        [per_u8_packed: AbstractValueSizePerArg, "per_u8_packed", 1],
-        [per_u16_packed: AbstractValueSizePerArg, { 5.. => "per_u16_packed" }, 2],
-        [per_u32_packed: AbstractValueSizePerArg, { 5.. => "per_u32_packed" }, 4],
-        [per_u64_packed: AbstractValueSizePerArg, "per_u64_packed", 8],
-        [
-            per_u128_packed: AbstractValueSizePerArg,
-            "per_u128_packed",
-            16
+        since 0 => [
+            // abstract value size
+            [u8: AbstractValueSize, "u8", 40],
+            [u64: AbstractValueSize, "u64", 40],
+            [u128: AbstractValueSize, "u128", 40],
+            [bool: AbstractValueSize, "bool", 40],
+            [address: AbstractValueSize, "address", 40],
+            [struct_: AbstractValueSize, "struct", 40],
+            [vector: AbstractValueSize, "vector", 40],
+            [reference: AbstractValueSize, "reference", 40],
+            [per_u8_packed: AbstractValueSizePerArg, "per_u8_packed", 1],
+            [per_u64_packed: AbstractValueSizePerArg, "per_u64_packed", 8],
+            [
+                per_u128_packed: AbstractValueSizePerArg,
+                "per_u128_packed",
+                16
+            ],
+            [
+                per_bool_packed: AbstractValueSizePerArg,
+                "per_bool_packed",
+                1
+            ],
+            [
+                per_address_packed: AbstractValueSizePerArg,
+                "per_address_packed",
+                32
+            ],
         ],
-        [per_u256_packed: AbstractValueSizePerArg, { 5.. => "per_u256_packed" }, 32],
-        [
-            per_bool_packed: AbstractValueSizePerArg,
-            "per_bool_packed",
-            1
-        ],
-        [
-            per_address_packed: AbstractValueSizePerArg,
-            "per_address_packed",
-            32
+        since 5 => [
+            [u16: AbstractValueSize, { 5.. => "u16" }, 40],
+            [u32: AbstractValueSize, { 5.. => "u32" }, 40],
+            [u256: AbstractValueSize, { 5.. => "u256" }, 40],
+            [per_u16_packed: AbstractValueSizePerArg, { 5.. => "per_u16_packed" }, 2],
+            [per_u32_packed: AbstractValueSizePerArg, { 5.. => "per_u32_packed" }, 4],
+            [per_u256_packed: AbstractValueSizePerArg, { 5.. => "per_u256_packed" }, 32],
         ],
     ]
 );
@@ -623,6 +629,12 @@ impl ToOnChainGasSchedule for MiscGasParameters {
     }
 }
 
+impl GasParameterNamespaces for MiscGasParameters {
+    fn namespaces(feature_version: u64) -> Vec<(u64, Vec<String>)> {
+        AbstractValueSizeGasParameters::namespaces(feature_version)
+    }
+}
+
 impl MiscGasParameters {
     pub fn zeros() -> Self {
         Self {
@@ -1,7 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::traits::{FromOnChainGasSchedule, InitialGasSchedule, ToOnChainGasSchedule};
+use crate::traits::{
+    FromOnChainGasSchedule, GasParameterNamespaces, InitialGasSchedule, ToOnChainGasSchedule,
+};
 use std::collections::BTreeMap;
 
 mod aptos_framework;
@@ -64,6 +66,14 @@ impl ToOnChainGasSchedule for AptosGasParameters {
     }
 }
 
+impl GasParameterNamespaces for AptosGasParameters {
+    fn namespaces(feature_version: u64) -> Vec<(u64, Vec<String>)> {
+        let mut namespaces = VMGasParameters::namespaces(feature_version);
+        namespaces.extend(NativeGasParameters::namespaces(feature_version));
+        namespaces
+    }
+}
+
 impl AptosGasParameters {
     pub fn zeros() -> Self {
         Self {
@@ -117,6 +127,15 @@ impl ToOnChainGasSchedule for VMGasParameters {
     }
 }
 
+impl GasParameterNamespaces for VMGasParameters {
+    fn namespaces(feature_version: u64) -> Vec<(u64, Vec<String>)> {
+        let mut namespaces = InstructionGasParameters::namespaces(feature_version);
+        namespaces.extend(TransactionGasParameters::namespaces(feature_version));
+        namespaces.extend(MiscGasParameters::namespaces(feature_version));
+        namespaces
+    }
+}
+
 impl VMGasParameters {
     pub fn zeros() -> Self {
         Self {
@@ -178,6 +197,15 @@ impl ToOnChainGasSchedule for NativeGasParameters {
     }
 }
 
+impl GasParameterNamespaces for NativeGasParameters {
+    fn namespaces(feature_version: u64) -> Vec<(u64, Vec<String>)> {
+        let mut namespaces = MoveStdlibGasParameters::namespaces(feature_version);
+        namespaces.extend(TableGasParameters::namespaces(feature_version));
+        namespaces.extend(AptosFrameworkGasParameters::namespaces(feature_version));
+        namespaces
+    }
+}
+
 impl NativeGasParameters {
     pub fn zeros() -> Self {
         Self {
@@ -22,14 +22,23 @@
 //!      gas schedule update proposals. Keep in mind that the value will only take effect
 //!      on chain after an update proposal including it has been applied.
 //!
+//! Gas parameters are grouped into versioned namespaces: each entry lives in the `since <version>`
+//! section of the gas feature version that introduced it (e.g., a new feature gated by version 12
+//! adds its parameters to the `since 12` section, creating it if needed). This is checked by a
+//! generated test, and the namespaces are used to review the (flat) on-chain gas schedule feature
+//! by feature (see [`GasScheduleNamespaces`]).
 //!
 //! The generation macro will automatically generate a struct type representing the gas parameter,
 //! which can be then used in gas expressions once imported.
 
 mod gas_schedule;
+mod namespace;
 mod traits;
 mod ver;
 
 pub use gas_schedule::*;
-pub use traits::{FromOnChainGasSchedule, InitialGasSchedule, ToOnChainGasSchedule};
+pub use namespace::{gas_parameter_category, GasParameterNamespace, GasScheduleNamespaces};
+pub use traits::{
+    FromOnChainGasSchedule, GasParameterNamespaces, InitialGasSchedule, ToOnChainGasSchedule,
+};
 pub use ver::{gas_feature_versions, LATEST_GAS_FEATURE_VERSION};
@@ -0,0 +1,180 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module groups the entries of the (flat) on-chain gas schedule by the versioned
+//! namespaces that their parameters are declared in (see the `since <version>` sections in
+//! `src/gas_schedule`). Namespaces are further split by category (e.g., `instr` or
+//! `aptos_framework.account`), so that the gas schedule can be reviewed feature by feature.
+
+use crate::{
+    gas_schedule::AptosGasParameters, traits::GasParameterNamespaces, LATEST_GAS_FEATURE_VERSION,
+};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+/// The parameter group prefixes whose parameters are further categorized by the
+/// second segment of their names (e.g., the native module name).
+const NESTED_CATEGORY_PREFIXES: &[&str] = &["aptos_framework", "move_stdlib", "misc"];
+
+/// A versioned namespace of gas parameters
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct GasParameterNamespace {
+    /// The category of the parameters (e.g., `txn` or `move_stdlib.bcs`)
+    pub category: String,
+    /// The gas feature version that introduced the parameters. This is `None` for
+    /// parameters that are unknown to this binary (e.g., removed or newer parameters).
+    pub feature_version: Option<u64>,
+}
+
+impl Display for GasParameterNamespace {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self.feature_version {
+            Some(feature_version) => write!(f, "{}@v{}", self.category, feature_version),
+            None => write!(f, "{}@unknown", self.category),
+        }
+    }
+}
+
+/// Returns the category of the given gas parameter, i.e., the prefix of its parameter
+/// group (e.g., `txn`), extended with the module name where applicable (e.g.,
+/// `aptos_framework.account` or `misc.abs_val`).
+pub fn gas_parameter_category(name: &str) -> String {
+    let segments: Vec<&str> = name.split('.').collect();
+    let num_category_segments = if NESTED_CATEGORY_PREFIXES.contains(&segments[0]) {
+        2
+    } else {
+        1
+    };
+
+    // The last segment is always (part of) the parameter name itself
+    let num_category_segments = num_category_segments.min(segments.len() - 1).max(1);
+    segments[..num_category_segments].join(".")
+}
+
+/// An index of the namespace version of each (known) on-chain gas parameter key
+#[derive(Clone, Debug)]
+pub struct GasScheduleNamespaces {
+    introduced_in: BTreeMap<String, u64>,
+}
+
+impl GasScheduleNamespaces {
+    /// Builds the index from the gas parameter namespaces declared by this binary. Keys
+    /// that were renamed keep the namespace of the parameter that they belong to.
+    pub fn new() -> Self {
+        let mut introduced_in = BTreeMap::new();
+        for feature_version in 0..=LATEST_GAS_FEATURE_VERSION {
+            for (namespace_version, keys) in AptosGasParameters::namespaces(feature_version) {
+                for key in keys {
+                    introduced_in.entry(key).or_insert(namespace_version);
+                }
+            }
+        }
+
+        Self { introduced_in }
+    }
+
+    /// Returns the versioned namespace of the given gas parameter
+    pub fn namespace_of(&self, name: &str) -> GasParameterNamespace {
+        GasParameterNamespace {
+            category: gas_parameter_category(name),
+            feature_version: self.introduced_in.get(name).cloned(),
+        }
+    }
+
+    /// Groups the given gas schedule entries by their versioned namespace
+    pub fn group_by_namespace<'a, T>(
+        &self,
+        entries: impl IntoIterator<Item = (&'a str, T)>,
+    ) -> BTreeMap<GasParameterNamespace, Vec<(&'a str, T)>> {
+        let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for (name, value) in entries {
+            groups
+                .entry(self.namespace_of(name))
+                .or_default()
+                .push((name, value));
+        }
+        groups
+    }
+}
+
+impl Default for GasScheduleNamespaces {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gas_feature_versions::RELEASE_V1_13, traits::ToOnChainGasSchedule};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_gas_parameter_category() {
+        assert_eq!(gas_parameter_category("instr.nop"), "instr");
+        assert_eq!(
+            gas_parameter_category("txn.max_transaction_size_in_bytes.gov"),
+            "txn"
+        );
+        assert_eq!(
+            gas_parameter_category("aptos_framework.account.create_address.base"),
+            "aptos_framework.account"
+        );
+        assert_eq!(gas_parameter_category("misc.abs_val.u8"), "misc.abs_val");
+        assert_eq!(
+            gas_parameter_category("aptos_framework.foo"),
+            "aptos_framework"
+        );
+        assert_eq!(gas_parameter_category("unknown"), "unknown");
+    }
+
+    #[test]
+    fn test_namespace_versions() {
+        let namespaces = GasScheduleNamespaces::new();
+
+        // Verify the namespaces of parameters introduced at different versions
+        let namespace = namespaces.namespace_of("instr.nop");
+        assert_eq!(namespace.category, "instr");
+        assert_eq!(namespace.feature_version, Some(0));
+
+        let namespace = namespaces.namespace_of("txn.max_transaction_size_in_bytes.gov");
+        assert_eq!(namespace.category, "txn");
+        assert_eq!(namespace.feature_version, Some(RELEASE_V1_13));
+        assert_eq!(namespace.to_string(), format!("txn@v{}", RELEASE_V1_13));
+
+        // Verify that renamed parameters keep the namespace they are declared in
+        let namespace = namespaces.namespace_of("txn.storage_io_per_state_slot_read");
+        assert_eq!(namespace.feature_version, Some(0));
+
+        // Verify that unknown parameters have no version
+        assert_eq!(
+            namespaces
+                .namespace_of("txn.unknown_parameter")
+                .feature_version,
+            None
+        );
+    }
+
+    #[test]
+    fn test_namespaces_cover_gas_schedule() {
+        for feature_version in 0..=LATEST_GAS_FEATURE_VERSION {
+            // Verify that every on-chain key belongs to exactly one namespace
+            let namespaced_keys: Vec<_> = AptosGasParameters::namespaces(feature_version)
+                .into_iter()
+                .flat_map(|(_, keys)| keys)
+                .collect();
+            let on_chain_keys: BTreeSet<_> = AptosGasParameters::zeros()
+                .to_on_chain_gas_schedule(feature_version)
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect();
+            assert_eq!(namespaced_keys.len(), on_chain_keys.len());
+            assert_eq!(
+                namespaced_keys.into_iter().collect::<BTreeSet<_>>(),
+                on_chain_keys
+            );
+        }
+    }
+}
@@ -27,3 +27,11 @@ pub trait InitialGasSchedule: Sized {
     /// Returns the initial value of this type, which is used in the genesis.
     fn initial() -> Self;
 }
+
+/// A trait for listing the versioned namespaces of the gas parameters, i.e., the parameters
+/// grouped by the gas feature version that introduced them.
+pub trait GasParameterNamespaces {
+    /// Returns the on-chain keys of the parameters that exist at the given feature version,
+    /// grouped by the feature version of their namespace.
+    fn namespaces(feature_version: u64) -> Vec<(u64, Vec<String>)>;
+}
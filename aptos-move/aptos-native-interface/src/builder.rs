@@ -7,7 +7,7 @@ use crate::{
 };
 use aptos_gas_algebra::DynamicExpression;
 use aptos_gas_schedule::{MiscGasParameters, NativeGasParameters, ToOnChainGasSchedule};
-use aptos_types::on_chain_config::{Features, TimedFeatures};
+use aptos_types::on_chain_config::{FeatureFlag, Features, TimedFeatures};
 use bytes::Bytes;
 use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
 use move_vm_types::{
@@ -16,6 +16,11 @@ use move_vm_types::{
 use smallvec::SmallVec;
 use std::{collections::VecDeque, sync::Arc};
 
+/// The abort code returned by natives that are disabled by an on-chain feature flag.
+///
+/// This corresponds to `std::error::unavailable(1)` in Move.
+pub const NATIVE_DISABLED_ABORT_CODE: u64 = 0x0D_0001;
+
 /// Data shared by all native functions, mostly on-chain configurations.
 #[derive(Debug)]
 struct SharedData {
@@ -33,6 +38,7 @@ pub struct SafeNativeBuilder {
     data: Arc<SharedData>,
     enable_incremental_gas_charging: bool,
     gas_hook: Option<Arc<dyn Fn(DynamicExpression) + Send + Sync>>,
+    required_features: Vec<FeatureFlag>,
 }
 
 impl SafeNativeBuilder {
@@ -57,6 +63,7 @@ impl SafeNativeBuilder {
             }),
             enable_incremental_gas_charging: true,
             gas_hook,
+            required_features: vec![],
         }
     }
 
@@ -85,6 +92,36 @@ impl SafeNativeBuilder {
         res
     }
 
+    /// Convenience function that makes all natives created within the given closure require the
+    /// given on-chain feature flag to be enabled.
+    ///
+    /// The feature flags are resolved when the natives are created (i.e., whenever a new VM is
+    /// created for a new set of on-chain configurations, such as at epoch boundaries). If any of
+    /// the required features is disabled, the natives abort deterministically with
+    /// [`NATIVE_DISABLED_ABORT_CODE`] without charging any gas. This allows a problematic native
+    /// to be switched off through governance, without a binary release.
+    ///
+    /// Calls can be nested, in which case all the given features are required.
+    ///
+    /// Note that [`Self::make_named_natives()`] is lazy, so its result must be collected within
+    /// the closure for the requirement to take effect.
+    pub fn with_required_feature<F, R>(&mut self, feature: FeatureFlag, action: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        self.required_features.push(feature);
+        let res = action(self);
+        self.required_features.pop();
+        res
+    }
+
+    /// Returns true iff all the features required by natives created at this point are enabled.
+    fn required_features_enabled(&self) -> bool {
+        self.required_features
+            .iter()
+            .all(|feature| self.data.features.is_enabled(*feature))
+    }
+
     /// Transforms a raw native function into a closure that can be used inside the Move VM.
     ///
     /// The closure will have access to the common Aptos configurations (features, gas params etc.),
@@ -104,10 +141,15 @@ impl SafeNativeBuilder {
         let hook = self.gas_hook.clone();
 
         let enable_incremental_gas_charging = self.enable_incremental_gas_charging;
+        let enabled = self.required_features_enabled();
 
         let closure = move |context: &mut NativeContext, ty_args, args| {
             use SafeNativeError::*;
 
+            if !enabled {
+                return Ok(NativeResult::err(0.into(), NATIVE_DISABLED_ABORT_CODE));
+            }
+
             let gas_budget = context.gas_balance();
 
             let mut context = SafeNativeContext {
@@ -185,6 +227,7 @@ impl SafeNativeBuilder {
             data,
             enable_incremental_gas_charging,
             gas_hook: _gas_hook,
+            required_features: _required_features,
         } = self;
         let SharedData {
             gas_feature_version,
@@ -206,3 +249,50 @@ impl SafeNativeBuilder {
         .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::on_chain_config::TimedFeaturesBuilder;
+
+    fn create_builder(features: Features) -> SafeNativeBuilder {
+        SafeNativeBuilder::new(
+            0,
+            NativeGasParameters::zeros(),
+            MiscGasParameters::zeros(),
+            TimedFeaturesBuilder::enable_all().build(),
+            features,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_required_features() {
+        // Create a builder with the bulletproofs natives disabled
+        let mut features = Features::default();
+        features.enable(FeatureFlag::BULLETPROOFS_NATIVES);
+        features.disable(FeatureFlag::BLS12_381_STRUCTURES);
+        let mut builder = create_builder(features);
+        assert!(builder.required_features_enabled());
+
+        // Verify natives requiring an enabled feature are enabled
+        builder.with_required_feature(FeatureFlag::BULLETPROOFS_NATIVES, |builder| {
+            assert!(builder.required_features_enabled());
+
+            // Verify nested requirements must all be enabled
+            builder.with_required_feature(FeatureFlag::BLS12_381_STRUCTURES, |builder| {
+                assert!(!builder.required_features_enabled());
+            });
+            assert!(builder.required_features_enabled());
+        });
+
+        // Verify natives requiring a disabled feature are disabled
+        builder.with_required_feature(FeatureFlag::BLS12_381_STRUCTURES, |builder| {
+            assert!(!builder.required_features_enabled());
+        });
+
+        // Verify the requirements are removed once the closures return
+        assert!(builder.required_features.is_empty());
+        assert!(builder.required_features_enabled());
+    }
+}
@@ -20,7 +20,7 @@ mod helpers;
 #[doc(hidden)]
 pub mod reexports;
 
-pub use builder::SafeNativeBuilder;
+pub use builder::{SafeNativeBuilder, NATIVE_DISABLED_ABORT_CODE};
 pub use context::SafeNativeContext;
 pub use errors::{SafeNativeError, SafeNativeResult};
 pub use native::RawSafeNative;
@@ -3,9 +3,103 @@
 
 use crate::{components::get_signer_arg, utils::*};
 use anyhow::Result;
+use aptos_gas_schedule::{GasParameterNamespace, GasScheduleNamespaces};
 use aptos_types::on_chain_config::{DiffItem, GasScheduleV2};
 use move_model::{code_writer::CodeWriter, emit, emitln, model::Loc};
 use sha3::{Digest, Sha3_512};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+};
+
+/// A human-readable report of the changes between two gas schedules, with the
+/// changed parameters grouped by their versioned namespace (see [`GasScheduleNamespaces`]).
+#[derive(Debug)]
+pub struct GasScheduleDiffReport {
+    /// The feature version of the old gas schedule
+    pub old_feature_version: u64,
+    /// The feature version of the new gas schedule
+    pub new_feature_version: u64,
+    /// The changed parameters (by namespace)
+    pub changes: BTreeMap<GasParameterNamespace, Vec<(String, DiffItem<u64>)>>,
+}
+
+impl GasScheduleDiffReport {
+    /// Diffs the given gas schedules
+    pub fn new(old_gas_schedule: &GasScheduleV2, new_gas_schedule: &GasScheduleV2) -> Self {
+        let changes = GasScheduleNamespaces::new()
+            .group_by_namespace(GasScheduleV2::diff(old_gas_schedule, new_gas_schedule))
+            .into_iter()
+            .map(|(namespace, changes)| {
+                let changes = changes
+                    .into_iter()
+                    .map(|(name, delta)| (name.to_string(), delta))
+                    .collect();
+                (namespace, changes)
+            })
+            .collect();
+
+        Self {
+            old_feature_version: old_gas_schedule.feature_version,
+            new_feature_version: new_gas_schedule.feature_version,
+            changes,
+        }
+    }
+
+    /// Returns the total number of changed parameters
+    pub fn num_changes(&self) -> usize {
+        self.changes.values().map(|changes| changes.len()).sum()
+    }
+}
+
+impl Display for GasScheduleDiffReport {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        if self.old_feature_version == self.new_feature_version {
+            writeln!(f, "Feature version: {}", self.new_feature_version)?;
+        } else {
+            writeln!(
+                f,
+                "Feature version: {} -> {}",
+                self.old_feature_version, self.new_feature_version
+            )?;
+        }
+        writeln!(
+            f,
+            "Changed parameters: {} (in {} namespaces)",
+            self.num_changes(),
+            self.changes.len()
+        )?;
+
+        for (namespace, changes) in &self.changes {
+            writeln!(f)?;
+            writeln!(f, "[{}]", namespace)?;
+            let max_len = changes
+                .iter()
+                .fold(0, |acc, (name, _)| usize::max(acc, name.len()));
+            for (name, delta) in changes {
+                let name_with_spaces = format!("{}{}", name, " ".repeat(max_len - name.len()));
+                match delta {
+                    DiffItem::Add { new_val } => {
+                        writeln!(f, "  +  {} :  {}", name_with_spaces, new_val)?;
+                    },
+                    DiffItem::Delete { old_val } => {
+                        writeln!(f, "  -  {} :  {}", name_with_spaces, old_val)?;
+                    },
+                    DiffItem::Modify { old_val, new_val } => {
+                        write!(f, "     {} :  {} -> {}", name_with_spaces, old_val, new_val)?;
+                        if *old_val != 0 {
+                            let percent_change =
+                                (*new_val as f64 - *old_val as f64) * 100.0 / *old_val as f64;
+                            write!(f, " ({:+.2}%)", percent_change)?;
+                        }
+                        writeln!(f)?;
+                    },
+                }
+            }
+        }
+        Ok(())
+    }
+}
 
 fn emit_gas_schedule_diff(
     writer: &CodeWriter,
@@ -149,3 +243,51 @@ pub fn generate_gas_upgrade_proposal(
     result.push(("gas-schedule".to_string(), proposal));
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_gas_schedule::gas_feature_versions::RELEASE_V1_13;
+
+    #[test]
+    fn test_gas_schedule_diff_report() {
+        let old_gas_schedule = GasScheduleV2 {
+            feature_version: 12,
+            entries: vec![
+                ("instr.nop".to_string(), 36),
+                ("instr.ret".to_string(), 220),
+                ("txn.unknown_parameter".to_string(), 1),
+            ],
+        };
+        let new_gas_schedule = GasScheduleV2 {
+            feature_version: 13,
+            entries: vec![
+                ("instr.nop".to_string(), 36),
+                ("instr.ret".to_string(), 330),
+                ("txn.max_transaction_size_in_bytes.gov".to_string(), 1024),
+            ],
+        };
+
+        // Verify that the changes are grouped by namespace (and unchanged parameters are omitted)
+        let report = GasScheduleDiffReport::new(&old_gas_schedule, &new_gas_schedule);
+        assert_eq!(report.num_changes(), 3);
+        let namespaces: Vec<_> = report
+            .changes
+            .keys()
+            .map(|namespace| namespace.to_string())
+            .collect();
+        let expected_namespaces = vec![
+            "instr@v0".to_string(),
+            "txn@unknown".to_string(),
+            format!("txn@v{}", RELEASE_V1_13),
+        ];
+        assert_eq!(namespaces, expected_namespaces);
+
+        // Verify the human-readable report
+        let report = report.to_string();
+        assert!(report.contains("Feature version: 12 -> 13"));
+        assert!(report.contains("instr.ret :  220 -> 330 (+50.00%)"));
+        assert!(report.contains("+  txn.max_transaction_size_in_bytes.gov :  1024"));
+        assert!(report.contains("-  txn.unknown_parameter :  1"));
+    }
+}
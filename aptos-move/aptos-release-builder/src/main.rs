@@ -6,18 +6,23 @@ use aptos_crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterialStringExt};
 use aptos_framework::natives::code::PackageRegistry;
 use aptos_gas_schedule::LATEST_GAS_FEATURE_VERSION;
 use aptos_release_builder::{
-    components::fetch_config,
+    components::{fetch_config, gas::GasScheduleDiffReport},
     initialize_aptos_core_path,
-    simulate::simulate_all_proposals,
+    rehearse::rehearse_epoch_change,
+    simulate::{collect_proposals, simulate_all_proposals},
     validate::{DEFAULT_RESOLUTION_TIME, FAST_RESOLUTION_TIME},
 };
 use aptos_types::{
     account_address::AccountAddress,
     chain_id::ChainId,
     jwks::{ObservedJWKs, SupportedOIDCProviders},
+    on_chain_config::GasScheduleV2,
 };
 use clap::{Parser, Subcommand};
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 use url::Url;
 
 #[derive(Parser)]
@@ -65,6 +70,51 @@ impl NetworkSelection {
     }
 }
 
+/// The source of a gas schedule (e.g., to diff)
+#[derive(Clone, Debug)]
+pub enum GasScheduleSource {
+    /// The gas schedule defined by this binary (at the latest feature version)
+    Current,
+    /// A gas schedule json file (e.g., as generated by `generate-gas-schedule`)
+    LocalFile(PathBuf),
+    /// The on-chain gas schedule of a network
+    Network(NetworkSelection),
+}
+
+impl FromStr for GasScheduleSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, anyhow::Error> {
+        Ok(if s == "current" {
+            Self::Current
+        } else if Path::new(s).is_file() {
+            Self::LocalFile(PathBuf::from(s))
+        } else {
+            Self::Network(NetworkSelection::from_str(s)?)
+        })
+    }
+}
+
+impl GasScheduleSource {
+    fn load_gas_schedule(&self) -> anyhow::Result<GasScheduleV2> {
+        match self {
+            Self::Current => Ok(aptos_gas_schedule_updator::current_gas_schedule(
+                LATEST_GAS_FEATURE_VERSION,
+            )),
+            Self::LocalFile(path) => {
+                let file_contents = std::fs::read_to_string(path).with_context(|| {
+                    format!("Failed to read the gas schedule at {}", path.display())
+                })?;
+                Ok(serde_json::from_str(&file_contents)?)
+            },
+            Self::Network(network) => {
+                let client = aptos_rest_client::Client::new(network.to_url()?);
+                fetch_config::<GasScheduleV2>(&client)
+            },
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Generate sets of governance proposals based on the release_config file passed in
@@ -92,6 +142,23 @@ pub enum Commands {
         #[clap(long)]
         network: NetworkSelection,
     },
+    /// Rehearse the epoch change caused by a multi-step proposal against a local DB
+    /// snapshot. The governance scripts are executed as if the proposal is already
+    /// approved, after which the epoch change is forced (applying all buffered config
+    /// changes and recomputing the validator set). Any execution failures are reported.
+    RehearseEpochChange {
+        /// Directory that contains the scripts of a single multi-step proposal.
+        #[clap(short, long)]
+        path: PathBuf,
+
+        /// Path to the DB snapshot (i.e., the root of an AptosDB).
+        #[clap(long)]
+        db_path: PathBuf,
+
+        /// The version of the DB snapshot to rehearse against (defaults to the latest version).
+        #[clap(long)]
+        version: Option<u64>,
+    },
     /// Generate sets of governance proposals with default release config.
     WriteDefault {
         #[clap(short, long)]
@@ -125,6 +192,20 @@ pub enum Commands {
         #[clap(short, long)]
         output_path: Option<PathBuf>,
     },
+    /// Diff two gas schedules, and print the changed parameters (grouped by their
+    /// versioned namespace, i.e., category and the gas feature version that introduced them).
+    DiffGasSchedules {
+        /// The old gas schedule.
+        ///
+        /// Possible values: devnet, testnet, mainnet, <url to rest endpoint> (for the on-chain
+        /// gas schedule), current (for the gas schedule of this binary), <path to json file>
+        #[clap(long)]
+        old: GasScheduleSource,
+
+        /// The new gas schedule. Takes the same values as `old`.
+        #[clap(long)]
+        new: GasScheduleSource,
+    },
     /// Print out current values of on chain configs.
     PrintConfigs {
         /// Url endpoint for the desired network. e.g: https://fullnode.mainnet.aptoslabs.com/v1.
@@ -202,6 +283,24 @@ async fn main() -> anyhow::Result<()> {
             simulate_all_proposals(network.to_url()?, &path).await?;
             Ok(())
         },
+        Commands::RehearseEpochChange {
+            path,
+            db_path,
+            version,
+        } => {
+            let proposal_scripts = match collect_proposals(&path)?.as_slice() {
+                [(_, proposal_scripts)] => proposal_scripts.clone(),
+                proposals => anyhow::bail!(
+                    "expected exactly one proposal at {}, found {}",
+                    path.display(),
+                    proposals.len()
+                ),
+            };
+
+            let report = rehearse_epoch_change(&db_path, version, &proposal_scripts).await?;
+            println!("{}", report);
+            Ok(())
+        },
         Commands::WriteDefault { output_path } => {
             aptos_release_builder::ReleaseConfig::default().save_config(output_path.as_path())
         },
@@ -297,6 +396,18 @@ async fn main() -> anyhow::Result<()> {
 
             Ok(())
         },
+        Commands::DiffGasSchedules { old, new } => {
+            let old_gas_schedule = old
+                .load_gas_schedule()
+                .context("Failed to load the old gas schedule")?;
+            let new_gas_schedule = new
+                .load_gas_schedule()
+                .context("Failed to load the new gas schedule")?;
+
+            let report = GasScheduleDiffReport::new(&old_gas_schedule, &new_gas_schedule);
+            println!("{}", report);
+            Ok(())
+        },
         Commands::PrintConfigs {
             endpoint,
             print_gas_schedule,
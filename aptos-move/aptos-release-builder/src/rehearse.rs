@@ -0,0 +1,230 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements the rehearsal of epoch changes.
+//!
+//! Given a DB snapshot (e.g., a backup restored locally, or the DB of a stopped node)
+//! and a proposed governance change set (i.e., the scripts of a multi-step proposal),
+//! the rehearsal executes the governance scripts on top of the snapshot (using the same
+//! mechanism as the proposal simulation) and then forces the epoch transition. This
+//! applies all buffered on-chain config changes, executes any framework upgrades and
+//! recomputes the validator set, exactly as would happen on-chain.
+//!
+//! Any execution failure is reported before the proposal is actually submitted. On
+//! success, a report of the resulting epoch change (i.e., the validator set changes
+//! and the on-chain configs that were modified) is returned.
+
+use crate::simulate::{
+    compile_proposal_scripts, execute_proposal_scripts, force_end_epoch, SimulationStateView,
+};
+use anyhow::{Context, Result};
+use aptos_move_debugger::aptos_debugger::AptosDebugger;
+use aptos_types::{
+    account_address::AccountAddress,
+    on_chain_config::{
+        AptosVersion, ConfigurationResource, Features, GasScheduleV2, OnChainConfig,
+        OnChainConsensusConfig, OnChainExecutionConfig, OnChainJWKConsensusConfig,
+        RandomnessConfigMoveStruct, StorageGasSchedule, ValidatorSet,
+    },
+    state_store::{state_key::StateKey, StateView},
+    transaction::Version,
+};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+/// The outcome of a successful epoch change rehearsal
+#[derive(Clone, Debug, Serialize)]
+pub struct EpochChangeReport {
+    /// The version of the DB snapshot the rehearsal was executed against
+    pub version: Version,
+    /// The epoch before the change
+    pub epoch_before: u64,
+    /// The epoch after the change
+    pub epoch_after: u64,
+    /// The active validators before the change
+    pub validators_before: Vec<AccountAddress>,
+    /// The active validators after the change
+    pub validators_after: Vec<AccountAddress>,
+    /// The on-chain configs that were modified by the change
+    pub changed_configs: Vec<String>,
+}
+
+impl EpochChangeReport {
+    /// Returns the validators that joined the active validator set
+    pub fn added_validators(&self) -> Vec<AccountAddress> {
+        self.validators_after
+            .iter()
+            .filter(|validator| !self.validators_before.contains(validator))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the validators that left the active validator set
+    pub fn removed_validators(&self) -> Vec<AccountAddress> {
+        self.validators_before
+            .iter()
+            .filter(|validator| !self.validators_after.contains(validator))
+            .cloned()
+            .collect()
+    }
+}
+
+impl Display for EpochChangeReport {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        writeln!(f, "Epoch change rehearsed at version {}", self.version)?;
+        writeln!(
+            f,
+            "    Epoch: {} -> {}",
+            self.epoch_before, self.epoch_after
+        )?;
+        writeln!(
+            f,
+            "    Active validators: {} -> {}",
+            self.validators_before.len(),
+            self.validators_after.len()
+        )?;
+        for validator in self.added_validators() {
+            writeln!(f, "        + {}", validator)?;
+        }
+        for validator in self.removed_validators() {
+            writeln!(f, "        - {}", validator)?;
+        }
+        if self.changed_configs.is_empty() {
+            writeln!(f, "    Changed on-chain configs: none")?;
+        } else {
+            writeln!(f, "    Changed on-chain configs:")?;
+            for config in &self.changed_configs {
+                writeln!(f, "        {}", config)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The raw bytes of the on-chain configs tracked by the rehearsal (by config name)
+type ConfigSnapshot = BTreeMap<&'static str, Option<Vec<u8>>>;
+
+/// Returns the raw bytes of the given on-chain config (if it exists)
+fn fetch_config_bytes<C: OnChainConfig>(state_view: &impl StateView) -> Result<Option<Vec<u8>>> {
+    let state_key = StateKey::on_chain_config::<C>()?;
+    let state_value = state_view.get_state_value(&state_key)?;
+    Ok(state_value.map(|state_value| state_value.bytes().to_vec()))
+}
+
+/// Takes a snapshot of all on-chain configs that may be modified by an epoch change
+fn snapshot_configs(state_view: &impl StateView) -> Result<ConfigSnapshot> {
+    let mut snapshot = BTreeMap::new();
+
+    macro_rules! snapshot_configs {
+        ($($type:ty), *) => {
+            $(
+                snapshot.insert(
+                    <$type>::TYPE_IDENTIFIER,
+                    fetch_config_bytes::<$type>(state_view)?,
+                );
+            )*
+        }
+    }
+
+    snapshot_configs!(
+        AptosVersion,
+        Features,
+        GasScheduleV2,
+        StorageGasSchedule,
+        OnChainConsensusConfig,
+        OnChainExecutionConfig,
+        OnChainJWKConsensusConfig,
+        RandomnessConfigMoveStruct,
+        ValidatorSet
+    );
+
+    Ok(snapshot)
+}
+
+/// Returns the current epoch and active validators of the given state view
+fn fetch_epoch_and_validators(state_view: &impl StateView) -> Result<(u64, Vec<AccountAddress>)> {
+    let configuration = ConfigurationResource::fetch_config(state_view)
+        .context("failed to fetch the reconfiguration resource")?;
+    let validator_set =
+        ValidatorSet::fetch_config(state_view).context("failed to fetch the validator set")?;
+    Ok((configuration.epoch(), validator_set.active_validators()))
+}
+
+/// Rehearses the epoch change that results from executing the given governance scripts
+/// (i.e., the steps of a multi-step proposal, in order) against the DB snapshot at the
+/// given path. If no version is specified, the latest version in the DB is used.
+pub async fn rehearse_epoch_change(
+    db_path: &Path,
+    version: Option<Version>,
+    proposal_scripts: &[PathBuf],
+) -> Result<EpochChangeReport> {
+    // Compile all scripts.
+    let compiled_scripts = compile_proposal_scripts(proposal_scripts)?;
+
+    // Set up the simulation state view on top of the DB snapshot.
+    let debugger = AptosDebugger::db(db_path)
+        .with_context(|| format!("failed to open the DB at {}", db_path.display()))?;
+    let version = match version {
+        Some(version) => version,
+        None => debugger
+            .get_latest_version()
+            .await
+            .context("failed to fetch the latest version from the DB")?,
+    };
+    let debugger_state_view = debugger.state_view_at_version(version);
+    let state_view = SimulationStateView::new(&debugger_state_view);
+
+    // Capture the state before the epoch change.
+    let (epoch_before, validators_before) = fetch_epoch_and_validators(&state_view)?;
+    let configs_before = snapshot_configs(&state_view)?;
+
+    // Execute the governance scripts, and force the epoch change (so that the changes
+    // buffered by the last script are applied, and the validator set is recomputed).
+    execute_proposal_scripts(&state_view, proposal_scripts, compiled_scripts)?;
+    force_end_epoch(&state_view).context("failed to execute the epoch change")?;
+
+    // Capture the state after the epoch change and compare.
+    let (epoch_after, validators_after) = fetch_epoch_and_validators(&state_view)?;
+    let configs_after = snapshot_configs(&state_view)?;
+    let changed_configs = configs_after
+        .iter()
+        .filter(|(name, bytes)| configs_before.get(*name) != Some(*bytes))
+        .map(|(name, _)| name.to_string())
+        .collect();
+
+    Ok(EpochChangeReport {
+        version,
+        epoch_before,
+        epoch_after,
+        validators_before,
+        validators_after,
+        changed_configs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_change_report_validator_diff() {
+        let validator_1 = AccountAddress::random();
+        let validator_2 = AccountAddress::random();
+        let validator_3 = AccountAddress::random();
+        let report = EpochChangeReport {
+            version: 100,
+            epoch_before: 5,
+            epoch_after: 6,
+            validators_before: vec![validator_1, validator_2],
+            validators_after: vec![validator_2, validator_3],
+            changed_configs: vec!["ValidatorSet".to_string()],
+        };
+
+        assert_eq!(report.added_validators(), vec![validator_3]);
+        assert_eq!(report.removed_validators(), vec![validator_1]);
+    }
+}
@@ -201,7 +201,7 @@ fn add_simple_native_function(
 /// It comprises two components:
 /// - A remote debugger state view to enable on-demand data fetching.
 /// - A local state store to allow new changes to be stacked on top of the remote state.
-struct SimulationStateView<'a, S> {
+pub(crate) struct SimulationStateView<'a, S> {
     remote: &'a S,
     states: Mutex<HashMap<StateKey, Option<StateValue>>>,
 }
@@ -210,6 +210,13 @@ impl<'a, S> SimulationStateView<'a, S>
 where
     S: StateView,
 {
+    pub(crate) fn new(remote: &'a S) -> Self {
+        Self {
+            remote,
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
     fn set_state_value(&self, state_key: StateKey, state_val: StateValue) {
         self.states.lock().insert(state_key, Some(state_val));
     }
@@ -459,7 +466,9 @@ fn add_script_execution_hash(
  * Simulation Workflow
  *
  **************************************************************************************************/
-fn force_end_epoch(state_view: &SimulationStateView<impl StateView>) -> Result<()> {
+/// Force-ends the current epoch, so that buffered configuration changes get applied
+/// and the validator set is recomputed.
+pub(crate) fn force_end_epoch(state_view: &SimulationStateView<impl StateView>) -> Result<()> {
     flush_warm_vm_cache();
     let vm = AptosVM::new_for_gov_sim(&state_view);
     let resolver = state_view.as_move_resolver();
@@ -494,14 +503,10 @@ fn force_end_epoch(state_view: &SimulationStateView<impl StateView>) -> Result<(
     Ok(())
 }
 
-pub async fn simulate_multistep_proposal(
-    remote_url: Url,
-    proposal_dir: &Path,
+/// Compiles the given governance scripts, returning the blob and hash of each script.
+pub(crate) fn compile_proposal_scripts(
     proposal_scripts: &[PathBuf],
-) -> Result<()> {
-    println!("Simulating proposal at {}", proposal_dir.display());
-
-    // Compile all scripts.
+) -> Result<Vec<(Vec<u8>, HashValue)>> {
     println!("Compiling scripts...");
     let mut compiled_scripts = vec![];
     for path in proposal_scripts {
@@ -529,17 +534,17 @@ pub async fn simulate_multistep_proposal(
         compiled_scripts.push((blob, hash));
     }
 
-    // Set up the simulation state view.
-    let client = Client::new(remote_url);
-    let debugger =
-        AptosDebugger::rest_client(client.clone()).context("failed to create AptosDebugger")?;
-    let state = client.get_ledger_information().await?.into_inner();
-
-    let state_view = SimulationStateView {
-        remote: &debugger.state_view_at_version(state.version),
-        states: Mutex::new(HashMap::new()),
-    };
+    Ok(compiled_scripts)
+}
 
+/// Executes the compiled governance scripts of a multi-step proposal (in order) on
+/// top of the given state view, as if the proposal is already approved. Fails if any
+/// of the scripts fails to execute.
+pub(crate) fn execute_proposal_scripts(
+    state_view: &SimulationStateView<impl StateView>,
+    proposal_scripts: &[PathBuf],
+    compiled_scripts: Vec<(Vec<u8>, HashValue)>,
+) -> Result<()> {
     // Create and fund a sender account that is used to send the governance scripts.
     print!("Creating and funding sender account.. ");
     std::io::stdout().flush()?;
@@ -658,6 +663,30 @@ pub async fn simulate_multistep_proposal(
         state_view.apply_write_set(write_set);
     }
 
+    Ok(())
+}
+
+pub async fn simulate_multistep_proposal(
+    remote_url: Url,
+    proposal_dir: &Path,
+    proposal_scripts: &[PathBuf],
+) -> Result<()> {
+    println!("Simulating proposal at {}", proposal_dir.display());
+
+    // Compile all scripts.
+    let compiled_scripts = compile_proposal_scripts(proposal_scripts)?;
+
+    // Set up the simulation state view.
+    let client = Client::new(remote_url);
+    let debugger =
+        AptosDebugger::rest_client(client.clone()).context("failed to create AptosDebugger")?;
+    let state = client.get_ledger_information().await?.into_inner();
+    let debugger_state_view = debugger.state_view_at_version(state.version);
+    let state_view = SimulationStateView::new(&debugger_state_view);
+
+    // Execute the governance scripts.
+    execute_proposal_scripts(&state_view, proposal_scripts, compiled_scripts)?;
+
     println!("All scripts succeeded!");
 
     Ok(())
@@ -91,6 +91,9 @@ pub struct DebuggerStateView {
         )>,
     >,
     version: Version,
+    /// State values that take precedence over the ones read from the DB
+    /// (e.g., to replay transactions with recorded randomness seeds).
+    overrides: HashMap<StateKey, StateValue>,
 }
 
 async fn handler_thread<'a>(
@@ -140,9 +143,16 @@ impl DebuggerStateView {
         Self {
             query_sender: Mutex::new(query_sender),
             version,
+            overrides: HashMap::new(),
         }
     }
 
+    /// Overrides the given state values for all reads from this view
+    pub fn with_overrides(mut self, overrides: HashMap<StateKey, StateValue>) -> Self {
+        self.overrides.extend(overrides);
+        self
+    }
+
     fn get_state_value_internal(
         &self,
         state_key: &StateKey,
@@ -165,6 +175,9 @@ impl TStateView for DebuggerStateView {
     }
 
     fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        if let Some(state_value) = self.overrides.get(state_key) {
+            return Ok(Some(state_value.clone()));
+        }
         self.get_state_value_internal(state_key, self.version)
             .map_err(Into::into)
     }
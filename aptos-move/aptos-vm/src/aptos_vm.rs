@@ -108,7 +108,6 @@ use move_vm_runtime::{
     module_traversal::{TraversalContext, TraversalStorage},
 };
 use move_vm_types::gas::{GasMeter, UnmeteredGasMeter};
-use num_cpus;
 use once_cell::sync::OnceCell;
 use std::{
     cmp::{max, min},
@@ -121,6 +120,7 @@ static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
 static NUM_EXECUTION_SHARD: OnceCell<usize> = OnceCell::new();
 static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static DISCARD_FAILED_BLOCKS: OnceCell<bool> = OnceCell::new();
+static SPECULATIVE_WRITE_BUDGET_BYTES: OnceCell<Option<u64>> = OnceCell::new();
 static PROCESSED_TRANSACTIONS_DETAILED_COUNTERS: OnceCell<bool> = OnceCell::new();
 
 macro_rules! deprecated_module_bundle {
@@ -300,7 +300,7 @@ impl AptosVM {
 
     /// Sets execution concurrency level when invoked the first time.
     pub fn set_concurrency_level_once(mut concurrency_level: usize) {
-        concurrency_level = min(concurrency_level, num_cpus::get());
+        concurrency_level = min(concurrency_level, aptos_runtimes::num_available_cpus());
         // Only the first call succeeds, due to OnceCell semantics.
         EXECUTION_CONCURRENCY_LEVEL.set(concurrency_level).ok();
     }
@@ -343,6 +343,20 @@ impl AptosVM {
         }
     }
 
+    /// Sets the speculative write budget (in bytes) when invoked the first time.
+    pub fn set_speculative_write_budget_bytes_once(budget_bytes: Option<u64>) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        SPECULATIVE_WRITE_BUDGET_BYTES.set(budget_bytes).ok();
+    }
+
+    /// Get the speculative write budget if already set, otherwise return default (None)
+    pub fn get_speculative_write_budget_bytes() -> Option<u64> {
+        match SPECULATIVE_WRITE_BUDGET_BYTES.get() {
+            Some(budget_bytes) => *budget_bytes,
+            None => None,
+        }
+    }
+
     /// Sets the # of async proof reading threads.
     pub fn set_num_proof_reading_threads_once(mut num_threads: usize) {
         // TODO(grao): Do more analysis to tune this magic number.
@@ -2583,6 +2597,7 @@ impl VMExecutor for AptosVM {
                     concurrency_level: Self::get_concurrency_level(),
                     allow_fallback: true,
                     discard_failed_blocks: Self::get_discard_failed_blocks(),
+                    speculative_write_budget_bytes: Self::get_speculative_write_budget_bytes(),
                 },
                 onchain: onchain_config,
             },
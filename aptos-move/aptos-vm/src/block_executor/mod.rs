@@ -2,6 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod randomness_seed_sidecar;
 pub(crate) mod vm_wrapper;
 
 use crate::{
@@ -50,7 +51,7 @@ use std::{
 pub static RAYON_EXEC_POOL: Lazy<Arc<rayon::ThreadPool>> = Lazy::new(|| {
     Arc::new(
         rayon::ThreadPoolBuilder::new()
-            .num_threads(num_cpus::get())
+            .num_threads(aptos_runtimes::num_available_cpus())
             .thread_name(|index| format!("par_exec-{}", index))
             .build()
             .unwrap(),
@@ -431,6 +432,14 @@ impl BlockAptosVM {
                     flush_speculative_logs(pos);
                 }
 
+                // Record the randomness seeds of the executed block (if enabled)
+                if matches!(
+                    state_view.id(),
+                    StateViewId::BlockExecution { .. } | StateViewId::ChunkExecution { .. }
+                ) {
+                    randomness_seed_sidecar::record_randomness_seeds(signature_verified_block);
+                }
+
                 Ok(BlockOutput::new(output_vec, block_end_info))
             },
             Err(BlockExecutionError::FatalBlockExecutorError(PanicError::CodeInvariantError(
@@ -0,0 +1,213 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A debug-only sidecar that records the randomness seed used by each executed transaction
+//! (as JSON lines), so that transactions consuming randomness can be replayed locally.
+
+use aptos_infallible::Mutex;
+use aptos_logger::{error, info};
+use aptos_types::{
+    randomness::RandomnessSeedRecord,
+    transaction::{signature_verified_transaction::SignatureVerifiedTransaction, Transaction},
+};
+use once_cell::sync::OnceCell;
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+/// The sidecar file (only set if randomness seed recording is enabled)
+static RANDOMNESS_SEED_SIDECAR: OnceCell<Mutex<File>> = OnceCell::new();
+
+/// Enables the recording of randomness seeds to the sidecar file at the given path.
+/// Only the first call succeeds, due to OnceCell semantics.
+pub fn enable_randomness_seed_sidecar(path: &Path) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    if RANDOMNESS_SEED_SIDECAR.set(Mutex::new(file)).is_ok() {
+        info!("Recording randomness seeds to sidecar file: {:?}", path);
+    }
+    Ok(())
+}
+
+/// Records the randomness seeds of the user transactions in the given transactions
+pub(crate) fn record_randomness_seeds(transactions: &[SignatureVerifiedTransaction]) {
+    let Some(sidecar) = RANDOMNESS_SEED_SIDECAR.get() else {
+        return; // Recording is disabled
+    };
+
+    let records = randomness_seed_records(transactions);
+    if records.is_empty() {
+        return;
+    }
+
+    let mut sidecar = sidecar.lock();
+    for record in records {
+        if let Err(error) = write_record(&mut *sidecar, &record) {
+            error!(
+                "Failed to record the randomness seed for transaction {}: {:?}",
+                record.txn_hash, error
+            );
+        }
+    }
+}
+
+/// Returns the randomness seed records of the user transactions in the given transactions,
+/// i.e., the seed of the block (with randomness) that each user transaction is executed in.
+fn randomness_seed_records(
+    transactions: &[SignatureVerifiedTransaction],
+) -> Vec<RandomnessSeedRecord> {
+    let mut records = vec![];
+    let mut block_randomness = None;
+    for transaction in transactions {
+        let SignatureVerifiedTransaction::Valid(transaction) = transaction else {
+            continue; // Invalid transactions are discarded (and consume no randomness)
+        };
+        match transaction {
+            Transaction::BlockMetadataExt(block_metadata_ext) => {
+                block_randomness = block_metadata_ext.randomness().map(|randomness| {
+                    (
+                        block_metadata_ext.epoch(),
+                        block_metadata_ext.round(),
+                        block_metadata_ext.id(),
+                        randomness.randomness_cloned(),
+                    )
+                });
+            },
+            Transaction::BlockMetadata(_) => {
+                block_randomness = None;
+            },
+            Transaction::UserTransaction(user_transaction) => {
+                if let Some((epoch, round, block_id, seed)) = &block_randomness {
+                    records.push(RandomnessSeedRecord::new(
+                        user_transaction.committed_hash(),
+                        *epoch,
+                        *round,
+                        *block_id,
+                        seed.clone(),
+                    ));
+                }
+            },
+            _ => {},
+        }
+    }
+    records
+}
+
+/// Writes the record as a single line of JSON to the sidecar file
+fn write_record(writer: &mut impl Write, record: &RandomnessSeedRecord) -> io::Result<()> {
+    let line = serde_json::to_string(record)?;
+    writeln!(writer, "{}", line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
+    use aptos_types::{
+        account_address::AccountAddress,
+        block_metadata::BlockMetadata,
+        block_metadata_ext::BlockMetadataExt,
+        randomness::{RandMetadata, Randomness},
+        test_helpers::transaction_test_helpers::get_test_signed_txn,
+        transaction::SignedTransaction,
+    };
+
+    fn create_user_transaction(sequence_number: u64) -> SignedTransaction {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let public_key = private_key.public_key();
+        get_test_signed_txn(
+            AccountAddress::random(),
+            sequence_number,
+            &private_key,
+            public_key,
+            None,
+        )
+    }
+
+    fn create_block_metadata_ext(round: u64, seed: Option<Vec<u8>>) -> BlockMetadataExt {
+        let randomness = seed.map(|seed| Randomness::new(RandMetadata { epoch: 1, round }, seed));
+        BlockMetadataExt::new_v1(
+            HashValue::random(),
+            1,
+            round,
+            AccountAddress::random(),
+            vec![],
+            vec![],
+            0,
+            randomness,
+        )
+    }
+
+    #[test]
+    fn test_randomness_seed_records() {
+        // Create three blocks: with randomness, without randomness and a legacy block
+        let block_with_randomness = create_block_metadata_ext(1, Some(vec![1; 32]));
+        let block_without_randomness = create_block_metadata_ext(2, None);
+        let legacy_block = BlockMetadata::new(
+            HashValue::random(),
+            1,
+            3,
+            AccountAddress::random(),
+            vec![],
+            vec![],
+            0,
+        );
+        let user_transactions: Vec<_> = (0..4).map(create_user_transaction).collect();
+        let transactions: Vec<SignatureVerifiedTransaction> = vec![
+            Transaction::BlockMetadataExt(block_with_randomness.clone()),
+            Transaction::UserTransaction(user_transactions[0].clone()),
+            Transaction::UserTransaction(user_transactions[1].clone()),
+            Transaction::BlockMetadataExt(block_without_randomness),
+            Transaction::UserTransaction(user_transactions[2].clone()),
+            Transaction::BlockMetadata(legacy_block),
+            Transaction::UserTransaction(user_transactions[3].clone()),
+        ]
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+        // Verify that only the transactions in the block with randomness are recorded
+        let expected_records: Vec<_> = user_transactions[..2]
+            .iter()
+            .map(|user_transaction| {
+                RandomnessSeedRecord::new(
+                    user_transaction.committed_hash(),
+                    1,
+                    1,
+                    block_with_randomness.id(),
+                    vec![1; 32],
+                )
+            })
+            .collect();
+        assert_eq!(randomness_seed_records(&transactions), expected_records);
+    }
+
+    #[test]
+    fn test_write_record() {
+        // Write two records to the sidecar
+        let records: Vec<_> = (0..2)
+            .map(|round| {
+                RandomnessSeedRecord::new(
+                    HashValue::random(),
+                    1,
+                    round,
+                    HashValue::random(),
+                    vec![round as u8; 32],
+                )
+            })
+            .collect();
+        let mut sidecar = vec![];
+        for record in &records {
+            write_record(&mut sidecar, record).unwrap();
+        }
+
+        // Verify that each record is written as a single line of JSON
+        let parsed_records: Vec<RandomnessSeedRecord> = String::from_utf8(sidecar)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(parsed_records, records);
+    }
+}
@@ -64,6 +64,7 @@ impl<S: StateView + Sync + Send + 'static> GlobalExecutor<S> {
                     concurrency_level: self.concurrency_level,
                     allow_fallback: true,
                     discard_failed_blocks: false,
+                    speculative_write_budget_bytes: None,
                 },
                 onchain: onchain_config,
             },
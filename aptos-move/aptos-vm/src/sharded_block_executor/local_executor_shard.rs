@@ -69,7 +69,7 @@ impl<S: StateView + Sync + Send + 'static> LocalExecutorService<S> {
             cross_shard_rx,
         ));
         // Limit the number of global executor threads to 32 as parallel execution doesn't scale well beyond that.
-        let executor_threads = num_cpus::get().min(32);
+        let executor_threads = aptos_runtimes::num_available_cpus().min(32);
         let global_executor = GlobalExecutor::new(cross_shard_client, executor_threads);
         (global_executor, cross_shard_tx)
     }
@@ -79,8 +79,9 @@ impl<S: StateView + Sync + Send + 'static> LocalExecutorService<S> {
         num_threads: Option<usize>,
     ) -> LocalExecutorClient<S> {
         let (global_executor, global_cross_shard_tx) = Self::setup_global_executor();
-        let num_threads = num_threads
-            .unwrap_or_else(|| (num_cpus::get() as f64 / num_shards as f64).ceil() as usize);
+        let num_threads = num_threads.unwrap_or_else(|| {
+            (aptos_runtimes::num_available_cpus() as f64 / num_shards as f64).ceil() as usize
+        });
         let (command_txs, command_rxs): (
             Vec<Sender<ExecutorShardCommand<S>>>,
             Vec<Receiver<ExecutorShardCommand<S>>>,
@@ -12,8 +12,8 @@ use aptos_aggregator::{
 };
 use aptos_mvhashmap::{
     types::{
-        MVDataError, MVDataOutput, MVDelayedFieldsError, MVGroupError, StorageVersion, TxnIndex,
-        ValueWithLayout, Version,
+        KeyEpoch, MVDataError, MVDataOutput, MVDelayedFieldsError, MVGroupError, StorageVersion,
+        TxnIndex, ValueWithLayout, Version,
     },
     versioned_data::VersionedData,
     versioned_delayed_fields::TVersionedDelayedFieldView,
@@ -297,6 +297,10 @@ impl DelayedFieldRead {
 #[derivative(Default(bound = "", new = "true"))]
 pub(crate) struct CapturedReads<T: Transaction> {
     data_reads: HashMap<T::Key, DataRead<T::Value>>,
+    /// Epochs of the keys in the versioned data map, observed atomically with the
+    /// fetches that produced the captured data reads. If the epoch of a key is
+    /// unchanged at validation time, its read is valid without re-fetching.
+    data_read_epochs: HashMap<T::Key, KeyEpoch>,
     group_reads: HashMap<T::Key, GroupRead<T>>,
     // Currently, we record paths for triggering module R/W fallback.
     // TODO: implement a general functionality once the fallback is removed.
@@ -451,6 +455,12 @@ impl<T: Transaction> CapturedReads<T> {
         }
     }
 
+    // Records the epoch of the key observed by the fetch that produced the last captured
+    // data read for the key (must be called after a successful 'capture_read').
+    pub(crate) fn capture_data_read_epoch(&mut self, state_key: T::Key, epoch: KeyEpoch) {
+        self.data_read_epochs.insert(state_key, epoch);
+    }
+
     // If maybe_tag is provided, then we check the group, otherwise, normal reads.
     pub(crate) fn get_by_kind(
         &self,
@@ -561,6 +571,15 @@ impl<T: Transaction> CapturedReads<T> {
         use MVDataError::*;
         use MVDataOutput::*;
         self.data_reads.iter().all(|(k, r)| {
+            // No writes to the key since the read: the fetch would return the same output.
+            if self
+                .data_read_epochs
+                .get(k)
+                .is_some_and(|epoch| *epoch == data_map.key_epoch(k))
+            {
+                return true;
+            }
+
             match data_map.fetch_data(k, idx_to_validate) {
                 Ok(Versioned(version, v)) => {
                     matches!(
@@ -756,7 +775,8 @@ impl<T: Transaction> UnsyncReadSet<T> {
 mod test {
     use super::*;
     use crate::proptest_types::types::{raw_metadata, KeyType, MockEvent, ValueType};
-    use aptos_mvhashmap::types::StorageVersion;
+    use aptos_mvhashmap::{types::StorageVersion, MVHashMap};
+    use aptos_types::executable::ExecutableTestType;
     use claims::{assert_err, assert_gt, assert_matches, assert_none, assert_ok, assert_some_eq};
     use move_vm_types::delayed_values::delayed_field_id::DelayedFieldID;
     use test_case::test_case;
@@ -1294,4 +1314,44 @@ mod test {
         captured_reads.mark_failure();
         assert!(captured_reads.speculative_failure);
     }
+
+    #[test]
+    fn validate_data_reads_with_epochs() {
+        let map =
+            MVHashMap::<KeyType<u32>, u32, ValueType, ExecutableTestType, DelayedFieldID>::new();
+        let key = KeyType::<u32>(10, false);
+        let value = ValueType::with_len_and_metadata(1, StateValueMetadata::none());
+        map.data().write(key, 0, 0, Arc::new(value.clone()), None);
+
+        let capture = |captured_reads: &mut CapturedReads<TestTransactionType>| {
+            let (data, epoch) = map.data().fetch_data_with_epoch(&key, 1);
+            let read = match data {
+                Ok(MVDataOutput::Versioned(version, value)) => {
+                    DataRead::from_value_with_layout(version, value)
+                },
+                _ => unreachable!("Must read the versioned write"),
+            };
+            assert_ok!(captured_reads.capture_read(key, None, read));
+            captured_reads.capture_data_read_epoch(key, epoch);
+        };
+
+        let mut captured_reads = CapturedReads::<TestTransactionType>::new();
+        capture(&mut captured_reads);
+        assert!(captured_reads.validate_data_reads(map.data(), 1));
+
+        // A write by a later transaction changes the epoch, but not the read output.
+        map.data().write(key, 2, 0, Arc::new(value.clone()), None);
+        assert!(captured_reads.validate_data_reads(map.data(), 1));
+
+        // A new incarnation of the transaction that was read from invalidates the read.
+        map.data().write(key, 0, 1, Arc::new(value), None);
+        assert!(!captured_reads.validate_data_reads(map.data(), 1));
+
+        // Reading again with the updated epoch makes the reads valid.
+        let mut captured_reads = CapturedReads::<TestTransactionType>::new();
+        capture(&mut captured_reads);
+        assert!(captured_reads.validate_data_reads(map.data(), 1));
+        map.data().mark_estimate(&key, 0);
+        assert!(!captured_reads.validate_data_reads(map.data(), 1));
+    }
 }
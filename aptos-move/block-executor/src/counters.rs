@@ -58,6 +58,24 @@ pub static MODULE_PUBLISHING_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of times the speculative write budget was exceeded in parallel execution.
+pub static SPECULATIVE_WRITE_BUDGET_EXCEEDED_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_execution_speculative_write_budget_exceeded_count",
+        "Count times the speculative write budget was exceeded in parallel execution (sequential fallback)"
+    )
+    .unwrap()
+});
+
+pub static PEAK_SPECULATIVE_WRITE_BYTES: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_execution_peak_speculative_write_bytes",
+        "Histogram for the peak (approx) size of the speculative writes buffered in parallel execution",
+        output_buckets(),
+    )
+    .unwrap()
+});
+
 /// Count of speculative transaction re-executions due to a failed validation.
 pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
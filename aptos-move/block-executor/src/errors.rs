@@ -11,6 +11,9 @@ pub(crate) enum ParallelBlockExecutionError {
     // aborting the parallel execution pipeline and falling back to the sequential execution.
     // TODO: provide proper multi-versioning for code (like data) for the cache.
     ModulePathReadWriteError,
+    // The speculative writes buffered during parallel execution exceeded the configured
+    // memory budget. The block is re-executed sequentially, which buffers no speculative writes.
+    SpeculativeWriteBudgetExceeded,
     /// unrecoverable VM error
     FatalVMError,
 }
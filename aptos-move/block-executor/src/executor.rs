@@ -13,6 +13,7 @@ use crate::{
     explicit_sync_wrapper::ExplicitSyncWrapper,
     limit_processor::BlockGasLimitProcessor,
     scheduler::{DependencyStatus, ExecutionTaskType, Scheduler, SchedulerTask, Wave},
+    speculative_write_budget::SpeculativeWriteBudget,
     task::{ExecutionStatus, ExecutorTask, TransactionOutput},
     txn_commit_hook::TransactionCommitHook,
     txn_last_input_output::{KeyKind, TxnLastInputOutput},
@@ -108,6 +109,7 @@ where
         executor: &E,
         base_view: &S,
         parallel_state: ParallelState<T, X>,
+        speculative_write_budget: &SpeculativeWriteBudget,
     ) -> Result<bool, PanicOr<ParallelBlockExecutionError>> {
         let _timer = TASK_EXECUTE_SECONDS.start_timer();
         let txn = &signature_verified_block[idx_to_execute as usize];
@@ -306,6 +308,13 @@ where
             versioned_cache.delayed_fields().remove(&id, idx_to_execute);
         }
 
+        let write_bytes = match &result {
+            ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output) => {
+                output.output_approx_size()
+            },
+            _ => 0,
+        };
+
         if !last_input_output.record(idx_to_execute, read_set, result, resource_write_set) {
             // Module R/W is an expected fallback behavior, no alert is required.
             debug!("[Execution] At txn {}, Module read & write", idx_to_execute);
@@ -314,6 +323,18 @@ where
                 ParallelBlockExecutionError::ModulePathReadWriteError,
             ));
         }
+
+        if !speculative_write_budget.record(idx_to_execute, write_bytes) {
+            // Exceeding the budget is an expected fallback behavior, no alert is required.
+            debug!(
+                "[Execution] At txn {}, speculative write budget exceeded",
+                idx_to_execute
+            );
+
+            return Err(PanicOr::Or(
+                ParallelBlockExecutionError::SpeculativeWriteBudgetExceeded,
+            ));
+        }
         Ok(needs_suffix_validation)
     }
 
@@ -465,6 +486,7 @@ where
         executor: &E,
         block: &[T],
         num_workers: usize,
+        speculative_write_budget: &SpeculativeWriteBudget,
     ) -> Result<(), PanicOr<ParallelBlockExecutionError>> {
         let mut block_limit_processor = shared_commit_state.acquire();
 
@@ -491,6 +513,7 @@ where
                         start_shared_counter,
                         shared_counter,
                     ),
+                    speculative_write_budget,
                 )?;
 
                 scheduler.finish_execution_during_commit(txn_idx)?;
@@ -759,6 +782,7 @@ where
         shared_commit_state: &ExplicitSyncWrapper<BlockGasLimitProcessor<T>>,
         final_results: &ExplicitSyncWrapper<Vec<E::Output>>,
         num_workers: usize,
+        speculative_write_budget: &SpeculativeWriteBudget,
     ) -> Result<(), PanicOr<ParallelBlockExecutionError>> {
         // Make executor for each task. TODO: fast concurrent executor.
         let init_timer = VM_INIT_SECONDS.start_timer();
@@ -799,6 +823,7 @@ where
                     &executor,
                     block,
                     num_workers,
+                    speculative_write_budget,
                 )?;
                 scheduler.queueing_commits_mark_done();
             }
@@ -837,6 +862,7 @@ where
                             start_shared_counter,
                             shared_counter,
                         ),
+                        speculative_write_budget,
                     )?;
                     scheduler.finish_execution(txn_idx, incarnation, needs_suffix_validation)?
                 },
@@ -907,6 +933,10 @@ where
 
         let last_input_output = TxnLastInputOutput::new(num_txns);
         let scheduler = Scheduler::new(num_txns);
+        let speculative_write_budget = SpeculativeWriteBudget::new(
+            self.config.local.speculative_write_budget_bytes,
+            num_txns as usize,
+        );
 
         let timer = RAYON_EXECUTION_SECONDS.start_timer();
         self.executor_thread_pool.scope(|s| {
@@ -924,9 +954,11 @@ where
                         &shared_commit_state,
                         &final_results,
                         num_workers,
+                        &speculative_write_budget,
                     ) {
                         // If there are multiple errors, they all get logged:
-                        // ModulePathReadWriteError and FatalVMError variant is logged at construction,
+                        // ModulePathReadWriteError, SpeculativeWriteBudgetExceeded and FatalVMError
+                        // variants are logged at construction,
                         // and below we log CodeInvariantErrors.
                         if let PanicOr::CodeInvariantError(err_msg) = err {
                             alert!("[BlockSTM] worker loop: CodeInvariantError({:?})", err_msg);
@@ -941,7 +973,18 @@ where
         });
         drop(timer);
 
+        // A module that is both published and read within the block must always lead to
+        // a fallback to sequential execution, as the Move-VM loader cache may otherwise
+        // serve a stale (or speculative) version of the module.
+        if !shared_maybe_error.load(Ordering::SeqCst)
+            && last_input_output.has_module_rw_intersection()
+        {
+            alert!("[BlockSTM] Module path read & written in a successful parallel execution");
+            shared_maybe_error.store(true, Ordering::SeqCst);
+        }
+
         counters::update_state_counters(versioned_cache.stats(), true);
+        speculative_write_budget.update_counters();
 
         // Explicit async drops.
         DEFAULT_DROPPER.schedule_drop((last_input_output, scheduler, versioned_cache));
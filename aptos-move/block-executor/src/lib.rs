@@ -149,6 +149,7 @@ mod limit_processor;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod proptest_types;
 mod scheduler;
+mod speculative_write_budget;
 pub mod task;
 pub mod txn_commit_hook;
 pub mod txn_last_input_output;
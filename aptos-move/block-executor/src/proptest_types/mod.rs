@@ -5,5 +5,7 @@
 pub(crate) mod baseline;
 pub mod bencher;
 #[cfg(test)]
+mod module_publishing_tests;
+#[cfg(test)]
 mod tests;
 pub(crate) mod types;
@@ -0,0 +1,389 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for module publishing in parallel execution. Modules are (re-)published and called
+//! at randomized positions, within a block and across block boundaries, and the output of
+//! the block executor (i.e., parallel execution, falling back to sequential execution when
+//! a module is both published and read in the block) is compared against the output of a
+//! purely sequential execution.
+
+use crate::{
+    executor::BlockExecutor,
+    proptest_types::types::{
+        CommittedStateView, KeyType, MockEvent, MockIncarnation, MockOutput, MockTask,
+        MockTransaction, TransactionGen, TransactionGenParams, ValueType,
+    },
+    txn_commit_hook::NoOpTransactionCommitHook,
+};
+use aptos_types::{
+    block_executor::config::BlockExecutorConfig, executable::ExecutableTestType,
+    transaction::BlockOutput, write_set::TransactionWrite,
+};
+use claims::assert_ok;
+use proptest::{
+    collection::vec,
+    prelude::*,
+    sample::Index,
+    strategy::{Strategy, ValueTree},
+    test_runner::TestRunner,
+};
+use rayon::ThreadPool;
+use std::sync::Arc;
+
+type Key = KeyType<[u8; 32]>;
+type Txn = MockTransaction<Key, MockEvent>;
+type Output = MockOutput<Key, MockEvent>;
+
+/// The number of keys used for resources (the module key is outside this range)
+const NUM_RESOURCE_KEYS: usize = 40;
+
+/// The index (in the key universe) of the module that is published and called
+const MODULE_KEY_INDEX: usize = 45;
+
+fn create_executor_thread_pool() -> Arc<ThreadPool> {
+    Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    )
+}
+
+/// Generates a block of transactions with static (i.e., incarnation independent) behavior
+/// that only access resources, along with the given number of random indices.
+fn generate_block(
+    runner: &mut TestRunner,
+    universe: &[[u8; 32]],
+    num_txns: usize,
+    num_indices: usize,
+) -> (Vec<Txn>, Vec<Index>) {
+    let transaction_gen = vec(
+        any_with::<TransactionGen<[u8; 32]>>(TransactionGenParams::default()),
+        num_txns,
+    )
+    .new_tree(runner)
+    .expect("creating a new value should succeed")
+    .current();
+    let indices = vec(any::<Index>(), num_indices)
+        .new_tree(runner)
+        .expect("creating a new value should succeed")
+        .current();
+
+    let transactions = transaction_gen
+        .into_iter()
+        .map(|txn_gen| txn_gen.materialize(&universe[0..NUM_RESOURCE_KEYS], (false, false)))
+        .collect();
+    (transactions, indices)
+}
+
+fn generate_universe(runner: &mut TestRunner) -> Vec<[u8; 32]> {
+    vec(any::<[u8; 32]>(), 50)
+        .new_tree(runner)
+        .expect("creating a new value should succeed")
+        .current()
+}
+
+/// Returns the (static) behavior of the given transaction
+fn behavior_mut(txn: &mut Txn) -> &mut MockIncarnation<Key, MockEvent> {
+    match txn {
+        MockTransaction::Write {
+            incarnation_behaviors,
+            ..
+        } => {
+            assert_eq!(incarnation_behaviors.len(), 1);
+            &mut incarnation_behaviors[0]
+        },
+        _ => unreachable!("Only write transactions are generated"),
+    }
+}
+
+/// Makes the given transaction (re-)publish the module with the given code version
+fn publish_module(txn: &mut Txn, module_key: Key, code_version: u8) {
+    let code = ValueType::from_value([code_version; 32], true);
+    behavior_mut(txn).writes.push((module_key, code));
+}
+
+/// Makes the given transaction call (i.e., read) the module. Returns the index of the read.
+fn call_module(txn: &mut Txn, module_key: Key) -> usize {
+    let behavior = behavior_mut(txn);
+    behavior.reads.push(module_key);
+    behavior.reads.len() - 1
+}
+
+/// Returns the module code expected to be read for the given code version (if any)
+fn expected_code(code_version: Option<u8>) -> Option<Vec<u8>> {
+    code_version.map(|code_version| {
+        ValueType::from_value([code_version; 32], true)
+            .extract_raw_bytes()
+            .unwrap()
+            .to_vec()
+    })
+}
+
+/// Executes the block with the block executor (which may fall back to sequential execution),
+/// and purely sequentially, and asserts that the outputs match. Returns the output.
+fn execute_and_compare(
+    transactions: &[Txn],
+    state_view: &CommittedStateView<Key>,
+    executor_thread_pool: &Arc<ThreadPool>,
+) -> BlockOutput<Output> {
+    let execute_block = |concurrency_level: usize| {
+        BlockExecutor::<
+            Txn,
+            MockTask<Key, MockEvent>,
+            CommittedStateView<Key>,
+            NoOpTransactionCommitHook<Output, usize>,
+            ExecutableTestType,
+        >::new(
+            BlockExecutorConfig::new_no_block_limit(concurrency_level),
+            executor_thread_pool.clone(),
+            None,
+        )
+        .execute_block((), transactions, state_view)
+    };
+
+    let parallel_output = assert_ok!(execute_block(num_cpus::get().max(2)));
+    let sequential_output = assert_ok!(execute_block(1));
+
+    let parallel_outputs = parallel_output.get_transaction_outputs_forced();
+    let sequential_outputs = sequential_output.get_transaction_outputs_forced();
+    assert_eq!(parallel_outputs.len(), sequential_outputs.len());
+    for (idx, (parallel, sequential)) in parallel_outputs
+        .iter()
+        .zip(sequential_outputs.iter())
+        .enumerate()
+    {
+        assert_eq!(parallel.skipped, sequential.skipped, "txn {}", idx);
+        assert_eq!(
+            parallel.read_results, sequential.read_results,
+            "txn {}",
+            idx
+        );
+        assert_eq!(
+            written_bytes(parallel),
+            written_bytes(sequential),
+            "txn {}",
+            idx
+        );
+    }
+
+    sequential_output
+}
+
+/// Returns the keys and bytes written by the given output
+fn written_bytes(output: &Output) -> Vec<(Key, Option<Vec<u8>>)> {
+    output
+        .writes
+        .iter()
+        .map(|(key, value)| (*key, value.extract_raw_bytes().map(|bytes| bytes.to_vec())))
+        .collect()
+}
+
+/// Returns true iff parallel execution (without the sequential fallback) succeeds
+fn parallel_execution_succeeds(
+    transactions: &[Txn],
+    state_view: &CommittedStateView<Key>,
+    executor_thread_pool: &Arc<ThreadPool>,
+) -> bool {
+    BlockExecutor::<
+        Txn,
+        MockTask<Key, MockEvent>,
+        CommittedStateView<Key>,
+        NoOpTransactionCommitHook<Output, usize>,
+        ExecutableTestType,
+    >::new(
+        BlockExecutorConfig::new_no_block_limit(num_cpus::get().max(2)),
+        executor_thread_pool.clone(),
+        None,
+    )
+    .execute_transactions_parallel(&(), transactions, state_view)
+    .is_ok()
+}
+
+/// Calls the module from the transactions at the given (random) positions. Returns the
+/// index of each calling transaction, along with the index of its module read.
+fn call_randomly(
+    transactions: &mut [Txn],
+    indices: &[Index],
+    module_key: Key,
+) -> Vec<(usize, usize)> {
+    let num_txns = transactions.len();
+    indices
+        .iter()
+        .map(|index| {
+            let call_idx = index.index(num_txns);
+            (
+                call_idx,
+                call_module(&mut transactions[call_idx], module_key),
+            )
+        })
+        .collect()
+}
+
+/// Asserts that all given calls observed the expected module code
+fn assert_calls_observe(
+    output: &BlockOutput<Output>,
+    calls: &[(usize, usize)],
+    expected_code: Option<Vec<u8>>,
+) {
+    let outputs = output.get_transaction_outputs_forced();
+    for (call_idx, read_idx) in calls {
+        assert_eq!(
+            outputs[*call_idx].read_results[*read_idx], expected_code,
+            "txn {}",
+            call_idx
+        );
+    }
+}
+
+#[test]
+fn module_republish_then_call_within_block() {
+    let executor_thread_pool = create_executor_thread_pool();
+    let mut runner = TestRunner::default();
+
+    for _ in 0..10 {
+        let universe = generate_universe(&mut runner);
+        let module_key = KeyType(universe[MODULE_KEY_INDEX], true);
+        let (mut transactions, indices) = generate_block(&mut runner, &universe, 300, 12);
+        let num_txns = transactions.len();
+
+        // Publish the module twice (the second publish is an upgrade), and call it from
+        // random positions (before, between and after the publishes).
+        let mut publishes: Vec<_> = indices[0..2]
+            .iter()
+            .map(|index| index.index(num_txns))
+            .collect();
+        publishes.sort();
+        publishes.dedup();
+        for (code_version, publish_idx) in publishes.iter().enumerate() {
+            publish_module(
+                &mut transactions[*publish_idx],
+                module_key,
+                code_version as u8,
+            );
+        }
+        let calls: Vec<_> = indices[2..]
+            .iter()
+            .map(|index| {
+                let call_idx = index.index(num_txns);
+                (
+                    call_idx,
+                    call_module(&mut transactions[call_idx], module_key),
+                )
+            })
+            .collect();
+
+        // The module is published and read in the same block, so parallel execution
+        // must always fall back to sequential execution.
+        let state_view = CommittedStateView::new();
+        assert!(!parallel_execution_succeeds(
+            &transactions,
+            &state_view,
+            &executor_thread_pool
+        ));
+        let output = execute_and_compare(&transactions, &state_view, &executor_thread_pool);
+
+        // Each call must observe the code of the latest preceding publish (if any). Note
+        // that a transaction reads before it writes, so it doesn't see its own publish.
+        let outputs = output.get_transaction_outputs_forced();
+        for (call_idx, read_idx) in calls {
+            let code_version = publishes
+                .iter()
+                .rposition(|publish_idx| *publish_idx < call_idx)
+                .map(|code_version| code_version as u8);
+            assert_eq!(
+                outputs[call_idx].read_results[read_idx],
+                expected_code(code_version),
+                "txn {}",
+                call_idx
+            );
+        }
+    }
+}
+
+#[test]
+fn module_upgrade_straddling_blocks() {
+    let executor_thread_pool = create_executor_thread_pool();
+    let mut runner = TestRunner::default();
+
+    for _ in 0..10 {
+        let universe = generate_universe(&mut runner);
+        let module_key = KeyType(universe[MODULE_KEY_INDEX], true);
+        let mut state_view = CommittedStateView::new();
+
+        // The first block publishes the module (as the last transaction, so that the
+        // publish straddles the block boundary with the calls in the next block).
+        let (mut transactions, _) = generate_block(&mut runner, &universe, 100, 0);
+        publish_module(transactions.last_mut().unwrap(), module_key, 1);
+        assert!(parallel_execution_succeeds(
+            &transactions,
+            &state_view,
+            &executor_thread_pool
+        ));
+        let output = execute_and_compare(&transactions, &state_view, &executor_thread_pool);
+        state_view.apply_block_output(&output);
+
+        // The second block only calls the module, so parallel execution must succeed
+        // (without a fallback), and all calls must observe the published code.
+        let (mut transactions, indices) = generate_block(&mut runner, &universe, 100, 10);
+        let calls = call_randomly(&mut transactions, &indices, module_key);
+        assert!(parallel_execution_succeeds(
+            &transactions,
+            &state_view,
+            &executor_thread_pool
+        ));
+        let output = execute_and_compare(&transactions, &state_view, &executor_thread_pool);
+        assert_calls_observe(&output, &calls, expected_code(Some(1)));
+        state_view.apply_block_output(&output);
+
+        // The third block upgrades the module, and the calls before the upgrade must
+        // observe the code committed by the first block.
+        let (mut transactions, indices) = generate_block(&mut runner, &universe, 100, 11);
+        let upgrade_idx = indices[0].index(transactions.len());
+        publish_module(&mut transactions[upgrade_idx], module_key, 2);
+        let calls = call_randomly(&mut transactions, &indices[1..], module_key);
+        let output = execute_and_compare(&transactions, &state_view, &executor_thread_pool);
+        for (call_idx, read_idx) in &calls {
+            let code_version = if *call_idx > upgrade_idx { 2 } else { 1 };
+            assert_eq!(
+                output.get_transaction_outputs_forced()[*call_idx].read_results[*read_idx],
+                expected_code(Some(code_version)),
+                "txn {}",
+                call_idx
+            );
+        }
+        state_view.apply_block_output(&output);
+
+        // The fourth block must observe the upgraded code
+        let (mut transactions, indices) = generate_block(&mut runner, &universe, 100, 10);
+        let calls = call_randomly(&mut transactions, &indices, module_key);
+        let output = execute_and_compare(&transactions, &state_view, &executor_thread_pool);
+        assert_calls_observe(&output, &calls, expected_code(Some(2)));
+    }
+}
+
+#[test]
+fn module_rw_intersection_never_commits_in_parallel() {
+    let executor_thread_pool = create_executor_thread_pool();
+    let mut runner = TestRunner::default();
+    let universe = generate_universe(&mut runner);
+    let module_key = KeyType(universe[MODULE_KEY_INDEX], true);
+
+    // Even if the module is only read by the transactions preceding the publish (i.e.,
+    // the reads can't observe it in any sequential order), the parallel execution must
+    // not commit, as the loader cache may have been populated speculatively.
+    let (mut transactions, indices) = generate_block(&mut runner, &universe, 200, 1);
+    let publish_idx = indices[0].index(transactions.len() - 1) + 1;
+    publish_module(&mut transactions[publish_idx], module_key, 1);
+    call_module(&mut transactions[0], module_key);
+
+    let state_view = CommittedStateView::new();
+    for _ in 0..20 {
+        assert!(!parallel_execution_succeeds(
+            &transactions,
+            &state_view,
+            &executor_thread_pool
+        ));
+    }
+    execute_and_compare(&transactions, &state_view, &executor_thread_pool);
+}
@@ -94,6 +94,63 @@ fn run_transactions<K, V, E>(
     }
 }
 
+fn run_transactions_with_speculative_write_budget<K, V, E>(
+    key_universe: &[K],
+    transaction_gens: Vec<TransactionGen<V>>,
+    speculative_write_budget_bytes: u64,
+) where
+    K: Hash + Clone + Debug + Eq + Send + Sync + PartialOrd + Ord + 'static,
+    V: Clone + Eq + Send + Sync + Arbitrary + 'static,
+    E: Send + Sync + Debug + Clone + TransactionEvent + 'static,
+    Vec<u8>: From<V>,
+{
+    let transactions: Vec<_> = transaction_gens
+        .into_iter()
+        .map(|txn_gen| txn_gen.materialize(key_universe, (false, false)))
+        .collect();
+
+    let data_view = EmptyDataView::<KeyType<K>> {
+        phantom: PhantomData,
+    };
+
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+    let new_block_executor = |config| {
+        BlockExecutor::<
+            MockTransaction<KeyType<K>, E>,
+            MockTask<KeyType<K>, E>,
+            EmptyDataView<KeyType<K>>,
+            NoOpTransactionCommitHook<MockOutput<KeyType<K>, E>, usize>,
+            ExecutableTestType,
+        >::new(config, executor_thread_pool.clone(), None)
+    };
+
+    let mut config = BlockExecutorConfig::new_no_block_limit(num_cpus::get());
+    config.local.speculative_write_budget_bytes = Some(speculative_write_budget_bytes);
+    let bounded_block_executor = new_block_executor(config);
+
+    // The parallel execution exceeds the speculative write budget (and is aborted)
+    let output =
+        bounded_block_executor.execute_transactions_parallel(&(), &transactions, &data_view);
+    assert_matches!(output, Err(()));
+
+    // The block falls back to the sequential execution, and its output matches the output
+    // of the (unbounded) parallel execution, i.e., both match the baseline.
+    let baseline_output = BaselineOutput::generate(&transactions, None);
+    let fallback_output = bounded_block_executor.execute_block((), &transactions, &data_view);
+    baseline_output.assert_output(&fallback_output);
+
+    let parallel_output =
+        new_block_executor(BlockExecutorConfig::new_no_block_limit(num_cpus::get()))
+            .execute_transactions_parallel(&(), &transactions, &data_view);
+    assert_ok!(&parallel_output);
+    baseline_output.assert_parallel_output(&parallel_output);
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(32))]
     #[test]
@@ -145,6 +202,14 @@ proptest! {
     ) {
         run_transactions::<[u8; 32], [u8; 32], MockEvent>(&universe, transaction_gen, abort_transactions, skip_rest_transactions, 1, (false, false), None);
     }
+
+    #[test]
+    fn speculative_write_budget_fallback(
+        universe in vec(any::<[u8; 32]>(), 100),
+        transaction_gen in vec(any::<TransactionGen<[u8;32]>>(), 1000).no_shrink(),
+    ) {
+        run_transactions_with_speculative_write_budget::<[u8; 32], [u8; 32], MockEvent>(&universe, transaction_gen, 1);
+    }
 }
 
 fn dynamic_read_writes_with_block_gas_limit(num_txns: usize, maybe_block_gas_limit: Option<u64>) {
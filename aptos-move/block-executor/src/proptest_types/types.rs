@@ -22,7 +22,7 @@ use aptos_types::{
         state_value::{StateValue, StateValueMetadata},
         StateViewId, TStateView,
     },
-    transaction::BlockExecutableTransaction as Transaction,
+    transaction::{BlockExecutableTransaction as Transaction, BlockOutput},
     write_set::{TransactionWrite, WriteOp, WriteOpKind},
 };
 use aptos_vm_types::resolver::{TExecutorView, TResourceGroupView};
@@ -136,6 +136,55 @@ where
     }
 }
 
+/// A mock state view holding the state committed by previous blocks. This allows testing
+/// behavior that straddles block boundaries (e.g., module upgrades).
+pub(crate) struct CommittedStateView<K> {
+    pub(crate) state: HashMap<K, StateValue>,
+}
+
+impl<K: Hash + Eq + Clone> CommittedStateView<K> {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: HashMap::new(),
+        }
+    }
+
+    /// Applies the writes of all transactions in the given (committed) block output
+    pub(crate) fn apply_block_output<E>(&mut self, block_output: &BlockOutput<MockOutput<K, E>>) {
+        for output in block_output.get_transaction_outputs_forced() {
+            for (key, value) in output.writes.iter() {
+                match value.as_state_value() {
+                    Some(state_value) => {
+                        self.state.insert(key.clone(), state_value);
+                    },
+                    None => {
+                        self.state.remove(key);
+                    },
+                }
+            }
+        }
+    }
+}
+
+impl<K> TStateView for CommittedStateView<K>
+where
+    K: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + 'static,
+{
+    type Key = K;
+
+    fn get_state_value(&self, key: &K) -> Result<Option<StateValue>, StateviewError> {
+        Ok(self.state.get(key).cloned())
+    }
+
+    fn id(&self) -> StateViewId {
+        StateViewId::Miscellaneous
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage, StateviewError> {
+        unreachable!("Not used in tests");
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////
 // Generation of transactions
 ///////////////////////////////////////////////////////////////////////////
@@ -1196,8 +1245,9 @@ where
     }
 
     fn output_approx_size(&self) -> u64 {
+        // Approximated by the number of writes (enough to test the speculative write budget).
         // TODO add block output limit testing
-        0
+        (self.writes.len() + self.group_writes.len() + self.deltas.len()) as u64
     }
 
     fn get_write_summary(
@@ -0,0 +1,102 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters::{PEAK_SPECULATIVE_WRITE_BYTES, SPECULATIVE_WRITE_BUDGET_EXCEEDED_COUNT};
+use aptos_mvhashmap::types::TxnIndex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Tracks the (approximate) size of the speculative writes buffered in the multi-versioned
+/// data structures during parallel execution. Only the writes of the latest incarnation of
+/// each transaction are buffered, so re-executions replace (rather than add to) the size
+/// recorded for the transaction. If a budget is set, and the total size exceeds it, the
+/// parallel execution must be aborted (and the block executed sequentially instead).
+pub(crate) struct SpeculativeWriteBudget {
+    budget_bytes: Option<u64>,
+    // The size of the writes of the latest incarnation of each transaction.
+    txn_write_bytes: Vec<AtomicU64>,
+    total_write_bytes: AtomicU64,
+    peak_write_bytes: AtomicU64,
+}
+
+impl SpeculativeWriteBudget {
+    pub(crate) fn new(budget_bytes: Option<u64>, num_txns: usize) -> Self {
+        // Nothing is tracked if there is no budget.
+        let num_tracked_txns = if budget_bytes.is_some() { num_txns } else { 0 };
+        Self {
+            budget_bytes,
+            txn_write_bytes: (0..num_tracked_txns).map(|_| AtomicU64::new(0)).collect(),
+            total_write_bytes: AtomicU64::new(0),
+            peak_write_bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// Records the size of the writes of the latest incarnation of the given transaction.
+    /// Returns false iff the total size of the buffered writes exceeds the budget.
+    pub(crate) fn record(&self, txn_idx: TxnIndex, write_bytes: u64) -> bool {
+        let Some(budget_bytes) = self.budget_bytes else {
+            return true;
+        };
+
+        let prev_write_bytes =
+            self.txn_write_bytes[txn_idx as usize].swap(write_bytes, Ordering::Relaxed);
+        let total_write_bytes = if write_bytes >= prev_write_bytes {
+            self.total_write_bytes
+                .fetch_add(write_bytes - prev_write_bytes, Ordering::Relaxed)
+                + (write_bytes - prev_write_bytes)
+        } else {
+            self.total_write_bytes
+                .fetch_sub(prev_write_bytes - write_bytes, Ordering::Relaxed)
+                - (prev_write_bytes - write_bytes)
+        };
+        self.peak_write_bytes
+            .fetch_max(total_write_bytes, Ordering::Relaxed);
+
+        total_write_bytes <= budget_bytes
+    }
+
+    pub(crate) fn peak_write_bytes(&self) -> u64 {
+        self.peak_write_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Updates the budget pressure counters, once the parallel execution is done.
+    pub(crate) fn update_counters(&self) {
+        if let Some(budget_bytes) = self.budget_bytes {
+            let peak_write_bytes = self.peak_write_bytes();
+            PEAK_SPECULATIVE_WRITE_BYTES.observe(peak_write_bytes as f64);
+            if peak_write_bytes > budget_bytes {
+                SPECULATIVE_WRITE_BUDGET_EXCEEDED_COUNT.inc();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpeculativeWriteBudget;
+
+    #[test]
+    fn test_speculative_write_budget() {
+        let budget = SpeculativeWriteBudget::new(Some(100), 3);
+
+        // Writes within the budget are accepted
+        assert!(budget.record(0, 40));
+        assert!(budget.record(1, 40));
+
+        // Re-executions replace the previously recorded size
+        assert!(budget.record(1, 10));
+        assert!(budget.record(2, 50));
+        assert_eq!(budget.peak_write_bytes(), 100);
+
+        // Exceeding the budget is reported
+        assert!(!budget.record(0, 41));
+        assert_eq!(budget.peak_write_bytes(), 101);
+    }
+
+    #[test]
+    fn test_no_speculative_write_budget() {
+        let budget = SpeculativeWriteBudget::new(None, 2);
+        assert!(budget.record(0, u64::MAX));
+        assert!(budget.record(1, u64::MAX));
+        assert_eq!(budget.peak_write_bytes(), 0);
+    }
+}
@@ -164,6 +164,15 @@ impl<T: Transaction, O: TransactionOutput<Txn = T>, E: Debug + Send + Clone>
             || Self::append_and_check(module_writes_keys, &self.module_writes, &self.module_reads)
     }
 
+    /// Returns true iff a module path was both read and written in the (speculative) executions.
+    /// After a successful parallel execution this must never hold, as the intersection is always
+    /// detected by 'record', which causes a fallback to sequential execution.
+    pub(crate) fn has_module_rw_intersection(&self) -> bool {
+        self.module_reads
+            .iter()
+            .any(|path| self.module_writes.contains(path.key()))
+    }
+
     pub(crate) fn read_set(&self, txn_idx: TxnIndex) -> Option<Arc<CapturedReads<T>>> {
         self.inputs[txn_idx as usize].load_full()
     }
@@ -550,7 +550,8 @@ impl<'a, T: Transaction, X: Executable> ResourceState<T> for ParallelState<'a, T
         }
 
         loop {
-            match self.versioned_map.data().fetch_data(key, txn_idx) {
+            let (data, epoch) = self.versioned_map.data().fetch_data_with_epoch(key, txn_idx);
+            match data {
                 Ok(Versioned(version, value)) => {
                     // If we have a known layout, upgrade RawFromStorage value to Exchanged.
                     if let UnknownOrLayout::Known(layout) = layout {
@@ -592,9 +593,8 @@ impl<'a, T: Transaction, X: Executable> ResourceState<T> for ParallelState<'a, T
                         },
                     };
 
-                    if self
-                        .captured_reads
-                        .borrow_mut()
+                    let mut captured_reads = self.captured_reads.borrow_mut();
+                    if captured_reads
                         .capture_read(key.clone(), None, data_read.clone())
                         .is_err()
                     {
@@ -603,6 +603,7 @@ impl<'a, T: Transaction, X: Executable> ResourceState<T> for ParallelState<'a, T
                             "Inconsistency in reads (must be due to speculation)".to_string(),
                         );
                     }
+                    captured_reads.capture_data_read_epoch(key.clone(), epoch);
 
                     return ReadResult::from_data_read(data_read);
                 },
@@ -611,9 +612,8 @@ impl<'a, T: Transaction, X: Executable> ResourceState<T> for ParallelState<'a, T
                         .downcast(target_kind)
                         .expect("Downcast from Resolved must succeed");
 
-                    if self
-                        .captured_reads
-                        .borrow_mut()
+                    let mut captured_reads = self.captured_reads.borrow_mut();
+                    if captured_reads
                         .capture_read(key.clone(), None, data_read.clone())
                         .is_err()
                     {
@@ -622,6 +622,7 @@ impl<'a, T: Transaction, X: Executable> ResourceState<T> for ParallelState<'a, T
                             "Inconsistency in reads (must be due to speculation)".to_string(),
                         );
                     }
+                    captured_reads.capture_data_read_epoch(key.clone(), epoch);
 
                     return ReadResult::from_data_read(data_read);
                 },
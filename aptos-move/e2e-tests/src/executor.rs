@@ -529,6 +529,7 @@ impl FakeExecutor {
                 },
                 allow_fallback: self.allow_block_executor_fallback,
                 discard_failed_blocks: false,
+                speculative_write_budget_bytes: None,
             },
             onchain: onchain_config,
         };
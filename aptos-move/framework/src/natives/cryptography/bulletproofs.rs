@@ -12,6 +12,7 @@ use aptos_native_interface::{
     safely_pop_arg, RawSafeNative, SafeNativeBuilder, SafeNativeContext, SafeNativeError,
     SafeNativeResult,
 };
+use aptos_types::on_chain_config::FeatureFlag;
 use bulletproofs::{BulletproofGens, PedersenGens};
 #[cfg(feature = "testing")]
 use byteorder::{ByteOrder, LittleEndian};
@@ -204,21 +205,26 @@ fn verify_range_proof(
     Ok(smallvec![Value::bool(success)])
 }
 
-pub fn make_all(
-    builder: &SafeNativeBuilder,
-) -> impl Iterator<Item = (String, NativeFunction)> + '_ {
+pub fn make_all(builder: &mut SafeNativeBuilder) -> impl Iterator<Item = (String, NativeFunction)> {
     let mut natives = vec![];
 
     #[cfg(feature = "testing")]
-    natives.extend([(
+    natives.extend(builder.make_named_natives([(
         "prove_range_internal",
         native_test_only_prove_range as RawSafeNative,
-    )]);
-
-    natives.extend([(
-        "verify_range_proof_internal",
-        native_verify_range_proof as RawSafeNative,
-    )]);
-
-    builder.make_named_natives(natives)
+    )]));
+
+    // The verifier can be switched off via the on-chain feature flag
+    natives.extend(
+        builder.with_required_feature(FeatureFlag::BULLETPROOFS_NATIVES, |builder| {
+            builder
+                .make_named_natives([(
+                    "verify_range_proof_internal",
+                    native_verify_range_proof as RawSafeNative,
+                )])
+                .collect::<Vec<_>>()
+        }),
+    );
+
+    natives.into_iter()
 }
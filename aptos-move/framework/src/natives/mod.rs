@@ -38,7 +38,7 @@ pub mod status {
 
 pub fn all_natives(
     framework_addr: AccountAddress,
-    builder: &SafeNativeBuilder,
+    builder: &mut SafeNativeBuilder,
     inject_create_signer_for_gov_sim: bool,
 ) -> NativeFunctionTable {
     let mut natives = vec![];
@@ -0,0 +1,83 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares the cost of validating a large read set by re-fetching every key against
+//! comparing the per-key epochs observed at execution time.
+
+use aptos_mvhashmap::{
+    types::{KeyEpoch, MVDataOutput, TxnIndex},
+    MVHashMap,
+};
+use aptos_types::{
+    executable::ExecutableTestType, state_store::state_key::StateKey, write_set::WriteOp,
+};
+use bytes::Bytes;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use move_vm_types::delayed_values::delayed_field_id::DelayedFieldID;
+use std::sync::Arc;
+
+/// Number of transactions writing to every key before the validated transaction.
+const NUM_WRITERS: TxnIndex = 8;
+
+type BenchMap = MVHashMap<StateKey, u32, WriteOp, ExecutableTestType, DelayedFieldID>;
+
+/// Creates a map with the given number of keys, each written by NUM_WRITERS transactions,
+/// and returns the keys along with the epochs observed by a read from the last transaction.
+fn setup(num_keys: usize) -> (BenchMap, Vec<(StateKey, KeyEpoch)>) {
+    let map = BenchMap::new();
+    let reads = (0..num_keys)
+        .map(|i| {
+            let key = StateKey::raw(format!("key_{}", i).as_bytes());
+            for txn_idx in 0..NUM_WRITERS {
+                let value = WriteOp::legacy_modification(Bytes::from(vec![txn_idx as u8; 64]));
+                map.data()
+                    .write(key.clone(), txn_idx, 0, Arc::new(value), None);
+            }
+            let (output, epoch) = map.data().fetch_data_with_epoch(&key, NUM_WRITERS);
+            assert!(matches!(output, Ok(MVDataOutput::Versioned(_, _))));
+            (key, epoch)
+        })
+        .collect();
+    (map, reads)
+}
+
+fn read_validation_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read_validation");
+
+    for num_keys in [100, 1000, 10000] {
+        let (map, reads) = setup(num_keys);
+        group.throughput(Throughput::Elements(num_keys as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("fetch_data", num_keys),
+            &reads,
+            |b, reads| {
+                b.iter(|| {
+                    reads.iter().all(|(key, _)| {
+                        matches!(
+                            black_box(map.data().fetch_data(key, NUM_WRITERS)),
+                            Ok(MVDataOutput::Versioned(Ok((idx, 0)), _)) if idx == NUM_WRITERS - 1
+                        )
+                    })
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("key_epoch", num_keys),
+            &reads,
+            |b, reads| {
+                b.iter(|| {
+                    reads
+                        .iter()
+                        .all(|(key, epoch)| black_box(map.data().key_epoch(key)) == *epoch)
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, read_validation_benches);
+criterion_main!(benches);
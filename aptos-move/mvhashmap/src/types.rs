@@ -20,6 +20,9 @@ use std::sync::{atomic::AtomicU32, Arc};
 pub type AtomicTxnIndex = AtomicU32;
 pub type TxnIndex = u32;
 pub type Incarnation = u32;
+/// Counts the modifications to the versioned entries of a single key. Two reads of
+/// a key that observe the same epoch are guaranteed to observe the same entries.
+pub type KeyEpoch = u64;
 
 /// Custom error type representing storage version. Result<Index, StorageVersion>
 /// then represents either index of some type (i.e. TxnIndex, Version), or a
@@ -340,6 +340,55 @@ fn materialize_delta_shortcut() {
     assert_eq!(vd.fetch_data(&ap, 10), Ok(Resolved(50)));
 }
 
+#[test]
+fn key_epoch_tracks_modifications() {
+    let vd: VersionedData<KeyType<Vec<u8>>, TestValue> = VersionedData::new();
+    let ap = KeyType(b"/foo/b".to_vec());
+
+    // Keys that were never written have epoch 0.
+    assert_eq!(vd.key_epoch(&ap), 0);
+    let (r, epoch) = vd.fetch_data_with_epoch(&ap, 5);
+    assert_err_eq!(r, MVDataError::Uninitialized);
+    assert_eq!(epoch, 0);
+
+    vd.set_base_value(
+        ap.clone(),
+        ValueWithLayout::RawFromStorage(Arc::new(TestValue::creation_with_len(1))),
+    );
+    assert_eq!(vd.key_epoch(&ap), 1);
+    // Providing the same base value again does not change the entries.
+    vd.set_base_value(
+        ap.clone(),
+        ValueWithLayout::RawFromStorage(Arc::new(TestValue::creation_with_len(1))),
+    );
+    assert_eq!(vd.key_epoch(&ap), 1);
+
+    vd.write(ap.clone(), 3, 0, arc_value_for(3, 1), None);
+    let (r, epoch) = vd.fetch_data_with_epoch(&ap, 5);
+    assert_ok_eq!(
+        r,
+        MVDataOutput::Versioned(
+            Ok((3, 0)),
+            ValueWithLayout::Exchanged(arc_value_for(3, 1), None)
+        )
+    );
+    assert_eq!(epoch, 2);
+
+    // Reads do not change the epoch.
+    assert_eq!(vd.fetch_data(&ap, 5), vd.fetch_data(&ap, 5));
+    assert_eq!(vd.key_epoch(&ap), 2);
+
+    vd.mark_estimate(&ap, 3);
+    assert_eq!(vd.key_epoch(&ap), 3);
+    vd.remove(&ap, 3);
+    assert_eq!(vd.key_epoch(&ap), 4);
+    vd.add_delta(ap.clone(), 7, delta_add(5, 100));
+    assert_eq!(vd.key_epoch(&ap), 5);
+
+    // Other keys are unaffected.
+    assert_eq!(vd.key_epoch(&KeyType(b"/foo/c".to_vec())), 0);
+}
+
 #[test]
 #[should_panic]
 fn aggregator_base_mismatch() {
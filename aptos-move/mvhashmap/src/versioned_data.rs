@@ -3,7 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::types::{
-    Flag, Incarnation, MVDataError, MVDataOutput, ShiftedTxnIndex, TxnIndex, ValueWithLayout,
+    Flag, Incarnation, KeyEpoch, MVDataError, MVDataOutput, ShiftedTxnIndex, TxnIndex,
+    ValueWithLayout,
 };
 use anyhow::Result;
 use aptos_aggregator::delta_change_set::DeltaOp;
@@ -48,8 +49,12 @@ enum EntryCell<V> {
 
 /// A versioned value internally is represented as a BTreeMap from indices of
 /// transactions that update the given access path & the corresponding entries.
+/// The epoch is incremented on every modification of the map that may change
+/// the outcome of a read, so that validation can skip keys that were not
+/// modified since they were read.
 struct VersionedValue<V> {
     versioned_map: BTreeMap<ShiftedTxnIndex, CachePadded<Entry<V>>>,
+    epoch: KeyEpoch,
 }
 
 /// Maps each key (access path) to an internal versioned value representation.
@@ -103,11 +108,16 @@ impl<V: TransactionWrite> Default for VersionedValue<V> {
     fn default() -> Self {
         Self {
             versioned_map: BTreeMap::new(),
+            epoch: 0,
         }
     }
 }
 
 impl<V: TransactionWrite> VersionedValue<V> {
+    fn bump_epoch(&mut self) {
+        self.epoch += 1;
+    }
+
     fn read(&self, txn_idx: TxnIndex) -> anyhow::Result<MVDataOutput<V>, MVDataError> {
         use MVDataError::*;
         use MVDataOutput::*;
@@ -235,6 +245,7 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
             ShiftedTxnIndex::new(txn_idx),
             CachePadded::new(Entry::new_delta_from(delta)),
         );
+        v.bump_epoch();
     }
 
     /// Mark an entry from transaction 'txn_idx' at access path 'key' as an estimated write
@@ -245,6 +256,7 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
             .get_mut(&ShiftedTxnIndex::new(txn_idx))
             .expect("Entry by the txn must exist to mark estimate")
             .mark_estimate();
+        v.bump_epoch();
     }
 
     /// Delete an entry from transaction 'txn_idx' at access path 'key'. Will panic
@@ -256,6 +268,7 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
             v.versioned_map.remove(&ShiftedTxnIndex::new(txn_idx)),
             "Entry for key / idx must exist to be deleted"
         );
+        v.bump_epoch();
     }
 
     pub fn fetch_data(
@@ -263,10 +276,27 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
         key: &K,
         txn_idx: TxnIndex,
     ) -> anyhow::Result<MVDataOutput<V>, MVDataError> {
+        self.fetch_data_with_epoch(key, txn_idx).0
+    }
+
+    /// Same as [`Self::fetch_data`], but also returns the epoch of the key at the time
+    /// of the read. If [`Self::key_epoch`] later returns the same epoch, the key has not
+    /// been modified since, and repeating the read would return the same output.
+    pub fn fetch_data_with_epoch(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+    ) -> (anyhow::Result<MVDataOutput<V>, MVDataError>, KeyEpoch) {
         self.values
             .get(key)
-            .map(|v| v.read(txn_idx))
-            .unwrap_or(Err(MVDataError::Uninitialized))
+            .map(|v| (v.read(txn_idx), v.epoch))
+            .unwrap_or((Err(MVDataError::Uninitialized), 0))
+    }
+
+    /// Returns the current epoch of the key, i.e. the number of modifications to the
+    /// versioned entries of the key (0 if the key was never written).
+    pub fn key_epoch(&self, key: &K) -> KeyEpoch {
+        self.values.get(key).map_or(0, |v| v.epoch)
     }
 
     pub fn fetch_exchanged_data(
@@ -289,13 +319,14 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
 
         use btree_map::Entry::*;
         use ValueWithLayout::*;
-        match v.versioned_map.entry(ShiftedTxnIndex::zero_idx()) {
+        let updated = match v.versioned_map.entry(ShiftedTxnIndex::zero_idx()) {
             Vacant(v) => {
                 if let Some(base_size) = value.bytes_len() {
                     self.total_base_value_size
                         .fetch_add(base_size as u64, Ordering::Relaxed);
                 }
                 v.insert(CachePadded::new(Entry::new_write_from(0, value)));
+                true
             },
             Occupied(mut o) => {
                 if let EntryCell::Write(i, existing_value) = &o.get().cell {
@@ -304,14 +335,17 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
                         (RawFromStorage(ev), RawFromStorage(v)) => {
                             // Base value from storage needs to be identical
                             // Assert the length of bytes for efficiency (instead of full equality)
-                            assert!(v.bytes().map(|b| b.len()) == ev.bytes().map(|b| b.len()))
+                            assert!(v.bytes().map(|b| b.len()) == ev.bytes().map(|b| b.len()));
+                            false
                         },
                         (Exchanged(_, _), RawFromStorage(_)) => {
                             // Stored value contains more info, nothing to do.
+                            false
                         },
                         (RawFromStorage(_), Exchanged(_, _)) => {
                             // Received more info, update.
                             o.insert(CachePadded::new(Entry::new_write_from(0, value)));
+                            true
                         },
                         (Exchanged(ev, e_layout), Exchanged(v, layout)) => {
                             // base value may have already been provided by another transaction
@@ -326,11 +360,17 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
                             if layout.is_none() {
                                 assert_eq!(v.bytes().map(|b| b.len()), ev.bytes().map(|b| b.len()));
                             }
+                            false
                         },
                     }
+                } else {
+                    false
                 }
             },
         };
+        if updated {
+            v.bump_epoch();
+        }
     }
 
     /// Versioned write of data at a given key (and version).
@@ -350,6 +390,7 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
                 ValueWithLayout::Exchanged(data, maybe_layout),
             )),
         );
+        v.bump_epoch();
 
         // Assert that the previous entry for txn_idx, if present, had lower incarnation.
         assert!(prev_entry.map_or(true, |entry| -> bool {
@@ -381,6 +422,7 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
                 ValueWithLayout::Exchanged(arc_data.clone(), None),
             )),
         );
+        v.bump_epoch();
 
         // Changes versioned metadata that was stored.
         prev_entry.map_or(true, |entry| -> bool {
@@ -23,6 +23,8 @@ use aptos_build_info::build_information;
 use aptos_config::config::{merge_node_config, NodeConfig, PersistableConfig};
 use aptos_framework::ReleaseBundle;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
+use aptos_mempool::MempoolLoadMonitor;
+use aptos_peer_monitoring_service_types::MempoolUtilization;
 use aptos_state_sync_driver::driver_factory::StateSyncRuntimes;
 use aptos_types::{chain_id::ChainId, on_chain_config::OnChainJWKConsensusConfig};
 use clap::Parser;
@@ -218,6 +220,9 @@ pub fn start(
     // Setup panic handler
     aptos_crash_handler::setup_panic_handler();
 
+    // Apply the CPU override before any thread pools are created (so they're sized for it)
+    aptos_runtimes::set_num_available_cpus_override(config.node_startup.num_available_cpus());
+
     // Create global rayon thread pool
     utils::create_global_rayon_pool(create_global_rayon_pool);
 
@@ -227,6 +232,9 @@ pub fn start(
     // Instantiate the global logger
     let (remote_log_receiver, logger_filter_update) = logger::create_logger(&config, log_file);
 
+    // Enable crash bundles (if configured)
+    utils::setup_crash_bundles(&config);
+
     assert!(
         !cfg!(feature = "testing") && !cfg!(feature = "fuzzing"),
         "Testing features shouldn't be compiled"
@@ -251,9 +259,18 @@ pub fn start(
         warn!("Failpoints is set in the node config, but the binary didn't compile with this feature!");
     }
 
-    // Set up the node environment and start it
-    let _node_handle =
-        setup_environment_and_start_node(config, remote_log_receiver, Some(logger_filter_update))?;
+    // Set up the node environment and start it (reporting any fatal errors)
+    let _node_handle = match setup_environment_and_start_node(
+        config,
+        remote_log_receiver,
+        Some(logger_filter_update),
+    ) {
+        Ok(node_handle) => node_handle,
+        Err(error) => {
+            aptos_crash_handler::report_fatal_error(&error);
+            return Err(error);
+        },
+    };
     let term = Arc::new(AtomicBool::new(false));
     while !term.load(Ordering::Acquire) {
         thread::park();
@@ -601,6 +618,16 @@ pub fn setup_environment_and_start_node(
     // Log the node config at node startup
     node_config.log_all_configs();
 
+    // Size all runtimes and thread pools for the CPUs available to the node
+    let num_available_cpus = node_config.node_startup.num_available_cpus();
+    aptos_runtimes::set_num_available_cpus_override(num_available_cpus);
+    info!(
+        "Sizing runtimes and thread pools for {} CPUs (cgroup CPU quota: {:?}, cgroup IO limits: {})",
+        num_available_cpus,
+        aptos_runtimes::detect_cgroup_cpu_quota(),
+        aptos_runtimes::detect_cgroup_io_limits(),
+    );
+
     // Starts the admin service
     let mut admin_service = services::start_admin_service(&node_config);
 
@@ -619,12 +646,16 @@ pub fn setup_environment_and_start_node(
     // Set the chain_id in global AptosNodeIdentity
     aptos_node_identity::set_chain_id(chain_id)?;
 
+    // Create the peers and metadata (shared by the networks and the telemetry service)
+    let peers_and_metadata = network::create_peers_and_metadata(&node_config);
+
     // Start the telemetry service (as early as possible and before any blocking calls)
     let telemetry_runtime = services::start_telemetry_service(
         &node_config,
         remote_log_rx,
         logger_filter_update_job,
         chain_id,
+        peers_and_metadata.clone(),
     );
 
     // Create an event subscription service (and reconfig subscriptions for consensus and mempool)
@@ -638,7 +669,6 @@ pub fn setup_environment_and_start_node(
     ) = state_sync::create_event_subscription_service(&node_config, &db_rw);
 
     // Set up the networks and gather the application network handles
-    let peers_and_metadata = network::create_peers_and_metadata(&node_config);
     let (
         network_runtimes,
         consensus_network_interfaces,
@@ -655,11 +685,14 @@ pub fn setup_environment_and_start_node(
         &mut event_subscription_service,
     );
 
-    // Start the peer monitoring service
+    // Start the peer monitoring service. The mempool utilization is
+    // reported by mempool (once started) and served to peers.
+    let mempool_utilization = MempoolUtilization::default();
     let peer_monitoring_service_runtime = services::start_peer_monitoring_service(
         &node_config,
         peer_monitoring_service_network_interfaces,
         db_rw.reader.clone(),
+        mempool_utilization.clone(),
     );
 
     // Start state sync and get the notification endpoints for mempool and consensus
@@ -672,14 +705,19 @@ pub fn setup_environment_and_start_node(
             db_rw.clone(),
         )?;
 
-    // Start the node inspection service
+    // Start the node inspection service. The mempool peer priorities
+    // are published by mempool (once started) to the shared handle.
+    let mempool_prioritized_peers = Arc::default();
     services::start_node_inspection_service(
         &node_config,
         aptos_data_client,
         peers_and_metadata.clone(),
+        mempool_prioritized_peers.clone(),
     );
 
-    // Bootstrap the API and indexer
+    // Bootstrap the API and indexer. The mempool load state is published by
+    // mempool (once started) and used by the API to apply submission backpressure.
+    let mempool_load_monitor = MempoolLoadMonitor::default();
     let (
         mempool_client_receiver,
         api_runtime,
@@ -687,7 +725,14 @@ pub fn setup_environment_and_start_node(
         indexer_runtime,
         indexer_grpc_runtime,
         internal_indexer_db_runtime,
-    ) = services::bootstrap_api_and_indexer(&node_config, db_rw.clone(), chain_id, indexer_db_opt)?;
+    ) = services::bootstrap_api_and_indexer(
+        &node_config,
+        db_rw.clone(),
+        chain_id,
+        indexer_db_opt,
+        mempool_load_monitor.clone(),
+        &admin_service,
+    )?;
 
     // Create mempool and get the consensus to mempool sender
     let (mempool_runtime, consensus_to_mempool_sender) =
@@ -698,7 +743,11 @@ pub fn setup_environment_and_start_node(
             mempool_network_interfaces,
             mempool_listener,
             mempool_client_receiver,
-            peers_and_metadata,
+            peers_and_metadata.clone(),
+            mempool_utilization,
+            mempool_load_monitor,
+            mempool_prioritized_peers,
+            &mut admin_service,
         );
 
     // Create the DKG runtime and get the VTxn pool
@@ -44,6 +44,9 @@ pub fn create_logger(
     if node_config.logger.enable_backtrace {
         logger_builder.enable_backtrace();
     }
+    if node_config.crash_handler.crash_bundle_dir.is_some() {
+        logger_builder.recent_logs_capacity(node_config.crash_handler.num_recent_logs);
+    }
     if let Some(log_file) = log_file {
         logger_builder.printer(Box::new(FileWriter::new(log_file)));
     }
@@ -108,7 +108,7 @@ pub fn jwk_consensus_network_configuration(node_config: &NodeConfig) -> NetworkA
 /// Returns the network application config for the mempool client and service
 pub fn mempool_network_configuration(node_config: &NodeConfig) -> NetworkApplicationConfig {
     let direct_send_protocols = vec![ProtocolId::MempoolDirectSend];
-    let rpc_protocols = vec![]; // Mempool does not use RPC
+    let rpc_protocols = vec![ProtocolId::MempoolRpc]; // Only used for transaction fetches
 
     let network_client_config =
         NetworkClientConfig::new(direct_send_protocols.clone(), rpc_protocols.clone());
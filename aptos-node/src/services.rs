@@ -7,8 +7,8 @@ use aptos_build_info::build_information;
 use aptos_config::config::NodeConfig;
 use aptos_consensus::{
     consensus_observer::publisher::consensus_publisher::ConsensusPublisher,
-    network_interface::ConsensusMsg, persistent_liveness_storage::StorageWriteProxy,
-    quorum_store::quorum_store_db::QuorumStoreDB,
+    network_interface::ConsensusMsg, payload_unavailability,
+    persistent_liveness_storage::StorageWriteProxy, quorum_store::quorum_store_db::QuorumStoreDB,
 };
 use aptos_consensus_notifications::ConsensusNotifier;
 use aptos_data_client::client::AptosDataClient;
@@ -18,8 +18,13 @@ use aptos_indexer_grpc_fullnode::runtime::bootstrap as bootstrap_indexer_grpc;
 use aptos_indexer_grpc_table_info::runtime::{
     bootstrap as bootstrap_indexer_table_info, bootstrap_internal_indexer_db,
 };
+use aptos_infallible::RwLock;
+use aptos_inspection_service::PayloadUnavailabilityReporter;
 use aptos_logger::{debug, telemetry_log_writer::TelemetryLog, LoggerFilterUpdater};
-use aptos_mempool::{network::MempoolSyncMsg, MempoolClientRequest, QuorumStoreRequest};
+use aptos_mempool::{
+    network::MempoolSyncMsg, MempoolClientRequest, MempoolLoadMonitor, PrioritizedPeerInfo,
+    QuorumStoreRequest,
+};
 use aptos_mempool_notifications::MempoolNotificationListener;
 use aptos_network::application::{interface::NetworkClientInterface, storage::PeersAndMetadata};
 use aptos_network_benchmark::{run_netbench_service, NetbenchMessage};
@@ -27,7 +32,7 @@ use aptos_peer_monitoring_service_server::{
     network::PeerMonitoringServiceNetworkEvents, storage::StorageReader,
     PeerMonitoringServiceServer,
 };
-use aptos_peer_monitoring_service_types::PeerMonitoringServiceMessage;
+use aptos_peer_monitoring_service_types::{MempoolUtilization, PeerMonitoringServiceMessage};
 use aptos_storage_interface::{DbReader, DbReaderWriter};
 use aptos_time_service::TimeService;
 use aptos_types::{chain_id::ChainId, indexer::indexer_db_reader::IndexerReader};
@@ -46,6 +51,8 @@ pub fn bootstrap_api_and_indexer(
     db_rw: DbReaderWriter,
     chain_id: ChainId,
     internal_indexer_db: Option<InternalIndexerDB>,
+    mempool_load_monitor: MempoolLoadMonitor,
+    admin_service: &AdminService,
 ) -> anyhow::Result<(
     Receiver<MempoolClientRequest>,
     Option<Runtime>,
@@ -58,6 +65,9 @@ pub fn bootstrap_api_and_indexer(
     let (mempool_client_sender, mempool_client_receiver) =
         mpsc::channel(AC_SMP_CHANNEL_BUFFER_SIZE);
 
+    // Expose the pending mempool transactions via the admin service
+    admin_service.set_mempool_client_sender(mempool_client_sender.clone());
+
     let (indexer_table_info_runtime, indexer_async_v2) = match bootstrap_indexer_table_info(
         node_config,
         chain_id,
@@ -89,6 +99,7 @@ pub fn bootstrap_api_and_indexer(
             db_rw.reader.clone(),
             mempool_client_sender.clone(),
             indexer_reader.clone(),
+            mempool_load_monitor,
         )?)
     } else {
         None
@@ -162,6 +173,10 @@ pub fn start_mempool_runtime_and_get_consensus_sender(
     mempool_listener: MempoolNotificationListener,
     mempool_client_receiver: Receiver<MempoolClientRequest>,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    mempool_utilization: MempoolUtilization,
+    mempool_load_monitor: MempoolLoadMonitor,
+    mempool_prioritized_peers: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+    admin_service: &mut AdminService,
 ) -> (Runtime, Sender<QuorumStoreRequest>) {
     // Create a communication channel between consensus and mempool
     let (consensus_to_mempool_sender, consensus_to_mempool_receiver) =
@@ -169,7 +184,7 @@ pub fn start_mempool_runtime_and_get_consensus_sender(
 
     // Bootstrap and start mempool
     let instant = Instant::now();
-    let mempool = aptos_mempool::bootstrap(
+    let (mempool, client_quota_manager) = aptos_mempool::bootstrap(
         node_config,
         Arc::clone(&db_rw.reader),
         network_interfaces.network_client,
@@ -179,9 +194,15 @@ pub fn start_mempool_runtime_and_get_consensus_sender(
         mempool_listener,
         mempool_reconfig_subscription,
         peers_and_metadata,
+        mempool_utilization,
+        mempool_load_monitor,
+        mempool_prioritized_peers,
     );
     debug!("Mempool started in {} ms", instant.elapsed().as_millis());
 
+    // Expose the client quota usage via the admin service
+    admin_service.set_mempool_client_quota_manager(client_quota_manager);
+
     (mempool, consensus_to_mempool_sender)
 }
 
@@ -195,11 +216,19 @@ pub fn start_node_inspection_service(
     node_config: &NodeConfig,
     aptos_data_client: AptosDataClient,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    mempool_prioritized_peers: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
 ) {
+    // Register the consensus payload unavailability report with the inspection service
+    let payload_unavailability_reporter: PayloadUnavailabilityReporter = Arc::new(|| {
+        serde_json::to_string(&payload_unavailability::get_payload_unavailability_report())
+    });
+
     aptos_inspection_service::start_inspection_service(
         node_config.clone(),
         aptos_data_client,
         peers_and_metadata,
+        mempool_prioritized_peers,
+        payload_unavailability_reporter,
     )
 }
 
@@ -208,14 +237,16 @@ pub fn start_peer_monitoring_service(
     node_config: &NodeConfig,
     network_interfaces: ApplicationNetworkInterfaces<PeerMonitoringServiceMessage>,
     db_reader: Arc<dyn DbReader>,
+    mempool_utilization: MempoolUtilization,
 ) -> Runtime {
     // Get the network client and events
     let network_client = network_interfaces.network_client;
     let network_service_events = network_interfaces.network_service_events;
 
-    // Create a new runtime for the monitoring service
+    // Create a new runtime for the monitoring service. The monitoring
+    // service is not safety-critical, so panics are contained.
     let peer_monitoring_service_runtime =
-        aptos_runtimes::spawn_named_runtime("peer-mon".into(), None);
+        aptos_runtimes::spawn_supervised_runtime("peer-mon".into(), "peer-monitoring", None);
 
     // Create and spawn the peer monitoring server
     let peer_monitoring_network_events =
@@ -225,22 +256,29 @@ pub fn start_peer_monitoring_service(
         peer_monitoring_service_runtime.handle().clone(),
         peer_monitoring_network_events,
         network_client.get_peers_and_metadata(),
+        mempool_utilization,
         StorageReader::new(db_reader),
         TimeService::real(),
     );
     peer_monitoring_service_runtime.spawn(peer_monitoring_server.start());
 
-    // Spawn the peer monitoring client
+    // Spawn the peer monitoring client (the client is restarted if it panics)
     if node_config
         .peer_monitoring_service
         .enable_peer_monitoring_client
     {
-        peer_monitoring_service_runtime.spawn(
-            aptos_peer_monitoring_service_client::start_peer_monitor(
-                node_config.clone(),
-                network_client,
-                Some(peer_monitoring_service_runtime.handle().clone()),
-            ),
+        let node_config = node_config.clone();
+        let runtime_handle = peer_monitoring_service_runtime.handle().clone();
+        aptos_runtimes::spawn_supervised(
+            peer_monitoring_service_runtime.handle(),
+            "peer-monitoring",
+            move || {
+                aptos_peer_monitoring_service_client::start_peer_monitor(
+                    node_config.clone(),
+                    network_client.clone(),
+                    Some(runtime_handle.clone()),
+                )
+            },
         );
     }
 
@@ -268,6 +306,7 @@ pub fn start_telemetry_service(
     remote_log_rx: Option<Receiver<TelemetryLog>>,
     logger_filter_update_job: Option<LoggerFilterUpdater>,
     chain_id: ChainId,
+    peers_and_metadata: Arc<PeersAndMetadata>,
 ) -> Option<Runtime> {
     let build_info = build_information!();
     aptos_telemetry::service::start_telemetry_service(
@@ -276,5 +315,6 @@ pub fn start_telemetry_service(
         build_info,
         remote_log_rx,
         logger_filter_update_job,
+        peers_and_metadata,
     )
 }
@@ -99,7 +99,7 @@ pub(crate) fn bootstrap_db(
         node_config.storage.get_dir_paths(),
         false, /* readonly */
         node_config.storage.storage_pruner_config,
-        node_config.storage.rocksdb_configs,
+        node_config.storage.rocksdb_configs.clone(),
         node_config.storage.enable_indexer,
         node_config.storage.buffered_state_target_items,
         node_config.storage.max_num_nodes_per_lru_cache_shard,
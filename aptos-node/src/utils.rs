@@ -3,12 +3,15 @@
 
 use anyhow::anyhow;
 use aptos_config::config::{NodeConfig, DEFAULT_EXECUTION_CONCURRENCY_LEVEL};
+use aptos_crash_handler::CrashBundleConfig;
+use aptos_crypto::HashValue;
+use aptos_logger::warn;
 use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReaderWriter};
 use aptos_types::{
     account_config::ChainIdResource, chain_id::ChainId, on_chain_config::OnChainConfig,
     vm::configs::set_paranoid_type_checks,
 };
-use aptos_vm::AptosVM;
+use aptos_vm::{block_executor::randomness_seed_sidecar::enable_randomness_seed_sidecar, AptosVM};
 use std::cmp::min;
 
 /// Error message to display when non-production features are enabled
@@ -23,16 +26,44 @@ PLEASE RECOMPILE APTOS-NODE SEPARATELY using the following command:
 
 "#;
 
-/// Initializes a global rayon thread pool iff `create_global_rayon_pool` is true
+/// Initializes a global rayon thread pool (sized for the available CPUs) iff
+/// `create_global_rayon_pool` is true
 pub fn create_global_rayon_pool(create_global_rayon_pool: bool) {
     if create_global_rayon_pool {
         rayon::ThreadPoolBuilder::new()
             .thread_name(|index| format!("rayon-global-{}", index))
+            .num_threads(aptos_runtimes::num_available_cpus())
             .build_global()
             .expect("Failed to build rayon global thread pool.");
     }
 }
 
+/// Enables crash bundles (if a crash bundle directory is configured)
+pub fn setup_crash_bundles(node_config: &NodeConfig) {
+    let crash_handler_config = &node_config.crash_handler;
+    if let Some(crash_bundle_dir) = &crash_handler_config.crash_bundle_dir {
+        // Compute a digest of the node config (to identify the config in crash bundles)
+        let config_digest = match serde_yaml::to_string(node_config) {
+            Ok(serialized_config) => HashValue::sha3_256_of(serialized_config.as_bytes()).to_hex(),
+            Err(error) => {
+                warn!(
+                    "Failed to serialize the node config for the crash bundles: {:?}",
+                    error
+                );
+                String::new()
+            },
+        };
+
+        aptos_crash_handler::setup_crash_bundles(CrashBundleConfig {
+            crash_bundle_dir: crash_bundle_dir.clone(),
+            config_digest,
+            include_metrics_snapshot: crash_handler_config.include_metrics_snapshot,
+            notify_telemetry: crash_handler_config.notify_telemetry,
+            max_crash_bundles: crash_handler_config.max_crash_bundles,
+        });
+    }
+}
+
 /// Fetches the chain ID from on-chain resources
 pub fn fetch_chain_id(db: &DbReaderWriter) -> anyhow::Result<ChainId> {
     let db_state_view = db
@@ -50,13 +81,16 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     let effective_concurrency_level = if node_config.execution.concurrency_level == 0 {
         min(
             DEFAULT_EXECUTION_CONCURRENCY_LEVEL,
-            (num_cpus::get() / 2) as u16,
+            (node_config.node_startup.num_available_cpus() / 2) as u16,
         )
     } else {
         node_config.execution.concurrency_level
     };
     AptosVM::set_concurrency_level_once(effective_concurrency_level as usize);
     AptosVM::set_discard_failed_blocks(node_config.execution.discard_failed_blocks);
+    AptosVM::set_speculative_write_budget_bytes_once(
+        node_config.execution.speculative_write_budget_bytes,
+    );
     AptosVM::set_num_proof_reading_threads_once(
         node_config.execution.num_proof_reading_threads as usize,
     );
@@ -67,4 +101,13 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     {
         AptosVM::set_processed_transactions_detailed_counters();
     }
+
+    if let Some(sidecar_path) = &node_config.execution.randomness_seed_sidecar_path {
+        if let Err(error) = enable_randomness_seed_sidecar(sidecar_path) {
+            warn!(
+                "Failed to open the randomness seed sidecar file {:?}: {:?}",
+                sidecar_path, error
+            );
+        }
+    }
 }
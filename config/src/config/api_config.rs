@@ -10,9 +10,13 @@ use crate::{
     },
     utils,
 };
+use aptos_crypto::HashValue;
 use aptos_types::{account_address::AccountAddress, chain_id::ChainId};
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::{
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -28,6 +32,11 @@ pub struct ApiConfig {
     /// Path to a local TLS key to enable HTTPS
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tls_key_path: Option<String>,
+    /// Path to a local CA certificate to enable mutual TLS (requires HTTPS). If set,
+    /// only clients presenting a certificate signed by the CA can connect, so the
+    /// client API keys can only be used by clients that also hold a certificate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_client_ca_path: Option<String>,
     /// A maximum limit to the body of a POST request in bytes
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content_length_limit: Option<u64>,
@@ -49,8 +58,14 @@ pub struct ApiConfig {
     /// Enables transaction simulation
     #[serde(default = "default_enabled")]
     pub transaction_simulation_enabled: bool,
+    /// Enables structured VM status details for failed user transactions (e.g., the
+    /// abort code names resolved from the module error maps, and the entry function)
+    #[serde(default = "default_disabled")]
+    pub detailed_vm_status_enabled: bool,
     /// Maximum number of transactions that can be sent with the Batch submit API
     pub max_submit_transaction_batch_size: usize,
+    /// Maximum number of transaction hashes that can be sent with the mempool status API
+    pub max_transaction_mempool_status_batch_size: usize,
     /// Maximum page size for transaction paginated APIs
     pub max_transactions_page_size: u16,
     /// Maximum page size for block transaction APIs
@@ -89,12 +104,92 @@ pub struct ApiConfig {
     pub wait_by_hash_poll_interval_ms: u64,
     /// The number of active wait_by_hash requests that can be active at any given time.
     pub wait_by_hash_max_active_connections: usize,
+    /// API keys that authenticate clients. Transactions submitted with a valid
+    /// key are attributed to the client's identity (e.g., for mempool quotas).
+    /// Only the hash of each key is stored, so the keys never appear in the config.
+    pub client_api_keys: Vec<ClientApiKey>,
+    /// The request rate limits enforced on each client (identified by API key or IP)
+    pub rate_limit: ApiRateLimitConfig,
+}
+
+/// The hash of an API key (provided in the `x-aptos-api-key` header) and the client it identifies
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientApiKey {
+    /// The identity of the client that owns the key
+    pub identity: String,
+    /// The SHA3-256 hash of the secret API key (the key itself is never stored)
+    pub api_key_hash: HashValue,
+}
+
+impl ClientApiKey {
+    /// Creates a client API key entry from the given identity and secret key
+    pub fn new(identity: String, api_key: &str) -> Self {
+        Self {
+            identity,
+            api_key_hash: Self::hash_api_key(api_key),
+        }
+    }
+
+    /// Returns the hash of the given secret API key
+    pub fn hash_api_key(api_key: &str) -> HashValue {
+        HashValue::sha3_256_of(api_key.as_bytes())
+    }
+}
+
+/// The request rate limits enforced on each API client. Clients that provide a valid API
+/// key are limited per key, and all other clients are limited per IP address. Requests are
+/// counted in fixed windows, and the remaining budget is reported in the response headers.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiRateLimitConfig {
+    /// Whether to enforce the rate limits (and report them in the response headers)
+    pub enabled: bool,
+    /// The duration of each rate limit window (secs)
+    pub window_secs: u64,
+    /// The maximum number of requests per window (for each client IP address)
+    pub max_requests_per_ip: u64,
+    /// The maximum number of requests per window (for each client API key)
+    pub max_requests_per_api_key: u64,
+    /// Overrides of the limits for specific routes. Requests to these routes are
+    /// counted separately (i.e., they don't consume the default budget).
+    pub route_overrides: Vec<RouteRateLimitConfig>,
+    /// The addresses of trusted proxies (e.g., load balancers). Requests from these
+    /// addresses are attributed to the client address in the `X-Forwarded-For` header.
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl Default for ApiRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_secs: 60,
+            max_requests_per_ip: 6_000,
+            max_requests_per_api_key: 60_000,
+            route_overrides: vec![],
+            trusted_proxies: vec![],
+        }
+    }
+}
+
+/// The rate limits of all routes that start with the given path prefix (e.g.,
+/// "/v1/transactions"). If several prefixes match, the longest one is used.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouteRateLimitConfig {
+    /// The path prefix of the routes
+    pub path_prefix: String,
+    /// The maximum number of requests per window (for each client IP address)
+    pub max_requests_per_ip: u64,
+    /// The maximum number of requests per window (for each client API key)
+    pub max_requests_per_api_key: u64,
 }
 
 const DEFAULT_ADDRESS: &str = "127.0.0.1";
 const DEFAULT_PORT: u16 = 8080;
 const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 8 * 1024 * 1024; // 8 MB
 pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 10;
+pub const DEFAULT_MAX_TRANSACTION_MEMPOOL_STATUS_BATCH_SIZE: usize = 100;
 pub const DEFAULT_MAX_PAGE_SIZE: u16 = 100;
 const DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE: u16 = 9999;
 const DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE: u16 = 9999;
@@ -117,6 +212,7 @@ impl Default for ApiConfig {
                 .unwrap(),
             tls_cert_path: None,
             tls_key_path: None,
+            tls_client_ca_path: None,
             content_length_limit: None,
             failpoints_enabled: default_disabled(),
             bcs_output_enabled: default_enabled(),
@@ -124,7 +220,10 @@ impl Default for ApiConfig {
             encode_submission_enabled: default_enabled(),
             transaction_submission_enabled: default_enabled(),
             transaction_simulation_enabled: default_enabled(),
+            detailed_vm_status_enabled: default_disabled(),
             max_submit_transaction_batch_size: DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE,
+            max_transaction_mempool_status_batch_size:
+                DEFAULT_MAX_TRANSACTION_MEMPOOL_STATUS_BATCH_SIZE,
             max_block_transactions_page_size: *MAX_RECEIVING_BLOCK_TXNS as u16,
             max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_events_page_size: DEFAULT_MAX_PAGE_SIZE,
@@ -141,6 +240,8 @@ impl Default for ApiConfig {
             wait_by_hash_timeout_ms: 1_000,
             wait_by_hash_poll_interval_ms: 20,
             wait_by_hash_max_active_connections: 100,
+            client_api_keys: vec![],
+            rate_limit: ApiRateLimitConfig::default(),
         }
     }
 }
@@ -200,6 +301,67 @@ impl ConfigSanitizer for ApiConfig {
             }
         }
 
+        // Verify that mutual TLS is only enabled with TLS
+        if api_config.tls_client_ca_path.is_some()
+            && (api_config.tls_cert_path.is_none() || api_config.tls_key_path.is_none())
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "tls_client_ca_path requires both tls_cert_path and tls_key_path to be set!".into(),
+            ));
+        }
+
+        // Verify that the client API keys are non-empty and unique
+        let empty_api_key_hash = ClientApiKey::hash_api_key("");
+        let mut api_key_hashes = HashSet::new();
+        for client_api_key in &api_config.client_api_keys {
+            if client_api_key.identity.is_empty()
+                || client_api_key.api_key_hash == empty_api_key_hash
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "Client API keys must have a non-empty identity and key!".into(),
+                ));
+            }
+            if !api_key_hashes.insert(client_api_key.api_key_hash) {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "Duplicate client API key found for identity: {}",
+                        client_api_key.identity
+                    ),
+                ));
+            }
+        }
+
+        // Verify that the rate limits (if enabled) are valid
+        let rate_limit = &api_config.rate_limit;
+        if rate_limit.enabled {
+            if rate_limit.window_secs == 0
+                || rate_limit.max_requests_per_ip == 0
+                || rate_limit.max_requests_per_api_key == 0
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "The rate limit window and limits must be greater than 0!".into(),
+                ));
+            }
+            let mut path_prefixes = HashSet::new();
+            for route_override in &rate_limit.route_overrides {
+                if !route_override.path_prefix.starts_with('/')
+                    || !path_prefixes.insert(&route_override.path_prefix)
+                {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        format!(
+                            "Route rate limit prefixes must be unique and start with '/'! Found: {}",
+                            route_override.path_prefix
+                        ),
+                    ));
+                }
+            }
+        }
+
         // Sanitize the gas estimation config
         GasEstimationConfig::sanitize(node_config, node_type, chain_id)?;
 
@@ -315,4 +477,81 @@ mod tests {
                 .unwrap_err();
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
+
+    #[test]
+    fn test_sanitize_client_ca_without_tls() {
+        // Create a node config with a client CA but without TLS
+        let node_config = NodeConfig {
+            api: ApiConfig {
+                enabled: true,
+                tls_client_ca_path: Some("ca.pem".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails because
+        // mutual TLS requires TLS to be enabled.
+        let error =
+            ApiConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::mainnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_duplicate_client_api_keys() {
+        // Create a node config with duplicate client API keys
+        let client_api_key = ClientApiKey::new("client".into(), "secret");
+        let node_config = NodeConfig {
+            api: ApiConfig {
+                enabled: true,
+                client_api_keys: vec![client_api_key.clone(), client_api_key],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails because
+        // the client API keys are not unique.
+        let error =
+            ApiConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::mainnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_rate_limit() {
+        // Create a node config with rate limits enabled
+        let route_override = RouteRateLimitConfig {
+            path_prefix: "/v1/transactions".into(),
+            max_requests_per_ip: 10,
+            max_requests_per_api_key: 100,
+        };
+        let mut node_config = NodeConfig {
+            api: ApiConfig {
+                enabled: true,
+                rate_limit: ApiRateLimitConfig {
+                    enabled: true,
+                    route_overrides: vec![route_override.clone()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization passes
+        ApiConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::mainnet())).unwrap();
+
+        // Add a duplicate route override and verify that sanitization fails
+        node_config
+            .api
+            .rate_limit
+            .route_overrides
+            .push(route_override);
+        let error =
+            ApiConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::mainnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
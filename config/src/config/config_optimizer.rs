@@ -8,7 +8,7 @@ use crate::{
     config::{
         node_config_loader::NodeType, utils::get_config_name, AdminServiceConfig, Error,
         ExecutionConfig, IndexerConfig, InspectionServiceConfig, LoggerConfig, MempoolConfig,
-        NodeConfig, Peer, PeerRole, PeerSet, StateSyncConfig,
+        NodeConfig, Peer, PeerRole, PeerSet, StateSyncConfig, StorageConfig,
     },
     network_id::NetworkId,
 };
@@ -134,6 +134,9 @@ impl ConfigOptimizer for NodeConfig {
         if StateSyncConfig::optimize(node_config, local_config_yaml, node_type, chain_id)? {
             optimizers_with_modifications.push(StateSyncConfig::get_optimizer_name());
         }
+        if StorageConfig::optimize(node_config, local_config_yaml, node_type, chain_id)? {
+            optimizers_with_modifications.push(StorageConfig::get_optimizer_name());
+        }
         if optimize_all_network_configs(node_config, local_config_yaml, node_type, chain_id)? {
             optimizers_with_modifications.push(ALL_NETWORKS_OPTIMIZER_NAME.to_string());
         }
@@ -7,7 +7,8 @@ use crate::config::{
     utils::{are_failpoints_enabled, get_config_name},
     AdminServiceConfig, ApiConfig, BaseConfig, ConsensusConfig, DagConsensusConfig, Error,
     ExecutionConfig, IndexerGrpcConfig, InspectionServiceConfig, LoggerConfig, MempoolConfig,
-    NetbenchConfig, NodeConfig, StateSyncConfig, StorageConfig,
+    NetbenchConfig, NetworkConfig, NodeConfig, PeerMonitoringServiceConfig, StateSyncConfig,
+    StorageConfig,
 };
 use aptos_types::chain_id::ChainId;
 use std::collections::HashSet;
@@ -61,6 +62,8 @@ impl ConfigSanitizer for NodeConfig {
         LoggerConfig::sanitize(node_config, node_type, chain_id)?;
         MempoolConfig::sanitize(node_config, node_type, chain_id)?;
         NetbenchConfig::sanitize(node_config, node_type, chain_id)?;
+        NetworkConfig::sanitize(node_config, node_type, chain_id)?;
+        PeerMonitoringServiceConfig::sanitize(node_config, node_type, chain_id)?;
         StateSyncConfig::sanitize(node_config, node_type, chain_id)?;
         StorageConfig::sanitize(node_config, node_type, chain_id)?;
         InternalIndexerDBConfig::sanitize(node_config, node_type, chain_id)?;
@@ -203,10 +206,7 @@ fn sanitize_validator_network_config(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        config::{node_startup_config::NodeStartupConfig, NetworkConfig},
-        network_id::NetworkId,
-    };
+    use crate::{config::node_startup_config::NodeStartupConfig, network_id::NetworkId};
 
     #[test]
     fn test_disable_config_sanitizer() {
@@ -89,6 +89,35 @@ pub struct ConsensusConfig {
     pub rand_rb_config: ReliableBroadcastConfig,
     pub num_bounded_executor_tasks: u64,
     pub enable_pre_commit: bool,
+    pub vote_recovery: VoteRecoveryConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VoteRecoveryConfig {
+    // Whether to request missing votes from peers when a round stalls with partial voting power
+    // (instead of waiting for the round to time out).
+    pub enable_vote_recovery: bool,
+    // The time (after round start) to wait for the missing votes before requesting them.
+    pub recovery_delay_ms: u64,
+    // The interval at which the current round is checked for missing votes.
+    pub check_interval_ms: u64,
+    // The maximum number of peers to request missing votes from (once per round).
+    pub max_peers_per_round: usize,
+    // The RPC timeout for each vote retrieval request.
+    pub rpc_timeout_ms: u64,
+}
+
+impl Default for VoteRecoveryConfig {
+    fn default() -> Self {
+        Self {
+            enable_vote_recovery: false,
+            recovery_delay_ms: 500,
+            check_interval_ms: 100,
+            max_peers_per_round: 10,
+            rpc_timeout_ms: 500,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -354,6 +383,7 @@ impl Default for ConsensusConfig {
             },
             num_bounded_executor_tasks: 16,
             enable_pre_commit: true,
+            vote_recovery: VoteRecoveryConfig::default(),
         }
     }
 }
@@ -25,6 +25,8 @@ pub struct ConsensusObserverConfig {
     pub max_network_channel_size: u64,
     /// Maximum number of parallel serialization tasks for message sends
     pub max_parallel_serialization_tasks: usize,
+    /// Maximum number of parallel verification tasks for received block payloads
+    pub max_parallel_verification_tasks: usize,
     /// Timeout (in milliseconds) for network RPC requests
     pub network_request_timeout_ms: u64,
 
@@ -50,6 +52,7 @@ impl Default for ConsensusObserverConfig {
             publisher_enabled: false,
             max_network_channel_size: 1000,
             max_parallel_serialization_tasks: num_cpus::get(), // Default to the number of CPUs
+            max_parallel_verification_tasks: num_cpus::get(),  // Default to the number of CPUs
             network_request_timeout_ms: 10_000,                // 10 seconds
             garbage_collection_interval_ms: 60_000,            // 60 seconds
             max_num_pending_blocks: 100,                       // 100 blocks
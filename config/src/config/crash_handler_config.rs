@@ -0,0 +1,34 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CrashHandlerConfig {
+    /// If set, a crash bundle is written to this directory on a panic or fatal error.
+    /// Each bundle holds the fingerprinted stack trace, the recent log entries, a digest
+    /// of the node config and a snapshot of the metrics.
+    pub crash_bundle_dir: Option<PathBuf>,
+    /// The number of recent log entries held in memory (and included in crash bundles)
+    pub num_recent_logs: usize,
+    /// Whether to include a snapshot of the metrics in crash bundles
+    pub include_metrics_snapshot: bool,
+    /// Whether to report crashes (i.e., the fingerprint and bundle location) via telemetry
+    pub notify_telemetry: bool,
+    /// The maximum number of crash bundles kept in the directory (the oldest are removed)
+    pub max_crash_bundles: usize,
+}
+
+impl Default for CrashHandlerConfig {
+    fn default() -> Self {
+        Self {
+            crash_bundle_dir: None,
+            num_recent_logs: 1_000,
+            include_metrics_snapshot: true,
+            notify_telemetry: false,
+            max_crash_bundles: 10,
+        }
+    }
+}
@@ -46,6 +46,9 @@ pub struct ExecutionConfig {
     pub paranoid_type_verification: bool,
     /// Enabled discarding blocks that fail execution due to BlockSTM/VM issue.
     pub discard_failed_blocks: bool,
+    /// If set, bounds the memory (in bytes) used to buffer speculative writes during
+    /// parallel execution. Blocks that exceed the budget are re-executed sequentially.
+    pub speculative_write_budget_bytes: Option<u64>,
     /// Enables paranoid mode for hot potatoes, which adds extra runtime VM checks
     pub paranoid_hot_potato_verification: bool,
     /// Enables enhanced metrics around processed transactions
@@ -54,6 +57,10 @@ pub struct ExecutionConfig {
     pub transaction_filter: Filter,
     /// Used during DB bootstrapping
     pub genesis_waypoint: Option<WaypointConfig>,
+    /// If set, the randomness seed used by each executed transaction is appended (as a
+    /// line of JSON) to this sidecar file. This is for debugging only, e.g., to replay
+    /// transactions that consume randomness with `aptos move replay`.
+    pub randomness_seed_sidecar_path: Option<PathBuf>,
 }
 
 impl std::fmt::Debug for ExecutionConfig {
@@ -83,9 +90,11 @@ impl Default for ExecutionConfig {
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
             discard_failed_blocks: false,
+            speculative_write_budget_bytes: None,
             processed_transactions_detailed_counters: false,
             transaction_filter: Filter::empty(),
             genesis_waypoint: None,
+            randomness_seed_sidecar_path: None,
         }
     }
 }
@@ -176,6 +185,14 @@ impl ConfigSanitizer for ExecutionConfig {
             }
         }
 
+        // Ensure that the speculative write budget (if set) is non-zero
+        if execution_config.speculative_write_budget_bytes == Some(0) {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "speculative_write_budget_bytes must be non-zero (if set)!".into(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -429,6 +446,22 @@ mod test {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_speculative_write_budget() {
+        // Create a node config with a zero speculative write budget
+        let node_config = NodeConfig {
+            execution: ExecutionConfig {
+                speculative_write_budget_bytes: Some(0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error = ExecutionConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
     #[test]
     fn test_no_genesis() {
         let (mut config, path) = generate_config();
@@ -13,6 +13,10 @@ pub struct InternalIndexerDBConfig {
     pub enable_transaction: bool,
     pub enable_event: bool,
     pub enable_statekeys: bool,
+    /// Whether to index the storage usage (and storage deposits) of each account. The
+    /// usage is cumulative, so the index must be built from genesis (i.e., it can't be
+    /// enabled on an existing indexer, or on a node bootstrapped from a state snapshot).
+    pub enable_storage_usage: bool,
     pub batch_size: usize,
 }
 
@@ -21,12 +25,14 @@ impl InternalIndexerDBConfig {
         enable_transaction: bool,
         enable_event: bool,
         enable_statekeys: bool,
+        enable_storage_usage: bool,
         batch_size: usize,
     ) -> Self {
         Self {
             enable_transaction,
             enable_event,
             enable_statekeys,
+            enable_storage_usage,
             batch_size,
         }
     }
@@ -43,8 +49,15 @@ impl InternalIndexerDBConfig {
         self.enable_statekeys
     }
 
+    pub fn enable_storage_usage(&self) -> bool {
+        self.enable_storage_usage
+    }
+
     pub fn is_internal_indexer_db_enabled(&self) -> bool {
-        self.enable_transaction || self.enable_event || self.enable_statekeys
+        self.enable_transaction
+            || self.enable_event
+            || self.enable_statekeys
+            || self.enable_storage_usage
     }
 
     pub fn batch_size(&self) -> usize {
@@ -58,6 +71,7 @@ impl Default for InternalIndexerDBConfig {
             enable_transaction: false,
             enable_event: false,
             enable_statekeys: false,
+            enable_storage_usage: false,
             batch_size: 10_000,
         }
     }
@@ -34,10 +34,28 @@ pub struct LoggerConfig {
     pub enable_telemetry_flush: bool,
     /// Level for telemetry logging
     pub telemetry_level: Level,
+    /// Whether to periodically export aggregated connection metadata via telemetry
+    pub enable_telemetry_connection_metadata: bool,
+    /// How peer IDs are exposed in the connection metadata exported via telemetry
+    pub telemetry_peer_id_privacy: TelemetryPeerIdPrivacy,
     /// Tokio console port for local debugging
     pub tokio_console_port: Option<u16>,
 }
 
+/// How peer IDs are exposed in the data exported via telemetry
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TelemetryPeerIdPrivacy {
+    /// Peer IDs are not exported (only aggregated data is exported)
+    #[default]
+    Omit,
+    /// Peer IDs are exported as salted hashes, which can't be linked to the
+    /// real peer IDs (or across node restarts)
+    Anonymized,
+    /// Peer IDs are exported as is
+    Plaintext,
+}
+
 impl Default for LoggerConfig {
     fn default() -> LoggerConfig {
         LoggerConfig {
@@ -48,6 +66,8 @@ impl Default for LoggerConfig {
             enable_telemetry_remote_log: true,
             enable_telemetry_flush: true,
             telemetry_level: Level::Error,
+            enable_telemetry_connection_metadata: true,
+            telemetry_peer_id_privacy: TelemetryPeerIdPrivacy::default(),
 
             // This is the default port used by tokio-console.
             // Setting this to None will disable tokio-console
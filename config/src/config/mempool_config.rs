@@ -2,14 +2,21 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::config::{
-    config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
-    node_config_loader::NodeType, Error, NodeConfig, MAX_APPLICATION_MESSAGE_SIZE,
+use crate::{
+    config::{
+        config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
+        node_config_loader::NodeType, Error, NodeConfig, MAX_APPLICATION_MESSAGE_SIZE,
+    },
+    network_id::NetworkId,
 };
 use aptos_global_constants::DEFAULT_BUCKETS;
-use aptos_types::chain_id::ChainId;
+use aptos_types::{account_address::AccountAddress, chain_id::ChainId, PeerId};
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -37,6 +44,291 @@ impl Default for LoadBalancingThresholdConfig {
     }
 }
 
+/// The mode used to select the (primary) peers that transactions are broadcast to
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BroadcastPeerSelectionMode {
+    /// The peers with the highest priority are always selected
+    #[default]
+    Strict,
+    /// Peers are sampled (each time peer priorities are updated) with probability
+    /// proportional to their priority score. This spreads transaction dissemination
+    /// across peers, while still favoring the peers with higher priority.
+    WeightedRandom,
+}
+
+/// The strategy used to prioritize the peers that transactions are broadcast to
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerPrioritizationStrategy {
+    /// Peers are prioritized by network ID (and ties are broken randomly)
+    Simple,
+    /// Peers are prioritized by network ID, distance from the validators, mempool
+    /// fullness, ping latency and broadcast ACK latency
+    Intelligent,
+}
+
+impl PeerPrioritizationStrategy {
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            PeerPrioritizationStrategy::Simple => "simple",
+            PeerPrioritizationStrategy::Intelligent => "intelligent",
+        }
+    }
+}
+
+/// Quotas enforced on the transactions submitted by each authenticated API client.
+/// These are independent of (and in addition to) any per-IP limits.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientQuotaConfig {
+    /// Maximum number of transaction submissions per second (per client)
+    pub max_submissions_per_sec: u64,
+    /// Maximum number of transactions (per client) that can be in mempool at any point
+    pub max_pending_transactions: usize,
+}
+
+impl Default for ClientQuotaConfig {
+    fn default() -> ClientQuotaConfig {
+        ClientQuotaConfig {
+            max_submissions_per_sec: 100,
+            max_pending_transactions: 10_000,
+        }
+    }
+}
+
+/// Gas-price QoS lanes used to compose broadcast batches. Transactions are split into a
+/// high-fee lane and a normal lane, and each broadcast batch is composed from both lanes
+/// (according to the configured ratio), with the high-fee transactions sent first.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BroadcastLanesConfig {
+    /// Transactions with a gas unit price (i.e., ranking score) at or above this threshold
+    /// are in the high-fee lane. This must be one of the (non-zero) broadcast buckets.
+    pub high_fee_threshold: u64,
+    /// The percentage of each broadcast batch given to the high-fee lane. The rest of the
+    /// batch is given to the normal lane. If a lane does not have enough transactions to
+    /// fill its share, the remainder of the batch is filled from the other lane.
+    pub high_fee_batch_percent: u64,
+}
+
+impl Default for BroadcastLanesConfig {
+    fn default() -> BroadcastLanesConfig {
+        BroadcastLanesConfig {
+            high_fee_threshold: 300,
+            high_fee_batch_percent: 75,
+        }
+    }
+}
+
+/// Broadcast deduplication using transaction hash filters. Peers periodically send a
+/// compact (bloom) filter of the transactions they hold to the peers broadcasting to
+/// them. Transactions matching a peer's filter are skipped when broadcasting to that peer,
+/// and are re-checked against the peer's next filter (so false positives are still broadcast).
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BroadcastFilterConfig {
+    /// The interval (ms) at which the local filter is sent to the peers broadcasting to us
+    pub filter_update_interval_ms: u64,
+    /// The maximum age (ms) of a filter received from a peer. Older filters are ignored.
+    pub max_filter_age_ms: u64,
+    /// The number of filter bits per transaction (this determines the false positive rate)
+    pub bits_per_transaction: u64,
+    /// The maximum number of transactions held in the local filter
+    pub max_filter_transactions: usize,
+}
+
+impl Default for BroadcastFilterConfig {
+    fn default() -> BroadcastFilterConfig {
+        BroadcastFilterConfig {
+            filter_update_interval_ms: 5_000,
+            max_filter_age_ms: 15_000,
+            bits_per_transaction: 10,
+            max_filter_transactions: 100_000,
+        }
+    }
+}
+
+/// Scoring of the transactions received from each peer via shared mempool. Peers that send
+/// a high percentage of invalid or expired transactions are penalized, i.e., they are pushed
+/// to the bottom of the prioritized peers list. Already committed transactions (e.g., due to
+/// commit races) are not counted.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerMisbehaviorScoringConfig {
+    /// The number of transactions received from a peer in each scoring window. The peer
+    /// is (re-)scored each time a window completes.
+    pub transactions_per_scoring_window: u64,
+    /// The percentage of invalid or expired transactions (in a scoring window) at
+    /// which a peer is penalized. A penalized peer is forgiven once it completes a window
+    /// below the threshold.
+    pub misbehavior_threshold_percent: u64,
+    /// Whether to request a disconnect from peers when they are penalized
+    pub disconnect_penalized_peers: bool,
+}
+
+impl Default for PeerMisbehaviorScoringConfig {
+    fn default() -> PeerMisbehaviorScoringConfig {
+        PeerMisbehaviorScoringConfig {
+            transactions_per_scoring_window: 100,
+            misbehavior_threshold_percent: 50,
+            disconnect_penalized_peers: false,
+        }
+    }
+}
+
+/// Backpressure on transaction submissions (e.g., via the REST API). Mempool periodically
+/// publishes its load state, and submissions are rejected (with a suggested retry delay)
+/// while mempool is full, instead of accepting transactions that are likely to be evicted.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubmissionBackpressureConfig {
+    /// The interval (ms) at which the mempool load state is updated
+    pub load_update_interval_ms: u64,
+    /// The mempool utilization (as a percentage of capacity) at which mempool is degraded
+    pub degraded_utilization_percent: u64,
+    /// The mempool utilization (as a percentage of capacity) at which mempool is full,
+    /// and submissions are rejected
+    pub full_utilization_percent: u64,
+    /// The maximum retry delay (secs) suggested to clients whose submissions are rejected
+    pub max_retry_after_secs: u64,
+}
+
+impl Default for SubmissionBackpressureConfig {
+    fn default() -> SubmissionBackpressureConfig {
+        SubmissionBackpressureConfig {
+            load_update_interval_ms: 1_000,
+            degraded_utilization_percent: 75,
+            full_utilization_percent: 95,
+            max_retry_after_secs: 30,
+        }
+    }
+}
+
+/// The broadcast fanout (i.e., the number of top prioritized peers) on a single network
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkFanoutConfig {
+    /// The network of the peers
+    pub network_id: NetworkId,
+    /// The maximum number of top prioritized peers on the network
+    pub fanout: usize,
+}
+
+/// Adaptive (per peer) sizing of broadcast batches. Each peer's batch size is adjusted
+/// AIMD-style: it grows additively while the peer ACKs broadcasts quickly, and shrinks
+/// multiplicatively when ACKs are slow, broadcasts fail (or time out), or the peer asks
+/// us to retry or backoff. This gives slow peers smaller batches, and fast peers larger ones.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdaptiveBatchSizingConfig {
+    /// The minimum number of transactions in a broadcast batch
+    pub min_batch_size: usize,
+    /// The maximum number of transactions in a broadcast batch
+    pub max_batch_size: usize,
+    /// The number of transactions added to the batch size after each fast ACK
+    pub additive_increase: usize,
+    /// The percentage by which the batch size is reduced after a slow ACK or failure
+    pub multiplicative_decrease_percent: u64,
+    /// The ACK latency (ms) above which an ACK is considered slow
+    pub target_ack_latency_ms: u64,
+}
+
+impl Default for AdaptiveBatchSizingConfig {
+    fn default() -> AdaptiveBatchSizingConfig {
+        AdaptiveBatchSizingConfig {
+            min_batch_size: 25,
+            max_batch_size: 1_000,
+            additive_increase: 25,
+            multiplicative_decrease_percent: 50,
+            target_ack_latency_ms: 500,
+        }
+    }
+}
+
+/// Overrides of the system TTL (i.e., the time after which transactions are evicted from
+/// mempool) for specific transactions. For example, zero gas price transactions can be
+/// given a shorter TTL, and the operator's own accounts can be given a longer TTL. The
+/// policy only applies to transactions inserted after it is set, and can be adjusted at
+/// runtime (e.g., via the admin service).
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EvictionPolicyConfig {
+    /// If set, the system TTL (secs) of transactions with a gas unit price of zero
+    pub zero_gas_price_ttl_secs: Option<u64>,
+    /// The system TTL (secs) of the transactions of specific senders (e.g., allow-listed
+    /// accounts). These take precedence over all other TTLs.
+    pub sender_ttl_secs: BTreeMap<AccountAddress, u64>,
+}
+
+impl EvictionPolicyConfig {
+    /// Verifies that all TTLs in the policy are non-zero
+    pub fn verify(&self) -> Result<(), String> {
+        if self.zero_gas_price_ttl_secs == Some(0) {
+            return Err("The zero gas price TTL must be non-zero!".into());
+        }
+        if let Some((sender, _)) = self.sender_ttl_secs.iter().find(|(_, ttl)| **ttl == 0) {
+            return Err(format!("The TTL of sender {} must be non-zero!", sender));
+        }
+        Ok(())
+    }
+}
+
+/// The peer prioritization parameters that can be adjusted at runtime (e.g., via the
+/// admin service), without restarting the node. New parameters are picked up on the
+/// next peer priority update. See the corresponding fields in `MempoolConfig`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeerPrioritizationParams {
+    /// The interval to update peer priorities (seconds)
+    pub shared_mempool_priority_update_interval_secs: u64,
+    /// The number of top prioritized peers that transactions are broadcast to (if set)
+    pub broadcast_fanout: Option<usize>,
+    /// The number of top prioritized peers on each network that transactions are broadcast to
+    #[serde(default)]
+    pub broadcast_fanout_per_network: Vec<NetworkFanoutConfig>,
+    /// The strategy used to prioritize the peers that transactions are broadcast to
+    pub prioritization_strategy: PeerPrioritizationStrategy,
+}
+
+impl PeerPrioritizationParams {
+    /// Verifies that the update interval and broadcast fanouts are valid
+    pub fn verify(&self) -> Result<(), String> {
+        if self.shared_mempool_priority_update_interval_secs == 0 {
+            return Err("The priority update interval must be greater than 0!".into());
+        }
+
+        // Verify that the broadcast fanout (if set) is valid
+        if self.broadcast_fanout == Some(0) {
+            return Err("The broadcast fanout must be greater than 0!".into());
+        }
+
+        // Verify that the per network broadcast fanouts (if any) are valid
+        let broadcast_fanout_per_network = &self.broadcast_fanout_per_network;
+        if !broadcast_fanout_per_network.is_empty() {
+            let mut network_ids = HashSet::new();
+            for network_fanout in broadcast_fanout_per_network {
+                if !network_ids.insert(network_fanout.network_id) {
+                    return Err(format!(
+                        "The broadcast fanout of network {} is specified more than once!",
+                        network_fanout.network_id
+                    ));
+                }
+            }
+            if broadcast_fanout_per_network
+                .iter()
+                .all(|network_fanout| network_fanout.fanout == 0)
+            {
+                return Err(
+                    "The broadcast fanout of at least one network must be greater than 0!".into(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct MempoolConfig {
@@ -50,6 +342,44 @@ pub struct MempoolConfig {
     pub default_failovers: usize,
     /// Whether or not to enable intelligent peer prioritization
     pub enable_intelligent_peer_prioritization: bool,
+    /// The mode used to select the peers that transactions are broadcast to
+    pub broadcast_peer_selection_mode: BroadcastPeerSelectionMode,
+    /// The alternative prioritization strategies to evaluate in shadow mode. On every priority
+    /// update, their rankings are computed, logged and compared against the active ranking (via
+    /// metrics), but they are never used for broadcasting. This allows a new strategy to be
+    /// validated on live traffic before switching to it.
+    pub shadow_prioritization_strategies: Vec<PeerPrioritizationStrategy>,
+    /// When intelligently prioritizing peers, ping latencies are grouped into buckets of this
+    /// size (in ms). Peers in the same bucket are considered equal, and are further compared
+    /// by their broadcast ACK latencies. This deprioritizes peers with fast pings but slow
+    /// (e.g., overloaded) mempools. If 0, ping latencies are compared directly.
+    pub peer_ping_latency_slack_ms: u64,
+    /// Whether to favor network diversity when prioritizing peers. If enabled, peers in the
+    /// same network group (i.e., the same /24 subnet, or the same rack for DNS addresses)
+    /// as a higher priority peer are deprioritized, so that broadcasts are not all sent
+    /// to a single datacenter.
+    pub enable_peer_network_diversity: bool,
+    /// The peers (e.g., the validator of a VFN) that are always prioritized above all
+    /// other peers (in the given order), regardless of their measured latencies or
+    /// distances. The remaining peers are prioritized (as usual) below the pinned peers.
+    pub pinned_upstream_peers: Vec<PeerId>,
+    /// When intelligently prioritizing peers, upstream peers that report a mempool utilization
+    /// (as a percentage of their capacity) at or above this threshold are deprioritized. This
+    /// requires mempool monitoring to be enabled in the peer monitoring service.
+    pub upstream_mempool_full_threshold_percent: u64,
+    /// If set, transactions are broadcast to (at most) the top `broadcast_fanout` prioritized
+    /// peers, overriding the number of peers derived from the load balancing thresholds.
+    pub broadcast_fanout: Option<usize>,
+    /// If set, transactions are broadcast to (at most) the given number of top prioritized
+    /// peers on each network (e.g., 1 VFN peer and 4 public peers), instead of the top peers
+    /// across all networks. Networks without a fanout have no top peers (if there are no peers
+    /// on the networks with a fanout, transactions are only broadcast to failover peers). This
+    /// overrides the `broadcast_fanout`. Note: each top peer is assigned at least one sender
+    /// bucket, so the total fanout must not exceed `num_sender_buckets`.
+    pub broadcast_fanout_per_network: Vec<NetworkFanoutConfig>,
+    /// The number of consecutive failed or timed out broadcasts to a top peer after which
+    /// the peer is replaced by the next prioritized peer (until it acknowledges a broadcast).
+    pub broadcast_failover_threshold: u32,
     /// The maximum number of broadcasts sent to a single peer that are pending a response ACK at any point.
     pub max_broadcasts_per_peer: usize,
     /// Maximum number of inbound network messages to the Mempool application
@@ -62,8 +392,15 @@ pub struct MempoolConfig {
     pub shared_mempool_backoff_interval_ms: u64,
     /// Maximum number of transactions to batch for a Mempool submission to an upstream node.
     pub shared_mempool_batch_size: usize,
+    /// If set, the broadcast batch size is adapted per peer (based on the observed ACK
+    /// latencies and failures), and `shared_mempool_batch_size` is only the initial size.
+    pub adaptive_batch_sizing: Option<AdaptiveBatchSizingConfig>,
     /// Maximum number of bytes to batch for a Mempool submission to an upstream node.
     pub shared_mempool_max_batch_bytes: u64,
+    /// The memory budget (in bytes) of the cache of serialized broadcast batches. The cache
+    /// allows retries and broadcasts of the same batch to multiple peers to reuse the same
+    /// serialization. A value of 0 disables the cache.
+    pub broadcast_serialization_cache_bytes: usize,
     /// Maximum Mempool inbound message workers.  Controls concurrency of Mempool consumption.
     pub shared_mempool_max_concurrent_inbound_syncs: usize,
     /// Interval to broadcast to upstream nodes.
@@ -72,6 +409,11 @@ pub struct MempoolConfig {
     pub shared_mempool_peer_update_interval_ms: u64,
     /// Interval to update peer priorities in shared mempool (seconds).
     pub shared_mempool_priority_update_interval_secs: u64,
+    /// If set, peer priorities are also updated (ahead of the priority update interval)
+    /// when the monitoring metadata of a peer changes materially, i.e., its distance from
+    /// the validators changes, or its ping latency changes by at least this percentage.
+    /// Such updates happen at most once per peer update interval.
+    pub priority_update_latency_change_percent: Option<u64>,
     /// The amount of time to wait after transaction insertion to broadcast to a failover peer.
     pub shared_mempool_failover_delay_ms: u64,
     /// Number of seconds until the transaction will be removed from the Mempool ignoring if the transaction has expired.
@@ -94,12 +436,64 @@ pub struct MempoolConfig {
     /// We divide the transactions into buckets based on hash of the sender address.
     /// This is the number of sender buckets we use.
     pub num_sender_buckets: u8,
+    /// The number of independent partitions (each with its own lock and indices) that the
+    /// core mempool is split into. Each shard owns a disjoint set of sender buckets, so this
+    /// must not exceed `num_sender_buckets`. The mempool capacity is divided evenly across shards.
+    pub num_mempool_shards: u8,
+    /// The number of recently rejected transactions (per mempool shard) for which the
+    /// rejection reason is retained, so that it can be reported for transactions that
+    /// were removed from mempool without ever being committed.
+    pub max_rejected_transactions_to_retain: usize,
+    /// Urgency bands (in seconds until transaction expiration, in increasing order) used to
+    /// prioritize broadcasts. Transactions closer to expiration (i.e., in earlier bands) are
+    /// broadcast and rebroadcast first, and those beyond the last band are broadcast last.
+    /// An empty list disables expiration-based broadcast prioritization.
+    pub broadcast_urgency_bands_secs: Vec<u64>,
+    /// If set, broadcast batches are composed from separate high-fee and normal-fee lanes,
+    /// so that high-fee transactions propagate ahead of other transactions during congestion
+    /// (without starving the normal-fee transactions).
+    pub broadcast_lanes: Option<BroadcastLanesConfig>,
+    /// If set, peers exchange filters of the transactions they hold, and transactions
+    /// already held by a peer are not broadcast to it. All peers must support the filter
+    /// messages before this is enabled.
+    pub broadcast_filter: Option<BroadcastFilterConfig>,
+    /// If set, broadcasts sent by this node request a delivery receipt in the ACK (i.e.,
+    /// the number of broadcast transactions that were new to the recipient, and the number
+    /// that were duplicates). Recipients only send receipts when they are requested, but
+    /// all peers must support the receipt messages before this is enabled.
+    pub enable_broadcast_delivery_receipts: bool,
+    /// If set, peers are scored by the transactions they send us, and misbehaving peers
+    /// (e.g., those spamming invalid transactions) are deprioritized.
+    pub peer_misbehavior_scoring: Option<PeerMisbehaviorScoringConfig>,
     /// Load balancing configuration for the mempool. This is used only by PFNs.
     pub load_balancing_thresholds: Vec<LoadBalancingThresholdConfig>,
     /// When the load is low, PFNs send all the mempool traffic to only one upstream FN. When the load increases suddenly, PFNs will take
     /// up to 10 minutes (shared_mempool_priority_update_interval_secs) to enable the load balancing. If this flag is enabled,
     /// then the PFNs will always do load balancing irrespective of the load.
     pub enable_max_load_balancing_at_any_load: bool,
+    /// Quotas for transactions submitted by authenticated API clients
+    pub client_quotas: ClientQuotaConfig,
+    /// If set, all transactions accepted into mempool are journaled to this file, so that
+    /// pending transactions survive a node restart. On startup, the journaled transactions
+    /// are reloaded and re-validated (and those that are no longer valid are dropped).
+    pub transaction_journal_path: Option<PathBuf>,
+    /// If set, the prioritized peers (and the latencies observed for them) are persisted to
+    /// this file, so that a restarting node can immediately broadcast to known-good peers
+    /// (instead of waiting for the monitoring metadata of each peer to be observed again).
+    pub prioritized_peers_snapshot_path: Option<PathBuf>,
+    /// The maximum age (in seconds) of a prioritized peers snapshot for it to be restored
+    pub prioritized_peers_snapshot_max_age_secs: u64,
+    /// The minimum interval (in seconds) between saves of the prioritized peers snapshot
+    pub prioritized_peers_snapshot_interval_secs: u64,
+    /// If set, transaction submissions are rejected while mempool is full
+    pub submission_backpressure: Option<SubmissionBackpressureConfig>,
+    /// The system TTL overrides for specific transactions (e.g., per sender)
+    pub eviction_policy: EvictionPolicyConfig,
+    /// An alternative eviction policy to evaluate in shadow mode. On every garbage
+    /// collection, the transactions it would have evicted (or kept) differently from the
+    /// active policy are logged and counted (via metrics), but they are never evicted by it.
+    /// Only the expired transactions are visited (via an additional TTL index in mempool).
+    pub shadow_eviction_policy: Option<EvictionPolicyConfig>,
 }
 
 impl Default for MempoolConfig {
@@ -108,7 +502,9 @@ impl Default for MempoolConfig {
             shared_mempool_tick_interval_ms: 10,
             shared_mempool_backoff_interval_ms: 30_000,
             shared_mempool_batch_size: 300,
+            adaptive_batch_sizing: None,
             shared_mempool_max_batch_bytes: MAX_APPLICATION_MESSAGE_SIZE as u64,
+            broadcast_serialization_cache_bytes: 32 * 1024 * 1024, // 32 MiB
             shared_mempool_ack_timeout_ms: 2_000,
             shared_mempool_max_concurrent_inbound_syncs: 4,
             max_broadcasts_per_peer: 20,
@@ -119,8 +515,18 @@ impl Default for MempoolConfig {
             capacity_per_user: 100,
             default_failovers: 1,
             enable_intelligent_peer_prioritization: true,
+            broadcast_peer_selection_mode: BroadcastPeerSelectionMode::Strict,
+            shadow_prioritization_strategies: vec![],
+            peer_ping_latency_slack_ms: 0,
+            enable_peer_network_diversity: false,
+            pinned_upstream_peers: vec![],
+            upstream_mempool_full_threshold_percent: 90,
+            broadcast_fanout: None,
+            broadcast_fanout_per_network: vec![],
+            broadcast_failover_threshold: 3,
             shared_mempool_peer_update_interval_ms: 1_000,
             shared_mempool_priority_update_interval_secs: 600, // 10 minutes (frequent reprioritization is expensive)
+            priority_update_latency_change_percent: Some(50),
             shared_mempool_failover_delay_ms: 500,
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
@@ -131,6 +537,13 @@ impl Default for MempoolConfig {
             usecase_stats_num_blocks_to_track: 40,
             usecase_stats_num_top_to_track: 5,
             num_sender_buckets: 4,
+            num_mempool_shards: 1,
+            max_rejected_transactions_to_retain: 10_000,
+            broadcast_urgency_bands_secs: vec![],
+            broadcast_lanes: None,
+            broadcast_filter: None,
+            enable_broadcast_delivery_receipts: false,
+            peer_misbehavior_scoring: None,
             load_balancing_thresholds: vec![
                 LoadBalancingThresholdConfig {
                     avg_mempool_traffic_threshold_in_tps: 500,
@@ -164,17 +577,259 @@ impl Default for MempoolConfig {
                 },
             ],
             enable_max_load_balancing_at_any_load: false,
+            client_quotas: ClientQuotaConfig::default(),
+            transaction_journal_path: None,
+            prioritized_peers_snapshot_path: None,
+            prioritized_peers_snapshot_max_age_secs: 3_600, // 1 hour
+            prioritized_peers_snapshot_interval_secs: 300,  // 5 minutes
+            submission_backpressure: None,
+            eviction_policy: EvictionPolicyConfig::default(),
+            shadow_eviction_policy: None,
+        }
+    }
+}
+
+impl MempoolConfig {
+    /// Returns the peer prioritization parameters of the config
+    pub fn get_peer_prioritization_params(&self) -> PeerPrioritizationParams {
+        let prioritization_strategy = if self.enable_intelligent_peer_prioritization {
+            PeerPrioritizationStrategy::Intelligent
+        } else {
+            PeerPrioritizationStrategy::Simple
+        };
+        PeerPrioritizationParams {
+            shared_mempool_priority_update_interval_secs: self
+                .shared_mempool_priority_update_interval_secs,
+            broadcast_fanout: self.broadcast_fanout,
+            broadcast_fanout_per_network: self.broadcast_fanout_per_network.clone(),
+            prioritization_strategy,
         }
     }
+
+    /// Overrides the peer prioritization parameters of the config
+    pub fn set_peer_prioritization_params(&mut self, params: PeerPrioritizationParams) {
+        self.shared_mempool_priority_update_interval_secs =
+            params.shared_mempool_priority_update_interval_secs;
+        self.broadcast_fanout = params.broadcast_fanout;
+        self.broadcast_fanout_per_network = params.broadcast_fanout_per_network;
+        self.enable_intelligent_peer_prioritization =
+            params.prioritization_strategy == PeerPrioritizationStrategy::Intelligent;
+    }
 }
 
 impl ConfigSanitizer for MempoolConfig {
     fn sanitize(
-        _node_config: &NodeConfig,
+        node_config: &NodeConfig,
         _node_type: NodeType,
         _chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
-        Ok(()) // TODO: add reasonable verifications
+        let sanitizer_name = Self::get_sanitizer_name();
+        let mempool_config = &node_config.mempool;
+
+        // Verify that each mempool shard owns at least one sender bucket
+        let num_mempool_shards = mempool_config.num_mempool_shards;
+        if num_mempool_shards == 0 || num_mempool_shards > mempool_config.num_sender_buckets {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The number of mempool shards ({}) must be between 1 and the number of sender buckets ({})!",
+                    num_mempool_shards, mempool_config.num_sender_buckets
+                ),
+            ));
+        }
+
+        // Verify that the peer prioritization parameters (e.g., broadcast fanouts) are valid
+        if let Err(error) = mempool_config.get_peer_prioritization_params().verify() {
+            return Err(Error::ConfigSanitizerFailed(sanitizer_name, error));
+        }
+
+        // Verify that each top peer (across all per network fanouts) owns a sender bucket
+        let total_fanout: usize = mempool_config
+            .broadcast_fanout_per_network
+            .iter()
+            .map(|network_fanout| network_fanout.fanout)
+            .sum();
+        if total_fanout > mempool_config.num_sender_buckets as usize {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The total broadcast fanout across networks ({}) must not exceed the number of sender buckets ({})!",
+                    total_fanout, mempool_config.num_sender_buckets
+                ),
+            ));
+        }
+
+        // Verify that the client quotas allow submissions
+        let client_quotas = &mempool_config.client_quotas;
+        if client_quotas.max_submissions_per_sec == 0 || client_quotas.max_pending_transactions == 0
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The client quotas must be greater than 0! Found: {:?}",
+                    client_quotas
+                ),
+            ));
+        }
+
+        // Verify that the upstream mempool full threshold is a valid percentage
+        let full_threshold_percent = mempool_config.upstream_mempool_full_threshold_percent;
+        if full_threshold_percent == 0 || full_threshold_percent > 100 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The upstream mempool full threshold must be between 1 and 100! Found: {}",
+                    full_threshold_percent
+                ),
+            ));
+        }
+
+        // Verify that the broadcast urgency bands are strictly increasing
+        let urgency_bands = &mempool_config.broadcast_urgency_bands_secs;
+        if urgency_bands.windows(2).any(|bands| bands[0] >= bands[1]) {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The broadcast urgency bands must be strictly increasing! Found: {:?}",
+                    urgency_bands
+                ),
+            ));
+        }
+
+        // Verify that the broadcast lanes (if set) align with the broadcast buckets
+        if let Some(broadcast_lanes) = &mempool_config.broadcast_lanes {
+            let high_fee_threshold = broadcast_lanes.high_fee_threshold;
+            if high_fee_threshold == 0
+                || !mempool_config
+                    .broadcast_buckets
+                    .contains(&high_fee_threshold)
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The high-fee lane threshold must be one of the non-zero broadcast buckets! Found: {}, buckets: {:?}",
+                        high_fee_threshold, mempool_config.broadcast_buckets
+                    ),
+                ));
+            }
+            if broadcast_lanes.high_fee_batch_percent > 100 {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The high-fee lane batch percentage must be at most 100! Found: {}",
+                        broadcast_lanes.high_fee_batch_percent
+                    ),
+                ));
+            }
+        }
+
+        // Verify that the broadcast filter config is valid
+        if let Some(broadcast_filter) = &mempool_config.broadcast_filter {
+            if broadcast_filter.filter_update_interval_ms == 0
+                || broadcast_filter.max_filter_age_ms < broadcast_filter.filter_update_interval_ms
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The broadcast filter update interval must be non-zero, and at most the max filter age! Found: {} and {}",
+                        broadcast_filter.filter_update_interval_ms, broadcast_filter.max_filter_age_ms
+                    ),
+                ));
+            }
+            if !(1..=64).contains(&broadcast_filter.bits_per_transaction) {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The broadcast filter bits per transaction must be between 1 and 64! Found: {}",
+                        broadcast_filter.bits_per_transaction
+                    ),
+                ));
+            }
+        }
+
+        // Verify that the peer misbehavior scoring config is valid
+        if let Some(peer_misbehavior_scoring) = &mempool_config.peer_misbehavior_scoring {
+            if peer_misbehavior_scoring.transactions_per_scoring_window == 0 {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "The number of transactions per peer scoring window must be non-zero!".into(),
+                ));
+            }
+            if !(1..=100).contains(&peer_misbehavior_scoring.misbehavior_threshold_percent) {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The peer misbehavior threshold percentage must be between 1 and 100! Found: {}",
+                        peer_misbehavior_scoring.misbehavior_threshold_percent
+                    ),
+                ));
+            }
+        }
+
+        // Verify that the adaptive batch sizing config is valid
+        if let Some(adaptive_batch_sizing) = &mempool_config.adaptive_batch_sizing {
+            if adaptive_batch_sizing.min_batch_size == 0
+                || adaptive_batch_sizing.min_batch_size > adaptive_batch_sizing.max_batch_size
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The minimum batch size must be non-zero and at most the maximum batch size! Found: {} and {}",
+                        adaptive_batch_sizing.min_batch_size,
+                        adaptive_batch_sizing.max_batch_size
+                    ),
+                ));
+            }
+            if !(1..=100).contains(&adaptive_batch_sizing.multiplicative_decrease_percent) {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The multiplicative decrease percentage must be between 1 and 100! Found: {}",
+                        adaptive_batch_sizing.multiplicative_decrease_percent
+                    ),
+                ));
+            }
+        }
+
+        // Verify that the submission backpressure config is valid
+        if let Some(submission_backpressure) = &mempool_config.submission_backpressure {
+            if submission_backpressure.load_update_interval_ms == 0 {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "The mempool load update interval must be non-zero!".into(),
+                ));
+            }
+            if submission_backpressure.degraded_utilization_percent
+                > submission_backpressure.full_utilization_percent
+                || submission_backpressure.full_utilization_percent > 100
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The degraded utilization percentage must be at most the full utilization percentage, which must be at most 100! Found: {} and {}",
+                        submission_backpressure.degraded_utilization_percent,
+                        submission_backpressure.full_utilization_percent
+                    ),
+                ));
+            }
+        }
+
+        // Verify that the eviction policy is valid
+        if let Err(error) = mempool_config.eviction_policy.verify() {
+            return Err(Error::ConfigSanitizerFailed(sanitizer_name, error));
+        }
+
+        // Verify that the shadow eviction policy (if any) is valid
+        if let Some(shadow_eviction_policy) = &mempool_config.shadow_eviction_policy {
+            if let Err(error) = shadow_eviction_policy.verify() {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!("Invalid shadow eviction policy: {}", error),
+                ));
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -201,9 +856,10 @@ impl ConfigOptimizer for MempoolConfig {
                 mempool_config.shared_mempool_batch_size = 200;
                 modified_config = true;
             }
-            // Set the number of sender buckets for load balancing to 1 (default is 4)
+            // Set the number of sender buckets for load balancing to 1 (default is 4),
+            // unless more buckets are required to back the mempool shards.
             if local_mempool_config_yaml["num_sender_buckets"].is_null() {
-                mempool_config.num_sender_buckets = 1;
+                mempool_config.num_sender_buckets = mempool_config.num_mempool_shards.max(1);
                 modified_config = true;
             }
         }
@@ -220,9 +876,10 @@ impl ConfigOptimizer for MempoolConfig {
                 modified_config = true;
             }
 
-            // Set the number of sender buckets for load balancing to 1 (default is 4)
+            // Set the number of sender buckets for load balancing to 1 (default is 4),
+            // unless more buckets are required to back the mempool shards.
             if local_mempool_config_yaml["num_sender_buckets"].is_null() {
-                mempool_config.num_sender_buckets = 1;
+                mempool_config.num_sender_buckets = mempool_config.num_mempool_shards.max(1);
                 modified_config = true;
             }
 
@@ -346,4 +1003,366 @@ mod tests {
             local_max_broadcasts_per_peer
         );
     }
+
+    #[test]
+    fn test_optimize_validator_config_with_shards() {
+        // Create the default validator config with multiple mempool shards
+        let num_mempool_shards = 8;
+        let mut node_config = NodeConfig::get_default_validator_config();
+        node_config.mempool.num_mempool_shards = num_mempool_shards;
+
+        // Optimize the config and verify modifications are made
+        let modified_config = MempoolConfig::optimize(
+            &mut node_config,
+            &serde_yaml::from_str("{}").unwrap(), // An empty local config,
+            NodeType::Validator,
+            Some(ChainId::mainnet()),
+        )
+        .unwrap();
+        assert!(modified_config);
+
+        // Verify that there are enough sender buckets to back the shards
+        assert_eq!(node_config.mempool.num_sender_buckets, num_mempool_shards);
+    }
+
+    #[test]
+    fn test_sanitize_num_mempool_shards() {
+        // Verify that sanitization fails with no mempool shards (or more shards than
+        // sender buckets), and passes with a valid number of shards.
+        for (num_sender_buckets, num_mempool_shards, is_valid) in
+            [(4, 0, false), (4, 5, false), (4, 4, true)]
+        {
+            let mempool_config = MempoolConfig {
+                num_sender_buckets,
+                num_mempool_shards,
+                ..Default::default()
+            };
+            verify_sanitize_mempool_config(mempool_config, is_valid);
+        }
+    }
+
+    #[test]
+    fn test_sanitize_broadcast_urgency_bands() {
+        // Verify that sanitization passes iff the urgency bands are increasing (or empty)
+        for (broadcast_urgency_bands_secs, is_valid) in [
+            (vec![], true),
+            (vec![30], true),
+            (vec![30, 120, 600], true),
+            (vec![30, 30], false),
+            (vec![120, 30], false),
+        ] {
+            let mempool_config = MempoolConfig {
+                broadcast_urgency_bands_secs,
+                ..Default::default()
+            };
+            verify_sanitize_mempool_config(mempool_config, is_valid);
+        }
+    }
+
+    #[test]
+    fn test_sanitize_broadcast_fanout_per_network() {
+        // Verify that sanitization passes with valid (or no) per network fanouts, and fails
+        // with duplicate networks, no fanout or a total fanout that exceeds the number of
+        // sender buckets (4, by default).
+        for (broadcast_fanout_per_network, is_valid) in [
+            (vec![], true),
+            (
+                vec![
+                    create_network_fanout(NetworkId::Vfn, 1),
+                    create_network_fanout(NetworkId::Public, 3),
+                ],
+                true,
+            ),
+            (
+                vec![
+                    create_network_fanout(NetworkId::Vfn, 0),
+                    create_network_fanout(NetworkId::Public, 1),
+                ],
+                true,
+            ),
+            (
+                vec![
+                    create_network_fanout(NetworkId::Public, 1),
+                    create_network_fanout(NetworkId::Public, 2),
+                ],
+                false,
+            ),
+            (vec![create_network_fanout(NetworkId::Public, 0)], false),
+            (
+                vec![
+                    create_network_fanout(NetworkId::Vfn, 2),
+                    create_network_fanout(NetworkId::Public, 3),
+                ],
+                false,
+            ),
+        ] {
+            let mempool_config = MempoolConfig {
+                broadcast_fanout_per_network,
+                ..Default::default()
+            };
+            verify_sanitize_mempool_config(mempool_config, is_valid);
+        }
+    }
+
+    #[test]
+    fn test_peer_prioritization_params() {
+        // Override the peer prioritization parameters of a config
+        let mut mempool_config = MempoolConfig::default();
+        let params = PeerPrioritizationParams {
+            shared_mempool_priority_update_interval_secs: 60,
+            broadcast_fanout: Some(2),
+            broadcast_fanout_per_network: vec![create_network_fanout(NetworkId::Public, 3)],
+            prioritization_strategy: PeerPrioritizationStrategy::Simple,
+        };
+        params.verify().unwrap();
+        mempool_config.set_peer_prioritization_params(params.clone());
+
+        // Verify the config was updated
+        assert_eq!(
+            mempool_config.shared_mempool_priority_update_interval_secs,
+            60
+        );
+        assert!(!mempool_config.enable_intelligent_peer_prioritization);
+        assert_eq!(mempool_config.get_peer_prioritization_params(), params);
+
+        // Verify that verification fails with a zero update interval or fanout
+        for params in [
+            PeerPrioritizationParams {
+                shared_mempool_priority_update_interval_secs: 0,
+                ..params.clone()
+            },
+            PeerPrioritizationParams {
+                broadcast_fanout: Some(0),
+                ..params.clone()
+            },
+        ] {
+            assert!(params.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_sanitize_upstream_mempool_full_threshold() {
+        // Verify that sanitization passes iff the threshold is a valid percentage
+        for (upstream_mempool_full_threshold_percent, is_valid) in
+            [(1, true), (90, true), (100, true), (0, false), (101, false)]
+        {
+            let mempool_config = MempoolConfig {
+                upstream_mempool_full_threshold_percent,
+                ..Default::default()
+            };
+            verify_sanitize_mempool_config(mempool_config, is_valid);
+        }
+    }
+
+    #[test]
+    fn test_sanitize_broadcast_lanes() {
+        // Verify that sanitization passes with valid (or no) broadcast lanes,
+        // and fails with invalid thresholds or batch percentages.
+        let create_broadcast_lanes = |high_fee_threshold, high_fee_batch_percent| {
+            Some(BroadcastLanesConfig {
+                high_fee_threshold,
+                high_fee_batch_percent,
+            })
+        };
+        for (broadcast_lanes, is_valid) in [
+            (None, true),
+            (Some(BroadcastLanesConfig::default()), true),
+            (create_broadcast_lanes(150, 100), true),
+            (create_broadcast_lanes(0, 75), false),
+            (create_broadcast_lanes(200, 75), false),
+            (create_broadcast_lanes(300, 101), false),
+        ] {
+            let mempool_config = MempoolConfig {
+                broadcast_lanes,
+                ..Default::default()
+            };
+            verify_sanitize_mempool_config(mempool_config, is_valid);
+        }
+    }
+
+    #[test]
+    fn test_sanitize_broadcast_filter() {
+        // Verify that sanitization passes with a valid (or no) broadcast filter, and
+        // fails with a zero interval, a short max age, or an invalid number of bits.
+        let create_broadcast_filter =
+            |filter_update_interval_ms, max_filter_age_ms, bits_per_transaction| {
+                Some(BroadcastFilterConfig {
+                    filter_update_interval_ms,
+                    max_filter_age_ms,
+                    bits_per_transaction,
+                    ..Default::default()
+                })
+            };
+        for (broadcast_filter, is_valid) in [
+            (None, true),
+            (Some(BroadcastFilterConfig::default()), true),
+            (create_broadcast_filter(0, 15_000, 10), false),
+            (create_broadcast_filter(5_000, 1_000, 10), false),
+            (create_broadcast_filter(5_000, 15_000, 0), false),
+            (create_broadcast_filter(5_000, 15_000, 65), false),
+        ] {
+            let mempool_config = MempoolConfig {
+                broadcast_filter,
+                ..Default::default()
+            };
+            verify_sanitize_mempool_config(mempool_config, is_valid);
+        }
+    }
+
+    #[test]
+    fn test_sanitize_peer_misbehavior_scoring() {
+        // Verify that sanitization passes with a valid (or no) scoring config, and
+        // fails with an invalid window or misbehavior threshold.
+        let create_scoring = |transactions_per_scoring_window, misbehavior_threshold_percent| {
+            Some(PeerMisbehaviorScoringConfig {
+                transactions_per_scoring_window,
+                misbehavior_threshold_percent,
+                ..Default::default()
+            })
+        };
+        for (peer_misbehavior_scoring, is_valid) in [
+            (None, true),
+            (Some(PeerMisbehaviorScoringConfig::default()), true),
+            (create_scoring(0, 50), false),
+            (create_scoring(100, 0), false),
+            (create_scoring(100, 101), false),
+        ] {
+            let mempool_config = MempoolConfig {
+                peer_misbehavior_scoring,
+                ..Default::default()
+            };
+            verify_sanitize_mempool_config(mempool_config, is_valid);
+        }
+    }
+
+    #[test]
+    fn test_sanitize_adaptive_batch_sizing() {
+        // Verify that sanitization passes with valid (or no) adaptive batch sizing, and
+        // fails with invalid batch sizes or decrease percentages.
+        let create_batch_sizing =
+            |min_batch_size, max_batch_size, multiplicative_decrease_percent| {
+                Some(AdaptiveBatchSizingConfig {
+                    min_batch_size,
+                    max_batch_size,
+                    multiplicative_decrease_percent,
+                    ..Default::default()
+                })
+            };
+        for (adaptive_batch_sizing, is_valid) in [
+            (None, true),
+            (Some(AdaptiveBatchSizingConfig::default()), true),
+            (create_batch_sizing(100, 100, 100), true),
+            (create_batch_sizing(0, 100, 50), false),
+            (create_batch_sizing(101, 100, 50), false),
+            (create_batch_sizing(10, 100, 0), false),
+            (create_batch_sizing(10, 100, 101), false),
+        ] {
+            let mempool_config = MempoolConfig {
+                adaptive_batch_sizing,
+                ..Default::default()
+            };
+            verify_sanitize_mempool_config(mempool_config, is_valid);
+        }
+    }
+
+    #[test]
+    fn test_sanitize_submission_backpressure() {
+        // Verify that sanitization passes with valid (or no) submission backpressure, and
+        // fails with a zero interval or invalid utilization percentages.
+        let create_backpressure =
+            |load_update_interval_ms, degraded_utilization_percent, full_utilization_percent| {
+                Some(SubmissionBackpressureConfig {
+                    load_update_interval_ms,
+                    degraded_utilization_percent,
+                    full_utilization_percent,
+                    ..Default::default()
+                })
+            };
+        for (submission_backpressure, is_valid) in [
+            (None, true),
+            (Some(SubmissionBackpressureConfig::default()), true),
+            (
+                Some(SubmissionBackpressureConfig {
+                    degraded_utilization_percent: 100,
+                    full_utilization_percent: 100,
+                    ..Default::default()
+                }),
+                true,
+            ),
+            (create_backpressure(0, 75, 95), false),
+            (create_backpressure(1_000, 96, 95), false),
+            (create_backpressure(1_000, 75, 101), false),
+        ] {
+            let mempool_config = MempoolConfig {
+                submission_backpressure,
+                ..Default::default()
+            };
+            verify_sanitize_mempool_config(mempool_config, is_valid);
+        }
+    }
+
+    #[test]
+    fn test_sanitize_eviction_policy() {
+        // Verify that sanitization passes with a valid eviction policy, and
+        // fails with zero TTLs (for both the active and shadow policies).
+        let sender = AccountAddress::random();
+        let valid_eviction_policy = EvictionPolicyConfig {
+            zero_gas_price_ttl_secs: Some(60),
+            sender_ttl_secs: BTreeMap::from([(sender, 3_600)]),
+        };
+        let zero_gas_price_ttl_policy = EvictionPolicyConfig {
+            zero_gas_price_ttl_secs: Some(0),
+            ..Default::default()
+        };
+        let zero_sender_ttl_policy = EvictionPolicyConfig {
+            sender_ttl_secs: BTreeMap::from([(sender, 0)]),
+            ..Default::default()
+        };
+        for (eviction_policy, shadow_eviction_policy, is_valid) in [
+            (valid_eviction_policy.clone(), None, true),
+            (
+                EvictionPolicyConfig::default(),
+                Some(valid_eviction_policy),
+                true,
+            ),
+            (zero_gas_price_ttl_policy.clone(), None, false),
+            (zero_sender_ttl_policy, None, false),
+            (
+                EvictionPolicyConfig::default(),
+                Some(zero_gas_price_ttl_policy),
+                false,
+            ),
+        ] {
+            let mempool_config = MempoolConfig {
+                eviction_policy,
+                shadow_eviction_policy,
+                ..Default::default()
+            };
+            verify_sanitize_mempool_config(mempool_config, is_valid);
+        }
+    }
+
+    /// Sanitizes a node config with the given mempool config, and verifies
+    /// that sanitization passes iff the mempool config is expected to be valid.
+    fn verify_sanitize_mempool_config(mempool_config: MempoolConfig, is_valid: bool) {
+        let node_config = NodeConfig {
+            mempool: mempool_config,
+            ..Default::default()
+        };
+        let result =
+            MempoolConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()));
+        if is_valid {
+            result.unwrap();
+        } else {
+            assert!(matches!(
+                result.unwrap_err(),
+                Error::ConfigSanitizerFailed(_, _)
+            ));
+        }
+    }
+
+    /// Creates a network fanout config for the given network
+    fn create_network_fanout(network_id: NetworkId, fanout: usize) -> NetworkFanoutConfig {
+        NetworkFanoutConfig { network_id, fanout }
+    }
 }
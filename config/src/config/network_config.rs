@@ -4,8 +4,10 @@
 
 use crate::{
     config::{
+        config_sanitizer::ConfigSanitizer,
         identity_config::{Identity, IdentityFromStorage},
-        Error, IdentityBlob,
+        node_config_loader::NodeType,
+        Error, IdentityBlob, NodeConfig,
     },
     network_id::NetworkId,
     utils,
@@ -14,7 +16,7 @@ use aptos_crypto::{x25519, Uniform};
 use aptos_secure_storage::{CryptoStorage, KVStorage, Storage};
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_types::{
-    account_address::from_identity_public_key, network_address::NetworkAddress,
+    account_address::from_identity_public_key, chain_id::ChainId, network_address::NetworkAddress,
     transaction::authenticator::AuthenticationKey, PeerId,
 };
 use rand::{
@@ -39,6 +41,7 @@ pub const NETWORK_CHANNEL_SIZE: usize = 1024;
 pub const PING_INTERVAL_MS: u64 = 10_000;
 pub const PING_TIMEOUT_MS: u64 = 20_000;
 pub const PING_FAILURES_TOLERATED: u64 = 3;
+pub const HALF_OPEN_CONNECTION_TIMEOUT_MS: u64 = 60_000; /* 1 minute */
 pub const CONNECTIVITY_CHECK_INTERVAL_MS: u64 = 5000;
 pub const MAX_CONNECTION_DELAY_MS: u64 = 60_000; /* 1 minute */
 pub const MAX_FULLNODE_OUTBOUND_CONNECTIONS: usize = 6;
@@ -52,6 +55,42 @@ pub const MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024; /* 64 MiB */
 pub const CONNECTION_BACKOFF_BASE: u64 = 2;
 pub const IP_BYTE_BUCKET_RATE: usize = 102400 /* 100 KiB */;
 pub const IP_BYTE_BUCKET_SIZE: usize = IP_BYTE_BUCKET_RATE;
+pub const PROTOCOL_BYTE_BUCKET_RATE: u64 = 10 * 1024 * 1024; /* 10 MiB */
+pub const PROTOCOL_BYTE_BUCKET_SIZE: u64 = MAX_MESSAGE_SIZE as u64; /* 64 MiB */
+
+/// The names of all network protocols (i.e., of each `ProtocolId`), used to validate the
+/// protocol names in the config. These must match the protocol IDs in the network crate.
+pub const NETWORK_PROTOCOL_NAMES: &[&str] = &[
+    "ConsensusRpcBcs",
+    "ConsensusDirectSendBcs",
+    "MempoolDirectSend",
+    "StateSyncDirectSend",
+    "DiscoveryDirectSend",
+    "HealthCheckerRpc",
+    "ConsensusDirectSendJson",
+    "ConsensusRpcJson",
+    "StorageServiceRpc",
+    "MempoolRpc",
+    "PeerMonitoringServiceRpc",
+    "ConsensusRpcCompressed",
+    "ConsensusDirectSendCompressed",
+    "NetbenchDirectSend",
+    "NetbenchRpc",
+    "DKGDirectSendCompressed",
+    "DKGDirectSendBcs",
+    "DKGDirectSendJson",
+    "DKGRpcCompressed",
+    "DKGRpcBcs",
+    "DKGRpcJson",
+    "JWKConsensusDirectSendCompressed",
+    "JWKConsensusDirectSendBcs",
+    "JWKConsensusDirectSendJson",
+    "JWKConsensusRpcCompressed",
+    "JWKConsensusRpcBcs",
+    "JWKConsensusRpcJson",
+    "ConsensusObserver",
+    "ConsensusObserverRpc",
+];
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -110,6 +149,10 @@ pub struct NetworkConfig {
     pub ping_timeout_ms: u64,
     /// Number of failed healthcheck pings until a peer is marked unhealthy
     pub ping_failures_tolerated: u64,
+    /// Time after which a connection is considered half-open (and is closed) if the
+    /// remote peer has sent nothing since our oldest unanswered RPC request (e.g., a
+    /// healthcheck ping). If not specified, half-open connections are not detected.
+    pub half_open_connection_timeout_ms: Option<u64>,
     /// Maximum number of outbound connections, limited by ConnectivityManager
     pub max_outbound_connections: usize,
     /// Maximum number of outbound connections, limited by PeerManager
@@ -118,6 +161,14 @@ pub struct NetworkConfig {
     pub inbound_rate_limit_config: Option<RateLimitConfig>,
     /// Outbound rate limiting configuration, if not specified, no rate limiting
     pub outbound_rate_limit_config: Option<RateLimitConfig>,
+    /// Per-protocol rate limits applied to each connection, keyed by protocol name
+    /// (e.g., "StorageServiceRpc"). Protocols without an entry are not rate limited.
+    pub protocol_rate_limits: HashMap<String, ProtocolRateLimitConfig>,
+    /// Protocols deprecated on this network, keyed by protocol name (e.g., "MempoolDirectSend")
+    /// and mapped to the epoch at which they are sunset. Deprecated protocols are still
+    /// accepted (and logged). Sunset protocols are no longer advertised in the handshake,
+    /// and RPC requests on them are answered with an error.
+    pub deprecated_protocols: HashMap<String, u64>,
     /// The maximum size of an inbound or outbound message (it may be divided into multiple frame)
     pub max_message_size: usize,
     /// The maximum number of parallel message deserialization tasks that can run (per application)
@@ -154,10 +205,13 @@ impl NetworkConfig {
             ping_interval_ms: PING_INTERVAL_MS,
             ping_timeout_ms: PING_TIMEOUT_MS,
             ping_failures_tolerated: PING_FAILURES_TOLERATED,
+            half_open_connection_timeout_ms: Some(HALF_OPEN_CONNECTION_TIMEOUT_MS),
             max_outbound_connections: MAX_FULLNODE_OUTBOUND_CONNECTIONS,
             max_inbound_connections: MAX_INBOUND_CONNECTIONS,
             inbound_rate_limit_config: None,
             outbound_rate_limit_config: None,
+            protocol_rate_limits: HashMap::new(),
+            deprecated_protocols: HashMap::new(),
             max_message_size: MAX_MESSAGE_SIZE,
             inbound_rx_buffer_size_bytes: None,
             inbound_tx_buffer_size_bytes: None,
@@ -388,6 +442,87 @@ impl Default for RateLimitConfig {
     }
 }
 
+/// A token bucket rate limit for a single protocol on a single connection
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProtocolRateLimitConfig {
+    /// Maximum number of bytes/s for the protocol
+    pub byte_bucket_rate: u64,
+    /// Maximum burst of bytes for the protocol
+    pub byte_bucket_size: u64,
+    /// Whether to rate limit inbound messages (i.e., from the remote peer)
+    pub limit_inbound: bool,
+    /// Whether to rate limit outbound messages (i.e., to the remote peer)
+    pub limit_outbound: bool,
+}
+
+impl Default for ProtocolRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            byte_bucket_rate: PROTOCOL_BYTE_BUCKET_RATE,
+            byte_bucket_size: PROTOCOL_BYTE_BUCKET_SIZE,
+            limit_inbound: true,
+            limit_outbound: false,
+        }
+    }
+}
+
+impl ConfigSanitizer for NetworkConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        _node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+
+        // Verify the protocol configs of each network
+        let network_configs = node_config
+            .validator_network
+            .iter()
+            .chain(node_config.full_node_networks.iter());
+        for network_config in network_configs {
+            let network_id = network_config.network_id;
+
+            // Verify that the rate limits are for known protocols, and can be satisfied
+            for (protocol_name, rate_limit_config) in &network_config.protocol_rate_limits {
+                if !NETWORK_PROTOCOL_NAMES.contains(&protocol_name.as_str()) {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        format!(
+                            "The protocol rate limits of the {} network contain an unknown protocol: {}",
+                            network_id, protocol_name
+                        ),
+                    ));
+                }
+                if rate_limit_config.byte_bucket_rate == 0 {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        format!(
+                            "The byte_bucket_rate of protocol {} on the {} network must be non-zero!",
+                            protocol_name, network_id
+                        ),
+                    ));
+                }
+            }
+
+            // Verify that the deprecated protocols are known protocols
+            for protocol_name in network_config.deprecated_protocols.keys() {
+                if !NETWORK_PROTOCOL_NAMES.contains(&protocol_name.as_str()) {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        format!(
+                            "The deprecated protocols of the {} network contain an unknown protocol: {}",
+                            network_id, protocol_name
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 pub type PeerSet = HashMap<PeerId, Peer>;
 
 // TODO: Combine with RoleType?
@@ -528,4 +663,72 @@ mod tests {
         network_config.configure_num_deserialization_tasks();
         assert_eq!(network_config.max_parallel_deserialization_tasks, Some(1));
     }
+
+    #[test]
+    fn test_sanitize_protocol_rate_limits() {
+        // Create a node config with a valid protocol rate limit
+        let mut node_config = create_node_config_with_network(NetworkConfig {
+            protocol_rate_limits: HashMap::from([(
+                "StorageServiceRpc".into(),
+                ProtocolRateLimitConfig::default(),
+            )]),
+            ..NetworkConfig::network_with_id(NetworkId::Public)
+        });
+
+        // Verify that the config is valid
+        NetworkConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap();
+
+        // Add a rate limit for an unknown protocol and verify that sanitization fails
+        let network_config = &mut node_config.full_node_networks[0];
+        network_config
+            .protocol_rate_limits
+            .insert("UnknownProtocol".into(), ProtocolRateLimitConfig::default());
+        let error =
+            NetworkConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Set a zero byte bucket rate and verify that sanitization fails
+        let network_config = &mut node_config.full_node_networks[0];
+        network_config
+            .protocol_rate_limits
+            .remove("UnknownProtocol");
+        network_config.protocol_rate_limits.insert(
+            "StorageServiceRpc".into(),
+            ProtocolRateLimitConfig {
+                byte_bucket_rate: 0,
+                ..Default::default()
+            },
+        );
+        let error =
+            NetworkConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_deprecated_protocols() {
+        // Create a node config with a known deprecated protocol
+        let mut node_config = create_node_config_with_network(NetworkConfig {
+            deprecated_protocols: HashMap::from([("MempoolDirectSend".into(), 10)]),
+            ..NetworkConfig::network_with_id(NetworkId::Public)
+        });
+
+        // Verify that the config is valid
+        NetworkConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap();
+
+        // Deprecate an unknown protocol and verify that sanitization fails
+        node_config.full_node_networks[0]
+            .deprecated_protocols
+            .insert("UnknownProtocol".into(), 10);
+        let error =
+            NetworkConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    /// Creates a node config with the given (fullnode) network config
+    fn create_node_config_with_network(network_config: NetworkConfig) -> NodeConfig {
+        NodeConfig {
+            full_node_networks: vec![network_config],
+            ..Default::default()
+        }
+    }
 }
@@ -4,7 +4,8 @@
 use super::{DagConsensusConfig, IndexerTableInfoConfig};
 use crate::{
     config::{
-        consensus_observer_config::ConsensusObserverConfig, dkg_config::DKGConfig,
+        consensus_observer_config::ConsensusObserverConfig,
+        crash_handler_config::CrashHandlerConfig, dkg_config::DKGConfig,
         internal_indexer_db_config::InternalIndexerDBConfig,
         jwk_consensus_config::JWKConsensusConfig, netbench_config::NetbenchConfig,
         node_config_loader::NodeConfigLoader, node_startup_config::NodeStartupConfig,
@@ -45,6 +46,8 @@ pub struct NodeConfig {
     #[serde(default)]
     pub consensus_observer: ConsensusObserverConfig,
     #[serde(default)]
+    pub crash_handler: CrashHandlerConfig,
+    #[serde(default)]
     pub dag_consensus: DagConsensusConfig,
     #[serde(default)]
     pub dkg: DKGConfig,
@@ -92,6 +95,13 @@ impl NodeConfig {
     /// Logs the node config using INFO level logging. This is useful for
     /// working around the length restrictions in the logger.
     pub fn log_all_configs(&self) {
+        for (config_name, config_string) in self.get_all_config_strings() {
+            info!("Using {} config: {}", config_name, config_string);
+        }
+    }
+
+    /// Returns each top-level config (by name) serialized as a JSON string
+    fn get_all_config_strings(&self) -> Vec<(String, String)> {
         // Parse the node config as serde JSON
         let config_value =
             serde_json::to_value(self).expect("Failed to serialize the node config!");
@@ -99,12 +109,15 @@ impl NodeConfig {
             .as_object()
             .expect("Failed to get the config map!");
 
-        // Log each config entry
-        for (config_name, config_value) in config_map {
-            let config_string =
-                serde_json::to_string(config_value).expect("Failed to parse the config value!");
-            info!("Using {} config: {}", config_name, config_string);
-        }
+        // Serialize each config entry
+        config_map
+            .iter()
+            .map(|(config_name, config_value)| {
+                let config_string =
+                    serde_json::to_string(config_value).expect("Failed to parse the config value!");
+                (config_name.clone(), config_string)
+            })
+            .collect()
     }
 
     /// Returns the data directory for this config
@@ -294,7 +307,7 @@ pub fn merge_node_config(
 
 #[cfg(test)]
 mod test {
-    use crate::config::{merge_node_config, Error, NodeConfig, SafetyRulesConfig};
+    use crate::config::{merge_node_config, ClientApiKey, Error, NodeConfig, SafetyRulesConfig};
 
     #[test]
     fn verify_config_defaults() {
@@ -307,6 +320,31 @@ mod test {
         SafetyRulesConfig::get_default_config();
     }
 
+    #[test]
+    fn verify_logged_configs_omit_client_api_keys() {
+        // Create a node config with a client API key
+        let api_key = "my-secret-api-key";
+        let mut node_config = NodeConfig::get_default_pfn_config();
+        node_config
+            .api
+            .client_api_keys
+            .push(ClientApiKey::new("client".into(), api_key));
+
+        // Verify that the logged configs identify the client but don't contain the key
+        let api_config_string = node_config
+            .get_all_config_strings()
+            .into_iter()
+            .find(|(config_name, _)| config_name == "api")
+            .map(|(_, config_string)| config_string)
+            .unwrap();
+        assert!(api_config_string.contains("client"));
+        assert!(!api_config_string.contains(api_key));
+        assert!(node_config
+            .get_all_config_strings()
+            .iter()
+            .all(|(_, config_string)| !config_string.contains(api_key)));
+    }
+
     #[test]
     fn verify_merge_node_config() {
         let node_config = NodeConfig::get_default_pfn_config();
@@ -8,6 +8,31 @@ use serde::{Deserialize, Serialize};
 pub struct NodeStartupConfig {
     pub skip_config_optimizer: bool, // Whether or not to skip the config optimizer at startup
     pub skip_config_sanitizer: bool, // Whether or not to skip the config sanitizer at startup
+    /// The number of CPUs to size runtimes and thread pools for. If not set, this
+    /// is detected from the host cores and the cgroup CPU quota (if any).
+    pub num_available_cpus_override: Option<usize>,
+}
+
+impl NodeStartupConfig {
+    /// Returns the number of CPUs to size runtimes and thread pools for
+    pub fn num_available_cpus(&self) -> usize {
+        self.num_available_cpus_override
+            .filter(|num_cpus| *num_cpus > 0)
+            .unwrap_or_else(aptos_runtimes::num_available_cpus)
+    }
+
+    /// Returns the number of CPUs the node is limited to, i.e., if the number
+    /// has been overridden or a cgroup CPU quota is set. Otherwise, returns None.
+    pub fn cpu_limit(&self) -> Option<usize> {
+        let has_override = self
+            .num_available_cpus_override
+            .is_some_and(|num_cpus| num_cpus > 0);
+        if has_override || aptos_runtimes::detect_cgroup_cpu_quota().is_some() {
+            Some(self.num_available_cpus())
+        } else {
+            None
+        }
+    }
 }
 
 #[allow(clippy::derivable_impls)] // Derive default manually (this is safer than guessing defaults)
@@ -16,6 +41,7 @@ impl Default for NodeStartupConfig {
         Self {
             skip_config_optimizer: false,
             skip_config_sanitizer: false,
+            num_available_cpus_override: None,
         }
     }
 }
@@ -32,5 +58,28 @@ mod test {
         // Verify both fields are set to false
         assert!(!config.skip_config_optimizer);
         assert!(!config.skip_config_sanitizer);
+
+        // Verify the number of CPUs is detected
+        assert!(config.num_available_cpus_override.is_none());
+        assert!(config.num_available_cpus() > 0);
+
+        // Verify the CPU limit is only set if there's a cgroup CPU quota
+        assert_eq!(
+            config.cpu_limit().is_some(),
+            aptos_runtimes::detect_cgroup_cpu_quota().is_some()
+        );
+    }
+
+    #[test]
+    fn test_node_startup_config_num_cpus_override() {
+        // Create a config with a CPU override
+        let config = NodeStartupConfig {
+            num_available_cpus_override: Some(6),
+            ..Default::default()
+        };
+
+        // Verify the override is used
+        assert_eq!(config.num_available_cpus(), 6);
+        assert_eq!(config.cpu_limit(), Some(6));
     }
 }
@@ -1,17 +1,29 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{
+    config::{config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig},
+    network_id::NetworkId,
+};
+use aptos_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct PeerMonitoringServiceConfig {
+    pub alerting: AlertingConfig, // The alerting rules evaluated by the monitoring client
     pub enable_peer_monitoring_client: bool, // Whether or not to spawn the monitoring client
+    pub health_check_rate_limit_interval_ms: u64, // Min interval (ms) between health checks served per peer
     pub latency_monitoring: LatencyMonitoringConfig,
     pub max_concurrent_requests: u64, // Max num of concurrent server tasks
-    pub max_network_channel_size: u64, // Max num of pending network messages
-    pub max_num_response_bytes: u64,  // Max num of bytes in a (serialized) response
+    pub max_health_check_response_bytes: u64, // Max num of bytes in a (serialized) health check response
+    pub max_network_channel_size: u64,        // Max num of pending network messages
+    pub max_network_info_staleness_ms: u64,   // Max age (ms) of cached network info responses
+    pub max_node_info_staleness_ms: u64,      // Max age (ms) of cached node info responses
+    pub max_num_response_bytes: u64,          // Max num of bytes in a (serialized) response
     pub max_request_jitter_ms: u64, // Max amount of jitter (ms) that a request will be delayed for
+    pub mempool_monitoring: MempoolMonitoringConfig,
     pub metadata_update_interval_ms: u64, // The interval (ms) between metadata updates
     pub network_monitoring: NetworkMonitoringConfig,
     pub node_monitoring: NodeMonitoringConfig,
@@ -21,13 +33,19 @@ pub struct PeerMonitoringServiceConfig {
 impl Default for PeerMonitoringServiceConfig {
     fn default() -> Self {
         Self {
+            alerting: AlertingConfig::default(),
             enable_peer_monitoring_client: true,
+            health_check_rate_limit_interval_ms: 1000, // 1 second
             latency_monitoring: LatencyMonitoringConfig::default(),
             max_concurrent_requests: 1000,
+            max_health_check_response_bytes: 256,
             max_network_channel_size: 1000,
-            max_num_response_bytes: 100 * 1024, // 100 KB
-            max_request_jitter_ms: 1000,        // Monitoring requests are very infrequent
-            metadata_update_interval_ms: 5000,  // 5 seconds
+            max_network_info_staleness_ms: 1000, // 1 second
+            max_node_info_staleness_ms: 1000,    // 1 second
+            max_num_response_bytes: 100 * 1024,  // 100 KB
+            max_request_jitter_ms: 1000,         // Monitoring requests are very infrequent
+            mempool_monitoring: MempoolMonitoringConfig::default(),
+            metadata_update_interval_ms: 5000, // 5 seconds
             network_monitoring: NetworkMonitoringConfig::default(),
             node_monitoring: NodeMonitoringConfig::default(),
             peer_monitor_interval_usec: 1_000_000, // 1 second
@@ -35,6 +53,92 @@ impl Default for PeerMonitoringServiceConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertingConfig {
+    pub alert_evaluation_interval_ms: u64, // The interval (ms) between alerting rule evaluations
+    pub alerting_rules: Vec<AlertingRule>, // The alerting rules to evaluate (none by default)
+}
+
+impl Default for AlertingConfig {
+    fn default() -> Self {
+        Self {
+            alert_evaluation_interval_ms: 10_000, // 10 seconds
+            alerting_rules: vec![],
+        }
+    }
+}
+
+/// A rule that fires an alert if more than a percentage of the connected peers
+/// breach a metric threshold continuously for a minimum duration. For example:
+/// "alert if >50% of validator network peers have a p95 latency > 500ms for 5 minutes".
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertingRule {
+    pub name: String, // The unique name of the rule (used in logs and metrics)
+    pub network_id: Option<NetworkId>, // The network of the peers to evaluate (None for all networks)
+    pub metric: AlertingMetric,        // The peer metric to compare against the threshold
+    pub threshold: u64, // A peer breaches the rule if its metric value is above the threshold
+    pub max_breaching_peers_percentage: u64, // The percentage of breaching peers above which the rule is violated
+    pub min_violation_duration_ms: u64, // The duration (ms) the rule must be violated before alerting
+}
+
+/// The peer metrics that can be used by alerting rules
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertingMetric {
+    AverageLatencyMs,       // The average of the recent latency pings (ms)
+    DistanceFromValidators, // The reported distance from the validators (hops)
+    LatencyP95Ms,           // The 95th percentile of the recent latency pings (ms)
+}
+
+impl AlertingMetric {
+    /// Returns a summary label for the metric
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::AverageLatencyMs => "average_latency_ms",
+            Self::DistanceFromValidators => "distance_from_validators",
+            Self::LatencyP95Ms => "latency_p95_ms",
+        }
+    }
+}
+
+impl ConfigSanitizer for PeerMonitoringServiceConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        _node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let alerting_config = &node_config.peer_monitoring_service.alerting;
+
+        // Verify that the alerting rules are well-formed
+        let mut rule_names = HashSet::new();
+        for alerting_rule in &alerting_config.alerting_rules {
+            if !rule_names.insert(alerting_rule.name.clone()) {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "Alerting rule names must be unique! Duplicate name: {:?}",
+                        alerting_rule.name
+                    ),
+                ));
+            }
+            if alerting_rule.max_breaching_peers_percentage >= 100 {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The max breaching peers percentage must be less than 100! Rule: {:?}",
+                        alerting_rule.name
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct LatencyMonitoringConfig {
@@ -55,6 +159,24 @@ impl Default for LatencyMonitoringConfig {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MempoolMonitoringConfig {
+    pub enable_mempool_monitoring: bool, // Whether or not to request mempool info from peers
+    pub mempool_info_request_interval_ms: u64, // The interval (ms) between mempool info requests
+    pub mempool_info_request_timeout_ms: u64, // The timeout (ms) for each mempool info request
+}
+
+impl Default for MempoolMonitoringConfig {
+    fn default() -> Self {
+        Self {
+            enable_mempool_monitoring: false, // Older servers don't support mempool info requests
+            mempool_info_request_interval_ms: 15_000, // 15 seconds
+            mempool_info_request_timeout_ms: 10_000, // 10 seconds
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct NetworkMonitoringConfig {
@@ -86,3 +208,85 @@ impl Default for NodeMonitoringConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_alerting_rules() {
+        // Create a node config with two valid alerting rules
+        let mut node_config = NodeConfig {
+            peer_monitoring_service: PeerMonitoringServiceConfig {
+                alerting: AlertingConfig {
+                    alerting_rules: vec![
+                        create_alerting_rule("high_latency", 50),
+                        create_alerting_rule("far_from_validators", 0),
+                    ],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config passes sanitization
+        PeerMonitoringServiceConfig::sanitize(&node_config, NodeType::Validator, None).unwrap();
+
+        // Add a rule with a duplicate name and verify that sanitization fails
+        let alerting_rules = &mut node_config.peer_monitoring_service.alerting.alerting_rules;
+        alerting_rules.push(create_alerting_rule("high_latency", 10));
+        let error = PeerMonitoringServiceConfig::sanitize(&node_config, NodeType::Validator, None)
+            .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Replace the duplicate with a rule that can never fire and verify that sanitization fails
+        let alerting_rules = &mut node_config.peer_monitoring_service.alerting.alerting_rules;
+        alerting_rules.pop();
+        alerting_rules.push(create_alerting_rule("never_fires", 100));
+        let error = PeerMonitoringServiceConfig::sanitize(&node_config, NodeType::Validator, None)
+            .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_deserialize_alerting_rules() {
+        // Create an alerting config in yaml format
+        let alerting_config = r#"
+            alert_evaluation_interval_ms: 5000
+            alerting_rules:
+              - name: "high_validator_latency"
+                network_id: "validator"
+                metric: "latency_p95_ms"
+                threshold: 500
+                max_breaching_peers_percentage: 50
+                min_violation_duration_ms: 300000
+        "#;
+
+        // Verify that the config is deserialized correctly
+        let alerting_config: AlertingConfig = serde_yaml::from_str(alerting_config).unwrap();
+        assert_eq!(alerting_config, AlertingConfig {
+            alert_evaluation_interval_ms: 5000,
+            alerting_rules: vec![AlertingRule {
+                name: "high_validator_latency".into(),
+                network_id: Some(NetworkId::Validator),
+                metric: AlertingMetric::LatencyP95Ms,
+                threshold: 500,
+                max_breaching_peers_percentage: 50,
+                min_violation_duration_ms: 300_000,
+            }],
+        });
+    }
+
+    /// Creates an alerting rule with the given name and percentage
+    fn create_alerting_rule(name: &str, max_breaching_peers_percentage: u64) -> AlertingRule {
+        AlertingRule {
+            name: name.into(),
+            network_id: None,
+            metric: AlertingMetric::LatencyP95Ms,
+            threshold: 500,
+            max_breaching_peers_percentage,
+            min_violation_duration_ms: 0,
+        }
+    }
+}
@@ -120,6 +120,10 @@ pub struct StateSyncDriverConfig {
     pub max_stream_wait_time_ms: u64,
     /// The version lag we'll tolerate before snapshot syncing
     pub num_versions_to_skip_snapshot_sync: u64,
+    /// The percentage (0-100) of applied transaction output chunks to re-execute and audit
+    /// against local execution. A non-zero value enables hybrid output syncing, i.e., outputs
+    /// are applied for speed, but a random sample of chunks is verified by execution.
+    pub output_audit_percentage: u64,
 }
 
 /// The default state sync driver config will be the one that gets (and keeps)
@@ -140,6 +144,7 @@ impl Default for StateSyncDriverConfig {
             max_pending_mempool_notifications: 100,
             max_stream_wait_time_ms: 5000,
             num_versions_to_skip_snapshot_sync: 400_000_000, // At 5k TPS, this allows a node to fail for about 24 hours.
+            output_audit_percentage: 0,
         }
     }
 }
@@ -201,6 +206,9 @@ impl Default for StorageServiceConfig {
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct DataStreamingServiceConfig {
+    /// The data lookahead config for the data streaming service
+    pub data_lookahead: DataLookaheadConfig,
+
     /// The dynamic prefetching config for the data streaming service
     pub dynamic_prefetching: DynamicPrefetchingConfig,
 
@@ -246,6 +254,7 @@ pub struct DataStreamingServiceConfig {
 impl Default for DataStreamingServiceConfig {
     fn default() -> Self {
         Self {
+            data_lookahead: DataLookaheadConfig::default(),
             dynamic_prefetching: DynamicPrefetchingConfig::default(),
             enable_subscription_streaming: false,
             global_summary_refresh_interval_ms: 50,
@@ -262,6 +271,31 @@ impl Default for DataStreamingServiceConfig {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DataLookaheadConfig {
+    /// Whether or not to enable data lookahead. If enabled, verified data
+    /// notifications are buffered ahead of the consumer (e.g., the state sync
+    /// driver) instead of blocking the stream when the consumer falls behind.
+    pub enable_data_lookahead: bool,
+
+    /// The maximum number of bytes (estimated) held in the lookahead buffer
+    pub max_lookahead_bytes: u64,
+
+    /// The maximum number of notifications held in the lookahead buffer
+    pub max_lookahead_notifications: u64,
+}
+
+impl Default for DataLookaheadConfig {
+    fn default() -> Self {
+        Self {
+            enable_data_lookahead: false,
+            max_lookahead_bytes: 200 * 1024 * 1024, // 200 MiB
+            max_lookahead_notifications: 100,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct DynamicPrefetchingConfig {
@@ -368,6 +402,47 @@ impl Default for AptosDataMultiFetchConfig {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AptosDataRequestHedgingConfig {
+    /// Whether or not to enable request hedging for data client requests. If enabled,
+    /// requests that haven't received a response within the hedging delay are also
+    /// sent to another peer, and the first successful response is used.
+    pub enable_request_hedging: bool,
+    /// The percentile of the recent response latencies (for each request type)
+    /// that is used as the hedging delay (e.g., 95 for the p95 latency).
+    pub hedging_latency_percentile: u64,
+    /// The minimum hedging delay (in ms)
+    pub min_hedging_delay_ms: u64,
+    /// The maximum hedging delay (in ms)
+    pub max_hedging_delay_ms: u64,
+    /// The minimum number of observed response latencies (for a request
+    /// type) before requests of that type are hedged.
+    pub min_latency_samples: usize,
+    /// The maximum number of recent response latencies to track (for each request type)
+    pub max_latency_samples: usize,
+    /// The maximum number of hedged requests (as a percentage of all hedgeable requests).
+    /// This bounds the additional load that hedging places on peers.
+    pub max_hedged_requests_percent: u64,
+    /// The maximum number of hedged requests that can be sent in a burst
+    pub max_hedged_requests_burst: u64,
+}
+
+impl Default for AptosDataRequestHedgingConfig {
+    fn default() -> Self {
+        Self {
+            enable_request_hedging: false,
+            hedging_latency_percentile: 95,
+            min_hedging_delay_ms: 50,
+            max_hedging_delay_ms: 5_000, // 5 seconds
+            min_latency_samples: 50,
+            max_latency_samples: 1_000,
+            max_hedged_requests_percent: 5,
+            max_hedged_requests_burst: 10,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct AptosLatencyFilteringConfig {
@@ -396,6 +471,8 @@ pub struct AptosDataClientConfig {
     pub data_poller_config: AptosDataPollerConfig,
     /// The aptos data multi-fetch config for the data client
     pub data_multi_fetch_config: AptosDataMultiFetchConfig,
+    /// The aptos data request hedging config for the data client
+    pub data_request_hedging_config: AptosDataRequestHedgingConfig,
     /// Whether or not to ignore peers with low peer scores
     pub ignore_low_score_peers: bool,
     /// The aptos latency filtering config for the data client
@@ -433,6 +510,7 @@ impl Default for AptosDataClientConfig {
         Self {
             data_poller_config: AptosDataPollerConfig::default(),
             data_multi_fetch_config: AptosDataMultiFetchConfig::default(),
+            data_request_hedging_config: AptosDataRequestHedgingConfig::default(),
             ignore_low_score_peers: true,
             latency_filtering_config: AptosLatencyFilteringConfig::default(),
             latency_monitor_loop_interval_ms: 100,
@@ -458,8 +536,74 @@ impl ConfigSanitizer for StateSyncConfig {
         node_type: NodeType,
         chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
-        // Sanitize the state sync driver config
-        StateSyncDriverConfig::sanitize(node_config, node_type, chain_id)
+        // Sanitize the state sync driver, data streaming service and data client configs
+        StateSyncDriverConfig::sanitize(node_config, node_type, chain_id)?;
+        DataStreamingServiceConfig::sanitize(node_config, node_type, chain_id)?;
+        AptosDataClientConfig::sanitize(node_config, node_type, chain_id)
+    }
+}
+
+impl ConfigSanitizer for AptosDataClientConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        _node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let hedging_config = &node_config
+            .state_sync
+            .aptos_data_client
+            .data_request_hedging_config;
+
+        // Verify that the hedging latency percentile is valid
+        if hedging_config.hedging_latency_percentile == 0
+            || hedging_config.hedging_latency_percentile > 100
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The hedging latency percentile must be between 1 and 100! Found: {}",
+                    hedging_config.hedging_latency_percentile
+                ),
+            ));
+        }
+
+        // Verify that the hedging delay bounds are valid
+        if hedging_config.min_hedging_delay_ms > hedging_config.max_hedging_delay_ms {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The min hedging delay ({} ms) must not exceed the max hedging delay ({} ms)!",
+                    hedging_config.min_hedging_delay_ms, hedging_config.max_hedging_delay_ms
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ConfigSanitizer for DataStreamingServiceConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        _node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let data_lookahead_config = &node_config.state_sync.data_streaming_service.data_lookahead;
+
+        // Verify that the lookahead limits are non-zero (if lookahead is enabled)
+        if data_lookahead_config.enable_data_lookahead
+            && (data_lookahead_config.max_lookahead_bytes == 0
+                || data_lookahead_config.max_lookahead_notifications == 0)
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The data lookahead limits must be non-zero when lookahead is enabled!".to_string(),
+            ));
+        }
+
+        Ok(())
     }
 }
 
@@ -483,6 +627,17 @@ impl ConfigSanitizer for StateSyncDriverConfig {
             ));
         }
 
+        // Verify that the output audit percentage is valid
+        if state_sync_driver_config.output_audit_percentage > 100 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The output audit percentage must be at most 100! Found: {}",
+                    state_sync_driver_config.output_audit_percentage
+                ),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -788,6 +943,98 @@ mod tests {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_output_audit_percentage() {
+        // Create a node config with an invalid output audit percentage
+        let node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                state_sync_driver: StateSyncDriverConfig {
+                    output_audit_percentage: 101,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_data_lookahead_limits() {
+        // Create a node config with data lookahead enabled and a zero byte limit
+        let node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                data_streaming_service: DataStreamingServiceConfig {
+                    data_lookahead: DataLookaheadConfig {
+                        enable_data_lookahead: true,
+                        max_lookahead_bytes: 0,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_request_hedging() {
+        // Create a node config with an invalid hedging latency percentile
+        let node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                aptos_data_client: AptosDataClientConfig {
+                    data_request_hedging_config: AptosDataRequestHedgingConfig {
+                        hedging_latency_percentile: 101,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Create a node config with invalid hedging delay bounds
+        let node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                aptos_data_client: AptosDataClientConfig {
+                    data_request_hedging_config: AptosDataRequestHedgingConfig {
+                        min_hedging_delay_ms: 1_000,
+                        max_hedging_delay_ms: 100,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error =
+            StateSyncConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::testnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
     /// Creates and returns a node config with the syncing modes set to execution
     fn create_execution_mode_config() -> NodeConfig {
         NodeConfig {
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    config::{config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig},
+    config::{
+        config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
+        node_config_loader::NodeType, Error, NodeConfig,
+    },
     utils,
 };
 use anyhow::{bail, ensure, Result};
@@ -11,6 +14,7 @@ use aptos_logger::warn;
 use aptos_types::chain_id::ChainId;
 use arr_macro::arr;
 use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
 use std::{
     collections::HashMap,
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -24,6 +28,10 @@ pub const DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD: usize = 1 << 13;
 pub const BUFFERED_STATE_TARGET_ITEMS: usize = 100_000;
 pub const BUFFERED_STATE_TARGET_ITEMS_FOR_TEST: usize = 10;
 
+// The min and max number of RocksDB background jobs when optimizing for resource limits
+const MIN_ROCKSDB_BACKGROUND_JOBS: i32 = 2;
+const MAX_ROCKSDB_BACKGROUND_JOBS_WITH_IO_LIMITS: i32 = 4;
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct DbPathConfig {
@@ -101,7 +109,7 @@ impl ShardedDbPathConfig {
 /// Port selected RocksDB options for tuning underlying rocksdb instance of AptosDB.
 /// see <https://github.com/facebook/rocksdb/blob/master/include/rocksdb/options.h>
 /// for detailed explanations.
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct RocksdbConfig {
     /// Maximum number of files open by RocksDB at one time
@@ -116,6 +124,8 @@ pub struct RocksdbConfig {
     pub block_size: u64,
     /// Whether cache index and filter blocks into block cache.
     pub cache_index_and_filter_blocks: bool,
+    /// Bloom filter options for the column families of this DB
+    pub bloom_filter: BloomFilterConfig,
 }
 
 impl Default for RocksdbConfig {
@@ -135,11 +145,63 @@ impl Default for RocksdbConfig {
             block_size: 4 * (1u64 << 10),
             // Whether cache index and filter blocks into block cache.
             cache_index_and_filter_blocks: false,
+            // By default, no column families use bloom filters.
+            bloom_filter: BloomFilterConfig::default(),
         }
     }
 }
 
+/// Maximum number of bits per key allowed for a bloom filter
+pub const MAX_BLOOM_FILTER_BITS_PER_KEY: u32 = 64;
+
+/// The type of bloom filter used by a column family
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BloomFilterType {
+    /// Whole keys are filtered (for column families read with point lookups)
+    WholeKey,
+    /// Key prefixes are filtered (for column families read with prefix seeks).
+    /// This requires the column family to have a prefix extractor.
+    Prefix,
+}
+
+/// Bloom filter options for the column families of a RocksDB instance. Each column
+/// family should use the filter type that matches how it is read: whole key filtering
+/// for column families read with point lookups (e.g., Jellyfish Merkle nodes), and prefix
+/// filtering for column families read with prefix seeks (e.g., state values). By default,
+/// no column families use a bloom filter.
+///
+/// Changing these options is safe for existing DBs: SST files keep the filters they
+/// were written with, and the new options take effect as files are compacted.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BloomFilterConfig {
+    /// Bits per key for whole key filters (0 disables the filters)
+    pub whole_key_bits_per_key: u32,
+    /// Bits per key for prefix filters (0 disables the filters)
+    pub prefix_bits_per_key: u32,
+    /// The bloom filter type of each column family (keyed by column family name).
+    /// Column families that are not listed don't use a bloom filter.
+    pub filter_types: HashMap<String, BloomFilterType>,
+    /// The lengths (in bytes) of fixed length prefix extractors for column families
+    /// (keyed by column family name). Note: the state value column families always
+    /// use the state key as the prefix (this is required by their prefix seeks).
+    pub fixed_prefix_lengths: HashMap<String, usize>,
+}
+
+impl Default for BloomFilterConfig {
+    fn default() -> Self {
+        Self {
+            // 10 bits per key results in a ~1% false positive rate
+            whole_key_bits_per_key: 10,
+            prefix_bits_per_key: 10,
+            filter_types: HashMap::new(),
+            fixed_prefix_lengths: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct RocksdbConfigs {
     // TODO(grao): Add RocksdbConfig for individual ledger DBs when necessary.
@@ -541,6 +603,43 @@ impl ConfigSanitizer for StorageConfig {
             ));
         }
 
+        let rocksdb_configs = &config.rocksdb_configs;
+        for (db_name, rocksdb_config) in [
+            ("ledger_db_config", &rocksdb_configs.ledger_db_config),
+            (
+                "state_merkle_db_config",
+                &rocksdb_configs.state_merkle_db_config,
+            ),
+            ("state_kv_db_config", &rocksdb_configs.state_kv_db_config),
+            ("index_db_config", &rocksdb_configs.index_db_config),
+        ] {
+            let bloom_filter = &rocksdb_config.bloom_filter;
+            if bloom_filter.whole_key_bits_per_key > MAX_BLOOM_FILTER_BITS_PER_KEY
+                || bloom_filter.prefix_bits_per_key > MAX_BLOOM_FILTER_BITS_PER_KEY
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The bloom filter bits per key for {} are too large: {:?}. Max allowed: {}",
+                        db_name, bloom_filter, MAX_BLOOM_FILTER_BITS_PER_KEY
+                    ),
+                ));
+            }
+            if let Some((cf_name, _)) = bloom_filter
+                .fixed_prefix_lengths
+                .iter()
+                .find(|(_, prefix_length)| **prefix_length == 0)
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The fixed prefix length for column family {} in {} must be non-zero!",
+                        cf_name, db_name
+                    ),
+                ));
+            }
+        }
+
         if let Some(db_path_overrides) = config.db_path_overrides.as_ref() {
             if !config.rocksdb_configs.enable_storage_sharding {
                 return Err(Error::ConfigSanitizerFailed(
@@ -596,9 +695,81 @@ impl ConfigSanitizer for StorageConfig {
     }
 }
 
+impl ConfigOptimizer for StorageConfig {
+    fn optimize(
+        node_config: &mut NodeConfig,
+        local_config_yaml: &Value,
+        _node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<bool, Error> {
+        // Size the RocksDB background jobs for the CPU and IO limits of the node
+        let cpu_limit = node_config.node_startup.cpu_limit();
+        let io_limited = aptos_runtimes::detect_cgroup_io_limits();
+        Ok(optimize_rocksdb_background_jobs(
+            &mut node_config.storage.rocksdb_configs,
+            &local_config_yaml["storage"]["rocksdb_configs"],
+            cpu_limit,
+            io_limited,
+        ))
+    }
+}
+
+/// Caps the number of background jobs of each RocksDB instance (that hasn't been
+/// set in the local config) to the CPU limit of the node (if any). If the node has
+/// IO limits, the jobs are capped further to avoid thrashing the limited IO bandwidth.
+/// Nodes without CPU or IO limits are left unchanged. Returns true iff any config
+/// was modified.
+fn optimize_rocksdb_background_jobs(
+    rocksdb_configs: &mut RocksdbConfigs,
+    local_rocksdb_configs_yaml: &Value,
+    cpu_limit: Option<usize>,
+    io_limited: bool,
+) -> bool {
+    if cpu_limit.is_none() && !io_limited {
+        return false; // There are no limits to size the background jobs for
+    }
+
+    let mut max_background_jobs = cpu_limit
+        .map(|num_cpus| i32::try_from(num_cpus).unwrap_or(i32::MAX))
+        .unwrap_or(i32::MAX);
+    if io_limited {
+        max_background_jobs = max_background_jobs.min(MAX_ROCKSDB_BACKGROUND_JOBS_WITH_IO_LIMITS);
+    }
+    let max_background_jobs = max_background_jobs.max(MIN_ROCKSDB_BACKGROUND_JOBS);
+
+    let mut modified_config = false;
+    for (db_name, rocksdb_config) in [
+        ("ledger_db_config", &mut rocksdb_configs.ledger_db_config),
+        (
+            "state_merkle_db_config",
+            &mut rocksdb_configs.state_merkle_db_config,
+        ),
+        (
+            "state_kv_db_config",
+            &mut rocksdb_configs.state_kv_db_config,
+        ),
+        ("index_db_config", &mut rocksdb_configs.index_db_config),
+    ] {
+        if local_rocksdb_configs_yaml[db_name]["max_background_jobs"].is_null()
+            && rocksdb_config.max_background_jobs > max_background_jobs
+        {
+            rocksdb_config.max_background_jobs = max_background_jobs;
+            modified_config = true;
+        }
+    }
+    modified_config
+}
+
 #[cfg(test)]
 mod test {
-    use crate::config::{PrunerConfig, ShardPathConfig, ShardedDbPathConfig};
+    use super::optimize_rocksdb_background_jobs;
+    use crate::config::{
+        config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, BloomFilterConfig,
+        BloomFilterType, Error, NodeConfig, PrunerConfig, RocksdbConfig, RocksdbConfigs,
+        ShardPathConfig, ShardedDbPathConfig, StorageConfig, MAX_BLOOM_FILTER_BITS_PER_KEY,
+    };
+    use aptos_types::chain_id::ChainId;
+    use serde_yaml::Value;
 
     #[test]
     pub fn test_default_prune_window() {
@@ -674,4 +845,161 @@ mod test {
 
         assert!(path_overrides.get_shard_paths().is_err());
     }
+
+    #[test]
+    pub fn test_sanitize_bloom_filter_bits_per_key() {
+        // Create a node config with a valid bloom filter config
+        let mut node_config = NodeConfig {
+            storage: StorageConfig {
+                rocksdb_configs: RocksdbConfigs {
+                    state_kv_db_config: RocksdbConfig {
+                        bloom_filter: BloomFilterConfig {
+                            whole_key_bits_per_key: 0,
+                            prefix_bits_per_key: MAX_BLOOM_FILTER_BITS_PER_KEY,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config is valid
+        StorageConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::mainnet()))
+            .unwrap();
+
+        // Use too many bits per key and verify that sanitization fails
+        node_config
+            .storage
+            .rocksdb_configs
+            .state_kv_db_config
+            .bloom_filter
+            .prefix_bits_per_key = MAX_BLOOM_FILTER_BITS_PER_KEY + 1;
+        let error =
+            StorageConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::mainnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    pub fn test_sanitize_bloom_filter_prefix_lengths() {
+        // Create a node config with a fixed length prefix filter
+        let mut node_config = NodeConfig::default();
+        let bloom_filter = &mut node_config
+            .storage
+            .rocksdb_configs
+            .ledger_db_config
+            .bloom_filter;
+        bloom_filter
+            .filter_types
+            .insert("event_by_key".into(), BloomFilterType::Prefix);
+        bloom_filter
+            .fixed_prefix_lengths
+            .insert("event_by_key".into(), 40);
+
+        // Verify that the config is valid
+        StorageConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::mainnet()))
+            .unwrap();
+
+        // Use an empty prefix and verify that sanitization fails
+        node_config
+            .storage
+            .rocksdb_configs
+            .ledger_db_config
+            .bloom_filter
+            .fixed_prefix_lengths
+            .insert("event_by_key".into(), 0);
+        let error =
+            StorageConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::mainnet()))
+                .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    pub fn test_optimize_rocksdb_background_jobs() {
+        // Verify that nothing is modified when there are enough CPUs (and no IO limits)
+        let mut rocksdb_configs = RocksdbConfigs::default();
+        let modified_config =
+            optimize_rocksdb_background_jobs(&mut rocksdb_configs, &Value::Null, Some(64), false);
+        assert!(!modified_config);
+        assert_eq!(rocksdb_configs, RocksdbConfigs::default());
+
+        // Verify that the background jobs are capped by the number of CPUs
+        let modified_config =
+            optimize_rocksdb_background_jobs(&mut rocksdb_configs, &Value::Null, Some(8), false);
+        assert!(modified_config);
+        assert_eq!(rocksdb_configs.ledger_db_config.max_background_jobs, 8);
+        assert_eq!(
+            rocksdb_configs.state_merkle_db_config.max_background_jobs,
+            8
+        );
+        assert_eq!(rocksdb_configs.state_kv_db_config.max_background_jobs, 8);
+        assert_eq!(rocksdb_configs.index_db_config.max_background_jobs, 8);
+
+        // Verify that the background jobs are capped further with IO limits
+        let modified_config =
+            optimize_rocksdb_background_jobs(&mut rocksdb_configs, &Value::Null, Some(8), true);
+        assert!(modified_config);
+        assert_eq!(rocksdb_configs.ledger_db_config.max_background_jobs, 4);
+
+        // Verify that the background jobs never drop below the minimum
+        let modified_config =
+            optimize_rocksdb_background_jobs(&mut rocksdb_configs, &Value::Null, Some(1), false);
+        assert!(modified_config);
+        assert_eq!(rocksdb_configs.ledger_db_config.max_background_jobs, 2);
+    }
+
+    #[test]
+    pub fn test_optimize_rocksdb_background_jobs_no_cpu_limit() {
+        // Verify that nothing is modified when there are no CPU or IO limits
+        let mut rocksdb_configs = RocksdbConfigs::default();
+        let modified_config =
+            optimize_rocksdb_background_jobs(&mut rocksdb_configs, &Value::Null, None, false);
+        assert!(!modified_config);
+        assert_eq!(rocksdb_configs, RocksdbConfigs::default());
+
+        // Verify that the background jobs are only capped by the IO limits
+        let modified_config =
+            optimize_rocksdb_background_jobs(&mut rocksdb_configs, &Value::Null, None, true);
+        assert!(modified_config);
+        assert_eq!(rocksdb_configs.ledger_db_config.max_background_jobs, 4);
+        assert_eq!(
+            rocksdb_configs.state_merkle_db_config.max_background_jobs,
+            4
+        );
+        assert_eq!(rocksdb_configs.state_kv_db_config.max_background_jobs, 4);
+        assert_eq!(rocksdb_configs.index_db_config.max_background_jobs, 4);
+    }
+
+    #[test]
+    pub fn test_optimize_rocksdb_background_jobs_local_config() {
+        // Create a local config that sets the background jobs of the ledger db
+        let local_config_yaml = serde_yaml::from_str(
+            r#"
+            ledger_db_config:
+                max_background_jobs: 16
+            "#,
+        )
+        .unwrap();
+
+        // Optimize the config and verify only the other dbs are modified
+        let mut rocksdb_configs = RocksdbConfigs::default();
+        let modified_config = optimize_rocksdb_background_jobs(
+            &mut rocksdb_configs,
+            &local_config_yaml,
+            Some(4),
+            false,
+        );
+        assert!(modified_config);
+        assert_eq!(rocksdb_configs.ledger_db_config.max_background_jobs, 16);
+        assert_eq!(
+            rocksdb_configs.state_merkle_db_config.max_background_jobs,
+            4
+        );
+        assert_eq!(rocksdb_configs.state_kv_db_config.max_background_jobs, 4);
+        assert_eq!(rocksdb_configs.index_db_config.max_background_jobs, 4);
+    }
 }
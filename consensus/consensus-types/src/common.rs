@@ -124,7 +124,19 @@ pub struct RejectedTransactionSummary {
     pub sender: AccountAddress,
     pub sequence_number: u64,
     pub hash: HashValue,
-    pub reason: DiscardedVMStatus,
+    pub reason: TransactionRejectionReason,
+}
+
+/// The reason a transaction in an executed block was not committed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionRejectionReason {
+    /// The transaction was discarded during execution (e.g., it failed validation
+    /// at execution time, or its gas unit price is below the current minimum).
+    Discarded(DiscardedVMStatus),
+    /// The transaction was not executed because the block was cut (e.g., the block
+    /// gas limit was reached, or a reconfiguration occurred earlier in the block).
+    /// The transaction is still valid and may be included in a later block.
+    BlockCut,
 }
 
 #[derive(Debug)]
@@ -31,4 +31,5 @@ pub mod vote;
 pub mod vote_data;
 pub mod vote_msg;
 pub mod vote_proposal;
+pub mod vote_retrieval;
 pub mod wrapped_ledger_info;
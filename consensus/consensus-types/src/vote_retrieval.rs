@@ -0,0 +1,106 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{common::Round, sync_info::SyncInfo, vote::Vote, vote_msg::VoteMsg};
+use anyhow::ensure;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// RPC to request the vote of a peer for the given round. This is used to recover votes
+/// that were lost (e.g., due to transient message loss) when a round stalls.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct VoteRetrievalRequest {
+    epoch: u64,
+    round: Round,
+}
+
+impl VoteRetrievalRequest {
+    pub fn new(epoch: u64, round: Round) -> Self {
+        Self { epoch, round }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn round(&self) -> Round {
+        self.round
+    }
+}
+
+impl fmt::Display for VoteRetrievalRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[VoteRetrievalRequest epoch {}, round {}]",
+            self.epoch, self.round
+        )
+    }
+}
+
+/// Carries the vote of the peer for the requested round (if the peer voted in that round),
+/// and the peer's sync info (which may already certify the requested round).
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct VoteRetrievalResponse {
+    vote: Option<Vote>,
+    sync_info: SyncInfo,
+}
+
+impl VoteRetrievalResponse {
+    pub fn new(vote: Option<Vote>, sync_info: SyncInfo) -> Self {
+        Self { vote, sync_info }
+    }
+
+    pub fn vote(&self) -> Option<&Vote> {
+        self.vote.as_ref()
+    }
+
+    pub fn sync_info(&self) -> &SyncInfo {
+        &self.sync_info
+    }
+
+    /// Verifies that the response matches the request. The vote (and the sync info)
+    /// are verified when processed, as for any other vote (or sync info) message.
+    pub fn verify(&self, request: &VoteRetrievalRequest) -> anyhow::Result<()> {
+        ensure!(
+            self.sync_info.epoch() == request.epoch(),
+            "Vote retrieval response has a different epoch"
+        );
+        if let Some(vote) = &self.vote {
+            ensure!(
+                vote.epoch() == request.epoch(),
+                "Retrieved vote has a different epoch"
+            );
+            ensure!(
+                vote.vote_data().proposed().round() == request.round(),
+                "Retrieved vote has a different round"
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the vote message for the retrieved vote. Returns None if there is no vote,
+    /// or if the sync info already certifies the round of the vote.
+    pub fn into_vote_msg(self) -> Option<VoteMsg> {
+        let vote = self.vote?;
+        (vote.vote_data().proposed().round() > self.sync_info.highest_round())
+            .then(|| VoteMsg::new(vote, self.sync_info))
+    }
+}
+
+impl fmt::Display for VoteRetrievalResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.vote {
+            Some(vote) => write!(
+                f,
+                "[VoteRetrievalResponse vote: {}, sync_info: {}]",
+                vote, self.sync_info
+            ),
+            None => write!(
+                f,
+                "[VoteRetrievalResponse no vote, sync_info: {}]",
+                self.sync_info
+            ),
+        }
+    }
+}
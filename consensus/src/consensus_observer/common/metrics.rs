@@ -9,6 +9,7 @@ use aptos_metrics_core::{
     IntCounterVec, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
+use std::time::Duration;
 
 // Useful metric labels
 pub const BLOCK_PAYLOAD_LABEL: &str = "block_payload";
@@ -16,6 +17,8 @@ pub const COMMIT_DECISION_LABEL: &str = "commit_decision";
 pub const CREATED_SUBSCRIPTION_LABEL: &str = "created_subscription";
 pub const ORDERED_BLOCK_ENTRIES_LABEL: &str = "ordered_block_entries";
 pub const ORDERED_BLOCKS_LABEL: &str = "ordered_blocks";
+pub const PAYLOAD_DIGESTS_LABEL: &str = "payload_digests";
+pub const PAYLOAD_SIGNATURES_LABEL: &str = "payload_signatures";
 pub const PENDING_BLOCK_ENTRIES_LABEL: &str = "pending_block_entries";
 pub const PENDING_BLOCKS_LABEL: &str = "pending_blocks";
 pub const STORED_PAYLOADS_LABEL: &str = "stored_payloads";
@@ -40,6 +43,16 @@ pub static OBSERVER_NUM_ACTIVE_SUBSCRIPTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Histogram for tracking block payload verification latencies for the consensus observer
+pub static OBSERVER_PAYLOAD_VERIFICATION_LATENCIES: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "consensus_observer_payload_verification_latencies",
+        "Histogram related to block payload verification latencies for the consensus observer",
+        &["verification_type"]
+    )
+    .unwrap()
+});
+
 /// Gauge for tracking the number of processed blocks by the consensus observer
 pub static OBSERVER_NUM_PROCESSED_BLOCKS: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
@@ -192,6 +205,13 @@ pub fn increment_request_counter(
         .inc();
 }
 
+/// Observes the latency (in seconds) for the provided histogram and label
+pub fn observe_latency_with_label(histogram: &Lazy<HistogramVec>, label: &str, latency: Duration) {
+    histogram
+        .with_label_values(&[label])
+        .observe(latency.as_secs_f64())
+}
+
 /// Observes the value for the provided histogram and label
 pub fn observe_value_with_label(
     histogram: &Lazy<HistogramVec>,
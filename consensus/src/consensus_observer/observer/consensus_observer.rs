@@ -16,8 +16,12 @@ use crate::{
             },
         },
         observer::{
-            active_state::ActiveObserverState, ordered_blocks::OrderedBlockStore,
-            payload_store::BlockPayloadStore, pending_blocks::PendingBlockStore,
+            active_state::ActiveObserverState,
+            ordered_blocks::OrderedBlockStore,
+            payload_store::BlockPayloadStore,
+            payload_verifier,
+            payload_verifier::{PayloadVerificationResult, VerifiedNetworkMessage},
+            pending_blocks::PendingBlockStore,
             subscription_manager::SubscriptionManager,
         },
         publisher::consensus_publisher::ConsensusPublisher,
@@ -28,10 +32,14 @@ use crate::{
     pipeline::execution_client::TExecutionClient,
 };
 use aptos_channels::{aptos_channel, aptos_channel::Receiver, message_queues::QueueStyle};
-use aptos_config::config::{ConsensusObserverConfig, NodeConfig};
+use aptos_config::{
+    config::{ConsensusObserverConfig, NodeConfig},
+    network_id::PeerNetworkId,
+};
 use aptos_consensus_types::{pipeline, pipelined_block::PipelinedBlock};
 use aptos_crypto::{bls12381, Genesis};
 use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
+use aptos_infallible::RwLock;
 use aptos_logger::{debug, error, info, warn};
 use aptos_network::{
     application::interface::NetworkClient, protocols::wire::handshake::v1::ProtocolId,
@@ -83,6 +91,14 @@ pub struct ConsensusObserver {
 
     // The subscription manager
     subscription_manager: SubscriptionManager,
+
+    // The epoch state shared with the block payload verifier (used to
+    // verify payload signatures before the payloads are processed).
+    verifier_epoch_state: Arc<RwLock<Option<Arc<EpochState>>>>,
+
+    // The subscribed peer shared with the block payload verifier (only
+    // payloads sent by the subscribed peer are verified).
+    verifier_subscription_peer: Arc<RwLock<Option<PeerNetworkId>>>,
 }
 
 impl ConsensusObserver {
@@ -126,6 +142,8 @@ impl ConsensusObserver {
             sync_notification_sender,
             sync_handle: None,
             subscription_manager,
+            verifier_epoch_state: Arc::new(RwLock::new(None)),
+            verifier_subscription_peer: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -161,6 +179,11 @@ impl ConsensusObserver {
             .subscription_manager
             .check_and_manage_subscriptions()
             .await;
+
+        // Share the (possibly updated) subscription peer with the payload verifier
+        *self.verifier_subscription_peer.write() =
+            self.subscription_manager.get_active_subscription_peer();
+
         if new_subscription_created {
             // Clear the pending block state (a new subscription was created)
             self.clear_pending_block_state().await;
@@ -287,8 +310,13 @@ impl ConsensusObserver {
         }
     }
 
-    /// Processes the block payload message
-    async fn process_block_payload_message(&mut self, block_payload: BlockPayload) {
+    /// Processes the block payload message. If the payload has already been
+    /// verified by the payload verifier, the verification results are reused.
+    async fn process_block_payload_message(
+        &mut self,
+        block_payload: BlockPayload,
+        payload_verification_result: Option<PayloadVerificationResult>,
+    ) {
         // Get the epoch and round for the block
         let block_epoch = block_payload.block.epoch();
         let block_round = block_payload.block.round();
@@ -300,8 +328,21 @@ impl ConsensusObserver {
             block_round,
         );
 
+        // Unpack the payload verification results (if they exist)
+        let epoch_state = self.get_epoch_state();
+        let (digests_result, signatures_result) = match payload_verification_result {
+            Some(payload_verification_result) => {
+                let (digests_result, signatures_result) =
+                    payload_verification_result.into_results(epoch_state.epoch);
+                (Some(digests_result), signatures_result)
+            },
+            None => (None, None),
+        };
+
         // Verify the block payload digests
-        if let Err(error) = block_payload.verify_payload_digests() {
+        let digests_result =
+            digests_result.unwrap_or_else(|| block_payload.verify_payload_digests());
+        if let Err(error) = digests_result {
             error!(
                 LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
                     "Failed to verify block payload digests! Ignoring block: {:?}. Error: {:?}",
@@ -312,10 +353,11 @@ impl ConsensusObserver {
         }
 
         // If the payload is for the current epoch, verify the proof signatures
-        let epoch_state = self.get_epoch_state();
         let verified_payload = if block_epoch == epoch_state.epoch {
             // Verify the block proof signatures
-            if let Err(error) = block_payload.verify_payload_signatures(&epoch_state) {
+            let signatures_result = signatures_result
+                .unwrap_or_else(|| block_payload.verify_payload_signatures(&epoch_state));
+            if let Err(error) = signatures_result {
                 error!(
                     LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
                         "Failed to verify block payload signatures! Ignoring block: {:?}. Error: {:?}",
@@ -464,9 +506,10 @@ impl ConsensusObserver {
         false // The commit decision was not processed
     }
 
-    /// Processes a network message received by the consensus observer
-    async fn process_network_message(&mut self, network_message: ConsensusObserverNetworkMessage) {
+    /// Processes a (verified) network message received by the consensus observer
+    async fn process_network_message(&mut self, verified_message: VerifiedNetworkMessage) {
         // Unpack the network message
+        let (network_message, payload_verification_result) = verified_message.into_parts();
         let (peer_network_id, message) = network_message.into_parts();
 
         // Verify the message is from the peer we've subscribed to
@@ -526,7 +569,8 @@ impl ConsensusObserver {
                 log_received_message(log_message);
 
                 // Process the block payload message
-                self.process_block_payload_message(block_payload).await;
+                self.process_block_payload_message(block_payload, payload_verification_result)
+                    .await;
             },
         }
 
@@ -702,8 +746,9 @@ impl ConsensusObserver {
             .wait_for_epoch_start(block_payloads)
             .await;
 
-        // Fetch the new epoch state
+        // Fetch the new epoch state (and share it with the payload verifier)
         let epoch_state = self.get_epoch_state();
+        *self.verifier_epoch_state.write() = Some(epoch_state.clone());
 
         // Start the new epoch
         let sk = Arc::new(bls12381::PrivateKey::genesis());
@@ -733,7 +778,7 @@ impl ConsensusObserver {
     pub async fn start(
         mut self,
         consensus_observer_config: ConsensusObserverConfig,
-        mut consensus_observer_message_receiver: Receiver<(), ConsensusObserverNetworkMessage>,
+        consensus_observer_message_receiver: Receiver<(), ConsensusObserverNetworkMessage>,
         mut sync_notification_listener: tokio::sync::mpsc::UnboundedReceiver<(u64, Round)>,
     ) {
         // Create a progress check ticker
@@ -742,6 +787,15 @@ impl ConsensusObserver {
         )))
         .fuse();
 
+        // Create the verified message stream (block payloads are verified
+        // in parallel, but the messages are processed in order).
+        let mut verified_message_stream = Box::pin(payload_verifier::verify_messages_in_order(
+            consensus_observer_message_receiver,
+            self.verifier_subscription_peer.clone(),
+            self.verifier_epoch_state.clone(),
+            consensus_observer_config.max_parallel_verification_tasks,
+        ));
+
         // Wait for the epoch to start
         self.wait_for_epoch_start().await;
 
@@ -750,8 +804,8 @@ impl ConsensusObserver {
             .message("Starting the consensus observer loop!"));
         loop {
             tokio::select! {
-                Some(network_message) = consensus_observer_message_receiver.next() => {
-                    self.process_network_message(network_message).await;
+                Some(verified_message) = verified_message_stream.next() => {
+                    self.process_network_message(verified_message).await;
                 }
                 Some((epoch, round)) = sync_notification_listener.recv() => {
                     self.process_sync_notification(epoch, round).await;
@@ -5,6 +5,7 @@ pub mod active_state;
 pub mod consensus_observer;
 pub mod ordered_blocks;
 pub mod payload_store;
+pub mod payload_verifier;
 pub mod pending_blocks;
 pub mod subscription;
 pub mod subscription_manager;
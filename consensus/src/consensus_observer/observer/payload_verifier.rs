@@ -0,0 +1,343 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    common::{
+        error::Error,
+        logging::{LogEntry, LogSchema},
+        metrics,
+    },
+    network::{
+        network_handler::ConsensusObserverNetworkMessage,
+        observer_message::{BlockPayload, ConsensusObserverDirectSend},
+    },
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_infallible::RwLock;
+use aptos_logger::error;
+use aptos_types::epoch_state::EpochState;
+use futures::{
+    future::{self, Either},
+    Stream, StreamExt,
+};
+use std::{sync::Arc, time::Instant};
+
+/// The results of verifying a block payload (before the payload is
+/// processed by the consensus observer).
+#[derive(Debug)]
+pub struct PayloadVerificationResult {
+    // The result of verifying the payload digests
+    digests_result: Result<(), Error>,
+
+    // The epoch (and result) of the payload signature verification. This is
+    // only set if the epoch state for the payload was known at verification time.
+    signatures_result: Option<(u64, Result<(), Error>)>,
+}
+
+impl PayloadVerificationResult {
+    /// Consumes the verification result and returns the digests result
+    /// and the signatures result (only if it was verified for the given epoch).
+    pub fn into_results(self, epoch: u64) -> (Result<(), Error>, Option<Result<(), Error>>) {
+        let signatures_result = match self.signatures_result {
+            Some((verified_epoch, signatures_result)) if verified_epoch == epoch => {
+                Some(signatures_result)
+            },
+            _ => None,
+        };
+        (self.digests_result, signatures_result)
+    }
+}
+
+/// A network message along with the results of verifying its block
+/// payload (if the message contained a block payload).
+pub struct VerifiedNetworkMessage {
+    network_message: ConsensusObserverNetworkMessage,
+    payload_verification_result: Option<PayloadVerificationResult>,
+}
+
+impl VerifiedNetworkMessage {
+    pub fn new(
+        network_message: ConsensusObserverNetworkMessage,
+        payload_verification_result: Option<PayloadVerificationResult>,
+    ) -> Self {
+        Self {
+            network_message,
+            payload_verification_result,
+        }
+    }
+
+    /// Consumes and unpacks the message into its parts
+    pub fn into_parts(
+        self,
+    ) -> (
+        ConsensusObserverNetworkMessage,
+        Option<PayloadVerificationResult>,
+    ) {
+        (self.network_message, self.payload_verification_result)
+    }
+}
+
+/// Verifies the given block payload. The digests are always verified, but the
+/// signatures are only verified if the epoch state for the payload is known.
+pub fn verify_block_payload(
+    block_payload: &BlockPayload,
+    epoch_state: Option<Arc<EpochState>>,
+) -> PayloadVerificationResult {
+    // Verify the payload digests
+    let start_time = Instant::now();
+    let digests_result = block_payload.verify_payload_digests();
+    metrics::observe_latency_with_label(
+        &metrics::OBSERVER_PAYLOAD_VERIFICATION_LATENCIES,
+        metrics::PAYLOAD_DIGESTS_LABEL,
+        start_time.elapsed(),
+    );
+
+    // Verify the payload signatures (if the epoch state is known for the payload)
+    let signatures_result = match epoch_state {
+        Some(epoch_state) if epoch_state.epoch == block_payload.block.epoch() => {
+            let start_time = Instant::now();
+            let signatures_result = block_payload.verify_payload_signatures(&epoch_state);
+            metrics::observe_latency_with_label(
+                &metrics::OBSERVER_PAYLOAD_VERIFICATION_LATENCIES,
+                metrics::PAYLOAD_SIGNATURES_LABEL,
+                start_time.elapsed(),
+            );
+            Some((epoch_state.epoch, signatures_result))
+        },
+        _ => None,
+    };
+
+    PayloadVerificationResult {
+        digests_result,
+        signatures_result,
+    }
+}
+
+/// Returns a stream that verifies the block payloads of the given messages in
+/// parallel (on the blocking thread pool), but yields the verified messages in
+/// the same order they were received. This ensures a single slow verification
+/// doesn't delay the verification of subsequent messages. Payloads are only
+/// verified if they were sent by the currently subscribed peer (messages from
+/// other peers are dropped by the observer, so verifying them is wasted work).
+pub fn verify_messages_in_order(
+    network_messages: impl Stream<Item = ConsensusObserverNetworkMessage>,
+    subscription_peer: Arc<RwLock<Option<PeerNetworkId>>>,
+    epoch_state: Arc<RwLock<Option<Arc<EpochState>>>>,
+    max_parallel_verification_tasks: usize,
+) -> impl Stream<Item = VerifiedNetworkMessage> {
+    network_messages
+        .map(move |network_message| {
+            // Only block payloads from the subscribed peer require (expensive) verification
+            let (peer_network_id, message) = network_message.into_parts();
+            let is_subscribed_peer = *subscription_peer.read() == Some(peer_network_id);
+            match message {
+                ConsensusObserverDirectSend::BlockPayload(block_payload) if is_subscribed_peer => {
+                    // Spawn a new blocking task to verify the block payload
+                    let epoch_state = epoch_state.read().clone();
+                    Either::Left(tokio::task::spawn_blocking(move || {
+                        let verification_result = verify_block_payload(&block_payload, epoch_state);
+                        let network_message = ConsensusObserverNetworkMessage::new(
+                            peer_network_id,
+                            ConsensusObserverDirectSend::BlockPayload(block_payload),
+                        );
+                        VerifiedNetworkMessage::new(network_message, Some(verification_result))
+                    }))
+                },
+                message => {
+                    let network_message =
+                        ConsensusObserverNetworkMessage::new(peer_network_id, message);
+                    Either::Right(future::ready(Ok(VerifiedNetworkMessage::new(
+                        network_message,
+                        None,
+                    ))))
+                },
+            }
+        })
+        .buffered(max_parallel_verification_tasks)
+        .filter_map(|verification_result| {
+            let verified_message = match verification_result {
+                Ok(verified_message) => Some(verified_message),
+                Err(error) => {
+                    // We failed to run the verification task
+                    error!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "Failed to run the payload verification task! Error: {:?}",
+                            error
+                        ))
+                    );
+                    None
+                },
+            };
+            future::ready(verified_message)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::network::observer_message::BlockTransactionPayload;
+    use aptos_consensus_types::proof_of_store::{BatchId, BatchInfo, ProofOfStore};
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::{BlockInfo, Round},
+        validator_signer::ValidatorSigner,
+        validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
+        PeerId,
+    };
+    use futures::stream;
+
+    #[test]
+    fn test_verify_block_payload() {
+        // Create a block payload without any batches (the digests and signatures are valid)
+        let epoch = 10;
+        let block_payload = create_block_payload(epoch, 0, 0);
+
+        // Verify the block payload without an epoch state
+        let verification_result = verify_block_payload(&block_payload, None);
+        let (digests_result, signatures_result) = verification_result.into_results(epoch);
+        assert!(digests_result.is_ok());
+        assert!(signatures_result.is_none());
+
+        // Verify the block payload with an epoch state for a different epoch
+        let epoch_state = Arc::new(EpochState::new(epoch + 1, create_validator_verifier()));
+        let verification_result = verify_block_payload(&block_payload, Some(epoch_state));
+        let (digests_result, signatures_result) = verification_result.into_results(epoch);
+        assert!(digests_result.is_ok());
+        assert!(signatures_result.is_none());
+
+        // Verify the block payload with an epoch state for the payload epoch
+        let epoch_state = Arc::new(EpochState::new(epoch, create_validator_verifier()));
+        let verification_result = verify_block_payload(&block_payload, Some(epoch_state));
+        let (digests_result, signatures_result) = verification_result.into_results(epoch);
+        assert!(digests_result.is_ok());
+        assert!(signatures_result.unwrap().is_ok());
+
+        // Create a block payload with invalid batches (the digests and signatures are invalid)
+        let block_payload = create_block_payload(epoch, 0, 5);
+
+        // Verify the block payload and check that verification fails
+        let epoch_state = Arc::new(EpochState::new(epoch, create_validator_verifier()));
+        let verification_result = verify_block_payload(&block_payload, Some(epoch_state));
+        let (digests_result, signatures_result) = verification_result.into_results(epoch);
+        assert!(digests_result.is_err());
+        assert!(signatures_result.unwrap().is_err());
+
+        // Verify that the signature result is ignored when processing for a different epoch
+        let epoch_state = Arc::new(EpochState::new(epoch, create_validator_verifier()));
+        let verification_result = verify_block_payload(&block_payload, Some(epoch_state));
+        let (_, signatures_result) = verification_result.into_results(epoch + 1);
+        assert!(signatures_result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_verify_messages_in_order() {
+        // Create a set of block payloads from the subscribed peer (alternating between valid and invalid)
+        let epoch = 5;
+        let num_messages = 50;
+        let subscribed_peer = PeerNetworkId::random();
+        let network_messages: Vec<_> = (0..num_messages)
+            .map(|round| {
+                let num_batches = if round % 2 == 0 { 0 } else { 3 };
+                let block_payload = create_block_payload(epoch, round, num_batches);
+                ConsensusObserverNetworkMessage::new(
+                    subscribed_peer,
+                    ConsensusObserverDirectSend::BlockPayload(block_payload),
+                )
+            })
+            .collect();
+
+        // Verify the messages in parallel
+        let subscription_peer = Arc::new(RwLock::new(Some(subscribed_peer)));
+        let epoch_state = Arc::new(RwLock::new(Some(Arc::new(EpochState::new(
+            epoch,
+            create_validator_verifier(),
+        )))));
+        let verified_messages: Vec<_> = verify_messages_in_order(
+            stream::iter(network_messages),
+            subscription_peer.clone(),
+            epoch_state.clone(),
+            8,
+        )
+        .collect()
+        .await;
+
+        // Verify that all messages were returned in order with the correct results
+        assert_eq!(verified_messages.len(), num_messages as usize);
+        for (expected_round, verified_message) in verified_messages.into_iter().enumerate() {
+            let (network_message, verification_result) = verified_message.into_parts();
+            let (_, message) = network_message.into_parts();
+            match message {
+                ConsensusObserverDirectSend::BlockPayload(block_payload) => {
+                    assert_eq!(block_payload.block.round(), expected_round as Round);
+                },
+                message => panic!("Expected a block payload but got: {:?}", message),
+            }
+
+            let (digests_result, _) = verification_result.unwrap().into_results(epoch);
+            assert_eq!(digests_result.is_ok(), expected_round % 2 == 0);
+        }
+
+        // Create a block payload from a peer we're not subscribed to
+        let network_message = ConsensusObserverNetworkMessage::new(
+            PeerNetworkId::random(),
+            ConsensusObserverDirectSend::BlockPayload(create_block_payload(epoch, 0, 3)),
+        );
+
+        // Verify that the payload is not verified
+        let mut verified_messages: Vec<_> = verify_messages_in_order(
+            stream::iter(vec![network_message]),
+            subscription_peer,
+            epoch_state,
+            8,
+        )
+        .collect()
+        .await;
+        assert_eq!(verified_messages.len(), 1);
+        let (_, verification_result) = verified_messages.pop().unwrap().into_parts();
+        assert!(verification_result.is_none());
+    }
+
+    /// Creates a block payload with the given number of (invalid) batches
+    fn create_block_payload(epoch: u64, round: Round, num_batches: usize) -> BlockPayload {
+        // Create the block info
+        let block_info = BlockInfo::random_with_epoch(epoch, round);
+
+        // Create the proofs of store (with random digests and empty signatures)
+        let proofs_of_store = (0..num_batches)
+            .map(|_| {
+                let batch_info = BatchInfo::new(
+                    PeerId::random(),
+                    BatchId::new(0),
+                    epoch,
+                    0,
+                    HashValue::random(),
+                    0,
+                    0,
+                    0,
+                );
+                ProofOfStore::new(batch_info, AggregateSignature::empty())
+            })
+            .collect();
+
+        // Create the block payload
+        let block_transaction_payload = BlockTransactionPayload::new_quorum_store_inline_hybrid(
+            vec![],
+            proofs_of_store,
+            None,
+            vec![],
+        );
+        BlockPayload::new(block_info, block_transaction_payload)
+    }
+
+    /// Creates a validator verifier with a single random validator
+    fn create_validator_verifier() -> ValidatorVerifier {
+        let validator_signer = ValidatorSigner::random(None);
+        let validator_consensus_info = ValidatorConsensusInfo::new(
+            validator_signer.author(),
+            validator_signer.public_key(),
+            100,
+        );
+        ValidatorVerifier::new(vec![validator_consensus_info])
+    }
+}
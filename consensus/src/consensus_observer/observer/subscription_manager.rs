@@ -65,6 +65,13 @@ impl SubscriptionManager {
         }
     }
 
+    /// Returns the peer of the currently active subscription (if any)
+    pub fn get_active_subscription_peer(&self) -> Option<PeerNetworkId> {
+        self.active_observer_subscription
+            .as_ref()
+            .map(|subscription| subscription.get_peer_network_id())
+    }
+
     /// Checks if the active subscription is still healthy. If not, an error is returned.
     fn check_active_subscription(&mut self) -> Result<(), Error> {
         let active_observer_subscription = self.active_observer_subscription.take();
@@ -688,6 +688,37 @@ pub static ORDER_VOTE_BROADCASTED: Lazy<IntCounter> = Lazy::new(|| {
 
 ////////////////////////
 // SYNC MANAGER COUNTERS
+/// Counts the vote retrieval requests sent to recover the missing votes of stalled rounds,
+/// by result (i.e., "success" or "error").
+pub static VOTE_RECOVERY_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_vote_recovery_requests",
+        "Counts the vote retrieval requests sent to recover the missing votes of stalled rounds",
+        &["result"]
+    )
+    .unwrap()
+});
+
+/// Counts the votes recovered via vote retrieval requests.
+pub static VOTE_RECOVERY_RETRIEVED_VOTES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_vote_recovery_retrieved_votes",
+        "Counts the votes recovered via vote retrieval requests"
+    )
+    .unwrap()
+});
+
+/// Counts the vote retrieval requests received from peers, by result (i.e., "served" or
+/// "rate_limited").
+pub static VOTE_RETRIEVAL_REQUESTS_RECEIVED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_vote_retrieval_requests_received",
+        "Counts the vote retrieval requests received from peers",
+        &["result"]
+    )
+    .unwrap()
+});
+
 ////////////////////////
 /// Counts the number of times the sync info message has been set since last restart.
 pub static SYNC_INFO_MSGS_SENT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
@@ -1145,6 +1176,30 @@ pub static QUORUM_BATCH_READY_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// The payload became available after a stall
+pub const PAYLOAD_AVAILABLE_LABEL: &str = "available";
+/// The payload was still unavailable after a stall
+pub const PAYLOAD_UNAVAILABLE_LABEL: &str = "unavailable";
+
+/// Histogram of the durations of execution stalls waiting for payload availability
+pub static PAYLOAD_UNAVAILABILITY_STALL_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_consensus_payload_unavailability_stall_duration",
+        "Histogram of the durations of execution stalls waiting for payload availability",
+        &["result"]
+    )
+    .unwrap()
+});
+
+/// Count of the number of missing batches that stalled execution
+pub static PAYLOAD_UNAVAILABILITY_MISSING_BATCHES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_consensus_payload_unavailability_missing_batches",
+        "Count of the number of missing batches that stalled execution"
+    )
+    .unwrap()
+});
+
 /// Histogram of the time durations waiting for batch when executing.
 pub static BATCH_WAIT_DURATION: Lazy<DurationHistogram> = Lazy::new(|| {
     DurationHistogram::new(
@@ -1680,6 +1680,15 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                     Ok(())
                 }
             },
+            IncomingRpcRequest::VoteRetrieval(request) => {
+                if let Some(tx) = &self.round_manager_tx {
+                    let event = VerifiedEvent::VoteRetrievalRequest(Box::new(request));
+                    tx.push((peer_id, discriminant(&event)), (peer_id, event))
+                } else {
+                    error!("Round manager not started");
+                    Ok(())
+                }
+            },
             IncomingRpcRequest::BatchRetrieval(request) => {
                 if let Some(tx) = &self.batch_retrieval_tx {
                     tx.push(peer_id, request)
@@ -58,6 +58,8 @@ mod execution_pipeline;
 /// AptosNet interface.
 pub mod network_interface;
 mod payload_manager;
+/// Required by the inspection service
+pub mod payload_unavailability;
 mod qc_aggregator;
 mod transaction_deduper;
 mod transaction_filter;
@@ -9,8 +9,11 @@ use crate::{
 };
 use aptos_config::config::QcAggregatorType;
 use aptos_consensus_types::{
-    common::Round, delayed_qc_msg::DelayedQcMsg, sync_info::SyncInfo,
-    timeout_2chain::TwoChainTimeoutWithPartialSignatures, vote::Vote,
+    common::{Author, Round},
+    delayed_qc_msg::DelayedQcMsg,
+    sync_info::SyncInfo,
+    timeout_2chain::TwoChainTimeoutWithPartialSignatures,
+    vote::Vote,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::{prelude::*, Schema};
@@ -20,7 +23,7 @@ use aptos_types::{
 use futures::future::AbortHandle;
 use futures_channel::mpsc::UnboundedSender;
 use serde::Serialize;
-use std::{fmt, sync::Arc, time::Duration};
+use std::{collections::HashSet, fmt, sync::Arc, time::Duration};
 
 /// A reason for starting a new round: introduced for monitoring / debug purposes.
 #[derive(Serialize, Debug, PartialEq, Eq)]
@@ -153,6 +156,9 @@ pub struct RoundState {
     // a previous deadline expires.
     // Represents as Duration since UNIX_EPOCH.
     current_round_deadline: Duration,
+    // The time at which the current round started.
+    // Represents as Duration since UNIX_EPOCH.
+    current_round_start_time: Duration,
     // Service for timer
     time_service: Arc<dyn TimeService>,
     // To send local timeout events to the subscriber (e.g., SMR)
@@ -213,6 +219,7 @@ impl RoundState {
             highest_ordered_round: 0,
             current_round: 0,
             current_round_deadline: time_service.get_current_timestamp(),
+            current_round_start_time: time_service.get_current_timestamp(),
             time_service,
             timeout_sender,
             pending_votes,
@@ -238,6 +245,18 @@ impl RoundState {
         self.current_round_deadline
     }
 
+    /// Returns the time elapsed since the current round started
+    pub fn current_round_elapsed(&self) -> Duration {
+        self.time_service
+            .get_current_timestamp()
+            .saturating_sub(self.current_round_start_time)
+    }
+
+    /// Returns the authors of the votes received for the current round
+    pub fn current_round_voters(&self) -> HashSet<Author> {
+        self.pending_votes.voters().cloned().collect()
+    }
+
     /// In case the local timeout corresponds to the current round, reset the timeout and
     /// return true. Otherwise ignore and return false.
     pub fn process_local_timeout(&mut self, round: Round) -> bool {
@@ -262,6 +281,7 @@ impl RoundState {
 
             // Start a new round.
             self.current_round = new_round;
+            self.current_round_start_time = self.time_service.get_current_timestamp();
             self.pending_votes = PendingVotes::new(
                 self.time_service.clone(),
                 self.delayed_qc_tx.clone(),
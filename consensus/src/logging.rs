@@ -27,6 +27,7 @@ pub enum LogEvent {
     NetworkReceiveProposal,
     NewEpoch,
     NewRound,
+    PayloadUnavailable,
     ProofOfStoreInit,
     ProofOfStoreReady,
     ProofOfStoreCommit,
@@ -40,8 +41,10 @@ pub enum LogEvent {
     ReceiveProposal,
     ReceiveSyncInfo,
     ReceiveVote,
+    ReceiveVoteRetrieval,
     ReceiveOrderVote,
     RetrieveBlock,
+    RetrieveVote,
     StateSync,
     Timeout,
     Vote,
@@ -31,6 +31,7 @@ use aptos_consensus_types::{
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     vote_msg::VoteMsg,
+    vote_retrieval::{VoteRetrievalRequest, VoteRetrievalResponse},
 };
 use aptos_logger::prelude::*;
 use aptos_network::{
@@ -100,6 +101,15 @@ pub struct IncomingBlockRetrievalRequest {
     pub response_sender: oneshot::Sender<Result<Bytes, RpcError>>,
 }
 
+/// The vote retrieval request is used internally for implementing RPC: the callback is executed
+/// for carrying the response
+#[derive(Debug)]
+pub struct IncomingVoteRetrievalRequest {
+    pub req: VoteRetrievalRequest,
+    pub protocol: ProtocolId,
+    pub response_sender: oneshot::Sender<Result<Bytes, RpcError>>,
+}
+
 #[derive(Debug)]
 pub struct IncomingBatchRetrievalRequest {
     pub req: BatchRequest,
@@ -132,6 +142,7 @@ pub struct IncomingRandGenRequest {
 #[derive(Debug)]
 pub enum IncomingRpcRequest {
     BlockRetrieval(IncomingBlockRetrievalRequest),
+    VoteRetrieval(IncomingVoteRetrievalRequest),
     BatchRetrieval(IncomingBatchRetrievalRequest),
     DAGRequest(IncomingDAGRequest),
     CommitRequest(IncomingCommitRequest),
@@ -145,6 +156,7 @@ impl IncomingRpcRequest {
             IncomingRpcRequest::DAGRequest(req) => Some(req.req.epoch()),
             IncomingRpcRequest::RandGenRequest(req) => Some(req.req.epoch()),
             IncomingRpcRequest::CommitRequest(req) => req.req.epoch(),
+            IncomingRpcRequest::VoteRetrieval(req) => Some(req.req.epoch()),
             IncomingRpcRequest::BlockRetrieval(_) => None,
         }
     }
@@ -257,6 +269,43 @@ impl NetworkSender {
         Ok(response)
     }
 
+    /// Tries to retrieve the vote of the given peer for a stalled round: the function returns
+    /// a future that is fulfilled with VoteRetrievalResponse.
+    pub async fn request_vote(
+        &self,
+        retrieval_request: VoteRetrievalRequest,
+        from: Author,
+        timeout: Duration,
+    ) -> anyhow::Result<VoteRetrievalResponse> {
+        fail_point!("consensus::send::any", |_| {
+            Err(anyhow::anyhow!("Injected error in request_vote"))
+        });
+
+        ensure!(from != self.author, "Retrieve vote from self");
+        let msg = ConsensusMsg::VoteRetrievalRequest(Box::new(retrieval_request.clone()));
+        let response_msg = monitor!("vote_retrieval", self.send_rpc(from, msg, timeout).await)?;
+        let response = match response_msg {
+            ConsensusMsg::VoteRetrievalResponse(resp) => *resp,
+            _ => return Err(anyhow!("Invalid response to request")),
+        };
+        response.verify(&retrieval_request)?;
+
+        Ok(response)
+    }
+
+    /// Delivers the given message to ourself, as if it was received from the given peer.
+    /// This is used to process the votes (and sync info) retrieved from the peer, which
+    /// are then verified like any other message from the peer.
+    pub async fn deliver_to_self_from(&self, from: Author, msg: ConsensusMsg) {
+        let mut self_sender = self.self_sender.clone();
+        if let Err(err) = self_sender.send(Event::Message(from, msg)).await {
+            error!(
+                "Error delivering the message from {} to self: {:?}",
+                from, err
+            );
+        }
+    }
+
     pub async fn send_rpc_to_self(
         &self,
         msg: ConsensusMsg,
@@ -808,6 +857,19 @@ impl NetworkTask {
                                 response_sender: callback,
                             })
                         },
+                        ConsensusMsg::VoteRetrievalRequest(request) => {
+                            debug!(
+                                remote_peer = peer_id,
+                                event = LogEvent::ReceiveVoteRetrieval,
+                                "{}",
+                                request
+                            );
+                            IncomingRpcRequest::VoteRetrieval(IncomingVoteRetrievalRequest {
+                                req: *request,
+                                protocol,
+                                response_sender: callback,
+                            })
+                        },
                         ConsensusMsg::BatchRequestMsg(request) => {
                             debug!(
                                 remote_peer = peer_id,
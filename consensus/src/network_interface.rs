@@ -20,6 +20,7 @@ use aptos_consensus_types::{
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     vote_msg::VoteMsg,
+    vote_retrieval::{VoteRetrievalRequest, VoteRetrievalResponse},
 };
 use aptos_network::{
     application::{error::Error, interface::NetworkClientInterface},
@@ -80,6 +81,10 @@ pub enum ConsensusMsg {
     /// OrderVoteMsg is the struct that is broadcasted by a validator on receiving quorum certificate
     /// on a block.
     OrderVoteMsg(Box<OrderVoteMsg>),
+    /// RPC to get the vote of a peer for a stalled round.
+    VoteRetrievalRequest(Box<VoteRetrievalRequest>),
+    /// Carries the retrieved vote (if any) and the sync info of the peer.
+    VoteRetrievalResponse(Box<VoteRetrievalResponse>),
 }
 
 /// Network type for consensus
@@ -107,6 +112,8 @@ impl ConsensusMsg {
             ConsensusMsg::CommitMessage(_) => "CommitMessage",
             ConsensusMsg::RandGenMessage(_) => "RandGenMessage",
             ConsensusMsg::BatchResponseV2(_) => "BatchResponseV2",
+            ConsensusMsg::VoteRetrievalRequest(_) => "VoteRetrievalRequest",
+            ConsensusMsg::VoteRetrievalResponse(_) => "VoteRetrievalResponse",
         }
     }
 }
@@ -7,7 +7,7 @@ use crate::{
         observer::payload_store::BlockPayloadStatus,
         publisher::consensus_publisher::ConsensusPublisher,
     },
-    counters,
+    counters, payload_unavailability,
     quorum_store::{batch_store::BatchReader, quorum_store_coordinator::CoordinatorCommand},
 };
 use aptos_consensus_types::{
@@ -30,6 +30,7 @@ use std::{
     collections::{btree_map::Entry, BTreeMap},
     ops::Deref,
     sync::Arc,
+    time::Instant,
 };
 use tokio::sync::oneshot;
 
@@ -532,7 +533,30 @@ async fn process_payload_helper<T: TDataInfo>(
         (data_fut.iteration, data_fut.fut.clone())
     };
 
+    // If the data isn't ready, execution stalls waiting for the missing batches
+    let missing_batch_digests = if fut.peek().is_none() {
+        get_missing_batch_digests(
+            batch_reader.as_ref(),
+            data_ptr
+                .batch_summary
+                .iter()
+                .map(|data_info| data_info.info().digest()),
+        )
+    } else {
+        vec![]
+    };
+
+    let stall_start_time = Instant::now();
     let result = fut.await;
+    if !missing_batch_digests.is_empty() {
+        record_payload_stall(
+            block,
+            missing_batch_digests,
+            stall_start_time,
+            result.is_ok(),
+        );
+    }
+
     // If error, reschedule before returning the result
     if result.is_err() {
         let mut data_fut_guard = data_ptr.data_fut.lock();
@@ -563,6 +587,34 @@ async fn process_payload_helper<T: TDataInfo>(
     result
 }
 
+/// Returns the digests of the given batches that are not available locally
+fn get_missing_batch_digests<'a>(
+    batch_reader: &dyn BatchReader,
+    digests: impl Iterator<Item = &'a HashValue>,
+) -> Vec<HashValue> {
+    digests
+        .filter(|digest| batch_reader.exists(digest).is_none())
+        .cloned()
+        .collect()
+}
+
+/// Records that execution of the given block stalled waiting for the missing batches
+fn record_payload_stall(
+    block: &Block,
+    missing_batch_digests: Vec<HashValue>,
+    stall_start_time: Instant,
+    payload_available: bool,
+) {
+    payload_unavailability::record_payload_stall(
+        block.epoch(),
+        block.round(),
+        block.id(),
+        missing_batch_digests,
+        stall_start_time.elapsed(),
+        payload_available,
+    );
+}
+
 /// This is deprecated. Use `process_payload_helper` instead after migrating to
 /// OptQuorumStore payload
 async fn process_payload(
@@ -591,8 +643,22 @@ async fn process_payload(
                     block.round()
                 );
             }
+            let missing_batch_digests = get_missing_batch_digests(
+                batch_reader.as_ref(),
+                receivers.iter().map(|(digest, _)| digest),
+            );
+            let stall_start_time = Instant::now();
             for (digest, rx) in receivers {
-                match rx.await {
+                let result = rx.await;
+                if !matches!(result, Ok(Ok(_))) && !missing_batch_digests.is_empty() {
+                    record_payload_stall(
+                        block,
+                        missing_batch_digests.clone(),
+                        stall_start_time,
+                        false,
+                    );
+                }
+                match result {
                     Err(e) => {
                         // We probably advanced epoch already.
                         warn!(
@@ -647,6 +713,9 @@ async fn process_payload(
                     },
                 }
             }
+            if !missing_batch_digests.is_empty() {
+                record_payload_stall(block, missing_batch_digests, stall_start_time, true);
+            }
             let ret: Vec<SignedTransaction> = vec_ret.into_iter().flatten().collect();
             // execution asks for the data twice, so data is cached here for the second time.
             proof_with_data
@@ -0,0 +1,292 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Telemetry for the slow path of block execution, i.e., when execution stalls waiting
+//! for quorum store payloads (batches) that are not available locally. Each stall records
+//! the missing batches, the peers that were asked for them and the duration of the stall.
+//! The stalls are aggregated into a report (exposed via the inspection service) to guide
+//! improvements to batch fetching.
+
+use crate::{
+    counters,
+    logging::{LogEvent, LogSchema},
+};
+use aptos_consensus_types::common::Round;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::PeerId;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    time::Duration,
+};
+
+/// The maximum number of recent stalls held by the report
+const MAX_RECENT_STALLS: usize = 50;
+
+/// The maximum number of batches for which the requested peers are tracked
+const MAX_TRACKED_BATCH_REQUESTS: usize = 1_000;
+
+/// The interval (in seconds) at which the stalls are logged
+const STALL_LOG_INTERVAL_SECS: u64 = 1;
+
+/// The payload unavailability tracker (shared across epochs)
+static PAYLOAD_UNAVAILABILITY_TRACKER: Lazy<Mutex<PayloadUnavailabilityTracker>> =
+    Lazy::new(|| Mutex::new(PayloadUnavailabilityTracker::default()));
+
+/// A single stall of block execution waiting for payload availability
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PayloadStall {
+    pub epoch: u64,
+    pub round: Round,
+    pub block_id: HashValue,
+    /// The digests of the batches that were not available locally
+    pub missing_batch_digests: Vec<HashValue>,
+    /// The peers that were asked for the missing batches
+    pub requested_peers: BTreeSet<PeerId>,
+    /// The duration of the stall (in milliseconds)
+    pub stall_duration_ms: u64,
+    /// Whether the payload became available (otherwise, fetching the batches failed)
+    pub payload_available: bool,
+}
+
+/// An aggregated report of the stalls waiting for payload availability
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct PayloadUnavailabilityReport {
+    /// The total number of stalls
+    pub num_stalls: u64,
+    /// The number of stalls where fetching the missing batches failed
+    pub num_failed_stalls: u64,
+    /// The total number of missing batches (across all stalls)
+    pub num_missing_batches: u64,
+    /// The total duration of all stalls (in milliseconds)
+    pub total_stall_duration_ms: u64,
+    /// The duration of the longest stall (in milliseconds)
+    pub max_stall_duration_ms: u64,
+    /// The number of stalls in which each peer was asked for missing batches
+    pub peer_request_counts: BTreeMap<PeerId, u64>,
+    /// The most recent stalls (ordered from oldest to newest)
+    pub recent_stalls: VecDeque<PayloadStall>,
+}
+
+impl PayloadUnavailabilityReport {
+    /// Aggregates the given stall into the report
+    fn record_stall(&mut self, stall: PayloadStall) {
+        self.num_stalls += 1;
+        if !stall.payload_available {
+            self.num_failed_stalls += 1;
+        }
+        self.num_missing_batches += stall.missing_batch_digests.len() as u64;
+        self.total_stall_duration_ms += stall.stall_duration_ms;
+        self.max_stall_duration_ms = self.max_stall_duration_ms.max(stall.stall_duration_ms);
+        for peer in &stall.requested_peers {
+            *self.peer_request_counts.entry(*peer).or_default() += 1;
+        }
+
+        // Only hold the most recent stalls
+        self.recent_stalls.push_back(stall);
+        while self.recent_stalls.len() > MAX_RECENT_STALLS {
+            self.recent_stalls.pop_front();
+        }
+    }
+}
+
+/// Tracks the peers asked for missing batches, and aggregates the stalls
+#[derive(Debug, Default)]
+struct PayloadUnavailabilityTracker {
+    requested_peers: HashMap<HashValue, BTreeSet<PeerId>>,
+    request_order: VecDeque<HashValue>, // The batch digests (in the order first requested)
+    report: PayloadUnavailabilityReport,
+}
+
+impl PayloadUnavailabilityTracker {
+    /// Records that the given peers were asked for the specified batch
+    fn record_batch_request(&mut self, digest: HashValue, peers: &[PeerId]) {
+        if !self.requested_peers.contains_key(&digest) {
+            // Evict the oldest batches to bound the number of tracked batches
+            while self.request_order.len() >= MAX_TRACKED_BATCH_REQUESTS {
+                if let Some(oldest_digest) = self.request_order.pop_front() {
+                    self.requested_peers.remove(&oldest_digest);
+                }
+            }
+            self.request_order.push_back(digest);
+        }
+
+        self.requested_peers
+            .entry(digest)
+            .or_default()
+            .extend(peers.iter().cloned());
+    }
+
+    /// Records a stall waiting for the given missing batches, and returns the stall
+    fn record_stall(
+        &mut self,
+        epoch: u64,
+        round: Round,
+        block_id: HashValue,
+        missing_batch_digests: Vec<HashValue>,
+        stall_duration: Duration,
+        payload_available: bool,
+    ) -> PayloadStall {
+        let requested_peers = missing_batch_digests
+            .iter()
+            .filter_map(|digest| self.requested_peers.get(digest))
+            .flatten()
+            .cloned()
+            .collect();
+        let stall = PayloadStall {
+            epoch,
+            round,
+            block_id,
+            missing_batch_digests,
+            requested_peers,
+            stall_duration_ms: stall_duration.as_millis() as u64,
+            payload_available,
+        };
+
+        self.report.record_stall(stall.clone());
+        stall
+    }
+}
+
+/// Records that the given peers were asked for the specified (missing) batch
+pub(crate) fn record_batch_request(digest: HashValue, peers: &[PeerId]) {
+    PAYLOAD_UNAVAILABILITY_TRACKER
+        .lock()
+        .record_batch_request(digest, peers);
+}
+
+/// Records that execution of the given block stalled waiting for the missing batches
+pub(crate) fn record_payload_stall(
+    epoch: u64,
+    round: Round,
+    block_id: HashValue,
+    missing_batch_digests: Vec<HashValue>,
+    stall_duration: Duration,
+    payload_available: bool,
+) {
+    let stall = PAYLOAD_UNAVAILABILITY_TRACKER.lock().record_stall(
+        epoch,
+        round,
+        block_id,
+        missing_batch_digests,
+        stall_duration,
+        payload_available,
+    );
+
+    // Update the metrics
+    let result_label = if stall.payload_available {
+        counters::PAYLOAD_AVAILABLE_LABEL
+    } else {
+        counters::PAYLOAD_UNAVAILABLE_LABEL
+    };
+    counters::PAYLOAD_UNAVAILABILITY_STALL_DURATION
+        .with_label_values(&[result_label])
+        .observe(stall_duration.as_secs_f64());
+    counters::PAYLOAD_UNAVAILABILITY_MISSING_BATCHES
+        .inc_by(stall.missing_batch_digests.len() as u64);
+
+    // Log the stall (the report holds all recent stalls)
+    sample!(
+        SampleRate::Duration(Duration::from_secs(STALL_LOG_INTERVAL_SECS)),
+        warn!(
+            LogSchema::new(LogEvent::PayloadUnavailable)
+                .epoch(stall.epoch)
+                .round(stall.round)
+                .id(stall.block_id),
+            missing_batch_digests = ?stall.missing_batch_digests,
+            requested_peers = ?stall.requested_peers,
+            stall_duration_ms = stall.stall_duration_ms,
+            payload_available = stall.payload_available,
+            "Block execution stalled waiting for payload availability!"
+        )
+    );
+}
+
+/// Returns the aggregated report of the stalls waiting for payload availability
+pub fn get_payload_unavailability_report() -> PayloadUnavailabilityReport {
+    PAYLOAD_UNAVAILABILITY_TRACKER.lock().report.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PayloadUnavailabilityTracker, MAX_RECENT_STALLS, MAX_TRACKED_BATCH_REQUESTS};
+    use aptos_crypto::HashValue;
+    use aptos_types::PeerId;
+    use std::time::Duration;
+
+    #[test]
+    fn test_record_stalls() {
+        let mut tracker = PayloadUnavailabilityTracker::default();
+
+        // Record requests for several batches
+        let (digest_1, digest_2) = (HashValue::random(), HashValue::random());
+        let (peer_1, peer_2, peer_3) = (PeerId::random(), PeerId::random(), PeerId::random());
+        tracker.record_batch_request(digest_1, &[peer_1, peer_2]);
+        tracker.record_batch_request(digest_1, &[peer_3]);
+        tracker.record_batch_request(digest_2, &[peer_1]);
+
+        // Record a stall and verify the requested peers
+        let stall = tracker.record_stall(
+            1,
+            10,
+            HashValue::random(),
+            vec![digest_1],
+            Duration::from_millis(300),
+            true,
+        );
+        assert_eq!(stall.requested_peers.len(), 3);
+        assert_eq!(stall.stall_duration_ms, 300);
+
+        // Record a failed stall and verify the aggregated report
+        tracker.record_stall(
+            1,
+            11,
+            HashValue::random(),
+            vec![digest_2, HashValue::random()],
+            Duration::from_millis(100),
+            false,
+        );
+        let report = &tracker.report;
+        assert_eq!(report.num_stalls, 2);
+        assert_eq!(report.num_failed_stalls, 1);
+        assert_eq!(report.num_missing_batches, 3);
+        assert_eq!(report.total_stall_duration_ms, 400);
+        assert_eq!(report.max_stall_duration_ms, 300);
+        assert_eq!(report.peer_request_counts.get(&peer_1), Some(&2));
+        assert_eq!(report.peer_request_counts.get(&peer_3), Some(&1));
+        assert_eq!(report.recent_stalls.len(), 2);
+    }
+
+    #[test]
+    fn test_bounded_tracking() {
+        let mut tracker = PayloadUnavailabilityTracker::default();
+
+        // Record requests for many batches and verify the number of tracked batches is bounded
+        let peer = PeerId::random();
+        let digests: Vec<_> = (0..MAX_TRACKED_BATCH_REQUESTS + 10)
+            .map(|_| HashValue::random())
+            .collect();
+        for digest in &digests {
+            tracker.record_batch_request(*digest, &[peer]);
+        }
+        assert_eq!(tracker.requested_peers.len(), MAX_TRACKED_BATCH_REQUESTS);
+        assert!(!tracker.requested_peers.contains_key(&digests[0]));
+
+        // Record many stalls and verify the number of recent stalls is bounded
+        for round in 0..(MAX_RECENT_STALLS + 10) {
+            tracker.record_stall(
+                1,
+                round as u64,
+                HashValue::random(),
+                vec![],
+                Duration::from_millis(1),
+                true,
+            );
+        }
+        assert_eq!(tracker.report.recent_stalls.len(), MAX_RECENT_STALLS);
+        assert_eq!(tracker.report.recent_stalls[0].round, 10);
+    }
+}
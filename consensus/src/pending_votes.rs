@@ -324,6 +324,11 @@ impl PendingVotes {
         }
     }
 
+    /// Returns the authors of the votes received for the round
+    pub fn voters(&self) -> impl Iterator<Item = &Author> {
+        self.author_to_vote.keys()
+    }
+
     pub fn drain_votes(
         &mut self,
     ) -> (
@@ -4,6 +4,7 @@
 use crate::{
     monitor,
     network::QuorumStoreSender,
+    payload_unavailability,
     quorum_store::{
         counters,
         types::{BatchRequest, BatchResponse, PersistedValue},
@@ -154,6 +155,7 @@ impl<T: QuorumStoreSender + Sync + 'static> BatchRequester<T> {
                     _ = interval.tick() => {
                         // send batch request to a set of peers of size request_num_peers
                         if let Some(request_peers) = request_state.next_request_peers(request_num_peers) {
+                            payload_unavailability::record_batch_request(digest, &request_peers);
                             for peer in request_peers {
                                 futures.push(network_sender.request_batch(request.clone(), peer, rpc_timeout));
                             }
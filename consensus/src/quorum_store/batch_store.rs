@@ -12,11 +12,14 @@ use crate::{
     },
 };
 use anyhow::bail;
-use aptos_consensus_types::proof_of_store::SignedBatchInfo;
+use aptos_consensus_types::proof_of_store::{BatchInfo, ProofCache, ProofOfStore, SignedBatchInfo};
 use aptos_crypto::HashValue;
 use aptos_executor_types::{ExecutorError, ExecutorResult};
 use aptos_logger::prelude::*;
-use aptos_types::{transaction::SignedTransaction, validator_signer::ValidatorSigner, PeerId};
+use aptos_types::{
+    transaction::SignedTransaction, validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier, PeerId,
+};
 use dashmap::{
     mapref::entry::Entry::{Occupied, Vacant},
     DashMap,
@@ -147,6 +150,7 @@ impl BatchStore {
             .get_all_batches()
             .expect("failed to read data from db");
         let mut expired_keys = Vec::new();
+        let mut over_quota_keys = Vec::new();
         trace!(
             "QS: Batchreader {} {} {}",
             db_content.len(),
@@ -164,16 +168,35 @@ impl BatchStore {
 
             if last_certified_time >= expiration {
                 expired_keys.push(digest);
-            } else {
-                batch_store
-                    .insert_to_cache(&value)
-                    .expect("Storage limit exceeded upon BatchReader construction");
+            } else if let Err(error) = batch_store.insert_to_cache(&value) {
+                // The quotas may have been lowered since the batch was persisted
+                warn!(
+                    "QS: Discarding batch {} upon recovery, as the storage limit is exceeded: {:?}",
+                    digest, error
+                );
+                over_quota_keys.push(digest);
             }
         }
         trace!(
             "QS: Batchreader recovery expired keys len {}",
             expired_keys.len()
         );
+        counters::inc_db_recovery_count(
+            counters::RECOVERY_BATCH_LABEL,
+            counters::RECOVERED_LABEL,
+            batch_store.db_cache.len(),
+        );
+        counters::inc_db_recovery_count(
+            counters::RECOVERY_BATCH_LABEL,
+            counters::DISCARDED_EXPIRED_LABEL,
+            expired_keys.len(),
+        );
+        counters::inc_db_recovery_count(
+            counters::RECOVERY_BATCH_LABEL,
+            counters::DISCARDED_QUOTA_EXCEEDED_LABEL,
+            over_quota_keys.len(),
+        );
+        expired_keys.extend(over_quota_keys);
         db_clone
             .delete_batches(expired_keys)
             .expect("Deletion of expired keys should not fail");
@@ -185,6 +208,106 @@ impl BatchStore {
         *self.epoch.get().expect("Epoch should always be set")
     }
 
+    /// Recovers the signed proofs that were persisted before a restart (or crash).
+    /// Must be called after the batch store is constructed (i.e., after the batches
+    /// are recovered). A proof is only recovered if it belongs to the current epoch,
+    /// has not expired, is consistent with the batch store (i.e., the same batch was
+    /// recovered) and carries a valid multi-signature (which is then added to the
+    /// proof cache). All other proofs are discarded from the db.
+    pub(crate) fn recover_proofs(
+        &self,
+        validator: &ValidatorVerifier,
+        proof_cache: &ProofCache,
+    ) -> Vec<ProofOfStore> {
+        let db_proofs = self
+            .db
+            .get_all_proofs()
+            .expect("failed to read proofs from db");
+        let num_db_proofs = db_proofs.len();
+
+        let mut recovered_proofs = Vec::new();
+        let mut discarded_digests = Vec::new();
+        for (digest, proof) in db_proofs {
+            let discard_label = if proof.epoch() != self.epoch() {
+                Some(counters::DISCARDED_STALE_EPOCH_LABEL)
+            } else if proof.expiration() <= self.last_certified_time() {
+                Some(counters::DISCARDED_EXPIRED_LABEL)
+            } else if !self.contains_batch(proof.info()) {
+                Some(counters::DISCARDED_MISSING_BATCH_LABEL)
+            } else if proof.verify(validator, proof_cache).is_err() {
+                Some(counters::DISCARDED_INVALID_SIGNATURE_LABEL)
+            } else {
+                None
+            };
+
+            match discard_label {
+                Some(discard_label) => {
+                    trace!("QS: discarding proof upon recovery, digest {}", digest);
+                    counters::inc_db_recovery_count(
+                        counters::RECOVERY_PROOF_LABEL,
+                        discard_label,
+                        1,
+                    );
+                    discarded_digests.push(digest);
+                },
+                None => recovered_proofs.push(proof),
+            }
+        }
+        counters::inc_db_recovery_count(
+            counters::RECOVERY_PROOF_LABEL,
+            counters::RECOVERED_LABEL,
+            recovered_proofs.len(),
+        );
+        info!(
+            "QS: recovered {} of {} persisted proofs in epoch {}",
+            recovered_proofs.len(),
+            num_db_proofs,
+            self.epoch()
+        );
+
+        if let Err(e) = self.db.delete_proofs(discarded_digests) {
+            warn!("QS: failed to delete discarded proofs: {:?}", e);
+        }
+        recovered_proofs
+    }
+
+    /// Persists the given proofs, so that they can be recovered after a restart.
+    /// Proofs are only persisted for batches that are (already) in the batch store,
+    /// which ensures that a recovered proof always has its batch available locally.
+    pub(crate) fn persist_proofs(&self, proofs: &[ProofOfStore]) {
+        let proofs_to_persist: Vec<_> = proofs
+            .iter()
+            .filter(|proof| self.contains_batch(proof.info()))
+            .cloned()
+            .collect();
+        if proofs_to_persist.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.db.save_proofs(proofs_to_persist) {
+            warn!("QS: failed to persist proofs: {:?}", e);
+        }
+    }
+
+    /// Deletes the persisted proofs of the given (e.g., committed) batches
+    pub(crate) fn delete_proofs(&self, batches: &[BatchInfo]) {
+        if batches.is_empty() {
+            return;
+        }
+
+        let digests = batches.iter().map(|batch| *batch.digest()).collect();
+        if let Err(e) = self.db.delete_proofs(digests) {
+            warn!("QS: failed to delete proofs: {:?}", e);
+        }
+    }
+
+    /// Returns true iff the batch store contains the given batch
+    fn contains_batch(&self, batch_info: &BatchInfo) -> bool {
+        self.db_cache
+            .get(batch_info.digest())
+            .map_or(false, |value| value.batch_info() == batch_info)
+    }
+
     fn free_quota(&self, value: PersistedValue) {
         let mut quota_manager = self
             .peer_quota
@@ -500,3 +623,67 @@ impl<T: QuorumStoreSender + Clone + Send + Sync + 'static> BatchReader for Batch
 pub trait BatchWriter: Send + Sync {
     fn persist(&self, persist_requests: Vec<PersistedValue>) -> Vec<SignedBatchInfo>;
 }
+
+/// An update to the persisted proofs (see [`ProofPersister`])
+enum ProofUpdate {
+    Persist(Vec<ProofOfStore>),
+    Delete(Vec<BatchInfo>),
+}
+
+/// Persists (and deletes) proofs on a dedicated thread, so that the db writes are kept
+/// off the consensus path. The updates that are pending when the thread wakes up are
+/// coalesced, i.e., written to the db in (at most) one write per kind. The thread exits
+/// once the persister is dropped.
+pub(crate) struct ProofPersister {
+    update_sender: std::sync::mpsc::Sender<ProofUpdate>,
+}
+
+impl ProofPersister {
+    pub(crate) fn new(batch_store: Arc<BatchStore>) -> Self {
+        let (update_sender, update_receiver) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("qs-proof-persister".into())
+            .spawn(move || Self::process_updates(batch_store, update_receiver))
+            .expect("Failed to spawn the proof persister thread");
+        Self { update_sender }
+    }
+
+    /// Persists the given proofs asynchronously (see [`BatchStore::persist_proofs`])
+    pub(crate) fn persist_proofs(&self, proofs: Vec<ProofOfStore>) {
+        self.send_update(ProofUpdate::Persist(proofs));
+    }
+
+    /// Deletes the proofs of the given batches asynchronously
+    /// (see [`BatchStore::delete_proofs`]).
+    pub(crate) fn delete_proofs(&self, batches: Vec<BatchInfo>) {
+        self.send_update(ProofUpdate::Delete(batches));
+    }
+
+    fn send_update(&self, update: ProofUpdate) {
+        if self.update_sender.send(update).is_err() {
+            warn!("QS: failed to send the proof update, the proof persister has stopped");
+        }
+    }
+
+    fn process_updates(
+        batch_store: Arc<BatchStore>,
+        update_receiver: std::sync::mpsc::Receiver<ProofUpdate>,
+    ) {
+        while let Ok(update) = update_receiver.recv() {
+            // Coalesce all pending updates
+            let mut proofs_to_persist = vec![];
+            let mut batches_to_delete = vec![];
+            for update in std::iter::once(update).chain(update_receiver.try_iter()) {
+                match update {
+                    ProofUpdate::Persist(proofs) => proofs_to_persist.extend(proofs),
+                    ProofUpdate::Delete(batches) => batches_to_delete.extend(batches),
+                }
+            }
+
+            // Persist the proofs before the deletions, so that the proofs of
+            // committed batches are never recovered (and proposed again).
+            batch_store.persist_proofs(&proofs_to_persist);
+            batch_store.delete_proofs(&batches_to_delete);
+        }
+    }
+}
@@ -23,6 +23,16 @@ pub const CALLBACK_SUCCESS_LABEL: &str = "callback_success";
 pub const POS_EXPIRED_LABEL: &str = "expired";
 pub const POS_DUPLICATE_LABEL: &str = "duplicate";
 
+pub const RECOVERY_BATCH_LABEL: &str = "batch";
+pub const RECOVERY_PROOF_LABEL: &str = "proof";
+
+pub const RECOVERED_LABEL: &str = "recovered";
+pub const DISCARDED_EXPIRED_LABEL: &str = "discarded_expired";
+pub const DISCARDED_QUOTA_EXCEEDED_LABEL: &str = "discarded_quota_exceeded";
+pub const DISCARDED_STALE_EPOCH_LABEL: &str = "discarded_stale_epoch";
+pub const DISCARDED_MISSING_BATCH_LABEL: &str = "discarded_missing_batch";
+pub const DISCARDED_INVALID_SIGNATURE_LABEL: &str = "discarded_invalid_signature";
+
 static TRANSACTION_COUNT_BUCKETS: Lazy<Vec<f64>> = Lazy::new(|| {
     exponential_buckets(
         /*start=*/ 1.5, /*factor=*/ 1.5, /*count=*/ 25,
@@ -584,6 +594,22 @@ pub fn inc_rejected_pos_count(reason: &str) {
     REJECTED_POS_COUNT.with_label_values(&[reason]).inc();
 }
 
+/// Count of the batches and proofs recovered (or discarded) from the QS DB on startup.
+static DB_RECOVERY_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "quorum_store_db_recovery_count",
+        "Count of the batches and proofs recovered or discarded from the QS DB on startup.",
+        &["kind", "outcome"]
+    )
+    .unwrap()
+});
+
+pub fn inc_db_recovery_count(kind: &str, outcome: &str, count: usize) {
+    DB_RECOVERY_COUNT
+        .with_label_values(&[kind, outcome])
+        .inc_by(count as u64);
+}
+
 /// Count of the received batches since last restart.
 pub static RECEIVED_REMOTE_BATCH_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::batch_store::BatchStore;
+use super::batch_store::{BatchStore, ProofPersister};
 use crate::{
     monitor,
     quorum_store::{batch_generator::BackPressure, batch_proof_queue::BatchProofQueue, counters},
@@ -28,6 +28,7 @@ pub enum ProofManagerCommand {
 }
 
 pub struct ProofManager {
+    proof_persister: ProofPersister,
     batch_proof_queue: BatchProofQueue,
     back_pressure_total_txn_limit: u64,
     remaining_total_txn_num: u64,
@@ -47,6 +48,7 @@ impl ProofManager {
         enable_opt_quorum_store: bool,
     ) -> Self {
         Self {
+            proof_persister: ProofPersister::new(batch_store.clone()),
             batch_proof_queue: BatchProofQueue::new(my_peer_id, batch_store),
             back_pressure_total_txn_limit,
             remaining_total_txn_num: 0,
@@ -58,6 +60,17 @@ impl ProofManager {
     }
 
     pub(crate) fn receive_proofs(&mut self, proofs: Vec<ProofOfStore>) {
+        // Persist the proofs (in the background), so that they can be recovered after a crash
+        self.proof_persister.persist_proofs(proofs.clone());
+        self.insert_proofs(proofs);
+    }
+
+    /// Inserts the proofs recovered from the db (on startup) into the proof queue
+    pub(crate) fn receive_recovered_proofs(&mut self, proofs: Vec<ProofOfStore>) {
+        self.insert_proofs(proofs);
+    }
+
+    fn insert_proofs(&mut self, proofs: Vec<ProofOfStore>) {
         for proof in proofs.into_iter() {
             self.batch_proof_queue.insert_proof(proof);
         }
@@ -81,6 +94,8 @@ impl ProofManager {
             "QS: got clean request from execution at block timestamp {}",
             block_timestamp
         );
+        // Committed proofs should never be recovered (and proposed again)
+        self.proof_persister.delete_proofs(batches.clone());
         self.batch_proof_queue.mark_committed(batches);
         self.batch_proof_queue
             .handle_updated_block_timestamp(block_timestamp);
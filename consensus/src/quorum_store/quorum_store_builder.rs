@@ -337,6 +337,14 @@ impl InnerBuilder {
             );
         }
 
+        // Recover the proofs persisted before the restart (if any). This must
+        // happen before the proof cache is handed to the proof coordinator.
+        let recovered_proofs = self
+            .batch_store
+            .clone()
+            .unwrap()
+            .recover_proofs(&self.verifier, &self.proof_cache);
+
         let proof_coordinator_cmd_rx = self.proof_coordinator_cmd_rx.take().unwrap();
         let proof_coordinator = ProofCoordinator::new(
             self.config.proof_timeout_ms,
@@ -356,7 +364,7 @@ impl InnerBuilder {
         );
 
         let proof_manager_cmd_rx = self.proof_manager_cmd_rx.take().unwrap();
-        let proof_manager = ProofManager::new(
+        let mut proof_manager = ProofManager::new(
             self.author,
             self.config.back_pressure.backlog_txn_limit_count,
             self.config
@@ -367,6 +375,7 @@ impl InnerBuilder {
             self.config.allow_batches_without_pos_in_proposal,
             self.config.enable_opt_quorum_store,
         );
+        proof_manager.receive_recovered_proofs(recovered_proofs);
         spawn_named!(
             "proof_manager",
             proof_manager.start(
@@ -4,12 +4,15 @@
 use crate::{
     error::DbError,
     quorum_store::{
-        schema::{BatchIdSchema, BatchSchema, BATCH_CF_NAME, BATCH_ID_CF_NAME},
+        schema::{
+            BatchIdSchema, BatchSchema, ProofOfStoreSchema, BATCH_CF_NAME, BATCH_ID_CF_NAME,
+            PROOF_OF_STORE_CF_NAME,
+        },
         types::PersistedValue,
     },
 };
 use anyhow::Result;
-use aptos_consensus_types::proof_of_store::BatchId;
+use aptos_consensus_types::proof_of_store::{BatchId, ProofOfStore};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
 use aptos_schemadb::{Options, SchemaBatch, DB};
@@ -29,6 +32,12 @@ pub trait QuorumStoreStorage: Sync + Send {
     fn clean_and_get_batch_id(&self, current_epoch: u64) -> Result<Option<BatchId>, DbError>;
 
     fn save_batch_id(&self, epoch: u64, batch_id: BatchId) -> Result<(), DbError>;
+
+    fn save_proofs(&self, proofs: Vec<ProofOfStore>) -> Result<(), DbError>;
+
+    fn get_all_proofs(&self) -> Result<HashMap<HashValue, ProofOfStore>>;
+
+    fn delete_proofs(&self, digests: Vec<HashValue>) -> Result<(), DbError>;
 }
 
 /// The name of the quorum store db file
@@ -40,7 +49,7 @@ pub struct QuorumStoreDB {
 
 impl QuorumStoreDB {
     pub(crate) fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        let column_families = vec![BATCH_CF_NAME, BATCH_ID_CF_NAME];
+        let column_families = vec![BATCH_CF_NAME, BATCH_ID_CF_NAME, PROOF_OF_STORE_CF_NAME];
 
         // TODO: this fails twins tests because it assumes a unique path per process
         let path = db_root_path.as_ref().join(QUORUM_STORE_DB_NAME);
@@ -67,6 +76,9 @@ impl QuorumStoreStorage for QuorumStoreDB {
         for digest in digests.iter() {
             trace!("QS: db delete digest {}", digest);
             batch.delete::<BatchSchema>(digest)?;
+            // Delete the proof atomically with the batch, so that a proof
+            // is never recovered without its batch (e.g., after a crash).
+            batch.delete::<ProofOfStoreSchema>(digest)?;
         }
         self.db.write_schemas(batch)?;
         Ok(())
@@ -120,6 +132,33 @@ impl QuorumStoreStorage for QuorumStoreDB {
     fn save_batch_id(&self, epoch: u64, batch_id: BatchId) -> Result<(), DbError> {
         Ok(self.db.put::<BatchIdSchema>(&epoch, &batch_id)?)
     }
+
+    fn save_proofs(&self, proofs: Vec<ProofOfStore>) -> Result<(), DbError> {
+        let batch = SchemaBatch::new();
+        for proof in proofs.iter() {
+            trace!("QS: db persists proof for digest {}", proof.digest());
+            batch.put::<ProofOfStoreSchema>(proof.digest(), proof)?;
+        }
+        self.db.write_schemas(batch)?;
+        Ok(())
+    }
+
+    fn get_all_proofs(&self) -> Result<HashMap<HashValue, ProofOfStore>> {
+        let mut iter = self.db.iter::<ProofOfStoreSchema>()?;
+        iter.seek_to_first();
+        iter.map(|res| res.map_err(Into::into))
+            .collect::<Result<HashMap<HashValue, ProofOfStore>>>()
+    }
+
+    fn delete_proofs(&self, digests: Vec<HashValue>) -> Result<(), DbError> {
+        let batch = SchemaBatch::new();
+        for digest in digests.iter() {
+            trace!("QS: db delete proof for digest {}", digest);
+            batch.delete::<ProofOfStoreSchema>(digest)?;
+        }
+        self.db.write_schemas(batch)?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -170,5 +209,17 @@ pub mod mock {
         fn save_batch_id(&self, _: u64, _: BatchId) -> Result<(), DbError> {
             Ok(())
         }
+
+        fn save_proofs(&self, _: Vec<ProofOfStore>) -> Result<(), DbError> {
+            Ok(())
+        }
+
+        fn get_all_proofs(&self) -> Result<HashMap<HashValue, ProofOfStore>> {
+            Ok(HashMap::new())
+        }
+
+        fn delete_proofs(&self, _: Vec<HashValue>) -> Result<(), DbError> {
+            Ok(())
+        }
     }
 }
@@ -3,7 +3,7 @@
 
 use crate::quorum_store::types::PersistedValue;
 use anyhow::Result;
-use aptos_consensus_types::proof_of_store::BatchId;
+use aptos_consensus_types::proof_of_store::{BatchId, ProofOfStore};
 use aptos_crypto::HashValue;
 use aptos_schemadb::{
     schema::{KeyCodec, Schema, ValueCodec},
@@ -12,6 +12,7 @@ use aptos_schemadb::{
 
 pub(crate) const BATCH_CF_NAME: ColumnFamilyName = "batch";
 pub(crate) const BATCH_ID_CF_NAME: ColumnFamilyName = "batch_ID";
+pub(crate) const PROOF_OF_STORE_CF_NAME: ColumnFamilyName = "proof_of_store";
 
 #[derive(Debug)]
 pub(crate) struct BatchSchema;
@@ -72,3 +73,35 @@ impl ValueCodec<BatchIdSchema> for BatchId {
         Ok(bcs::from_bytes(data)?)
     }
 }
+
+/// Stores the signed proofs of (locally persisted) batches, keyed by batch digest.
+/// A proof is only written after its batch, and is deleted along with its batch.
+#[derive(Debug)]
+pub(crate) struct ProofOfStoreSchema;
+
+impl Schema for ProofOfStoreSchema {
+    type Key = HashValue;
+    type Value = ProofOfStore;
+
+    const COLUMN_FAMILY_NAME: aptos_schemadb::ColumnFamilyName = PROOF_OF_STORE_CF_NAME;
+}
+
+impl KeyCodec<ProofOfStoreSchema> for HashValue {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Ok(HashValue::from_slice(data)?)
+    }
+}
+
+impl ValueCodec<ProofOfStoreSchema> for ProofOfStore {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(&self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
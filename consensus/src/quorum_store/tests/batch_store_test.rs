@@ -2,22 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::quorum_store::{
-    batch_store::{BatchStore, BatchWriter, QuotaManager},
-    quorum_store_db::QuorumStoreDB,
+    batch_store::{BatchStore, BatchWriter, ProofPersister, QuotaManager},
+    quorum_store_db::{QuorumStoreDB, QuorumStoreStorage},
     types::{PersistedValue, StorageMode},
 };
-use aptos_consensus_types::proof_of_store::{BatchId, BatchInfo};
+use aptos_consensus_types::proof_of_store::{BatchId, BatchInfo, ProofCache, ProofOfStore};
 use aptos_crypto::HashValue;
 use aptos_temppath::TempPath;
 use aptos_types::{
-    account_address::AccountAddress, transaction::SignedTransaction,
-    validator_verifier::random_validator_verifier,
+    account_address::AccountAddress,
+    aggregate_signature::{AggregateSignature, PartialSignatures},
+    transaction::SignedTransaction,
+    validator_signer::ValidatorSigner,
+    validator_verifier::{random_validator_verifier, ValidatorVerifier},
 };
 use claims::{assert_err, assert_ok, assert_ok_eq};
 use once_cell::sync::Lazy;
-use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
-    Arc,
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 use tokio::task::spawn_blocking;
 
@@ -281,3 +287,143 @@ fn test_get_local_batch() {
     assert_err!(store.get_batch_from_local(&digest_2));
     assert_err!(store.get_batch_from_local(&digest_3));
 }
+
+fn proof_for_test(
+    batch_info: &BatchInfo,
+    signers: &[ValidatorSigner],
+    validator_verifier: &ValidatorVerifier,
+) -> ProofOfStore {
+    let mut partial_signatures = PartialSignatures::empty();
+    for signer in signers {
+        partial_signatures.add_signature(signer.author(), signer.sign(batch_info).unwrap());
+    }
+    let aggregated_signature = validator_verifier
+        .aggregate_signatures(&partial_signatures)
+        .unwrap();
+    ProofOfStore::new(batch_info.clone(), aggregated_signature)
+}
+
+#[test]
+fn test_recover_batches_and_proofs() {
+    let tmp_dir = TempPath::new();
+    let db = Arc::new(QuorumStoreDB::new(&tmp_dir));
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let epoch = 10;
+
+    // Persist three batches (the last one expires before the restart)
+    let store = BatchStore::new(epoch, 10, db.clone(), 2001, 2001, 2001, signers[0].clone());
+    let batch_infos: Vec<_> = [100, 100, 15]
+        .into_iter()
+        .map(|expiration| {
+            let persist_request =
+                request_for_test(&HashValue::random(), expiration, 10, Some(vec![]));
+            assert!(!store.persist(vec![persist_request.clone()]).is_empty());
+            persist_request.batch_info().clone()
+        })
+        .collect();
+
+    // Persist the proofs (one with an invalid signature)
+    let valid_proof = proof_for_test(&batch_infos[0], &signers, &validator_verifier);
+    let invalid_proof = ProofOfStore::new(batch_infos[1].clone(), AggregateSignature::empty());
+    let expired_proof = proof_for_test(&batch_infos[2], &signers, &validator_verifier);
+    store.persist_proofs(&[valid_proof.clone(), invalid_proof, expired_proof]);
+
+    // Proofs are not persisted for batches that are not in the batch store
+    let missing_batch_info = request_for_test(&HashValue::random(), 100, 10, None)
+        .batch_info()
+        .clone();
+    let missing_batch_proof = proof_for_test(&missing_batch_info, &signers, &validator_verifier);
+    store.persist_proofs(&[missing_batch_proof.clone()]);
+    assert_eq!(db.get_all_proofs().unwrap().len(), 3);
+
+    // Simulate an inconsistent db (e.g., due to a crash), where the batch is missing
+    assert_ok!(db.save_proofs(vec![missing_batch_proof]));
+    drop(store);
+
+    // Restart the batch store, and verify that the expired batch (and its proof) is discarded
+    let store = BatchStore::new(epoch, 20, db.clone(), 2001, 2001, 2001, signers[0].clone());
+    assert_ok!(store.get_batch_from_local(batch_infos[0].digest()));
+    assert_ok!(store.get_batch_from_local(batch_infos[1].digest()));
+    assert_err!(store.get_batch_from_local(batch_infos[2].digest()));
+
+    // Verify that only the valid proof is recovered (and added to the proof cache)
+    let proof_cache = ProofCache::new(10);
+    let recovered_proofs = store.recover_proofs(&validator_verifier, &proof_cache);
+    assert_eq!(recovered_proofs, vec![valid_proof.clone()]);
+    assert_eq!(
+        proof_cache.get(valid_proof.info()),
+        Some(valid_proof.multi_signature().clone())
+    );
+
+    // Verify that the discarded proofs were deleted from the db
+    let db_proofs = db.get_all_proofs().unwrap();
+    assert_eq!(db_proofs.len(), 1);
+    assert!(db_proofs.contains_key(valid_proof.digest()));
+
+    // Verify that the proofs of committed batches are deleted
+    store.delete_proofs(&[valid_proof.info().clone()]);
+    assert!(db.get_all_proofs().unwrap().is_empty());
+    assert!(store
+        .recover_proofs(&validator_verifier, &proof_cache)
+        .is_empty());
+}
+
+/// Waits until the given condition holds (or panics after a timeout)
+fn wait_for_condition(condition: impl Fn() -> bool) {
+    for _ in 0..100 {
+        if condition() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    panic!("Timed out waiting for the condition to hold!");
+}
+
+#[test]
+fn test_proof_persister() {
+    let tmp_dir = TempPath::new();
+    let db = Arc::new(QuorumStoreDB::new(&tmp_dir));
+    let (signers, validator_verifier) = random_validator_verifier(4, None, false);
+    let store = Arc::new(BatchStore::new(
+        10,
+        10,
+        db.clone(),
+        2001,
+        2001,
+        2001,
+        signers[0].clone(),
+    ));
+
+    // Persist two batches
+    let batch_infos: Vec<_> = (0..2)
+        .map(|_| {
+            let persist_request = request_for_test(&HashValue::random(), 100, 10, Some(vec![]));
+            assert!(!store.persist(vec![persist_request.clone()]).is_empty());
+            persist_request.batch_info().clone()
+        })
+        .collect();
+    let proofs: Vec<_> = batch_infos
+        .iter()
+        .map(|batch_info| proof_for_test(batch_info, &signers, &validator_verifier))
+        .collect();
+
+    // Verify that the proofs are persisted in the background
+    let proof_persister = ProofPersister::new(store.clone());
+    proof_persister.persist_proofs(vec![proofs[0].clone()]);
+    proof_persister.persist_proofs(vec![proofs[1].clone()]);
+    wait_for_condition(|| db.get_all_proofs().unwrap().len() == 2);
+
+    // Verify that the proofs of committed batches are deleted in the background
+    proof_persister.delete_proofs(vec![batch_infos[0].clone()]);
+    wait_for_condition(|| db.get_all_proofs().unwrap().len() == 1);
+    assert!(db
+        .get_all_proofs()
+        .unwrap()
+        .contains_key(proofs[1].digest()));
+
+    // Verify that a proof that is deleted right after being persisted is not left behind
+    proof_persister.delete_proofs(vec![batch_infos[1].clone()]);
+    proof_persister.persist_proofs(vec![proofs[0].clone()]);
+    proof_persister.delete_proofs(vec![batch_infos[0].clone()]);
+    wait_for_condition(|| db.get_all_proofs().unwrap().is_empty());
+}
@@ -8,9 +8,9 @@ use crate::{
     },
     test_utils::create_vec_signed_transactions,
 };
-use aptos_consensus_types::proof_of_store::BatchId;
+use aptos_consensus_types::proof_of_store::{BatchId, ProofOfStore};
 use aptos_temppath::TempPath;
-use aptos_types::account_address::AccountAddress;
+use aptos_types::{account_address::AccountAddress, aggregate_signature::AggregateSignature};
 use claims::assert_ok;
 
 #[test]
@@ -84,3 +84,46 @@ fn test_db_for_batch_id() {
         BatchId::new_for_test(2)
     );
 }
+
+#[test]
+fn test_db_for_proofs() {
+    let tmp_dir = TempPath::new();
+    let db = QuorumStoreDB::new(&tmp_dir);
+
+    // Persist several batches and their proofs
+    let source = AccountAddress::random();
+    let mut proofs = vec![];
+    for i in 0..3 {
+        let signed_txns = create_vec_signed_transactions(10);
+        let persist_request: PersistedValue =
+            Batch::new(BatchId::new_for_test(i), signed_txns, 1, 20, source, 0).into();
+        assert_ok!(db.save_batch(persist_request.clone()));
+        proofs.push(ProofOfStore::new(
+            persist_request.batch_info().clone(),
+            AggregateSignature::empty(),
+        ));
+    }
+    assert_ok!(db.save_proofs(proofs.clone()));
+
+    let all_proofs = db.get_all_proofs().expect("could not read from db");
+    assert_eq!(all_proofs.len(), 3);
+    for proof in &proofs {
+        assert_eq!(all_proofs.get(proof.digest()), Some(proof));
+    }
+
+    // Deleting a batch should also delete its proof
+    assert_ok!(db.delete_batches(vec![*proofs[0].digest()]));
+    let all_proofs = db.get_all_proofs().expect("could not read from db");
+    assert_eq!(all_proofs.len(), 2);
+    assert!(!all_proofs.contains_key(proofs[0].digest()));
+
+    // Deleting a proof should not delete its batch
+    assert_ok!(db.delete_proofs(vec![*proofs[1].digest()]));
+    let all_proofs = db.get_all_proofs().expect("could not read from db");
+    assert_eq!(all_proofs.len(), 1);
+    assert!(all_proofs.contains_key(proofs[2].digest()));
+    assert!(db
+        .get_batch(proofs[1].digest())
+        .expect("could not read from db")
+        .is_some());
+}
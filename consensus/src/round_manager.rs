@@ -23,7 +23,7 @@ use crate::{
     logging::{LogEvent, LogSchema},
     metrics_safety_rules::MetricsSafetyRules,
     monitor,
-    network::NetworkSender,
+    network::{IncomingVoteRetrievalRequest, NetworkSender},
     network_interface::ConsensusMsg,
     pending_order_votes::{OrderVoteReceptionResult, PendingOrderVotes},
     pending_votes::VoteReceptionResult,
@@ -49,6 +49,7 @@ use aptos_consensus_types::{
     vote::Vote,
     vote_data::VoteData,
     vote_msg::VoteMsg,
+    vote_retrieval::{VoteRetrievalRequest, VoteRetrievalResponse},
     wrapped_ledger_info::WrappedLedgerInfo,
 };
 use aptos_crypto::HashValue;
@@ -73,7 +74,7 @@ use futures::{channel::oneshot, stream::FuturesUnordered, Future, FutureExt, Str
 use futures_channel::mpsc::UnboundedReceiver;
 use lru::LruCache;
 use serde::Serialize;
-use std::{mem::Discriminant, pin::Pin, sync::Arc, time::Duration};
+use std::{collections::HashMap, mem::Discriminant, pin::Pin, sync::Arc, time::Duration};
 use tokio::{
     sync::oneshot as TokioOneshot,
     time::{sleep, Instant},
@@ -209,6 +210,8 @@ pub enum VerifiedEvent {
     BatchMsg(Box<BatchMsg>),
     SignedBatchInfo(Box<SignedBatchInfoMsg>),
     ProofOfStoreMsg(Box<ProofOfStoreMsg>),
+    // rpc requests
+    VoteRetrievalRequest(Box<IncomingVoteRetrievalRequest>),
     // local messages
     LocalTimeout(Round),
     // Shutdown the NetworkListener
@@ -253,6 +256,10 @@ pub struct RoundManager {
     futures: FuturesUnordered<
         Pin<Box<dyn Future<Output = (anyhow::Result<()>, Block, Instant)> + Send>>,
     >,
+    // The last round for which the missing votes were requested from peers (at most once per round).
+    last_vote_recovery_round: Round,
+    // The last round for which each peer was served a vote retrieval response (at most once per round).
+    last_vote_retrieval_rounds: HashMap<Author, Round>,
 }
 
 impl RoundManager {
@@ -302,6 +309,8 @@ impl RoundManager {
             pending_order_votes: PendingOrderVotes::new(),
             blocks_with_broadcasted_fast_shares: LruCache::new(5),
             futures: FuturesUnordered::new(),
+            last_vote_recovery_round: 0,
+            last_vote_retrieval_rounds: HashMap::new(),
         }
     }
 
@@ -683,6 +692,144 @@ impl RoundManager {
         Ok(())
     }
 
+    /// If the current round has stalled with partial voting power (i.e., some votes were
+    /// received, but not enough to form a QC), request the missing votes from the peers that
+    /// haven't voted, instead of waiting for the round to time out. The missing votes are
+    /// requested at most once per round, and from a bounded number of peers.
+    fn maybe_request_missing_votes(&mut self) {
+        let vote_recovery_config = self.local_config.vote_recovery.clone();
+        let round = self.round_state.current_round();
+        if round <= self.last_vote_recovery_round
+            || self.round_state.current_round_elapsed()
+                < Duration::from_millis(vote_recovery_config.recovery_delay_ms)
+        {
+            return;
+        }
+
+        // If no votes were received, the round has not stalled on missing votes
+        // (e.g., the proposal itself might be missing).
+        let voters = self.round_state.current_round_voters();
+        if voters.is_empty() {
+            return;
+        }
+        let self_author = self.proposal_generator.author();
+        let missing_voters: Vec<_> = self
+            .epoch_state
+            .verifier
+            .get_ordered_account_addresses_iter()
+            .filter(|author| *author != self_author && !voters.contains(author))
+            .take(vote_recovery_config.max_peers_per_round)
+            .collect();
+        if missing_voters.is_empty() {
+            return;
+        }
+        self.last_vote_recovery_round = round;
+
+        info!(
+            self.new_log(LogEvent::RetrieveVote),
+            "Round stalled with partial voting power, requesting the missing votes from {} peers",
+            missing_voters.len()
+        );
+        let request = VoteRetrievalRequest::new(self.epoch_state.epoch, round);
+        let rpc_timeout = Duration::from_millis(vote_recovery_config.rpc_timeout_ms);
+        let network = self.network.clone();
+        tokio::spawn(async move {
+            let requests = missing_voters.into_iter().map(|peer| {
+                let request = request.clone();
+                let network = network.clone();
+                async move {
+                    let response = network.request_vote(request, peer, rpc_timeout).await;
+                    (peer, response)
+                }
+            });
+            for (peer, response) in futures::future::join_all(requests).await {
+                match response {
+                    Ok(response) => {
+                        counters::VOTE_RECOVERY_REQUESTS
+                            .with_label_values(&["success"])
+                            .inc();
+                        Self::deliver_retrieved_vote(&network, peer, round, response).await;
+                    },
+                    Err(error) => {
+                        counters::VOTE_RECOVERY_REQUESTS
+                            .with_label_values(&["error"])
+                            .inc();
+                        warn!(
+                            remote_peer = peer,
+                            error = ?error,
+                            "Failed to retrieve the vote for round {}", round
+                        );
+                    },
+                }
+            }
+        });
+    }
+
+    /// Delivers the vote (and the sync info, if it certifies the stalled round) retrieved from
+    /// the given peer to ourself. These are then verified like any other message from the peer.
+    async fn deliver_retrieved_vote(
+        network: &NetworkSender,
+        peer: Author,
+        round: Round,
+        response: VoteRetrievalResponse,
+    ) {
+        if response.sync_info().highest_round() >= round {
+            let sync_info = response.sync_info().clone();
+            network
+                .deliver_to_self_from(peer, ConsensusMsg::SyncInfo(Box::new(sync_info)))
+                .await;
+        }
+        if let Some(vote_msg) = response.into_vote_msg() {
+            counters::VOTE_RECOVERY_RETRIEVED_VOTES.inc();
+            network
+                .deliver_to_self_from(peer, ConsensusMsg::VoteMsg(Box::new(vote_msg)))
+                .await;
+        }
+    }
+
+    /// Responds to a vote retrieval request with our vote for the requested round (if we
+    /// voted in that round) and our sync info. Each peer is served at most once per round.
+    fn process_vote_retrieval_request(
+        &mut self,
+        peer: Author,
+        request: IncomingVoteRetrievalRequest,
+    ) -> anyhow::Result<()> {
+        let round = request.req.round();
+        if let Some(last_round) = self.last_vote_retrieval_rounds.get(&peer) {
+            if round <= *last_round {
+                counters::VOTE_RETRIEVAL_REQUESTS_RECEIVED
+                    .with_label_values(&["rate_limited"])
+                    .inc();
+                bail!(
+                    "Vote retrieval for round {} from {} is rate limited (last round served: {})",
+                    round,
+                    peer,
+                    last_round
+                );
+            }
+        }
+        self.last_vote_retrieval_rounds.insert(peer, round);
+        counters::VOTE_RETRIEVAL_REQUESTS_RECEIVED
+            .with_label_values(&["served"])
+            .inc();
+
+        let vote = self
+            .round_state
+            .vote_sent()
+            .filter(|vote| vote.vote_data().proposed().round() == round);
+        let response = Box::new(VoteRetrievalResponse::new(
+            vote,
+            self.block_store.sync_info(),
+        ));
+        let response_bytes = request
+            .protocol
+            .to_bytes(&ConsensusMsg::VoteRetrievalResponse(response))?;
+        request
+            .response_sender
+            .send(Ok(response_bytes.into()))
+            .map_err(|_| anyhow::anyhow!("Failed to send vote retrieval response"))
+    }
+
     fn sync_only(&self) -> bool {
         let sync_or_not = self.local_config.sync_only || self.block_store.vote_back_pressure();
         if self.block_store.vote_back_pressure() {
@@ -1458,6 +1605,10 @@ impl RoundManager {
     ) {
         info!(epoch = self.epoch_state().epoch, "RoundManager started");
         let mut close_rx = close_rx.into_stream();
+        let vote_recovery_enabled = self.local_config.vote_recovery.enable_vote_recovery;
+        let mut vote_recovery_interval = tokio::time::interval(Duration::from_millis(
+            self.local_config.vote_recovery.check_interval_ms.max(1),
+        ));
         loop {
             tokio::select! {
                 biased;
@@ -1558,6 +1709,10 @@ impl RoundManager {
                             "process_local_timeout",
                             self.process_local_timeout(round).await
                         ),
+                        VerifiedEvent::VoteRetrievalRequest(request) => monitor!(
+                            "process_vote_retrieval",
+                            self.process_vote_retrieval_request(peer_id, *request)
+                        ),
                         unexpected_event => unreachable!("Unexpected event: {:?}", unexpected_event),
                     }
                     .with_context(|| format!("from peer {}", peer_id));
@@ -1571,6 +1726,9 @@ impl RoundManager {
                         }
                     }
                 },
+                _ = vote_recovery_interval.tick(), if vote_recovery_enabled => {
+                    monitor!("request_missing_votes", self.maybe_request_missing_votes());
+                },
             }
         }
         info!(epoch = self.epoch_state().epoch, "RoundManager stopped");
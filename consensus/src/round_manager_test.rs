@@ -13,7 +13,7 @@ use crate::{
         round_state::{ExponentialTimeInterval, RoundState},
     },
     metrics_safety_rules::MetricsSafetyRules,
-    network::{IncomingBlockRetrievalRequest, NetworkSender},
+    network::{IncomingBlockRetrievalRequest, IncomingVoteRetrievalRequest, NetworkSender},
     network_interface::{CommitMessage, ConsensusMsg, ConsensusNetworkClient, DIRECT_SEND, RPC},
     network_tests::{NetworkPlayground, TwinId},
     payload_manager::DirectMempoolPayloadManager,
@@ -29,7 +29,7 @@ use crate::{
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{ConsensusConfig, QcAggregatorType},
+    config::{ConsensusConfig, QcAggregatorType, VoteRecoveryConfig},
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_consensus_types::{
@@ -45,6 +45,7 @@ use aptos_consensus_types::{
     timeout_2chain::{TwoChainTimeout, TwoChainTimeoutWithPartialSignatures},
     utils::PayloadTxnsSize,
     vote_msg::VoteMsg,
+    vote_retrieval::VoteRetrievalRequest,
 };
 use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
@@ -75,6 +76,7 @@ use aptos_types::{
     validator_txn::ValidatorTransaction,
     validator_verifier::{generate_validator_verifier, random_validator_verifier},
     waypoint::Waypoint,
+    PeerId,
 };
 use futures::{
     channel::{mpsc, oneshot},
@@ -1373,6 +1375,224 @@ fn response_on_block_retrieval() {
     });
 }
 
+#[test]
+/// Verify that a vote retrieval request is served with the vote for the requested round,
+/// and that each peer is served at most once per round.
+fn response_on_vote_retrieval() {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let mut node = NodeSetup::create_nodes(
+        &mut playground,
+        runtime.handle().clone(),
+        1,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .pop()
+    .unwrap();
+
+    let genesis_qc = certificate_for_genesis();
+    timed_block_on(&runtime, async {
+        // Start round 1 and vote on the proposal
+        node.next_proposal().await;
+        let proposal = Block::new_proposal(
+            Payload::empty(false, true),
+            1,
+            1,
+            genesis_qc.clone(),
+            &node.signer,
+            Vec::new(),
+        )
+        .unwrap();
+        let proposal_id = proposal.id();
+        node.round_manager.process_proposal(proposal).await.unwrap();
+        node.next_vote().await;
+
+        // Verify that the vote for the round is retrieved
+        let peer = PeerId::random();
+        let (tx1, rx1) = oneshot::channel();
+        let vote_request = IncomingVoteRetrievalRequest {
+            req: VoteRetrievalRequest::new(1, 1),
+            protocol: ProtocolId::ConsensusRpcBcs,
+            response_sender: tx1,
+        };
+        node.round_manager
+            .process_vote_retrieval_request(peer, vote_request)
+            .unwrap();
+        match rx1.await {
+            Ok(Ok(bytes)) => {
+                let response = match bcs::from_bytes(&bytes) {
+                    Ok(ConsensusMsg::VoteRetrievalResponse(resp)) => *resp,
+                    _ => panic!("vote retrieval failure"),
+                };
+                let vote = response.vote().unwrap();
+                assert_eq!(vote.author(), node.signer.author());
+                assert_eq!(vote.vote_data().proposed().id(), proposal_id);
+                assert!(response.into_vote_msg().is_some());
+            },
+            _ => panic!("vote retrieval failure"),
+        }
+
+        // Verify that the same peer is not served twice for the same round
+        let (tx2, _rx2) = oneshot::channel();
+        let repeated_vote_request = IncomingVoteRetrievalRequest {
+            req: VoteRetrievalRequest::new(1, 1),
+            protocol: ProtocolId::ConsensusRpcBcs,
+            response_sender: tx2,
+        };
+        assert!(node
+            .round_manager
+            .process_vote_retrieval_request(peer, repeated_vote_request)
+            .is_err());
+
+        // Verify that no vote is returned for a round without a vote
+        let (tx3, rx3) = oneshot::channel();
+        let other_round_request = IncomingVoteRetrievalRequest {
+            req: VoteRetrievalRequest::new(1, 2),
+            protocol: ProtocolId::ConsensusRpcBcs,
+            response_sender: tx3,
+        };
+        node.round_manager
+            .process_vote_retrieval_request(peer, other_round_request)
+            .unwrap();
+        match rx3.await {
+            Ok(Ok(bytes)) => {
+                let response = match bcs::from_bytes(&bytes) {
+                    Ok(ConsensusMsg::VoteRetrievalResponse(resp)) => *resp,
+                    _ => panic!("vote retrieval failure"),
+                };
+                assert!(response.vote().is_none());
+            },
+            _ => panic!("vote retrieval failure"),
+        }
+    });
+}
+
+#[test]
+/// Verify that the missing votes of a stalled round are requested (once per round) from the
+/// peers that haven't voted, and that the retrieved votes are delivered to form a QC.
+fn request_missing_votes_on_stalled_round() {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let local_config = ConsensusConfig {
+        vote_recovery: VoteRecoveryConfig {
+            enable_vote_recovery: true,
+            recovery_delay_ms: 0,
+            rpc_timeout_ms: 5_000,
+            ..VoteRecoveryConfig::default()
+        },
+        ..ConsensusConfig::default()
+    };
+    let mut nodes = NodeSetup::create_nodes(
+        &mut playground,
+        runtime.handle().clone(),
+        4,
+        None,
+        None,
+        Some(local_config),
+        None,
+        None,
+    );
+    runtime.spawn(playground.start());
+
+    timed_block_on(&runtime, async {
+        // Vote on the proposal of round 1 on all nodes (the votes are sent to node 0)
+        for node in nodes.iter_mut() {
+            let proposal_msg = node.next_proposal().await;
+            node.round_manager
+                .process_proposal_msg(proposal_msg)
+                .await
+                .unwrap();
+        }
+
+        // Only process the votes of nodes 0 and 1 (i.e., the round stalls with partial
+        // voting power), and drop the votes of nodes 2 and 3.
+        let present_voters = [nodes[0].signer.author(), nodes[1].signer.author()];
+        let missing_voters = [nodes[2].signer.author(), nodes[3].signer.author()];
+        for _ in 0..4 {
+            let vote_msg = nodes[0].next_vote().await;
+            if present_voters.contains(&vote_msg.vote().author()) {
+                nodes[0]
+                    .round_manager
+                    .process_vote_msg(vote_msg)
+                    .await
+                    .unwrap();
+            }
+        }
+        assert_eq!(nodes[0].round_manager.round_state().current_round(), 1);
+
+        // Request the missing votes, and verify that they are only requested once per round
+        nodes[0].round_manager.maybe_request_missing_votes();
+        nodes[0].round_manager.maybe_request_missing_votes();
+
+        // Serve the vote retrieval requests on nodes 2 and 3
+        let requester = nodes[0].signer.author();
+        for node in nodes[2..].iter_mut() {
+            match node.next_network_event().await {
+                Event::RpcRequest(
+                    peer,
+                    ConsensusMsg::VoteRetrievalRequest(request),
+                    protocol,
+                    response_sender,
+                ) => {
+                    assert_eq!(peer, requester);
+                    assert_eq!(request.round(), 1);
+                    node.round_manager
+                        .process_vote_retrieval_request(
+                            peer,
+                            IncomingVoteRetrievalRequest {
+                                req: *request,
+                                protocol,
+                                response_sender,
+                            },
+                        )
+                        .unwrap();
+                },
+                event => panic!(
+                    "Unexpected event: {:?} on node {}",
+                    event,
+                    node.identity_desc()
+                ),
+            }
+            node.no_next_msg();
+        }
+        nodes[1].no_next_msg();
+
+        // Verify that the retrieved votes are delivered to node 0 (as if sent by the peers)
+        let mut retrieved_votes = vec![];
+        for _ in 0..2 {
+            match nodes[0].next_network_event().await {
+                Event::Message(peer, ConsensusMsg::VoteMsg(vote_msg)) => {
+                    assert_eq!(peer, vote_msg.vote().author());
+                    assert_eq!(vote_msg.vote().vote_data().proposed().round(), 1);
+                    retrieved_votes.push(*vote_msg);
+                },
+                event => panic!("Unexpected event: {:?}", event),
+            }
+        }
+        let retrieved_voters: Vec<_> = retrieved_votes
+            .iter()
+            .map(|vote_msg| vote_msg.vote().author())
+            .collect();
+        assert_eq!(retrieved_voters.len(), missing_voters.len());
+        assert!(missing_voters
+            .iter()
+            .all(|voter| retrieved_voters.contains(voter)));
+
+        // Verify that a retrieved vote completes the QC, and that round 2 starts
+        let retrieved_vote = retrieved_votes.pop().unwrap();
+        nodes[0]
+            .round_manager
+            .process_vote_msg(retrieved_vote)
+            .await
+            .unwrap();
+        assert_eq!(nodes[0].round_manager.round_state().current_round(), 2);
+    });
+}
+
 #[test]
 /// rebuild a node from previous storage without violating safety guarantees.
 fn recover_on_restart() {
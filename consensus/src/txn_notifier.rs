@@ -3,7 +3,7 @@
 
 use crate::{error::MempoolError, monitor};
 use anyhow::{format_err, Result};
-use aptos_consensus_types::common::RejectedTransactionSummary;
+use aptos_consensus_types::common::{RejectedTransactionSummary, TransactionRejectionReason};
 use aptos_mempool::QuorumStoreRequest;
 use aptos_types::transaction::{SignedTransaction, TransactionStatus};
 use futures::channel::{mpsc, oneshot};
@@ -14,8 +14,8 @@ use tokio::time::timeout;
 /// Notification of failed transactions.
 #[async_trait::async_trait]
 pub trait TxnNotifier: Send + Sync {
-    /// Notification of txns which failed execution. (Committed txns is notified by
-    /// state sync.)
+    /// Notification of txns which failed execution or were cut from the block.
+    /// (Committed txns is notified by state sync.)
     async fn notify_failed_txn(
         &self,
         txns: &[SignedTransaction],
@@ -23,7 +23,7 @@ pub trait TxnNotifier: Send + Sync {
     ) -> Result<(), MempoolError>;
 }
 
-/// Execution -> Mempool notification of failed transactions.
+/// Execution -> Mempool notification of failed (and block cut) transactions.
 pub struct MempoolNotifier {
     consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
     /// Timeout for consensus to get an ack from mempool for executed transactions (in milliseconds)
@@ -53,14 +53,19 @@ impl TxnNotifier for MempoolNotifier {
         let mut rejected_txns = vec![];
 
         for (txn, status) in user_txns.iter().zip_eq(user_txn_statuses) {
-            if let TransactionStatus::Discard(reason) = status {
-                rejected_txns.push(RejectedTransactionSummary {
-                    sender: txn.sender(),
-                    sequence_number: txn.sequence_number(),
-                    hash: txn.committed_hash(),
-                    reason: *reason,
-                });
-            }
+            let reason = match status {
+                TransactionStatus::Discard(reason) => {
+                    TransactionRejectionReason::Discarded(*reason)
+                },
+                TransactionStatus::Retry => TransactionRejectionReason::BlockCut,
+                TransactionStatus::Keep(_) => continue,
+            };
+            rejected_txns.push(RejectedTransactionSummary {
+                sender: txn.sender(),
+                sequence_number: txn.sequence_number(),
+                hash: txn.committed_hash(),
+                reason,
+            });
         }
 
         if rejected_txns.is_empty() {
@@ -0,0 +1,375 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Error;
+use aptos_config::{
+    config::{EvictionPolicyConfig, PeerPrioritizationParams},
+    network_id::PeerNetworkId,
+};
+use aptos_crypto::HashValue;
+use aptos_logger::info;
+use aptos_mempool::{
+    ClientQuotaManager, MempoolClientRequest, MempoolClientSender, PendingTransactionsCursor,
+    PendingTransactionsFilter, PendingTransactionsRequest,
+};
+use aptos_system_utils::utils::{reply_with, reply_with_status, spawn_blocking};
+use aptos_types::account_address::AccountAddress;
+use futures::{channel::oneshot, SinkExt};
+use http::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use serde::Deserialize;
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+
+/// The default number of pending transactions returned per page
+const DEFAULT_PENDING_TRANSACTIONS_LIMIT: usize = 100;
+
+/// The maximum number of pending transactions returned per page
+const MAX_PENDING_TRANSACTIONS_LIMIT: usize = 1_000;
+
+/// A request to fetch the given transactions (by hash) from the given peer
+#[derive(Debug, Deserialize)]
+struct FetchTransactionsRequest {
+    peer: PeerNetworkId,
+    hashes: Vec<HashValue>,
+}
+
+pub async fn handle_dump_client_quotas_request(
+    _req: Request<Body>,
+    client_quota_manager: Arc<ClientQuotaManager>,
+) -> hyper::Result<Response<Body>> {
+    info!("Dumping mempool client quota usage.");
+
+    match spawn_blocking(move || {
+        serde_json::to_string_pretty(&client_quota_manager.get_usage()).map_err(Into::into)
+    })
+    .await
+    {
+        Ok(result) => {
+            info!("Finished dumping mempool client quota usage.");
+            let headers: Vec<(_, HeaderValue)> = vec![
+                (CONTENT_LENGTH, HeaderValue::from(result.len())),
+                (CONTENT_TYPE, HeaderValue::from_static("application/json")),
+            ];
+            Ok(reply_with(headers, result))
+        },
+        Err(e) => {
+            info!("Failed to dump mempool client quota usage: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn handle_dump_pending_transactions_request(
+    req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let request = match parse_pending_transactions_request(&query_pairs) {
+        Ok(request) => request,
+        Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+    };
+
+    info!("Dumping pending mempool transactions.");
+
+    match dump_pending_transactions(request, mempool_client_sender).await {
+        Ok(result) => {
+            info!("Finished dumping pending mempool transactions.");
+            let headers: Vec<(_, HeaderValue)> = vec![
+                (CONTENT_LENGTH, HeaderValue::from(result.len())),
+                (CONTENT_TYPE, HeaderValue::from_static("application/json")),
+            ];
+            Ok(reply_with(headers, result))
+        },
+        Err(e) => {
+            info!("Failed to dump pending mempool transactions: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn handle_get_eviction_policy_request(
+    _req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    info!("Dumping the mempool eviction policy.");
+
+    match get_eviction_policy(mempool_client_sender).await {
+        Ok(result) => {
+            info!("Finished dumping the mempool eviction policy.");
+            let headers: Vec<(_, HeaderValue)> = vec![
+                (CONTENT_LENGTH, HeaderValue::from(result.len())),
+                (CONTENT_TYPE, HeaderValue::from_static("application/json")),
+            ];
+            Ok(reply_with(headers, result))
+        },
+        Err(e) => {
+            info!("Failed to dump the mempool eviction policy: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn handle_set_eviction_policy_request(
+    req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    // The new eviction policy is JSON encoded in the request body
+    let body = hyper::body::to_bytes(req.into_body()).await?;
+    let eviction_policy: EvictionPolicyConfig = match serde_json::from_slice(&body) {
+        Ok(eviction_policy) => eviction_policy,
+        Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+    };
+
+    info!("Updating the mempool eviction policy: {eviction_policy:?}");
+
+    match set_eviction_policy(eviction_policy, mempool_client_sender).await {
+        Ok(Ok(())) => {
+            info!("Finished updating the mempool eviction policy.");
+            Ok(reply_with_status(
+                StatusCode::OK,
+                "Updated the mempool eviction policy.",
+            ))
+        },
+        Ok(Err(e)) => Ok(reply_with_status(StatusCode::BAD_REQUEST, e.to_string())),
+        Err(e) => {
+            info!("Failed to update the mempool eviction policy: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn handle_get_prioritization_params_request(
+    _req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    info!("Dumping the mempool peer prioritization params.");
+
+    match get_prioritization_params(mempool_client_sender).await {
+        Ok(result) => {
+            info!("Finished dumping the mempool peer prioritization params.");
+            let headers: Vec<(_, HeaderValue)> = vec![
+                (CONTENT_LENGTH, HeaderValue::from(result.len())),
+                (CONTENT_TYPE, HeaderValue::from_static("application/json")),
+            ];
+            Ok(reply_with(headers, result))
+        },
+        Err(e) => {
+            info!("Failed to dump the mempool peer prioritization params: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn handle_set_prioritization_params_request(
+    req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    // The new prioritization params are JSON encoded in the request body
+    let body = hyper::body::to_bytes(req.into_body()).await?;
+    let params: PeerPrioritizationParams = match serde_json::from_slice(&body) {
+        Ok(params) => params,
+        Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+    };
+
+    info!("Updating the mempool peer prioritization params: {params:?}");
+
+    match set_prioritization_params(params, mempool_client_sender).await {
+        Ok(Ok(())) => {
+            info!("Finished updating the mempool peer prioritization params.");
+            Ok(reply_with_status(
+                StatusCode::OK,
+                "Updated the mempool peer prioritization params. These will be applied on the next priority update.",
+            ))
+        },
+        Ok(Err(e)) => Ok(reply_with_status(StatusCode::BAD_REQUEST, e.to_string())),
+        Err(e) => {
+            info!("Failed to update the mempool peer prioritization params: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn handle_fetch_transactions_request(
+    req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    // The fetch request is JSON encoded in the request body
+    let body = hyper::body::to_bytes(req.into_body()).await?;
+    let request: FetchTransactionsRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+    };
+
+    info!("Fetching mempool transactions: {request:?}");
+
+    match fetch_transactions(request, mempool_client_sender).await {
+        Ok(Ok(num_accepted_txns)) => {
+            info!("Finished fetching mempool transactions.");
+            Ok(reply_with_status(
+                StatusCode::OK,
+                format!("Fetched {num_accepted_txns} new transaction(s) into mempool."),
+            ))
+        },
+        Ok(Err(e)) => Ok(reply_with_status(StatusCode::BAD_GATEWAY, e.to_string())),
+        Err(e) => {
+            info!("Failed to fetch mempool transactions: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+/// Parses the pending transactions request from the given query parameters.
+/// Transaction payloads are redacted unless `include_payloads=true` is specified.
+fn parse_pending_transactions_request(
+    query_pairs: &HashMap<Cow<str>, Cow<str>>,
+) -> anyhow::Result<PendingTransactionsRequest> {
+    let start = match query_pairs.get("start") {
+        Some(start) => Some(start.parse::<PendingTransactionsCursor>()?),
+        None => None,
+    };
+    let limit = match query_pairs.get("limit") {
+        Some(limit) => limit.parse::<usize>()?,
+        None => DEFAULT_PENDING_TRANSACTIONS_LIMIT,
+    };
+    if limit == 0 || limit > MAX_PENDING_TRANSACTIONS_LIMIT {
+        anyhow::bail!(
+            "Invalid limit: {}. The limit must be between 1 and {}",
+            limit,
+            MAX_PENDING_TRANSACTIONS_LIMIT
+        );
+    }
+    let sender = match query_pairs.get("sender") {
+        Some(sender) => Some(sender.parse::<AccountAddress>()?),
+        None => None,
+    };
+    let min_gas_unit_price = match query_pairs.get("min_gas_unit_price") {
+        Some(min_gas_unit_price) => Some(min_gas_unit_price.parse()?),
+        None => None,
+    };
+    let parked_only = match query_pairs.get("parked_only") {
+        Some(parked_only) => parked_only.parse()?,
+        None => false,
+    };
+    let include_payloads = match query_pairs.get("include_payloads") {
+        Some(include_payloads) => include_payloads.parse()?,
+        None => false,
+    };
+
+    Ok(PendingTransactionsRequest {
+        start,
+        limit,
+        filter: PendingTransactionsFilter {
+            sender,
+            min_gas_unit_price,
+            parked_only,
+        },
+        include_payloads,
+    })
+}
+
+async fn dump_pending_transactions(
+    request: PendingTransactionsRequest,
+    mut mempool_client_sender: MempoolClientSender,
+) -> Result<String, Error> {
+    let (callback, callback_receiver) = oneshot::channel();
+    mempool_client_sender
+        .send(MempoolClientRequest::GetPendingTransactions(
+            request, callback,
+        ))
+        .await?;
+    let page = callback_receiver.await?;
+    Ok(serde_json::to_string_pretty(&page)?)
+}
+
+async fn get_eviction_policy(
+    mut mempool_client_sender: MempoolClientSender,
+) -> Result<String, Error> {
+    let (callback, callback_receiver) = oneshot::channel();
+    mempool_client_sender
+        .send(MempoolClientRequest::GetEvictionPolicy(callback))
+        .await?;
+    let eviction_policy = callback_receiver.await?;
+    Ok(serde_json::to_string_pretty(&eviction_policy)?)
+}
+
+/// Sends the eviction policy to mempool. The outer result holds any failure to reach
+/// mempool, and the inner result holds the outcome of applying the policy.
+async fn set_eviction_policy(
+    eviction_policy: EvictionPolicyConfig,
+    mut mempool_client_sender: MempoolClientSender,
+) -> Result<anyhow::Result<()>, Error> {
+    let (callback, callback_receiver) = oneshot::channel();
+    mempool_client_sender
+        .send(MempoolClientRequest::SetEvictionPolicy(
+            eviction_policy,
+            callback,
+        ))
+        .await?;
+    Ok(callback_receiver.await?)
+}
+
+async fn get_prioritization_params(
+    mut mempool_client_sender: MempoolClientSender,
+) -> Result<String, Error> {
+    let (callback, callback_receiver) = oneshot::channel();
+    mempool_client_sender
+        .send(MempoolClientRequest::GetPrioritizationParams(callback))
+        .await?;
+    let params = callback_receiver.await?;
+    Ok(serde_json::to_string_pretty(&params)?)
+}
+
+/// Sends the prioritization params to mempool. The outer result holds any failure to
+/// reach mempool, and the inner result holds the outcome of verifying the params.
+async fn set_prioritization_params(
+    params: PeerPrioritizationParams,
+    mut mempool_client_sender: MempoolClientSender,
+) -> Result<anyhow::Result<()>, Error> {
+    let (callback, callback_receiver) = oneshot::channel();
+    mempool_client_sender
+        .send(MempoolClientRequest::SetPrioritizationParams(
+            params, callback,
+        ))
+        .await?;
+    Ok(callback_receiver.await?)
+}
+
+/// Asks mempool to fetch the transactions from the peer. The outer result holds any
+/// failure to reach mempool, and the inner result holds the outcome of the fetch.
+async fn fetch_transactions(
+    request: FetchTransactionsRequest,
+    mut mempool_client_sender: MempoolClientSender,
+) -> Result<anyhow::Result<usize>, Error> {
+    let (callback, callback_receiver) = oneshot::channel();
+    mempool_client_sender
+        .send(MempoolClientRequest::FetchTransactions(
+            request.peer,
+            request.hashes,
+            callback,
+        ))
+        .await?;
+    Ok(callback_receiver.await?)
+}
@@ -7,6 +7,7 @@ use aptos_consensus::{
 };
 use aptos_infallible::RwLock;
 use aptos_logger::info;
+use aptos_mempool::{ClientQuotaManager, MempoolClientSender};
 use aptos_storage_interface::DbReaderWriter;
 use aptos_system_utils::utils::reply_with_status;
 #[cfg(target_os = "linux")]
@@ -26,6 +27,7 @@ use std::{
 use tokio::runtime::Runtime;
 
 mod consensus;
+mod mempool;
 
 #[derive(Default)]
 pub struct Context {
@@ -34,6 +36,8 @@ pub struct Context {
     aptos_db: RwLock<Option<Arc<DbReaderWriter>>>,
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
     quorum_store_db: RwLock<Option<Arc<QuorumStoreDB>>>,
+    mempool_client_quota_manager: RwLock<Option<Arc<ClientQuotaManager>>>,
+    mempool_client_sender: RwLock<Option<MempoolClientSender>>,
 }
 
 impl Context {
@@ -49,6 +53,14 @@ impl Context {
         *self.consensus_db.write() = Some(consensus_db);
         *self.quorum_store_db.write() = Some(quorum_store_db);
     }
+
+    fn set_mempool_client_quota_manager(&self, client_quota_manager: Arc<ClientQuotaManager>) {
+        *self.mempool_client_quota_manager.write() = Some(client_quota_manager);
+    }
+
+    fn set_mempool_client_sender(&self, mempool_client_sender: MempoolClientSender) {
+        *self.mempool_client_sender.write() = Some(mempool_client_sender);
+    }
 }
 
 pub struct AdminService {
@@ -107,6 +119,16 @@ impl AdminService {
             .set_consensus_dbs(consensus_db, quorum_store_db)
     }
 
+    pub fn set_mempool_client_quota_manager(&self, client_quota_manager: Arc<ClientQuotaManager>) {
+        self.context
+            .set_mempool_client_quota_manager(client_quota_manager)
+    }
+
+    pub fn set_mempool_client_sender(&self, mempool_client_sender: MempoolClientSender) {
+        self.context
+            .set_mempool_client_sender(mempool_client_sender)
+    }
+
     fn start(&self, address: SocketAddr, enabled: bool) {
         let context = self.context.clone();
         self.runtime.spawn(async move {
@@ -210,6 +232,86 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/debug/mempool/client_quotas") => {
+                let client_quota_manager = context.mempool_client_quota_manager.read().clone();
+                if let Some(client_quota_manager) = client_quota_manager {
+                    mempool::handle_dump_client_quotas_request(req, client_quota_manager).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool client quotas are not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/mempool/pending_transactions") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::handle_dump_pending_transactions_request(req, mempool_client_sender)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/mempool/eviction_policy") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::handle_get_eviction_policy_request(req, mempool_client_sender).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::POST, "/debug/mempool/eviction_policy") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::handle_set_eviction_policy_request(req, mempool_client_sender).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/mempool/prioritization_params") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::handle_get_prioritization_params_request(req, mempool_client_sender)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::POST, "/debug/mempool/prioritization_params") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::handle_set_prioritization_params_request(req, mempool_client_sender)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::POST, "/debug/mempool/fetch_transactions") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::handle_fetch_transactions_request(req, mempool_client_sender).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool is not available.",
+                    ))
+                }
+            },
             _ => Ok(reply_with_status(StatusCode::NOT_FOUND, "Not found.")),
         }
     }
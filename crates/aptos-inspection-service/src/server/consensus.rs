@@ -0,0 +1,52 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::{
+    peer_information::PEER_INFO_DISABLED_MESSAGE,
+    utils::{CONTENT_TYPE_JSON, CONTENT_TYPE_TEXT},
+};
+use aptos_config::config::NodeConfig;
+use hyper::{Body, StatusCode};
+use std::sync::Arc;
+
+/// A callback that returns the JSON formatted report of the execution stalls waiting
+/// for payload availability. This is registered by the node, so that the inspection
+/// service doesn't depend on consensus.
+pub type PayloadUnavailabilityReporter = Arc<dyn Fn() -> serde_json::Result<String> + Send + Sync>;
+
+/// Handles a new consensus payload unavailability request
+pub fn handle_payload_unavailability_request(
+    node_config: &NodeConfig,
+    payload_unavailability_reporter: PayloadUnavailabilityReporter,
+) -> (StatusCode, Body, String) {
+    // Only return the report if peer information is exposed (the report holds peer ids)
+    if node_config.inspection_service.expose_peer_information {
+        (
+            StatusCode::OK,
+            Body::from(get_payload_unavailability_json(
+                payload_unavailability_reporter,
+            )),
+            CONTENT_TYPE_JSON.into(),
+        )
+    } else {
+        (
+            StatusCode::FORBIDDEN,
+            Body::from(PEER_INFO_DISABLED_MESSAGE),
+            CONTENT_TYPE_TEXT.into(),
+        )
+    }
+}
+
+/// Returns a simple JSON formatted string with the report of the execution
+/// stalls waiting for payload availability (e.g., missing batches).
+fn get_payload_unavailability_json(
+    payload_unavailability_reporter: PayloadUnavailabilityReporter,
+) -> String {
+    match payload_unavailability_reporter() {
+        Ok(report) => report,
+        Err(error) => format!(
+            "Failed to get the payload unavailability report! Error: {}",
+            error
+        ),
+    }
+}
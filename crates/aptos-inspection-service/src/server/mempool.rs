@@ -0,0 +1,46 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::{
+    peer_information::PEER_INFO_DISABLED_MESSAGE,
+    utils::{CONTENT_TYPE_JSON, CONTENT_TYPE_TEXT},
+};
+use aptos_config::config::NodeConfig;
+use aptos_infallible::RwLock;
+use aptos_mempool::PrioritizedPeerInfo;
+use hyper::{Body, StatusCode};
+use std::sync::Arc;
+
+/// Handles a new mempool prioritized peers request
+pub fn handle_mempool_prioritized_peers_request(
+    node_config: &NodeConfig,
+    mempool_prioritized_peers: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+) -> (StatusCode, Body, String) {
+    // Only return the prioritized peers if peer information is exposed
+    if node_config.inspection_service.expose_peer_information {
+        (
+            StatusCode::OK,
+            Body::from(get_mempool_prioritized_peers_json(
+                mempool_prioritized_peers,
+            )),
+            CONTENT_TYPE_JSON.into(),
+        )
+    } else {
+        (
+            StatusCode::FORBIDDEN,
+            Body::from(PEER_INFO_DISABLED_MESSAGE),
+            CONTENT_TYPE_TEXT.into(),
+        )
+    }
+}
+
+/// Returns a simple JSON formatted string with the mempool peer priorities
+/// (ordered from highest to lowest priority) and the metadata that produced them.
+fn get_mempool_prioritized_peers_json(
+    mempool_prioritized_peers: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+) -> String {
+    match serde_json::to_string(&*mempool_prioritized_peers.read()) {
+        Ok(prioritized_peers) => prioritized_peers,
+        Err(error) => format!("Failed to get mempool peer priorities! Error: {}", error),
+    }
+}
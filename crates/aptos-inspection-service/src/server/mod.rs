@@ -4,7 +4,9 @@
 use crate::server::utils::CONTENT_TYPE_TEXT;
 use aptos_config::config::NodeConfig;
 use aptos_data_client::client::AptosDataClient;
+use aptos_infallible::RwLock;
 use aptos_logger::debug;
+use aptos_mempool::PrioritizedPeerInfo;
 use aptos_network::application::storage::PeersAndMetadata;
 use hyper::{
     service::{make_service_fn, service_fn},
@@ -12,14 +14,16 @@ use hyper::{
 };
 use std::{
     convert::Infallible,
-    net::{SocketAddr, ToSocketAddrs},
+    net::{SocketAddr, TcpListener, ToSocketAddrs},
     sync::Arc,
     thread,
 };
 
 mod configuration;
+mod consensus;
 mod index;
 mod json_encoder;
+mod mempool;
 mod metrics;
 mod peer_information;
 mod system_information;
@@ -28,11 +32,15 @@ pub mod utils;
 #[cfg(test)]
 mod tests;
 
+pub use consensus::PayloadUnavailabilityReporter;
+
 // The list of endpoints offered by the inspection service
 pub const CONFIGURATION_PATH: &str = "/configuration";
+pub const CONSENSUS_PAYLOAD_UNAVAILABILITY_PATH: &str = "/consensus/payload_unavailability";
 pub const FORGE_METRICS_PATH: &str = "/forge_metrics";
 pub const INDEX_PATH: &str = "/";
 pub const JSON_METRICS_PATH: &str = "/json_metrics";
+pub const MEMPOOL_PRIORITIZED_PEERS_PATH: &str = "/mempool/prioritized_peers";
 pub const METRICS_PATH: &str = "/metrics";
 pub const PEER_INFORMATION_PATH: &str = "/peer_information";
 pub const SYSTEM_INFORMATION_PATH: &str = "/system_information";
@@ -48,6 +56,8 @@ pub fn start_inspection_service(
     node_config: NodeConfig,
     aptos_data_client: AptosDataClient,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    mempool_prioritized_peers: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+    payload_unavailability_reporter: PayloadUnavailabilityReporter,
 ) {
     // Fetch the service port and address
     let service_port = node_config.inspection_service.port;
@@ -65,36 +75,77 @@ pub fn start_inspection_service(
         .next()
         .unwrap();
 
-    // Create a runtime for the inspection service
-    let runtime = aptos_runtimes::spawn_named_runtime("inspection".into(), None);
+    // Bind the inspection service address. This is done before supervision (and
+    // fails fast), as a failed bind is not transient and restarting won't help.
+    let listener = TcpListener::bind(address).unwrap_or_else(|error| {
+        panic!(
+            "Failed to bind the inspection service to {}: {:?}",
+            address, error
+        )
+    });
+
+    // Create a runtime for the inspection service. The inspection
+    // service is not safety-critical, so panics are contained.
+    let runtime = aptos_runtimes::spawn_supervised_runtime("inspection".into(), "inspection", None);
 
-    // Spawn the inspection service
+    // Spawn the inspection service (the server is restarted on the
+    // same listener if it panics).
     thread::spawn(move || {
-        // Create the service function that handles the endpoint requests
-        let make_service = make_service_fn(move |_conn| {
-            let node_config = node_config.clone();
-            let aptos_data_client = aptos_data_client.clone();
-            let peers_and_metadata = peers_and_metadata.clone();
-            async move {
-                Ok::<_, Infallible>(service_fn(move |request| {
-                    serve_requests(
-                        request,
-                        node_config.clone(),
-                        aptos_data_client.clone(),
-                        peers_and_metadata.clone(),
-                    )
-                }))
-            }
-        });
-
-        // Start and block on the server
-        runtime
-            .block_on(async {
-                let server = Server::bind(&address).serve(make_service);
-                server.await
-            })
-            .unwrap();
+        let supervisor =
+            aptos_runtimes::spawn_supervised(runtime.handle(), "inspection", move || {
+                let listener = listener
+                    .try_clone()
+                    .expect("Failed to clone the inspection service listener!");
+                run_inspection_server(
+                    listener,
+                    node_config.clone(),
+                    aptos_data_client.clone(),
+                    peers_and_metadata.clone(),
+                    mempool_prioritized_peers.clone(),
+                    payload_unavailability_reporter.clone(),
+                )
+            });
+
+        // Block on the supervised server
+        runtime.block_on(supervisor).unwrap();
+    });
+}
+
+/// Runs the inspection server on the given (bound) listener
+async fn run_inspection_server(
+    listener: TcpListener,
+    node_config: NodeConfig,
+    aptos_data_client: AptosDataClient,
+    peers_and_metadata: Arc<PeersAndMetadata>,
+    mempool_prioritized_peers: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+    payload_unavailability_reporter: PayloadUnavailabilityReporter,
+) {
+    // Create the service function that handles the endpoint requests
+    let make_service = make_service_fn(move |_conn| {
+        let node_config = node_config.clone();
+        let aptos_data_client = aptos_data_client.clone();
+        let peers_and_metadata = peers_and_metadata.clone();
+        let mempool_prioritized_peers = mempool_prioritized_peers.clone();
+        let payload_unavailability_reporter = payload_unavailability_reporter.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                serve_requests(
+                    request,
+                    node_config.clone(),
+                    aptos_data_client.clone(),
+                    peers_and_metadata.clone(),
+                    mempool_prioritized_peers.clone(),
+                    payload_unavailability_reporter.clone(),
+                )
+            }))
+        }
     });
+
+    // Start and block on the server
+    let server = Server::from_tcp(listener)
+        .expect("Failed to start the inspection service on the bound listener!")
+        .serve(make_service);
+    server.await.unwrap()
 }
 
 /// A simple helper function that handles each endpoint request
@@ -103,6 +154,8 @@ async fn serve_requests(
     node_config: NodeConfig,
     aptos_data_client: AptosDataClient,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    mempool_prioritized_peers: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+    payload_unavailability_reporter: PayloadUnavailabilityReporter,
 ) -> Result<Response<Body>, hyper::Error> {
     // Process the request and get the response components
     let (status_code, body, content_type) = match req.uri().path() {
@@ -111,6 +164,14 @@ async fn serve_requests(
             // Exposes the node configuration
             configuration::handle_configuration_request(&node_config)
         },
+        CONSENSUS_PAYLOAD_UNAVAILABILITY_PATH => {
+            // /consensus/payload_unavailability
+            // Exposes the report of the execution stalls waiting for payload availability
+            consensus::handle_payload_unavailability_request(
+                &node_config,
+                payload_unavailability_reporter,
+            )
+        },
         FORGE_METRICS_PATH => {
             // /forge_metrics
             // Exposes forge encoded metrics
@@ -126,6 +187,14 @@ async fn serve_requests(
             // Exposes JSON encoded metrics
             metrics::handle_json_metrics_request()
         },
+        MEMPOOL_PRIORITIZED_PEERS_PATH => {
+            // /mempool/prioritized_peers
+            // Exposes the mempool peer priorities (and the metadata that produced them)
+            mempool::handle_mempool_prioritized_peers_request(
+                &node_config,
+                mempool_prioritized_peers,
+            )
+        },
         METRICS_PATH => {
             // /metrics
             // Exposes text encoded metrics
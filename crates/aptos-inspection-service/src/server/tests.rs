@@ -7,11 +7,17 @@ use crate::{
         peer_information::PEER_INFO_DISABLED_MESSAGE, serve_requests,
         system_information::SYS_INFO_DISABLED_MESSAGE, utils::get_all_metrics,
     },
-    CONFIGURATION_PATH, FORGE_METRICS_PATH, INDEX_PATH, JSON_METRICS_PATH, METRICS_PATH,
-    PEER_INFORMATION_PATH, SYSTEM_INFORMATION_PATH,
+    PayloadUnavailabilityReporter, CONFIGURATION_PATH, CONSENSUS_PAYLOAD_UNAVAILABILITY_PATH,
+    FORGE_METRICS_PATH, INDEX_PATH, JSON_METRICS_PATH, MEMPOOL_PRIORITIZED_PEERS_PATH,
+    METRICS_PATH, PEER_INFORMATION_PATH, SYSTEM_INFORMATION_PATH,
+};
+use aptos_config::{
+    config::{AptosDataClientConfig, BaseConfig, ClientApiKey, NodeConfig},
+    network_id::NetworkId,
 };
-use aptos_config::config::{AptosDataClientConfig, BaseConfig, NodeConfig};
 use aptos_data_client::client::AptosDataClient;
+use aptos_infallible::RwLock;
+use aptos_mempool::PrioritizedPeerInfo;
 use aptos_network::application::{interface::NetworkClient, storage::PeersAndMetadata};
 use aptos_storage_interface::DbReader;
 use aptos_storage_service_client::StorageServiceClient;
@@ -57,6 +63,28 @@ async fn test_inspect_configuration() {
     assert!(response_body_string.contains("expose_configuration: true"));
 }
 
+#[tokio::test]
+async fn test_inspect_configuration_omits_client_api_keys() {
+    // Create a validator config with a client API key
+    let api_key = "my-secret-api-key";
+    let mut node_config = NodeConfig::get_default_validator_config();
+    node_config
+        .api
+        .client_api_keys
+        .push(ClientApiKey::new("client".into(), api_key));
+
+    // Enable the configuration endpoint and ping it
+    node_config.inspection_service.expose_configuration = true;
+    let mut response = send_get_request_to_path(&node_config, CONFIGURATION_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response identifies the client but doesn't contain the key
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response_body_string.contains("ClientApiKey"));
+    assert!(!response_body_string.contains(api_key));
+}
+
 #[tokio::test]
 async fn test_inspect_forge_metrics() {
     // Create a VFN config
@@ -86,10 +114,12 @@ async fn test_inspect_index() {
     // Verify that the response contains all the endpoints
     assert_eq!(response.status(), StatusCode::OK);
     assert!(response_body_string.contains(CONFIGURATION_PATH));
+    assert!(response_body_string.contains(CONSENSUS_PAYLOAD_UNAVAILABILITY_PATH));
     assert!(response_body_string.contains(FORGE_METRICS_PATH));
     assert!(response_body_string.contains(JSON_METRICS_PATH));
     assert!(response_body_string.contains(METRICS_PATH));
     assert!(response_body_string.contains(PEER_INFORMATION_PATH));
+    assert!(response_body_string.contains(MEMPOOL_PRIORITIZED_PEERS_PATH));
     assert!(response_body_string.contains(SYSTEM_INFORMATION_PATH));
 }
 
@@ -180,6 +210,89 @@ async fn test_inspect_peer_information() {
     assert!(response_body_string.contains("State sync metadata"));
 }
 
+#[tokio::test]
+async fn test_inspect_mempool_prioritized_peers() {
+    // Create a validator fullnode config
+    let mut config = NodeConfig::get_default_vfn_config();
+
+    // Create the mempool prioritized peers
+    let prioritized_peer = PrioritizedPeerInfo {
+        peer: "Vfn:0x1".into(),
+        priority: 0,
+        network_id: NetworkId::Vfn,
+        distance_from_validators: Some(0),
+        average_ping_latency_secs: Some(0.5),
+        broadcast_ack_latency_secs: None,
+        mempool_utilization: None,
+        network_group: Some("10.0.0".into()),
+        failed_broadcasts: false,
+        penalized: false,
+        pinned: false,
+        is_top_peer: true,
+    };
+    let mempool_prioritized_peers = Arc::new(RwLock::new(vec![prioritized_peer]));
+
+    // Disable the peer information endpoint and ping the prioritized peers endpoint
+    config.inspection_service.expose_peer_information = false;
+    let mut response = send_get_request_to_path_with_mempool_peers(
+        &config,
+        MEMPOOL_PRIORITIZED_PEERS_PATH,
+        mempool_prioritized_peers.clone(),
+    )
+    .await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+
+    // Verify that the response contains an error
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response_body, PEER_INFO_DISABLED_MESSAGE);
+
+    // Enable the peer information endpoint and ping the prioritized peers endpoint
+    config.inspection_service.expose_peer_information = true;
+    let mut response = send_get_request_to_path_with_mempool_peers(
+        &config,
+        MEMPOOL_PRIORITIZED_PEERS_PATH,
+        mempool_prioritized_peers,
+    )
+    .await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response contains the prioritized peer and its metadata
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response_body_string.contains("Vfn:0x1"));
+    assert!(response_body_string.contains("\"distance_from_validators\":0"));
+    assert!(response_body_string.contains("\"average_ping_latency_secs\":0.5"));
+    assert!(response_body_string.contains("\"is_top_peer\":true"));
+}
+
+#[tokio::test]
+async fn test_inspect_consensus_payload_unavailability() {
+    // Create a validator config
+    let mut config = NodeConfig::get_default_validator_config();
+
+    // Disable the peer information endpoint and ping the payload unavailability endpoint
+    config.inspection_service.expose_peer_information = false;
+    let mut response =
+        send_get_request_to_path(&config, CONSENSUS_PAYLOAD_UNAVAILABILITY_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+
+    // Verify that the response contains an error
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response_body, PEER_INFO_DISABLED_MESSAGE);
+
+    // Enable the peer information endpoint and ping the payload unavailability endpoint
+    config.inspection_service.expose_peer_information = true;
+    let mut response =
+        send_get_request_to_path(&config, CONSENSUS_PAYLOAD_UNAVAILABILITY_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response contains the report
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response_body_string.contains("\"num_stalls\":"));
+    assert!(response_body_string.contains("\"recent_stalls\":"));
+}
+
 rusty_fork_test! {
 #[test]
 fn test_gather_metrics() {
@@ -248,6 +361,22 @@ fn test_publish_metrics() {
 
 // Exercise the serve_requests() handler with a GET request to the given path
 async fn send_get_request_to_path(config: &NodeConfig, endpoint: &str) -> Response<Body> {
+    send_get_request_to_path_with_mempool_peers(config, endpoint, Arc::new(RwLock::new(vec![])))
+        .await
+}
+
+// A payload unavailability reporter that returns an empty report
+fn empty_payload_unavailability_reporter() -> PayloadUnavailabilityReporter {
+    Arc::new(|| Ok("{\"num_stalls\":0,\"recent_stalls\":[]}".into()))
+}
+
+// Exercise the serve_requests() handler with a GET request to the given
+// path (using the given mempool prioritized peers).
+async fn send_get_request_to_path_with_mempool_peers(
+    config: &NodeConfig,
+    endpoint: &str,
+    mempool_prioritized_peers: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+) -> Response<Body> {
     // Build the URI
     let uri = format!("http://127.0.0.1:9201{}", endpoint);
 
@@ -276,6 +405,8 @@ async fn send_get_request_to_path(config: &NodeConfig, endpoint: &str) -> Respon
         config.clone(),
         aptos_data_client,
         peers_and_metadata,
+        mempool_prioritized_peers,
+        empty_payload_unavailability_reporter(),
     )
     .await
     .unwrap()
@@ -10,7 +10,7 @@ use crate::{
         PROCESSED_STRUCT_LOG_COUNT, STRUCT_LOG_PARSE_ERROR_COUNT, STRUCT_LOG_QUEUE_ERROR_COUNT,
     },
     logger::Logger,
-    sample,
+    recent_logs, sample,
     sample::SampleRate,
     telemetry_log_writer::{TelemetryLog, TelemetryLogWriter},
     Event, Filter, Key, Level, LevelFilter, Metadata, ERROR_LOG_COUNT, INFO_LOG_COUNT,
@@ -305,6 +305,7 @@ pub struct AptosDataBuilder {
     is_async: bool,
     enable_telemetry_flush: bool,
     custom_format: Option<fn(&LogEntry) -> Result<String, fmt::Error>>,
+    recent_logs_capacity: usize,
 }
 
 impl AptosDataBuilder {
@@ -322,6 +323,7 @@ impl AptosDataBuilder {
             is_async: false,
             enable_telemetry_flush: true,
             custom_format: None,
+            recent_logs_capacity: 0,
         }
     }
 
@@ -373,6 +375,12 @@ impl AptosDataBuilder {
         self
     }
 
+    /// Sets the number of recent log entries to hold in memory (see `recent_logs`)
+    pub fn recent_logs_capacity(&mut self, recent_logs_capacity: usize) -> &mut Self {
+        self.recent_logs_capacity = recent_logs_capacity;
+        self
+    }
+
     pub fn enable_telemetry_flush(&mut self, enable_telemetry_flush: bool) -> &mut Self {
         self.enable_telemetry_flush = enable_telemetry_flush;
         self
@@ -426,6 +434,7 @@ impl AptosDataBuilder {
 
     fn build_logger(&mut self) -> Arc<AptosData> {
         let filter = self.build_filter();
+        recent_logs::set_recent_logs_capacity(self.recent_logs_capacity);
 
         if let Ok(log_format) = env::var(RUST_LOG_FORMAT) {
             let log_format = LogFormat::from_str(&log_format).unwrap();
@@ -547,6 +556,11 @@ impl AptosData {
     }
 
     fn send_entry(&self, entry: LogEntry) {
+        // Record the entry (in synchronous mode). Otherwise, this is done by the logger service.
+        if self.sender.is_none() {
+            record_recent_log(&entry);
+        }
+
         if let Some(printer) = &self.printer {
             let s = (self.formatter)(&entry).expect("Unable to format");
             printer.write(s);
@@ -618,6 +632,7 @@ impl LoggerService {
             match event {
                 LoggerServiceEvent::LogEntry(entry) => {
                     PROCESSED_STRUCT_LOG_COUNT.inc();
+                    record_recent_log(&entry);
                     match entry.metadata.level() {
                         Level::Error => ERROR_LOG_COUNT.inc(),
                         Level::Warn => WARN_LOG_COUNT.inc(),
@@ -776,6 +791,15 @@ fn text_format(entry: &LogEntry) -> Result<String, fmt::Error> {
     Ok(w)
 }
 
+/// Records the entry in the recent log entries (if enabled)
+fn record_recent_log(entry: &LogEntry) {
+    if recent_logs::is_enabled() {
+        if let Ok(log) = json_format(entry) {
+            recent_logs::record_log(log);
+        }
+    }
+}
+
 // converts a record into json format
 fn json_format(entry: &LogEntry) -> Result<String, fmt::Error> {
     match serde_json::to_string(&entry) {
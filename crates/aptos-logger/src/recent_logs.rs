@@ -0,0 +1,107 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A bounded buffer of the most recent log entries (in JSON format). This is disabled
+//! by default (i.e., the capacity is 0), and is useful for post-mortem debugging, e.g.,
+//! the recent entries can be included in crash reports.
+
+use once_cell::sync::Lazy;
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+/// The process-wide buffer of recent log entries
+static RECENT_LOGS: Lazy<RecentLogs> = Lazy::new(RecentLogs::default);
+
+/// Sets the maximum number of recent log entries to hold (0 disables the buffer)
+pub fn set_recent_logs_capacity(capacity: usize) {
+    RECENT_LOGS.set_capacity(capacity);
+}
+
+/// Returns the most recent log entries (ordered from oldest to newest)
+pub fn get_recent_logs() -> Vec<String> {
+    RECENT_LOGS.get_logs()
+}
+
+/// Returns true iff recent log entries are being held
+pub(crate) fn is_enabled() -> bool {
+    RECENT_LOGS.capacity.load(Ordering::Relaxed) > 0
+}
+
+/// Records the given (formatted) log entry
+pub(crate) fn record_log(log: String) {
+    RECENT_LOGS.record_log(log);
+}
+
+/// A bounded buffer of log entries (ordered from oldest to newest)
+#[derive(Debug, Default)]
+struct RecentLogs {
+    capacity: AtomicUsize,
+    logs: Mutex<VecDeque<String>>,
+}
+
+impl RecentLogs {
+    fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity, Ordering::Relaxed);
+        truncate_logs(&mut self.lock_logs(), capacity);
+    }
+
+    /// Records the given log entry, evicting the oldest entries if required
+    fn record_log(&self, log: String) {
+        let capacity = self.capacity.load(Ordering::Relaxed);
+        if capacity == 0 {
+            return;
+        }
+
+        let mut logs = self.lock_logs();
+        logs.push_back(log);
+        truncate_logs(&mut logs, capacity);
+    }
+
+    fn get_logs(&self) -> Vec<String> {
+        self.lock_logs().iter().cloned().collect()
+    }
+
+    /// Locks the log entries. Note: this is used when handling panics, so
+    /// the lock is recovered (instead of panicking) if it was poisoned.
+    fn lock_logs(&self) -> MutexGuard<'_, VecDeque<String>> {
+        self.logs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Evicts the oldest entries until the number of entries is within the capacity
+fn truncate_logs(logs: &mut VecDeque<String>, capacity: usize) {
+    while logs.len() > capacity {
+        logs.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RecentLogs;
+
+    #[test]
+    fn test_recent_logs() {
+        // Verify that no entries are held by default
+        let recent_logs = RecentLogs::default();
+        recent_logs.record_log("ignored".into());
+        assert!(recent_logs.get_logs().is_empty());
+
+        // Enable the buffer and record more entries than the capacity
+        recent_logs.set_capacity(2);
+        for index in 0..3 {
+            recent_logs.record_log(format!("log {}", index));
+        }
+        assert_eq!(recent_logs.get_logs(), vec!["log 1", "log 2"]);
+
+        // Shrink the capacity and verify the oldest entries are evicted
+        recent_logs.set_capacity(1);
+        assert_eq!(recent_logs.get_logs(), vec!["log 2"]);
+    }
+}
@@ -310,6 +310,7 @@ impl From<RestError> for ApiError {
                 AptosErrorCode::BcsNotSupported => ApiError::InvalidInput(Some(err.error.message)),
                 AptosErrorCode::InternalError => ApiError::InternalError(Some(err.error.message)),
                 AptosErrorCode::ApiDisabled => ApiError::InternalError(Some(err.error.message)),
+                AptosErrorCode::RateLimited => ApiError::InternalError(Some(err.error.message)),
             },
             RestError::Bcs(_) => ApiError::DeserializationFailed(None),
             RestError::Json(_) => ApiError::DeserializationFailed(None),
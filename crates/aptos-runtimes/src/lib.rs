@@ -5,6 +5,17 @@ use rayon::{ThreadPool, ThreadPoolBuilder};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::runtime::{Builder, Runtime};
 
+mod resource_limits;
+mod supervisor;
+
+pub use resource_limits::{
+    detect_cgroup_cpu_quota, detect_cgroup_io_limits, num_available_cpus,
+    set_num_available_cpus_override,
+};
+pub use supervisor::{
+    record_supervised_panic, spawn_supervised, spawn_supervised_runtime, supervised_component,
+};
+
 /// The max thread name length before the name will be truncated
 /// when it's displayed. Note: the max display length is 15, but
 /// we need to leave space for the thread IDs.
@@ -12,6 +23,8 @@ const MAX_THREAD_NAME_LENGTH: usize = 12;
 
 /// Returns a tokio runtime with named threads.
 /// This is useful for tracking threads when debugging.
+/// If the number of worker threads isn't specified, the
+/// runtime is sized for `num_available_cpus()`.
 pub fn spawn_named_runtime(thread_name: String, num_worker_threads: Option<usize>) -> Runtime {
     spawn_named_runtime_with_start_hook(thread_name, num_worker_threads, || {})
 }
@@ -49,9 +62,7 @@ where
         // Rest API calls overwhelm the node.
         .max_blocking_threads(MAX_BLOCKING_THREADS)
         .enable_all();
-    if let Some(num_worker_threads) = num_worker_threads {
-        builder.worker_threads(num_worker_threads);
-    }
+    builder.worker_threads(num_worker_threads.unwrap_or_else(num_available_cpus));
 
     // Spawn and return the runtime
     builder.build().unwrap_or_else(|error| {
@@ -64,6 +75,8 @@ where
 
 /// Returns a rayon threadpool with threads.
 /// This is useful for tracking threads when debugging.
+/// If the number of worker threads isn't specified, the
+/// threadpool is sized for `num_available_cpus()`.
 pub fn spawn_rayon_thread_pool(
     thread_name: String,
     num_worker_threads: Option<usize>,
@@ -88,13 +101,10 @@ where
     }
 
     let thread_name_clone = thread_name.clone();
-    let mut builder = ThreadPoolBuilder::new()
+    let builder = ThreadPoolBuilder::new()
         .thread_name(move |index| format!("{}-{index}", thread_name_clone))
-        .start_handler(move |_| on_thread_start());
-
-    if let Some(num_worker_threads) = num_worker_threads {
-        builder = builder.num_threads(num_worker_threads);
-    }
+        .start_handler(move |_| on_thread_start())
+        .num_threads(num_worker_threads.unwrap_or_else(num_available_cpus));
 
     // Spawn and return the threadpool
     builder.build().unwrap_or_else(|error| {
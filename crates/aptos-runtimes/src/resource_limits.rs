@@ -0,0 +1,166 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    fs,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+// Cgroup (v2) files that expose the CPU and IO limits of the container
+const CGROUP_V2_CPU_MAX_FILE: &str = "/sys/fs/cgroup/cpu.max";
+const CGROUP_V2_IO_MAX_FILE: &str = "/sys/fs/cgroup/io.max";
+
+// Cgroup (v1) files that expose the CPU and IO limits of the container
+const CGROUP_V1_CPU_QUOTA_FILE: &str = "/sys/fs/cgroup/cpu/cpu.cfs_quota_us";
+const CGROUP_V1_CPU_PERIOD_FILE: &str = "/sys/fs/cgroup/cpu/cpu.cfs_period_us";
+const CGROUP_V1_IO_THROTTLE_FILES: [&str; 4] = [
+    "/sys/fs/cgroup/blkio/blkio.throttle.read_bps_device",
+    "/sys/fs/cgroup/blkio/blkio.throttle.write_bps_device",
+    "/sys/fs/cgroup/blkio/blkio.throttle.read_iops_device",
+    "/sys/fs/cgroup/blkio/blkio.throttle.write_iops_device",
+];
+
+/// The number of CPUs to use instead of the detected number (0 if not set)
+static NUM_AVAILABLE_CPUS_OVERRIDE: AtomicUsize = AtomicUsize::new(0);
+
+/// Overrides the number of CPUs returned by `num_available_cpus()`.
+/// This should be called at startup, before any runtimes or thread pools
+/// are spawned. An override of 0 is ignored.
+pub fn set_num_available_cpus_override(num_cpus: usize) {
+    NUM_AVAILABLE_CPUS_OVERRIDE.store(num_cpus, Ordering::Relaxed);
+}
+
+/// Returns the number of CPUs that runtimes and thread pools should be sized for.
+/// If an override has been set, the override is returned. Otherwise, this is the
+/// number of host cores, capped by the cgroup CPU quota (if any). This prevents
+/// containerized nodes from oversubscribing their CPU quota.
+pub fn num_available_cpus() -> usize {
+    let num_cpus_override = NUM_AVAILABLE_CPUS_OVERRIDE.load(Ordering::Relaxed);
+    if num_cpus_override > 0 {
+        return num_cpus_override;
+    }
+
+    let num_host_cpus = std::thread::available_parallelism()
+        .map(|num_cpus| num_cpus.get())
+        .unwrap_or(1);
+    match detect_cgroup_cpu_quota() {
+        Some(cpu_quota) => num_host_cpus.min(cpu_quota.ceil() as usize).max(1),
+        None => num_host_cpus,
+    }
+}
+
+/// Returns the cgroup CPU quota (in number of CPUs), if one is set
+pub fn detect_cgroup_cpu_quota() -> Option<f64> {
+    if let Ok(cpu_max) = fs::read_to_string(CGROUP_V2_CPU_MAX_FILE) {
+        return parse_cgroup_v2_cpu_max(&cpu_max);
+    }
+    let cpu_quota = fs::read_to_string(CGROUP_V1_CPU_QUOTA_FILE).ok()?;
+    let cpu_period = fs::read_to_string(CGROUP_V1_CPU_PERIOD_FILE).ok()?;
+    parse_cgroup_v1_cpu_quota(&cpu_quota, &cpu_period)
+}
+
+/// Returns true iff cgroup IO limits (i.e., bandwidth or IOPS throttling)
+/// are set for any device
+pub fn detect_cgroup_io_limits() -> bool {
+    if let Ok(io_max) = fs::read_to_string(CGROUP_V2_IO_MAX_FILE) {
+        return parse_cgroup_v2_io_max(&io_max);
+    }
+    CGROUP_V1_IO_THROTTLE_FILES.iter().any(|file| {
+        fs::read_to_string(file)
+            .map(|throttle| !throttle.trim().is_empty())
+            .unwrap_or(false)
+    })
+}
+
+/// Parses the contents of a cgroup v2 `cpu.max` file (e.g., "200000 100000"
+/// or "max 100000") into a CPU quota
+fn parse_cgroup_v2_cpu_max(cpu_max: &str) -> Option<f64> {
+    let mut values = cpu_max.split_whitespace();
+    let quota = values.next()?;
+    let period = values.next()?;
+    if quota == "max" {
+        return None; // There is no quota
+    }
+    to_cpu_quota(quota.parse().ok()?, period.parse().ok()?)
+}
+
+/// Parses the contents of the cgroup v1 `cpu.cfs_quota_us` and `cpu.cfs_period_us`
+/// files into a CPU quota. A negative quota (i.e., -1) means there is no quota.
+fn parse_cgroup_v1_cpu_quota(cpu_quota: &str, cpu_period: &str) -> Option<f64> {
+    let quota: i64 = cpu_quota.trim().parse().ok()?;
+    let period: i64 = cpu_period.trim().parse().ok()?;
+    if quota < 0 || period < 0 {
+        return None; // There is no quota
+    }
+    to_cpu_quota(quota as u64, period as u64)
+}
+
+/// Converts a CPU quota and period (in microseconds) into a number of CPUs
+fn to_cpu_quota(quota: u64, period: u64) -> Option<f64> {
+    if quota == 0 || period == 0 {
+        return None; // The quota is invalid
+    }
+    Some(quota as f64 / period as f64)
+}
+
+/// Parses the contents of a cgroup v2 `io.max` file and returns true iff any
+/// device has a limit. Each line has the format "MAJ:MIN rbps=max wbps=max
+/// riops=max wiops=max", where any value other than "max" is a limit.
+fn parse_cgroup_v2_io_max(io_max: &str) -> bool {
+    io_max.lines().any(|line| {
+        line.split_whitespace()
+            .skip(1) // Skip the device number
+            .filter_map(|limit| limit.split_once('='))
+            .any(|(_, value)| value != "max")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cgroup_v2_cpu_max() {
+        assert_eq!(parse_cgroup_v2_cpu_max("200000 100000\n"), Some(2.0));
+        assert_eq!(parse_cgroup_v2_cpu_max("150000 100000"), Some(1.5));
+        assert_eq!(parse_cgroup_v2_cpu_max("max 100000\n"), None);
+        assert_eq!(parse_cgroup_v2_cpu_max("0 100000"), None);
+        assert_eq!(parse_cgroup_v2_cpu_max(""), None);
+        assert_eq!(parse_cgroup_v2_cpu_max("invalid"), None);
+    }
+
+    #[test]
+    fn test_parse_cgroup_v1_cpu_quota() {
+        assert_eq!(parse_cgroup_v1_cpu_quota("400000\n", "100000\n"), Some(4.0));
+        assert_eq!(parse_cgroup_v1_cpu_quota("50000", "100000"), Some(0.5));
+        assert_eq!(parse_cgroup_v1_cpu_quota("-1\n", "100000\n"), None);
+        assert_eq!(parse_cgroup_v1_cpu_quota("100000", "0"), None);
+        assert_eq!(parse_cgroup_v1_cpu_quota("invalid", "100000"), None);
+    }
+
+    #[test]
+    fn test_parse_cgroup_v2_io_max() {
+        assert!(!parse_cgroup_v2_io_max(""));
+        assert!(!parse_cgroup_v2_io_max(
+            "8:0 rbps=max wbps=max riops=max wiops=max\n"
+        ));
+        assert!(parse_cgroup_v2_io_max(
+            "8:0 rbps=max wbps=max riops=max wiops=max\n8:16 rbps=1048576 wbps=max riops=max wiops=max\n"
+        ));
+        assert!(parse_cgroup_v2_io_max("259:0 wiops=1000\n"));
+    }
+
+    #[test]
+    fn test_num_available_cpus_override() {
+        // Verify the detected number of CPUs is always positive
+        assert!(num_available_cpus() > 0);
+
+        // Set an override and verify it is used
+        set_num_available_cpus_override(3);
+        assert_eq!(num_available_cpus(), 3);
+
+        // Reset the override
+        set_num_available_cpus_override(0);
+        assert!(num_available_cpus() > 0);
+    }
+}
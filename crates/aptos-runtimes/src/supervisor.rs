@@ -0,0 +1,166 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A supervisor for non-safety-critical components (e.g., the API or the inspection
+//! service). Panics on the threads of a supervised runtime are contained (instead of
+//! taking down the whole node), and supervised tasks are restarted with backoff.
+//! Safety-critical components (e.g., consensus and storage) should never be supervised,
+//! so that they continue to fail fast.
+
+use crate::spawn_named_runtime_with_start_hook;
+use aptos_metrics_core::{register_int_counter_vec, IntCounterVec};
+use once_cell::sync::Lazy;
+use std::{
+    cell::Cell,
+    cmp::min,
+    future::Future,
+    time::{Duration, Instant},
+};
+use tokio::{
+    runtime::{Handle, Runtime},
+    task::JoinHandle,
+};
+
+/// The backoff before the first restart of a supervised task
+const INITIAL_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+/// The maximum backoff between restarts of a supervised task
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Counter for the panics observed in each supervised component
+static SUPERVISED_COMPONENT_PANICS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_supervised_component_panics",
+        "Number of panics (contained by the supervisor) in each supervised component",
+        &["component"]
+    )
+    .unwrap()
+});
+
+/// Counter for the restarts of each supervised component
+static SUPERVISED_COMPONENT_RESTARTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_supervised_component_restarts",
+        "Number of times a task of each supervised component was restarted after a panic",
+        &["component"]
+    )
+    .unwrap()
+});
+
+thread_local! {
+    /// The supervised component that owns the current thread (if any)
+    static SUPERVISED_COMPONENT: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+/// Returns the supervised component that owns the current thread (if any).
+/// This is used by the panic handler to decide whether a panic is contained.
+pub fn supervised_component() -> Option<&'static str> {
+    SUPERVISED_COMPONENT.with(|component| component.get())
+}
+
+/// Records a (contained) panic in the given supervised component
+pub fn record_supervised_panic(component: &'static str) {
+    SUPERVISED_COMPONENT_PANICS
+        .with_label_values(&[component])
+        .inc();
+}
+
+/// Returns a tokio runtime with named threads that belong to the given supervised
+/// component, i.e., panics on these threads unwind the panicking task (instead of
+/// exiting the process).
+pub fn spawn_supervised_runtime(
+    thread_name: String,
+    component: &'static str,
+    num_worker_threads: Option<usize>,
+) -> Runtime {
+    spawn_named_runtime_with_start_hook(thread_name, num_worker_threads, move || {
+        SUPERVISED_COMPONENT.with(|supervised_component| supervised_component.set(Some(component)))
+    })
+}
+
+/// Spawns the task created by `create_task` on the given runtime, and recreates the
+/// task (with exponential backoff) whenever it panics. The task is not restarted if
+/// it completes normally. Note: the runtime must be a supervised runtime (see
+/// `spawn_supervised_runtime`), otherwise panics will still exit the process.
+pub fn spawn_supervised<F, Fut>(
+    runtime_handle: &Handle,
+    component: &'static str,
+    create_task: F,
+) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let task_handle = runtime_handle.clone();
+    runtime_handle.spawn(async move {
+        let mut restart_backoff = INITIAL_RESTART_BACKOFF;
+        loop {
+            let start_time = Instant::now();
+            match task_handle.spawn(create_task()).await {
+                Ok(()) => return, // The task completed normally
+                Err(error) if error.is_panic() => {
+                    // If the task ran for a while before panicking, reset the backoff
+                    if start_time.elapsed() > MAX_RESTART_BACKOFF {
+                        restart_backoff = INITIAL_RESTART_BACKOFF;
+                    }
+                    // Note: the panic itself is logged by the panic handler
+                    tokio::time::sleep(restart_backoff).await;
+                    restart_backoff = min(restart_backoff * 2, MAX_RESTART_BACKOFF);
+
+                    SUPERVISED_COMPONENT_RESTARTS
+                        .with_label_values(&[component])
+                        .inc();
+                },
+                Err(_) => return, // The task was cancelled (e.g., the runtime is shutting down)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[test]
+    fn test_supervised_runtime_threads() {
+        // Verify that the current thread is not supervised
+        assert_eq!(supervised_component(), None);
+
+        // Verify that the threads of a supervised runtime belong to the component
+        let runtime = spawn_supervised_runtime("test-super".into(), "test", Some(1));
+        let component = runtime
+            .block_on(runtime.spawn(async { supervised_component() }))
+            .unwrap();
+        assert_eq!(component, Some("test"));
+    }
+
+    #[test]
+    fn test_spawn_supervised_restarts_on_panic() {
+        let runtime = spawn_supervised_runtime("test-super".into(), "test", Some(1));
+
+        // Create a task that panics on its first run, and completes on the second
+        let num_runs = Arc::new(AtomicUsize::new(0));
+        let num_runs_clone = num_runs.clone();
+        let supervisor = spawn_supervised(runtime.handle(), "test", move || {
+            let num_runs = num_runs_clone.clone();
+            async move {
+                if num_runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("Injected panic in supervised task!");
+                }
+            }
+        });
+
+        // Verify that the task was restarted once, and the supervisor completed
+        runtime.block_on(supervisor).unwrap();
+        assert_eq!(num_runs.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            SUPERVISED_COMPONENT_RESTARTS
+                .with_label_values(&["test"])
+                .get(),
+            1
+        );
+    }
+}
@@ -0,0 +1,310 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::{config::TelemetryPeerIdPrivacy, network_id::PeerNetworkId};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::warn;
+use aptos_network::application::storage::PeersAndMetadata;
+use aptos_telemetry_service::types::telemetry::TelemetryEvent;
+use prometheus::core::Collector;
+use rand::Rng;
+use rand_core::OsRng;
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
+
+/// Connection metadata event name
+const APTOS_NODE_CONNECTION_METADATA: &str = "APTOS_NODE_CONNECTION_METADATA";
+
+/// Connection metadata keys
+const CONNECTED_PEERS: &str = "connected_peers";
+const CONNECTED_PEER_IDS: &str = "connected_peer_ids";
+const PEER_CHURN_CONNECTS: &str = "peer_churn_connects";
+const PEER_CHURN_DISCONNECTS: &str = "peer_churn_disconnects";
+const PEER_CHURN_RATE_PER_MIN: &str = "peer_churn_rate_per_min";
+
+/// The number of hex characters of an anonymized peer ID
+const ANONYMIZED_PEER_ID_LENGTH: usize = 16;
+
+/// Collects aggregated connection metadata (e.g., peer counts per network and
+/// role, peer churn and handshake failure causes) to export via telemetry.
+/// Peer IDs are only exported if allowed by the configured privacy mode.
+pub(crate) struct ConnectionMetadataCollector {
+    peers_and_metadata: Arc<PeersAndMetadata>,
+    peer_id_privacy: TelemetryPeerIdPrivacy,
+    anonymization_salt: [u8; 32], // A random (per-process) salt used to anonymize peer IDs
+    previous_connected_peers: Mutex<Option<(HashSet<PeerNetworkId>, Instant)>>,
+}
+
+impl ConnectionMetadataCollector {
+    pub(crate) fn new(
+        peers_and_metadata: Arc<PeersAndMetadata>,
+        peer_id_privacy: TelemetryPeerIdPrivacy,
+    ) -> Self {
+        Self {
+            peers_and_metadata,
+            peer_id_privacy,
+            anonymization_salt: OsRng.gen(),
+            previous_connected_peers: Mutex::new(None),
+        }
+    }
+
+    /// Collects the connection metadata and returns it as a telemetry event
+    pub(crate) async fn create_connection_metadata_telemetry_event(&self) -> TelemetryEvent {
+        TelemetryEvent {
+            name: APTOS_NODE_CONNECTION_METADATA.into(),
+            params: self.collect_connection_metadata(),
+        }
+    }
+
+    /// Collects the connection metadata of all connected peers
+    fn collect_connection_metadata(&self) -> BTreeMap<String, String> {
+        let mut connection_metadata = BTreeMap::new();
+
+        // Fetch the metadata of all connected peers
+        let connected_peers_and_metadata =
+            match self.peers_and_metadata.get_connected_peers_and_metadata() {
+                Ok(connected_peers_and_metadata) => connected_peers_and_metadata,
+                Err(error) => {
+                    warn!(
+                        "Failed to fetch the connected peers and metadata: {:?}",
+                        error
+                    );
+                    return connection_metadata;
+                },
+            };
+
+        // Count the peers per network, connection origin, role and messaging protocol
+        let mut peer_counts: BTreeMap<String, u64> = BTreeMap::new();
+        for (peer_network_id, peer_metadata) in connected_peers_and_metadata.iter() {
+            let network_id = peer_network_id.network_id();
+            let connection_metadata = peer_metadata.get_connection_metadata();
+            for key in [
+                format!("{}_peers", network_id),
+                format!(
+                    "{}_{}_peers",
+                    network_id,
+                    connection_metadata.origin.as_str()
+                ),
+                format!("{}_{}_peers", network_id, connection_metadata.role.as_str()),
+                format!(
+                    "messaging_protocol_{}_peers",
+                    connection_metadata.messaging_protocol
+                ),
+            ] {
+                *peer_counts.entry(key.to_lowercase()).or_default() += 1;
+            }
+        }
+        for (key, count) in peer_counts {
+            connection_metadata.insert(key, count.to_string());
+        }
+        connection_metadata.insert(
+            CONNECTED_PEERS.into(),
+            connected_peers_and_metadata.len().to_string(),
+        );
+
+        // Calculate the peer churn since the last collection
+        let connected_peers: HashSet<PeerNetworkId> =
+            connected_peers_and_metadata.keys().cloned().collect();
+        let now = Instant::now();
+        let mut previous_connected_peers = self.previous_connected_peers.lock();
+        if let Some((previous_peers, previous_time)) = previous_connected_peers.as_ref() {
+            let num_connects = connected_peers.difference(previous_peers).count();
+            let num_disconnects = previous_peers.difference(&connected_peers).count();
+            let elapsed_mins = now.duration_since(*previous_time).as_secs_f64() / 60.0;
+            let churn_rate_per_min = if elapsed_mins > 0.0 {
+                (num_connects + num_disconnects) as f64 / elapsed_mins
+            } else {
+                0.0
+            };
+            connection_metadata.insert(PEER_CHURN_CONNECTS.into(), num_connects.to_string());
+            connection_metadata.insert(PEER_CHURN_DISCONNECTS.into(), num_disconnects.to_string());
+            connection_metadata.insert(
+                PEER_CHURN_RATE_PER_MIN.into(),
+                format!("{:.2}", churn_rate_per_min),
+            );
+        }
+
+        // Export the connected peer IDs (if allowed by the privacy mode)
+        if let Some(peer_ids) = self.export_peer_ids(&connected_peers) {
+            connection_metadata.insert(CONNECTED_PEER_IDS.into(), peer_ids);
+        }
+        *previous_connected_peers = Some((connected_peers, now));
+
+        // Collect the handshake failure causes
+        collect_handshake_failure_metrics(&mut connection_metadata);
+
+        connection_metadata
+    }
+
+    /// Returns the given peers as a sorted, comma separated list of "network:peer_id"
+    /// entries. The peer IDs are anonymized or omitted according to the privacy mode.
+    fn export_peer_ids(&self, peers: &HashSet<PeerNetworkId>) -> Option<String> {
+        let mut peer_ids: Vec<String> = match self.peer_id_privacy {
+            TelemetryPeerIdPrivacy::Omit => return None,
+            TelemetryPeerIdPrivacy::Anonymized => peers
+                .iter()
+                .map(|peer| format!("{}:{}", peer.network_id(), self.anonymize_peer_id(peer)))
+                .collect(),
+            TelemetryPeerIdPrivacy::Plaintext => peers
+                .iter()
+                .map(|peer| format!("{}:{}", peer.network_id(), peer.peer_id()))
+                .collect(),
+        };
+        peer_ids.sort();
+        Some(peer_ids.join(","))
+    }
+
+    /// Anonymizes the peer ID by hashing it with the (per-process) salt. This
+    /// allows peers to be distinguished across events of the same process,
+    /// without revealing their identities.
+    fn anonymize_peer_id(&self, peer: &PeerNetworkId) -> String {
+        let mut bytes = self.anonymization_salt.to_vec();
+        bytes.extend_from_slice(peer.peer_id().as_ref());
+        HashValue::sha3_256_of(&bytes).to_hex()[..ANONYMIZED_PEER_ID_LENGTH].to_string()
+    }
+}
+
+/// Collects the number of handshake failures per cause and appends them to the given map
+fn collect_handshake_failure_metrics(connection_metadata: &mut BTreeMap<String, String>) {
+    let mut handshake_failures: BTreeMap<String, f64> = BTreeMap::new();
+    for metric_family in aptos_network::counters::APTOS_NETWORK_HANDSHAKE_FAILURES.collect() {
+        for metric in metric_family.get_metric() {
+            for label in metric.get_label() {
+                if label.get_name() == "cause" {
+                    *handshake_failures
+                        .entry(label.get_value().to_string())
+                        .or_default() += metric.get_counter().get_value();
+                }
+            }
+        }
+    }
+    for (cause, count) in handshake_failures {
+        connection_metadata.insert(format!("handshake_failures_{}", cause), count.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_config::{config::PeerRole, network_id::NetworkId};
+    use aptos_netcore::transport::ConnectionOrigin;
+    use aptos_network::transport::ConnectionMetadata;
+    use aptos_types::PeerId;
+
+    #[test]
+    fn test_collect_connection_metadata() {
+        // Create the peers and metadata with two connected peers
+        let peers_and_metadata = PeersAndMetadata::new(&[NetworkId::Validator, NetworkId::Public]);
+        let validator_peer = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+        let public_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        insert_connected_peer(
+            &peers_and_metadata,
+            validator_peer,
+            PeerRole::Validator,
+            ConnectionOrigin::Outbound,
+        );
+        insert_connected_peer(
+            &peers_and_metadata,
+            public_peer,
+            PeerRole::Unknown,
+            ConnectionOrigin::Inbound,
+        );
+
+        // Collect the connection metadata and verify the peer counts
+        let collector =
+            ConnectionMetadataCollector::new(peers_and_metadata.clone(), Default::default());
+        let connection_metadata = collector.collect_connection_metadata();
+        assert_eq!(connection_metadata[CONNECTED_PEERS], "2");
+        assert_eq!(connection_metadata["validator_peers"], "1");
+        assert_eq!(connection_metadata["validator_outbound_peers"], "1");
+        assert_eq!(connection_metadata["validator_validator_peers"], "1");
+        assert_eq!(connection_metadata["public_inbound_peers"], "1");
+        assert_eq!(connection_metadata["public_unknown_peer_peers"], "1");
+        assert_eq!(connection_metadata["messaging_protocol_v1_peers"], "2");
+
+        // Verify that the churn is not reported for the first collection
+        assert!(!connection_metadata.contains_key(PEER_CHURN_CONNECTS));
+
+        // Disconnect a peer and connect a new one
+        let connection_id = peers_and_metadata
+            .get_metadata_for_peer(public_peer)
+            .unwrap()
+            .get_connection_metadata()
+            .connection_id;
+        peers_and_metadata
+            .remove_peer_metadata(public_peer, connection_id)
+            .unwrap();
+        insert_connected_peer(
+            &peers_and_metadata,
+            PeerNetworkId::new(NetworkId::Public, PeerId::random()),
+            PeerRole::Unknown,
+            ConnectionOrigin::Inbound,
+        );
+
+        // Verify the churn is reported
+        let connection_metadata = collector.collect_connection_metadata();
+        assert_eq!(connection_metadata[CONNECTED_PEERS], "2");
+        assert_eq!(connection_metadata[PEER_CHURN_CONNECTS], "1");
+        assert_eq!(connection_metadata[PEER_CHURN_DISCONNECTS], "1");
+    }
+
+    #[test]
+    fn test_peer_id_privacy() {
+        // Create the peers and metadata with a single connected peer
+        let peers_and_metadata = PeersAndMetadata::new(&[NetworkId::Validator]);
+        let peer = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+        insert_connected_peer(
+            &peers_and_metadata,
+            peer,
+            PeerRole::Validator,
+            ConnectionOrigin::Outbound,
+        );
+
+        // Verify that peer IDs are omitted by default
+        let collector =
+            ConnectionMetadataCollector::new(peers_and_metadata.clone(), Default::default());
+        let connection_metadata = collector.collect_connection_metadata();
+        assert!(!connection_metadata.contains_key(CONNECTED_PEER_IDS));
+
+        // Verify that anonymized peer IDs don't reveal the peer ID
+        let collector = ConnectionMetadataCollector::new(
+            peers_and_metadata.clone(),
+            TelemetryPeerIdPrivacy::Anonymized,
+        );
+        let connection_metadata = collector.collect_connection_metadata();
+        let peer_ids = &connection_metadata[CONNECTED_PEER_IDS];
+        assert!(peer_ids.starts_with("Validator:"));
+        assert!(!peer_ids.contains(&peer.peer_id().short_str().to_string()));
+
+        // Verify that anonymized peer IDs are stable for the same collector
+        let connection_metadata = collector.collect_connection_metadata();
+        assert_eq!(&connection_metadata[CONNECTED_PEER_IDS], peer_ids);
+
+        // Verify that plaintext peer IDs are exported as is
+        let collector =
+            ConnectionMetadataCollector::new(peers_and_metadata, TelemetryPeerIdPrivacy::Plaintext);
+        let connection_metadata = collector.collect_connection_metadata();
+        assert_eq!(
+            connection_metadata[CONNECTED_PEER_IDS],
+            format!("Validator:{}", peer.peer_id())
+        );
+    }
+
+    /// Inserts a connected peer with the given role and origin into the peers and metadata
+    fn insert_connected_peer(
+        peers_and_metadata: &PeersAndMetadata,
+        peer: PeerNetworkId,
+        role: PeerRole,
+        origin: ConnectionOrigin,
+    ) {
+        let connection_metadata =
+            ConnectionMetadata::mock_with_role_and_origin(peer.peer_id(), role, origin);
+        peers_and_metadata
+            .insert_connection_metadata(peer, connection_metadata)
+            .unwrap();
+    }
+}
@@ -3,6 +3,7 @@
 
 #![forbid(unsafe_code)]
 
+mod connection_metadata;
 mod constants;
 mod core_metrics;
 mod metrics;
@@ -4,7 +4,8 @@
 #![forbid(unsafe_code)]
 
 use crate::{
-    constants::*, core_metrics::create_core_metric_telemetry_event, metrics,
+    connection_metadata::ConnectionMetadataCollector, constants::*,
+    core_metrics::create_core_metric_telemetry_event, metrics,
     network_metrics::create_network_metric_telemetry_event, sender::TelemetrySender,
     system_information::create_system_info_telemetry_event,
     telemetry_log_sender::TelemetryLogSender, utils::create_build_info_telemetry_event,
@@ -14,6 +15,7 @@ use aptos_logger::{
     aptos_logger::RUST_LOG_TELEMETRY, prelude::*, telemetry_log_writer::TelemetryLog,
     LoggerFilterUpdater,
 };
+use aptos_network::application::storage::PeersAndMetadata;
 use aptos_telemetry_service::types::telemetry::{TelemetryDump, TelemetryEvent};
 use aptos_types::chain_id::ChainId;
 use futures::channel::mpsc::{self, Receiver};
@@ -26,6 +28,7 @@ use std::{
     collections::BTreeMap,
     env,
     future::Future,
+    sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tokio::{runtime::Runtime, task::JoinHandle, time};
@@ -103,6 +106,7 @@ pub fn start_telemetry_service(
     build_info: BTreeMap<String, String>,
     remote_log_rx: Option<mpsc::Receiver<TelemetryLog>>,
     logger_filter_update_job: Option<LoggerFilterUpdater>,
+    peers_and_metadata: Arc<PeersAndMetadata>,
 ) -> Option<Runtime> {
     if enable_prometheus_node_metrics() {
         aptos_node_resource_metrics::register_node_metrics_collector();
@@ -122,6 +126,7 @@ pub fn start_telemetry_service(
         build_info,
         remote_log_rx,
         logger_filter_update_job,
+        peers_and_metadata,
     ));
 
     Some(telemetry_runtime)
@@ -133,6 +138,7 @@ async fn spawn_telemetry_service(
     build_info: BTreeMap<String, String>,
     remote_log_rx: Option<mpsc::Receiver<TelemetryLog>>,
     logger_filter_update_job: Option<LoggerFilterUpdater>,
+    peers_and_metadata: Arc<PeersAndMetadata>,
 ) {
     let telemetry_svc_url = env::var(ENV_TELEMETRY_SERVICE_URL).unwrap_or_else(|_| {
         if chain_id == ChainId::mainnet() {
@@ -185,6 +191,12 @@ async fn spawn_telemetry_service(
 
     try_spawn_log_sender(telemetry_sender.clone(), remote_log_rx);
     try_spawn_metrics_sender(telemetry_sender.clone());
+    try_spawn_connection_metadata_sender(
+        &node_config,
+        telemetry_sender.clone(),
+        chain_id,
+        peers_and_metadata,
+    );
     try_spawn_custom_event_sender(node_config, telemetry_sender.clone(), chain_id, build_info);
     try_spawn_log_env_poll_task(telemetry_sender);
 
@@ -240,6 +252,33 @@ fn try_spawn_custom_event_sender(
     }
 }
 
+fn try_spawn_connection_metadata_sender(
+    node_config: &NodeConfig,
+    telemetry_sender: TelemetrySender,
+    chain_id: ChainId,
+    peers_and_metadata: Arc<PeersAndMetadata>,
+) {
+    if enable_push_custom_events() && node_config.logger.enable_telemetry_connection_metadata {
+        // Periodically send the aggregated connection metadata (only to the telemetry service)
+        let peer_id = fetch_peer_id(node_config);
+        let collector = ConnectionMetadataCollector::new(
+            peers_and_metadata,
+            node_config.logger.telemetry_peer_id_privacy,
+        );
+        tokio::spawn(async move {
+            run_function_periodically(NODE_CONNECTION_METADATA_FREQ_SECS, || {
+                send_connection_metadata(
+                    peer_id.clone(),
+                    chain_id.to_string(),
+                    &collector,
+                    Some(telemetry_sender.clone()),
+                )
+            })
+            .await;
+        });
+    }
+}
+
 fn try_spawn_metrics_sender(telemetry_sender: TelemetrySender) {
     if enable_prometheus_push_metrics() {
         tokio::spawn(async move {
@@ -400,6 +439,17 @@ async fn send_node_network_metrics(
     send_telemetry_event_with_ip(peer_id, chain_id, telemetry_sender, telemetry_event).await;
 }
 
+/// Collects and sends the aggregated connection metadata via telemetry
+async fn send_connection_metadata(
+    peer_id: String,
+    chain_id: String,
+    collector: &ConnectionMetadataCollector,
+    telemetry_sender: Option<TelemetrySender>,
+) {
+    let telemetry_event = collector.create_connection_metadata_telemetry_event().await;
+    send_telemetry_event_with_ip(peer_id, chain_id, telemetry_sender, telemetry_event).await;
+}
+
 /// Collects and sends the system information via telemetry
 async fn send_system_information(
     peer_id: String,
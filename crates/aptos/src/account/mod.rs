@@ -13,6 +13,7 @@ pub mod key_rotation;
 pub mod list;
 pub mod multisig_account;
 pub mod transfer;
+pub mod watch;
 
 /// Tool for interacting with accounts
 ///
@@ -29,6 +30,7 @@ pub enum AccountTool {
     LookupAddress(key_rotation::LookupAddress),
     RotateKey(key_rotation::RotateKey),
     Transfer(transfer::TransferCoins),
+    Watch(watch::WatchAccount),
 }
 
 impl AccountTool {
@@ -43,6 +45,7 @@ impl AccountTool {
             AccountTool::LookupAddress(tool) => tool.execute_serialized().await,
             AccountTool::RotateKey(tool) => tool.execute_serialized().await,
             AccountTool::Transfer(tool) => tool.execute_serialized().await,
+            AccountTool::Watch(tool) => tool.execute_serialized().await,
         }
     }
 }
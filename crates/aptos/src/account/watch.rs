@@ -0,0 +1,610 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode, ProfileOptions, RestOptions,
+};
+use aptos_api_types::{Event, Transaction, VersionedEvent};
+use aptos_rest_client::Client;
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::{File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+/// Maximum number of transactions to fetch for the account in a single poll
+const MAX_TRANSACTIONS_PER_POLL: u64 = 100;
+
+/// Maximum number of events to fetch for each event handle in a single poll
+const MAX_EVENTS_PER_HANDLE_PER_POLL: u64 = 100;
+
+/// Watch an account for new events and resource changes
+///
+/// This polls the node for new events emitted to the account's event handles (including events
+/// emitted by transactions of other accounts, e.g. deposits), for new transactions sent by the
+/// account (and the events they emitted), and for changes to the account's resources.  Each
+/// change is streamed as a line of JSON to the terminal or to a JSONL file.  Only changes after
+/// the watch starts are reported.  Watching stops after `--max-polls` polls, or when interrupted
+/// with ctrl-c.
+#[derive(Debug, Parser)]
+pub struct WatchAccount {
+    /// Address of the account you want to watch
+    #[clap(long, alias = "address", value_parser = crate::common::types::load_account_arg)]
+    pub(crate) account: Option<AccountAddress>,
+
+    /// Watch the events emitted to the account's event handles, and by transactions sent from
+    /// the account
+    ///
+    /// If neither `--events` nor `--resources` is given, both are watched.
+    #[clap(long)]
+    pub(crate) events: bool,
+
+    /// Watch the resources of the account for changes
+    ///
+    /// If neither `--events` nor `--resources` is given, both are watched.
+    #[clap(long)]
+    pub(crate) resources: bool,
+
+    /// Interval between polls of the node in milliseconds
+    #[clap(long, default_value_t = 1000)]
+    pub(crate) poll_interval_ms: u64,
+
+    /// Maximum number of polls before the watch stops.  If not given, watches until interrupted
+    #[clap(long)]
+    pub(crate) max_polls: Option<u64>,
+
+    /// File to append the changes to as JSONL.  If not given, changes are printed to stdout
+    #[clap(long, value_parser)]
+    pub(crate) output_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+/// A single change observed on the watched account
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum AccountChange {
+    Event {
+        version: u64,
+        /// The sequence number of the transaction that emitted the event, if it was sent by the
+        /// watched account
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sequence_number: Option<u64>,
+        event: Event,
+    },
+    ResourceAdded {
+        version: u64,
+        resource_type: String,
+        data: serde_json::Value,
+    },
+    ResourceModified {
+        version: u64,
+        resource_type: String,
+        data: serde_json::Value,
+    },
+    ResourceRemoved {
+        version: u64,
+        resource_type: String,
+    },
+}
+
+impl AccountChange {
+    /// Returns the ledger version at which the change was observed
+    fn version(&self) -> u64 {
+        match self {
+            AccountChange::Event { version, .. }
+            | AccountChange::ResourceAdded { version, .. }
+            | AccountChange::ResourceModified { version, .. }
+            | AccountChange::ResourceRemoved { version, .. } => *version,
+        }
+    }
+}
+
+/// Summary of a finished watch
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchSummary {
+    num_polls: u64,
+    num_events: u64,
+    num_resource_changes: u64,
+}
+
+#[async_trait]
+impl CliCommand<WatchSummary> for WatchAccount {
+    fn command_name(&self) -> &'static str {
+        "WatchAccount"
+    }
+
+    async fn execute(self) -> CliTypedResult<WatchSummary> {
+        let account = if let Some(account) = self.account {
+            account
+        } else if let Some(Some(account)) = CliConfig::load_profile(
+            self.profile_options.profile_name(),
+            ConfigSearchMode::CurrentDirAndParents,
+        )?
+        .map(|p| p.account)
+        {
+            account
+        } else {
+            return Err(CliError::CommandArgumentError(
+                "Please provide an account using --account or run aptos init".to_string(),
+            ));
+        };
+        if self.poll_interval_ms == 0 {
+            return Err(CliError::CommandArgumentError(
+                "--poll-interval-ms must be greater than 0".to_string(),
+            ));
+        }
+        let watch_all = !self.events && !self.resources;
+        let watch_events = self.events || watch_all;
+        let watch_resources = self.resources || watch_all;
+
+        let mut output = match &self.output_file {
+            Some(path) => Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|err| CliError::IO(path.display().to_string(), err))?,
+            ),
+            None => None,
+        };
+
+        // Snapshot the current state of the account, so only new changes are reported
+        let client = self.rest_options.client(&self.profile_options)?;
+        let mut next_sequence_number = if watch_events {
+            client
+                .get_account(account)
+                .await?
+                .into_inner()
+                .sequence_number
+        } else {
+            0
+        };
+        let mut resources = get_resources(&client, account).await?.0;
+        let mut next_event_sequence_numbers: BTreeMap<EventHandleId, u64> =
+            get_event_handles(account, &resources)
+                .into_iter()
+                .map(|(handle_id, handle)| (handle_id, handle.counter))
+                .collect();
+
+        let mut summary = WatchSummary {
+            num_polls: 0,
+            num_events: 0,
+            num_resource_changes: 0,
+        };
+        let mut interval = tokio::time::interval(Duration::from_millis(self.poll_interval_ms));
+        loop {
+            if let Some(max_polls) = self.max_polls {
+                if summary.num_polls >= max_polls {
+                    break;
+                }
+            }
+            tokio::select! {
+                _ = interval.tick() => {},
+                _ = tokio::signal::ctrl_c() => {
+                    eprintln!("Received ctrl-c, stopping the watch");
+                    break;
+                },
+            }
+            summary.num_polls += 1;
+
+            let mut changes = vec![];
+            let (new_resources, version) = get_resources(&client, account).await?;
+            if watch_events {
+                let event_handles = get_event_handles(account, &new_resources);
+                let mut events = get_new_handle_events(
+                    &client,
+                    account,
+                    &event_handles,
+                    &mut next_event_sequence_numbers,
+                )
+                .await?;
+
+                // Events emitted to the account's own event handles were fetched above
+                let handle_creation_numbers = event_handles
+                    .values()
+                    .map(|handle| handle.creation_number)
+                    .collect();
+                let (transaction_events, sequence_number) = get_new_transaction_events(
+                    &client,
+                    account,
+                    next_sequence_number,
+                    &handle_creation_numbers,
+                )
+                .await?;
+                next_sequence_number = sequence_number;
+                events.extend(transaction_events);
+                events.sort_by_key(AccountChange::version);
+
+                summary.num_events += events.len() as u64;
+                changes.extend(events);
+            }
+            if watch_resources {
+                let resource_changes = diff_resources(&resources, &new_resources, version);
+                summary.num_resource_changes += resource_changes.len() as u64;
+                changes.extend(resource_changes);
+            }
+            resources = new_resources;
+
+            for change in changes {
+                write_change(output.as_mut(), &change)?;
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Fetches the resources of the account (by type), and the ledger version they were read at
+async fn get_resources(
+    client: &Client,
+    account: AccountAddress,
+) -> CliTypedResult<(BTreeMap<String, serde_json::Value>, u64)> {
+    let (resources, state) = client.get_account_resources(account).await?.into_parts();
+    let resources = resources
+        .into_iter()
+        .map(|resource| (resource.resource_type.to_string(), resource.data))
+        .collect();
+    Ok((resources, state.version))
+}
+
+/// Identifies an event handle of the account, as the type of the resource holding the handle and
+/// the name of the field it is stored in
+type EventHandleId = (String, String);
+
+/// An event handle of the account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EventHandle {
+    creation_number: u64,
+    counter: u64,
+}
+
+/// Finds the event handles of the account that are stored (as fields) in its resources
+fn get_event_handles(
+    account: AccountAddress,
+    resources: &BTreeMap<String, serde_json::Value>,
+) -> BTreeMap<EventHandleId, EventHandle> {
+    let mut event_handles = BTreeMap::new();
+    for (resource_type, data) in resources {
+        let Some(fields) = data.as_object() else {
+            continue;
+        };
+        for (field_name, value) in fields {
+            if let Some(event_handle) = parse_event_handle(account, value) {
+                event_handles.insert((resource_type.clone(), field_name.clone()), event_handle);
+            }
+        }
+    }
+    event_handles
+}
+
+/// Parses the JSON value as an event handle (i.e., `{"counter", "guid": {"id": {"addr",
+/// "creation_num"}}}`), if it's an event handle created by the account
+fn parse_event_handle(account: AccountAddress, value: &serde_json::Value) -> Option<EventHandle> {
+    let counter = value.get("counter")?.as_str()?.parse().ok()?;
+    let id = value.get("guid")?.get("id")?;
+    let creation_number = id.get("creation_num")?.as_str()?.parse().ok()?;
+    let creator = AccountAddress::from_str(id.get("addr")?.as_str()?).ok()?;
+    (creator == account).then_some(EventHandle {
+        creation_number,
+        counter,
+    })
+}
+
+/// Fetches the new events of each of the account's event handles, starting at the next event
+/// sequence number of each handle (or at 0 for handles created during the watch).  The next event
+/// sequence numbers are updated with the fetched events.
+async fn get_new_handle_events(
+    client: &Client,
+    account: AccountAddress,
+    event_handles: &BTreeMap<EventHandleId, EventHandle>,
+    next_event_sequence_numbers: &mut BTreeMap<EventHandleId, u64>,
+) -> CliTypedResult<Vec<AccountChange>> {
+    let mut changes = vec![];
+    for (handle_id, event_handle) in event_handles {
+        let next_event_sequence_number = next_event_sequence_numbers
+            .entry(handle_id.clone())
+            .or_insert(0);
+        if event_handle.counter <= *next_event_sequence_number {
+            continue;
+        }
+
+        let (resource_type, field_name) = handle_id;
+        let limit = (event_handle.counter - *next_event_sequence_number)
+            .min(MAX_EVENTS_PER_HANDLE_PER_POLL);
+        let events = client
+            .get_account_events(
+                account,
+                resource_type,
+                field_name,
+                Some(*next_event_sequence_number),
+                Some(limit as u16),
+            )
+            .await?
+            .into_inner();
+        for event in events {
+            *next_event_sequence_number =
+                (*next_event_sequence_number).max(event.sequence_number.0 + 1);
+            changes.push(versioned_event_change(event));
+        }
+    }
+    Ok(changes)
+}
+
+/// Converts an event fetched from an event handle into an account change
+fn versioned_event_change(event: VersionedEvent) -> AccountChange {
+    AccountChange::Event {
+        version: event.version.0,
+        sequence_number: None,
+        event: Event {
+            guid: event.guid,
+            sequence_number: event.sequence_number,
+            typ: event.typ,
+            data: event.data,
+        },
+    }
+}
+
+/// Fetches the events of all transactions sent by the account, starting at the given sequence
+/// number.  Events emitted to the account's own event handles (identified by their creation
+/// numbers) are skipped, as they are fetched from the handles directly.  Returns the events, and
+/// the sequence number to continue from in the next poll.
+async fn get_new_transaction_events(
+    client: &Client,
+    account: AccountAddress,
+    start_sequence_number: u64,
+    handle_creation_numbers: &BTreeSet<u64>,
+) -> CliTypedResult<(Vec<AccountChange>, u64)> {
+    let mut next_sequence_number = start_sequence_number;
+    let transactions = client
+        .get_account_transactions(
+            account,
+            Some(start_sequence_number),
+            Some(MAX_TRANSACTIONS_PER_POLL),
+        )
+        .await?
+        .into_inner();
+
+    let mut changes = vec![];
+    for transaction in transactions {
+        if let Transaction::UserTransaction(txn) = transaction {
+            let sequence_number = txn.request.sequence_number.0;
+            changes.extend(transaction_event_changes(
+                account,
+                txn.info.version.0,
+                sequence_number,
+                txn.events,
+                handle_creation_numbers,
+            ));
+            next_sequence_number = next_sequence_number.max(sequence_number + 1);
+        }
+    }
+    Ok((changes, next_sequence_number))
+}
+
+/// Converts the events of a transaction sent by the account into account changes, skipping the
+/// events emitted to the account's own event handles
+fn transaction_event_changes(
+    account: AccountAddress,
+    version: u64,
+    sequence_number: u64,
+    events: Vec<Event>,
+    handle_creation_numbers: &BTreeSet<u64>,
+) -> Vec<AccountChange> {
+    events
+        .into_iter()
+        .filter(|event| {
+            *event.guid.account_address.inner() != account
+                || !handle_creation_numbers.contains(&event.guid.creation_number.0)
+        })
+        .map(|event| AccountChange::Event {
+            version,
+            sequence_number: Some(sequence_number),
+            event,
+        })
+        .collect()
+}
+
+/// Computes the resource changes between two snapshots of the account's resources
+fn diff_resources(
+    previous: &BTreeMap<String, serde_json::Value>,
+    current: &BTreeMap<String, serde_json::Value>,
+    version: u64,
+) -> Vec<AccountChange> {
+    let mut changes = vec![];
+    for (resource_type, data) in current {
+        match previous.get(resource_type) {
+            None => changes.push(AccountChange::ResourceAdded {
+                version,
+                resource_type: resource_type.clone(),
+                data: data.clone(),
+            }),
+            Some(previous_data) if previous_data != data => {
+                changes.push(AccountChange::ResourceModified {
+                    version,
+                    resource_type: resource_type.clone(),
+                    data: data.clone(),
+                })
+            },
+            Some(_) => {},
+        }
+    }
+    for resource_type in previous.keys() {
+        if !current.contains_key(resource_type) {
+            changes.push(AccountChange::ResourceRemoved {
+                version,
+                resource_type: resource_type.clone(),
+            });
+        }
+    }
+    changes
+}
+
+/// Writes a change as a single line of JSON to the output file, or to stdout
+fn write_change(output: Option<&mut File>, change: &AccountChange) -> CliTypedResult<()> {
+    let line = serde_json::to_string(change)
+        .map_err(|err| CliError::UnexpectedError(format!("Failed to serialize change: {}", err)))?;
+    match output {
+        Some(file) => {
+            writeln!(file, "{}", line).map_err(|err| CliError::IO("output file".to_string(), err))
+        },
+        None => {
+            println!("{}", line);
+            Ok(())
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_api_types::{EventGuid, MoveType, U64};
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_resources() {
+        let previous = BTreeMap::from([
+            ("0x1::a::Unchanged".to_string(), json!({"value": "1"})),
+            ("0x1::a::Modified".to_string(), json!({"value": "1"})),
+            ("0x1::a::Removed".to_string(), json!({"value": "1"})),
+        ]);
+        let current = BTreeMap::from([
+            ("0x1::a::Unchanged".to_string(), json!({"value": "1"})),
+            ("0x1::a::Modified".to_string(), json!({"value": "2"})),
+            ("0x1::a::Added".to_string(), json!({"value": "3"})),
+        ]);
+
+        // Verify that added, modified and removed resources are reported
+        assert_eq!(diff_resources(&previous, &current, 10), vec![
+            AccountChange::ResourceAdded {
+                version: 10,
+                resource_type: "0x1::a::Added".to_string(),
+                data: json!({"value": "3"}),
+            },
+            AccountChange::ResourceModified {
+                version: 10,
+                resource_type: "0x1::a::Modified".to_string(),
+                data: json!({"value": "2"}),
+            },
+            AccountChange::ResourceRemoved {
+                version: 10,
+                resource_type: "0x1::a::Removed".to_string(),
+            },
+        ]);
+
+        // Verify that no changes are reported for identical snapshots
+        assert!(diff_resources(&current, &current, 11).is_empty());
+    }
+
+    #[test]
+    fn test_get_event_handles() {
+        let account = AccountAddress::from_str("0xa").unwrap();
+        let other_account = AccountAddress::from_str("0xb").unwrap();
+        let coin_store = "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>".to_string();
+        let resources = BTreeMap::from([
+            (
+                coin_store.clone(),
+                json!({
+                    "coin": {"value": "100"},
+                    "deposit_events": create_event_handle_json(account, 2, 5),
+                    "withdraw_events": create_event_handle_json(account, 3, 0),
+                }),
+            ),
+            (
+                "0x1::a::Other".to_string(),
+                json!({"events": create_event_handle_json(other_account, 4, 1)}),
+            ),
+        ]);
+
+        // Verify that only the event handles of the account are found
+        assert_eq!(
+            get_event_handles(account, &resources),
+            BTreeMap::from([
+                ((coin_store.clone(), "deposit_events".to_string()), EventHandle {
+                    creation_number: 2,
+                    counter: 5,
+                }),
+                ((coin_store, "withdraw_events".to_string()), EventHandle {
+                    creation_number: 3,
+                    counter: 0,
+                }),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_transaction_event_changes() {
+        let account = AccountAddress::from_str("0xa").unwrap();
+        let other_account = AccountAddress::from_str("0xb").unwrap();
+        let own_handle_event = create_event(account, 2);
+        let other_handle_event = create_event(other_account, 2);
+        let unknown_handle_event = create_event(account, 7);
+        let module_event = create_event(AccountAddress::ZERO, 0);
+
+        // Verify that only events emitted to the account's own event handles are skipped
+        let changes = transaction_event_changes(
+            account,
+            10,
+            3,
+            vec![
+                own_handle_event,
+                other_handle_event.clone(),
+                unknown_handle_event.clone(),
+                module_event.clone(),
+            ],
+            &BTreeSet::from([2, 3]),
+        );
+        let expected_events = vec![other_handle_event, unknown_handle_event, module_event];
+        assert_eq!(
+            changes,
+            expected_events
+                .into_iter()
+                .map(|event| AccountChange::Event {
+                    version: 10,
+                    sequence_number: Some(3),
+                    event,
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// Creates the JSON representation of an event handle
+    fn create_event_handle_json(
+        creator: AccountAddress,
+        creation_number: u64,
+        counter: u64,
+    ) -> serde_json::Value {
+        json!({
+            "counter": counter.to_string(),
+            "guid": {
+                "id": {
+                    "addr": creator.to_hex_literal(),
+                    "creation_num": creation_number.to_string(),
+                },
+            },
+        })
+    }
+
+    /// Creates an event emitted to the given event handle
+    fn create_event(creator: AccountAddress, creation_number: u64) -> Event {
+        Event {
+            guid: EventGuid {
+                creation_number: U64(creation_number),
+                account_address: creator.into(),
+            },
+            sequence_number: U64(0),
+            typ: MoveType::Bool,
+            data: json!(true),
+        }
+    }
+}
@@ -23,7 +23,7 @@ use aptos_api_types::ViewFunction;
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
     encoding_type::{EncodingError, EncodingType},
-    x25519, PrivateKey, ValidCryptoMaterialStringExt,
+    x25519, PrivateKey, SigningKey, ValidCryptoMaterialStringExt,
 };
 use aptos_global_constants::adjust_gas_headroom;
 use aptos_keygen::KeyGen;
@@ -41,7 +41,8 @@ use aptos_sdk::{
 use aptos_types::{
     chain_id::ChainId,
     transaction::{
-        authenticator::AuthenticationKey, EntryFunction, MultisigTransactionPayload, Script,
+        authenticator::{AccountAuthenticator, AuthenticationKey},
+        EntryFunction, MultisigTransactionPayload, RawTransaction, RawTransactionWithData, Script,
         SignedTransaction, TransactionArgument, TransactionPayload, TransactionStatus,
     },
 };
@@ -1577,6 +1578,15 @@ impl Default for GasOptions {
     }
 }
 
+/// A fee payer transaction that was signed by the sender, but must still be
+/// countersigned by the fee payer before it can be submitted
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PartiallySignedTransaction {
+    pub raw_txn: RawTransaction,
+    pub sender_authenticator: AccountAuthenticator,
+    pub fee_payer_address: AccountAddress,
+}
+
 #[derive(Debug)]
 pub enum AccountType {
     Local,
@@ -1706,21 +1716,240 @@ impl TransactionOptions {
         let client = self.rest_client()?;
         let (sender_public_key, sender_address) = self.get_public_key_and_address()?;
 
-        // Ask to confirm price if the gas unit price is estimated above the lowest value when
-        // it is automatically estimated
-        let ask_to_confirm_price;
-        let gas_unit_price = if let Some(gas_unit_price) = self.gas_options.gas_unit_price {
-            ask_to_confirm_price = false;
-            gas_unit_price
-        } else {
-            let gas_unit_price = client.estimate_gas_price().await?.into_inner().gas_estimate;
+        let (gas_unit_price, ask_to_confirm_price) = self.gas_unit_price(&client).await?;
+        let (sequence_number, chain_id, expiration_time_secs) = self
+            .sequence_number_and_chain_id(&client, sender_address)
+            .await?;
+        // TODO: Check auth key against current private key and provide a better message
+
+        let max_gas = self
+            .max_gas(&client, gas_unit_price, ask_to_confirm_price, || {
+                let unsigned_transaction = TransactionFactory::new(chain_id)
+                    .with_gas_unit_price(gas_unit_price)
+                    .payload(payload.clone())
+                    .sender(sender_address)
+                    .sequence_number(sequence_number)
+                    .expiration_timestamp_secs(expiration_time_secs)
+                    .build();
+                SignedTransaction::new(
+                    unsigned_transaction,
+                    sender_public_key.clone(),
+                    Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+                )
+            })
+            .await?;
 
-            ask_to_confirm_price = true;
-            gas_unit_price
+        // Build a transaction
+        let transaction_factory = TransactionFactory::new(chain_id)
+            .with_gas_unit_price(gas_unit_price)
+            .with_max_gas_amount(max_gas)
+            .with_transaction_expiration_time(self.gas_options.expiration_secs);
+
+        // Sign it with the appropriate signer
+        let transaction = match self.get_transaction_account_type() {
+            Ok(AccountType::Local) => {
+                let (private_key, _) = self.get_key_and_address()?;
+                let sender_account =
+                    &mut LocalAccount::new(sender_address, private_key, sequence_number);
+                sender_account.sign_with_transaction_builder(transaction_factory.payload(payload))
+            },
+            Ok(AccountType::HardwareWallet) => {
+                let sender_account = &mut HardwareWalletAccount::new(
+                    sender_address,
+                    sender_public_key,
+                    self.profile_options
+                        .derivation_path()
+                        .expect("derivative path is missing from profile")
+                        .unwrap(),
+                    HardwareWalletType::Ledger,
+                    sequence_number,
+                );
+                sender_account
+                    .sign_with_transaction_builder(transaction_factory.payload(payload))?
+            },
+            Err(err) => return Err(err),
         };
 
+        self.submit_and_wait(&client, &transaction).await
+    }
+
+    /// Submit a fee payer transaction, signed by both the sender and the fee payer (i.e.,
+    /// the account of the given profile, which pays the gas for the transaction)
+    pub async fn submit_fee_payer_transaction(
+        &self,
+        payload: TransactionPayload,
+        fee_payer_profile: &str,
+    ) -> CliTypedResult<Transaction> {
+        let client = self.rest_client()?;
+        let (sender_private_key, sender_address) = self.get_key_and_address()?;
+        let (fee_payer_private_key, fee_payer_address) = PrivateKeyInputOptions::default()
+            .extract_private_key_and_address(
+                self.encoding_options.encoding,
+                &ProfileOptions {
+                    profile: Some(fee_payer_profile.to_string()),
+                },
+                None,
+            )?;
+
+        let raw_txn = self
+            .build_fee_payer_transaction(&client, payload, &sender_private_key, fee_payer_address)
+            .await?;
+        let transaction = raw_txn
+            .sign_fee_payer(
+                &sender_private_key,
+                vec![],
+                vec![],
+                fee_payer_address,
+                &fee_payer_private_key,
+            )
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+            .into_inner();
+
+        self.submit_and_wait(&client, &transaction).await
+    }
+
+    /// Builds a fee payer transaction and signs it as the sender. The resulting
+    /// transaction must be countersigned by the fee payer before it can be submitted.
+    pub async fn sign_fee_payer_transaction_as_sender(
+        &self,
+        payload: TransactionPayload,
+        fee_payer_address: AccountAddress,
+    ) -> CliTypedResult<PartiallySignedTransaction> {
+        let client = self.rest_client()?;
+        let (sender_private_key, _) = self.get_key_and_address()?;
+
+        let raw_txn = self
+            .build_fee_payer_transaction(&client, payload, &sender_private_key, fee_payer_address)
+            .await?;
+        let message =
+            RawTransactionWithData::new_fee_payer(raw_txn.clone(), vec![], fee_payer_address);
+        let sender_signature = sender_private_key
+            .sign(&message)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+
+        Ok(PartiallySignedTransaction {
+            raw_txn,
+            sender_authenticator: AccountAuthenticator::ed25519(
+                sender_private_key.public_key(),
+                sender_signature,
+            ),
+            fee_payer_address,
+        })
+    }
+
+    /// Countersigns the given partially signed transaction as the fee payer, and submits it
+    pub async fn submit_partially_signed_transaction(
+        &self,
+        partially_signed_txn: PartiallySignedTransaction,
+    ) -> CliTypedResult<Transaction> {
+        let client = self.rest_client()?;
+        let (fee_payer_private_key, fee_payer_address) = self.get_key_and_address()?;
+        let PartiallySignedTransaction {
+            raw_txn,
+            sender_authenticator,
+            fee_payer_address: expected_fee_payer_address,
+        } = partially_signed_txn;
+        if fee_payer_address != expected_fee_payer_address {
+            return Err(CliError::CommandArgumentError(format!(
+                "The transaction must be signed by fee payer {}, but the signer is {}",
+                expected_fee_payer_address, fee_payer_address
+            )));
+        }
+
+        // Ask the fee payer to confirm the cost of the transaction
+        let message = format!(
+            "Do you want to pay for a transaction from {} for a maximum of {} Octas at a gas unit price of {} Octas?",
+            raw_txn.sender(),
+            raw_txn.max_gas_amount() * raw_txn.gas_unit_price(),
+            raw_txn.gas_unit_price()
+        );
+        prompt_yes_with_override(&message, self.prompt_options)?;
+
+        let message =
+            RawTransactionWithData::new_fee_payer(raw_txn.clone(), vec![], fee_payer_address);
+        let fee_payer_signature = fee_payer_private_key
+            .sign(&message)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        let transaction = SignedTransaction::new_fee_payer(
+            raw_txn,
+            sender_authenticator,
+            vec![],
+            vec![],
+            fee_payer_address,
+            AccountAuthenticator::ed25519(fee_payer_private_key.public_key(), fee_payer_signature),
+        );
+
+        self.submit_and_wait(&client, &transaction).await
+    }
+
+    /// Builds a (raw) fee payer transaction, by simulating it (with the fee payer) to estimate
+    /// the max gas, if required.
+    async fn build_fee_payer_transaction(
+        &self,
+        client: &Client,
+        payload: TransactionPayload,
+        sender_private_key: &Ed25519PrivateKey,
+        fee_payer_address: AccountAddress,
+    ) -> CliTypedResult<RawTransaction> {
+        let sender_address = self.sender_address()?;
+        let (gas_unit_price, ask_to_confirm_price) = self.gas_unit_price(client).await?;
+        let (sequence_number, chain_id, expiration_time_secs) = self
+            .sequence_number_and_chain_id(client, sender_address)
+            .await?;
+
+        let transaction_factory =
+            TransactionFactory::new(chain_id).with_gas_unit_price(gas_unit_price);
+        let max_gas = self
+            .max_gas(client, gas_unit_price, ask_to_confirm_price, || {
+                let unsigned_transaction = transaction_factory
+                    .payload(payload.clone())
+                    .sender(sender_address)
+                    .sequence_number(sequence_number)
+                    .expiration_timestamp_secs(expiration_time_secs)
+                    .build();
+                SignedTransaction::new_fee_payer(
+                    unsigned_transaction,
+                    AccountAuthenticator::ed25519(
+                        sender_private_key.public_key(),
+                        Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+                    ),
+                    vec![],
+                    vec![],
+                    fee_payer_address,
+                    AccountAuthenticator::NoAccountAuthenticator,
+                )
+            })
+            .await?;
+
+        Ok(transaction_factory
+            .with_max_gas_amount(max_gas)
+            .payload(payload)
+            .sender(sender_address)
+            .sequence_number(sequence_number)
+            .expiration_timestamp_secs(expiration_time_secs)
+            .build())
+    }
+
+    /// Returns the gas unit price for the transaction, and whether it was estimated
+    /// (in which case the user should be asked to confirm the price).
+    async fn gas_unit_price(&self, client: &Client) -> CliTypedResult<(u64, bool)> {
+        if let Some(gas_unit_price) = self.gas_options.gas_unit_price {
+            Ok((gas_unit_price, false))
+        } else {
+            let gas_unit_price = client.estimate_gas_price().await?.into_inner().gas_estimate;
+            Ok((gas_unit_price, true))
+        }
+    }
+
+    /// Returns the sequence number of the sender, the chain id and the expiration time
+    /// (in seconds) for a new transaction.
+    async fn sequence_number_and_chain_id(
+        &self,
+        client: &Client,
+        sender_address: AccountAddress,
+    ) -> CliTypedResult<(u64, ChainId, u64)> {
         // Get sequence number for account
-        let (account, state) = get_account_with_state(&client, sender_address).await?;
+        let (account, state) = get_account_with_state(client, sender_address).await?;
         let sequence_number = account.sequence_number;
 
         // Retrieve local time, and ensure it's within an expected skew of the blockchain
@@ -1737,32 +1966,35 @@ impl TransactionOptions {
         }
         let expiration_time_secs = now + self.gas_options.expiration_secs;
 
-        let chain_id = ChainId::new(state.chain_id);
-        // TODO: Check auth key against current private key and provide a better message
+        Ok((
+            sequence_number,
+            ChainId::new(state.chain_id),
+            expiration_time_secs,
+        ))
+    }
 
-        let max_gas = if let Some(max_gas) = self.gas_options.max_gas {
+    /// Returns the max gas for the transaction, by simulating the transaction (built with
+    /// `build_simulated_transaction`) if it isn't set explicitly. The user is asked to
+    /// confirm the cost of the transaction.
+    async fn max_gas<F>(
+        &self,
+        client: &Client,
+        gas_unit_price: u64,
+        ask_to_confirm_price: bool,
+        build_simulated_transaction: F,
+    ) -> CliTypedResult<u64>
+    where
+        F: FnOnce() -> SignedTransaction,
+    {
+        if let Some(max_gas) = self.gas_options.max_gas {
             // If the gas unit price was estimated ask, but otherwise you've chosen hwo much you want to spend
             if ask_to_confirm_price {
                 let message = format!("Do you want to submit transaction for a maximum of {} Octas at a gas unit price of {} Octas?",  max_gas * gas_unit_price, gas_unit_price);
                 prompt_yes_with_override(&message, self.prompt_options)?;
             }
-            max_gas
+            Ok(max_gas)
         } else {
-            let transaction_factory =
-                TransactionFactory::new(chain_id).with_gas_unit_price(gas_unit_price);
-
-            let unsigned_transaction = transaction_factory
-                .payload(payload.clone())
-                .sender(sender_address)
-                .sequence_number(sequence_number)
-                .expiration_timestamp_secs(expiration_time_secs)
-                .build();
-
-            let signed_transaction = SignedTransaction::new(
-                unsigned_transaction,
-                sender_public_key.clone(),
-                Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
-            );
+            let signed_transaction = build_simulated_transaction();
 
             let txns = client
                 .simulate_with_gas_estimation(&signed_transaction, true, false)
@@ -1791,43 +2023,19 @@ impl TransactionOptions {
                     upper_cost_bound,
                     gas_unit_price);
             prompt_yes_with_override(&message, self.prompt_options)?;
-            adjusted_max_gas
-        };
-
-        // Build a transaction
-        let transaction_factory = TransactionFactory::new(chain_id)
-            .with_gas_unit_price(gas_unit_price)
-            .with_max_gas_amount(max_gas)
-            .with_transaction_expiration_time(self.gas_options.expiration_secs);
-
-        // Sign it with the appropriate signer
-        let transaction = match self.get_transaction_account_type() {
-            Ok(AccountType::Local) => {
-                let (private_key, _) = self.get_key_and_address()?;
-                let sender_account =
-                    &mut LocalAccount::new(sender_address, private_key, sequence_number);
-                sender_account.sign_with_transaction_builder(transaction_factory.payload(payload))
-            },
-            Ok(AccountType::HardwareWallet) => {
-                let sender_account = &mut HardwareWalletAccount::new(
-                    sender_address,
-                    sender_public_key,
-                    self.profile_options
-                        .derivation_path()
-                        .expect("derivative path is missing from profile")
-                        .unwrap(),
-                    HardwareWalletType::Ledger,
-                    sequence_number,
-                );
-                sender_account
-                    .sign_with_transaction_builder(transaction_factory.payload(payload))?
-            },
-            Err(err) => return Err(err),
-        };
+            Ok(adjusted_max_gas)
+        }
+    }
 
+    /// Submits the given (signed) transaction, and waits for it to be committed
+    async fn submit_and_wait(
+        &self,
+        client: &Client,
+        transaction: &SignedTransaction,
+    ) -> CliTypedResult<Transaction> {
         // Submit the transaction, printing out a useful transaction link
         client
-            .submit_bcs(&transaction)
+            .submit_bcs(transaction)
             .await
             .map_err(|err| CliError::ApiError(err.to_string()))?;
         let transaction_hash = transaction.clone().committed_hash();
@@ -1841,7 +2049,7 @@ impl TransactionOptions {
             explorer_transaction_link(transaction_hash, network)
         );
         let response = client
-            .wait_for_signed_transaction(&transaction)
+            .wait_for_signed_transaction(transaction)
             .await
             .map_err(|err| CliError::ApiError(err.to_string()))?;
 
@@ -9,12 +9,12 @@ use crate::{
             load_account_arg, ArgWithTypeJSON, CliConfig, CliError, CliTypedResult,
             ConfigSearchMode, EntryFunctionArguments, EntryFunctionArgumentsJSON,
             MoveManifestAccountWrapper, MovePackageDir, OptimizationLevel, OverrideSizeCheckOption,
-            ProfileOptions, PromptOptions, RestOptions, SaveFile, ScriptFunctionArguments,
-            TransactionOptions, TransactionSummary,
+            PartiallySignedTransaction, ProfileOptions, PromptOptions, RestOptions, SaveFile,
+            ScriptFunctionArguments, TransactionOptions, TransactionSummary,
         },
         utils::{
             check_if_file_exists, create_dir_if_not_exist, dir_default_to_current,
-            profile_or_submit, prompt_yes_with_override, write_to_file,
+            profile_or_submit, prompt_yes_with_override, read_from_file, write_to_file,
         },
     },
     governance::CompileScriptFunction,
@@ -42,6 +42,8 @@ use aptos_types::{
     account_address::{create_resource_address, AccountAddress},
     object_address::create_object_code_deployment_address,
     on_chain_config::aptos_test_feature_flags_genesis,
+    randomness::{PerBlockRandomness, RandomnessSeedRecord},
+    state_store::{state_key::StateKey, state_value::StateValue},
     transaction::{Transaction, TransactionArgument, TransactionPayload, TransactionStatus},
 };
 use aptos_vm::data_cache::AsMoveResolver;
@@ -53,7 +55,10 @@ use move_command_line_common::{address::NumericalAddress, env::MOVE_HOME};
 use move_compiler_v2::Experiment;
 use move_core_types::{identifier::Identifier, language_storage::ModuleId, u256::U256};
 use move_model::metadata::{CompilerVersion, LanguageVersion};
-use move_package::{source_package::layout::SourcePackageLayout, BuildConfig, CompilerConfig};
+use move_package::{
+    source_package::{layout::SourcePackageLayout, manifest_parser::parse_move_manifest_from_file},
+    BuildConfig, CompilerConfig,
+};
 use move_unit_test::UnitTestingConfig;
 pub use package_hooks::*;
 use serde::{Deserialize, Serialize};
@@ -115,6 +120,8 @@ pub enum MoveTool {
     Publish(PublishPackage),
     Run(RunFunction),
     RunScript(RunScript),
+    SignFeePayerTransaction(SignFeePayerTransaction),
+    SubmitFeePayerTransaction(SubmitFeePayerTransaction),
     #[clap(subcommand, hide = true)]
     Show(show::ShowTool),
     Test(TestPackage),
@@ -151,6 +158,8 @@ impl MoveTool {
             MoveTool::Publish(tool) => tool.execute_serialized().await,
             MoveTool::Run(tool) => tool.execute_serialized().await,
             MoveTool::RunScript(tool) => tool.execute_serialized().await,
+            MoveTool::SignFeePayerTransaction(tool) => tool.execute_serialized().await,
+            MoveTool::SubmitFeePayerTransaction(tool) => tool.execute_serialized().await,
             MoveTool::Show(tool) => tool.execute_serialized().await,
             MoveTool::Test(tool) => tool.execute_serialized().await,
             MoveTool::VerifyPackage(tool) => tool.execute_serialized().await,
@@ -1491,13 +1500,19 @@ impl CliCommand<&'static str> for DownloadPackage {
 
 /// Downloads a package and verifies the bytecode
 ///
-/// Downloads the package from onchain and verifies the bytecode matches a local compilation of the Move code
+/// Downloads the package from onchain and verifies the bytecode matches a local compilation of the Move code.
+/// The package is rebuilt with the toolchain (i.e., the compiler, language and bytecode versions)
+/// recorded in the deployed bytecode, and the bytecode is compared module by module.
 #[derive(Parser)]
 pub struct VerifyPackage {
     /// Address of the account containing the package
     #[clap(long, value_parser = crate::common::types::load_account_arg)]
     pub(crate) account: AccountAddress,
 
+    /// Name of the on-chain package to verify. Defaults to the name of the local package.
+    #[clap(long)]
+    pub(crate) package: Option<String>,
+
     /// Artifacts to be generated when building this package.
     #[clap(long, default_value_t = IncludedArtifacts::Sparse)]
     pub(crate) included_artifacts: IncludedArtifacts,
@@ -1517,24 +1532,22 @@ impl CliCommand<&'static str> for VerifyPackage {
     }
 
     async fn execute(self) -> CliTypedResult<&'static str> {
-        // First build the package locally to get the package metadata
-        let build_options = BuildOptions {
-            install_dir: self.move_options.output_dir.clone(),
-            bytecode_version: fix_bytecode_version(
-                self.move_options.bytecode_version,
-                self.move_options.language_version,
-            ),
-            ..self.included_artifacts.build_options(&self.move_options)?
+        // First determine the name of the package to verify
+        let package_path = self.move_options.get_package_path()?;
+        let package_name = match self.package {
+            Some(package_name) => package_name,
+            None => parse_move_manifest_from_file(&package_path)
+                .map_err(|e| CliError::UnableToParse("Move.toml", format!("{:#}", e)))?
+                .package
+                .name
+                .to_string(),
         };
-        let pack = BuiltPackage::build(self.move_options.get_package_path()?, build_options)
-            .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
-        let compiled_metadata = pack.extract_metadata()?;
 
-        // Now pull the compiled package
+        // Now pull the compiled package (and its deployed bytecode)
         let url = self.rest_options.url(&self.profile_options)?;
-        let registry = CachedPackageRegistry::create(url, self.account, false).await?;
+        let registry = CachedPackageRegistry::create(url, self.account, true).await?;
         let package = registry
-            .get_package(pack.name())
+            .get_package(&package_name)
             .await
             .map_err(|s| CliError::CommandArgumentError(s.to_string()))?;
 
@@ -1547,10 +1560,55 @@ impl CliCommand<&'static str> for VerifyPackage {
             ));
         }
 
+        // Build the package locally, pinning the toolchain recorded on-chain
+        let toolchain = registry.get_package_toolchain(&package)?;
+        eprintln!(
+            "Building package `{}` with the on-chain toolchain ({})",
+            package_name, toolchain
+        );
+        let mut build_options = BuildOptions {
+            install_dir: self.move_options.output_dir.clone(),
+            bytecode_version: fix_bytecode_version(
+                self.move_options.bytecode_version,
+                self.move_options.language_version,
+            ),
+            ..self.included_artifacts.build_options(&self.move_options)?
+        };
+        if toolchain.compiler_version.is_some() {
+            build_options.compiler_version = toolchain.compiler_version;
+        }
+        if toolchain.language_version.is_some() {
+            build_options.language_version = toolchain.language_version;
+        }
+        if toolchain.bytecode_version.is_some() {
+            build_options.bytecode_version = toolchain.bytecode_version;
+        }
+        let pack = BuiltPackage::build(package_path, build_options)
+            .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
+        let compiled_metadata = pack.extract_metadata()?;
+
+        // Verify that the bytecode matches (module by module)
+        let local_bytecode: BTreeMap<_, _> = pack
+            .modules()
+            .map(|module| module.self_id().name().to_string())
+            .zip(pack.extract_code())
+            .collect();
+        let mismatches = registry.compare_package_bytecode(&package, &local_bytecode)?;
+        if !mismatches.is_empty() {
+            return Err(CliError::UnexpectedError(format!(
+                "The bytecode of package `{}` doesn't match the local build:\n{}",
+                package_name,
+                mismatches
+                    .iter()
+                    .map(|mismatch| format!("\t- {}", mismatch))
+                    .join("\n")
+            )));
+        }
+
         // Verify that the source digest matches
         package.verify(&compiled_metadata)?;
 
-        Ok("Successfully verified source of package")
+        Ok("Successfully verified source and bytecode of package")
     }
 }
 
@@ -1671,6 +1729,14 @@ pub struct RunFunction {
     pub(crate) entry_function_args: EntryFunctionArguments,
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+
+    /// Profile of the fee payer account, which pays the gas for the transaction
+    ///
+    /// If set, the transaction is signed by both the sender and the fee payer before it
+    /// is submitted. If the fee payer must countersign separately, use
+    /// `aptos move sign-fee-payer-transaction` instead.
+    #[clap(long, conflicts_with_all = &["local", "benchmark", "profile_gas"])]
+    pub(crate) fee_payer_profile: Option<String>,
 }
 
 #[async_trait]
@@ -1680,11 +1746,95 @@ impl CliCommand<TransactionSummary> for RunFunction {
     }
 
     async fn execute(self) -> CliTypedResult<TransactionSummary> {
-        profile_or_submit(
-            TransactionPayload::EntryFunction(self.entry_function_args.try_into()?),
-            &self.txn_options,
-        )
-        .await
+        let payload = TransactionPayload::EntryFunction(self.entry_function_args.try_into()?);
+        if let Some(fee_payer_profile) = &self.fee_payer_profile {
+            self.txn_options
+                .submit_fee_payer_transaction(payload, fee_payer_profile)
+                .await
+                .map(TransactionSummary::from)
+        } else {
+            profile_or_submit(payload, &self.txn_options).await
+        }
+    }
+}
+
+/// Sign a fee payer transaction (i.e., a transaction whose gas is paid by another
+/// account) as the sender, and store it in an output file
+///
+/// The fee payer must then countersign and submit the transaction using
+/// `aptos move submit-fee-payer-transaction`.
+#[derive(Parser)]
+pub struct SignFeePayerTransaction {
+    #[clap(flatten)]
+    pub(crate) entry_function_args: EntryFunctionArguments,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+
+    /// Address of the fee payer account, which pays the gas for the transaction
+    #[clap(long, value_parser = load_account_arg)]
+    pub(crate) fee_payer_address: AccountAddress,
+
+    /// Output file to write the partially signed transaction to
+    #[clap(long, value_parser)]
+    pub(crate) output_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<String> for SignFeePayerTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignFeePayerTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<String> {
+        let save_file = SaveFile {
+            output_file: self.output_file,
+            prompt_options: self.txn_options.prompt_options,
+        };
+        save_file.check_file()?;
+
+        let partially_signed_txn = self
+            .txn_options
+            .sign_fee_payer_transaction_as_sender(
+                TransactionPayload::EntryFunction(self.entry_function_args.try_into()?),
+                self.fee_payer_address,
+            )
+            .await?;
+        save_file.save_to_file(
+            "Partially signed transaction",
+            &bcs::to_bytes(&partially_signed_txn)?,
+        )?;
+        Ok(format!(
+            "Partially signed transaction saved to {}",
+            save_file.output_file.display()
+        ))
+    }
+}
+
+/// Countersign a partially signed transaction (see `aptos move sign-fee-payer-transaction`)
+/// as the fee payer, and submit it
+#[derive(Parser)]
+pub struct SubmitFeePayerTransaction {
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+
+    /// File containing the partially signed transaction
+    #[clap(long, value_parser)]
+    pub(crate) partially_signed_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SubmitFeePayerTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitFeePayerTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let partially_signed_txn: PartiallySignedTransaction =
+            bcs::from_bytes(&read_from_file(&self.partially_signed_file)?)?;
+        self.txn_options
+            .submit_partially_signed_transaction(partially_signed_txn)
+            .await
+            .map(TransactionSummary::from)
     }
 }
 
@@ -1773,6 +1923,14 @@ pub struct Replay {
     /// If present, skip the comparison against the expected transaction output.
     #[clap(long)]
     pub(crate) skip_comparison: bool,
+
+    /// Path to a randomness seed sidecar file (recorded by a node with
+    /// `execution.randomness_seed_sidecar_path` set).
+    ///
+    /// If given, the seed recorded for the transaction is used for the randomness
+    /// natives, instead of the seed read from the network.
+    #[clap(long, value_parser)]
+    pub(crate) randomness_seed_file: Option<PathBuf>,
 }
 
 impl FromStr for ReplayNetworkSelection {
@@ -1810,7 +1968,7 @@ impl CliCommand<TransactionSummary> for Replay {
             RestEndpoint(url) => url,
         };
 
-        let debugger = AptosDebugger::rest_client(Client::new(
+        let mut debugger = AptosDebugger::rest_client(Client::new(
             Url::parse(rest_endpoint)
                 .map_err(|_err| CliError::UnableToParse("url", rest_endpoint.to_string()))?,
         ))?;
@@ -1832,6 +1990,11 @@ impl CliCommand<TransactionSummary> for Replay {
 
         let hash = txn.committed_hash();
 
+        // Override the block randomness with the seed recorded for the transaction (if given).
+        if let Some(randomness_seed_file) = &self.randomness_seed_file {
+            override_randomness_seed(&mut debugger, randomness_seed_file, hash)?;
+        }
+
         // Execute the transaction.
         let (vm_status, vm_output) = if self.profile_gas {
             println!("Profiling transaction...");
@@ -1901,6 +2064,52 @@ impl CliCommand<TransactionSummary> for Replay {
     }
 }
 
+/// Overrides the block randomness (read by the randomness natives) with the seed recorded
+/// for the given transaction in the randomness seed sidecar file.
+fn override_randomness_seed(
+    debugger: &mut AptosDebugger,
+    randomness_seed_file: &Path,
+    txn_hash: HashValue,
+) -> CliTypedResult<()> {
+    let file_path = randomness_seed_file.display().to_string();
+    let contents = std::fs::read_to_string(randomness_seed_file)
+        .map_err(|err| CliError::IO(file_path.clone(), err))?;
+    let record = find_randomness_seed_record(&contents, txn_hash)?.ok_or_else(|| {
+        CliError::UnexpectedError(format!(
+            "No randomness seed recorded for transaction {} in {}",
+            txn_hash, file_path
+        ))
+    })?;
+
+    let state_key = StateKey::on_chain_config::<PerBlockRandomness>()
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+    let state_value = StateValue::new_legacy(bcs::to_bytes(&record.per_block_randomness())?.into());
+    debugger.set_state_override(state_key, state_value);
+    println!(
+        "Using the recorded randomness seed of block {} (epoch {}, round {})",
+        record.block_id, record.epoch, record.round
+    );
+    Ok(())
+}
+
+/// Returns the record of the given transaction in the contents of a randomness seed
+/// sidecar file (if any). If a transaction was executed more than once (e.g., after a
+/// restart), the last record is returned.
+fn find_randomness_seed_record(
+    contents: &str,
+    txn_hash: HashValue,
+) -> CliTypedResult<Option<RandomnessSeedRecord>> {
+    let mut found_record = None;
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        let record = serde_json::from_str::<RandomnessSeedRecord>(line)
+            .map_err(|err| CliError::UnableToParse("randomness seed file", err.to_string()))?;
+        if record.txn_hash == txn_hash {
+            found_record = Some(record);
+        }
+    }
+    Ok(found_record)
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum FunctionArgType {
     Address,
@@ -2361,3 +2570,41 @@ impl FromStr for MemberId {
         parse_member_id(s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_randomness_seed_record() {
+        // Create a sidecar with records of two transactions (the first one recorded twice)
+        let first_txn_hash = HashValue::random();
+        let second_txn_hash = HashValue::random();
+        let records = [
+            RandomnessSeedRecord::new(first_txn_hash, 1, 1, HashValue::random(), vec![1; 32]),
+            RandomnessSeedRecord::new(second_txn_hash, 1, 1, HashValue::random(), vec![2; 32]),
+            RandomnessSeedRecord::new(first_txn_hash, 1, 2, HashValue::random(), vec![3; 32]),
+        ];
+        let contents = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap() + "\n")
+            .collect::<String>();
+
+        // Verify that the (last) record of each transaction is found
+        assert_eq!(
+            find_randomness_seed_record(&contents, first_txn_hash).unwrap(),
+            Some(records[2].clone())
+        );
+        assert_eq!(
+            find_randomness_seed_record(&contents, second_txn_hash).unwrap(),
+            Some(records[1].clone())
+        );
+        assert_eq!(
+            find_randomness_seed_record(&contents, HashValue::random()).unwrap(),
+            None
+        );
+
+        // Verify that malformed sidecar files are rejected
+        assert!(find_randomness_seed_record("not json", first_txn_hash).is_err());
+    }
+}
@@ -2,12 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::bail;
+use aptos_crypto::HashValue;
 use aptos_framework::{
+    get_compilation_metadata_from_compiled_module,
     natives::code::{ModuleMetadata, PackageMetadata, PackageRegistry, UpgradePolicy},
     unzip_metadata_str,
 };
 use aptos_rest_client::Client;
 use aptos_types::account_address::AccountAddress;
+use move_binary_format::CompiledModule;
+use move_model::metadata::{CompilerVersion, LanguageVersion};
 use move_package::compilation::package_layout::CompiledPackageLayout;
 use reqwest::Url;
 use std::{collections::BTreeMap, fmt, fs, path::Path};
@@ -31,6 +35,72 @@ pub struct CachedModuleMetadata<'a> {
     metadata: &'a ModuleMetadata,
 }
 
+/// The toolchain that built a package (as recorded in its deployed bytecode)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PackageToolchain {
+    pub compiler_version: Option<CompilerVersion>,
+    pub language_version: Option<LanguageVersion>,
+    pub bytecode_version: Option<u32>,
+}
+
+impl fmt::Display for PackageToolchain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let to_string = |value: Option<String>| value.unwrap_or_else(|| "unknown".into());
+        write!(
+            f,
+            "compiler version: {}, language version: {}, bytecode version: {}",
+            to_string(self.compiler_version.map(|version| version.to_string())),
+            to_string(self.language_version.map(|version| version.to_string())),
+            to_string(self.bytecode_version.map(|version| version.to_string())),
+        )
+    }
+}
+
+/// A mismatch between the deployed bytecode of a module and its local build
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BytecodeMismatch {
+    /// The module is deployed on-chain, but wasn't built locally
+    MissingLocally(String),
+    /// The module was built locally, but isn't deployed on-chain
+    MissingOnChain(String),
+    /// The bytecode of the module differs
+    HashMismatch {
+        module_name: String,
+        on_chain_hash: HashValue,
+        local_hash: HashValue,
+    },
+}
+
+impl fmt::Display for BytecodeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BytecodeMismatch::MissingLocally(module_name) => {
+                write!(
+                    f,
+                    "module `{}` is deployed, but wasn't built locally",
+                    module_name
+                )
+            },
+            BytecodeMismatch::MissingOnChain(module_name) => {
+                write!(
+                    f,
+                    "module `{}` was built locally, but isn't deployed",
+                    module_name
+                )
+            },
+            BytecodeMismatch::HashMismatch {
+                module_name,
+                on_chain_hash,
+                local_hash,
+            } => write!(
+                f,
+                "module `{}` bytecode doesn't match (on-chain hash: {}, local hash: {})",
+                module_name, on_chain_hash, local_hash
+            ),
+        }
+    }
+}
+
 impl fmt::Display for CachedPackageMetadata<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "{}", self.metadata)?;
@@ -117,6 +187,90 @@ impl CachedPackageRegistry {
             .get(module_name.as_ref())
             .map(|v| v.as_slice()))
     }
+
+    /// Returns the toolchain that built the given package, as recorded in the metadata of
+    /// its deployed modules. Requires the registry to be created with the bytecode.
+    pub fn get_package_toolchain(
+        &self,
+        package: &CachedPackageMetadata<'_>,
+    ) -> anyhow::Result<PackageToolchain> {
+        let mut package_toolchain = None;
+        for module_name in package.module_names() {
+            let module = CompiledModule::deserialize(self.get_module_bytecode(module_name)?)?;
+
+            // Modules without compilation metadata were built by the legacy compiler
+            let (compiler_version, language_version) =
+                match get_compilation_metadata_from_compiled_module(&module) {
+                    Some(metadata) => (
+                        Some(metadata.compiler_version()?),
+                        Some(metadata.language_version()?),
+                    ),
+                    None => (Some(CompilerVersion::V1), None),
+                };
+            let module_toolchain = PackageToolchain {
+                compiler_version,
+                language_version,
+                bytecode_version: Some(module.version),
+            };
+
+            // All modules of a package are published together (i.e., by the same toolchain)
+            match package_toolchain {
+                None => package_toolchain = Some(module_toolchain),
+                Some(package_toolchain) if package_toolchain != module_toolchain => bail!(
+                    "The modules of package `{}` were built by different toolchains ({} and {})",
+                    package.name(),
+                    package_toolchain,
+                    module_toolchain
+                ),
+                Some(_) => {},
+            }
+        }
+        Ok(package_toolchain.unwrap_or_default())
+    }
+
+    /// Compares the deployed bytecode of the given package with the given local
+    /// bytecode (by module name), and returns the mismatches (module by module).
+    /// Requires the registry to be created with the bytecode.
+    pub fn compare_package_bytecode(
+        &self,
+        package: &CachedPackageMetadata<'_>,
+        local_bytecode: &BTreeMap<String, Vec<u8>>,
+    ) -> anyhow::Result<Vec<BytecodeMismatch>> {
+        let mut mismatches = vec![];
+        for module_name in package.module_names() {
+            let on_chain_bytecode = self.get_module_bytecode(module_name)?;
+            match local_bytecode.get(module_name) {
+                Some(bytecode) => {
+                    let on_chain_hash = HashValue::sha3_256_of(on_chain_bytecode);
+                    let local_hash = HashValue::sha3_256_of(bytecode);
+                    if on_chain_hash != local_hash {
+                        mismatches.push(BytecodeMismatch::HashMismatch {
+                            module_name: module_name.to_string(),
+                            on_chain_hash,
+                            local_hash,
+                        });
+                    }
+                },
+                None => mismatches.push(BytecodeMismatch::MissingLocally(module_name.into())),
+            }
+        }
+
+        let on_chain_module_names = package.module_names();
+        for module_name in local_bytecode.keys() {
+            if !on_chain_module_names.contains(&module_name.as_str()) {
+                mismatches.push(BytecodeMismatch::MissingOnChain(module_name.clone()));
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// Returns the deployed bytecode of the given module (which must have been fetched)
+    fn get_module_bytecode(&self, module_name: &str) -> anyhow::Result<&[u8]> {
+        match self.bytecode.get(module_name) {
+            Some(bytecode) => Ok(bytecode.as_slice()),
+            None => bail!("bytecode of module `{}` not found", module_name),
+        }
+    }
 }
 
 impl<'a> CachedPackageMetadata<'a> {
@@ -339,6 +339,7 @@ impl CliTestFramework {
                 json_file: None,
             },
             txn_options: self.transaction_options(sender_index, gas_options),
+            fee_payer_profile: None,
         }
         .execute()
         .await
@@ -628,6 +629,7 @@ impl CliTestFramework {
                 json_file: None,
             },
             txn_options: self.transaction_options(owner_index, None),
+            fee_payer_profile: None,
         }
         .execute()
         .await
@@ -950,6 +952,7 @@ impl CliTestFramework {
                 json_file: None,
             },
             txn_options: self.transaction_options(index, gas_options),
+            fee_payer_profile: None,
         }
         .execute()
         .await
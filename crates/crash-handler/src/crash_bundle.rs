@@ -0,0 +1,349 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Crash bundles: on a panic (or fatal error), a bundle of debugging information is
+//! written to the configured directory. Each bundle contains the crash report (with a
+//! fingerprint of the crash, the details and the backtrace), the recent structured logs
+//! and (optionally) a snapshot of the metrics. The fingerprint only depends on the crash
+//! location and the top frames of the stack, so recurring crashes can be grouped together.
+//! Only the most recent bundles are kept in the directory (the oldest are removed).
+
+use crate::CrashInfo;
+use aptos_logger::{prelude::*, recent_logs};
+use aptos_metrics_core::{gather, Encoder, TextEncoder};
+use backtrace::Backtrace;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use sha3::{Digest, Sha3_256};
+use std::{
+    fmt::{Debug, Display},
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The prefix of each crash bundle directory (followed by the timestamp and fingerprint)
+const CRASH_BUNDLE_PREFIX: &str = "crash-";
+
+/// The number of bytes of the digest used as the crash fingerprint
+const FINGERPRINT_LENGTH_BYTES: usize = 8;
+
+/// The maximum number of stack frames used to compute a crash fingerprint
+const MAX_FINGERPRINT_FRAMES: usize = 8;
+
+/// Stack frames (by symbol prefix) that are ignored when computing a crash fingerprint,
+/// i.e., the frames of the panic machinery and the crash handler itself.
+const IGNORED_FRAME_PREFIXES: &[&str] = &[
+    "aptos_crash_handler::",
+    "backtrace::",
+    "core::panicking::",
+    "rust_begin_unwind",
+    "std::panicking::",
+    "std::sys",
+    "<alloc::boxed::Box<F,A> as core::ops::function::Fn",
+];
+
+/// The crash bundle settings (set once the node config is loaded)
+static CRASH_BUNDLE_CONFIG: OnceCell<CrashBundleConfig> = OnceCell::new();
+
+/// The settings used to write crash bundles
+#[derive(Clone, Debug)]
+pub struct CrashBundleConfig {
+    /// The directory to which crash bundles are written
+    pub crash_bundle_dir: PathBuf,
+    /// A digest of the node config (to identify the config the node crashed with)
+    pub config_digest: String,
+    /// Whether to include a snapshot of the metrics in each crash bundle
+    pub include_metrics_snapshot: bool,
+    /// Whether to notify telemetry (via an error log) when a crash bundle is written
+    pub notify_telemetry: bool,
+    /// The maximum number of crash bundles kept in the directory (the latest is always kept)
+    pub max_crash_bundles: usize,
+}
+
+/// Enables crash bundles using the given settings. Note: the settings can only be
+/// set once per process, and subsequent calls are ignored.
+pub fn setup_crash_bundles(crash_bundle_config: CrashBundleConfig) {
+    if CRASH_BUNDLE_CONFIG.set(crash_bundle_config).is_err() {
+        warn!("The crash bundle config has already been set! Ignoring the new config.");
+    }
+}
+
+/// The type of crash recorded in a crash bundle
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CrashKind {
+    Panic,
+    FatalError,
+}
+
+/// The crash report written to each crash bundle
+#[derive(Debug, Serialize)]
+struct CrashReport<'a> {
+    kind: CrashKind,
+    fingerprint: &'a str,
+    timestamp_ms: u64,
+    config_digest: &'a str,
+    details: &'a str,
+    backtrace: &'a str,
+}
+
+/// Reports a fatal (i.e., unrecoverable) error. The error is logged and, if crash
+/// bundles are enabled, a crash bundle is written. The caller is responsible for
+/// terminating the process.
+pub fn report_fatal_error<E: Debug + Display>(error: &E) {
+    let backtrace = Backtrace::new();
+    let fingerprint = compute_fingerprint(&normalize_error_message(error), &backtrace);
+    let info = CrashInfo {
+        details: format!("{:?}", error),
+        fingerprint,
+        backtrace: format!("{:#?}", backtrace),
+    };
+    error!("Fatal error! {}", toml::to_string_pretty(&info).unwrap());
+    eprintln!("Fatal error! {:?}", error);
+
+    write_crash_bundle(CrashKind::FatalError, &info);
+    aptos_logger::flush();
+}
+
+/// Computes a fingerprint of the crash using the given crash location and the top
+/// (non-panic) frames of the backtrace. Symbol hashes are ignored, and the digest is
+/// stable across builds, so the fingerprint remains the same across occurrences (and
+/// releases) of the same crash.
+pub(crate) fn compute_fingerprint(location: &str, backtrace: &Backtrace) -> String {
+    let mut hasher = Sha3_256::new();
+    hasher.update(location.as_bytes());
+    for frame_name in get_fingerprint_frames(backtrace) {
+        hasher.update(b"\n");
+        hasher.update(frame_name.as_bytes());
+    }
+    hasher.finalize()[..FINGERPRINT_LENGTH_BYTES]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Returns the (demangled and hash-free) names of the top frames used for fingerprinting
+fn get_fingerprint_frames(backtrace: &Backtrace) -> Vec<String> {
+    backtrace
+        .frames()
+        .iter()
+        .flat_map(|frame| frame.symbols())
+        .filter_map(|symbol| symbol.name().map(|name| format!("{:#}", name)))
+        .filter(|name| {
+            !IGNORED_FRAME_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        })
+        .take(MAX_FINGERPRINT_FRAMES)
+        .collect()
+}
+
+/// Normalizes the error message for fingerprinting (by stripping digits, e.g.,
+/// versions, rounds and ports), so that similar errors share a fingerprint.
+fn normalize_error_message<E: Display>(error: &E) -> String {
+    error
+        .to_string()
+        .chars()
+        .filter(|character| !character.is_ascii_digit())
+        .collect()
+}
+
+/// Writes a crash bundle for the given crash (if crash bundles are enabled)
+pub(crate) fn write_crash_bundle(kind: CrashKind, info: &CrashInfo) {
+    if let Some(crash_bundle_config) = CRASH_BUNDLE_CONFIG.get() {
+        write_crash_bundle_with_config(crash_bundle_config, kind, info);
+    }
+}
+
+/// Writes a crash bundle for the given crash using the given settings, and removes the
+/// oldest bundles (beyond the maximum). Returns the bundle directory (if it was written).
+fn write_crash_bundle_with_config(
+    crash_bundle_config: &CrashBundleConfig,
+    kind: CrashKind,
+    info: &CrashInfo,
+) -> Option<PathBuf> {
+    // Write the crash bundle
+    let timestamp_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default();
+    let bundle_dir = crash_bundle_config.crash_bundle_dir.join(format!(
+        "{}{}-{}",
+        CRASH_BUNDLE_PREFIX, timestamp_ms, info.fingerprint
+    ));
+    let crash_report = CrashReport {
+        kind,
+        fingerprint: &info.fingerprint,
+        timestamp_ms,
+        config_digest: &crash_bundle_config.config_digest,
+        details: &info.details,
+        backtrace: &info.backtrace,
+    };
+    if let Err(error) = write_bundle_files(&bundle_dir, &crash_report, crash_bundle_config) {
+        eprintln!(
+            "Failed to write the crash bundle to {:?}: {}",
+            bundle_dir, error
+        );
+        return None;
+    }
+    eprintln!("Wrote the crash bundle to {:?}", bundle_dir);
+
+    // Remove the oldest crash bundles
+    if let Err(error) = prune_crash_bundles(
+        &crash_bundle_config.crash_bundle_dir,
+        crash_bundle_config.max_crash_bundles,
+    ) {
+        eprintln!("Failed to remove the old crash bundles: {}", error);
+    }
+
+    // Notify telemetry (error logs are forwarded to telemetry)
+    if crash_bundle_config.notify_telemetry {
+        error!(
+            crash_kind = ?kind,
+            crash_fingerprint = info.fingerprint,
+            crash_bundle_dir = ?bundle_dir,
+            "The node crashed! A crash bundle was written."
+        );
+        aptos_logger::flush();
+    }
+
+    Some(bundle_dir)
+}
+
+/// Removes the oldest crash bundles in the given directory, so that (at most)
+/// the given number of bundles are kept. The latest bundle is always kept.
+fn prune_crash_bundles(crash_bundle_dir: &Path, max_crash_bundles: usize) -> io::Result<()> {
+    // Identify the crash bundles (by timestamp)
+    let mut crash_bundles = vec![];
+    for entry in fs::read_dir(crash_bundle_dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let timestamp_ms = file_name
+            .strip_prefix(CRASH_BUNDLE_PREFIX)
+            .and_then(|suffix| suffix.split('-').next())
+            .and_then(|timestamp_ms| timestamp_ms.parse::<u64>().ok());
+        if let Some(timestamp_ms) = timestamp_ms {
+            crash_bundles.push((timestamp_ms, file_name, entry.path()));
+        }
+    }
+
+    // Remove the oldest crash bundles (beyond the maximum)
+    crash_bundles.sort();
+    let num_bundles_to_remove = crash_bundles.len().saturating_sub(max_crash_bundles.max(1));
+    for (_, _, bundle_dir) in crash_bundles.into_iter().take(num_bundles_to_remove) {
+        fs::remove_dir_all(bundle_dir)?;
+    }
+    Ok(())
+}
+
+/// Writes the files of the crash bundle to the given directory
+fn write_bundle_files(
+    bundle_dir: &Path,
+    crash_report: &CrashReport,
+    crash_bundle_config: &CrashBundleConfig,
+) -> io::Result<()> {
+    fs::create_dir_all(bundle_dir)?;
+
+    // Write the crash report
+    let crash_report = toml::to_string_pretty(crash_report).map_err(io::Error::other)?;
+    fs::write(bundle_dir.join("crash_report.toml"), crash_report)?;
+
+    // Write the recent logs (one JSON entry per line)
+    let recent_logs = recent_logs::get_recent_logs();
+    fs::write(bundle_dir.join("recent_logs.jsonl"), recent_logs.join("\n"))?;
+
+    // Write the metrics snapshot
+    if crash_bundle_config.include_metrics_snapshot {
+        let mut metrics = vec![];
+        TextEncoder::new()
+            .encode(&gather(), &mut metrics)
+            .map_err(io::Error::other)?;
+        fs::write(bundle_dir.join("metrics.txt"), metrics)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compute_fingerprint, get_fingerprint_frames, normalize_error_message,
+        write_crash_bundle_with_config, CrashBundleConfig, CrashKind,
+    };
+    use crate::CrashInfo;
+    use aptos_temppath::TempPath;
+    use backtrace::Backtrace;
+    use std::{fs, thread, time::Duration};
+
+    #[test]
+    fn test_crash_bundles() {
+        let crash_bundle_dir = TempPath::new();
+        crash_bundle_dir.create_as_dir().unwrap();
+        let crash_bundle_config = CrashBundleConfig {
+            crash_bundle_dir: crash_bundle_dir.path().to_path_buf(),
+            config_digest: "config_digest".into(),
+            include_metrics_snapshot: true,
+            notify_telemetry: false,
+            max_crash_bundles: 2,
+        };
+
+        // Write several crash bundles
+        let mut bundle_dirs = vec![];
+        for index in 0..3 {
+            let info = CrashInfo {
+                details: format!("details_{}", index),
+                fingerprint: format!("fingerprint_{}", index),
+                backtrace: "backtrace".into(),
+            };
+            let bundle_dir =
+                write_crash_bundle_with_config(&crash_bundle_config, CrashKind::Panic, &info)
+                    .unwrap();
+            bundle_dirs.push(bundle_dir);
+            thread::sleep(Duration::from_millis(2)); // Ensure distinct timestamps
+        }
+
+        // Verify that the latest bundle holds the crash report, logs and metrics
+        let latest_bundle_dir = bundle_dirs.last().unwrap();
+        let crash_report = fs::read_to_string(latest_bundle_dir.join("crash_report.toml")).unwrap();
+        for expected_value in ["panic", "fingerprint_2", "config_digest", "details_2"] {
+            assert!(crash_report.contains(expected_value));
+        }
+        assert!(latest_bundle_dir.join("recent_logs.jsonl").exists());
+        assert!(latest_bundle_dir.join("metrics.txt").exists());
+
+        // Verify that only the latest bundles are kept
+        let existing_bundles: Vec<_> = bundle_dirs
+            .iter()
+            .map(|bundle_dir| bundle_dir.exists())
+            .collect();
+        assert_eq!(existing_bundles, vec![false, true, true]);
+    }
+
+    #[test]
+    fn test_fingerprints() {
+        // Verify the fingerprint is stable for the same location and stack
+        let backtrace = Backtrace::new();
+        let fingerprint = compute_fingerprint("src/lib.rs:10:5", &backtrace);
+        assert_eq!(
+            fingerprint,
+            compute_fingerprint("src/lib.rs:10:5", &backtrace)
+        );
+        assert_ne!(
+            fingerprint,
+            compute_fingerprint("src/lib.rs:11:5", &backtrace)
+        );
+
+        // Verify the fingerprint frames skip the backtrace machinery and the crash handler
+        for frame_name in get_fingerprint_frames(&backtrace) {
+            assert!(!frame_name.starts_with("backtrace::"));
+            assert!(!frame_name.starts_with("aptos_crash_handler::"));
+        }
+
+        // Verify the error messages are normalized
+        assert_eq!(
+            normalize_error_message(&"Failed to bind to port 6180 (attempt 2)"),
+            normalize_error_message(&"Failed to bind to port 8080 (attempt 3)")
+        );
+    }
+}
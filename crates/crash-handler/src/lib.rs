@@ -4,6 +4,9 @@
 
 #![forbid(unsafe_code)]
 
+mod crash_bundle;
+
+use crate::crash_bundle::CrashKind;
 use aptos_logger::prelude::*;
 use backtrace::Backtrace;
 use move_core_types::state::{self, VMState};
@@ -13,9 +16,12 @@ use std::{
     process,
 };
 
+pub use crash_bundle::{report_fatal_error, setup_crash_bundles, CrashBundleConfig};
+
 #[derive(Debug, Serialize)]
 pub struct CrashInfo {
     details: String,
+    fingerprint: String,
     backtrace: String,
 }
 
@@ -23,7 +29,8 @@ pub struct CrashInfo {
 ///
 /// Tokio's default behavior is to catch panics and ignore them.  Invoking this function will
 /// ensure that all subsequent thread panics (even Tokio threads) will report the
-/// details/backtrace and then exit.
+/// details/backtrace and then exit. The only exception are panics on the threads of
+/// supervised (i.e., non-safety-critical) components, which are contained and restarted.
 pub fn setup_panic_handler() {
     panic::set_hook(Box::new(move |pi: &PanicInfo<'_>| {
         handle_panic(pi);
@@ -34,9 +41,18 @@ pub fn setup_panic_handler() {
 fn handle_panic(panic_info: &PanicInfo<'_>) {
     // The Display formatter for a PanicInfo contains the message, payload and location.
     let details = format!("{}", panic_info);
-    let backtrace = format!("{:#?}", Backtrace::new());
+    let backtrace = Backtrace::new();
+    let location = panic_info
+        .location()
+        .map(|location| location.to_string())
+        .unwrap_or_default();
+    let fingerprint = crash_bundle::compute_fingerprint(&location, &backtrace);
 
-    let info = CrashInfo { details, backtrace };
+    let info = CrashInfo {
+        details,
+        fingerprint,
+        backtrace: format!("{:#?}", backtrace),
+    };
     let crash_info = toml::to_string_pretty(&info).unwrap();
     error!("{}", crash_info);
     // TODO / HACK ALARM: Write crash info synchronously via eprintln! to ensure it is written before the process exits which error! doesn't guarantee.
@@ -54,6 +70,17 @@ fn handle_panic(panic_info: &PanicInfo<'_>) {
         return;
     }
 
+    // Do not kill the process if the panic happened in a supervised (i.e., non-safety-critical)
+    // component. The panic will unwind the task, and the supervisor will restart it. No crash
+    // bundle is written, as the node doesn't crash.
+    if let Some(component) = aptos_runtimes::supervised_component() {
+        aptos_runtimes::record_supervised_panic(component);
+        return;
+    }
+
+    // Write a crash bundle (if enabled)
+    crash_bundle::write_crash_bundle(CrashKind::Panic, &info);
+
     // Kill the process
     process::exit(12);
 }
@@ -0,0 +1,641 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coordinated multi-machine emission. A single controller owns the workload
+//! definition (the `EmitArgs`) and waits for a fixed number of workers to
+//! register. Each worker is then assigned its share of the load (target TPS,
+//! mempool backlog and accounts), a distinct account minter seed (so that
+//! workers never share accounts), and a common start time (so that the
+//! transaction phases of all workers transition together). Once the workers
+//! are done, they report their stats back and the controller aggregates them.
+//!
+//! The protocol is a minimal JSON over HTTP protocol, driven by the workers:
+//! - `POST /v1/register`: registers a worker and returns its worker index.
+//! - `GET /v1/assignment/<worker_index>`: returns the worker instruction.
+//! - `POST /v1/report`: reports the results of the worker emission.
+
+use crate::{
+    args::{ClusterArgs, EmitArgs},
+    emitter::{parse_seed, stats::TxnStats},
+    wrappers::emit_transactions,
+};
+use anyhow::{bail, Context, Result};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::{info, warn};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use url::Url;
+use warp::{http::StatusCode, Filter};
+
+#[derive(Clone, Debug, Parser)]
+pub struct ControllerArgs {
+    /// The address the controller listens on for worker requests
+    #[clap(long, default_value = "0.0.0.0:9100")]
+    pub listen_address: SocketAddr,
+
+    /// The number of workers to wait for before starting the emission
+    #[clap(long)]
+    pub num_workers: usize,
+
+    /// The max time to wait for all workers to register
+    #[clap(long, default_value_t = 300)]
+    pub worker_registration_timeout_secs: u64,
+
+    /// The delay between the last worker registering and the common start time
+    /// (this should be larger than the worker polling interval).
+    #[clap(long, default_value_t = 10)]
+    pub start_delay_secs: u64,
+
+    /// The additional time (after the emission duration) to wait for worker reports
+    #[clap(long, default_value_t = 600)]
+    pub worker_report_grace_period_secs: u64,
+
+    /// The max time to wait for the registered workers to receive the abort
+    /// instruction (if the emission is aborted)
+    #[clap(long, default_value_t = 30)]
+    pub worker_abort_timeout_secs: u64,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct WorkerArgs {
+    /// The URL of the emission controller
+    #[clap(long)]
+    pub controller_url: Url,
+
+    /// A human readable identifier for the worker (used for logging)
+    #[clap(long)]
+    pub worker_id: Option<String>,
+
+    /// The interval at which the worker polls the controller for its assignment
+    #[clap(long, default_value_t = 1)]
+    pub poll_interval_secs: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RegisterRequest {
+    pub worker_id: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RegisterResponse {
+    pub worker_index: usize,
+    pub num_workers: usize,
+}
+
+/// The assignment the controller hands out to a single worker
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WorkerAssignment {
+    pub worker_index: usize,
+    pub emit_args: EmitArgs,
+    pub start_time_unix_secs: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum WorkerInstruction {
+    /// Not all workers have registered yet, so the worker should poll again
+    Wait,
+    /// The worker should start emitting with the given assignment
+    Emit(WorkerAssignment),
+    /// The emission has been aborted, so the worker should exit
+    Abort(String),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WorkerReport {
+    pub worker_index: usize,
+    pub result: Result<TxnStats, String>,
+}
+
+/// The aggregated results of a coordinated emission
+#[derive(Clone, Debug)]
+pub struct CoordinatedEmissionStats {
+    pub total: TxnStats,
+    pub per_worker: Vec<Option<TxnStats>>,
+    pub failed_workers: Vec<(usize, String)>,
+}
+
+/// The shared state of the controller (as seen by the HTTP handlers)
+struct ControllerState {
+    num_workers: usize,
+    registered_workers: Vec<String>,
+    assignments: Option<Vec<WorkerAssignment>>,
+    abort_reason: Option<String>,
+    aborted_workers: HashSet<usize>,
+    reports: Vec<Option<Result<TxnStats, String>>>,
+}
+
+impl ControllerState {
+    fn new(num_workers: usize) -> Self {
+        Self {
+            num_workers,
+            registered_workers: vec![],
+            assignments: None,
+            abort_reason: None,
+            aborted_workers: HashSet::new(),
+            reports: vec![None; num_workers],
+        }
+    }
+
+    fn register(&mut self, worker_id: String) -> Result<RegisterResponse, String> {
+        if let Some(abort_reason) = &self.abort_reason {
+            return Err(format!("The emission was aborted: {}", abort_reason));
+        }
+        if self.registered_workers.len() >= self.num_workers {
+            return Err(format!(
+                "All {} workers have already registered!",
+                self.num_workers
+            ));
+        }
+
+        let worker_index = self.registered_workers.len();
+        info!(
+            "Registered emission worker {} (index: {})",
+            worker_id, worker_index
+        );
+        self.registered_workers.push(worker_id);
+        Ok(RegisterResponse {
+            worker_index,
+            num_workers: self.num_workers,
+        })
+    }
+
+    fn instruction(&mut self, worker_index: usize) -> WorkerInstruction {
+        if let Some(abort_reason) = &self.abort_reason {
+            self.aborted_workers.insert(worker_index);
+            return WorkerInstruction::Abort(abort_reason.clone());
+        }
+        match &self.assignments {
+            Some(assignments) => match assignments.get(worker_index) {
+                Some(assignment) => WorkerInstruction::Emit(assignment.clone()),
+                None => WorkerInstruction::Abort(format!("Unknown worker index: {}", worker_index)),
+            },
+            None => WorkerInstruction::Wait,
+        }
+    }
+
+    fn report(&mut self, report: WorkerReport) -> Result<(), String> {
+        match self.reports.get_mut(report.worker_index) {
+            Some(entry) => {
+                info!(
+                    "Received report from emission worker {}: {:?}",
+                    report.worker_index,
+                    report.result.as_ref().map(|stats| stats.rate().to_string())
+                );
+                *entry = Some(report.result);
+                Ok(())
+            },
+            None => Err(format!("Unknown worker index: {}", report.worker_index)),
+        }
+    }
+
+    fn all_workers_registered(&self) -> bool {
+        self.registered_workers.len() == self.num_workers
+    }
+
+    fn all_workers_reported(&self) -> bool {
+        self.reports.iter().all(Option::is_some)
+    }
+
+    /// Returns true iff all registered workers have received the abort instruction
+    fn all_workers_aborted(&self) -> bool {
+        (0..self.registered_workers.len())
+            .all(|worker_index| self.aborted_workers.contains(&worker_index))
+    }
+}
+
+/// Runs the emission controller. This waits for all workers to register, hands
+/// out the worker assignments, and then aggregates the worker reports.
+pub async fn run_emission_controller(
+    controller_args: &ControllerArgs,
+    emit_args: &EmitArgs,
+) -> Result<CoordinatedEmissionStats> {
+    if controller_args.num_workers == 0 {
+        bail!("The number of emission workers must be greater than 0!");
+    }
+
+    // Start the controller server
+    let state = Arc::new(Mutex::new(ControllerState::new(
+        controller_args.num_workers,
+    )));
+    let (_, server) = warp::serve(controller_routes(state.clone()))
+        .try_bind_ephemeral(controller_args.listen_address)
+        .context("Failed to start the emission controller server")?;
+    let server_handle = tokio::spawn(server);
+    info!(
+        "Emission controller listening on {}, waiting for {} workers",
+        controller_args.listen_address, controller_args.num_workers
+    );
+
+    let result = coordinate_emission(controller_args, emit_args, state).await;
+    server_handle.abort();
+    result
+}
+
+async fn coordinate_emission(
+    controller_args: &ControllerArgs,
+    emit_args: &EmitArgs,
+    state: Arc<Mutex<ControllerState>>,
+) -> Result<CoordinatedEmissionStats> {
+    // Wait for all workers to register
+    let registration_deadline =
+        Instant::now() + Duration::from_secs(controller_args.worker_registration_timeout_secs);
+    while !state.lock().all_workers_registered() {
+        if Instant::now() > registration_deadline {
+            let abort_reason = format!(
+                "Timed out waiting for workers to register! Registered: {:?}",
+                state.lock().registered_workers
+            );
+            abort_emission(controller_args, &state, abort_reason.clone()).await;
+            bail!(abort_reason);
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    // Create and hand out the worker assignments
+    let start_time_unix_secs = unix_time_secs() + controller_args.start_delay_secs;
+    let assignments = (0..controller_args.num_workers)
+        .map(|worker_index| {
+            create_worker_assignment(
+                emit_args,
+                worker_index,
+                controller_args.num_workers,
+                start_time_unix_secs,
+            )
+        })
+        .collect();
+    state.lock().assignments = Some(assignments);
+    info!(
+        "All {} workers registered. Emission starts at unix time {}",
+        controller_args.num_workers, start_time_unix_secs
+    );
+
+    // Wait for all workers to report (the workers may also need time to mint accounts)
+    let report_deadline = Instant::now()
+        + Duration::from_secs(
+            controller_args.start_delay_secs
+                + emit_args.coordination_delay_between_instances.unwrap_or(0)
+                + emit_args.duration
+                + controller_args.worker_report_grace_period_secs,
+        );
+    while !state.lock().all_workers_reported() {
+        if Instant::now() > report_deadline {
+            warn!("Timed out waiting for all worker reports! Aggregating the received reports.");
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    // Aggregate the worker reports
+    let reports = state.lock().reports.clone();
+    let mut per_worker = vec![];
+    let mut failed_workers = vec![];
+    for (worker_index, report) in reports.into_iter().enumerate() {
+        match report {
+            Some(Ok(stats)) => per_worker.push(Some(stats)),
+            Some(Err(error)) => {
+                failed_workers.push((worker_index, error));
+                per_worker.push(None);
+            },
+            None => {
+                failed_workers.push((worker_index, "Worker never reported!".into()));
+                per_worker.push(None);
+            },
+        }
+    }
+    let total = aggregate_worker_stats(per_worker.iter().flatten());
+
+    Ok(CoordinatedEmissionStats {
+        total,
+        per_worker,
+        failed_workers,
+    })
+}
+
+/// Aborts the emission and waits (up to the abort timeout) for all registered
+/// workers to receive the abort instruction, so that they don't keep waiting
+/// for an assignment that will never arrive.
+async fn abort_emission(
+    controller_args: &ControllerArgs,
+    state: &Arc<Mutex<ControllerState>>,
+    abort_reason: String,
+) {
+    warn!("Aborting the coordinated emission: {}", abort_reason);
+    state.lock().abort_reason = Some(abort_reason);
+
+    let abort_deadline =
+        Instant::now() + Duration::from_secs(controller_args.worker_abort_timeout_secs);
+    while !state.lock().all_workers_aborted() {
+        if Instant::now() > abort_deadline {
+            warn!("Timed out waiting for all workers to receive the abort instruction!");
+            return;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+fn controller_routes(
+    state: Arc<Mutex<ControllerState>>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let with_state = warp::any().map(move || state.clone());
+
+    let register = warp::path!("v1" / "register")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state.clone())
+        .map(
+            |request: RegisterRequest, state: Arc<Mutex<ControllerState>>| match state
+                .lock()
+                .register(request.worker_id)
+            {
+                Ok(response) => {
+                    warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)
+                },
+                Err(error) => {
+                    warp::reply::with_status(warp::reply::json(&error), StatusCode::CONFLICT)
+                },
+            },
+        );
+
+    let assignment = warp::path!("v1" / "assignment" / usize)
+        .and(warp::get())
+        .and(with_state.clone())
+        .map(|worker_index: usize, state: Arc<Mutex<ControllerState>>| {
+            warp::reply::json(&state.lock().instruction(worker_index))
+        });
+
+    let report = warp::path!("v1" / "report")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_state)
+        .map(|report: WorkerReport, state: Arc<Mutex<ControllerState>>| {
+            match state.lock().report(report) {
+                Ok(()) => warp::reply::with_status(warp::reply::json(&()), StatusCode::OK),
+                Err(error) => {
+                    warp::reply::with_status(warp::reply::json(&error), StatusCode::BAD_REQUEST)
+                },
+            }
+        });
+
+    register.or(assignment).or(report)
+}
+
+/// Runs an emission worker. This registers with the controller, waits for the
+/// worker assignment, emits the assigned load, and reports the results back.
+pub async fn run_emission_worker(
+    cluster_args: &ClusterArgs,
+    worker_args: &WorkerArgs,
+) -> Result<TxnStats> {
+    let client = reqwest::Client::new();
+    let worker_id = worker_args
+        .worker_id
+        .clone()
+        .unwrap_or_else(|| format!("worker-{}", HashValue::random().to_hex()));
+
+    // Register with the controller
+    let register_response: RegisterResponse = client
+        .post(worker_args.controller_url.join("v1/register")?)
+        .json(&RegisterRequest {
+            worker_id: worker_id.clone(),
+        })
+        .send()
+        .await?
+        .error_for_status()
+        .context("Failed to register with the emission controller")?
+        .json()
+        .await?;
+    let worker_index = register_response.worker_index;
+    info!(
+        "Registered worker {} with index {} (out of {} workers)",
+        worker_id, worker_index, register_response.num_workers
+    );
+
+    // Poll the controller until the assignment is available
+    let assignment_url = worker_args
+        .controller_url
+        .join(&format!("v1/assignment/{}", worker_index))?;
+    let assignment = loop {
+        let instruction: WorkerInstruction = client
+            .get(assignment_url.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match instruction {
+            WorkerInstruction::Wait => {
+                tokio::time::sleep(Duration::from_secs(worker_args.poll_interval_secs)).await;
+            },
+            WorkerInstruction::Emit(assignment) => break assignment,
+            WorkerInstruction::Abort(abort_reason) => {
+                bail!(
+                    "The emission was aborted by the controller: {}",
+                    abort_reason
+                )
+            },
+        }
+    };
+
+    // Wait for the common start time
+    let start_time_unix_secs = assignment.start_time_unix_secs;
+    let now_unix_secs = unix_time_secs();
+    if start_time_unix_secs > now_unix_secs {
+        tokio::time::sleep(Duration::from_secs(start_time_unix_secs - now_unix_secs)).await;
+    }
+
+    // Emit the assigned load and report the results to the controller
+    info!("Starting emission with assignment: {:?}", assignment);
+    let result = emit_transactions(cluster_args, &assignment.emit_args).await;
+    let report = WorkerReport {
+        worker_index,
+        result: result
+            .as_ref()
+            .cloned()
+            .map_err(|error| format!("{:?}", error)),
+    };
+    client
+        .post(worker_args.controller_url.join("v1/report")?)
+        .json(&report)
+        .send()
+        .await?
+        .error_for_status()
+        .context("Failed to report the results to the emission controller")?;
+
+    result
+}
+
+/// Creates the assignment for the given worker. The load is split evenly
+/// across all workers, and each worker is given a distinct account minter
+/// seed (if a seed was specified), so that workers don't share accounts.
+fn create_worker_assignment(
+    emit_args: &EmitArgs,
+    worker_index: usize,
+    num_workers: usize,
+    start_time_unix_secs: u64,
+) -> WorkerAssignment {
+    let mut worker_emit_args = emit_args.clone();
+    worker_emit_args.target_tps = emit_args
+        .target_tps
+        .map(|target_tps| split_evenly(target_tps, worker_index, num_workers));
+    worker_emit_args.mempool_backlog = emit_args
+        .mempool_backlog
+        .map(|mempool_backlog| split_evenly(mempool_backlog, worker_index, num_workers));
+    worker_emit_args.num_accounts = emit_args
+        .num_accounts
+        .map(|num_accounts| split_evenly(num_accounts, worker_index, num_workers).max(1));
+    worker_emit_args.expected_max_txns = emit_args.expected_max_txns.map(|expected_max_txns| {
+        split_evenly(expected_max_txns as usize, worker_index, num_workers) as u64
+    });
+    worker_emit_args.account_minter_seed = emit_args
+        .account_minter_seed
+        .as_ref()
+        .map(|seed| derive_worker_seed(seed, worker_index));
+
+    WorkerAssignment {
+        worker_index,
+        emit_args: worker_emit_args,
+        start_time_unix_secs,
+    }
+}
+
+/// Returns the share of the total for the given worker (the remainder
+/// is spread across the first workers).
+fn split_evenly(total: usize, worker_index: usize, num_workers: usize) -> usize {
+    let share = total / num_workers;
+    if worker_index < total % num_workers {
+        share + 1
+    } else {
+        share
+    }
+}
+
+/// Derives a distinct account minter seed for the given worker
+fn derive_worker_seed(seed: &str, worker_index: usize) -> String {
+    let mut seed_bytes = parse_seed(seed).to_vec();
+    seed_bytes.extend((worker_index as u64).to_le_bytes());
+    format!("{:?}", HashValue::sha3_256_of(&seed_bytes).to_vec())
+}
+
+/// Aggregates the stats of all workers. The workers emit in parallel, so the
+/// duration of the aggregated stats is the longest worker duration.
+fn aggregate_worker_stats<'a>(worker_stats: impl Iterator<Item = &'a TxnStats>) -> TxnStats {
+    worker_stats.fold(TxnStats::default(), |total, stats| {
+        let mut aggregated = &total + stats;
+        aggregated.lasted = total.lasted.max(stats.lasted);
+        aggregated
+    })
+}
+
+fn unix_time_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the unix epoch!")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_evenly() {
+        let shares: Vec<_> = (0..4).map(|index| split_evenly(10, index, 4)).collect();
+        assert_eq!(shares, vec![3, 3, 2, 2]);
+        assert_eq!(shares.iter().sum::<usize>(), 10);
+
+        let shares: Vec<_> = (0..3).map(|index| split_evenly(2, index, 3)).collect();
+        assert_eq!(shares, vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn test_create_worker_assignment() {
+        let emit_args = EmitArgs {
+            target_tps: Some(100_000),
+            num_accounts: Some(1_000),
+            account_minter_seed: Some(format!("{:?}", vec![7u8; 32])),
+            ..EmitArgs::default()
+        };
+
+        let assignments: Vec<_> = (0..3)
+            .map(|index| create_worker_assignment(&emit_args, index, 3, 10))
+            .collect();
+
+        // Verify the load is split across the workers
+        let total_tps: usize = assignments
+            .iter()
+            .map(|assignment| assignment.emit_args.target_tps.unwrap())
+            .sum();
+        assert_eq!(total_tps, 100_000);
+        let total_accounts: usize = assignments
+            .iter()
+            .map(|assignment| assignment.emit_args.num_accounts.unwrap())
+            .sum();
+        assert_eq!(total_accounts, 1_000);
+
+        // Verify the workers have distinct (and valid) seeds, and a common start time
+        let seeds: Vec<_> = assignments
+            .iter()
+            .map(|assignment| {
+                parse_seed(assignment.emit_args.account_minter_seed.as_ref().unwrap())
+            })
+            .collect();
+        assert_ne!(seeds[0], seeds[1]);
+        assert_ne!(seeds[1], seeds[2]);
+        assert!(assignments
+            .iter()
+            .all(|assignment| assignment.start_time_unix_secs == 10));
+    }
+
+    #[test]
+    fn test_abort_registered_workers() {
+        // Register a single worker (out of two)
+        let mut state = ControllerState::new(2);
+        let response = state.register("worker-0".into()).unwrap();
+        assert!(matches!(
+            state.instruction(response.worker_index),
+            WorkerInstruction::Wait
+        ));
+        assert!(!state.all_workers_aborted());
+
+        // Abort the emission and verify the registered worker receives the abort
+        state.abort_reason = Some("Timed out!".into());
+        assert!(!state.all_workers_aborted());
+        assert!(matches!(
+            state.instruction(response.worker_index),
+            WorkerInstruction::Abort(_)
+        ));
+        assert!(state.all_workers_aborted());
+
+        // Verify that no more workers can register
+        assert!(state.register("worker-1".into()).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_worker_stats() {
+        let worker_stats = vec![
+            TxnStats {
+                submitted: 10,
+                committed: 8,
+                lasted: Duration::from_secs(60),
+                ..TxnStats::default()
+            },
+            TxnStats {
+                submitted: 20,
+                committed: 15,
+                lasted: Duration::from_secs(62),
+                ..TxnStats::default()
+            },
+        ];
+
+        let total = aggregate_worker_stats(worker_stats.iter());
+        assert_eq!(total.submitted, 30);
+        assert_eq!(total.committed, 23);
+        assert_eq!(total.lasted, Duration::from_secs(62));
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use serde::{Deserialize, Serialize};
 use std::{
     fmt,
     ops::{Add, Sub},
@@ -11,7 +12,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct TxnStats {
     pub submitted: u64,
     pub committed: u64,
@@ -198,7 +199,7 @@ impl AtomicHistogramAccumulator {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AtomicHistogramSnapshot {
     capacity: usize,
     step_width: u64,
@@ -5,6 +5,7 @@
 
 mod args;
 mod cluster;
+mod coordinator;
 pub mod emitter;
 mod instance;
 mod wrappers;
@@ -13,6 +14,10 @@ mod wrappers;
 pub use args::{ClusterArgs, CoinSourceArgs, CreateAccountsArgs, EmitArgs};
 // We export these if you want finer grained control.
 pub use cluster::Cluster;
+pub use coordinator::{
+    run_emission_controller, run_emission_worker, ControllerArgs, CoordinatedEmissionStats,
+    WorkerArgs,
+};
 pub use emitter::{
     query_sequence_number, query_sequence_numbers,
     stats::{TxnStats, TxnStatsRate},
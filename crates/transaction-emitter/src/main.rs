@@ -7,7 +7,8 @@ mod diag;
 use anyhow::{Context, Result};
 use aptos_logger::{Level, Logger};
 use aptos_transaction_emitter_lib::{
-    create_accounts_command, emit_transactions, Cluster, ClusterArgs, CreateAccountsArgs, EmitArgs,
+    create_accounts_command, emit_transactions, run_emission_controller, run_emission_worker,
+    Cluster, ClusterArgs, ControllerArgs, CreateAccountsArgs, EmitArgs, WorkerArgs,
 };
 use clap::{Parser, Subcommand};
 use diag::diag;
@@ -25,6 +26,14 @@ enum TxnEmitterCommand {
     /// recording stats as we go.
     EmitTx(EmitTx),
 
+    /// Runs the controller for a coordinated multi-machine emission. The controller
+    /// splits the load across the registered workers and aggregates their results.
+    EmitTxController(EmitTxController),
+
+    /// Runs a worker for a coordinated multi-machine emission. The worker emits
+    /// the load assigned to it by the controller against the target peer(s).
+    EmitTxWorker(EmitTxWorker),
+
     /// Create test accounts, for use with EmitTx
     CreateAccounts(CreateAccounts),
 
@@ -46,6 +55,24 @@ struct EmitTx {
     emit_args: EmitArgs,
 }
 
+#[derive(Parser, Debug)]
+struct EmitTxController {
+    #[clap(flatten)]
+    controller_args: ControllerArgs,
+
+    #[clap(flatten)]
+    emit_args: EmitArgs,
+}
+
+#[derive(Parser, Debug)]
+struct EmitTxWorker {
+    #[clap(flatten)]
+    cluster_args: ClusterArgs,
+
+    #[clap(flatten)]
+    worker_args: WorkerArgs,
+}
+
 #[derive(Parser, Debug)]
 struct CreateAccounts {
     #[clap(flatten)]
@@ -84,6 +111,30 @@ pub async fn main() -> Result<()> {
             println!("Average rate: {}", stats.rate());
             Ok(())
         },
+        TxnEmitterCommand::EmitTxController(args) => {
+            let stats = run_emission_controller(&args.controller_args, &args.emit_args)
+                .await
+                .context("Coordinated emission failed")?;
+            for (worker_index, worker_stats) in stats.per_worker.iter().enumerate() {
+                if let Some(worker_stats) = worker_stats {
+                    println!("Worker {} rate: {}", worker_index, worker_stats.rate());
+                }
+            }
+            for (worker_index, error) in &stats.failed_workers {
+                println!("Worker {} failed: {}", worker_index, error);
+            }
+            println!("Total stats: {}", stats.total);
+            println!("Average rate: {}", stats.total.rate());
+            Ok(())
+        },
+        TxnEmitterCommand::EmitTxWorker(args) => {
+            let stats = run_emission_worker(&args.cluster_args, &args.worker_args)
+                .await
+                .context("Emission worker failed")?;
+            println!("Worker stats: {}", stats);
+            println!("Worker rate: {}", stats.rate());
+            Ok(())
+        },
         TxnEmitterCommand::CreateAccounts(args) => {
             create_accounts_command(&args.cluster_args, &args.create_accounts_args)
                 .await
@@ -1,7 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::service::RawDataServerWrapper;
+use crate::{
+    cursor_store::{CursorStore, CursorStoreConfig},
+    service::RawDataServerWrapper,
+};
 use anyhow::{bail, Result};
 use aptos_indexer_grpc_server_framework::RunnableConfig;
 use aptos_indexer_grpc_utils::{
@@ -82,6 +85,10 @@ pub struct IndexerGrpcDataServiceConfig {
     /// separate filters that describe each type of txn we want to strip.
     #[serde(default = "IndexerGrpcDataServiceConfig::default_txns_to_strip_filter")]
     pub txns_to_strip_filter: BooleanTransactionFilter,
+    /// If given, the service persists named consumer cursors (i.e., the last version
+    /// committed by each consumer), allowing consumers to resume their streams.
+    #[serde(default)]
+    pub cursor_store_config: Option<CursorStoreConfig>,
 }
 
 impl IndexerGrpcDataServiceConfig {
@@ -95,6 +102,7 @@ impl IndexerGrpcDataServiceConfig {
         enable_cache_compression: bool,
         in_memory_cache_config: InMemoryCacheConfig,
         txns_to_strip_filter: BooleanTransactionFilter,
+        cursor_store_config: Option<CursorStoreConfig>,
     ) -> Self {
         Self {
             data_service_grpc_tls_config,
@@ -108,6 +116,7 @@ impl IndexerGrpcDataServiceConfig {
             enable_cache_compression,
             in_memory_cache_config,
             txns_to_strip_filter,
+            cursor_store_config,
         }
     }
 
@@ -176,6 +185,10 @@ impl RunnableConfig for IndexerGrpcDataServiceConfig {
             )
             .await?;
         println!(">>>> InMemoryCache established");
+        let cursor_store = match &self.cursor_store_config {
+            Some(cursor_store_config) => Some(Arc::new(CursorStore::new(cursor_store_config)?)),
+            None => None,
+        };
         // Add authentication interceptor.
         let server = RawDataServerWrapper::new(
             self.redis_read_replica_address.clone(),
@@ -184,6 +197,7 @@ impl RunnableConfig for IndexerGrpcDataServiceConfig {
             self.txns_to_strip_filter.clone(),
             cache_storage_format,
             Arc::new(in_memory_cache),
+            cursor_store,
         )?;
         let svc = aptos_protos::indexer::v1::raw_data_server::RawDataServer::new(server)
             .send_compressed(CompressionEncoding::Zstd)
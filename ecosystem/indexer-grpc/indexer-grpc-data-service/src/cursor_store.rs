@@ -0,0 +1,279 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use tokio::sync::Mutex;
+
+/// Consumer cursors (owner -> consumer id -> last committed version)
+type Cursors = BTreeMap<String, BTreeMap<String, u64>>;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CursorStoreConfig {
+    /// The file in which consumer cursors (owner -> consumer id -> last committed version) are persisted.
+    pub cursor_store_path: PathBuf,
+}
+
+/// The reasons a cursor commit can be rejected.
+#[derive(Debug)]
+pub enum CursorCommitError {
+    /// The committed version would move the consumer's cursor backwards.
+    VersionRegression {
+        committed_version: u64,
+        requested_version: u64,
+    },
+    /// The cursor could not be persisted.
+    PersistenceFailure(anyhow::Error),
+}
+
+/// A simple file-backed store of named consumer cursors. This allows consumers
+/// to resume their streams after a restart without maintaining their own
+/// checkpoint store. Cursors are namespaced by their owner (i.e., the authenticated
+/// identity of the request), so consumers cannot read or move each other's cursors.
+/// All cursors are kept in memory and the entire file is rewritten on every commit
+/// (the number of consumers is expected to be small).
+#[derive(Debug)]
+pub struct CursorStore {
+    cursor_store_path: PathBuf,
+    cursors: Mutex<Cursors>,
+}
+
+impl CursorStore {
+    /// Creates a new cursor store, loading any cursors previously persisted at the given path
+    pub fn new(config: &CursorStoreConfig) -> Result<Self> {
+        let cursor_store_path = config.cursor_store_path.clone();
+        let cursors = if cursor_store_path.exists() {
+            let contents = std::fs::read(&cursor_store_path).with_context(|| {
+                format!("Failed to read cursor store file {:?}", cursor_store_path)
+            })?;
+            serde_json::from_slice(&contents).with_context(|| {
+                format!("Failed to parse cursor store file {:?}", cursor_store_path)
+            })?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            cursor_store_path,
+            cursors: Mutex::new(cursors),
+        })
+    }
+
+    /// Returns the last committed version for the given owner and consumer (if any)
+    pub async fn get_committed_version(&self, owner: &str, consumer_id: &str) -> Option<u64> {
+        self.cursors
+            .lock()
+            .await
+            .get(owner)
+            .and_then(|consumers| consumers.get(consumer_id))
+            .copied()
+    }
+
+    /// Commits the given version for the owner's consumer and persists all cursors.
+    /// Committed versions must never move backwards.
+    pub async fn commit_version(
+        &self,
+        owner: &str,
+        consumer_id: &str,
+        version: u64,
+    ) -> Result<(), CursorCommitError> {
+        // Note: the lock is held while persisting, to ensure commits are written in order
+        let mut cursors = self.cursors.lock().await;
+        let committed_version = cursors
+            .get(owner)
+            .and_then(|consumers| consumers.get(consumer_id))
+            .copied();
+        if let Some(committed_version) = committed_version {
+            if version < committed_version {
+                return Err(CursorCommitError::VersionRegression {
+                    committed_version,
+                    requested_version: version,
+                });
+            }
+        }
+
+        // Persist the updated cursors (off the async runtime) before updating the in-memory state
+        let mut updated_cursors = cursors.clone();
+        updated_cursors
+            .entry(owner.to_string())
+            .or_default()
+            .insert(consumer_id.to_string(), version);
+        let cursor_store_path = self.cursor_store_path.clone();
+        let cursors_to_persist = updated_cursors.clone();
+        tokio::task::spawn_blocking(move || {
+            persist_cursors(&cursor_store_path, &cursors_to_persist)
+        })
+        .await
+        .map_err(|error| CursorCommitError::PersistenceFailure(error.into()))?
+        .map_err(CursorCommitError::PersistenceFailure)?;
+        *cursors = updated_cursors;
+
+        Ok(())
+    }
+}
+
+/// Atomically and durably writes the cursors to the given path (by writing to
+/// a temporary file, syncing it and renaming it over the existing file).
+fn persist_cursors(cursor_store_path: &Path, cursors: &Cursors) -> Result<()> {
+    let contents = serde_json::to_vec_pretty(cursors)?;
+    let temp_path = cursor_store_path.with_extension("tmp");
+    let mut temp_file = File::create(&temp_path)
+        .with_context(|| format!("Failed to create cursor store file {:?}", temp_path))?;
+    temp_file
+        .write_all(&contents)
+        .and_then(|_| temp_file.sync_all())
+        .with_context(|| format!("Failed to write cursor store file {:?}", temp_path))?;
+    std::fs::rename(&temp_path, cursor_store_path).with_context(|| {
+        format!(
+            "Failed to move cursor store file {:?} to {:?}",
+            temp_path, cursor_store_path
+        )
+    })?;
+
+    // Sync the parent directory so that the rename itself is durable
+    if let Some(parent) = cursor_store_path.parent() {
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        File::open(parent)
+            .and_then(|directory| directory.sync_all())
+            .with_context(|| format!("Failed to sync cursor store directory {:?}", parent))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_cursor_store_commit_and_reload() {
+        let dir = tempdir().unwrap();
+        let config = CursorStoreConfig {
+            cursor_store_path: dir.path().join("cursors.json"),
+        };
+
+        // Commit progress for two consumers
+        let cursor_store = CursorStore::new(&config).unwrap();
+        assert_eq!(
+            cursor_store
+                .get_committed_version("owner", "consumer_1")
+                .await,
+            None
+        );
+        cursor_store
+            .commit_version("owner", "consumer_1", 100)
+            .await
+            .unwrap();
+        cursor_store
+            .commit_version("owner", "consumer_2", 5)
+            .await
+            .unwrap();
+        cursor_store
+            .commit_version("owner", "consumer_1", 150)
+            .await
+            .unwrap();
+
+        // Verify that the cursors survive a restart
+        let cursor_store = CursorStore::new(&config).unwrap();
+        assert_eq!(
+            cursor_store
+                .get_committed_version("owner", "consumer_1")
+                .await,
+            Some(150)
+        );
+        assert_eq!(
+            cursor_store
+                .get_committed_version("owner", "consumer_2")
+                .await,
+            Some(5)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cursor_store_isolates_owners() {
+        let dir = tempdir().unwrap();
+        let config = CursorStoreConfig {
+            cursor_store_path: dir.path().join("cursors.json"),
+        };
+
+        // Commit progress for the same consumer id under two owners
+        let cursor_store = CursorStore::new(&config).unwrap();
+        cursor_store
+            .commit_version("owner_1", "consumer", 100)
+            .await
+            .unwrap();
+        cursor_store
+            .commit_version("owner_2", "consumer", 5)
+            .await
+            .unwrap();
+
+        // Verify that each owner only sees (and moves) its own cursor
+        assert_eq!(
+            cursor_store
+                .get_committed_version("owner_1", "consumer")
+                .await,
+            Some(100)
+        );
+        assert_eq!(
+            cursor_store
+                .get_committed_version("owner_2", "consumer")
+                .await,
+            Some(5)
+        );
+        assert_eq!(
+            cursor_store
+                .get_committed_version("owner_3", "consumer")
+                .await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cursor_store_rejects_regression() {
+        let dir = tempdir().unwrap();
+        let config = CursorStoreConfig {
+            cursor_store_path: dir.path().join("cursors.json"),
+        };
+
+        // Commit a version and verify that moving backwards is rejected
+        let cursor_store = CursorStore::new(&config).unwrap();
+        cursor_store
+            .commit_version("owner", "consumer", 100)
+            .await
+            .unwrap();
+        let error = cursor_store
+            .commit_version("owner", "consumer", 99)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            CursorCommitError::VersionRegression {
+                committed_version: 100,
+                requested_version: 99,
+            }
+        ));
+
+        // Verify the cursor is unchanged (and that re-committing the same version is allowed)
+        assert_eq!(
+            cursor_store
+                .get_committed_version("owner", "consumer")
+                .await,
+            Some(100)
+        );
+        cursor_store
+            .commit_version("owner", "consumer", 100)
+            .await
+            .unwrap();
+    }
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod config;
+mod cursor_store;
 mod metrics;
 mod service;
 
 pub use config::{IndexerGrpcDataServiceConfig, NonTlsConfig, SERVER_NAME};
+pub use cursor_store::CursorStoreConfig;
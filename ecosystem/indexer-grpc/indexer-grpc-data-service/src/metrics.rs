@@ -162,3 +162,13 @@ pub static NUM_TRANSACTIONS_STRIPPED: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Latest version committed by each named consumer (see `CommitProgress`).
+pub static CONSUMER_COMMITTED_VERSION: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_grpc_data_service_consumer_committed_version",
+        "Latest version committed by each named consumer",
+        &["owner", "consumer_id"],
+    )
+    .unwrap()
+});
@@ -1,11 +1,15 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::metrics::{
-    BYTES_READY_TO_TRANSFER_FROM_SERVER, BYTES_READY_TO_TRANSFER_FROM_SERVER_AFTER_STRIPPING,
-    CONNECTION_COUNT, ERROR_COUNT, LATEST_PROCESSED_VERSION_PER_PROCESSOR,
-    NUM_TRANSACTIONS_STRIPPED, PROCESSED_LATENCY_IN_SECS_PER_PROCESSOR,
-    PROCESSED_VERSIONS_COUNT_PER_PROCESSOR, SHORT_CONNECTION_COUNT,
+use crate::{
+    cursor_store::{CursorCommitError, CursorStore},
+    metrics::{
+        BYTES_READY_TO_TRANSFER_FROM_SERVER, BYTES_READY_TO_TRANSFER_FROM_SERVER_AFTER_STRIPPING,
+        CONNECTION_COUNT, CONSUMER_COMMITTED_VERSION, ERROR_COUNT,
+        LATEST_PROCESSED_VERSION_PER_PROCESSOR, NUM_TRANSACTIONS_STRIPPED,
+        PROCESSED_LATENCY_IN_SECS_PER_PROCESSOR, PROCESSED_VERSIONS_COUNT_PER_PROCESSOR,
+        SHORT_CONNECTION_COUNT,
+    },
 };
 use anyhow::{Context, Result};
 use aptos_indexer_grpc_utils::{
@@ -26,7 +30,10 @@ use aptos_indexer_grpc_utils::{
 };
 use aptos_moving_average::MovingAverage;
 use aptos_protos::{
-    indexer::v1::{raw_data_server::RawData, GetTransactionsRequest, TransactionsResponse},
+    indexer::v1::{
+        raw_data_server::RawData, CommitProgressRequest, CommitProgressResponse,
+        GetProgressRequest, GetProgressResponse, GetTransactionsRequest, TransactionsResponse,
+    },
     transaction::v1::{transaction::TxnData, Transaction},
 };
 use aptos_transaction_filter::{BooleanTransactionFilter, Filterable};
@@ -82,6 +89,7 @@ pub struct RawDataServerWrapper {
     pub txns_to_strip_filter: BooleanTransactionFilter,
     pub cache_storage_format: StorageFormat,
     in_memory_cache: Arc<InMemoryCache>,
+    cursor_store: Option<Arc<CursorStore>>,
 }
 
 // Exclude in_memory-cache
@@ -96,6 +104,7 @@ impl std::fmt::Debug for RawDataServerWrapper {
             )
             .field("txns_to_strip_filter", &self.txns_to_strip_filter)
             .field("cache_storage_format", &self.cache_storage_format)
+            .field("cursor_store", &self.cursor_store)
             .finish()
     }
 }
@@ -108,6 +117,7 @@ impl RawDataServerWrapper {
         txns_to_strip_filter: BooleanTransactionFilter,
         cache_storage_format: StorageFormat,
         in_memory_cache: Arc<InMemoryCache>,
+        cursor_store: Option<Arc<CursorStore>>,
     ) -> anyhow::Result<Self> {
         Ok(Self {
             redis_client: Arc::new(
@@ -120,8 +130,80 @@ impl RawDataServerWrapper {
             txns_to_strip_filter,
             cache_storage_format,
             in_memory_cache,
+            cursor_store,
         })
     }
+
+    /// Returns the cursor store, or an error if consumer cursors are not enabled
+    fn get_cursor_store(&self) -> Result<&Arc<CursorStore>, Status> {
+        self.cursor_store.as_ref().ok_or_else(|| {
+            Status::failed_precondition("Consumer cursors are not enabled on this data service.")
+        })
+    }
+
+    /// Determines the starting version for a request. If a consumer id is given,
+    /// the stream resumes after the consumer's last committed version, and an
+    /// explicit starting version may not skip past it.
+    async fn get_starting_version(
+        &self,
+        request: &GetTransactionsRequest,
+        request_metadata: &IndexerGrpcRequestMetadata,
+    ) -> Result<u64, Status> {
+        let committed_version = match &request.consumer_id {
+            Some(consumer_id) => {
+                self.get_cursor_store()?
+                    .get_committed_version(
+                        get_cursor_owner(request_metadata)?,
+                        validate_consumer_id(consumer_id)?,
+                    )
+                    .await
+            },
+            None => None,
+        };
+
+        let starting_version = match (request.starting_version, committed_version) {
+            (Some(starting_version), Some(committed_version)) => {
+                if starting_version > committed_version.saturating_add(1) {
+                    return Err(Status::invalid_argument(format!(
+                        "Starting version {} would skip versions after the last committed version {}.",
+                        starting_version, committed_version
+                    )));
+                }
+                starting_version
+            },
+            (Some(starting_version), None) => starting_version,
+            // Resume after the last committed version
+            (None, Some(committed_version)) => committed_version.saturating_add(1),
+            // Live mode if starting version isn't specified
+            (None, None) => self
+                .in_memory_cache
+                .latest_version()
+                .await
+                .saturating_sub(1),
+        };
+        Ok(starting_version)
+    }
+}
+
+/// Returns the owner of the consumer cursors accessed by the request, i.e., the
+/// authenticated identity of the request (as set by the API gateway). This
+/// ensures consumers can only read and move their own cursors.
+fn get_cursor_owner(request_metadata: &IndexerGrpcRequestMetadata) -> Result<&str, Status> {
+    let owner = request_metadata.request_identifier.as_str();
+    if owner.is_empty() || owner == "unspecified" {
+        return Err(Status::unauthenticated(
+            "Consumer cursors require an authenticated request identity.",
+        ));
+    }
+    Ok(owner)
+}
+
+/// Verifies that the consumer id is not empty
+fn validate_consumer_id(consumer_id: &str) -> Result<&str, Status> {
+    if consumer_id.is_empty() {
+        return Err(Status::invalid_argument("Consumer id must not be empty."));
+    }
+    Ok(consumer_id)
 }
 
 /// Enum to represent the status of the data fetching overall.
@@ -163,15 +245,9 @@ impl RawData for RawDataServerWrapper {
 
         // Response channel to stream the data to the client.
         let (tx, rx) = channel(self.data_service_response_channel_size);
-        let current_version = match &request.starting_version {
-            Some(version) => *version,
-            // Live mode if starting version isn't specified
-            None => self
-                .in_memory_cache
-                .latest_version()
-                .await
-                .saturating_sub(1),
-        };
+        let current_version = self
+            .get_starting_version(&request, &request_metadata)
+            .await?;
 
         let file_store_operator: Box<dyn FileStoreOperator> = self.file_store_config.create();
         let file_store_operator = Arc::new(file_store_operator);
@@ -223,6 +299,79 @@ impl RawData for RawDataServerWrapper {
         );
         Ok(response)
     }
+
+    /// Persists the last version processed by the given consumer
+    async fn commit_progress(
+        &self,
+        req: Request<CommitProgressRequest>,
+    ) -> Result<Response<CommitProgressResponse>, Status> {
+        let cursor_store = self.get_cursor_store()?;
+        let request_metadata = get_request_metadata(&req)?;
+        let owner = get_cursor_owner(&request_metadata)?;
+        let request = req.into_inner();
+        let consumer_id = validate_consumer_id(request.consumer_id.as_deref().unwrap_or_default())?;
+        let version = request
+            .version
+            .ok_or_else(|| Status::invalid_argument("Version must be specified."))?;
+
+        // Consumers cannot commit versions that the service hasn't served yet
+        let latest_version = self.in_memory_cache.latest_version().await;
+        if version >= latest_version {
+            return Err(Status::invalid_argument(format!(
+                "Version {} is beyond the latest available version {}.",
+                version,
+                latest_version.saturating_sub(1)
+            )));
+        }
+
+        match cursor_store
+            .commit_version(owner, consumer_id, version)
+            .await
+        {
+            Ok(()) => {
+                CONSUMER_COMMITTED_VERSION
+                    .with_label_values(&[owner, consumer_id])
+                    .set(version as i64);
+                Ok(Response::new(CommitProgressResponse {
+                    committed_version: Some(version),
+                }))
+            },
+            Err(CursorCommitError::VersionRegression {
+                committed_version,
+                requested_version,
+            }) => Err(Status::failed_precondition(format!(
+                "Version {} is behind the last committed version {} for consumer {}.",
+                requested_version, committed_version, consumer_id
+            ))),
+            Err(CursorCommitError::PersistenceFailure(error)) => {
+                error!(
+                    owner = owner,
+                    consumer_id = consumer_id,
+                    error = ?error,
+                    "[Data Service] Failed to persist consumer cursor."
+                );
+                ERROR_COUNT
+                    .with_label_values(&["cursor_persistence_failure"])
+                    .inc();
+                Err(Status::internal("Failed to persist consumer progress."))
+            },
+        }
+    }
+
+    /// Returns the last version committed by the given consumer
+    async fn get_progress(
+        &self,
+        req: Request<GetProgressRequest>,
+    ) -> Result<Response<GetProgressResponse>, Status> {
+        let cursor_store = self.get_cursor_store()?;
+        let request_metadata = get_request_metadata(&req)?;
+        let owner = get_cursor_owner(&request_metadata)?;
+        let request = req.into_inner();
+        let consumer_id = validate_consumer_id(request.consumer_id.as_deref().unwrap_or_default())?;
+        Ok(Response::new(GetProgressResponse {
+            committed_version: cursor_store.get_committed_version(owner, consumer_id).await,
+        }))
+    }
 }
 
 enum DataFetchSubTaskResult {
@@ -857,9 +1006,7 @@ async fn data_fetch_error_handling(err: anyhow::Error, current_version: u64, cha
 }
 
 /// Gets the request metadata. Useful for logging.
-fn get_request_metadata(
-    req: &Request<GetTransactionsRequest>,
-) -> tonic::Result<IndexerGrpcRequestMetadata> {
+fn get_request_metadata<T>(req: &Request<T>) -> tonic::Result<IndexerGrpcRequestMetadata> {
     let request_metadata_pairs = vec![
         (
             "request_identifier_type",
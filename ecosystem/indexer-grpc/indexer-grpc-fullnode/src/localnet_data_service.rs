@@ -4,7 +4,10 @@
 use crate::{stream_coordinator::IndexerStreamCoordinator, ServiceContext};
 use aptos_logger::{error, info};
 use aptos_protos::{
-    indexer::v1::{raw_data_server::RawData, GetTransactionsRequest, TransactionsResponse},
+    indexer::v1::{
+        raw_data_server::RawData, CommitProgressRequest, CommitProgressResponse,
+        GetProgressRequest, GetProgressResponse, GetTransactionsRequest, TransactionsResponse,
+    },
     internal::fullnode::v1::transactions_from_node_response,
 };
 use futures::Stream;
@@ -124,4 +127,22 @@ impl RawData for LocalnetDataService {
             Box::pin(output_stream) as Self::GetTransactionsStream
         ))
     }
+
+    async fn commit_progress(
+        &self,
+        _req: Request<CommitProgressRequest>,
+    ) -> Result<Response<CommitProgressResponse>, Status> {
+        Err(Status::unimplemented(
+            "Consumer progress is not persisted by the localnet data service.",
+        ))
+    }
+
+    async fn get_progress(
+        &self,
+        _req: Request<GetProgressRequest>,
+    ) -> Result<Response<GetProgressResponse>, Status> {
+        Err(Status::unimplemented(
+            "Consumer progress is not persisted by the localnet data service.",
+        ))
+    }
 }
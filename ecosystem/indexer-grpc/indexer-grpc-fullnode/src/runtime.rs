@@ -40,7 +40,8 @@ pub fn bootstrap(
         return None;
     }
 
-    let runtime = aptos_runtimes::spawn_named_runtime("indexer-grpc".to_string(), None);
+    let runtime =
+        aptos_runtimes::spawn_supervised_runtime("indexer-grpc".to_string(), "indexer-grpc", None);
 
     let node_config = config.clone();
 
@@ -50,67 +51,74 @@ pub fn bootstrap(
     let processor_batch_size = node_config.indexer_grpc.processor_batch_size;
     let output_batch_size = node_config.indexer_grpc.output_batch_size;
 
-    runtime.spawn(async move {
-        let context = Arc::new(Context::new(
-            chain_id,
-            db,
-            mp_sender,
-            node_config,
-            indexer_reader,
-        ));
-        let service_context = ServiceContext {
-            context: context.clone(),
-            processor_task_count,
-            processor_batch_size,
-            output_batch_size,
-        };
-        // If we are here, we know indexer grpc is enabled.
-        let server = FullnodeDataService {
-            service_context: service_context.clone(),
-        };
-        let localnet_data_server = LocalnetDataService { service_context };
+    // The indexer grpc service is not safety-critical, so it is restarted on panics
+    aptos_runtimes::spawn_supervised(runtime.handle(), "indexer-grpc", move || {
+        let db = db.clone();
+        let mp_sender = mp_sender.clone();
+        let node_config = node_config.clone();
+        let indexer_reader = indexer_reader.clone();
+        async move {
+            let context = Arc::new(Context::new(
+                chain_id,
+                db,
+                mp_sender,
+                node_config,
+                indexer_reader,
+            ));
+            let service_context = ServiceContext {
+                context: context.clone(),
+                processor_task_count,
+                processor_batch_size,
+                output_batch_size,
+            };
+            // If we are here, we know indexer grpc is enabled.
+            let server = FullnodeDataService {
+                service_context: service_context.clone(),
+            };
+            let localnet_data_server = LocalnetDataService { service_context };
 
-        let reflection_service = tonic_reflection::server::Builder::configure()
-            // Note: It is critical that the file descriptor set is registered for every
-            // file that the top level API proto depends on recursively. If you don't,
-            // compilation will still succeed but reflection will fail at runtime.
-            //
-            // TODO: Add a test for this / something in build.rs, this is a big footgun.
-            .register_encoded_file_descriptor_set(INDEXER_V1_FILE_DESCRIPTOR_SET)
-            .register_encoded_file_descriptor_set(TRANSACTION_V1_TESTING_FILE_DESCRIPTOR_SET)
-            .register_encoded_file_descriptor_set(UTIL_TIMESTAMP_FILE_DESCRIPTOR_SET)
-            .build()
-            .expect("Failed to build reflection service");
+            let reflection_service = tonic_reflection::server::Builder::configure()
+                // Note: It is critical that the file descriptor set is registered for every
+                // file that the top level API proto depends on recursively. If you don't,
+                // compilation will still succeed but reflection will fail at runtime.
+                //
+                // TODO: Add a test for this / something in build.rs, this is a big footgun.
+                .register_encoded_file_descriptor_set(INDEXER_V1_FILE_DESCRIPTOR_SET)
+                .register_encoded_file_descriptor_set(TRANSACTION_V1_TESTING_FILE_DESCRIPTOR_SET)
+                .register_encoded_file_descriptor_set(UTIL_TIMESTAMP_FILE_DESCRIPTOR_SET)
+                .build()
+                .expect("Failed to build reflection service");
 
-        let reflection_service_clone = reflection_service.clone();
+            let reflection_service_clone = reflection_service.clone();
 
-        let tonic_server = Server::builder()
-            .http2_keepalive_interval(Some(std::time::Duration::from_secs(60)))
-            .http2_keepalive_timeout(Some(std::time::Duration::from_secs(5)))
-            .add_service(reflection_service_clone);
+            let tonic_server = Server::builder()
+                .http2_keepalive_interval(Some(std::time::Duration::from_secs(60)))
+                .http2_keepalive_timeout(Some(std::time::Duration::from_secs(5)))
+                .add_service(reflection_service_clone);
 
-        let router = match use_data_service_interface {
-            false => {
-                let svc = FullnodeDataServer::new(server)
-                    .send_compressed(CompressionEncoding::Zstd)
-                    .accept_compressed(CompressionEncoding::Zstd)
-                    .accept_compressed(CompressionEncoding::Gzip);
-                tonic_server.add_service(svc)
-            },
-            true => {
-                let svc = RawDataServer::new(localnet_data_server)
-                    .send_compressed(CompressionEncoding::Zstd)
-                    .accept_compressed(CompressionEncoding::Zstd)
-                    .accept_compressed(CompressionEncoding::Gzip);
-                tonic_server.add_service(svc)
-            },
-        };
-        // Make port into a config
-        router
-            .serve(address.to_socket_addrs().unwrap().next().unwrap())
-            .await
-            .unwrap();
-        info!(address = address, "[indexer-grpc] Started GRPC server");
+            let router = match use_data_service_interface {
+                false => {
+                    let svc = FullnodeDataServer::new(server)
+                        .send_compressed(CompressionEncoding::Zstd)
+                        .accept_compressed(CompressionEncoding::Zstd)
+                        .accept_compressed(CompressionEncoding::Gzip);
+                    tonic_server.add_service(svc)
+                },
+                true => {
+                    let svc = RawDataServer::new(localnet_data_server)
+                        .send_compressed(CompressionEncoding::Zstd)
+                        .accept_compressed(CompressionEncoding::Zstd)
+                        .accept_compressed(CompressionEncoding::Gzip);
+                    tonic_server.add_service(svc)
+                },
+            };
+            // Make port into a config
+            router
+                .serve(address.to_socket_addrs().unwrap().next().unwrap())
+                .await
+                .unwrap();
+            info!(address = address, "[indexer-grpc] Started GRPC server");
+        }
     });
     Some(runtime)
 }
@@ -43,7 +43,8 @@ impl InternalIndexerDBService {
                 .expect("Failed to open internal indexer db"),
         );
 
-        let internal_indexer_db_config = InternalIndexerDBConfig::new(false, false, true, 10_000);
+        let internal_indexer_db_config =
+            InternalIndexerDBConfig::new(false, false, true, false, 10_000);
         Some(InternalIndexerDB::new(arc_db, internal_indexer_db_config))
     }
 
@@ -59,11 +60,11 @@ impl InternalIndexerDBService {
             .get_dir_paths()
             .default_root_path()
             .join(INTERNAL_INDEXER_DB);
-        let rocksdb_config = node_config.storage.rocksdb_configs.index_db_config;
+        let rocksdb_config = &node_config.storage.rocksdb_configs.index_db_config;
         let db_path = db_path_buf.as_path();
 
         let arc_db = Arc::new(
-            open_internal_indexer_db(db_path, &rocksdb_config)
+            open_internal_indexer_db(db_path, rocksdb_config)
                 .expect("Failed to open internal indexer db"),
         );
         Some(InternalIndexerDB::new(
@@ -129,6 +130,17 @@ impl InternalIndexerDBService {
             }
         }
 
+        if node_config.indexer_db_config.enable_storage_usage() {
+            let storage_usage_start_version = self
+                .db_indexer
+                .indexer_db
+                .get_storage_usage_version()?
+                .map_or(0, |v| v + 1);
+            if start_version != storage_usage_start_version {
+                panic!("Cannot start storage usage indexer because the progress doesn't match.");
+            }
+        }
+
         Ok(start_version)
     }
 
@@ -107,6 +107,14 @@ pub trait ChunkExecutorTrait: Send + Sync {
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
     ) -> Result<()>;
 
+    /// Re-executes the transactions in the given transaction output chunk (on top of the latest
+    /// enqueued state) and verifies that the resulting outputs match the outputs in the chunk.
+    /// This doesn't modify any state, so it must be called before the chunk is enqueued.
+    fn audit_chunk_by_execution(
+        &self,
+        txn_output_list_with_proof: &TransactionOutputListWithProof,
+    ) -> Result<()>;
+
     /// As a separate stage, calculate the transaction accumulator changes, prepare for db commission.
     fn update_ledger(&self) -> Result<()>;
 
@@ -145,6 +145,19 @@ impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
         })
     }
 
+    fn audit_chunk_by_execution(
+        &self,
+        txn_output_list_with_proof: &TransactionOutputListWithProof,
+    ) -> Result<()> {
+        let _guard = CONCURRENCY_GAUGE.concurrency_with(&["chunk", "audit_by_execution"]);
+
+        // Audits don't modify any state, so failures are always left to the caller
+        // (i.e., we don't panic even if there's a pending pre-committed ledger).
+        let locked = self.inner.read();
+        let inner = locked.as_ref().expect("not reset");
+        inner.audit_chunk_by_execution(txn_output_list_with_proof)
+    }
+
     fn update_ledger(&self) -> Result<()> {
         let _guard = CONCURRENCY_GAUGE.concurrency_with(&["chunk", "update_ledger"]);
 
@@ -459,6 +472,63 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         Ok(())
     }
 
+    fn audit_chunk_by_execution(
+        &self,
+        txn_output_list_with_proof: &TransactionOutputListWithProof,
+    ) -> Result<()> {
+        let _timer = APTOS_CHUNK_EXECUTOR_OTHER_SECONDS.timer_with(&["audit_chunk_by_execution"]);
+
+        let num_txns = txn_output_list_with_proof.transactions_and_outputs.len();
+        ensure!(num_txns != 0, "Empty transaction list!");
+        let first_version_in_request = txn_output_list_with_proof
+            .first_transaction_output_version
+            .ok_or_else(|| anyhow!("Non-empty chunk with first_version == None."))?;
+        let parent_state = self.commit_queue.lock().latest_state();
+        ensure!(
+            first_version_in_request == parent_state.next_version(),
+            "Unexpected chunk. version in request: {}, current_version: {:?}",
+            first_version_in_request,
+            parent_state.current_version,
+        );
+
+        // Re-execute the transactions on top of the parent state.
+        let txns = txn_output_list_with_proof
+            .transactions_and_outputs
+            .iter()
+            .map(|(txn, _)| txn.clone().into())
+            .collect::<Vec<SignatureVerifiedTransaction>>();
+        let state_view = self.latest_state_view(&parent_state)?;
+        // State sync executor shouldn't have block gas limit.
+        let chunk_output = ChunkOutput::by_transaction_execution::<V>(
+            txns.into(),
+            state_view,
+            BlockExecutorConfigFromOnchain::new_no_block_limit(),
+        )?;
+        ensure!(
+            chunk_output.transaction_outputs.len() == num_txns,
+            "Unexpected number of executed outputs. expected: {}, actual: {}",
+            num_txns,
+            chunk_output.transaction_outputs.len(),
+        );
+
+        // Verify the executed outputs against the outputs in the chunk.
+        for (version, executed_output, (_, expected_output), txn_info) in multizip((
+            first_version_in_request..,
+            chunk_output.transaction_outputs.iter(),
+            txn_output_list_with_proof.transactions_and_outputs.iter(),
+            txn_output_list_with_proof.proof.transaction_infos.iter(),
+        )) {
+            executed_output.ensure_match_transaction_info(
+                version,
+                txn_info,
+                Some(expected_output.write_set()),
+                Some(expected_output.events()),
+            )?;
+        }
+
+        Ok(())
+    }
+
     pub fn update_ledger(&self) -> Result<()> {
         let _timer = APTOS_CHUNK_EXECUTOR_OTHER_SECONDS.timer_with(&["chunk_update_ledger_total"]);
 
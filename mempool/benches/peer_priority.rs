@@ -0,0 +1,77 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::{
+    config::{MempoolConfig, NodeType},
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_infallible::RwLock;
+use aptos_mempool::PrioritizedPeersState;
+use aptos_time_service::TimeService;
+use aptos_types::PeerId;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// The numbers of peers to benchmark against
+const NUM_PEERS: &[usize] = &[10, 100, 500, 1000];
+
+/// Creates a prioritized peers state with the given number of (public) peers
+fn create_prioritized_peers_state(num_peers: usize) -> (PrioritizedPeersState, Vec<PeerNetworkId>) {
+    let peers: Vec<_> = (0..num_peers)
+        .map(|_| PeerNetworkId::new(NetworkId::Public, PeerId::random()))
+        .collect();
+
+    let mut prioritized_peers_state = PrioritizedPeersState::new(
+        MempoolConfig::default(),
+        NodeType::PublicFullnode,
+        TimeService::mock(),
+    );
+    let peers_and_metadata = peers.iter().map(|peer| (*peer, None)).collect();
+    prioritized_peers_state.update_prioritized_peers(peers_and_metadata, 0, 0);
+
+    (prioritized_peers_state, peers)
+}
+
+/// Compares the priority lookups (for all peers) against a linear scan of
+/// the peers (i.e., the previous implementation of the lookups). Both take
+/// a read lock per lookup, as the prioritized peers are shared behind a lock.
+fn peer_priority_lookups(c: &mut Criterion) {
+    let mut group = c.benchmark_group("peer_priority_lookups");
+    for num_peers in NUM_PEERS {
+        let (prioritized_peers_state, peers) = create_prioritized_peers_state(*num_peers);
+        let prioritized_peers = RwLock::new(peers.clone());
+
+        group.bench_with_input(
+            BenchmarkId::new("index_map", num_peers),
+            &peers,
+            |b, peers| {
+                b.iter(|| {
+                    for peer in peers {
+                        black_box(prioritized_peers_state.get_peer_priority(peer));
+                    }
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("linear_scan", num_peers),
+            &peers,
+            |b, peers| {
+                b.iter(|| {
+                    for peer in peers {
+                        black_box(
+                            prioritized_peers
+                                .read()
+                                .iter()
+                                .position(|prioritized_peer| prioritized_peer == peer)
+                                .unwrap_or(usize::MAX),
+                        );
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, peer_priority_lookups);
+criterion_main!(benches);
@@ -7,19 +7,21 @@ use crate::core_mempool::transaction::{MempoolTransaction, SequenceInfo, Timelin
 use crate::{
     counters,
     logging::{LogEntry, LogSchema},
-    shared_mempool::types::{MultiBucketTimelineIndexIds, TimelineIndexIdentifier},
+    shared_mempool::types::{
+        MultiBucketTimelineIndexIds, PendingTransactionsCursor, TimelineIndexIdentifier,
+    },
 };
 use aptos_consensus_types::common::TransactionSummary;
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
-use aptos_types::account_address::AccountAddress;
+use aptos_types::{account_address::AccountAddress, vm_status::DiscardedVMStatus};
 use rand::seq::SliceRandom;
 use std::{
     cmp::Ordering,
     collections::{btree_set::Iter, BTreeMap, BTreeSet, HashMap},
     hash::Hash,
     iter::Rev,
-    ops::Bound,
+    ops::{Bound, Range},
     time::{Duration, Instant, SystemTime},
 };
 
@@ -175,6 +177,31 @@ impl TTLIndex {
         self.data.iter()
     }
 
+    /// Returns the keys of the transactions that expire in the given time range
+    /// (i.e., at or after `start`, but before `end`)
+    pub(crate) fn iter_expiring_between(
+        &self,
+        start: Duration,
+        end: Duration,
+    ) -> impl Iterator<Item = &TTLOrderingKey> {
+        let start_key = TTLOrderingKey {
+            expiration_time: start.min(end),
+            address: AccountAddress::ZERO,
+            sequence_number: 0,
+        };
+        let end_key = TTLOrderingKey {
+            expiration_time: end,
+            address: AccountAddress::ZERO,
+            sequence_number: 0,
+        };
+        self.data.range(start_key..end_key)
+    }
+
+    /// Returns the expiration time of the given transaction (as ordered by this index)
+    pub(crate) fn get_expiration_time(&self, txn: &MempoolTransaction) -> Duration {
+        (self.get_expiration_time)(txn)
+    }
+
     pub(crate) fn size(&self) -> usize {
         self.data.len()
     }
@@ -202,6 +229,61 @@ impl Ord for TTLOrderingKey {
     }
 }
 
+/// InsertionTimeIndex orders all transactions by the time they were inserted into Mempool
+/// (with ties broken by sender and sequence number). It's used to page through the contents
+/// of Mempool, where the key of each transaction serves as the cursor.
+pub struct InsertionTimeIndex {
+    data: BTreeSet<PendingTransactionsCursor>,
+}
+
+impl InsertionTimeIndex {
+    pub(crate) fn new() -> Self {
+        Self {
+            data: BTreeSet::new(),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
+        self.data.insert(Self::make_key(txn));
+    }
+
+    pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
+        self.data.remove(&Self::make_key(txn));
+    }
+
+    /// Returns the keys of all transactions at or after the given key (in insertion order)
+    pub(crate) fn iter_from(
+        &self,
+        start: Option<&PendingTransactionsCursor>,
+    ) -> impl Iterator<Item = &PendingTransactionsCursor> {
+        let start = start.map_or(Bound::Unbounded, Bound::Included);
+        self.data.range((start, Bound::Unbounded))
+    }
+
+    /// Returns the keys of all transactions, most recently inserted first
+    pub(crate) fn iter_newest_first(&self) -> impl Iterator<Item = &PendingTransactionsCursor> {
+        self.data.iter().rev()
+    }
+
+    pub(crate) fn make_key(txn: &MempoolTransaction) -> PendingTransactionsCursor {
+        let insertion_time_usecs = txn
+            .insertion_info
+            .insertion_time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        PendingTransactionsCursor {
+            insertion_time_usecs,
+            sender: txn.get_sender(),
+            sequence_number: txn.sequence_info.transaction_sequence_number,
+        }
+    }
+
+    pub(crate) fn size(&self) -> usize {
+        self.data.len()
+    }
+}
+
 /// TimelineIndex is an ordered log of all transactions that are "ready" for broadcast.
 /// We only add a transaction to the index if it has a chance to be included in the next consensus
 /// block (which means its status is != NotReady or its sequential to another "ready" transaction).
@@ -321,29 +403,78 @@ impl MultiBucketTimelineIndex {
         timeline_id: &MultiBucketTimelineIndexIds,
         count: usize,
         before: Option<Instant>,
+    ) -> Vec<Vec<(AccountAddress, u64)>> {
+        self.read_buckets(timeline_id, 0..self.timelines.len(), count, before)
+    }
+
+    /// Read all transactions from the timeline since <timeline_id>, composing the batch
+    /// from two lanes: the high-fee lane (i.e., the buckets starting at `first_high_fee_bucket`)
+    /// and the normal lane (i.e., all lower buckets). At most `high_fee_count` transactions
+    /// are read from the high-fee lane, unless the normal lane has too few transactions to
+    /// fill the rest of the batch (and vice versa). At most `count` transactions will be returned.
+    pub(crate) fn read_timeline_with_lanes(
+        &self,
+        timeline_id: &MultiBucketTimelineIndexIds,
+        count: usize,
+        before: Option<Instant>,
+        first_high_fee_bucket: usize,
+        high_fee_count: usize,
+    ) -> Vec<Vec<(AccountAddress, u64)>> {
+        let first_high_fee_bucket = first_high_fee_bucket.min(self.timelines.len());
+        let mut normal_txns =
+            self.read_buckets(timeline_id, 0..first_high_fee_bucket, count, before);
+        let mut high_fee_txns = self.read_buckets(
+            timeline_id,
+            first_high_fee_bucket..self.timelines.len(),
+            count,
+            before,
+        );
+
+        // Split the batch between the lanes, giving any unused share to the other lane
+        let num_normal_txns: usize = normal_txns.iter().map(Vec::len).sum();
+        let num_high_fee_txns: usize = high_fee_txns.iter().map(Vec::len).sum();
+        let reserved_high_fee_txns = num_high_fee_txns.min(high_fee_count).min(count);
+        let num_normal_txns = num_normal_txns.min(count - reserved_high_fee_txns);
+        let num_high_fee_txns = num_high_fee_txns.min(count - num_normal_txns);
+        truncate_buckets(&mut normal_txns, num_normal_txns);
+        truncate_buckets(&mut high_fee_txns, num_high_fee_txns);
+
+        normal_txns.append(&mut high_fee_txns);
+        normal_txns
+    }
+
+    /// Read all transactions from the given range of buckets since <timeline_id>, starting
+    /// with the highest bucket. At most `count` transactions will be returned (one list per
+    /// bucket in the range, in bucket order).
+    fn read_buckets(
+        &self,
+        timeline_id: &MultiBucketTimelineIndexIds,
+        buckets: Range<usize>,
+        count: usize,
+        before: Option<Instant>,
     ) -> Vec<Vec<(AccountAddress, u64)>> {
         assert!(timeline_id.id_per_bucket.len() == self.bucket_mins.len());
 
+        let num_buckets = buckets.len();
         let mut added = 0;
         let mut returned = vec![];
-        for (timeline, &timeline_id) in self
-            .timelines
+        for (timeline, &timeline_id) in self.timelines[buckets.clone()]
             .iter()
-            .zip(timeline_id.id_per_bucket.iter())
+            .zip(timeline_id.id_per_bucket[buckets].iter())
             .rev()
         {
-            let txns = timeline.read_timeline(timeline_id, count - added, before);
-            added += txns.len();
-            returned.push(txns);
-
             if added == count {
                 break;
             }
+
+            let txns = timeline.read_timeline(timeline_id, count - added, before);
+            added += txns.len();
+            returned.push(txns);
         }
-        while returned.len() < self.timelines.len() {
+        while returned.len() < num_buckets {
             returned.push(vec![]);
         }
-        returned.iter().rev().cloned().collect()
+        returned.into_iter().rev().collect()
     }
 
     /// Read transactions from the timeline from `start_id` (exclusive) to `end_id` (inclusive).
@@ -409,6 +540,16 @@ impl MultiBucketTimelineIndex {
     }
 }
 
+/// Truncates the given (per bucket) transaction lists to at most `count` transactions
+/// in total, keeping the transactions of the highest buckets.
+fn truncate_buckets(buckets: &mut [Vec<(AccountAddress, u64)>], count: usize) {
+    let mut remaining = count;
+    for bucket in buckets.iter_mut().rev() {
+        bucket.truncate(remaining);
+        remaining -= bucket.len();
+    }
+}
+
 /// ParkingLotIndex keeps track of "not_ready" transactions, e.g., transactions that
 /// can't be included in the next block because their sequence number is too high.
 /// We keep a separate index to be able to efficiently evict them when Mempool is full.
@@ -515,6 +656,63 @@ impl ParkingLotIndex {
     }
 }
 
+/// RejectedTransactionIndex retains the rejection reasons of the most recently rejected
+/// transactions, so that their status can still be reported after they've been removed.
+/// The oldest entries are evicted once the index reaches its capacity.
+///
+/// Entries are keyed by an insertion number, so that both evictions and removals
+/// are logarithmic in the size of the index.
+pub struct RejectedTransactionIndex {
+    // The rejection reason and insertion number of each transaction
+    reasons: HashMap<HashValue, (DiscardedVMStatus, u64)>,
+    // The transaction hashes ordered by insertion number
+    insertion_order: BTreeMap<u64, HashValue>,
+    next_insertion_number: u64,
+    capacity: usize,
+}
+
+impl RejectedTransactionIndex {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            reasons: HashMap::new(),
+            insertion_order: BTreeMap::new(),
+            next_insertion_number: 0,
+            capacity,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, hash: HashValue, reason: DiscardedVMStatus) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Some((existing_reason, _)) = self.reasons.get_mut(&hash) {
+            *existing_reason = reason;
+            return;
+        }
+
+        let insertion_number = self.next_insertion_number;
+        self.next_insertion_number += 1;
+        self.reasons.insert(hash, (reason, insertion_number));
+        self.insertion_order.insert(insertion_number, hash);
+        while self.insertion_order.len() > self.capacity {
+            if let Some((_, evicted_hash)) = self.insertion_order.pop_first() {
+                self.reasons.remove(&evicted_hash);
+            }
+        }
+    }
+
+    /// Removes the entry for the given hash (e.g., if the transaction is resubmitted)
+    pub(crate) fn remove(&mut self, hash: &HashValue) {
+        if let Some((_, insertion_number)) = self.reasons.remove(hash) {
+            self.insertion_order.remove(&insertion_number);
+        }
+    }
+
+    pub(crate) fn get(&self, hash: &HashValue) -> Option<DiscardedVMStatus> {
+        self.reasons.get(hash).map(|(reason, _)| *reason)
+    }
+}
+
 /// Logical pointer to `MempoolTransaction`.
 /// Includes Account's address and transaction sequence number.
 pub type TxnPointer = TransactionSummary;
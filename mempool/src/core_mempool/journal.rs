@@ -0,0 +1,312 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! An append-only, on-disk journal of the transactions accepted into core mempool.
+//! The journal allows pending transactions to survive a node restart (e.g., during
+//! an upgrade): on startup, the journaled transactions are reloaded and re-validated.
+//!
+//! Each record is a little-endian `u32` length prefix, followed by the BCS encoded
+//! transaction. Transactions that leave mempool are not recorded; instead, the journal
+//! is periodically compacted (i.e., rewritten to hold only the transactions currently
+//! in mempool). Records are written without syncing, so the journal survives process
+//! restarts, but the most recent records may be lost if the host itself crashes.
+//!
+//! All file I/O is performed by a dedicated writer thread, so that adding transactions
+//! to mempool never blocks on the disk. If the writer falls behind, new records are
+//! dropped (and the corresponding transactions are simply not reloaded on restart).
+use crate::{
+    counters,
+    logging::{LogEntry, LogSchema},
+};
+use anyhow::{Context, Result};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::transaction::SignedTransaction;
+use std::{
+    collections::HashSet,
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+/// The size (in bytes) of the length prefix of each journal record
+const RECORD_LENGTH_PREFIX_BYTES: usize = 4;
+
+/// The minimum number of records in the journal before compaction is considered
+const MIN_RECORDS_FOR_COMPACTION: usize = 1_000;
+
+/// The maximum number of journal commands waiting for the writer thread
+const MAX_PENDING_JOURNAL_COMMANDS: usize = 10_000;
+
+/// The commands processed (in order) by the journal writer thread
+enum JournalCommand {
+    /// Appends the transaction to the journal
+    Append(SignedTransaction),
+    /// Marks the start of a compaction. All records appended before the marker are
+    /// candidates for removal by the following `Compact` command (records appended
+    /// after it are always retained).
+    StartCompaction,
+    /// Drops all candidate records whose transactions are not in the given set of
+    /// (committed) transaction hashes, i.e., that have since left mempool.
+    Compact(HashSet<HashValue>),
+}
+
+/// The handle to the journal writer thread. Dropping the handle flushes all
+/// pending commands to the journal and stops the writer thread.
+pub(crate) struct TransactionJournal {
+    command_sender: Option<SyncSender<JournalCommand>>,
+    compaction_lock: Mutex<()>, // Ensures compactions are never interleaved
+    num_records: Arc<AtomicUsize>, // The number of records written to the journal file
+    writer_thread: Option<JoinHandle<()>>,
+}
+
+impl TransactionJournal {
+    /// Opens the journal at the given path (creating it if it doesn't exist), and
+    /// returns the journal along with all transactions previously recorded in it.
+    /// A truncated or corrupt record (e.g., due to a crash during a write) ends the
+    /// journal: it, and any records after it, are discarded.
+    pub(crate) fn open(journal_path: &Path) -> Result<(Self, Vec<SignedTransaction>)> {
+        let journaled_transactions = if journal_path.exists() {
+            decode_records(&read_journal(journal_path)?)
+        } else {
+            vec![]
+        };
+
+        // Rewrite the journal, so that any discarded records are dropped from the file
+        let mut contents = vec![];
+        for transaction in &journaled_transactions {
+            contents.extend(encode_record(transaction)?);
+        }
+        replace_journal(journal_path, &contents)?;
+        let writer = JournalWriter {
+            journal_path: journal_path.to_path_buf(),
+            journal_file: create_journal_file(journal_path)?,
+            num_records: Arc::new(AtomicUsize::new(journaled_transactions.len())),
+            num_compaction_candidates: None,
+        };
+
+        // Start the writer thread
+        let num_records = writer.num_records.clone();
+        let (command_sender, command_receiver) = mpsc::sync_channel(MAX_PENDING_JOURNAL_COMMANDS);
+        let writer_thread = std::thread::Builder::new()
+            .name("mempool-journal".into())
+            .spawn(move || writer.run(command_receiver))
+            .context("Failed to spawn the mempool journal writer thread")?;
+
+        let journal = Self {
+            command_sender: Some(command_sender),
+            compaction_lock: Mutex::new(()),
+            num_records,
+            writer_thread: Some(writer_thread),
+        };
+        Ok((journal, journaled_transactions))
+    }
+
+    /// Queues the given transaction to be appended to the journal. This never
+    /// blocks: if the writer thread has fallen behind, the record is dropped.
+    pub(crate) fn append(&self, transaction: SignedTransaction) -> Result<()> {
+        match self
+            .command_sender()
+            .try_send(JournalCommand::Append(transaction))
+        {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => Err(anyhow::anyhow!(
+                "The mempool journal writer has fallen behind"
+            )),
+            Err(TrySendError::Disconnected(_)) => {
+                Err(anyhow::anyhow!("The mempool journal writer has stopped"))
+            },
+        }
+    }
+
+    /// Returns true iff the journal holds enough stale records (i.e., more than
+    /// twice the number of transactions currently in mempool) to warrant compaction
+    pub(crate) fn needs_compaction(&self, num_mempool_transactions: usize) -> bool {
+        let num_records = self.num_records.load(Ordering::Relaxed);
+        num_records >= MIN_RECORDS_FOR_COMPACTION
+            && num_records > num_mempool_transactions.saturating_mul(2)
+    }
+
+    /// Compacts the journal, i.e., drops the records of all transactions that have left
+    /// mempool. The given function must return the hashes of all transactions currently
+    /// in mempool, and is only called after the compaction is queued, to guarantee that
+    /// transactions accepted concurrently are never dropped from the journal.
+    pub(crate) fn compact(
+        &self,
+        get_transaction_hashes: impl FnOnce() -> HashSet<HashValue>,
+    ) -> Result<()> {
+        let _compaction_guard = self.compaction_lock.lock();
+        let command_sender = self.command_sender();
+        command_sender
+            .send(JournalCommand::StartCompaction)
+            .context("The mempool journal writer has stopped")?;
+        command_sender
+            .send(JournalCommand::Compact(get_transaction_hashes()))
+            .context("The mempool journal writer has stopped")
+    }
+
+    fn command_sender(&self) -> &SyncSender<JournalCommand> {
+        self.command_sender
+            .as_ref()
+            .expect("The command sender is only taken on drop!")
+    }
+}
+
+impl Drop for TransactionJournal {
+    fn drop(&mut self) {
+        // Close the channel and wait for the writer to process all pending commands
+        self.command_sender.take();
+        if let Some(writer_thread) = self.writer_thread.take() {
+            let _ = writer_thread.join();
+        }
+    }
+}
+
+/// The journal writer, which owns the journal file and runs on a dedicated thread
+struct JournalWriter {
+    journal_path: PathBuf,
+    journal_file: File,
+    num_records: Arc<AtomicUsize>,
+    num_compaction_candidates: Option<usize>, // The number of records before the compaction marker
+}
+
+impl JournalWriter {
+    /// Processes journal commands until the journal handle is dropped
+    fn run(mut self, command_receiver: Receiver<JournalCommand>) {
+        while let Ok(command) = command_receiver.recv() {
+            let (operation, result) = match command {
+                JournalCommand::Append(transaction) => {
+                    (counters::JOURNAL_APPEND_LABEL, self.append(&transaction))
+                },
+                JournalCommand::StartCompaction => {
+                    self.num_compaction_candidates = Some(self.num_records.load(Ordering::Relaxed));
+                    continue;
+                },
+                JournalCommand::Compact(transaction_hashes) => (
+                    counters::JOURNAL_COMPACT_LABEL,
+                    self.compact(&transaction_hashes),
+                ),
+            };
+            if let Err(error) = result {
+                counters::CORE_MEMPOOL_JOURNAL_ERRORS
+                    .with_label_values(&[operation])
+                    .inc();
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(60)),
+                    error!(
+                        LogSchema::new(LogEntry::TransactionJournal).error(&error),
+                        "Failed to update the mempool transaction journal! Operation: {}",
+                        operation
+                    )
+                );
+            }
+        }
+    }
+
+    /// Appends the given transaction to the journal
+    fn append(&mut self, transaction: &SignedTransaction) -> Result<()> {
+        let record = encode_record(transaction)?;
+        self.journal_file.write_all(&record).with_context(|| {
+            format!(
+                "Failed to append to mempool journal {:?}",
+                self.journal_path
+            )
+        })?;
+        self.num_records.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Rewrites the journal, dropping all compaction candidates that are not in the given
+    /// set of transaction hashes (and retaining all records appended after the candidates).
+    fn compact(&mut self, transaction_hashes: &HashSet<HashValue>) -> Result<()> {
+        let num_compaction_candidates = self
+            .num_compaction_candidates
+            .take()
+            .context("Compaction was not started")?;
+
+        let mut contents = vec![];
+        let mut num_records = 0;
+        let transactions = decode_records(&read_journal(&self.journal_path)?);
+        for (index, transaction) in transactions.into_iter().enumerate() {
+            if index >= num_compaction_candidates
+                || transaction_hashes.contains(&transaction.committed_hash())
+            {
+                contents.extend(encode_record(&transaction)?);
+                num_records += 1;
+            }
+        }
+
+        replace_journal(&self.journal_path, &contents)?;
+        self.journal_file = create_journal_file(&self.journal_path)?;
+        self.num_records.store(num_records, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Reads the entire journal at the given path
+fn read_journal(journal_path: &Path) -> Result<Vec<u8>> {
+    let mut contents = vec![];
+    File::open(journal_path)
+        .and_then(|mut file| file.read_to_end(&mut contents))
+        .with_context(|| format!("Failed to read mempool journal {:?}", journal_path))?;
+    Ok(contents)
+}
+
+/// Atomically replaces the journal contents (by writing them to a
+/// temporary file and renaming it over the existing journal).
+fn replace_journal(journal_path: &Path, contents: &[u8]) -> Result<()> {
+    let temp_path = journal_path.with_extension("tmp");
+    std::fs::write(&temp_path, contents)
+        .with_context(|| format!("Failed to write mempool journal {:?}", temp_path))?;
+    std::fs::rename(&temp_path, journal_path).with_context(|| {
+        format!(
+            "Failed to move mempool journal {:?} to {:?}",
+            temp_path, journal_path
+        )
+    })
+}
+
+/// Opens the journal file at the given path for appending
+fn create_journal_file(journal_path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal_path)
+        .with_context(|| format!("Failed to open mempool journal {:?}", journal_path))
+}
+
+/// Encodes the given transaction as a length prefixed journal record
+fn encode_record(transaction: &SignedTransaction) -> Result<Vec<u8>> {
+    let bytes = bcs::to_bytes(transaction)?;
+    let mut record = Vec::with_capacity(RECORD_LENGTH_PREFIX_BYTES + bytes.len());
+    record.extend((bytes.len() as u32).to_le_bytes());
+    record.extend(bytes);
+    Ok(record)
+}
+
+/// Decodes all complete journal records in the given contents
+fn decode_records(mut contents: &[u8]) -> Vec<SignedTransaction> {
+    let mut transactions = vec![];
+    while contents.len() >= RECORD_LENGTH_PREFIX_BYTES {
+        let (length_prefix, record_and_remaining) = contents.split_at(RECORD_LENGTH_PREFIX_BYTES);
+        let record_length = u32::from_le_bytes(length_prefix.try_into().unwrap()) as usize;
+        if record_and_remaining.len() < record_length {
+            break; // The record was truncated
+        }
+        let (record, remaining_contents) = record_and_remaining.split_at(record_length);
+        match bcs::from_bytes(record) {
+            Ok(transaction) => transactions.push(transaction),
+            Err(_) => break, // The record is corrupt
+        }
+        contents = remaining_contents;
+    }
+    transactions
+}
@@ -13,11 +13,16 @@ use crate::{
     counters,
     logging::{LogEntry, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
-    shared_mempool::types::{
-        MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+    shared_mempool::{
+        load_state::MempoolLoadState,
+        types::{
+            MempoolSenderBucket, MempoolTransactionStatus, MultiBucketTimelineIndexIds,
+            PendingTransaction, PendingTransactionsCursor, PendingTransactionsRequest,
+            TimelineIndexIdentifier,
+        },
     },
 };
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{EvictionPolicyConfig, NodeConfig};
 use aptos_consensus_types::common::{TransactionInProgress, TransactionSummary};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
@@ -38,6 +43,12 @@ pub struct Mempool {
     transactions: TransactionStore,
 
     pub system_transaction_timeout: Duration,
+    // The system TTL overrides for specific transactions (e.g., per sender)
+    eviction_policy: EvictionPolicyConfig,
+    // The eviction policy evaluated in shadow mode (if any). It never evicts transactions.
+    shadow_eviction_policy: Option<EvictionPolicyConfig>,
+    // The time (since epoch) of the last shadow eviction policy evaluation
+    last_shadow_eviction_evaluation: Duration,
 }
 
 impl Mempool {
@@ -47,6 +58,22 @@ impl Mempool {
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
+            eviction_policy: config.mempool.eviction_policy.clone(),
+            shadow_eviction_policy: config.mempool.shadow_eviction_policy.clone(),
+            last_shadow_eviction_evaluation: Duration::ZERO,
+        }
+    }
+
+    /// Creates the mempool backing the given shard of a [`ShardedMempool`](super::ShardedMempool)
+    pub(crate) fn new_shard(config: &NodeConfig, shard_id: usize) -> Self {
+        Mempool {
+            transactions: TransactionStore::new_shard(&config.mempool, shard_id),
+            system_transaction_timeout: Duration::from_secs(
+                config.mempool.system_transaction_timeout_secs,
+            ),
+            eviction_policy: config.mempool.eviction_policy.clone(),
+            shadow_eviction_policy: config.mempool.shadow_eviction_policy.clone(),
+            last_shadow_eviction_evaluation: Duration::ZERO,
         }
     }
 
@@ -56,6 +83,13 @@ impl Mempool {
             .commit_transaction(sender, sequence_number);
     }
 
+    /// This function will be called once a block of transactions has been stored.
+    /// Returns the number of parked transactions that were promoted (see
+    /// [`TransactionStore::commit_transactions`]).
+    pub(crate) fn commit_transactions(&mut self, transactions: &[(AccountAddress, u64)]) -> usize {
+        self.transactions.commit_transactions(transactions)
+    }
+
     pub(crate) fn log_commit_transaction(
         &self,
         sender: &AccountAddress,
@@ -120,7 +154,18 @@ impl Mempool {
         };
         self.log_reject_transaction(sender, sequence_number, label);
         self.transactions
-            .reject_transaction(sender, sequence_number, hash);
+            .reject_transaction(sender, sequence_number, hash, reason);
+    }
+
+    /// Handles a transaction that was dropped from a block by the block cut (e.g., due to
+    /// block limits or conflicts). The transaction is still valid, so it is kept in mempool
+    /// to be included in a subsequent block.
+    pub(crate) fn retain_block_cut_transaction(
+        &mut self,
+        sender: &AccountAddress,
+        sequence_number: u64,
+    ) {
+        self.log_reject_transaction(sender, sequence_number, counters::COMMIT_BLOCK_CUT_LABEL);
     }
 
     pub(crate) fn log_txn_latency(
@@ -140,7 +185,24 @@ impl Mempool {
         }
     }
 
-    fn log_consensus_pulled_latency(&self, account: AccountAddress, sequence_number: u64) {
+    pub(crate) fn log_consensus_pulled_latency(
+        &self,
+        account: AccountAddress,
+        sequence_number: u64,
+    ) {
+        if let Some(ranking_score) = self
+            .transactions
+            .get_ranking_score(&account, sequence_number)
+        {
+            counters::core_mempool_txn_ranking_score(
+                counters::CONSENSUS_PULLED_LABEL,
+                counters::CONSENSUS_PULLED_LABEL,
+                self.transactions
+                    .get_bucket(ranking_score, &account)
+                    .as_str(),
+                ranking_score,
+            );
+        }
         if let Some((insertion_info, bucket, priority)) = self
             .transactions
             .get_insertion_info_and_bucket(&account, sequence_number)
@@ -270,6 +332,17 @@ impl Mempool {
         self.transactions.get_by_hash(hash)
     }
 
+    /// Returns the mempool status of each of the given transaction hashes
+    pub(crate) fn get_statuses_by_hash(
+        &self,
+        hashes: &[HashValue],
+    ) -> Vec<MempoolTransactionStatus> {
+        hashes
+            .iter()
+            .map(|hash| self.transactions.get_status_by_hash(*hash))
+            .collect()
+    }
+
     /// Used to add a transaction to the Mempool.
     /// Performs basic validation: checks account's sequence number.
     pub(crate) fn add_txn(
@@ -302,7 +375,7 @@ impl Mempool {
 
         let now = SystemTime::now();
         let expiration_time =
-            aptos_infallible::duration_since_epoch_at(&now) + self.system_transaction_timeout;
+            aptos_infallible::duration_since_epoch_at(&now) + self.get_system_ttl(&txn);
 
         let sender = txn.sender();
         let txn_info = MempoolTransaction::new(
@@ -385,7 +458,6 @@ impl Mempool {
     ///                          exclude_transactions. Should only be true for Quorum Store.
     /// `exclude_transactions` - transactions that were sent to Consensus but were not committed yet
     ///  mempool should filter out such transactions.
-    #[allow(clippy::explicit_counter_loop)]
     pub(crate) fn get_batch(
         &self,
         max_txns: u64,
@@ -393,6 +465,27 @@ impl Mempool {
         return_non_full: bool,
         exclude_transactions: BTreeMap<TransactionSummary, TransactionInProgress>,
     ) -> Vec<SignedTransaction> {
+        let (block, total_bytes) =
+            self.collect_batch(max_txns, max_bytes, return_non_full, &exclude_transactions);
+
+        counters::mempool_service_transactions(counters::GET_BLOCK_LABEL, block.len());
+        counters::MEMPOOL_SERVICE_BYTES_GET_BLOCK.observe(total_bytes as f64);
+        for transaction in &block {
+            self.log_consensus_pulled_latency(transaction.sender(), transaction.sequence_number());
+        }
+        block
+    }
+
+    /// Selects the next block of transactions for consensus (see [`Mempool::get_batch`]),
+    /// along with its total size in bytes, without recording the block as pulled.
+    #[allow(clippy::explicit_counter_loop)]
+    pub(crate) fn collect_batch(
+        &self,
+        max_txns: u64,
+        max_bytes: u64,
+        return_non_full: bool,
+        exclude_transactions: &BTreeMap<TransactionSummary, TransactionInProgress>,
+    ) -> (Vec<SignedTransaction>, u64) {
         let start_time = Instant::now();
         let exclude_size = exclude_transactions.len();
         let mut inserted = HashSet::new();
@@ -420,7 +513,7 @@ impl Mempool {
             }
             let tx_seq = txn.sequence_number.transaction_sequence_number;
             let txn_in_sequence = tx_seq > 0
-                && Self::txn_was_chosen(txn.address, tx_seq - 1, &inserted, &exclude_transactions);
+                && Self::txn_was_chosen(txn.address, tx_seq - 1, &inserted, exclude_transactions);
             let account_sequence_number = self.transactions.get_sequence_number(&txn.address);
             // include transaction if it's "next" for given account or
             // we've already sent its ancestor to Consensus.
@@ -453,10 +546,7 @@ impl Mempool {
         let mut block = Vec::with_capacity(result_size);
         let mut full_bytes = false;
         for (sender, sequence_number) in result {
-            if let Some((txn, ranking_score)) = self
-                .transactions
-                .get_with_ranking_score(&sender, sequence_number)
-            {
+            if let Some(txn) = self.transactions.get(&sender, sequence_number) {
                 let txn_size = txn.txn_bytes_len() as u64;
                 if total_bytes + txn_size > max_bytes {
                     full_bytes = true;
@@ -467,14 +557,6 @@ impl Mempool {
                 if total_bytes == max_bytes {
                     full_bytes = true;
                 }
-                counters::core_mempool_txn_ranking_score(
-                    counters::CONSENSUS_PULLED_LABEL,
-                    counters::CONSENSUS_PULLED_LABEL,
-                    self.transactions
-                        .get_bucket(ranking_score, &sender)
-                        .as_str(),
-                    ranking_score,
-                );
             }
         }
         let block_end_time = start_time.elapsed();
@@ -517,22 +599,61 @@ impl Mempool {
             block.clear();
         }
 
-        counters::mempool_service_transactions(counters::GET_BLOCK_LABEL, block.len());
-        counters::MEMPOOL_SERVICE_BYTES_GET_BLOCK.observe(total_bytes as f64);
-        for transaction in &block {
-            self.log_consensus_pulled_latency(transaction.sender(), transaction.sequence_number());
-        }
-        block
+        (block, total_bytes)
     }
 
     /// Periodic core mempool garbage collection.
     /// Removes all expired transactions and clears expired entries in metrics
     /// cache and sequence number cache.
     pub(crate) fn gc(&mut self) {
-        let now = aptos_infallible::duration_since_epoch();
+        self.gc_at(aptos_infallible::duration_since_epoch());
+    }
+
+    /// Removes the transactions whose system TTL expired by the given time
+    pub(crate) fn gc_at(&mut self, now: Duration) {
+        self.evaluate_shadow_eviction_policy(now);
         self.transactions.gc_by_system_ttl(now);
     }
 
+    /// Compares the shadow eviction policy (if any) against the active eviction policy, i.e.,
+    /// counts and logs the transactions that the shadow policy would have evicted (since the
+    /// last evaluation) but are kept, and the transactions about to be evicted that the shadow
+    /// policy would have kept. Note: the shadow policy never affects which transactions are evicted.
+    pub(crate) fn evaluate_shadow_eviction_policy(
+        &mut self,
+        now: Duration,
+    ) -> Option<ShadowEvictionDivergence> {
+        let shadow_eviction_policy = self.shadow_eviction_policy.as_ref()?;
+        let (would_have_evicted_txns, would_have_kept_txns) = self
+            .transactions
+            .get_shadow_eviction_divergence(self.last_shadow_eviction_evaluation, now)?;
+        self.last_shadow_eviction_evaluation = now;
+
+        // Update the metrics and log the divergence (if any)
+        counters::core_mempool_shadow_eviction_divergence(
+            counters::SHADOW_WOULD_HAVE_EVICTED_LABEL,
+            would_have_evicted_txns.len(),
+        );
+        counters::core_mempool_shadow_eviction_divergence(
+            counters::SHADOW_WOULD_HAVE_KEPT_LABEL,
+            would_have_kept_txns.len(),
+        );
+        if !would_have_evicted_txns.is_empty() || !would_have_kept_txns.is_empty() {
+            debug!(
+                LogSchema::new(LogEntry::EvictionPolicy),
+                "Evaluated shadow eviction policy: {:?}. Would have evicted: {}. Would have kept: {}",
+                shadow_eviction_policy,
+                would_have_evicted_txns,
+                would_have_kept_txns,
+            );
+        }
+
+        Some(ShadowEvictionDivergence {
+            num_would_have_evicted: would_have_evicted_txns.len(),
+            num_would_have_kept: would_have_kept_txns.len(),
+        })
+    }
+
     /// Garbage collection based on client-specified expiration time.
     pub(crate) fn gc_by_expiration_time(&mut self, block_time: Duration) {
         self.transactions.gc_by_expiration_time(block_time);
@@ -568,6 +689,15 @@ impl Mempool {
             .timeline_range(sender_bucket, start_end_pairs)
     }
 
+    pub(crate) fn timeline_range_min_expiration_secs(
+        &self,
+        sender_bucket: MempoolSenderBucket,
+        start_end_pairs: HashMap<TimelineIndexIdentifier, (u64, u64)>,
+    ) -> Option<u64> {
+        self.transactions
+            .timeline_range_min_expiration_secs(sender_bucket, start_end_pairs)
+    }
+
     pub(crate) fn timeline_range_of_message(
         &self,
         sender_start_end_pairs: HashMap<
@@ -588,6 +718,49 @@ impl Mempool {
         self.transactions.gen_snapshot()
     }
 
+    /// Returns (up to `limit`) transactions that match the given request, in insertion order
+    /// (see [`TransactionStore::get_pending_transactions`])
+    pub(crate) fn get_pending_transactions(
+        &self,
+        request: &PendingTransactionsRequest,
+        limit: usize,
+    ) -> (Vec<PendingTransaction>, Option<PendingTransactionsCursor>) {
+        self.transactions.get_pending_transactions(request, limit)
+    }
+
+    /// Returns the system TTL of the given transaction, i.e., the time after which
+    /// it is evicted from mempool (if it hasn't been committed or expired already).
+    pub(crate) fn get_system_ttl(&self, txn: &SignedTransaction) -> Duration {
+        get_system_ttl_for_policy(&self.eviction_policy, self.system_transaction_timeout, txn)
+    }
+
+    /// Returns the current eviction policy
+    pub(crate) fn get_eviction_policy(&self) -> &EvictionPolicyConfig {
+        &self.eviction_policy
+    }
+
+    /// Replaces the eviction policy. The new policy only applies to transactions
+    /// inserted from now on (i.e., existing transactions keep their system TTL).
+    pub(crate) fn set_eviction_policy(&mut self, eviction_policy: EvictionPolicyConfig) {
+        self.eviction_policy = eviction_policy;
+    }
+
+    /// Returns the load statistics of mempool
+    pub(crate) fn get_load_state(&self) -> MempoolLoadState {
+        self.transactions.get_load_state()
+    }
+
+    /// Returns the hashes of (up to `max_hashes` of) the most recently inserted
+    /// transactions in mempool, newest first
+    pub(crate) fn get_transaction_hashes(&self, max_hashes: usize) -> Vec<HashValue> {
+        self.transactions.get_transaction_hashes(max_hashes)
+    }
+
+    /// Returns the number of transactions in mempool
+    pub(crate) fn get_num_transactions(&self) -> usize {
+        self.transactions.get_num_transactions()
+    }
+
     #[cfg(test)]
     pub fn get_parking_lot_size(&self) -> usize {
         self.transactions.get_parking_lot_size()
@@ -598,3 +771,27 @@ impl Mempool {
         &self.transactions
     }
 }
+
+/// The divergence of the shadow eviction policy from the active eviction policy
+#[derive(Debug, Default, Eq, PartialEq)]
+pub(crate) struct ShadowEvictionDivergence {
+    pub num_would_have_evicted: usize, // The number of kept txns the shadow would have evicted
+    pub num_would_have_kept: usize,    // The number of evicted txns the shadow would have kept
+}
+
+/// Returns the system TTL of the given transaction under the given eviction policy
+/// (falling back to the default system TTL if the policy has no override for it).
+pub(crate) fn get_system_ttl_for_policy(
+    eviction_policy: &EvictionPolicyConfig,
+    default_system_ttl: Duration,
+    txn: &SignedTransaction,
+) -> Duration {
+    let ttl_override_secs = match eviction_policy.sender_ttl_secs.get(&txn.sender()) {
+        Some(sender_ttl_secs) => Some(*sender_ttl_secs),
+        None if txn.gas_unit_price() == 0 => eviction_policy.zero_gas_price_ttl_secs,
+        None => None,
+    };
+    ttl_override_secs
+        .map(Duration::from_secs)
+        .unwrap_or(default_system_ttl)
+}
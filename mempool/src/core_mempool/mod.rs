@@ -3,16 +3,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod index;
+mod journal;
 mod mempool;
+mod sharded_mempool;
 pub mod transaction;
 mod transaction_store;
 
-pub use self::{
-    mempool::Mempool as CoreMempool, transaction::TimelineState,
-    transaction_store::TXN_INDEX_ESTIMATED_BYTES,
-};
+#[cfg(test)]
+pub(crate) use self::journal::TransactionJournal;
 #[cfg(test)]
 pub use self::{
+    mempool::Mempool as CoreMempool,
     transaction::{MempoolTransaction, SubmittedBy},
     transaction_store::sender_bucket,
 };
+pub use self::{
+    sharded_mempool::ShardedMempool,
+    transaction::TimelineState,
+    transaction_store::{IDEMPOTENT_TRANSACTION_MESSAGE, TXN_INDEX_ESTIMATED_BYTES},
+};
@@ -0,0 +1,597 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A core mempool that is partitioned by sender into independent shards, each
+//! with its own lock and indices, so that concurrent ingestion for different
+//! senders does not contend on a single mutex.
+use crate::{
+    core_mempool::{
+        journal::TransactionJournal, mempool::Mempool, transaction::TimelineState,
+        transaction_store::sender_bucket,
+    },
+    counters,
+    logging::{LogEntry, LogSchema, TxnsLog},
+    network::BroadcastPeerPriority,
+    shared_mempool::{
+        load_state::MempoolLoadState,
+        types::{
+            MempoolSenderBucket, MempoolTransactionStatus, MultiBucketTimelineIndexIds,
+            PendingTransactionsPage, PendingTransactionsRequest, TimelineIndexIdentifier,
+        },
+    },
+};
+use anyhow::Result;
+use aptos_config::config::{EvictionPolicyConfig, NodeConfig};
+use aptos_consensus_types::common::{TransactionInProgress, TransactionSummary};
+use aptos_crypto::HashValue;
+use aptos_infallible::{Mutex, MutexGuard};
+use aptos_logger::prelude::*;
+use aptos_types::{
+    account_address::AccountAddress,
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    transaction::{use_case::UseCaseKey, SignedTransaction},
+    vm_status::DiscardedVMStatus,
+};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// The core mempool, split into `num_mempool_shards` independent [`Mempool`]s.
+///
+/// Every sender bucket (and therefore every sender) is owned by exactly one shard,
+/// so per-account sequence number tracking and per-bucket broadcast timelines are
+/// unaffected by sharding. Operations on a single sender only lock that sender's
+/// shard. Consensus pulls gather a block from each shard and merge them by gas price.
+///
+/// Operations that span shards (e.g., `gc` and hash lookups) visit each shard in turn, so
+/// they may interleave with concurrent single-sender operations. This is safe because each
+/// sender is only ever updated within its own shard. Consensus pulls instead hold the locks
+/// of all shards (see [`ShardedMempool::lock_all`]), so that a block is cut from a single
+/// consistent view of the mempool.
+///
+/// If a transaction journal is configured, all accepted transactions are also recorded
+/// on disk, so that they can be reloaded (and re-validated) after a node restart.
+pub struct ShardedMempool {
+    shards: Vec<Mutex<Mempool>>,
+    num_sender_buckets: MempoolSenderBucket,
+
+    // The on-disk journal of accepted transactions (if enabled)
+    journal: Option<TransactionJournal>,
+    // The transactions reloaded from the journal on startup (until they are replayed)
+    journaled_transactions: Mutex<Vec<SignedTransaction>>,
+}
+
+impl ShardedMempool {
+    pub fn new(config: &NodeConfig) -> Self {
+        // Each shard gets an equal share of the mempool capacity
+        let num_shards = config.mempool.num_mempool_shards.max(1) as usize;
+        let mut shard_config = config.clone();
+        shard_config.mempool.capacity = (config.mempool.capacity / num_shards).max(1);
+        shard_config.mempool.capacity_bytes = (config.mempool.capacity_bytes / num_shards).max(1);
+
+        let shards = (0..num_shards)
+            .map(|shard_id| Mutex::new(Mempool::new_shard(&shard_config, shard_id)))
+            .collect();
+
+        // Open the transaction journal (if enabled). If the journal cannot be
+        // opened, mempool continues to operate without it.
+        let (journal, journaled_transactions) = match &config.mempool.transaction_journal_path {
+            Some(journal_path) => match TransactionJournal::open(journal_path) {
+                Ok((journal, journaled_transactions)) => {
+                    info!(
+                        LogSchema::new(LogEntry::TransactionJournal)
+                            .num_txns(journaled_transactions.len()),
+                        "Opened the mempool transaction journal at {:?}", journal_path
+                    );
+                    (Some(journal), journaled_transactions)
+                },
+                Err(error) => {
+                    error!(
+                        LogSchema::new(LogEntry::TransactionJournal).error(&error),
+                        "Failed to open the mempool transaction journal! Transactions will not be journaled."
+                    );
+                    (None, vec![])
+                },
+            },
+            None => (None, vec![]),
+        };
+
+        Self {
+            shards,
+            num_sender_buckets: config.mempool.num_sender_buckets,
+            journal,
+            journaled_transactions: Mutex::new(journaled_transactions),
+        }
+    }
+
+    /// Returns the number of shards
+    pub(crate) fn num_shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the shard that owns the given sender bucket
+    pub(crate) fn shard_id_of_bucket(&self, sender_bucket: MempoolSenderBucket) -> usize {
+        sender_bucket as usize % self.num_shards()
+    }
+
+    /// Returns the shard that owns the given sender
+    pub(crate) fn shard_id_of_sender(&self, sender: &AccountAddress) -> usize {
+        self.shard_id_of_bucket(sender_bucket(sender, self.num_sender_buckets))
+    }
+
+    fn shard_of_sender(&self, sender: &AccountAddress) -> &Mutex<Mempool> {
+        &self.shards[self.shard_id_of_sender(sender)]
+    }
+
+    fn shard_of_bucket(&self, sender_bucket: MempoolSenderBucket) -> &Mutex<Mempool> {
+        &self.shards[self.shard_id_of_bucket(sender_bucket)]
+    }
+
+    /// See [`Mempool::commit_transaction`]
+    pub(crate) fn commit_transaction(&self, sender: &AccountAddress, sequence_number: u64) {
+        self.shard_of_sender(sender)
+            .lock()
+            .commit_transaction(sender, sequence_number);
+    }
+
+    /// See [`Mempool::commit_transactions`]. The transactions are grouped by shard,
+    /// so that each shard is only locked once.
+    pub(crate) fn commit_transactions(&self, transactions: &[(AccountAddress, u64)]) -> usize {
+        let mut shard_transactions = vec![vec![]; self.num_shards()];
+        for (sender, sequence_number) in transactions {
+            shard_transactions[self.shard_id_of_sender(sender)].push((*sender, *sequence_number));
+        }
+
+        shard_transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, transactions)| !transactions.is_empty())
+            .map(|(shard_id, transactions)| {
+                self.shards[shard_id]
+                    .lock()
+                    .commit_transactions(transactions)
+            })
+            .sum()
+    }
+
+    /// See [`Mempool::log_commit_transaction`]
+    pub(crate) fn log_commit_transaction(
+        &self,
+        sender: &AccountAddress,
+        sequence_number: u64,
+        tracked_use_case: Option<(UseCaseKey, &String)>,
+        block_timestamp: Duration,
+    ) {
+        self.shard_of_sender(sender).lock().log_commit_transaction(
+            sender,
+            sequence_number,
+            tracked_use_case,
+            block_timestamp,
+        );
+    }
+
+    /// See [`Mempool::reject_transaction`]
+    pub(crate) fn reject_transaction(
+        &self,
+        sender: &AccountAddress,
+        sequence_number: u64,
+        hash: &HashValue,
+        reason: &DiscardedVMStatus,
+    ) {
+        self.shard_of_sender(sender).lock().reject_transaction(
+            sender,
+            sequence_number,
+            hash,
+            reason,
+        );
+    }
+
+    /// See [`Mempool::retain_block_cut_transaction`]
+    pub(crate) fn retain_block_cut_transaction(
+        &self,
+        sender: &AccountAddress,
+        sequence_number: u64,
+    ) {
+        self.shard_of_sender(sender)
+            .lock()
+            .retain_block_cut_transaction(sender, sequence_number);
+    }
+
+    /// See [`Mempool::get_by_hash`]
+    pub(crate) fn get_by_hash(&self, hash: HashValue) -> Option<SignedTransaction> {
+        self.shards
+            .iter()
+            .find_map(|shard| shard.lock().get_by_hash(hash))
+    }
+
+    /// See [`Mempool::get_statuses_by_hash`]
+    pub(crate) fn get_statuses_by_hash(
+        &self,
+        hashes: &[HashValue],
+    ) -> Vec<MempoolTransactionStatus> {
+        let mut statuses = vec![MempoolTransactionStatus::Unknown; hashes.len()];
+        for shard in &self.shards {
+            let shard_statuses = shard.lock().get_statuses_by_hash(hashes);
+            for (status, shard_status) in statuses.iter_mut().zip(shard_statuses) {
+                if shard_status != MempoolTransactionStatus::Unknown {
+                    *status = shard_status;
+                }
+            }
+        }
+        statuses
+    }
+
+    /// See [`Mempool::add_txn`]
+    pub(crate) fn add_txn(
+        &self,
+        txn: SignedTransaction,
+        ranking_score: u64,
+        db_sequence_number: u64,
+        timeline_state: TimelineState,
+        client_submitted: bool,
+        ready_time_at_sender: Option<u64>,
+        priority: Option<BroadcastPeerPriority>,
+    ) -> MempoolStatus {
+        let journaled_txn = self.journal.as_ref().map(|_| txn.clone());
+        let status = self.shard_of_sender(&txn.sender()).lock().add_txn(
+            txn,
+            ranking_score,
+            db_sequence_number,
+            timeline_state,
+            client_submitted,
+            ready_time_at_sender,
+            priority,
+        );
+
+        // Record the transaction in the journal (if it was accepted). This is done
+        // after the shard lock is released, and the record is only queued for the
+        // journal writer thread (so the disk is never accessed on this path).
+        if let (Some(journal), Some(txn)) = (&self.journal, journaled_txn) {
+            if status.code == MempoolStatusCode::Accepted {
+                if let Err(error) = journal.append(txn) {
+                    counters::CORE_MEMPOOL_JOURNAL_ERRORS
+                        .with_label_values(&[counters::JOURNAL_APPEND_LABEL])
+                        .inc();
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(60)),
+                        error!(
+                            LogSchema::new(LogEntry::TransactionJournal).error(&error),
+                            "Failed to append the transaction to the mempool journal!"
+                        )
+                    );
+                }
+            }
+        }
+
+        status
+    }
+
+    /// Returns (and clears) the transactions that were reloaded from the
+    /// transaction journal on startup. These must be re-validated before
+    /// they are added back to mempool.
+    pub(crate) fn take_journaled_transactions(&self) -> Vec<SignedTransaction> {
+        std::mem::take(&mut *self.journaled_transactions.lock())
+    }
+
+    /// Locks all shards (in shard order) and returns a guard that holds the locks until it
+    /// is dropped. This allows operations that span shards (e.g., garbage collection followed
+    /// by a consensus pull) to observe a consistent view of the whole mempool.
+    ///
+    /// Single-shard operations never lock more than one shard at a time, so taking the locks
+    /// in shard order cannot deadlock.
+    pub(crate) fn lock_all(&self) -> LockedShards<'_> {
+        LockedShards {
+            sharded_mempool: self,
+            shards: self.shards.iter().map(|shard| shard.lock()).collect(),
+        }
+    }
+
+    /// Fetches the next block of transactions for consensus (see [`LockedShards::get_batch`]).
+    pub(crate) fn get_batch(
+        &self,
+        max_txns: u64,
+        max_bytes: u64,
+        return_non_full: bool,
+        exclude_transactions: BTreeMap<TransactionSummary, TransactionInProgress>,
+    ) -> Vec<SignedTransaction> {
+        self.lock_all()
+            .get_batch(max_txns, max_bytes, return_non_full, exclude_transactions)
+    }
+
+    /// See [`Mempool::gc`]. This also compacts the transaction journal (if required).
+    pub(crate) fn gc(&self) {
+        for shard in &self.shards {
+            shard.lock().gc();
+        }
+        self.compact_journal_if_needed();
+    }
+
+    /// Compacts the transaction journal (i.e., drops the records of transactions that
+    /// have left mempool), if enough stale records have accumulated. Only the hashes of
+    /// the transactions in mempool are collected (one shard at a time); the journal itself
+    /// is rewritten by the journal writer thread.
+    fn compact_journal_if_needed(&self) {
+        if let Some(journal) = &self.journal {
+            if !journal.needs_compaction(self.get_num_transactions()) {
+                return;
+            }
+
+            let get_transaction_hashes = || {
+                self.shards
+                    .iter()
+                    .flat_map(|shard| shard.lock().get_transaction_hashes(usize::MAX))
+                    .collect()
+            };
+            if let Err(error) = journal.compact(get_transaction_hashes) {
+                counters::CORE_MEMPOOL_JOURNAL_ERRORS
+                    .with_label_values(&[counters::JOURNAL_COMPACT_LABEL])
+                    .inc();
+                error!(
+                    LogSchema::new(LogEntry::TransactionJournal).error(&error),
+                    "Failed to compact the mempool transaction journal!"
+                );
+            }
+        }
+    }
+
+    /// See [`Mempool::gc_by_expiration_time`]
+    pub(crate) fn gc_by_expiration_time(&self, block_time: Duration) {
+        for shard in &self.shards {
+            shard.lock().gc_by_expiration_time(block_time);
+        }
+    }
+
+    /// See [`Mempool::read_timeline`]
+    pub(crate) fn read_timeline(
+        &self,
+        sender_bucket: MempoolSenderBucket,
+        timeline_id: &MultiBucketTimelineIndexIds,
+        count: usize,
+        before: Option<Instant>,
+        priority_of_receiver: BroadcastPeerPriority,
+    ) -> (Vec<(SignedTransaction, u64)>, MultiBucketTimelineIndexIds) {
+        self.shard_of_bucket(sender_bucket).lock().read_timeline(
+            sender_bucket,
+            timeline_id,
+            count,
+            before,
+            priority_of_receiver,
+        )
+    }
+
+    /// See [`Mempool::timeline_range`]
+    pub(crate) fn timeline_range(
+        &self,
+        sender_bucket: MempoolSenderBucket,
+        start_end_pairs: HashMap<TimelineIndexIdentifier, (u64, u64)>,
+    ) -> Vec<(SignedTransaction, u64)> {
+        self.shard_of_bucket(sender_bucket)
+            .lock()
+            .timeline_range(sender_bucket, start_end_pairs)
+    }
+
+    /// See [`Mempool::timeline_range_min_expiration_secs`]
+    pub(crate) fn timeline_range_min_expiration_secs(
+        &self,
+        sender_bucket: MempoolSenderBucket,
+        start_end_pairs: HashMap<TimelineIndexIdentifier, (u64, u64)>,
+    ) -> Option<u64> {
+        self.shard_of_bucket(sender_bucket)
+            .lock()
+            .timeline_range_min_expiration_secs(sender_bucket, start_end_pairs)
+    }
+
+    /// See [`Mempool::timeline_range_of_message`]
+    pub(crate) fn timeline_range_of_message(
+        &self,
+        sender_start_end_pairs: HashMap<
+            MempoolSenderBucket,
+            HashMap<TimelineIndexIdentifier, (u64, u64)>,
+        >,
+    ) -> Vec<(SignedTransaction, u64)> {
+        sender_start_end_pairs
+            .into_iter()
+            .flat_map(|(sender_bucket, start_end_pairs)| {
+                self.timeline_range(sender_bucket, start_end_pairs)
+            })
+            .collect()
+    }
+
+    pub fn gen_snapshot(&self) -> TxnsLog {
+        let mut txns_log = TxnsLog::new();
+        for shard in &self.shards {
+            txns_log.append(shard.lock().gen_snapshot());
+        }
+        txns_log
+    }
+
+    /// Returns a page of the transactions (across all shards) that match the given
+    /// request, in insertion order. Each shard only scans a bounded number of transactions
+    /// per page, so a page may hold fewer than `limit` transactions even if more remain
+    /// (in which case `next_start` is still set).
+    pub(crate) fn get_pending_transactions(
+        &self,
+        request: &PendingTransactionsRequest,
+    ) -> PendingTransactionsPage {
+        // If the request is for a single sender, only the sender's shard is queried
+        let shards = match &request.filter.sender {
+            Some(sender) => std::slice::from_ref(self.shard_of_sender(sender)),
+            None => &self.shards[..],
+        };
+
+        // Fetch one extra transaction from each shard, to identify the start of the next page.
+        // Each shard locks (and scans) its transactions independently.
+        let limit = request.limit;
+        let mut transactions = vec![];
+        let mut scan_end = None;
+        for shard in shards {
+            let (shard_transactions, shard_scan_end) = shard
+                .lock()
+                .get_pending_transactions(request, limit.saturating_add(1));
+            transactions.extend(shard_transactions);
+            scan_end = scan_end.into_iter().chain(shard_scan_end).min();
+        }
+        transactions.sort_by_key(|transaction| transaction.cursor);
+
+        // If a shard stopped scanning early, the page must end where that shard stopped
+        // (as the shard may hold matching transactions that weren't scanned yet).
+        if let Some(scan_end) = scan_end {
+            transactions.retain(|transaction| transaction.cursor < scan_end);
+        }
+        let next_start = transactions
+            .get(limit)
+            .map(|transaction| transaction.cursor)
+            .into_iter()
+            .chain(scan_end)
+            .min();
+        transactions.truncate(limit);
+        PendingTransactionsPage {
+            transactions,
+            next_start,
+        }
+    }
+
+    /// Returns the hashes of (up to `max_hashes` of) the most recently inserted transactions
+    /// across all shards. The hashes are split evenly between the shards (any share left
+    /// unused by a shard is passed on to the remaining shards), so no shard is favored.
+    pub(crate) fn get_transaction_hashes(&self, max_hashes: usize) -> Vec<HashValue> {
+        let mut hashes = vec![];
+        for (shard_index, shard) in self.shards.iter().enumerate() {
+            let remaining_hashes = max_hashes.saturating_sub(hashes.len());
+            let remaining_shards = self.shards.len() - shard_index;
+            let shard_hashes = remaining_hashes.div_ceil(remaining_shards);
+            if shard_hashes == 0 {
+                break;
+            }
+            hashes.extend(shard.lock().get_transaction_hashes(shard_hashes));
+        }
+        hashes
+    }
+
+    /// Returns the load statistics aggregated across all shards
+    pub(crate) fn get_load_state(&self) -> MempoolLoadState {
+        let mut load_state = MempoolLoadState::default();
+        for shard in &self.shards {
+            let shard_load_state = shard.lock().get_load_state();
+            load_state.num_transactions += shard_load_state.num_transactions;
+            load_state.capacity += shard_load_state.capacity;
+            load_state.size_bytes += shard_load_state.size_bytes;
+            load_state.capacity_bytes += shard_load_state.capacity_bytes;
+            load_state.num_parked_transactions += shard_load_state.num_parked_transactions;
+            load_state.num_system_ttl_evictions += shard_load_state.num_system_ttl_evictions;
+            load_state.next_system_ttl_eviction = load_state
+                .next_system_ttl_eviction
+                .into_iter()
+                .chain(shard_load_state.next_system_ttl_eviction)
+                .min();
+        }
+        load_state
+    }
+
+    /// Returns the number of transactions across all shards
+    pub(crate) fn get_num_transactions(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().get_num_transactions())
+            .sum()
+    }
+
+    /// Returns the current eviction policy (which is the same for all shards)
+    pub fn get_eviction_policy(&self) -> EvictionPolicyConfig {
+        self.shards[0].lock().get_eviction_policy().clone()
+    }
+
+    /// Verifies and applies the given eviction policy to all shards. The new policy
+    /// only applies to transactions inserted from now on.
+    pub fn set_eviction_policy(&self, eviction_policy: EvictionPolicyConfig) -> Result<()> {
+        eviction_policy.verify().map_err(anyhow::Error::msg)?;
+        for shard in &self.shards {
+            shard.lock().set_eviction_policy(eviction_policy.clone());
+        }
+        Ok(())
+    }
+}
+
+/// All shards of a [`ShardedMempool`], locked for the lifetime of this guard
+/// (see [`ShardedMempool::lock_all`]).
+pub(crate) struct LockedShards<'a> {
+    sharded_mempool: &'a ShardedMempool,
+    shards: Vec<MutexGuard<'a, Mempool>>,
+}
+
+impl LockedShards<'_> {
+    /// See [`Mempool::gc_by_expiration_time`]
+    pub(crate) fn gc_by_expiration_time(&mut self, block_time: Duration) {
+        for shard in self.shards.iter_mut() {
+            shard.gc_by_expiration_time(block_time);
+        }
+    }
+
+    /// Fetches the next block of transactions for consensus (see [`Mempool::get_batch`]).
+    ///
+    /// A candidate block is collected from each shard in turn, and the candidates are
+    /// merged by gas price. Each sender lives in a single shard and the order within each
+    /// candidate is preserved, so transactions of a sender remain in sequence number order.
+    pub(crate) fn get_batch(
+        &mut self,
+        max_txns: u64,
+        max_bytes: u64,
+        return_non_full: bool,
+        exclude_transactions: BTreeMap<TransactionSummary, TransactionInProgress>,
+    ) -> Vec<SignedTransaction> {
+        if let [shard] = self.shards.as_mut_slice() {
+            return shard.get_batch(max_txns, max_bytes, return_non_full, exclude_transactions);
+        }
+
+        let mut shard_batches: Vec<VecDeque<SignedTransaction>> = self
+            .shards
+            .iter_mut()
+            .map(|shard| {
+                let (batch, _) =
+                    shard.collect_batch(max_txns, max_bytes, true, &exclude_transactions);
+                VecDeque::from(batch)
+            })
+            .collect();
+
+        let mut block = vec![];
+        let mut total_bytes = 0;
+        let mut full_bytes = false;
+        while (block.len() as u64) < max_txns {
+            let next_batch = shard_batches
+                .iter_mut()
+                .filter(|batch| !batch.is_empty())
+                .max_by_key(|batch| batch[0].gas_unit_price());
+            let batch = match next_batch {
+                Some(batch) => batch,
+                None => break,
+            };
+
+            let txn_size = batch[0].txn_bytes_len() as u64;
+            if total_bytes + txn_size > max_bytes {
+                full_bytes = true;
+                break;
+            }
+            total_bytes += txn_size;
+            block.extend(batch.pop_front());
+            if total_bytes == max_bytes {
+                full_bytes = true;
+            }
+        }
+
+        if !return_non_full && !full_bytes && (block.len() as u64) < max_txns {
+            block.clear();
+        }
+
+        counters::mempool_service_transactions(counters::GET_BLOCK_LABEL, block.len());
+        counters::MEMPOOL_SERVICE_BYTES_GET_BLOCK.observe(total_bytes as f64);
+        for transaction in &block {
+            let shard_id = self
+                .sharded_mempool
+                .shard_id_of_sender(&transaction.sender());
+            self.shards[shard_id]
+                .log_consensus_pulled_latency(transaction.sender(), transaction.sequence_number());
+        }
+        block
+    }
+}
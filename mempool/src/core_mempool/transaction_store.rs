@@ -5,17 +5,22 @@
 use crate::{
     core_mempool::{
         index::{
-            AccountTransactions, MultiBucketTimelineIndex, ParkingLotIndex, PriorityIndex,
-            PriorityQueueIter, TTLIndex,
+            AccountTransactions, InsertionTimeIndex, MultiBucketTimelineIndex, ParkingLotIndex,
+            PriorityIndex, PriorityQueueIter, RejectedTransactionIndex, TTLIndex,
         },
-        mempool::Mempool,
+        mempool::{get_system_ttl_for_policy, Mempool},
         transaction::{InsertionInfo, MempoolTransaction, TimelineState},
     },
     counters::{self, BROADCAST_BATCHED_LABEL, BROADCAST_READY_LABEL, CONSENSUS_READY_LABEL},
     logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
-    shared_mempool::types::{
-        MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+    shared_mempool::{
+        load_state::MempoolLoadState,
+        types::{
+            MempoolSenderBucket, MempoolTransactionInfo, MempoolTransactionStatus,
+            MultiBucketTimelineIndexIds, PendingTransaction, PendingTransactionsCursor,
+            PendingTransactionsRequest, TimelineIndexIdentifier,
+        },
     },
 };
 use aptos_config::config::MempoolConfig;
@@ -25,10 +30,11 @@ use aptos_types::{
     account_address::AccountAddress,
     mempool_status::{MempoolStatus, MempoolStatusCode},
     transaction::SignedTransaction,
+    vm_status::DiscardedVMStatus,
 };
 use std::{
     cmp::max,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     mem::size_of,
     ops::Bound,
     time::{Duration, Instant, SystemTime},
@@ -37,9 +43,17 @@ use std::{
 /// Estimated per-txn overhead of indexes. Needs to be updated if additional indexes are added.
 pub const TXN_INDEX_ESTIMATED_BYTES: usize = size_of::<crate::core_mempool::index::OrderedQueueKey>() // priority_index
     + size_of::<crate::core_mempool::index::TTLOrderingKey>() * 2 // expiration_time_index + system_ttl_index
+    + size_of::<crate::shared_mempool::types::PendingTransactionsCursor>() // insertion_time_index
     + (size_of::<u64>() * 3 + size_of::<AccountAddress>()) // timeline_index
     + (size_of::<HashValue>() + size_of::<u64>() + size_of::<AccountAddress>()); // hash_index
 
+/// The maximum number of transactions scanned for a single page of pending transactions
+/// (see [`TransactionStore::get_pending_transactions`])
+const MAX_PENDING_TRANSACTIONS_SCANNED: usize = 10_000;
+
+/// The message of the (accepted) status returned when a transaction is already in mempool
+pub const IDEMPOTENT_TRANSACTION_MESSAGE: &str = "Transaction already in mempool";
+
 pub fn sender_bucket(
     address: &AccountAddress,
     num_sender_buckets: MempoolSenderBucket,
@@ -63,6 +77,12 @@ pub struct TransactionStore {
     // we keep it separate from `expiration_time_index` so Mempool can't be clogged
     //  by old transactions even if it hasn't received commit callbacks for a while
     system_ttl_index: TTLIndex,
+    // TTLIndex based on the system expiration time under the shadow eviction policy (if any).
+    // This is only used to compare the shadow policy against the active policy (it never
+    // evicts transactions), without scanning the whole store on every garbage collection.
+    shadow_system_ttl_index: Option<TTLIndex>,
+    // All transactions, ordered by insertion time (used to page through mempool)
+    insertion_time_index: InsertionTimeIndex,
     // Broadcast-ready transactions.
     // For each sender bucket, we maintain a timeline per txn fee range.
     timeline_index: HashMap<MempoolSenderBucket, MultiBucketTimelineIndex>,
@@ -77,14 +97,28 @@ pub struct TransactionStore {
     // Using transaction commited hash because from end user's point view, a transaction should only have
     // one valid hash.
     hash_index: HashMap<HashValue, (AccountAddress, u64)>,
+    // Rejection reasons of recently rejected transactions (by committed hash), so that
+    // the status of transactions that were never committed can still be reported.
+    rejected_transaction_index: RejectedTransactionIndex,
     // estimated size in bytes
     size_bytes: usize,
+    // the number of transactions evicted by the last system TTL garbage collection
+    num_system_ttl_evictions: u64,
+
+    // metrics
+    // the shard of the core mempool that this store backs (if any)
+    shard_label: Option<String>,
+    // whether the index size gauges should be updated (these are shared across shards)
+    track_index_sizes: bool,
 
     // configuration
     capacity: usize,
     capacity_bytes: usize,
     capacity_per_user: usize,
     max_batch_bytes: u64,
+    // the broadcast lanes (if enabled), as the index of the first high-fee
+    // timeline bucket and the percentage of each batch given to the high-fee lane
+    broadcast_lanes: Option<(usize, u64)>,
 
     // eager expiration
     eager_expire_threshold: Option<Duration>,
@@ -100,6 +134,31 @@ impl TransactionStore {
                 MultiBucketTimelineIndex::new(config.broadcast_buckets.clone()).unwrap(),
             );
         }
+        let broadcast_lanes = config.broadcast_lanes.as_ref().map(|broadcast_lanes| {
+            let high_fee_threshold = broadcast_lanes.high_fee_threshold;
+            let first_high_fee_bucket = config
+                .broadcast_buckets
+                .partition_point(|bucket_min| *bucket_min < high_fee_threshold);
+            (
+                first_high_fee_bucket,
+                broadcast_lanes.high_fee_batch_percent,
+            )
+        });
+        let default_system_ttl = Duration::from_secs(config.system_transaction_timeout_secs);
+        let shadow_system_ttl_index =
+            config
+                .shadow_eviction_policy
+                .clone()
+                .map(|shadow_eviction_policy| {
+                    TTLIndex::new(Box::new(move |t: &MempoolTransaction| {
+                        aptos_infallible::duration_since_epoch_at(&t.insertion_info.insertion_time)
+                            + get_system_ttl_for_policy(
+                                &shadow_eviction_policy,
+                                default_system_ttl,
+                                &t.txn,
+                            )
+                    }))
+                });
         Self {
             // main DS
             transactions: HashMap::new(),
@@ -107,22 +166,33 @@ impl TransactionStore {
 
             // various indexes
             system_ttl_index: TTLIndex::new(Box::new(|t: &MempoolTransaction| t.expiration_time)),
+            shadow_system_ttl_index,
             expiration_time_index: TTLIndex::new(Box::new(|t: &MempoolTransaction| {
                 Duration::from_secs(t.txn.expiration_timestamp_secs())
             })),
+            insertion_time_index: InsertionTimeIndex::new(),
             priority_index: PriorityIndex::new(),
             timeline_index,
             num_sender_buckets: config.num_sender_buckets,
             parking_lot_index: ParkingLotIndex::new(),
             hash_index: HashMap::new(),
+            rejected_transaction_index: RejectedTransactionIndex::new(
+                config.max_rejected_transactions_to_retain,
+            ),
             // estimated size in bytes
             size_bytes: 0,
+            num_system_ttl_evictions: 0,
+
+            // metrics
+            shard_label: None,
+            track_index_sizes: config.num_mempool_shards <= 1,
 
             // configuration
             capacity: config.capacity,
             capacity_bytes: config.capacity_bytes,
             capacity_per_user: config.capacity_per_user,
             max_batch_bytes: config.shared_mempool_max_batch_bytes,
+            broadcast_lanes,
 
             // eager expiration
             eager_expire_threshold: config.eager_expire_threshold_ms.map(Duration::from_millis),
@@ -130,6 +200,14 @@ impl TransactionStore {
         }
     }
 
+    /// Creates a store that backs the given shard of a sharded core mempool
+    pub(crate) fn new_shard(config: &MempoolConfig, shard_id: usize) -> Self {
+        Self {
+            shard_label: Some(shard_id.to_string()),
+            ..Self::new(config)
+        }
+    }
+
     #[inline]
     fn get_mempool_txn(
         &self,
@@ -153,18 +231,6 @@ impl TransactionStore {
         None
     }
 
-    /// Fetch transaction by account address + sequence_number, including ranking score
-    pub(crate) fn get_with_ranking_score(
-        &self,
-        address: &AccountAddress,
-        sequence_number: u64,
-    ) -> Option<(SignedTransaction, u64)> {
-        if let Some(txn) = self.get_mempool_txn(address, sequence_number) {
-            return Some((txn.txn.clone(), txn.ranking_score));
-        }
-        None
-    }
-
     pub(crate) fn get_by_hash(&self, hash: HashValue) -> Option<SignedTransaction> {
         match self.hash_index.get(&hash) {
             Some((address, seq)) => self.get(address, *seq),
@@ -172,6 +238,33 @@ impl TransactionStore {
         }
     }
 
+    /// Returns the mempool status of the transaction with the given hash
+    pub(crate) fn get_status_by_hash(&self, hash: HashValue) -> MempoolTransactionStatus {
+        let txn = self
+            .hash_index
+            .get(&hash)
+            .and_then(|(address, seq)| self.get_mempool_txn(address, *seq));
+        match txn {
+            Some(txn) => {
+                let info = MempoolTransactionInfo {
+                    ranking_score: txn.ranking_score,
+                    time_in_mempool: SystemTime::now()
+                        .duration_since(txn.insertion_info.insertion_time)
+                        .unwrap_or(Duration::ZERO),
+                };
+                if self.priority_index.contains(txn) {
+                    MempoolTransactionStatus::Pending(info)
+                } else {
+                    MempoolTransactionStatus::Parked(info)
+                }
+            },
+            None => match self.rejected_transaction_index.get(&hash) {
+                Some(reason) => MempoolTransactionStatus::Rejected(reason),
+                None => MempoolTransactionStatus::Unknown,
+            },
+        }
+    }
+
     pub(crate) fn get_insertion_info_and_bucket(
         &self,
         address: &AccountAddress,
@@ -263,7 +356,8 @@ impl TransactionStore {
                     // If the transaction is the same, it's an idempotent call
                     // Updating signers is not supported, the previous submission must fail
                     counters::CORE_MEMPOOL_IDEMPOTENT_TXNS.inc();
-                    return MempoolStatus::new(MempoolStatusCode::Accepted);
+                    return MempoolStatus::new(MempoolStatusCode::Accepted)
+                        .with_message(IDEMPOTENT_TRANSACTION_MESSAGE.to_string());
                 }
             }
         }
@@ -294,7 +388,13 @@ impl TransactionStore {
 
             // insert into storage and other indexes
             self.system_ttl_index.insert(&txn);
+            if let Some(shadow_system_ttl_index) = &mut self.shadow_system_ttl_index {
+                shadow_system_ttl_index.insert(&txn);
+            }
             self.expiration_time_index.insert(&txn);
+            self.insertion_time_index.insert(&txn);
+            self.rejected_transaction_index
+                .remove(&txn.get_committed_hash());
             self.hash_index
                 .insert(txn.get_committed_hash(), (txn.get_sender(), txn_seq_num));
             self.sequence_numbers.insert(txn.get_sender(), acc_seq_num);
@@ -307,6 +407,19 @@ impl TransactionStore {
     }
 
     fn track_indices(&self) {
+        if let Some(shard_label) = &self.shard_label {
+            counters::core_mempool_shard_occupancy(
+                shard_label,
+                self.system_ttl_index.size(),
+                self.size_bytes,
+            );
+        }
+
+        // The index gauges are not labeled by shard, so only track them when unsharded
+        if !self.track_index_sizes {
+            return;
+        }
+
         counters::core_mempool_index_size(
             counters::SYSTEM_TTL_INDEX_LABEL,
             self.system_ttl_index.size(),
@@ -315,6 +428,10 @@ impl TransactionStore {
             counters::EXPIRATION_TIME_INDEX_LABEL,
             self.expiration_time_index.size(),
         );
+        counters::core_mempool_index_size(
+            counters::INSERTION_TIME_INDEX_LABEL,
+            self.insertion_time_index.size(),
+        );
         counters::core_mempool_index_size(
             counters::PRIORITY_INDEX_LABEL,
             self.priority_index.size(),
@@ -464,8 +581,11 @@ impl TransactionStore {
     ///   should be included in both the PriorityIndex (ordering for Consensus) and
     ///   TimelineIndex (txns for SharedMempool).
     /// - Other txns are considered to be "non-ready" and should be added to ParkingLotIndex.
-    fn process_ready_transactions(&mut self, address: &AccountAddress, sequence_num: u64) {
+    ///
+    /// Returns the number of txns that were promoted out of the ParkingLotIndex.
+    fn process_ready_transactions(&mut self, address: &AccountAddress, sequence_num: u64) -> usize {
         let sender_bucket = sender_bucket(address, self.num_sender_buckets);
+        let mut num_promoted_txns = 0;
         if let Some(txns) = self.transactions.get_mut(address) {
             let mut min_seq = sequence_num;
 
@@ -505,6 +625,12 @@ impl TransactionStore {
 
                 // Remove txn from parking lot after it has been promoted to
                 // priority_index / timeline_index, i.e., txn status is ready.
+                if self
+                    .parking_lot_index
+                    .contains(address, min_seq, txn.get_committed_hash())
+                {
+                    num_promoted_txns += 1;
+                }
                 self.parking_lot_index.remove(txn);
                 min_seq += 1;
             }
@@ -528,6 +654,7 @@ impl TransactionStore {
             );
             self.track_indices();
         }
+        num_promoted_txns
     }
 
     fn clean_committed_transactions(&mut self, address: &AccountAddress, sequence_number: u64) {
@@ -565,23 +692,59 @@ impl TransactionStore {
     /// It includes deletion of all transactions with sequence number <= `account_sequence_number`
     /// and potential promotion of sequential txns to PriorityIndex/TimelineIndex.
     pub fn commit_transaction(&mut self, account: &AccountAddress, sequence_number: u64) {
+        let new_seq_number = self.commit_sequence_number(account, sequence_number);
+        self.process_ready_transactions(account, new_seq_number);
+    }
+
+    /// Handles the commit of a block of transactions.
+    /// All committed transactions are removed first, and then a single revalidation pass
+    /// re-checks the parked transactions of every sender touched by the block, promoting
+    /// those that are now ready (i.e., the sequence number gap was filled). Returns the
+    /// number of promoted transactions.
+    pub fn commit_transactions(&mut self, transactions: &[(AccountAddress, u64)]) -> usize {
+        let mut touched_senders = HashSet::new();
+        for (account, sequence_number) in transactions {
+            self.commit_sequence_number(account, *sequence_number);
+            touched_senders.insert(*account);
+        }
+
+        touched_senders
+            .iter()
+            .map(|account| {
+                let sequence_number = self.get_sequence_number(account).map_or(0, |v| *v);
+                self.process_ready_transactions(account, sequence_number)
+            })
+            .sum()
+    }
+
+    /// Updates the sequence number of the account after a commit, and removes all
+    /// committed transactions. Returns the new sequence number of the account.
+    fn commit_sequence_number(&mut self, account: &AccountAddress, sequence_number: u64) -> u64 {
         let current_seq_number = self.get_sequence_number(account).map_or(0, |v| *v);
         let new_seq_number = max(current_seq_number, sequence_number + 1);
         self.sequence_numbers.insert(*account, new_seq_number);
         self.clean_committed_transactions(account, new_seq_number);
-        self.process_ready_transactions(account, new_seq_number);
+        new_seq_number
     }
 
+    /// Handles transaction rejection.
+    /// The transaction is removed (if it's still in mempool) and the rejection reason is
+    /// retained, so that it can be reported via [`TransactionStore::get_status_by_hash`].
     pub fn reject_transaction(
         &mut self,
         account: &AccountAddress,
         sequence_number: u64,
         hash: &HashValue,
+        reason: &DiscardedVMStatus,
     ) {
         let mut txn_to_remove = None;
+        let mut retain_reason = true;
         if let Some((indexed_account, indexed_sequence_number)) = self.hash_index.get(hash) {
             if account == indexed_account && sequence_number == *indexed_sequence_number {
                 txn_to_remove = self.get_mempool_txn(account, sequence_number).cloned();
+            } else {
+                // The hash belongs to a different transaction, so ignore the rejection
+                retain_reason = false;
             }
         }
         if let Some(txn_to_remove) = txn_to_remove {
@@ -599,13 +762,20 @@ impl TransactionStore {
                 trace!(LogSchema::new(LogEntry::CleanRejectedTxn).txns(txns_log));
             }
         }
+        if retain_reason {
+            self.rejected_transaction_index.insert(*hash, *reason);
+        }
     }
 
     /// Removes transaction from all indexes. Only call after removing from main transactions DS.
     fn index_remove(&mut self, txn: &MempoolTransaction) {
         counters::CORE_MEMPOOL_REMOVED_TXNS.inc();
         self.system_ttl_index.remove(txn);
+        if let Some(shadow_system_ttl_index) = &mut self.shadow_system_ttl_index {
+            shadow_system_ttl_index.remove(txn);
+        }
         self.expiration_time_index.remove(txn);
+        self.insertion_time_index.remove(txn);
         self.priority_index.remove(txn);
         let sender_bucket = sender_bucket(&txn.get_sender(), self.num_sender_buckets);
         self.timeline_index
@@ -650,21 +820,29 @@ impl TransactionStore {
         let mut batch_total_bytes: u64 = 0;
         let mut last_timeline_id = timeline_id.id_per_bucket.clone();
 
-        // Add as many transactions to the batch as possible
-        for (i, bucket) in self
-            .timeline_index
-            .get(&sender_bucket)
-            .unwrap_or_else(|| {
-                panic!(
-                    "Unable to get the timeline index for the sender bucket {}",
-                    sender_bucket
+        // Read the candidate transactions (from the broadcast lanes, if enabled)
+        let timeline_index = self.timeline_index.get(&sender_bucket).unwrap_or_else(|| {
+            panic!(
+                "Unable to get the timeline index for the sender bucket {}",
+                sender_bucket
+            )
+        });
+        let timeline = match self.broadcast_lanes {
+            Some((first_high_fee_bucket, high_fee_batch_percent)) => {
+                let high_fee_count = (count as u64 * high_fee_batch_percent / 100) as usize;
+                timeline_index.read_timeline_with_lanes(
+                    timeline_id,
+                    count,
+                    before,
+                    first_high_fee_bucket,
+                    high_fee_count,
                 )
-            })
-            .read_timeline(timeline_id, count, before)
-            .iter()
-            .enumerate()
-            .rev()
-        {
+            },
+            None => timeline_index.read_timeline(timeline_id, count, before),
+        };
+
+        // Add as many transactions to the batch as possible
+        for (i, bucket) in timeline.iter().enumerate().rev() {
             for (address, sequence_number) in bucket {
                 if let Some(txn) = self.get_mempool_txn(address, *sequence_number) {
                     let transaction_bytes = txn.txn.raw_txn_bytes_len() as u64;
@@ -735,6 +913,32 @@ impl TransactionStore {
             .collect()
     }
 
+    /// Returns the earliest expiration time (in secs) of the transactions in the given timeline
+    /// range, without cloning them. Returns `None` if none of them are still in mempool.
+    pub(crate) fn timeline_range_min_expiration_secs(
+        &self,
+        sender_bucket: MempoolSenderBucket,
+        start_end_pairs: HashMap<TimelineIndexIdentifier, (u64, u64)>,
+    ) -> Option<u64> {
+        self.timeline_index
+            .get(&sender_bucket)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Unable to get the timeline index for the sender bucket {}",
+                    sender_bucket
+                )
+            })
+            .timeline_range(start_end_pairs)
+            .iter()
+            .filter_map(|(account, sequence_number)| {
+                self.transactions
+                    .get(account)
+                    .and_then(|txns| txns.get(sequence_number))
+                    .map(|txn| txn.txn.expiration_timestamp_secs())
+            })
+            .min()
+    }
+
     /// If the oldest transaction (that never entered parking lot) is larger than
     /// eager_expire_threshold, there is significant backlog so add eager_expire_time
     fn eager_expire_time(&self, gc_time: Duration) -> Duration {
@@ -793,6 +997,9 @@ impl TransactionStore {
         counters::CORE_MEMPOOL_GC_EVENT_COUNT
             .with_label_values(&[metric_label])
             .inc();
+        if by_system_ttl {
+            self.num_system_ttl_evictions = 0;
+        }
 
         let mut gc_txns = index.gc(now);
         // sort the expired txns by order of sequence number per account
@@ -850,6 +1057,9 @@ impl TransactionStore {
 
                     // remove txn
                     self.index_remove(&txn);
+                    if by_system_ttl {
+                        self.num_system_ttl_evictions += 1;
+                    }
                 }
             }
         }
@@ -890,6 +1100,161 @@ impl TransactionStore {
         txns_log
     }
 
+    /// Compares the shadow system TTLs (if any) against the active system TTLs. Returns the
+    /// transactions that expired under the shadow TTLs since `last_evaluation` (but are kept
+    /// by the active TTLs at `now`), and the transactions that expired under the active TTLs
+    /// (but would be kept by the shadow TTLs at `now`). Only the expired transactions are
+    /// visited (via the TTL indexes), so this doesn't scan the whole store.
+    pub(crate) fn get_shadow_eviction_divergence(
+        &self,
+        last_evaluation: Duration,
+        now: Duration,
+    ) -> Option<(TxnsLog, TxnsLog)> {
+        let shadow_system_ttl_index = self.shadow_system_ttl_index.as_ref()?;
+
+        // Identify the transactions the shadow TTLs would have evicted (but are kept)
+        let mut would_have_evicted_txns = TxnsLog::new_with_max(10);
+        for key in shadow_system_ttl_index.iter_expiring_between(last_evaluation, now) {
+            if let Some(txn) = self.get_mempool_txn(&key.address, key.sequence_number) {
+                if txn.expiration_time >= now {
+                    would_have_evicted_txns.add(key.address, key.sequence_number);
+                }
+            }
+        }
+
+        // Identify the transactions about to be evicted that the shadow TTLs would have kept
+        let mut would_have_kept_txns = TxnsLog::new_with_max(10);
+        for key in self
+            .system_ttl_index
+            .iter_expiring_between(Duration::ZERO, now)
+        {
+            if let Some(txn) = self.get_mempool_txn(&key.address, key.sequence_number) {
+                if shadow_system_ttl_index.get_expiration_time(txn) >= now {
+                    would_have_kept_txns.add(key.address, key.sequence_number);
+                }
+            }
+        }
+
+        Some((would_have_evicted_txns, would_have_kept_txns))
+    }
+
+    /// Returns (up to `limit`) transactions that match the given request, in insertion order.
+    /// At most `MAX_PENDING_TRANSACTIONS_SCANNED` transactions are scanned for each request
+    /// (to bound the time the mempool lock is held). If the scan stops early, the cursor of
+    /// the first transaction that wasn't scanned is also returned (to resume the scan from).
+    pub(crate) fn get_pending_transactions(
+        &self,
+        request: &PendingTransactionsRequest,
+        limit: usize,
+    ) -> (Vec<PendingTransaction>, Option<PendingTransactionsCursor>) {
+        // If the request is for a single sender, only scan the sender's transactions
+        if let Some(sender) = request.filter.sender {
+            let mut keys: Vec<_> = self
+                .transactions
+                .get(&sender)
+                .map(|txns| {
+                    txns.values()
+                        .map(InsertionTimeIndex::make_key)
+                        .filter(|key| request.start.map_or(true, |start| *key >= start))
+                        .collect()
+                })
+                .unwrap_or_default();
+            keys.sort();
+            let transactions = keys
+                .iter()
+                .filter_map(|key| self.get_pending_transaction(request, key))
+                .take(limit)
+                .collect();
+            return (transactions, None);
+        }
+
+        let mut transactions = vec![];
+        for (num_scanned, key) in self
+            .insertion_time_index
+            .iter_from(request.start.as_ref())
+            .enumerate()
+        {
+            if transactions.len() >= limit {
+                break;
+            }
+            if num_scanned >= MAX_PENDING_TRANSACTIONS_SCANNED {
+                return (transactions, Some(*key));
+            }
+            transactions.extend(self.get_pending_transaction(request, key));
+        }
+        (transactions, None)
+    }
+
+    /// Returns the summary of the transaction with the given key, if it matches the request
+    fn get_pending_transaction(
+        &self,
+        request: &PendingTransactionsRequest,
+        key: &PendingTransactionsCursor,
+    ) -> Option<PendingTransaction> {
+        let filter = &request.filter;
+        let txn = self.get_mempool_txn(&key.sender, key.sequence_number)?;
+        let parked = !self.priority_index.contains(txn);
+        let gas_unit_price = txn.txn.gas_unit_price();
+        if (filter.parked_only && !parked)
+            || filter
+                .min_gas_unit_price
+                .map_or(false, |min_gas_unit_price| {
+                    gas_unit_price < min_gas_unit_price
+                })
+        {
+            return None;
+        }
+
+        let payload = txn.txn.payload();
+        Some(PendingTransaction {
+            cursor: *key,
+            hash: txn.get_committed_hash(),
+            gas_unit_price,
+            max_gas_amount: txn.txn.max_gas_amount(),
+            expiration_timestamp_secs: txn.txn.expiration_timestamp_secs(),
+            parked,
+            payload_size_bytes: bcs::serialized_size(payload).unwrap_or_default(),
+            payload: request.include_payloads.then(|| payload.clone()),
+        })
+    }
+
+    /// Returns the hashes of (up to `max_hashes` of) the most recently inserted transactions
+    /// in the store, newest first. These are the transactions most likely to still be
+    /// broadcast by peers (e.g., when building a transaction filter).
+    pub(crate) fn get_transaction_hashes(&self, max_hashes: usize) -> Vec<HashValue> {
+        self.insertion_time_index
+            .iter_newest_first()
+            .filter_map(|key| {
+                self.get_mempool_txn(&key.sender, key.sequence_number)
+                    .map(|txn| txn.get_committed_hash())
+            })
+            .take(max_hashes)
+            .collect()
+    }
+
+    /// Returns the load statistics of the store (the load level is not derived here)
+    pub(crate) fn get_load_state(&self) -> MempoolLoadState {
+        let next_system_ttl_eviction = self.system_ttl_index.iter().next().map(|key| {
+            key.expiration_time
+                .saturating_sub(aptos_infallible::duration_since_epoch())
+        });
+        MempoolLoadState {
+            num_transactions: self.system_ttl_index.size(),
+            capacity: self.capacity,
+            size_bytes: self.size_bytes,
+            capacity_bytes: self.capacity_bytes,
+            num_parked_transactions: self.parking_lot_index.size(),
+            num_system_ttl_evictions: self.num_system_ttl_evictions,
+            next_system_ttl_eviction,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the number of transactions in the store
+    pub(crate) fn get_num_transactions(&self) -> usize {
+        self.system_ttl_index.size()
+    }
+
     #[cfg(test)]
     pub(crate) fn get_parking_lot_size(&self) -> usize {
         self.parking_lot_index.size()
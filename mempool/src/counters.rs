@@ -17,11 +17,20 @@ use std::time::Duration;
 pub const PRIORITY_INDEX_LABEL: &str = "priority";
 pub const EXPIRATION_TIME_INDEX_LABEL: &str = "expiration";
 pub const SYSTEM_TTL_INDEX_LABEL: &str = "system_ttl";
+pub const INSERTION_TIME_INDEX_LABEL: &str = "insertion_time";
 pub const TIMELINE_INDEX_LABEL: &str = "timeline";
 pub const PARKING_LOT_INDEX_LABEL: &str = "parking_lot";
 pub const TRANSACTION_HASH_INDEX_LABEL: &str = "transaction_hash";
 pub const SIZE_BYTES_LABEL: &str = "size_bytes";
 
+// Peer priority set labels
+pub const TOP_1_PEER_LABEL: &str = "top_1";
+pub const TOP_K_PEERS_LABEL: &str = "top_k";
+pub const ALL_PEERS_LABEL: &str = "all";
+
+// Core mempool shard occupancy labels
+pub const SHARD_TXNS_LABEL: &str = "txns";
+
 // Core mempool stages labels
 pub const BROADCAST_RECEIVED_LABEL: &str = "broadcast_received";
 pub const COMMIT_ACCEPTED_LABEL: &str = "commit_accepted";
@@ -29,6 +38,7 @@ pub const COMMIT_ACCEPTED_BLOCK_LABEL: &str = "commit_accepted_block";
 pub const COMMIT_REJECTED_LABEL: &str = "commit_rejected";
 pub const COMMIT_REJECTED_DUPLICATE_LABEL: &str = "commit_rejected_duplicate";
 pub const COMMIT_IGNORED_LABEL: &str = "commit_ignored";
+pub const COMMIT_BLOCK_CUT_LABEL: &str = "commit_block_cut";
 pub const CONSENSUS_READY_LABEL: &str = "consensus_ready";
 pub const CONSENSUS_PULLED_LABEL: &str = "consensus_pulled";
 pub const BROADCAST_READY_LABEL: &str = "broadcast_ready";
@@ -40,10 +50,18 @@ pub const NON_PARKED_COMMIT_ACCEPTED_LABEL: &str = "non_park_commit_accepted";
 pub const GC_SYSTEM_TTL_LABEL: &str = "system_ttl";
 pub const GC_CLIENT_EXP_LABEL: &str = "client_expiration";
 
+// Core mempool shadow eviction divergence labels
+pub const SHADOW_WOULD_HAVE_EVICTED_LABEL: &str = "would_have_evicted";
+pub const SHADOW_WOULD_HAVE_KEPT_LABEL: &str = "would_have_kept";
+
 // Core mempool GC txn status label
 pub const GC_ACTIVE_TXN_LABEL: &str = "active";
 pub const GC_PARKED_TXN_LABEL: &str = "parked";
 
+// Core mempool journal operation labels
+pub const JOURNAL_APPEND_LABEL: &str = "append";
+pub const JOURNAL_COMPACT_LABEL: &str = "compact";
+
 // Mempool service request type labels
 pub const GET_BLOCK_LABEL: &str = "get_block";
 pub const GET_BLOCK_LOCK_LABEL: &str = "get_block_lock";
@@ -67,8 +85,12 @@ pub const SUCCESS_LABEL: &str = "success";
 // Bounded executor task labels
 pub const CLIENT_EVENT_LABEL: &str = "client_event";
 pub const CLIENT_EVENT_GET_TXN_LABEL: &str = "client_event_get_txn";
+pub const CLIENT_EVENT_GET_TXN_STATUSES_LABEL: &str = "client_event_get_txn_statuses";
+pub const CLIENT_EVENT_GET_PENDING_TXNS_LABEL: &str = "client_event_get_pending_txns";
 pub const RECONFIG_EVENT_LABEL: &str = "reconfig";
 pub const PEER_BROADCAST_EVENT_LABEL: &str = "peer_broadcast";
+pub const PEER_TRANSACTION_REQUEST_EVENT_LABEL: &str = "peer_transaction_request";
+pub const PEER_TRANSACTION_RESPONSE_EVENT_LABEL: &str = "peer_transaction_response";
 
 // task spawn stage labels
 pub const SPAWN_LABEL: &str = "spawn";
@@ -77,12 +99,39 @@ pub const START_LABEL: &str = "start";
 // Mempool network msg failure type labels:
 pub const BROADCAST_TXNS: &str = "broadcast_txns";
 pub const ACK_TXNS: &str = "ack_txns";
+pub const TRANSACTION_FILTER: &str = "transaction_filter";
+pub const TRANSACTION_FETCH: &str = "transaction_fetch";
 
 // Broadcast/ACK type labels
 pub const EXPIRED_BROADCAST_LABEL: &str = "expired";
 pub const RETRY_BROADCAST_LABEL: &str = "retry";
 pub const BACKPRESSURE_BROADCAST_LABEL: &str = "backpressure";
 
+// Broadcast delivery receipt labels
+pub const DELIVERED_NEW_LABEL: &str = "new";
+pub const DELIVERED_DUPLICATE_LABEL: &str = "duplicate";
+
+// Transaction filter event labels
+pub const FILTER_SENT_LABEL: &str = "sent";
+pub const FILTER_RECEIVED_LABEL: &str = "received";
+pub const FILTER_INVALID_LABEL: &str = "invalid";
+
+// Transaction fetch event labels
+pub const FETCH_REQUEST_SENT_LABEL: &str = "request_sent";
+pub const FETCH_REQUEST_RECEIVED_LABEL: &str = "request_received";
+pub const FETCH_RESPONSE_SENT_LABEL: &str = "response_sent";
+pub const FETCH_RESPONSE_RECEIVED_LABEL: &str = "response_received";
+pub const FETCH_UNREQUESTED_TRANSACTION_LABEL: &str = "unrequested_transaction";
+
+// Broadcast serialization cache event labels
+pub const SERIALIZATION_CACHE_HIT_LABEL: &str = "hit";
+pub const SERIALIZATION_CACHE_MISS_LABEL: &str = "miss";
+pub const SERIALIZATION_CACHE_EVICTED_LABEL: &str = "evicted";
+
+// Peer misbehavior event labels
+pub const PEER_PENALIZED_LABEL: &str = "penalized";
+pub const PEER_DISCONNECTED_LABEL: &str = "disconnected";
+
 // ACK direction labels
 pub const RECEIVED_LABEL: &str = "received";
 pub const SENT_LABEL: &str = "sent";
@@ -141,6 +190,33 @@ pub fn core_mempool_index_size(label: &'static str, size: usize) {
         .set(size as i64)
 }
 
+/// Gauge tracking the load level of core mempool (0 = healthy, 1 = degraded, 2 = full)
+pub static CORE_MEMPOOL_LOAD_LEVEL: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_core_mempool_load_level",
+        "Load level of core mempool (0 = healthy, 1 = degraded, 2 = full)"
+    )
+    .unwrap()
+});
+
+pub static CORE_MEMPOOL_SHARD_OCCUPANCY: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_core_mempool_shard_occupancy",
+        "Number of transactions and estimated bytes held by each core mempool shard",
+        &["shard", "type"]
+    )
+    .unwrap()
+});
+
+pub fn core_mempool_shard_occupancy(shard: &str, num_txns: usize, num_bytes: usize) {
+    CORE_MEMPOOL_SHARD_OCCUPANCY
+        .with_label_values(&[shard, SHARD_TXNS_LABEL])
+        .set(num_txns as i64);
+    CORE_MEMPOOL_SHARD_OCCUPANCY
+        .with_label_values(&[shard, SIZE_BYTES_LABEL])
+        .set(num_bytes as i64);
+}
+
 pub static SENDER_BUCKET_FREQUENCIES: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "aptos_core_mempool_sender_bucket_frequencies",
@@ -195,6 +271,26 @@ pub static CORE_MEMPOOL_GAS_UPGRADED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter tracking the number of failed mempool transaction journal operations
+pub static CORE_MEMPOOL_JOURNAL_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_core_mempool_journal_errors",
+        "Number of failed mempool transaction journal operations",
+        &["operation"]
+    )
+    .unwrap()
+});
+
+/// Counter tracking the number of journaled txns replayed on startup (by mempool status)
+pub static CORE_MEMPOOL_JOURNAL_REPLAYED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_core_mempool_journal_replayed_txns",
+        "Number of journaled txns replayed on startup (by mempool status)",
+        &["status"]
+    )
+    .unwrap()
+});
+
 pub fn core_mempool_txn_commit_latency(
     stage: &'static str,
     submitted_by: &'static str,
@@ -296,6 +392,23 @@ pub static CORE_MEMPOOL_GC_EAGER_EXPIRE_EVENT_COUNT: Lazy<IntCounter> = Lazy::ne
         .unwrap()
 });
 
+/// Counter tracking the number of transactions that the shadow eviction policy would have
+/// evicted (but the active policy kept), and vice versa
+static CORE_MEMPOOL_SHADOW_EVICTION_DIVERGENCE: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_core_mempool_shadow_eviction_divergence",
+        "Number of txns the shadow eviction policy would have evicted or kept differently from the active policy",
+        &["type"]
+    )
+    .unwrap()
+});
+
+pub fn core_mempool_shadow_eviction_divergence(divergence_label: &str, num_txns: usize) {
+    CORE_MEMPOOL_SHADOW_EVICTION_DIVERGENCE
+        .with_label_values(&[divergence_label])
+        .inc_by(num_txns as u64);
+}
+
 /// Counter tracking time for how long a transaction stayed in core-mempool before being garbage-collected
 pub static CORE_MEMPOOL_GC_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -344,6 +457,16 @@ pub fn mempool_service_transactions(label: &'static str, num: usize) {
         .observe(num as f64)
 }
 
+/// Histogram for the number of parked transactions promoted (i.e., that became ready) per committed block
+pub static CORE_MEMPOOL_PARKED_TXNS_PROMOTED_PER_BLOCK: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_core_mempool_parked_txns_promoted_per_block",
+        "Number of parked transactions promoted to ready per committed block",
+        TRANSACTION_COUNT_BUCKETS.clone()
+    )
+    .unwrap()
+});
+
 /// Histogram for the byte size of transactions processed in get_block
 pub static MEMPOOL_SERVICE_BYTES_GET_BLOCK: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
@@ -428,6 +551,38 @@ pub fn process_get_txn_latency_timer_client() -> HistogramTimer {
         .start_timer()
 }
 
+/// Counter for tracking e2e latency for mempool to process get txn statuses by hash requests from clients
+static PROCESS_GET_TXN_STATUSES_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_shared_mempool_get_txn_statuses_request_latency",
+        "Latency of mempool processing get txn statuses by hash requests",
+        &["network"]
+    )
+    .unwrap()
+});
+
+pub fn process_get_txn_statuses_latency_timer_client() -> HistogramTimer {
+    PROCESS_GET_TXN_STATUSES_LATENCY
+        .with_label_values(&[CLIENT_LABEL])
+        .start_timer()
+}
+
+/// Counter for tracking e2e latency for mempool to process pending txn listing requests from clients
+static PROCESS_GET_PENDING_TXNS_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_shared_mempool_get_pending_txns_request_latency",
+        "Latency of mempool processing pending txn listing requests",
+        &["network"]
+    )
+    .unwrap()
+});
+
+pub fn process_get_pending_txns_latency_timer_client() -> HistogramTimer {
+    PROCESS_GET_PENDING_TXNS_LATENCY
+        .with_label_values(&[CLIENT_LABEL])
+        .start_timer()
+}
+
 /// Tracks latency of different stages of txn processing (e.g. vm validation, storage read)
 pub static PROCESS_TXN_BREAKDOWN_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -481,6 +636,48 @@ pub fn shared_mempool_pending_broadcasts(peer: &PeerNetworkId) -> IntGauge {
     ])
 }
 
+static SHARED_MEMPOOL_BROADCAST_BATCH_SIZE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_shared_mempool_broadcast_batch_size",
+        "The adaptive broadcast batch size of each peer",
+        &["network", "recipient"]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_broadcast_batch_size(peer: &PeerNetworkId) -> IntGauge {
+    SHARED_MEMPOOL_BROADCAST_BATCH_SIZE.with_label_values(&[
+        peer.network_id().as_str(),
+        peer.peer_id().short_str().as_str(),
+    ])
+}
+
+/// Counter tracking the transactions delivered to each peer (as reported by the
+/// broadcast delivery receipts), i.e., the transactions that were new to the peer,
+/// and the transactions that the peer already had.
+static SHARED_MEMPOOL_BROADCAST_DELIVERED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_shared_mempool_broadcast_delivered_txns",
+        "Number of broadcast transactions delivered to each peer (by delivery type)",
+        &["network", "recipient", "type"]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_broadcast_delivered_txns_inc(
+    peer: &PeerNetworkId,
+    delivery_type: &'static str,
+    num_txns: u64,
+) {
+    SHARED_MEMPOOL_BROADCAST_DELIVERED_TXNS
+        .with_label_values(&[
+            peer.network_id().as_str(),
+            peer.peer_id().short_str().as_str(),
+            delivery_type,
+        ])
+        .inc_by(num_txns);
+}
+
 /// Counter tracking the number of peers that changed priority in shared mempool
 pub static SHARED_MEMPOOL_PRIORITY_CHANGE_COUNT: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -494,6 +691,80 @@ pub fn shared_mempool_priority_change_count(change_count: i64) {
     SHARED_MEMPOOL_PRIORITY_CHANGE_COUNT.set(change_count);
 }
 
+/// Counter tracking the number of times the top-1 peer and the set of top-k peers changed
+static SHARED_MEMPOOL_PRIORITY_SET_CHANGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_shared_mempool_priority_set_changes",
+        "Number of times the top-1 peer or the set of top-k peers changed in shared mempool",
+        &["set"]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_priority_set_changed(set_label: &str) {
+    SHARED_MEMPOOL_PRIORITY_SET_CHANGES
+        .with_label_values(&[set_label])
+        .inc();
+}
+
+/// Gauge tracking the divergence of the shadow prioritization strategies from the active
+/// prioritization (i.e., whether the top-1 peer differs, and the number of top-k and all
+/// peers that differ), at the last priority update.
+static SHARED_MEMPOOL_SHADOW_PRIORITY_DIVERGENCE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_shared_mempool_shadow_priority_divergence",
+        "Divergence of the shadow prioritization strategies from the active prioritization",
+        &["strategy", "set"]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_shadow_priority_divergence(
+    strategy_label: &str,
+    set_label: &str,
+    divergence: usize,
+) {
+    SHARED_MEMPOOL_SHADOW_PRIORITY_DIVERGENCE
+        .with_label_values(&[strategy_label, set_label])
+        .set(divergence as i64);
+}
+
+/// Gauge tracking the time (in seconds) since peer priorities were last updated
+pub static SHARED_MEMPOOL_SECS_SINCE_PRIORITY_UPDATE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_shared_mempool_secs_since_priority_update",
+        "Time (in seconds) since peer priorities were last updated in shared mempool",
+    )
+    .unwrap()
+});
+
+/// Gauge tracking the number of peers without an observed ping latency (at the last priority update)
+pub static SHARED_MEMPOOL_PEERS_WITHOUT_PING_LATENCY: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_shared_mempool_peers_without_ping_latency",
+        "Number of peers without an observed ping latency at the last priority update",
+    )
+    .unwrap()
+});
+
+/// Counter tracking the number of top peers that were replaced due to repeated broadcast failures
+pub static SHARED_MEMPOOL_BROADCAST_FAILOVER_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_shared_mempool_broadcast_failover_count",
+        "Number of top peers that were replaced due to repeated broadcast failures",
+    )
+    .unwrap()
+});
+
+/// Counter tracking the number of catch-up broadcasts scheduled for newly promoted peers
+pub static SHARED_MEMPOOL_PROMOTION_REBROADCAST_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_shared_mempool_promotion_rebroadcast_count",
+        "Number of catch-up broadcasts (i.e., the in-flight window) scheduled for newly promoted peers",
+    )
+    .unwrap()
+});
+
 static SHARED_MEMPOOL_TRANSACTIONS_PROCESSED: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_shared_mempool_transactions_processed",
@@ -543,6 +814,95 @@ pub fn shared_mempool_broadcast_type_inc(network_id: NetworkId, label: &str) {
         .inc();
 }
 
+/// Counter tracking the transaction filters sent to and received from peers
+static SHARED_MEMPOOL_TRANSACTION_FILTER_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_shared_mempool_transaction_filter_count",
+        "Number of transaction filters sent to and received from peers",
+        &["network", "type"]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_transaction_filter_inc(network_id: NetworkId, label: &str) {
+    SHARED_MEMPOOL_TRANSACTION_FILTER_COUNT
+        .with_label_values(&[network_id.as_str(), label])
+        .inc();
+}
+
+/// Counter tracking the transaction fetch requests and responses sent to and received from peers
+static SHARED_MEMPOOL_TRANSACTION_FETCH_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_shared_mempool_transaction_fetch_count",
+        "Number of transaction fetch requests and responses sent to and received from peers",
+        &["network", "type"]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_transaction_fetch_inc(network_id: NetworkId, label: &str) {
+    SHARED_MEMPOOL_TRANSACTION_FETCH_COUNT
+        .with_label_values(&[network_id.as_str(), label])
+        .inc();
+}
+
+/// Counter tracking the transactions not broadcast because the peer's filter holds them
+static SHARED_MEMPOOL_FILTERED_BROADCAST_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_shared_mempool_filtered_broadcast_txns",
+        "Number of transactions not broadcast because the peer's filter holds them",
+        &["network"]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_filtered_broadcast_txns_inc(network_id: NetworkId, num_txns: usize) {
+    SHARED_MEMPOOL_FILTERED_BROADCAST_TXNS
+        .with_label_values(&[network_id.as_str()])
+        .inc_by(num_txns as u64);
+}
+
+/// Counter tracking the peers penalized (and disconnected) for sending misbehaving transactions
+static SHARED_MEMPOOL_PEER_MISBEHAVIOR_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_shared_mempool_peer_misbehavior_count",
+        "Number of peers penalized (and disconnected) for sending invalid or expired transactions",
+        &["network", "type"]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_peer_misbehavior_inc(network_id: NetworkId, label: &str) {
+    SHARED_MEMPOOL_PEER_MISBEHAVIOR_COUNT
+        .with_label_values(&[network_id.as_str(), label])
+        .inc();
+}
+
+/// Counter tracking the hits, misses and evictions of the broadcast serialization cache
+static SHARED_MEMPOOL_SERIALIZATION_CACHE_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_shared_mempool_serialization_cache_count",
+        "Number of hits, misses and evictions of the broadcast serialization cache",
+        &["type"]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_serialization_cache_inc(label: &str) {
+    SHARED_MEMPOOL_SERIALIZATION_CACHE_COUNT
+        .with_label_values(&[label])
+        .inc();
+}
+
+/// Gauge tracking the number of bytes held by the broadcast serialization cache
+pub static SHARED_MEMPOOL_SERIALIZATION_CACHE_BYTES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_shared_mempool_serialization_cache_bytes",
+        "Number of bytes held by the broadcast serialization cache"
+    )
+    .unwrap()
+});
+
 static SHARED_MEMPOOL_ACK_TYPE_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_shared_mempool_ack_count",
@@ -58,16 +58,25 @@
 
 #[cfg(any(test, feature = "fuzzing"))]
 mod tests;
+#[cfg(any(test, feature = "fuzzing"))]
+pub use shared_mempool::PrioritizedPeersState;
 pub use shared_mempool::{
-    bootstrap, network,
+    bootstrap,
+    client_quotas::{ClientQuotaManager, ClientQuotaUsage},
+    load_state::{MempoolLoadLevel, MempoolLoadMonitor, MempoolLoadState},
+    network,
     network::MempoolSyncMsg,
+    transaction_filter::TransactionHashFilter,
     types::{
-        MempoolClientRequest, MempoolClientSender, MempoolEventsReceiver, QuorumStoreRequest,
-        QuorumStoreResponse, SubmissionStatus,
+        MempoolClientRequest, MempoolClientSender, MempoolEventsReceiver, MempoolTransactionInfo,
+        MempoolTransactionStatus, PendingTransaction, PendingTransactionsCursor,
+        PendingTransactionsFilter, PendingTransactionsPage, PendingTransactionsRequest,
+        QuorumStoreRequest, QuorumStoreResponse, SubmissionStatus,
     },
+    PrioritizedPeerInfo,
 };
 #[cfg(any(test, feature = "fuzzing"))]
-pub use tests::{fuzzing, mocks};
+pub use tests::{fuzzing, mocks, prioritization_replay};
 
 mod core_mempool;
 pub mod counters;
@@ -68,9 +68,22 @@ impl TxnsLog {
         self.len += 1;
     }
 
+    /// Appends the transactions of another log to this one
+    pub fn append(&mut self, other: TxnsLog) {
+        let num_displayable = self.max_displayed.saturating_sub(self.txns.len());
+        self.txns
+            .extend(other.txns.into_iter().take(num_displayable));
+        self.len += other.len;
+    }
+
     pub fn is_empty(&self) -> bool {
         self.len == 0
     }
+
+    /// Returns the number of transactions added to the log (including those not displayed)
+    pub fn len(&self) -> usize {
+        self.len
+    }
 }
 
 impl fmt::Display for TxnsLog {
@@ -156,6 +169,8 @@ pub enum LogEntry {
     ReconfigUpdate,
     JsonRpc,
     GetTransaction,
+    GetTransactionStatuses,
+    GetPendingTransactions,
     GetBlock,
     QuorumStore,
     StateSyncCommit,
@@ -173,6 +188,12 @@ pub enum LogEntry {
     DBError,
     UnexpectedNetworkMsg,
     MempoolSnapshot,
+    TransactionJournal,
+    TransactionFilter,
+    EvictionPolicy,
+    PrioritizationParams,
+    TransactionFetch,
+    PeerMisbehavior,
 }
 
 #[derive(Clone, Copy, Serialize)]
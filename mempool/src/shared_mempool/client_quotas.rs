@@ -0,0 +1,182 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-client quotas for transactions submitted by authenticated API clients.
+//! Each client identity is limited in its submission rate (using a token bucket)
+//! and in the number of its transactions that can be in mempool at any point.
+
+use crate::core_mempool::ShardedMempool;
+use aptos_config::config::ClientQuotaConfig;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::mempool_status::{MempoolStatus, MempoolStatusCode};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
+
+/// The usage counters of a single client (as reported via the admin service)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ClientQuotaUsage {
+    /// The number of transactions submitted by the client
+    pub num_submitted: u64,
+    /// The number of submitted transactions that were accepted into mempool
+    pub num_accepted: u64,
+    /// The number of submitted transactions rejected by the rate quota
+    pub num_rate_limited: u64,
+    /// The number of submitted transactions rejected by the occupancy quota
+    pub num_occupancy_limited: u64,
+    /// The number of the client's transactions currently in mempool
+    pub num_pending_transactions: usize,
+}
+
+/// The quota state of a single client
+struct ClientQuotaState {
+    available_submissions: f64, // The tokens currently available in the rate bucket
+    last_refill_time: Instant,
+    pending_transactions: HashSet<HashValue>, // The accepted transactions (by committed hash)
+    reserved_transactions: HashSet<HashValue>, // The transactions still being added to mempool
+    usage: ClientQuotaUsage,
+}
+
+impl ClientQuotaState {
+    fn new(config: &ClientQuotaConfig, now: Instant) -> Self {
+        Self {
+            available_submissions: config.max_submissions_per_sec as f64,
+            last_refill_time: now,
+            pending_transactions: HashSet::new(),
+            reserved_transactions: HashSet::new(),
+            usage: ClientQuotaUsage::default(),
+        }
+    }
+
+    /// Refills the rate bucket based on the time elapsed since the last refill.
+    /// The bucket holds at most one second worth of submissions.
+    fn refill(&mut self, config: &ClientQuotaConfig, now: Instant) {
+        let max_submissions = config.max_submissions_per_sec as f64;
+        let elapsed_secs = now
+            .saturating_duration_since(self.last_refill_time)
+            .as_secs_f64();
+        self.available_submissions =
+            (self.available_submissions + elapsed_secs * max_submissions).min(max_submissions);
+        self.last_refill_time = now;
+    }
+
+    /// Removes the transactions that are no longer in mempool (e.g., because
+    /// they were committed, rejected or expired).
+    fn prune_pending_transactions(&mut self, mempool: &ShardedMempool) {
+        self.pending_transactions
+            .retain(|hash| mempool.get_by_hash(*hash).is_some());
+        self.usage.num_pending_transactions = self.pending_transactions.len();
+    }
+
+    /// Returns the number of transactions that count towards the occupancy quota,
+    /// i.e., the pending transactions and those reserved by in-flight submissions.
+    fn num_occupied_slots(&self) -> usize {
+        self.pending_transactions.len() + self.reserved_transactions.len()
+    }
+}
+
+/// Enforces the per-client rate and occupancy quotas, and tracks the usage of each client
+pub struct ClientQuotaManager {
+    config: ClientQuotaConfig,
+    mempool: Arc<ShardedMempool>,
+    time_service: TimeService,
+    client_states: Mutex<HashMap<String, ClientQuotaState>>,
+}
+
+impl ClientQuotaManager {
+    pub(crate) fn new(
+        config: ClientQuotaConfig,
+        mempool: Arc<ShardedMempool>,
+        time_service: TimeService,
+    ) -> Self {
+        Self {
+            config,
+            mempool,
+            time_service,
+            client_states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Checks (and consumes) the quotas of the client for a new submission. If the
+    /// quotas allow it, an occupancy slot is reserved for the transaction (under the
+    /// same lock), so concurrent submissions can't exceed the occupancy quota. The
+    /// reservation must be released with [`Self::complete_submission`].
+    /// Returns the rejection status if any quota is exceeded.
+    pub(crate) fn check_quotas(
+        &self,
+        client: &str,
+        committed_hash: HashValue,
+    ) -> Result<(), MempoolStatus> {
+        let now = self.time_service.now();
+        let mut client_states = self.client_states.lock();
+        let state = client_states
+            .entry(client.to_string())
+            .or_insert_with(|| ClientQuotaState::new(&self.config, now));
+        state.usage.num_submitted += 1;
+
+        // Verify the submission rate quota
+        state.refill(&self.config, now);
+        if state.available_submissions < 1.0 {
+            state.usage.num_rate_limited += 1;
+            return Err(
+                MempoolStatus::new(MempoolStatusCode::TooManyTransactions).with_message(format!(
+                    "Client {} exceeded its quota of {} submissions per second",
+                    client, self.config.max_submissions_per_sec
+                )),
+            );
+        }
+
+        // Verify the occupancy quota (pruning is only needed once the quota is reached)
+        if state.num_occupied_slots() >= self.config.max_pending_transactions {
+            state.prune_pending_transactions(&self.mempool);
+            if state.num_occupied_slots() >= self.config.max_pending_transactions {
+                state.usage.num_occupancy_limited += 1;
+                return Err(MempoolStatus::new(MempoolStatusCode::TooManyTransactions)
+                    .with_message(format!(
+                        "Client {} exceeded its quota of {} transactions in mempool",
+                        client, self.config.max_pending_transactions
+                    )));
+            }
+        }
+
+        state.available_submissions -= 1.0;
+        state.reserved_transactions.insert(committed_hash);
+        Ok(())
+    }
+
+    /// Releases the occupancy slot reserved for the transaction by [`Self::check_quotas`].
+    /// If the transaction was accepted into mempool, it becomes a pending transaction.
+    pub(crate) fn complete_submission(
+        &self,
+        client: &str,
+        committed_hash: HashValue,
+        accepted: bool,
+    ) {
+        let mut client_states = self.client_states.lock();
+        if let Some(state) = client_states.get_mut(client) {
+            state.reserved_transactions.remove(&committed_hash);
+            if accepted {
+                state.usage.num_accepted += 1;
+                state.pending_transactions.insert(committed_hash);
+                state.usage.num_pending_transactions = state.pending_transactions.len();
+            }
+        }
+    }
+
+    /// Returns the usage of each client that has submitted transactions
+    pub fn get_usage(&self) -> BTreeMap<String, ClientQuotaUsage> {
+        let mut client_states = self.client_states.lock();
+        client_states
+            .iter_mut()
+            .map(|(client, state)| {
+                state.prune_pending_transactions(&self.mempool);
+                (client.clone(), state.usage.clone())
+            })
+            .collect()
+    }
+}
@@ -5,22 +5,28 @@
 //! Processes that are directly spawned by shared mempool runtime initialization
 use super::types::MempoolClientRequest;
 use crate::{
-    core_mempool::{CoreMempool, TimelineState},
+    core_mempool::{ShardedMempool, TimelineState},
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
-    network::{BroadcastPeerPriority, MempoolSyncMsg},
+    network::{BroadcastPeerPriority, MempoolNetworkInterface, MempoolSyncMsg},
     shared_mempool::{
+        load_state::MempoolLoadMonitor,
         tasks::{self, process_committed_transactions},
         types::{
             notify_subscribers, MempoolMessageId, ScheduledBroadcast, SharedMempool,
-            SharedMempoolNotification,
+            SharedMempoolNotification, SubmissionStatus,
         },
         use_case_history::UseCaseHistory,
     },
     MempoolEventsReceiver, QuorumStoreRequest,
 };
+use anyhow::Result;
 use aptos_bounded_executor::BoundedExecutor;
-use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_config::{
+    config::SubmissionBackpressureConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_crypto::HashValue;
 use aptos_event_notifications::ReconfigNotificationListener;
 use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
@@ -30,16 +36,19 @@ use aptos_network::{
         interface::{NetworkClientInterface, NetworkServiceEvents},
         storage::PeersAndMetadata,
     },
-    protocols::network::Event,
+    protocols::network::{Event, RpcError},
+    ProtocolId,
 };
+use aptos_peer_monitoring_service_types::MempoolUtilization;
 use aptos_types::{
     on_chain_config::{OnChainConfigPayload, OnChainConfigProvider},
     transaction::SignedTransaction,
     PeerId,
 };
 use aptos_vm_validator::vm_validator::TransactionValidation;
+use bytes::Bytes;
 use futures::{
-    channel::mpsc,
+    channel::{mpsc, oneshot},
     stream::{select_all, FuturesUnordered},
     FutureExt, StreamExt,
 };
@@ -51,7 +60,7 @@ use std::{
     time::{Duration, Instant, SystemTime},
 };
 use tokio::{runtime::Handle, time::interval};
-use tokio_stream::wrappers::IntervalStream;
+use tokio_stream::wrappers::{IntervalStream, ReceiverStream};
 
 /// Coordinator that handles inbound network events and outbound txn broadcasts.
 pub(crate) async fn coordinator<NetworkClient, TransactionValidator, ConfigProvider>(
@@ -85,6 +94,11 @@ pub(crate) async fn coordinator<NetworkClient, TransactionValidator, ConfigProvi
     let mut update_peers_interval =
         tokio::time::interval(Duration::from_millis(peer_update_interval_ms));
 
+    // Subscribe to peer monitoring updates, so that peer priorities can be updated as soon
+    // as the monitoring metadata changes (the update interval above acts as a fallback).
+    let mut monitoring_updates =
+        ReceiverStream::new(peers_and_metadata.subscribe_to_monitoring_updates()).fuse();
+
     // Spawn a dedicated task to handle commit notifications from state sync
     spawn_commit_notification_handler(&smp, mempool_listener);
 
@@ -123,7 +137,10 @@ pub(crate) async fn coordinator<NetworkClient, TransactionValidator, ConfigProvi
                 handle_network_event(&bounded_executor, &mut smp, network_id, event).await;
             },
             _ = update_peers_interval.tick().fuse() => {
-                handle_update_peers(peers_and_metadata.clone(), &mut smp, &mut scheduled_broadcasts, executor.clone()).await;
+                handle_update_peers(peers_and_metadata.clone(), &mut smp, &mut scheduled_broadcasts, executor.clone(), false).await;
+            },
+            peer = monitoring_updates.select_next_some() => {
+                handle_peer_monitoring_update(peers_and_metadata.clone(), &mut smp, &mut scheduled_broadcasts, executor.clone(), peer).await;
             },
             complete => break,
         }
@@ -163,6 +180,38 @@ fn spawn_commit_notification_handler<NetworkClient, TransactionValidator>(
     });
 }
 
+/// Spawn a task for processing a transaction submission from a client such as API service.
+/// If the submission is on behalf of an authenticated API client, `client` holds its identity.
+async fn handle_client_transaction_submission<NetworkClient, TransactionValidator>(
+    smp: &mut SharedMempool<NetworkClient, TransactionValidator>,
+    bounded_executor: &BoundedExecutor,
+    txn: SignedTransaction,
+    client: Option<String>,
+    callback: oneshot::Sender<Result<SubmissionStatus>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    // This timer measures how long it took for the bounded executor to *schedule* the
+    // task.
+    let _timer =
+        counters::task_spawn_latency_timer(counters::CLIENT_EVENT_LABEL, counters::SPAWN_LABEL);
+    // This timer measures how long it took for the task to go from scheduled to started.
+    let task_start_timer =
+        counters::task_spawn_latency_timer(counters::CLIENT_EVENT_LABEL, counters::START_LABEL);
+    smp.network_interface
+        .num_mempool_txns_received_since_peers_updated += 1;
+    bounded_executor
+        .spawn(tasks::process_client_transaction_submission(
+            smp.clone(),
+            txn,
+            client,
+            callback,
+            task_start_timer,
+        ))
+        .await;
+}
+
 /// Spawn a task for processing `MempoolClientRequest`s from a client such as API service
 async fn handle_client_request<NetworkClient, TransactionValidator>(
     smp: &mut SharedMempool<NetworkClient, TransactionValidator>,
@@ -174,56 +223,145 @@ async fn handle_client_request<NetworkClient, TransactionValidator>(
 {
     match request {
         MempoolClientRequest::SubmitTransaction(txn, callback) => {
+            handle_client_transaction_submission(smp, bounded_executor, txn, None, callback).await;
+        },
+        MempoolClientRequest::SubmitTransactionFromClient(txn, client, callback) => {
+            handle_client_transaction_submission(
+                smp,
+                bounded_executor,
+                txn,
+                Some(client),
+                callback,
+            )
+            .await;
+        },
+        MempoolClientRequest::GetTransactionByHash(hash, callback) => {
+            // This timer measures how long it took for the bounded executor to *schedule* the
+            // task.
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_TXN_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            // This timer measures how long it took for the task to go from scheduled to started.
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_TXN_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_transaction(
+                    smp.clone(),
+                    hash,
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        },
+        MempoolClientRequest::GetTransactionStatusesByHash(hashes, callback) => {
             // This timer measures how long it took for the bounded executor to *schedule* the
             // task.
             let _timer = counters::task_spawn_latency_timer(
-                counters::CLIENT_EVENT_LABEL,
+                counters::CLIENT_EVENT_GET_TXN_STATUSES_LABEL,
                 counters::SPAWN_LABEL,
             );
             // This timer measures how long it took for the task to go from scheduled to started.
             let task_start_timer = counters::task_spawn_latency_timer(
-                counters::CLIENT_EVENT_LABEL,
+                counters::CLIENT_EVENT_GET_TXN_STATUSES_LABEL,
                 counters::START_LABEL,
             );
-            smp.network_interface
-                .num_mempool_txns_received_since_peers_updated += 1;
             bounded_executor
-                .spawn(tasks::process_client_transaction_submission(
+                .spawn(tasks::process_client_get_transaction_statuses(
                     smp.clone(),
-                    txn,
+                    hashes,
                     callback,
                     task_start_timer,
                 ))
                 .await;
         },
-        MempoolClientRequest::GetTransactionByHash(hash, callback) => {
+        MempoolClientRequest::GetPendingTransactions(request, callback) => {
             // This timer measures how long it took for the bounded executor to *schedule* the
             // task.
             let _timer = counters::task_spawn_latency_timer(
-                counters::CLIENT_EVENT_GET_TXN_LABEL,
+                counters::CLIENT_EVENT_GET_PENDING_TXNS_LABEL,
                 counters::SPAWN_LABEL,
             );
             // This timer measures how long it took for the task to go from scheduled to started.
             let task_start_timer = counters::task_spawn_latency_timer(
-                counters::CLIENT_EVENT_GET_TXN_LABEL,
+                counters::CLIENT_EVENT_GET_PENDING_TXNS_LABEL,
                 counters::START_LABEL,
             );
             bounded_executor
-                .spawn(tasks::process_client_get_transaction(
+                .spawn(tasks::process_client_get_pending_transactions(
                     smp.clone(),
-                    hash,
+                    request,
                     callback,
                     task_start_timer,
                 ))
                 .await;
         },
+        // The eviction policy requests are cheap, so they are processed inline
+        MempoolClientRequest::GetEvictionPolicy(callback) => {
+            if callback.send(smp.mempool.get_eviction_policy()).is_err() {
+                counters::CLIENT_CALLBACK_FAIL.inc();
+            }
+        },
+        MempoolClientRequest::SetEvictionPolicy(eviction_policy, callback) => {
+            let result = smp.mempool.set_eviction_policy(eviction_policy.clone());
+            match &result {
+                Ok(()) => info!(
+                    LogSchema::new(LogEntry::EvictionPolicy),
+                    "Updated the mempool eviction policy: {:?}", eviction_policy
+                ),
+                Err(error) => warn!(
+                    LogSchema::new(LogEntry::EvictionPolicy),
+                    "Rejected the mempool eviction policy: {:?}", error
+                ),
+            }
+            if callback.send(result).is_err() {
+                counters::CLIENT_CALLBACK_FAIL.inc();
+            }
+        },
+        // The prioritization params requests are also cheap (and processed inline)
+        MempoolClientRequest::GetPrioritizationParams(callback) => {
+            if callback
+                .send(smp.network_interface.get_prioritization_params())
+                .is_err()
+            {
+                counters::CLIENT_CALLBACK_FAIL.inc();
+            }
+        },
+        MempoolClientRequest::SetPrioritizationParams(params, callback) => {
+            let result = smp
+                .network_interface
+                .set_prioritization_params(params.clone());
+            match &result {
+                Ok(()) => info!(
+                    LogSchema::new(LogEntry::PrioritizationParams),
+                    "Updated the peer prioritization params: {:?}", params
+                ),
+                Err(error) => warn!(
+                    LogSchema::new(LogEntry::PrioritizationParams),
+                    "Rejected the peer prioritization params: {:?}", error
+                ),
+            }
+            if callback.send(result).is_err() {
+                counters::CLIENT_CALLBACK_FAIL.inc();
+            }
+        },
+        MempoolClientRequest::FetchTransactions(peer, hashes, callback) => {
+            // Only request the transactions that aren't already in mempool
+            let hashes = hashes
+                .into_iter()
+                .filter(|hash| smp.mempool.get_by_hash(*hash).is_none())
+                .collect();
+            fetch_transactions(bounded_executor, smp, hashes, peer, callback).await;
+        },
     }
 }
 
 /// Handle removing committed transactions from local mempool immediately.  This should be done
 /// immediately to ensure broadcasts of committed transactions stop as soon as possible.
 fn handle_commit_notification<TransactionValidator>(
-    mempool: &Arc<Mutex<CoreMempool>>,
+    mempool: &Arc<ShardedMempool>,
     mempool_validator: &Arc<RwLock<TransactionValidator>>,
     use_case_history: &Arc<Mutex<UseCaseHistory>>,
     msg: MempoolCommitNotification,
@@ -296,6 +434,7 @@ async fn process_received_txns<NetworkClient, TransactionValidator>(
         Option<u64>,
         Option<BroadcastPeerPriority>,
     )>,
+    request_receipt: bool,
     peer_id: PeerId,
 ) where
     NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
@@ -305,14 +444,8 @@ async fn process_received_txns<NetworkClient, TransactionValidator>(
         .num_mempool_txns_received_since_peers_updated += transactions.len() as u64;
     let smp_clone = smp.clone();
     let peer = PeerNetworkId::new(network_id, peer_id);
-    let ineligible_for_broadcast = (smp.network_interface.is_validator()
-        && !smp.broadcast_within_validator_network())
-        || smp.network_interface.is_upstream_peer(&peer, None);
-    let timeline_state = if ineligible_for_broadcast {
-        TimelineState::NonQualified
-    } else {
-        TimelineState::NotReady
-    };
+    smp.network_interface.record_broadcast_sender(peer);
+    let timeline_state = get_peer_timeline_state(smp, &peer);
     // This timer measures how long it took for the bounded executor to
     // *schedule* the task.
     let _timer = counters::task_spawn_latency_timer(
@@ -330,6 +463,7 @@ async fn process_received_txns<NetworkClient, TransactionValidator>(
             smp_clone,
             transactions,
             message_id,
+            request_receipt,
             timeline_state,
             peer,
             task_start_timer,
@@ -337,6 +471,97 @@ async fn process_received_txns<NetworkClient, TransactionValidator>(
         .await;
 }
 
+/// Returns the timeline state for transactions received from the given peer
+fn get_peer_timeline_state<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+    peer: &PeerNetworkId,
+) -> TimelineState
+where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let ineligible_for_broadcast = (smp.network_interface.is_validator()
+        && !smp.broadcast_within_validator_network())
+        || smp.network_interface.is_upstream_peer(peer, None);
+    if ineligible_for_broadcast {
+        TimelineState::NonQualified
+    } else {
+        TimelineState::NotReady
+    }
+}
+
+/// Spawns a task to serve a transaction request (RPC) from the given peer
+async fn process_transaction_request<NetworkClient, TransactionValidator>(
+    bounded_executor: &BoundedExecutor,
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+    hashes: Vec<HashValue>,
+    peer: PeerNetworkId,
+    protocol_id: ProtocolId,
+    response_sender: oneshot::Sender<Result<Bytes, RpcError>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    // This timer measures how long it took for the bounded executor to
+    // *schedule* the task.
+    let _timer = counters::task_spawn_latency_timer(
+        counters::PEER_TRANSACTION_REQUEST_EVENT_LABEL,
+        counters::SPAWN_LABEL,
+    );
+    // This timer measures how long it took for the task to go from scheduled
+    // to started.
+    let task_start_timer = counters::task_spawn_latency_timer(
+        counters::PEER_TRANSACTION_REQUEST_EVENT_LABEL,
+        counters::START_LABEL,
+    );
+    bounded_executor
+        .spawn(tasks::process_transaction_request(
+            smp.clone(),
+            hashes,
+            peer,
+            protocol_id,
+            response_sender,
+            task_start_timer,
+        ))
+        .await;
+}
+
+/// Spawns a task to fetch (and process) the given transactions from the given peer
+async fn fetch_transactions<NetworkClient, TransactionValidator>(
+    bounded_executor: &BoundedExecutor,
+    smp: &mut SharedMempool<NetworkClient, TransactionValidator>,
+    hashes: Vec<HashValue>,
+    peer: PeerNetworkId,
+    callback: oneshot::Sender<Result<usize>>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let timeline_state = get_peer_timeline_state(smp, &peer);
+    // This timer measures how long it took for the bounded executor to
+    // *schedule* the task.
+    let _timer = counters::task_spawn_latency_timer(
+        counters::PEER_TRANSACTION_RESPONSE_EVENT_LABEL,
+        counters::SPAWN_LABEL,
+    );
+    // This timer measures how long it took for the task to go from scheduled
+    // to started.
+    let task_start_timer = counters::task_spawn_latency_timer(
+        counters::PEER_TRANSACTION_RESPONSE_EVENT_LABEL,
+        counters::START_LABEL,
+    );
+    bounded_executor
+        .spawn(tasks::fetch_transactions(
+            smp.clone(),
+            hashes,
+            timeline_state,
+            peer,
+            callback,
+            task_start_timer,
+        ))
+        .await;
+}
+
 /// Handles all network messages.
 /// - Network messages follow a simple Request/Response framework to accept new transactions
 /// TODO: Move to RPC off of DirectSend
@@ -363,6 +588,7 @@ async fn handle_network_event<NetworkClient, TransactionValidator>(
                         network_id,
                         message_id,
                         transactions.into_iter().map(|t| (t, None, None)).collect(),
+                        false,
                         peer_id,
                     )
                     .await;
@@ -380,6 +606,25 @@ async fn handle_network_event<NetworkClient, TransactionValidator>(
                             .into_iter()
                             .map(|t| (t.0, Some(t.1), Some(t.2)))
                             .collect(),
+                        false,
+                        peer_id,
+                    )
+                    .await;
+                },
+                MempoolSyncMsg::BroadcastTransactionsRequestWithReceipt {
+                    message_id,
+                    transactions,
+                } => {
+                    process_received_txns(
+                        bounded_executor,
+                        smp,
+                        network_id,
+                        message_id,
+                        transactions
+                            .into_iter()
+                            .map(|t| (t.0, Some(t.1), Some(t.2)))
+                            .collect(),
+                        true,
                         peer_id,
                     )
                     .await;
@@ -395,33 +640,112 @@ async fn handle_network_event<NetworkClient, TransactionValidator>(
                         message_id,
                         retry,
                         backoff,
+                        None,
+                        ack_timestamp,
+                    );
+                },
+                MempoolSyncMsg::BroadcastTransactionsResponseWithReceipt {
+                    message_id,
+                    retry,
+                    backoff,
+                    receipt,
+                } => {
+                    let ack_timestamp = SystemTime::now();
+                    smp.network_interface.process_broadcast_ack(
+                        PeerNetworkId::new(network_id, peer_id),
+                        message_id,
+                        retry,
+                        backoff,
+                        Some(receipt),
                         ack_timestamp,
                     );
                 },
+                MempoolSyncMsg::TransactionFilterUpdate { filter } => {
+                    smp.network_interface.process_transaction_filter(
+                        PeerNetworkId::new(network_id, peer_id),
+                        filter,
+                    );
+                },
+                MempoolSyncMsg::RequestTransactions { .. }
+                | MempoolSyncMsg::RequestTransactionsResponse { .. } => {
+                    // Transaction fetches are only served over RPC (so that responses
+                    // are always matched against their requests).
+                    log_unexpected_network_msg(network_id, peer_id);
+                },
             }
         },
-        Event::RpcRequest(peer_id, _msg, _, _res_tx) => {
-            counters::unexpected_msg_count_inc(&network_id);
-            sample!(
-                SampleRate::Duration(Duration::from_secs(60)),
-                warn!(LogSchema::new(LogEntry::UnexpectedNetworkMsg)
-                    .peer(&PeerNetworkId::new(network_id, peer_id)))
-            );
+        Event::RpcRequest(peer_id, msg, protocol_id, response_sender) => match msg {
+            MempoolSyncMsg::RequestTransactions { hashes } => {
+                counters::shared_mempool_event_inc("rpc_request");
+                process_transaction_request(
+                    bounded_executor,
+                    smp,
+                    hashes,
+                    PeerNetworkId::new(network_id, peer_id),
+                    protocol_id,
+                    response_sender,
+                )
+                .await;
+            },
+            _ => log_unexpected_network_msg(network_id, peer_id),
         },
     }
 }
 
+/// Logs (and counts) an unexpected network message from the given peer
+fn log_unexpected_network_msg(network_id: NetworkId, peer_id: PeerId) {
+    counters::unexpected_msg_count_inc(&network_id);
+    sample!(
+        SampleRate::Duration(Duration::from_secs(60)),
+        warn!(LogSchema::new(LogEntry::UnexpectedNetworkMsg)
+            .peer(&PeerNetworkId::new(network_id, peer_id)))
+    );
+}
+
+/// Updates the peer priorities if the monitoring metadata of the given peer changed materially
+async fn handle_peer_monitoring_update<NetworkClient, TransactionValidator>(
+    peers_and_metadata: Arc<PeersAndMetadata>,
+    smp: &mut SharedMempool<NetworkClient, TransactionValidator>,
+    scheduled_broadcasts: &mut FuturesUnordered<ScheduledBroadcast>,
+    executor: Handle,
+    peer: PeerNetworkId,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let monitoring_changed = match peers_and_metadata.get_metadata_for_peer(peer) {
+        Ok(peer_metadata) => smp
+            .network_interface
+            .requires_update_for_monitoring_change(&peer, &peer_metadata),
+        Err(_) => false, // The peer is no longer connected
+    };
+    if monitoring_changed {
+        counters::shared_mempool_event_inc("monitoring_update");
+        handle_update_peers(
+            peers_and_metadata,
+            smp,
+            scheduled_broadcasts,
+            executor,
+            monitoring_changed,
+        )
+        .await;
+    }
+}
+
 async fn handle_update_peers<NetworkClient, TransactionValidator>(
     peers_and_metadata: Arc<PeersAndMetadata>,
     smp: &mut SharedMempool<NetworkClient, TransactionValidator>,
     scheduled_broadcasts: &mut FuturesUnordered<ScheduledBroadcast>,
     executor: Handle,
+    monitoring_changed: bool,
 ) where
     NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
     TransactionValidator: TransactionValidation + 'static,
 {
     if let Ok(connected_peers) = peers_and_metadata.get_connected_peers_and_metadata() {
-        let (newly_added_upstream, disabled) = smp.network_interface.update_peers(&connected_peers);
+        let (newly_added_upstream, disabled) = smp
+            .network_interface
+            .update_peers(&connected_peers, monitoring_changed);
         if !newly_added_upstream.is_empty() || !disabled.is_empty() {
             counters::shared_mempool_event_inc("peer_update");
             notify_subscribers(SharedMempoolNotification::PeerStateChange, &smp.subscribers);
@@ -434,11 +758,19 @@ async fn handle_update_peers<NetworkClient, TransactionValidator>(
         for peer in &disabled {
             debug!(LogSchema::new(LogEntry::LostPeer).peer(peer));
         }
+
+        // Disconnect from the newly penalized (i.e., misbehaving) peers (if enabled)
+        for peer in smp.network_interface.take_peers_to_disconnect() {
+            let network_interface = smp.network_interface.clone();
+            executor.spawn(async move {
+                network_interface.disconnect_from_peer(peer).await;
+            });
+        }
     }
 }
 
 /// Garbage collect all expired transactions by SystemTTL.
-pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval_ms: u64) {
+pub(crate) async fn gc_coordinator(mempool: Arc<ShardedMempool>, gc_interval_ms: u64) {
     debug!(LogSchema::event_log(LogEntry::GCRuntime, LogEvent::Start));
     let mut interval = IntervalStream::new(interval(Duration::from_millis(gc_interval_ms)));
     while let Some(_interval) = interval.next().await {
@@ -446,7 +778,7 @@ pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval
             SampleRate::Duration(Duration::from_secs(60)),
             debug!(LogSchema::event_log(LogEntry::GCRuntime, LogEvent::Live))
         );
-        mempool.lock().gc();
+        mempool.gc();
     }
 
     error!(LogSchema::event_log(
@@ -455,13 +787,62 @@ pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval
     ));
 }
 
+/// Periodically reports the utilization of core mempool, so that it can be
+/// served to peers (e.g., by the peer monitoring service).
+pub(crate) async fn mempool_utilization_job(
+    mempool: Arc<ShardedMempool>,
+    mempool_utilization: MempoolUtilization,
+    capacity: usize,
+    update_interval_ms: u64,
+) {
+    let mut interval = IntervalStream::new(interval(Duration::from_millis(update_interval_ms)));
+    while let Some(_interval) = interval.next().await {
+        let num_transactions = mempool.get_num_transactions();
+        mempool_utilization.update(num_transactions as u64, capacity as u64);
+    }
+}
+
+/// Periodically publishes the load state of core mempool, so that it can be
+/// queried by the transaction submission path (e.g., to apply backpressure).
+pub(crate) async fn mempool_load_job(
+    mempool: Arc<ShardedMempool>,
+    mempool_load_monitor: MempoolLoadMonitor,
+    submission_backpressure_config: SubmissionBackpressureConfig,
+) {
+    let mut interval = IntervalStream::new(interval(Duration::from_millis(
+        submission_backpressure_config.load_update_interval_ms,
+    )));
+    while let Some(_interval) = interval.next().await {
+        let mut load_state = mempool.get_load_state();
+        load_state.update_level(&submission_backpressure_config);
+        counters::CORE_MEMPOOL_LOAD_LEVEL.set(load_state.level as i64);
+        mempool_load_monitor.update(load_state);
+    }
+}
+
+/// Periodically sends a filter of the transactions in core mempool to the
+/// peers broadcasting to this node (so that they can skip broadcasting them).
+pub(crate) async fn transaction_filter_job<NetworkClient>(
+    network_interface: MempoolNetworkInterface<NetworkClient>,
+    mempool: Arc<ShardedMempool>,
+    filter_update_interval_ms: u64,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+{
+    let mut interval =
+        IntervalStream::new(interval(Duration::from_millis(filter_update_interval_ms)));
+    while let Some(_interval) = interval.next().await {
+        network_interface.send_transaction_filter(&mempool);
+    }
+}
+
 /// Periodically logs a snapshot of transactions in core mempool.
 /// In the future we may want an interactive way to directly query mempool's internal state.
 /// For now, we will rely on this periodic snapshot to observe the internal state.
-pub(crate) async fn snapshot_job(mempool: Arc<Mutex<CoreMempool>>, snapshot_interval_secs: u64) {
+pub(crate) async fn snapshot_job(mempool: Arc<ShardedMempool>, snapshot_interval_secs: u64) {
     let mut interval = IntervalStream::new(interval(Duration::from_secs(snapshot_interval_secs)));
     while let Some(_interval) = interval.next().await {
-        let snapshot = mempool.lock().gen_snapshot();
+        let snapshot = mempool.gen_snapshot();
         trace!(LogSchema::new(LogEntry::MempoolSnapshot).txns(snapshot));
     }
 }
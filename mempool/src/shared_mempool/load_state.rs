@@ -0,0 +1,171 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The load state of mempool, published periodically so that the transaction submission
+//! path (e.g., the REST API) can query it synchronously and apply backpressure (i.e.,
+//! reject submissions with a suggested retry delay) while mempool is full.
+
+use aptos_config::config::SubmissionBackpressureConfig;
+use aptos_infallible::RwLock;
+use serde::Serialize;
+use std::{sync::Arc, time::Duration};
+
+/// The load level of mempool
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolLoadLevel {
+    #[default]
+    Healthy, // Mempool has plenty of capacity
+    Degraded, // Mempool is filling up, or transactions are being evicted by the system TTL
+    Full,     // Mempool is (close to) full, and submissions should be rejected
+}
+
+/// A snapshot of the load state of mempool
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct MempoolLoadState {
+    /// The load level of mempool
+    pub level: MempoolLoadLevel,
+    /// The number of transactions in mempool
+    pub num_transactions: usize,
+    /// The maximum number of transactions in mempool
+    pub capacity: usize,
+    /// The (estimated) size of the transactions in mempool
+    pub size_bytes: usize,
+    /// The maximum size of the transactions in mempool
+    pub capacity_bytes: usize,
+    /// The number of transactions in the parking lot (i.e., not ready for consensus)
+    pub num_parked_transactions: usize,
+    /// The number of transactions evicted by the last system TTL garbage collection
+    pub num_system_ttl_evictions: u64,
+    /// The time until the next transaction is evicted by the system TTL (if any)
+    pub next_system_ttl_eviction: Option<Duration>,
+    /// The suggested delay before retrying a rejected submission (only set when full)
+    pub retry_after: Option<Duration>,
+}
+
+impl MempoolLoadState {
+    /// Returns the utilization of mempool (as a percentage of the transaction
+    /// or byte capacity, whichever is higher).
+    pub fn utilization_percent(&self) -> u64 {
+        let utilization = |used: usize, capacity: usize| {
+            if capacity == 0 {
+                0
+            } else {
+                (used as u128 * 100 / capacity as u128) as u64
+            }
+        };
+        utilization(self.num_transactions, self.capacity)
+            .max(utilization(self.size_bytes, self.capacity_bytes))
+    }
+
+    /// Returns true iff submissions should be rejected
+    pub fn is_full(&self) -> bool {
+        self.level == MempoolLoadLevel::Full
+    }
+
+    /// Derives the load level (and suggested retry delay) from the load statistics
+    pub(crate) fn update_level(&mut self, config: &SubmissionBackpressureConfig) {
+        let utilization_percent = self.utilization_percent();
+        self.level = if utilization_percent >= config.full_utilization_percent {
+            MempoolLoadLevel::Full
+        } else if utilization_percent >= config.degraded_utilization_percent
+            || self.num_system_ttl_evictions > 0
+        {
+            MempoolLoadLevel::Degraded
+        } else {
+            MempoolLoadLevel::Healthy
+        };
+
+        // If mempool is full, suggest retrying once the next transaction is evicted
+        // by the system TTL (space is also freed as transactions are committed).
+        let max_retry_after = Duration::from_secs(config.max_retry_after_secs.max(1));
+        self.retry_after = self.is_full().then(|| {
+            self.next_system_ttl_eviction
+                .unwrap_or(max_retry_after)
+                .clamp(Duration::from_secs(1), max_retry_after)
+        });
+    }
+}
+
+/// A shared handle to the load state of mempool. This is updated by mempool,
+/// and queried (synchronously) by the transaction submission path.
+#[derive(Clone, Debug, Default)]
+pub struct MempoolLoadMonitor {
+    load_state: Arc<RwLock<MempoolLoadState>>,
+}
+
+impl MempoolLoadMonitor {
+    /// Returns the latest load state of mempool
+    pub fn get_load_state(&self) -> MempoolLoadState {
+        self.load_state.read().clone()
+    }
+
+    /// Updates the load state of mempool
+    pub(crate) fn update(&self, load_state: MempoolLoadState) {
+        *self.load_state.write() = load_state;
+    }
+
+    /// Overrides the load state of mempool (e.g., to test submission backpressure)
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn set_load_state_for_testing(&self, load_state: MempoolLoadState) {
+        self.update(load_state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MempoolLoadLevel, MempoolLoadState};
+    use aptos_config::config::SubmissionBackpressureConfig;
+    use std::time::Duration;
+
+    #[test]
+    fn test_load_level() {
+        let config = SubmissionBackpressureConfig::default();
+
+        // Verify that a lightly loaded mempool is healthy
+        let mut load_state = create_load_state(100, 0, 0);
+        load_state.update_level(&config);
+        assert_eq!(load_state.level, MempoolLoadLevel::Healthy);
+        assert_eq!(load_state.retry_after, None);
+
+        // Verify that system TTL evictions degrade mempool
+        let mut load_state = create_load_state(100, 0, 1);
+        load_state.update_level(&config);
+        assert_eq!(load_state.level, MempoolLoadLevel::Degraded);
+
+        // Verify that byte utilization is considered
+        let mut load_state = create_load_state(100, 800, 0);
+        load_state.update_level(&config);
+        assert_eq!(load_state.level, MempoolLoadLevel::Degraded);
+
+        // Verify that a full mempool suggests a bounded retry delay
+        let mut load_state = create_load_state(950, 0, 0);
+        load_state.next_system_ttl_eviction = Some(Duration::from_secs(5));
+        load_state.update_level(&config);
+        assert!(load_state.is_full());
+        assert_eq!(load_state.retry_after, Some(Duration::from_secs(5)));
+
+        load_state.next_system_ttl_eviction = Some(Duration::from_secs(1_000));
+        load_state.update_level(&config);
+        assert_eq!(
+            load_state.retry_after,
+            Some(Duration::from_secs(config.max_retry_after_secs))
+        );
+    }
+
+    /// Creates a load state with the given statistics (and a capacity of 1000 transactions and bytes)
+    fn create_load_state(
+        num_transactions: usize,
+        size_bytes: usize,
+        num_system_ttl_evictions: u64,
+    ) -> MempoolLoadState {
+        MempoolLoadState {
+            num_transactions,
+            capacity: 1_000,
+            size_bytes,
+            capacity_bytes: 1_000,
+            num_system_ttl_evictions,
+            ..Default::default()
+        }
+    }
+}
@@ -2,10 +2,18 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod client_quotas;
+pub mod load_state;
 pub mod network;
 mod priority;
+mod priority_snapshot;
 mod runtime;
+mod serialization_cache;
+pub mod transaction_filter;
 pub(crate) mod types;
+pub use priority::PrioritizedPeerInfo;
+#[cfg(any(test, feature = "fuzzing"))]
+pub use priority::PrioritizedPeersState;
 pub use runtime::bootstrap;
 #[cfg(any(test, feature = "fuzzing"))]
 pub(crate) use runtime::start_shared_mempool;
@@ -5,22 +5,27 @@
 //! Interface between Mempool and Network layers.
 
 use crate::{
+    core_mempool::ShardedMempool,
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     shared_mempool::{
-        priority::PrioritizedPeersState,
+        priority::{PeerMisbehaviorCounts, PrioritizedPeerInfo, PrioritizedPeersState},
+        serialization_cache::{BroadcastBatchKey, BroadcastSerializationCache},
         tasks,
+        transaction_filter::TransactionHashFilter,
         types::{
-            notify_subscribers, MempoolMessageId, MempoolSenderBucket, PeerSyncState,
-            SharedMempool, SharedMempoolNotification,
+            notify_subscribers, BroadcastInfo, FilteredTransaction, MempoolMessageId,
+            MempoolSenderBucket, PeerSyncState, SharedMempool, SharedMempoolNotification,
+            SubmissionStatusBundle,
         },
     },
 };
 use aptos_config::{
-    config::{MempoolConfig, NodeType},
+    config::{MempoolConfig, NodeType, PeerPrioritizationParams},
     network_id::PeerNetworkId,
 };
-use aptos_infallible::RwLock;
+use aptos_crypto::HashValue;
+use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_network::{
@@ -30,20 +35,67 @@ use aptos_network::{
 use aptos_time_service::TimeService;
 use aptos_types::transaction::SignedTransaction;
 use aptos_vm_validator::vm_validator::TransactionValidation;
+use bytes::Bytes;
 use fail::fail_point;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap},
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::Display,
     ops::Add,
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc,
     },
-    time::{Duration, Instant, SystemTime},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
 
+/// Returns the urgency band of a transaction with the given expiration time, i.e., the
+/// index of the first band (in seconds until expiration) that the transaction falls into.
+/// Transactions beyond all bands are assigned the lowest urgency (i.e., the number of bands).
+pub(crate) fn expiration_urgency_band(
+    urgency_bands_secs: &[u64],
+    expiration_timestamp_secs: u64,
+    now_secs: u64,
+) -> usize {
+    let secs_until_expiration = expiration_timestamp_secs.saturating_sub(now_secs);
+    urgency_bands_secs
+        .iter()
+        .position(|band_secs| secs_until_expiration <= *band_secs)
+        .unwrap_or(urgency_bands_secs.len())
+}
+
+/// Returns the message (of the given candidates) holding the transaction closest to
+/// expiration, given the earliest expiration time of each candidate (`None` if none of
+/// its transactions are still in mempool). Ties are broken in favor of the latest message
+/// in the timeline index.
+pub(crate) fn most_urgent_message<'a>(
+    urgency_bands_secs: &[u64],
+    candidates: impl Iterator<Item = (&'a MempoolMessageId, Option<u64>)>,
+    now_secs: u64,
+) -> Option<&'a MempoolMessageId> {
+    candidates
+        .filter_map(|(message_id, min_expiration_secs)| {
+            min_expiration_secs.map(|expiration_secs| {
+                (
+                    expiration_urgency_band(urgency_bands_secs, expiration_secs, now_secs),
+                    Reverse(message_id),
+                )
+            })
+        })
+        .min()
+        .map(|(_, Reverse(message_id))| message_id)
+}
+
+/// Returns the current time in seconds since the unix epoch
+fn current_time_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
 /// Container for exchanging transactions with other Mempools.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum MempoolSyncMsg {
@@ -72,6 +124,38 @@ pub enum MempoolSyncMsg {
         /// to reach the upstream node.
         transactions: Vec<(SignedTransaction, u64, BroadcastPeerPriority)>,
     },
+    /// A filter of the transactions held by the sender. The receiver can skip
+    /// broadcasting transactions that match the filter to the sender.
+    TransactionFilterUpdate { filter: TransactionHashFilter },
+    /// A request for the transactions with the given hashes. This is used to pull
+    /// transactions the sender learned about (e.g., via batch digests), but doesn't hold.
+    RequestTransactions { hashes: Vec<HashValue> },
+    /// The response to a transaction request. This only contains the requested
+    /// transactions held by the responder (the rest are omitted).
+    RequestTransactionsResponse {
+        transactions: Vec<SignedTransaction>,
+    },
+    /// Broadcast ack issued by the receiver (with a delivery receipt). This is only
+    /// sent in response to a BroadcastTransactionsRequestWithReceipt.
+    BroadcastTransactionsResponseWithReceipt {
+        message_id: MempoolMessageId,
+        /// Retry signal from recipient if there are txns in corresponding broadcast
+        /// that were rejected from mempool but may succeed on resend.
+        retry: bool,
+        /// A backpressure signal from the recipient when it is overwhelmed (e.g., mempool is full).
+        backoff: bool,
+        /// The delivery receipt of the corresponding broadcast
+        receipt: BroadcastDeliveryReceipt,
+    },
+    /// Broadcast request issued by the sender (with ready times), that also asks the
+    /// receiver to include a delivery receipt in the ack (i.e., in a
+    /// BroadcastTransactionsResponseWithReceipt).
+    BroadcastTransactionsRequestWithReceipt {
+        /// Unique id of sync request. Can be used by sender for rebroadcast analysis
+        message_id: MempoolMessageId,
+        /// The transactions, ready times and priorities (as in BroadcastTransactionsRequestWithReadyTime)
+        transactions: Vec<(SignedTransaction, u64, BroadcastPeerPriority)>,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -96,6 +180,26 @@ pub enum BroadcastPeerPriority {
     Failover,
 }
 
+/// The type of broadcast request sent to peers (i.e., the message variant)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BroadcastRequestType {
+    /// A BroadcastTransactionsRequest
+    Plain,
+    /// A BroadcastTransactionsRequestWithReadyTime
+    WithReadyTime,
+    /// A BroadcastTransactionsRequestWithReceipt
+    WithReceipt,
+}
+
+/// A summary of how the transactions in a broadcast were received by the recipient
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BroadcastDeliveryReceipt {
+    /// The number of transactions that were new to the recipient (i.e., added to mempool)
+    pub num_new_transactions: u64,
+    /// The number of transactions the recipient already had (or already committed)
+    pub num_duplicate_transactions: u64,
+}
+
 impl Display for BroadcastPeerPriority {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -112,6 +216,14 @@ pub(crate) struct MempoolNetworkInterface<NetworkClient> {
     node_type: NodeType,
     mempool_config: MempoolConfig,
     prioritized_peers_state: PrioritizedPeersState,
+    // The peers that broadcast to us since our transaction filter was last sent
+    broadcast_senders: Arc<RwLock<HashSet<PeerNetworkId>>>,
+    // The counts of the transactions received from each peer (in the current scoring window)
+    peer_misbehavior_counts: Arc<RwLock<HashMap<PeerNetworkId, PeerMisbehaviorCounts>>>,
+    // The newly penalized peers that should be disconnected
+    peers_to_disconnect: Vec<PeerNetworkId>,
+    // The cache of serialized broadcast batches (if enabled)
+    serialization_cache: Option<Arc<Mutex<BroadcastSerializationCache>>>,
     pub num_mempool_txns_received_since_peers_updated: u64,
     pub num_committed_txns_received_since_peers_updated: Arc<AtomicU64>,
 }
@@ -124,17 +236,59 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
     ) -> MempoolNetworkInterface<NetworkClient> {
         let prioritized_peers_state =
             PrioritizedPeersState::new(mempool_config.clone(), node_type, TimeService::real());
+        let serialization_cache =
+            (mempool_config.broadcast_serialization_cache_bytes > 0).then(|| {
+                Arc::new(Mutex::new(BroadcastSerializationCache::new(
+                    mempool_config.broadcast_serialization_cache_bytes,
+                )))
+            });
         Self {
             network_client,
             sync_states: Arc::new(RwLock::new(HashMap::new())),
             node_type,
             mempool_config,
             prioritized_peers_state,
+            broadcast_senders: Arc::new(RwLock::new(HashSet::new())),
+            peer_misbehavior_counts: Arc::new(RwLock::new(HashMap::new())),
+            peers_to_disconnect: vec![],
+            serialization_cache,
             num_mempool_txns_received_since_peers_updated: 0,
             num_committed_txns_received_since_peers_updated: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Returns a handle to the current list of prioritized peers (with
+    /// the metadata that produced it). This is useful for debugging.
+    pub fn get_prioritized_peers_info(&self) -> Arc<RwLock<Vec<PrioritizedPeerInfo>>> {
+        self.prioritized_peers_state.get_prioritized_peers_info()
+    }
+
+    /// Sets the handle to which the list of prioritized peers is published
+    pub fn set_prioritized_peers_info(
+        &mut self,
+        prioritized_peers_info: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+    ) {
+        self.prioritized_peers_state
+            .set_prioritized_peers_info(prioritized_peers_info);
+    }
+
+    /// Returns the peer prioritization parameters (including any pending update)
+    pub fn get_prioritization_params(&self) -> PeerPrioritizationParams {
+        self.prioritized_peers_state.get_prioritization_params()
+    }
+
+    /// Verifies and sets the peer prioritization parameters. These
+    /// are picked up on the next update of the prioritized peers.
+    pub fn set_prioritization_params(
+        &mut self,
+        params: PeerPrioritizationParams,
+    ) -> anyhow::Result<()> {
+        params.verify().map_err(anyhow::Error::msg)?;
+        self.prioritized_peers_state
+            .set_prioritization_params(params);
+        Ok(())
+    }
+
     /// Returns peers to add (with metadata) and peers to disable
     fn get_upstream_peers_to_add_and_disable(
         &self,
@@ -187,10 +341,12 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
     }
 
     /// Update peers based on updated view of connected peers. Return (peers newly added that need
-    /// to start broadcasts, peers that will be disabled from broadcasts).
+    /// to start broadcasts, peers that will be disabled from broadcasts). If `monitoring_changed`
+    /// is set, the peer priorities are updated regardless of the priority update interval.
     pub fn update_peers(
         &mut self,
         all_connected_peers: &HashMap<PeerNetworkId, PeerMetadata>,
+        monitoring_changed: bool,
     ) -> (Vec<PeerNetworkId>, Vec<PeerNetworkId>) {
         // Get the upstream peers to add or disable, using a read lock
         let (to_add, to_disable) = self.get_upstream_peers_to_add_and_disable(all_connected_peers);
@@ -210,11 +366,39 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         // This should be called even if there are no changes to the peers, as the
         // peer metadata may have changed (e.g., ping latencies).
         let peers_changed = !to_add.is_empty() || !to_disable.is_empty();
-        self.update_prioritized_peers(all_connected_peers, peers_changed);
+        let previous_top_peers = self.prioritized_peers_state.get_top_peers();
+        self.update_prioritized_peers(all_connected_peers, peers_changed || monitoring_changed);
+
+        // If any newly added peers were promoted above the previous broadcast targets,
+        // catch them up by sending the size of the in-flight broadcast window back-to-back.
+        let top_peers = self.prioritized_peers_state.get_top_peers();
+        let promoted_peers: Vec<_> = to_add
+            .iter()
+            .map(|(peer, _)| *peer)
+            .filter(|peer| top_peers.contains(peer) && !previous_top_peers.contains(peer))
+            .collect();
+        self.rebroadcast_in_flight_window(&promoted_peers, &previous_top_peers);
 
         (to_add.iter().map(|(peer, _)| *peer).collect(), to_disable)
     }
 
+    /// Returns true iff the updated monitoring metadata of the given peer
+    /// should trigger a peer priority update (see `PrioritizedPeersState`).
+    pub fn requires_update_for_monitoring_change(
+        &self,
+        peer: &PeerNetworkId,
+        peer_metadata: &PeerMetadata,
+    ) -> bool {
+        // Only fullnodes should prioritize peers (e.g., VFNs and PFNs)
+        !self.node_type.is_validator()
+            && self
+                .prioritized_peers_state
+                .requires_update_for_monitoring_change(
+                    peer,
+                    Some(peer_metadata.get_peer_monitoring_metadata()),
+                )
+    }
+
     /// Updates the prioritized peers list
     fn update_prioritized_peers(
         &mut self,
@@ -226,8 +410,31 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
             return;
         }
 
+        // Update the peers that repeatedly failed broadcasts. If a top peer has
+        // failed, the priorities are updated to promote the next peer.
+        let failed_peers = self
+            .sync_states
+            .read()
+            .iter()
+            .filter(|(_, sync_state)| {
+                sync_state.broadcast_info.consecutive_broadcast_failures
+                    >= self.mempool_config.broadcast_failover_threshold
+            })
+            .map(|(peer, _)| *peer)
+            .collect();
+        let top_peer_failed = self
+            .prioritized_peers_state
+            .update_failed_peers(failed_peers);
+
+        // Update the peers penalized for misbehavior. If a prioritized peer is
+        // newly penalized, the priorities are updated to deprioritize the peer.
+        let prioritized_peer_penalized = self.update_penalized_peers(all_connected_peers);
+
         // If the prioritized peers list is not ready for an update, return early
-        if !self.prioritized_peers_state.ready_for_update(peers_changed) {
+        if !self
+            .prioritized_peers_state
+            .ready_for_update(peers_changed || top_peer_failed || prioritized_peer_penalized)
+        {
             return;
         }
 
@@ -246,6 +453,33 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
             })
             .collect();
 
+        // Update the broadcast ACK latencies observed for each peer
+        let broadcast_ack_latencies = self
+            .sync_states
+            .read()
+            .iter()
+            .filter_map(|(peer, sync_state)| {
+                sync_state
+                    .broadcast_info
+                    .average_ack_latency_secs
+                    .map(|ack_latency| (*peer, ack_latency))
+            })
+            .collect();
+        self.prioritized_peers_state
+            .update_broadcast_ack_latencies(broadcast_ack_latencies);
+
+        // Update the connection metadata of each peer (used to favor network diversity)
+        let peer_connection_metadata = peer_network_ids
+            .iter()
+            .filter_map(|peer| {
+                all_connected_peers
+                    .get(peer)
+                    .map(|metadata| (*peer, metadata.get_connection_metadata()))
+            })
+            .collect();
+        self.prioritized_peers_state
+            .update_peer_connection_metadata(peer_connection_metadata);
+
         // Update the prioritized peers list
         self.prioritized_peers_state.update_prioritized_peers(
             peers_and_metadata,
@@ -259,6 +493,132 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
             .store(0, Ordering::SeqCst);
     }
 
+    /// Scores the peers that completed a misbehavior scoring window (if peer misbehavior
+    /// scoring is enabled) and updates the penalized peers. Returns true iff a currently
+    /// prioritized peer was newly penalized.
+    fn update_penalized_peers(
+        &mut self,
+        all_connected_peers: &HashMap<PeerNetworkId, PeerMetadata>,
+    ) -> bool {
+        let scoring_config = match &self.mempool_config.peer_misbehavior_scoring {
+            Some(scoring_config) => scoring_config,
+            None => return false, // Peer misbehavior scoring is disabled
+        };
+
+        // Score the peers that completed a scoring window (and start a new window).
+        // The counts of disconnected peers are dropped.
+        let mut peer_misbehavior_scores = HashMap::new();
+        {
+            let mut peer_misbehavior_counts = self.peer_misbehavior_counts.write();
+            peer_misbehavior_counts.retain(|peer, _| all_connected_peers.contains_key(peer));
+            for (peer, misbehavior_counts) in peer_misbehavior_counts.iter_mut() {
+                if misbehavior_counts.is_window_complete(scoring_config) {
+                    peer_misbehavior_scores
+                        .insert(*peer, misbehavior_counts.is_misbehaving(scoring_config));
+                    *misbehavior_counts = PeerMisbehaviorCounts::default();
+                }
+            }
+        }
+
+        // Update the penalized peers (and disconnect from the newly penalized peers, if enabled)
+        let newly_penalized_peers = self
+            .prioritized_peers_state
+            .update_penalized_peers(peer_misbehavior_scores, |peer| {
+                all_connected_peers.contains_key(peer)
+            });
+        if scoring_config.disconnect_penalized_peers {
+            self.peers_to_disconnect
+                .extend(newly_penalized_peers.iter().cloned());
+        }
+        newly_penalized_peers
+            .iter()
+            .any(|peer| self.prioritized_peers_state.get_peer_priority(peer) != usize::MAX)
+    }
+
+    /// Returns (and clears) the newly penalized peers that should be disconnected
+    pub fn take_peers_to_disconnect(&mut self) -> Vec<PeerNetworkId> {
+        std::mem::take(&mut self.peers_to_disconnect)
+    }
+
+    /// Requests a disconnect from the given (e.g., misbehaving) peer
+    pub async fn disconnect_from_peer(&self, peer: PeerNetworkId) {
+        if let Err(error) = self.network_client.disconnect_from_peer(peer).await {
+            warn!(LogSchema::new(LogEntry::PeerMisbehavior)
+                .peer(&peer)
+                .error(&error.into()));
+            return;
+        }
+        counters::shared_mempool_peer_misbehavior_inc(
+            peer.network_id(),
+            counters::PEER_DISCONNECTED_LABEL,
+        );
+    }
+
+    /// Schedules each of the promoted peers to receive as many broadcasts back-to-back as
+    /// the previous broadcast targets have in flight (i.e., unacknowledged). Otherwise,
+    /// transactions broadcast while the promoted peers were disconnected (e.g., a VFN's
+    /// validator) would only reach them one batch per tick interval. Note: the broadcasts
+    /// are taken from each promoted peer's own timelines, as the message IDs of the
+    /// previous targets refer to their (potentially different) sender buckets.
+    fn rebroadcast_in_flight_window(
+        &self,
+        promoted_peers: &[PeerNetworkId],
+        previous_top_peers: &HashSet<PeerNetworkId>,
+    ) {
+        // Return early if there are no promoted peers
+        if promoted_peers.is_empty() {
+            return;
+        }
+
+        // Determine the largest in-flight broadcast window of the previous broadcast targets
+        let mut sync_states = self.sync_states.write();
+        let in_flight_window = previous_top_peers
+            .iter()
+            .filter_map(|peer| sync_states.get(peer))
+            .map(|sync_state| {
+                let broadcast_info = &sync_state.broadcast_info;
+                broadcast_info.sent_messages.len() + broadcast_info.retry_messages.len()
+            })
+            .max()
+            .unwrap_or(0)
+            .min(self.mempool_config.max_broadcasts_per_peer);
+        if in_flight_window == 0 {
+            return;
+        }
+
+        // Schedule the catch-up broadcasts for each promoted peer
+        for peer in promoted_peers {
+            if let Some(sync_state) = sync_states.get_mut(peer) {
+                info!(
+                    "Sending {} catch-up broadcast(s) to promoted mempool peer: {:?}",
+                    in_flight_window, peer
+                );
+                counters::SHARED_MEMPOOL_PROMOTION_REBROADCAST_COUNT
+                    .inc_by(in_flight_window as u64);
+                sync_state.broadcast_info.catch_up_broadcasts = in_flight_window;
+            }
+        }
+    }
+
+    /// Consumes one of the catch-up broadcasts of the given peer (after a broadcast attempt),
+    /// and returns true iff the next broadcast should be sent immediately. Failed broadcasts
+    /// (e.g., if there are no transactions left to send) and backoff mode end the catch-up.
+    pub fn take_catch_up_broadcast(&self, peer: &PeerNetworkId, broadcast_succeeded: bool) -> bool {
+        let mut sync_states = self.sync_states.write();
+        let broadcast_info = match sync_states.get_mut(peer) {
+            Some(state) => &mut state.broadcast_info,
+            None => return false,
+        };
+
+        if !broadcast_succeeded || broadcast_info.backoff_mode {
+            broadcast_info.catch_up_broadcasts = 0;
+        } else {
+            broadcast_info.catch_up_broadcasts =
+                broadcast_info.catch_up_broadcasts.saturating_sub(1);
+        }
+        broadcast_info.catch_up_broadcasts > 0
+    }
+
     pub fn is_validator(&self) -> bool {
         self.node_type.is_validator()
     }
@@ -287,6 +647,7 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         message_id: MempoolMessageId,
         retry: bool,
         backoff: bool,
+        receipt: Option<BroadcastDeliveryReceipt>,
         timestamp: SystemTime,
     ) {
         let mut sync_states = self.sync_states.write();
@@ -307,6 +668,30 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
             counters::SHARED_MEMPOOL_BROADCAST_RTT
                 .with_label_values(&[network_id.as_str()])
                 .observe(rtt.as_secs_f64());
+            sync_state.broadcast_info.record_ack_latency(rtt);
+            sync_state.broadcast_info.reset_broadcast_failures();
+
+            // Only fast ACKs without retry or backoff requests grow the batch size
+            if let Some(config) = &self.mempool_config.adaptive_batch_sizing {
+                let fast_ack = !retry
+                    && !backoff
+                    && rtt <= Duration::from_millis(config.target_ack_latency_ms);
+                self.adapt_broadcast_batch_size(&peer, &mut sync_state.broadcast_info, fast_ack);
+            }
+
+            // Record how many of the broadcast transactions were new to the peer
+            if let Some(receipt) = receipt {
+                counters::shared_mempool_broadcast_delivered_txns_inc(
+                    &peer,
+                    counters::DELIVERED_NEW_LABEL,
+                    receipt.num_new_transactions,
+                );
+                counters::shared_mempool_broadcast_delivered_txns_inc(
+                    &peer,
+                    counters::DELIVERED_DUPLICATE_LABEL,
+                    receipt.num_duplicate_transactions,
+                );
+            }
 
             counters::shared_mempool_pending_broadcasts(&peer).dec();
         } else {
@@ -349,6 +734,257 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         }
     }
 
+    /// Records that the given peer broadcast transactions to us (if broadcast
+    /// filters are enabled), so that our transaction filter is sent to it.
+    pub fn record_broadcast_sender(&self, peer: PeerNetworkId) {
+        if self.mempool_config.broadcast_filter.is_some() {
+            self.broadcast_senders.write().insert(peer);
+        }
+    }
+
+    /// Records the results of processing the transactions received from the given peer
+    /// (if peer misbehavior scoring is enabled). These are used to penalize peers that
+    /// send many invalid or expired transactions. Transactions that were just committed
+    /// (e.g., resent by a peer that hasn't seen the commit yet) are not penalized.
+    pub fn record_received_transactions(
+        &self,
+        peer: &PeerNetworkId,
+        results: &[SubmissionStatusBundle],
+    ) {
+        // Only fullnodes should prioritize (and thus penalize) peers
+        if self.mempool_config.peer_misbehavior_scoring.is_none() || self.node_type.is_validator() {
+            return;
+        }
+
+        let mut peer_misbehavior_counts = self.peer_misbehavior_counts.write();
+        let misbehavior_counts = peer_misbehavior_counts.entry(*peer).or_default();
+        for (_, (mempool_status, vm_status)) in results {
+            misbehavior_counts.record_transaction(mempool_status, *vm_status);
+        }
+    }
+
+    /// Processes a transaction filter received from the given peer. Filters
+    /// are only stored for peers that we broadcast to (and ignored otherwise).
+    pub fn process_transaction_filter(&self, peer: PeerNetworkId, filter: TransactionHashFilter) {
+        if self.mempool_config.broadcast_filter.is_none() {
+            return; // Broadcast filters are disabled
+        }
+
+        // Verify the filter is well formed
+        let network_id = peer.network_id();
+        if !filter.is_valid() {
+            counters::shared_mempool_transaction_filter_inc(
+                network_id,
+                counters::FILTER_INVALID_LABEL,
+            );
+            sample!(
+                SampleRate::Duration(Duration::from_secs(60)),
+                warn!(
+                    LogSchema::new(LogEntry::TransactionFilter).peer(&peer),
+                    "Received an invalid transaction filter!"
+                )
+            );
+            return;
+        }
+
+        // Store the filter for the peer. The transactions skipped because of the previous
+        // filter are re-checked against the new filter (which uses a fresh seed): those that
+        // no longer match were false positives, and must still be broadcast to the peer.
+        if let Some(state) = self.sync_states.write().get_mut(&peer) {
+            counters::shared_mempool_transaction_filter_inc(
+                network_id,
+                counters::FILTER_RECEIVED_LABEL,
+            );
+            let filtered_transactions = std::mem::take(&mut state.filtered_transactions);
+            state.unfiltered_transactions.extend(
+                filtered_transactions
+                    .into_iter()
+                    .filter(|(hash, _, _)| !filter.contains(hash)),
+            );
+            state.transaction_filter = Some((filter, Instant::now()));
+        }
+    }
+
+    /// Sends a filter of the transactions in mempool to all peers that broadcast
+    /// to us since the filter was last sent, so that they can skip broadcasting
+    /// the transactions we already hold.
+    pub fn send_transaction_filter(&self, mempool: &ShardedMempool) {
+        let broadcast_filter_config = match &self.mempool_config.broadcast_filter {
+            Some(broadcast_filter_config) => broadcast_filter_config,
+            None => return, // Broadcast filters are disabled
+        };
+
+        // Identify the peers that broadcast to us since the last filter
+        let peers: Vec<_> = self.broadcast_senders.write().drain().collect();
+        if peers.is_empty() {
+            return;
+        }
+
+        // Build the filter and send it to each peer
+        let transaction_hashes =
+            mempool.get_transaction_hashes(broadcast_filter_config.max_filter_transactions);
+        let filter = TransactionHashFilter::new(
+            &transaction_hashes,
+            broadcast_filter_config.bits_per_transaction,
+        );
+        for peer in peers {
+            let message = MempoolSyncMsg::TransactionFilterUpdate {
+                filter: filter.clone(),
+            };
+            if let Err(error) = self.send_message_to_peer(peer, message) {
+                counters::network_send_fail_inc(counters::TRANSACTION_FILTER);
+                warn!(
+                    LogSchema::event_log(LogEntry::TransactionFilter, LogEvent::NetworkSendFail)
+                        .peer(&peer)
+                        .error(&error.into())
+                );
+            } else {
+                counters::shared_mempool_transaction_filter_inc(
+                    peer.network_id(),
+                    counters::FILTER_SENT_LABEL,
+                );
+            }
+        }
+    }
+
+    /// Fetches the transactions with the given hashes from the given peer (via RPC). The
+    /// hashes are split into multiple requests (of at most the broadcast batch size each).
+    /// Each response is matched against its request, so any transactions that weren't
+    /// requested (or are duplicates) are dropped.
+    pub async fn fetch_transactions(
+        &self,
+        peer: PeerNetworkId,
+        hashes: Vec<HashValue>,
+    ) -> Result<Vec<SignedTransaction>, Error> {
+        let max_hashes_per_request = self.mempool_config.shared_mempool_batch_size.max(1);
+        let rpc_timeout = Duration::from_millis(self.mempool_config.shared_mempool_ack_timeout_ms);
+
+        let mut fetched_transactions = vec![];
+        for hashes in hashes.chunks(max_hashes_per_request) {
+            let request = MempoolSyncMsg::RequestTransactions {
+                hashes: hashes.to_vec(),
+            };
+            counters::shared_mempool_transaction_fetch_inc(
+                peer.network_id(),
+                counters::FETCH_REQUEST_SENT_LABEL,
+            );
+            let response = match self
+                .network_client
+                .send_to_peer_rpc(request, rpc_timeout, peer)
+                .await
+            {
+                Ok(response) => response,
+                Err(error) => {
+                    counters::network_send_fail_inc(counters::TRANSACTION_FETCH);
+                    return Err(error);
+                },
+            };
+            let MempoolSyncMsg::RequestTransactionsResponse { transactions } = response else {
+                return Err(Error::UnexpectedError(
+                    "Received an invalid response to a transaction request".into(),
+                ));
+            };
+            counters::shared_mempool_transaction_fetch_inc(
+                peer.network_id(),
+                counters::FETCH_RESPONSE_RECEIVED_LABEL,
+            );
+
+            // Only keep the requested transactions (each at most once)
+            let mut requested_hashes: HashSet<_> = hashes.iter().collect();
+            for transaction in transactions {
+                if requested_hashes.remove(&transaction.committed_hash()) {
+                    fetched_transactions.push(transaction);
+                } else {
+                    counters::shared_mempool_transaction_fetch_inc(
+                        peer.network_id(),
+                        counters::FETCH_UNREQUESTED_TRANSACTION_LABEL,
+                    );
+                }
+            }
+        }
+        Ok(fetched_transactions)
+    }
+
+    /// Removes the transactions held by the given peer (according to the latest
+    /// filter received from it) from the batch. Returns the removed transactions.
+    fn remove_filtered_transactions(
+        &self,
+        state: &PeerSyncState,
+        transactions: &mut Vec<(SignedTransaction, u64, BroadcastPeerPriority)>,
+    ) -> Vec<FilteredTransaction> {
+        let max_filter_age_ms = match &self.mempool_config.broadcast_filter {
+            Some(broadcast_filter_config) => broadcast_filter_config.max_filter_age_ms,
+            None => return vec![], // Broadcast filters are disabled
+        };
+
+        match &state.transaction_filter {
+            Some((filter, received_time))
+                if received_time.elapsed() <= Duration::from_millis(max_filter_age_ms) =>
+            {
+                let mut filtered_transactions = vec![];
+                transactions.retain(|(txn, ready_time, priority)| {
+                    let hash = txn.committed_hash();
+                    let filtered = filter.contains(&hash);
+                    if filtered {
+                        filtered_transactions.push((hash, *ready_time, priority.clone()));
+                    }
+                    !filtered
+                });
+                filtered_transactions
+            },
+            _ => vec![], // The peer has no (fresh) filter
+        }
+    }
+
+    /// Skips the transactions held by the given peer (according to the latest filter
+    /// received from it) in a fresh broadcast. The skipped transactions are recorded
+    /// (up to the filter capacity), so that they can be re-checked against the peer's
+    /// next filter (instead of being considered delivered).
+    fn skip_filtered_transactions(
+        &self,
+        peer: &PeerNetworkId,
+        state: &mut PeerSyncState,
+        transactions: &mut Vec<(SignedTransaction, u64, BroadcastPeerPriority)>,
+    ) {
+        let filtered_transactions = self.remove_filtered_transactions(state, transactions);
+        if filtered_transactions.is_empty() {
+            return;
+        }
+
+        counters::shared_mempool_filtered_broadcast_txns_inc(
+            peer.network_id(),
+            filtered_transactions.len(),
+        );
+        if let Some(broadcast_filter_config) = &self.mempool_config.broadcast_filter {
+            let max_filtered_transactions = broadcast_filter_config.max_filter_transactions;
+            let num_remaining =
+                max_filtered_transactions.saturating_sub(state.filtered_transactions.len());
+            state
+                .filtered_transactions
+                .extend(filtered_transactions.into_iter().take(num_remaining));
+        }
+    }
+
+    /// Returns (up to `max_txns` of) the filtered transactions that didn't match the peer's
+    /// next filter, and that are still in mempool. These must still be broadcast to the peer.
+    fn take_unfiltered_transactions(
+        &self,
+        state: &mut PeerSyncState,
+        max_txns: usize,
+        mempool: &ShardedMempool,
+    ) -> Vec<(SignedTransaction, u64, BroadcastPeerPriority)> {
+        let num_txns = max_txns.min(state.unfiltered_transactions.len());
+        state
+            .unfiltered_transactions
+            .drain(..num_txns)
+            .filter_map(|(hash, ready_time, priority)| {
+                mempool
+                    .get_by_hash(hash)
+                    .map(|txn| (txn, ready_time, priority))
+            })
+            .collect()
+    }
+
     /// Determines the broadcast batch.  There are three types of batches:
     /// * Expired -> This timed out waiting for a response and needs to be resent
     /// * Retry -> This received a response telling it to retry later
@@ -382,7 +1018,7 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         // Sync peer's pending broadcasts with latest mempool state.
         // A pending or retry broadcast might become empty if the corresponding txns were committed through
         // another peer, so don't track broadcasts for committed txns.
-        let mempool = smp.mempool.lock();
+        let mempool = &smp.mempool;
         state.broadcast_info.sent_messages = state
             .broadcast_info
             .sent_messages
@@ -410,7 +1046,7 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         // 1. Batch that did not receive ACK in configured window of time
         // 2. Batch that an earlier ACK marked as retriable
         let mut pending_broadcasts = 0;
-        let mut expired_message_id = None;
+        let mut expired_message_ids = vec![];
 
         // Find earliest message in timeline index that expired.
         // Note that state.broadcast_info.sent_messages is ordered in decreasing order in the timeline index
@@ -419,7 +1055,7 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
                 self.mempool_config.shared_mempool_ack_timeout_ms,
             ));
             if SystemTime::now().duration_since(deadline).is_ok() {
-                expired_message_id = Some(message);
+                expired_message_ids.push(message);
             } else {
                 pending_broadcasts += 1;
             }
@@ -433,45 +1069,45 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
                 return Err(BroadcastError::TooManyPendingBroadcasts(peer));
             }
         }
-        let retry_message_id = state.broadcast_info.retry_messages.iter().next_back();
+        let mut expired_message_id = expired_message_ids.last().copied();
+        let mut retry_message_id = state.broadcast_info.retry_messages.iter().next_back();
 
-        let (message_id, transactions, metric_label) =
+        // If expiration-based prioritization is enabled, rebroadcast the message holding the
+        // transaction closest to expiration first (instead of the latest message).
+        if !self.mempool_config.broadcast_urgency_bands_secs.is_empty() {
+            let candidate_message_ids = expired_message_ids
+                .iter()
+                .copied()
+                .chain(state.broadcast_info.retry_messages.iter());
+            if let Some(message_id) =
+                self.get_most_urgent_message(&peer, candidate_message_ids, mempool)
+            {
+                if expired_message_ids.contains(&message_id) {
+                    (expired_message_id, retry_message_id) = (Some(message_id), None);
+                } else {
+                    (expired_message_id, retry_message_id) = (None, Some(message_id));
+                }
+            }
+        }
+
+        let (message_id, mut transactions, metric_label) =
             match std::cmp::max(expired_message_id, retry_message_id) {
                 Some(message_id) => {
-                    let metric_label = if Some(message_id) == expired_message_id {
+                    let expired = Some(message_id) == expired_message_id;
+                    let message_id = message_id.clone();
+                    let metric_label = if expired {
+                        // The broadcast timed out waiting for an ACK
+                        state.broadcast_info.record_broadcast_failure(&message_id);
                         Some(counters::EXPIRED_BROADCAST_LABEL)
                     } else {
                         Some(counters::RETRY_BROADCAST_LABEL)
                     };
 
-                    let txns = message_id
-                        .decode()
-                        .into_iter()
-                        .flat_map(|(sender_bucket, start_end_pairs)| {
-                            if self.node_type.is_validator() {
-                                mempool
-                                    .timeline_range(sender_bucket, start_end_pairs)
-                                    .into_iter()
-                                    .map(|(txn, ready_time)| {
-                                        (txn, ready_time, BroadcastPeerPriority::Primary)
-                                    })
-                                    .collect::<Vec<_>>()
-                            } else {
-                                self.prioritized_peers_state
-                                    .get_sender_bucket_priority_for_peer(&peer, sender_bucket)
-                                    .map_or_else(Vec::new, |priority| {
-                                        mempool
-                                            .timeline_range(sender_bucket, start_end_pairs)
-                                            .into_iter()
-                                            .map(|(txn, ready_time)| {
-                                                (txn, ready_time, priority.clone())
-                                            })
-                                            .collect::<Vec<_>>()
-                                    })
-                            }
-                        })
-                        .collect::<Vec<_>>();
-                    (message_id.clone(), txns, metric_label)
+                    // Don't resend the transactions the peer already holds (these were
+                    // skipped when the broadcast was first sent, if the filter matched).
+                    let mut txns = self.get_message_transactions(&peer, &message_id, mempool);
+                    self.remove_filtered_transactions(state, &mut txns);
+                    (message_id, txns, metric_label)
                 },
                 None => {
                     // Fresh broadcast
@@ -508,7 +1144,10 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
                         }
                     });
 
-                    let max_txns = self.mempool_config.shared_mempool_batch_size;
+                    let max_txns = state.broadcast_info.get_batch_size(
+                        self.mempool_config.shared_mempool_batch_size,
+                        self.mempool_config.adaptive_batch_sizing.as_ref(),
+                    );
                     let mut output_txns = vec![];
                     let mut output_updates = vec![];
                     for (sender_bucket, peer_priority) in sender_buckets {
@@ -542,21 +1181,131 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
                         }
                     }
 
-                    (
-                        MempoolMessageId::from_timeline_ids(output_updates),
-                        output_txns,
-                        None,
-                    )
+                    // Skip the transactions the peer already holds (these are recorded for
+                    // re-checking against the peer's next filter), and add any previously
+                    // skipped transactions that turned out to be false positives. These are
+                    // not covered by the message id (so they are not resent on retries).
+                    let message_id = MempoolMessageId::from_timeline_ids(output_updates);
+                    let num_timeline_txns = output_txns.len();
+                    self.skip_filtered_transactions(&peer, state, &mut output_txns);
+                    output_txns.extend(self.take_unfiltered_transactions(state, max_txns, mempool));
+
+                    // If all timeline transactions were skipped (and there is nothing else
+                    // to send), advance the timelines, as the batch doesn't need to be sent.
+                    if output_txns.is_empty() && num_timeline_txns > 0 {
+                        state.update(&message_id);
+                    }
+
+                    (message_id, output_txns, None)
                 },
             };
 
+        // If the broadcast timed out waiting for an ACK, shrink the peer's batch size
+        if metric_label == Some(counters::EXPIRED_BROADCAST_LABEL) {
+            self.adapt_broadcast_batch_size(&peer, &mut state.broadcast_info, false);
+        }
+
         if transactions.is_empty() {
             return Err(BroadcastError::NoTransactions(peer));
         }
 
+        // Order the transactions in the batch by urgency (the sort is stable, so the
+        // original order is preserved for transactions in the same urgency band).
+        if !self.mempool_config.broadcast_urgency_bands_secs.is_empty() {
+            let now_secs = current_time_secs();
+            transactions.sort_by_cached_key(|(txn, _, _)| self.get_urgency_band(txn, now_secs));
+        }
+
         Ok((message_id, transactions, metric_label))
     }
 
+    /// Returns the transactions (that are still in mempool) of the given broadcast message
+    fn get_message_transactions(
+        &self,
+        peer: &PeerNetworkId,
+        message_id: &MempoolMessageId,
+        mempool: &ShardedMempool,
+    ) -> Vec<(SignedTransaction, u64, BroadcastPeerPriority)> {
+        message_id
+            .decode()
+            .into_iter()
+            .flat_map(|(sender_bucket, start_end_pairs)| {
+                if self.node_type.is_validator() {
+                    mempool
+                        .timeline_range(sender_bucket, start_end_pairs)
+                        .into_iter()
+                        .map(|(txn, ready_time)| (txn, ready_time, BroadcastPeerPriority::Primary))
+                        .collect::<Vec<_>>()
+                } else {
+                    self.prioritized_peers_state
+                        .get_sender_bucket_priority_for_peer(peer, sender_bucket)
+                        .map_or_else(Vec::new, |priority| {
+                            mempool
+                                .timeline_range(sender_bucket, start_end_pairs)
+                                .into_iter()
+                                .map(|(txn, ready_time)| (txn, ready_time, priority.clone()))
+                                .collect::<Vec<_>>()
+                        })
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the message (of the given candidates) holding the transaction closest to
+    /// expiration. See [`most_urgent_message`].
+    fn get_most_urgent_message<'a>(
+        &self,
+        peer: &PeerNetworkId,
+        candidate_message_ids: impl Iterator<Item = &'a MempoolMessageId>,
+        mempool: &ShardedMempool,
+    ) -> Option<&'a MempoolMessageId> {
+        let candidates = candidate_message_ids.map(|message_id| {
+            (
+                message_id,
+                self.get_message_min_expiration_secs(peer, message_id, mempool),
+            )
+        });
+        most_urgent_message(
+            &self.mempool_config.broadcast_urgency_bands_secs,
+            candidates,
+            current_time_secs(),
+        )
+    }
+
+    /// Returns the earliest expiration time of the transactions (that are still in mempool)
+    /// of the given broadcast message. Unlike [`Self::get_message_transactions`], this
+    /// doesn't clone the transactions.
+    fn get_message_min_expiration_secs(
+        &self,
+        peer: &PeerNetworkId,
+        message_id: &MempoolMessageId,
+        mempool: &ShardedMempool,
+    ) -> Option<u64> {
+        message_id
+            .decode()
+            .into_iter()
+            .filter(|(sender_bucket, _)| {
+                self.node_type.is_validator()
+                    || self
+                        .prioritized_peers_state
+                        .get_sender_bucket_priority_for_peer(peer, *sender_bucket)
+                        .is_some()
+            })
+            .filter_map(|(sender_bucket, start_end_pairs)| {
+                mempool.timeline_range_min_expiration_secs(sender_bucket, start_end_pairs)
+            })
+            .min()
+    }
+
+    /// Returns the broadcast urgency band of the given transaction
+    fn get_urgency_band(&self, txn: &SignedTransaction, now_secs: u64) -> usize {
+        expiration_urgency_band(
+            &self.mempool_config.broadcast_urgency_bands_secs,
+            txn.expiration_timestamp_secs(),
+            now_secs,
+        )
+    }
+
     /// Sends a batch to the given peer
     async fn send_batch_to_peer(
         &self,
@@ -565,18 +1314,70 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         // For each transaction, we include the ready time in millis since epoch
         transactions: Vec<(SignedTransaction, u64, BroadcastPeerPriority)>,
     ) -> Result<(), BroadcastError> {
-        let request = if self.mempool_config.include_ready_time_in_broadcast {
-            MempoolSyncMsg::BroadcastTransactionsRequestWithReadyTime {
-                message_id,
-                transactions,
-            }
+        // Identify the type of broadcast request to send. Delivery receipts are
+        // opted into by the sender (per request), so receivers only send receipts
+        // to the peers that asked for them.
+        let request_receipt = self.mempool_config.enable_broadcast_delivery_receipts;
+        let include_ready_time =
+            request_receipt || self.mempool_config.include_ready_time_in_broadcast;
+        let request_type = if request_receipt {
+            BroadcastRequestType::WithReceipt
+        } else if include_ready_time {
+            BroadcastRequestType::WithReadyTime
         } else {
-            MempoolSyncMsg::BroadcastTransactionsRequest {
+            BroadcastRequestType::Plain
+        };
+
+        // If the serialization cache is enabled, reuse (or cache) the serialized batch
+        let serialization_cache_and_key = self.serialization_cache.as_ref().map(|cache| {
+            let batch_key = BroadcastBatchKey::new(&message_id, &transactions, request_type);
+            (cache, batch_key)
+        });
+        if let Some((serialization_cache, batch_key)) = &serialization_cache_and_key {
+            let cached_batch = serialization_cache.lock().get(batch_key);
+            if let Some(serialized_batch) = cached_batch {
+                return self.send_serialized_batch_to_peer(peer, serialized_batch);
+            }
+        }
+
+        let request = match request_type {
+            BroadcastRequestType::WithReceipt => {
+                MempoolSyncMsg::BroadcastTransactionsRequestWithReceipt {
+                    message_id,
+                    transactions,
+                }
+            },
+            BroadcastRequestType::WithReadyTime => {
+                MempoolSyncMsg::BroadcastTransactionsRequestWithReadyTime {
+                    message_id,
+                    transactions,
+                }
+            },
+            BroadcastRequestType::Plain => MempoolSyncMsg::BroadcastTransactionsRequest {
                 message_id,
                 transactions: transactions.into_iter().map(|(txn, _, _)| txn).collect(),
-            }
+            },
         };
 
+        // Serialize the batch (and cache it) before sending, if the cache is enabled
+        if let Some((serialization_cache, batch_key)) = serialization_cache_and_key {
+            let serialized_batch = self
+                .network_client
+                .to_bytes_by_protocol(vec![peer], request)
+                .map_err(|error| BroadcastError::NetworkError(peer, error))?
+                .remove(&peer)
+                .ok_or_else(|| {
+                    BroadcastError::NetworkError(
+                        peer,
+                        anyhow::anyhow!("Failed to serialize the batch for the peer!"),
+                    )
+                })?;
+            serialization_cache
+                .lock()
+                .insert(batch_key, serialized_batch.clone());
+            return self.send_serialized_batch_to_peer(peer, serialized_batch);
+        }
+
         if let Err(e) = self.network_client.send_to_peer(request, peer) {
             counters::network_send_fail_inc(counters::BROADCAST_TXNS);
             return Err(BroadcastError::NetworkError(peer, e.into()));
@@ -584,6 +1385,19 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         Ok(())
     }
 
+    /// Sends an already serialized batch to the given peer
+    fn send_serialized_batch_to_peer(
+        &self,
+        peer: PeerNetworkId,
+        serialized_batch: Bytes,
+    ) -> Result<(), BroadcastError> {
+        if let Err(e) = self.network_client.send_to_peer_raw(serialized_batch, peer) {
+            counters::network_send_fail_inc(counters::BROADCAST_TXNS);
+            return Err(BroadcastError::NetworkError(peer, e.into()));
+        }
+        Ok(())
+    }
+
     /// Sends a message to the given peer
     pub fn send_message_to_peer(
         &self,
@@ -633,8 +1447,13 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
             self.determine_broadcast_batch(peer, scheduled_backoff, smp)?;
         let num_txns = transactions.len();
         let send_time = SystemTime::now();
-        self.send_batch_to_peer(peer, message_id.clone(), transactions)
-            .await?;
+        if let Err(error) = self
+            .send_batch_to_peer(peer, message_id.clone(), transactions)
+            .await
+        {
+            self.record_broadcast_failure(&peer, &message_id);
+            return Err(error);
+        }
         let num_pending_broadcasts =
             self.update_broadcast_state(peer, message_id.clone(), send_time)?;
         notify_subscribers(SharedMempoolNotification::Broadcast, &smp.subscribers);
@@ -665,6 +1484,31 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
         Ok(())
     }
 
+    /// Records a failed broadcast (with the given message ID) to the given peer
+    fn record_broadcast_failure(&self, peer: &PeerNetworkId, message_id: &MempoolMessageId) {
+        if let Some(state) = self.sync_states.write().get_mut(peer) {
+            state.broadcast_info.record_broadcast_failure(message_id);
+            self.adapt_broadcast_batch_size(peer, &mut state.broadcast_info, false);
+        }
+    }
+
+    /// Adapts the broadcast batch size of the given peer (if adaptive batch sizing is enabled)
+    fn adapt_broadcast_batch_size(
+        &self,
+        peer: &PeerNetworkId,
+        broadcast_info: &mut BroadcastInfo,
+        fast_ack: bool,
+    ) {
+        if let Some(config) = &self.mempool_config.adaptive_batch_sizing {
+            let batch_size = broadcast_info.adapt_batch_size(
+                config,
+                self.mempool_config.shared_mempool_batch_size,
+                fast_ack,
+            );
+            counters::shared_mempool_broadcast_batch_size(peer).set(batch_size as i64);
+        }
+    }
+
     pub fn sync_states_exists(&self, peer: &PeerNetworkId) -> bool {
         self.sync_states.read().get(peer).is_some()
     }
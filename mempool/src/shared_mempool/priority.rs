@@ -1,25 +1,120 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::types::MempoolSenderBucket;
+use super::{
+    priority_snapshot::{PrioritizedPeerSnapshot, PrioritizedPeersSnapshot},
+    types::MempoolSenderBucket,
+};
 use crate::{counters, network::BroadcastPeerPriority};
 use aptos_config::{
-    config::{MempoolConfig, NodeType},
+    config::{
+        BroadcastPeerSelectionMode, MempoolConfig, NetworkFanoutConfig, NodeType,
+        PeerMisbehaviorScoringConfig, PeerPrioritizationParams, PeerPrioritizationStrategy,
+    },
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_infallible::RwLock;
 use aptos_logger::prelude::*;
+use aptos_network::transport::ConnectionMetadata;
 use aptos_peer_monitoring_service_types::PeerMonitoringMetadata;
 use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::{
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    network_address::{NetworkAddress, Protocol},
+    vm_status::StatusCode,
+};
 use itertools::Itertools;
+use rand::Rng;
+use serde::Serialize;
 use std::{
     cmp::{max, min, Ordering},
-    collections::{hash_map::RandomState, HashMap},
+    collections::{hash_map::RandomState, HashMap, HashSet},
     hash::{BuildHasher, Hasher},
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
+/// The interval (in seconds) at which the shadow peer rankings are logged
+const SHADOW_RANKING_LOG_INTERVAL_SECS: u64 = 60;
+
+/// The priority of a single peer, along with the metadata that produced it
+/// (as observed at the last priority update). This is exposed for debugging.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PrioritizedPeerInfo {
+    /// The peer (and the network it belongs to)
+    pub peer: String,
+    /// The priority of the peer (the lower the value, the higher the priority)
+    pub priority: usize,
+    /// The network of the peer
+    pub network_id: NetworkId,
+    /// The distance of the peer from the validators (if known)
+    pub distance_from_validators: Option<u64>,
+    /// The average ping latency (in seconds) to the peer (if known)
+    pub average_ping_latency_secs: Option<f64>,
+    /// The average broadcast ACK latency (in seconds) of the peer (if known)
+    pub broadcast_ack_latency_secs: Option<f64>,
+    /// The mempool utilization (as a fraction of capacity) reported by the peer (if known)
+    pub mempool_utilization: Option<f64>,
+    /// The network group (e.g., /24 subnet) of the peer (if known)
+    pub network_group: Option<String>,
+    /// Whether the peer repeatedly failed broadcasts
+    pub failed_broadcasts: bool,
+    /// Whether the peer is penalized for misbehavior (e.g., sending invalid transactions)
+    pub penalized: bool,
+    /// Whether the peer is pinned to the top of the prioritized peers
+    pub pinned: bool,
+    /// Whether the peer is a primary broadcast target for any sender bucket
+    pub is_top_peer: bool,
+}
+
+/// The counts of the transactions received from a peer in the current scoring
+/// window. These are used to identify misbehaving (e.g., spamming) peers.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PeerMisbehaviorCounts {
+    num_received: u64, // The number of transactions received from the peer
+    num_invalid: u64,  // The number of transactions that failed validation
+    num_expired: u64,  // The number of transactions that were already expired
+}
+
+impl PeerMisbehaviorCounts {
+    /// Records the result of processing a transaction received from the peer
+    pub fn record_transaction(
+        &mut self,
+        mempool_status: &MempoolStatus,
+        vm_status: Option<StatusCode>,
+    ) {
+        self.num_received += 1;
+        match (mempool_status.code, vm_status) {
+            (MempoolStatusCode::VmError, Some(StatusCode::TRANSACTION_EXPIRED)) => {
+                self.num_expired += 1;
+            },
+            // Transactions that were just committed (e.g., resent by a peer that hasn't
+            // seen the commit yet) are the result of commit races, not misbehavior.
+            (MempoolStatusCode::VmError, Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD)) => {},
+            (MempoolStatusCode::VmError, _) => {
+                self.num_invalid += 1;
+            },
+            // The transaction was accepted, or rejected due to local state (e.g., limits,
+            // conflicting updates, or a sequence number that is stale only because of a
+            // local commit race).
+            _ => {},
+        }
+    }
+
+    /// Returns true iff the current scoring window is complete
+    pub fn is_window_complete(&self, config: &PeerMisbehaviorScoringConfig) -> bool {
+        self.num_received >= config.transactions_per_scoring_window
+    }
+
+    /// Returns true iff the percentage of misbehaving (i.e., invalid
+    /// or expired) transactions is at or above the threshold.
+    pub fn is_misbehaving(&self, config: &PeerMisbehaviorScoringConfig) -> bool {
+        let num_misbehaving = self.num_invalid + self.num_expired;
+        self.num_received > 0
+            && num_misbehaving * 100 >= self.num_received * config.misbehavior_threshold_percent
+    }
+}
+
 /// A simple struct that offers comparisons and ordering for peer prioritization
 #[derive(Clone, Debug)]
 struct PrioritizedPeersComparator {
@@ -39,12 +134,22 @@ impl PrioritizedPeersComparator {
         &self,
         peer_a: &(PeerNetworkId, Option<&PeerMonitoringMetadata>),
         peer_b: &(PeerNetworkId, Option<&PeerMonitoringMetadata>),
+        penalized_peers: &HashSet<PeerNetworkId>,
     ) -> Ordering {
         // Deconstruct the peer tuples
         let (peer_network_id_a, _) = peer_a;
         let (peer_network_id_b, _) = peer_b;
 
-        // First, compare by network ID (i.e., Validator > VFN > Public)
+        // First, compare by misbehavior (i.e., penalized peers are always at the bottom)
+        let misbehavior_ordering = compare_misbehavior(
+            penalized_peers.contains(peer_network_id_a),
+            penalized_peers.contains(peer_network_id_b),
+        );
+        if !misbehavior_ordering.is_eq() {
+            return misbehavior_ordering; // Only return if it's not equal
+        }
+
+        // Otherwise, compare by network ID (i.e., Validator > VFN > Public)
         let network_ordering = compare_network_id(
             &peer_network_id_a.network_id(),
             &peer_network_id_b.network_id(),
@@ -63,12 +168,26 @@ impl PrioritizedPeersComparator {
         &self,
         peer_a: &(PeerNetworkId, Option<&PeerMonitoringMetadata>),
         peer_b: &(PeerNetworkId, Option<&PeerMonitoringMetadata>),
+        broadcast_ack_latencies: &HashMap<PeerNetworkId, f64>,
+        penalized_peers: &HashSet<PeerNetworkId>,
+        ping_latency_slack_ms: u64,
+        mempool_full_threshold: f64,
     ) -> Ordering {
         // Deconstruct the peer tuples
         let (peer_network_id_a, monitoring_metadata_a) = peer_a;
         let (peer_network_id_b, monitoring_metadata_b) = peer_b;
 
-        // First, compare by network ID (i.e., Validator > VFN > Public)
+        // First, compare by misbehavior (i.e., penalized peers are always at the bottom).
+        // This ensures that spamming peers are deprioritized, regardless of their latency.
+        let misbehavior_ordering = compare_misbehavior(
+            penalized_peers.contains(peer_network_id_a),
+            penalized_peers.contains(peer_network_id_b),
+        );
+        if !misbehavior_ordering.is_eq() {
+            return misbehavior_ordering; // Only return if it's not equal
+        }
+
+        // Otherwise, compare by network ID (i.e., Validator > VFN > Public)
         let network_ordering = compare_network_id(
             &peer_network_id_a.network_id(),
             &peer_network_id_b.network_id(),
@@ -85,17 +204,92 @@ impl PrioritizedPeersComparator {
             return distance_ordering; // Only return if it's not equal
         }
 
-        // Otherwise, compare by peer ping latency (the lower the better)
-        let latency_ordering = compare_ping_latency(monitoring_metadata_a, monitoring_metadata_b);
+        // Otherwise, compare by mempool fullness (peers with nearly full mempools
+        // are deprioritized, as they are likely to drop or delay our broadcasts).
+        let mempool_ordering = compare_mempool_fullness(
+            monitoring_metadata_a,
+            monitoring_metadata_b,
+            mempool_full_threshold,
+        );
+        if !mempool_ordering.is_eq() {
+            return mempool_ordering; // Only return if it's not equal
+        }
+
+        // Otherwise, compare by peer ping latency (the lower the better).
+        // Ping latencies in the same bucket (of the slack size) are considered equal.
+        let latency_ordering = compare_ping_latency_buckets(
+            monitoring_metadata_a,
+            monitoring_metadata_b,
+            ping_latency_slack_ms,
+        );
         if !latency_ordering.is_eq() {
             return latency_ordering; // Only return if it's not equal
         }
 
+        // Otherwise, compare by broadcast ACK latency (the lower the better).
+        // This deprioritizes peers that respond to pings quickly, but are slow
+        // to process broadcasts (e.g., because their mempools are overloaded).
+        let ack_latency_ordering = compare_broadcast_ack_latency(
+            broadcast_ack_latencies.get(peer_network_id_a),
+            broadcast_ack_latencies.get(peer_network_id_b),
+        );
+        if !ack_latency_ordering.is_eq() {
+            return ack_latency_ordering; // Only return if it's not equal
+        }
+
         // Otherwise, simply hash the peer IDs and compare the hashes.
         // In practice, this should be relatively rare.
         self.compare_hash(peer_network_id_a, peer_network_id_b)
     }
 
+    /// Reorders the given peers (sorted by descending priority) to favor network diversity.
+    /// Within each network ID, a peer that is the k-th peer of its network group (e.g., the
+    /// same /24 subnet or rack) is placed after all peers that are the (k-1)-th of their
+    /// group. Otherwise, the existing order is preserved. Peers without a known network
+    /// group are never penalized.
+    fn diversify_by_network_group(
+        &self,
+        sorted_peers: Vec<PeerNetworkId>,
+        peer_network_groups: &HashMap<PeerNetworkId, String>,
+    ) -> Vec<PeerNetworkId> {
+        let mut network_start_positions: HashMap<NetworkId, usize> = HashMap::new();
+        let mut network_group_counts: HashMap<(NetworkId, &String), usize> = HashMap::new();
+        sorted_peers
+            .into_iter()
+            .enumerate()
+            .map(|(position, peer)| {
+                // Peers are already grouped by network ID, so keep each network in place
+                let network_id = peer.network_id();
+                let network_start_position = *network_start_positions
+                    .entry(network_id)
+                    .or_insert(position);
+
+                // Count the higher priority peers in the same network group
+                let num_higher_priority_peers_in_group = match peer_network_groups.get(&peer) {
+                    Some(network_group) => {
+                        let count = network_group_counts
+                            .entry((network_id, network_group))
+                            .or_insert(0);
+                        *count += 1;
+                        *count - 1
+                    },
+                    None => 0,
+                };
+
+                (
+                    (
+                        network_start_position,
+                        num_higher_priority_peers_in_group,
+                        position,
+                    ),
+                    peer,
+                )
+            })
+            .sorted_by_key(|(sort_key, _)| *sort_key)
+            .map(|(_, peer)| peer)
+            .collect()
+    }
+
     /// Compares the hash of the given peer IDs
     fn compare_hash(
         &self,
@@ -121,9 +315,20 @@ pub struct PrioritizedPeersState {
     // The current mempool configuration
     mempool_config: MempoolConfig,
 
+    // The prioritization parameters set at runtime (if any), that
+    // will be applied to the config on the next priority update.
+    pending_prioritization_params: Option<PeerPrioritizationParams>,
+
     // The current list of prioritized peers
     prioritized_peers: Arc<RwLock<Vec<PeerNetworkId>>>,
 
+    // The priority (i.e., index in the prioritized peers list) of each peer. This
+    // is updated with the prioritized peers, and used for constant time lookups.
+    peer_priorities: Arc<RwLock<HashMap<PeerNetworkId, usize>>>,
+
+    // The current list of prioritized peers (with the metadata that produced it)
+    prioritized_peers_info: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+
     // We divide mempool transactions into buckets based on hash of the sender.
     // For load balancing, we send transactions from a subset of buckets to a peer.
     // This map stores the buckets that are sent to a peer and the priority of the peer
@@ -137,6 +342,28 @@ pub struct PrioritizedPeersState {
     // Whether ping latencies were observed for all peers
     observed_all_ping_latencies: bool,
 
+    // The average broadcast ACK latencies (in seconds) observed for each peer
+    broadcast_ack_latencies: HashMap<PeerNetworkId, f64>,
+
+    // The network groups (e.g., /24 subnets or racks) of each peer
+    peer_network_groups: HashMap<PeerNetworkId, String>,
+
+    // The peers that repeatedly failed broadcasts (these are not selected as top peers)
+    failed_peers: HashSet<PeerNetworkId>,
+
+    // The peers penalized for misbehavior (these are deprioritized and not selected as top peers)
+    penalized_peers: HashSet<PeerNetworkId>,
+
+    // The latencies restored from the prioritized peers snapshot. These are used for
+    // peers whose ping latencies haven't been observed yet (i.e., after a restart).
+    restored_peer_latencies: HashMap<PeerNetworkId, PrioritizedPeerSnapshot>,
+
+    // The last time the prioritized peers snapshot was saved
+    last_snapshot_save_time: Option<Instant>,
+
+    // The distance from the validators and ping latency of each peer (at the last update)
+    prioritized_peers_monitoring: HashMap<PeerNetworkId, (Option<u64>, Option<f64>)>,
+
     // The last time peer priorities were updated
     last_peer_priority_update: Option<Instant>,
 
@@ -153,26 +380,179 @@ impl PrioritizedPeersState {
         node_type: NodeType,
         time_service: TimeService,
     ) -> Self {
-        Self {
+        let mut prioritized_peers_state = Self {
             mempool_config,
+            pending_prioritization_params: None,
             prioritized_peers: Arc::new(RwLock::new(Vec::new())),
+            peer_priorities: Arc::new(RwLock::new(HashMap::new())),
+            prioritized_peers_info: Arc::new(RwLock::new(Vec::new())),
             peer_comparator: PrioritizedPeersComparator::new(),
             observed_all_ping_latencies: false,
+            broadcast_ack_latencies: HashMap::new(),
+            peer_network_groups: HashMap::new(),
+            failed_peers: HashSet::new(),
+            penalized_peers: HashSet::new(),
+            restored_peer_latencies: HashMap::new(),
+            last_snapshot_save_time: None,
+            prioritized_peers_monitoring: HashMap::new(),
             last_peer_priority_update: None,
             time_service,
             peer_to_sender_buckets: HashMap::new(),
             node_type,
+        };
+        prioritized_peers_state.restore_prioritized_peers_snapshot();
+        prioritized_peers_state
+    }
+
+    /// Restores the latencies observed for the prioritized peers before the last restart
+    /// (if a recent snapshot exists). These are used until the latencies of each peer are
+    /// observed again, so that known-good peers are prioritized immediately.
+    fn restore_prioritized_peers_snapshot(&mut self) {
+        let Some(snapshot_path) = &self.mempool_config.prioritized_peers_snapshot_path else {
+            return; // Snapshots are disabled
+        };
+
+        // Load the snapshot
+        let snapshot = match PrioritizedPeersSnapshot::load(snapshot_path) {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return, // No snapshot exists
+            Err(error) => {
+                warn!("Failed to load the prioritized peers snapshot: {:?}", error);
+                return;
+            },
+        };
+        if snapshot.is_expired(
+            self.time_service.now_secs(),
+            self.mempool_config.prioritized_peers_snapshot_max_age_secs,
+        ) {
+            info!(
+                "Ignoring the expired prioritized peers snapshot (taken at: {:?} secs)",
+                snapshot.timestamp_secs
+            );
+            return;
+        }
+
+        // Restore the latencies of each peer
+        for peer_snapshot in snapshot.peers {
+            if let Some(broadcast_ack_latency_secs) = peer_snapshot.broadcast_ack_latency_secs {
+                self.broadcast_ack_latencies
+                    .insert(peer_snapshot.peer, broadcast_ack_latency_secs);
+            }
+            self.restored_peer_latencies
+                .insert(peer_snapshot.peer, peer_snapshot);
+        }
+        info!(
+            "Restored the prioritized peers snapshot. Number of peers: {:?}",
+            self.restored_peer_latencies.len()
+        );
+    }
+
+    /// Saves the prioritized peers (and the latencies observed for them) to the
+    /// snapshot file (if enabled), so that they can be restored after a restart.
+    /// To avoid writing the file on every update, saves are rate limited.
+    fn save_prioritized_peers_snapshot(
+        &mut self,
+        peers_and_metadata: &[(PeerNetworkId, Option<&PeerMonitoringMetadata>)],
+    ) {
+        let Some(snapshot_path) = &self.mempool_config.prioritized_peers_snapshot_path else {
+            return; // Snapshots are disabled
+        };
+
+        // Check if the snapshot was saved recently
+        let now = self.time_service.now();
+        let snapshot_interval =
+            Duration::from_secs(self.mempool_config.prioritized_peers_snapshot_interval_secs);
+        if let Some(last_snapshot_save_time) = self.last_snapshot_save_time {
+            if now.duration_since(last_snapshot_save_time) < snapshot_interval {
+                return;
+            }
+        }
+        self.last_snapshot_save_time = Some(now);
+
+        // Create the snapshot
+        let ping_latencies: HashMap<_, _> = peers_and_metadata
+            .iter()
+            .map(|(peer, metadata)| (*peer, get_peer_ping_latency(metadata)))
+            .collect();
+        let peers = self
+            .prioritized_peers
+            .read()
+            .iter()
+            .map(|peer| PrioritizedPeerSnapshot {
+                peer: *peer,
+                average_ping_latency_secs: ping_latencies.get(peer).copied().flatten(),
+                broadcast_ack_latency_secs: self.broadcast_ack_latencies.get(peer).copied(),
+            })
+            .collect();
+        let snapshot = PrioritizedPeersSnapshot {
+            timestamp_secs: self.time_service.now_secs(),
+            peers,
+        };
+
+        // Save the snapshot
+        if let Err(error) = snapshot.save(snapshot_path) {
+            warn!("Failed to save the prioritized peers snapshot: {:?}", error);
         }
     }
 
+    /// Returns the monitoring metadata (with the restored ping latencies) of the
+    /// peers whose ping latencies haven't been observed yet, but were restored.
+    fn get_restored_monitoring_metadata(
+        &self,
+        peers_and_metadata: &[(PeerNetworkId, Option<&PeerMonitoringMetadata>)],
+    ) -> HashMap<PeerNetworkId, PeerMonitoringMetadata> {
+        peers_and_metadata
+            .iter()
+            .filter(|(_, metadata)| get_peer_ping_latency(metadata).is_none())
+            .filter_map(|(peer, metadata)| {
+                let restored_ping_latency = self
+                    .restored_peer_latencies
+                    .get(peer)?
+                    .average_ping_latency_secs?;
+                let mut restored_metadata = metadata.cloned().unwrap_or_default();
+                restored_metadata.average_ping_latency_secs = Some(restored_ping_latency);
+                Some((*peer, restored_metadata))
+            })
+            .collect()
+    }
+
+    /// Returns a handle to the current list of prioritized peers (with
+    /// the metadata that produced it). This is useful for debugging.
+    pub fn get_prioritized_peers_info(&self) -> Arc<RwLock<Vec<PrioritizedPeerInfo>>> {
+        self.prioritized_peers_info.clone()
+    }
+
+    /// Sets the handle to which the list of prioritized peers is published. This
+    /// allows the handle to be shared (e.g., with the inspection service) before
+    /// mempool is started.
+    pub fn set_prioritized_peers_info(
+        &mut self,
+        prioritized_peers_info: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+    ) {
+        self.prioritized_peers_info = prioritized_peers_info;
+    }
+
     /// Returns the priority of the given peer. The lower the
     /// value, the higher the priority.
     pub fn get_peer_priority(&self, peer_network_id: &PeerNetworkId) -> usize {
-        let prioritized_peers = self.prioritized_peers.read();
-        prioritized_peers
-            .iter()
-            .find_position(|peer| *peer == peer_network_id)
-            .map_or(usize::MAX, |(position, _)| position)
+        self.peer_priorities
+            .read()
+            .get(peer_network_id)
+            .copied()
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Returns the prioritization parameters (including any pending update)
+    pub fn get_prioritization_params(&self) -> PeerPrioritizationParams {
+        self.pending_prioritization_params
+            .clone()
+            .unwrap_or_else(|| self.mempool_config.get_peer_prioritization_params())
+    }
+
+    /// Sets the prioritization parameters. These are picked up on the next priority
+    /// update (which is triggered as soon as possible, see `ready_for_update`).
+    pub fn set_prioritization_params(&mut self, params: PeerPrioritizationParams) {
+        self.pending_prioritization_params = Some(params);
     }
 
     pub fn get_sender_bucket_priority_for_peer(
@@ -187,6 +567,11 @@ impl PrioritizedPeersState {
 
     /// Returns true iff the prioritized peers list is ready for another update
     pub fn ready_for_update(&self, peers_changed: bool) -> bool {
+        // If the prioritization parameters were updated, we should apply them now
+        if self.pending_prioritization_params.is_some() {
+            return true;
+        }
+
         // If intelligent peer prioritization is disabled, we should only
         // update the prioritized peers if the peers have changed.
         if !self.mempool_config.enable_intelligent_peer_prioritization {
@@ -206,6 +591,8 @@ impl PrioritizedPeersState {
             None => true, // We haven't updated yet
             Some(last_update) => {
                 let duration_since_update = self.time_service.now().duration_since(last_update);
+                counters::SHARED_MEMPOOL_SECS_SINCE_PRIORITY_UPDATE
+                    .set(duration_since_update.as_secs() as i64);
                 let update_interval_secs = self
                     .mempool_config
                     .shared_mempool_priority_update_interval_secs;
@@ -214,6 +601,155 @@ impl PrioritizedPeersState {
         }
     }
 
+    /// Returns true iff the given (updated) monitoring metadata of the peer should trigger
+    /// a priority update, i.e., the peer's distance from the validators changed, or its
+    /// ping latency changed materially since the last update. To avoid frequent updates,
+    /// this is only true if priorities weren't updated within the last peer update interval.
+    pub fn requires_update_for_monitoring_change(
+        &self,
+        peer: &PeerNetworkId,
+        monitoring_metadata: Option<&PeerMonitoringMetadata>,
+    ) -> bool {
+        // Event-driven updates require intelligent peer prioritization
+        if !self.mempool_config.enable_intelligent_peer_prioritization {
+            return false;
+        }
+        let latency_change_percent =
+            match self.mempool_config.priority_update_latency_change_percent {
+                Some(latency_change_percent) => latency_change_percent as f64,
+                None => return false, // Event-driven updates are disabled
+            };
+
+        // Compare the metadata against the metadata at the last update (new
+        // peers are prioritized when they are added, so they are ignored here).
+        let (previous_distance, previous_latency) =
+            match self.prioritized_peers_monitoring.get(peer) {
+                Some(previous_monitoring) => *previous_monitoring,
+                None => return false,
+            };
+        let distance = get_distance_from_validators(&monitoring_metadata);
+        let latency = get_peer_ping_latency(&monitoring_metadata);
+        let distance_changed = distance != previous_distance;
+        let latency_changed = match (previous_latency, latency) {
+            (Some(previous_latency), Some(latency)) => {
+                (latency - previous_latency).abs() * 100.0
+                    >= previous_latency * latency_change_percent
+            },
+            (None, Some(_)) => true, // The latency was observed for the first time
+            _ => false,
+        };
+        if !distance_changed && !latency_changed {
+            return false;
+        }
+
+        // Only update if enough time has passed since the last update
+        match self.last_peer_priority_update {
+            None => true,
+            Some(last_update) => {
+                let duration_since_update = self.time_service.now().duration_since(last_update);
+                duration_since_update.as_millis()
+                    >= self.mempool_config.shared_mempool_peer_update_interval_ms as u128
+            },
+        }
+    }
+
+    /// Updates the average broadcast ACK latencies (in seconds) observed for each
+    /// peer. These are used to prioritize peers at the next priority update.
+    pub fn update_broadcast_ack_latencies(
+        &mut self,
+        broadcast_ack_latencies: HashMap<PeerNetworkId, f64>,
+    ) {
+        self.broadcast_ack_latencies = broadcast_ack_latencies;
+
+        // Use the restored ACK latencies for peers without observed ACK latencies
+        for (peer, peer_snapshot) in &self.restored_peer_latencies {
+            if let Some(broadcast_ack_latency_secs) = peer_snapshot.broadcast_ack_latency_secs {
+                self.broadcast_ack_latencies
+                    .entry(*peer)
+                    .or_insert(broadcast_ack_latency_secs);
+            }
+        }
+    }
+
+    /// Updates the peers that repeatedly failed (or timed out on) broadcasts. Returns true
+    /// iff a peer that is currently a top (i.e., primary) peer has newly failed, in which
+    /// case the priorities should be updated to promote the next peer.
+    pub fn update_failed_peers(&mut self, failed_peers: HashSet<PeerNetworkId>) -> bool {
+        let num_failed_top_peers = failed_peers
+            .iter()
+            .filter(|peer| !self.failed_peers.contains(peer) && self.is_top_peer(peer))
+            .count();
+        if num_failed_top_peers > 0 {
+            info!(
+                "{} top peer(s) repeatedly failed broadcasts. Failed peers: {:?}",
+                num_failed_top_peers, failed_peers
+            );
+            counters::SHARED_MEMPOOL_BROADCAST_FAILOVER_COUNT.inc_by(num_failed_top_peers as u64);
+        }
+        self.failed_peers = failed_peers;
+        num_failed_top_peers > 0
+    }
+
+    /// Updates the penalized peers using the given misbehavior scores (i.e., whether each
+    /// scored peer misbehaved in its last scoring window). Penalties of peers that are no
+    /// longer connected are dropped. Returns the newly penalized peers.
+    pub fn update_penalized_peers(
+        &mut self,
+        peer_misbehavior_scores: HashMap<PeerNetworkId, bool>,
+        is_connected: impl Fn(&PeerNetworkId) -> bool,
+    ) -> Vec<PeerNetworkId> {
+        self.penalized_peers.retain(|peer| is_connected(peer));
+
+        let mut newly_penalized_peers = vec![];
+        for (peer, is_misbehaving) in peer_misbehavior_scores {
+            if !is_misbehaving {
+                self.penalized_peers.remove(&peer); // The peer is forgiven
+            } else if self.penalized_peers.insert(peer) {
+                newly_penalized_peers.push(peer);
+            }
+        }
+
+        if !newly_penalized_peers.is_empty() {
+            info!(
+                "Peers were penalized for sending invalid or expired transactions: {:?}",
+                newly_penalized_peers
+            );
+            for peer in &newly_penalized_peers {
+                counters::shared_mempool_peer_misbehavior_inc(
+                    peer.network_id(),
+                    counters::PEER_PENALIZED_LABEL,
+                );
+            }
+        }
+        newly_penalized_peers
+    }
+
+    /// Returns true iff the given peer is assigned any sender buckets with primary priority
+    fn is_top_peer(&self, peer: &PeerNetworkId) -> bool {
+        self.peer_to_sender_buckets
+            .get(peer)
+            .map_or(false, |sender_buckets| {
+                sender_buckets
+                    .values()
+                    .any(|priority| *priority == BroadcastPeerPriority::Primary)
+            })
+    }
+
+    /// Updates the network groups of each peer (derived from the peer's connection
+    /// metadata). These are used to favor network diversity at the next priority update.
+    pub fn update_peer_connection_metadata(
+        &mut self,
+        peer_connection_metadata: HashMap<PeerNetworkId, ConnectionMetadata>,
+    ) {
+        self.peer_network_groups = peer_connection_metadata
+            .into_iter()
+            .filter_map(|(peer, connection_metadata)| {
+                get_peer_network_group(&connection_metadata)
+                    .map(|network_group| (peer, network_group))
+            })
+            .collect();
+    }
+
     pub(crate) fn get_sender_buckets_for_peer(
         &self,
         peer: &PeerNetworkId,
@@ -221,25 +757,98 @@ impl PrioritizedPeersState {
         self.peer_to_sender_buckets.get(peer)
     }
 
-    /// Sorts the given peers by priority using the prioritized peer comparator.
+    /// Returns the prioritization strategy used for broadcasting
+    fn get_active_prioritization_strategy(&self) -> PeerPrioritizationStrategy {
+        // Only use intelligent peer prioritization if it is enabled
+        if self.mempool_config.enable_intelligent_peer_prioritization {
+            PeerPrioritizationStrategy::Intelligent
+        } else {
+            PeerPrioritizationStrategy::Simple
+        }
+    }
+
+    /// Sorts the given peers by priority using the active prioritization strategy.
     /// The peers are sorted in descending order (i.e., higher values are prioritized).
     fn sort_peers_by_priority(
         &self,
         peers_and_metadata: &[(PeerNetworkId, Option<&PeerMonitoringMetadata>)],
     ) -> Vec<PeerNetworkId> {
-        peers_and_metadata
+        self.sort_peers_by_strategy(
+            peers_and_metadata,
+            self.get_active_prioritization_strategy(),
+        )
+    }
+
+    /// Sorts the given peers by priority using the given prioritization strategy.
+    /// The peers are sorted in descending order (i.e., higher values are prioritized).
+    fn sort_peers_by_strategy(
+        &self,
+        peers_and_metadata: &[(PeerNetworkId, Option<&PeerMonitoringMetadata>)],
+        prioritization_strategy: PeerPrioritizationStrategy,
+    ) -> Vec<PeerNetworkId> {
+        let mempool_full_threshold =
+            self.mempool_config.upstream_mempool_full_threshold_percent as f64 / 100.0;
+        let sorted_peers = peers_and_metadata
             .iter()
             .sorted_by(|peer_a, peer_b| {
-                // Only use intelligent peer prioritization if it is enabled
-                let ordering = if self.mempool_config.enable_intelligent_peer_prioritization {
-                    self.peer_comparator.compare_intelligent(peer_a, peer_b)
-                } else {
-                    self.peer_comparator.compare_simple(peer_a, peer_b)
+                let ordering = match prioritization_strategy {
+                    PeerPrioritizationStrategy::Simple => {
+                        self.peer_comparator
+                            .compare_simple(peer_a, peer_b, &self.penalized_peers)
+                    },
+                    PeerPrioritizationStrategy::Intelligent => {
+                        self.peer_comparator.compare_intelligent(
+                            peer_a,
+                            peer_b,
+                            &self.broadcast_ack_latencies,
+                            &self.penalized_peers,
+                            self.mempool_config.peer_ping_latency_slack_ms,
+                            mempool_full_threshold,
+                        )
+                    },
                 };
                 ordering.reverse() // Prioritize higher values (i.e., sorted by descending order)
             })
             .map(|(peer, _)| *peer)
-            .collect()
+            .collect();
+
+        // Favor network diversity amongst the top peers (if enabled)
+        let sorted_peers = if self.mempool_config.enable_peer_network_diversity {
+            self.peer_comparator
+                .diversify_by_network_group(sorted_peers, &self.peer_network_groups)
+        } else {
+            sorted_peers
+        };
+
+        // Move the pinned peers (if any) to the top
+        self.pin_upstream_peers(sorted_peers)
+    }
+
+    /// Returns true iff the given peer is pinned to the top of the prioritized peers
+    fn is_pinned_peer(&self, peer: &PeerNetworkId) -> bool {
+        self.mempool_config
+            .pinned_upstream_peers
+            .contains(&peer.peer_id())
+    }
+
+    /// Moves the pinned peers to the top of the given (sorted) peers, in the order
+    /// they are configured. The order of the remaining peers is preserved.
+    fn pin_upstream_peers(&self, sorted_peers: Vec<PeerNetworkId>) -> Vec<PeerNetworkId> {
+        let pinned_upstream_peers = &self.mempool_config.pinned_upstream_peers;
+        if pinned_upstream_peers.is_empty() {
+            return sorted_peers;
+        }
+
+        let (mut pinned_peers, unpinned_peers): (Vec<_>, Vec<_>) = sorted_peers
+            .into_iter()
+            .partition(|peer| self.is_pinned_peer(peer));
+        pinned_peers.sort_by_key(|peer| {
+            pinned_upstream_peers
+                .iter()
+                .position(|peer_id| *peer_id == peer.peer_id())
+        });
+        pinned_peers.extend(unpinned_peers);
+        pinned_peers
     }
 
     fn update_sender_bucket_for_peers(
@@ -293,7 +902,17 @@ impl PrioritizedPeersState {
             1,
             min(
                 self.mempool_config.num_sender_buckets,
-                if self.mempool_config.enable_max_load_balancing_at_any_load {
+                if !self.mempool_config.broadcast_fanout_per_network.is_empty() {
+                    let total_fanout: usize = self
+                        .mempool_config
+                        .broadcast_fanout_per_network
+                        .iter()
+                        .map(|network_fanout| network_fanout.fanout)
+                        .sum();
+                    min(total_fanout, u8::MAX as usize) as u8
+                } else if let Some(broadcast_fanout) = self.mempool_config.broadcast_fanout {
+                    min(broadcast_fanout, u8::MAX as usize) as u8
+                } else if self.mempool_config.enable_max_load_balancing_at_any_load {
                     u8::MAX
                 } else {
                     threshold_config.max_number_of_upstream_peers
@@ -317,11 +936,51 @@ impl PrioritizedPeersState {
             num_top_peers
         );
 
-        if self.node_type.is_validator_fullnode() {
-            // Use the peer on the VFN network with lowest ping latency as the primary peer
-            let peers_in_vfn_network = self
+        // Only healthy peers (i.e., those that haven't repeatedly failed broadcasts, and
+        // aren't penalized) are selected as top peers, so that the next peer is promoted in
+        // place of an unhealthy one. If all peers are unhealthy, fall back to all peers.
+        // If per network fanouts are configured, only peers on networks with a non-zero
+        // fanout are candidates (so that none of the fallbacks below pick other networks).
+        let broadcast_fanout_per_network = &self.mempool_config.broadcast_fanout_per_network;
+        let is_fanout_network = |peer: &PeerNetworkId| {
+            broadcast_fanout_per_network.is_empty()
+                || broadcast_fanout_per_network.iter().any(|network_fanout| {
+                    network_fanout.network_id == peer.network_id() && network_fanout.fanout > 0
+                })
+        };
+        let mut candidate_top_peers: Vec<PeerNetworkId> = self
+            .prioritized_peers
+            .read()
+            .iter()
+            .filter(|peer| {
+                is_fanout_network(peer)
+                    && !self.failed_peers.contains(peer)
+                    && !self.penalized_peers.contains(peer)
+            })
+            .cloned()
+            .collect();
+        if candidate_top_peers.is_empty() {
+            candidate_top_peers = self
                 .prioritized_peers
                 .read()
+                .iter()
+                .filter(|peer| is_fanout_network(peer))
+                .cloned()
+                .collect();
+        }
+
+        if !broadcast_fanout_per_network.is_empty() {
+            // Use the top candidate peers on each network (up to the network's fanout)
+            top_peers = select_top_peers_per_network(
+                &candidate_top_peers,
+                broadcast_fanout_per_network,
+                num_top_peers as usize,
+            );
+        }
+
+        if top_peers.is_empty() && self.node_type.is_validator_fullnode() {
+            // Use the peer on the VFN network with lowest ping latency as the primary peer
+            let peers_in_vfn_network = candidate_top_peers
                 .iter()
                 .cloned()
                 .filter(|peer| peer.network_id() == NetworkId::Vfn)
@@ -332,15 +991,36 @@ impl PrioritizedPeersState {
             }
         }
 
+        if top_peers.is_empty()
+            && self.mempool_config.broadcast_peer_selection_mode
+                == BroadcastPeerSelectionMode::WeightedRandom
+        {
+            // Sample the top peers with probability proportional to their priority score
+            top_peers = sample_peers_by_priority_score(
+                &candidate_top_peers,
+                num_top_peers as usize,
+                &mut rand::thread_rng(),
+            );
+        }
+
+        if top_peers.is_empty() && self.mempool_config.broadcast_fanout.is_some() {
+            // Use the top-K candidate peers, where K is the configured fanout
+            top_peers = candidate_top_peers
+                .iter()
+                .take(num_top_peers as usize)
+                .cloned()
+                .collect();
+        }
+
         if top_peers.is_empty() {
-            let base_ping_latency = self.prioritized_peers.read().first().and_then(|peer| {
+            let base_ping_latency = candidate_top_peers.first().and_then(|peer| {
                 peer_monitoring_data
                     .get(peer)
                     .and_then(|metadata| get_peer_ping_latency(metadata))
             });
 
             // Extract top peers with ping latency less than base_ping_latency + 50 ms
-            for peer in self.prioritized_peers.read().iter() {
+            for peer in candidate_top_peers.iter() {
                 if top_peers.len() >= num_top_peers as usize {
                     break;
                 }
@@ -366,19 +1046,22 @@ impl PrioritizedPeersState {
         );
 
         assert!(top_peers.len() <= num_top_peers as usize);
-        // Top peers shouldn't be empty if prioritized_peers is not zero
-        assert!(self.prioritized_peers.read().is_empty() || !top_peers.is_empty());
+        // Top peers shouldn't be empty if there are candidate top peers. Note: if per network
+        // fanouts are configured, there may be no candidates (i.e., no peers on those networks).
+        assert!(candidate_top_peers.is_empty() || !top_peers.is_empty());
 
         self.peer_to_sender_buckets = HashMap::new();
         if !self.prioritized_peers.read().is_empty() {
             // Assign sender buckets with Primary priority
             let mut peer_index = 0;
-            for bucket_index in 0..self.mempool_config.num_sender_buckets {
-                self.peer_to_sender_buckets
-                    .entry(*top_peers.get(peer_index).unwrap())
-                    .or_default()
-                    .insert(bucket_index, BroadcastPeerPriority::Primary);
-                peer_index = (peer_index + 1) % top_peers.len();
+            if !top_peers.is_empty() {
+                for bucket_index in 0..self.mempool_config.num_sender_buckets {
+                    self.peer_to_sender_buckets
+                        .entry(*top_peers.get(peer_index).unwrap())
+                        .or_default()
+                        .insert(bucket_index, BroadcastPeerPriority::Primary);
+                    peer_index = (peer_index + 1) % top_peers.len();
+                }
             }
 
             // Assign sender buckets with Failover priority. Use Round Robin.
@@ -411,21 +1094,53 @@ impl PrioritizedPeersState {
         num_mempool_txns_received_since_peers_updated: u64,
         num_committed_txns_recieved_since_peers_updated: u64,
     ) {
+        // Apply any pending prioritization parameters to the config
+        if let Some(params) = self.pending_prioritization_params.take() {
+            info!(
+                "Applying the updated peer prioritization parameters: {:?}",
+                params
+            );
+            self.mempool_config.set_peer_prioritization_params(params);
+        }
+
+        // Use the restored ping latencies for peers whose latencies haven't been observed
+        let observed_peers_and_metadata = peers_and_metadata;
+        let restored_monitoring_metadata =
+            self.get_restored_monitoring_metadata(&observed_peers_and_metadata);
+        let peers_and_metadata: Vec<_> = observed_peers_and_metadata
+            .iter()
+            .map(|(peer, metadata)| (*peer, restored_monitoring_metadata.get(peer).or(*metadata)))
+            .collect();
+
         let peer_monitoring_data: HashMap<PeerNetworkId, Option<&PeerMonitoringMetadata>> =
             peers_and_metadata.clone().into_iter().collect();
 
+        // Save the current top peers (to identify churn after the update)
+        let current_top_peer = self.prioritized_peers.read().first().cloned();
+        let current_top_peers = self.get_top_peers();
+
         // Calculate the new set of prioritized peers
         let new_prioritized_peers = self.sort_peers_by_priority(&peers_and_metadata);
 
         // Update the prioritized peer metrics
         self.update_prioritized_peer_metrics(&new_prioritized_peers);
 
-        // Update the prioritized peers
-        *self.prioritized_peers.write() = new_prioritized_peers;
+        // Update the prioritized peers and peer priorities (while holding both
+        // locks, so that lookups never observe priorities that don't match the list)
+        {
+            let mut prioritized_peers = self.prioritized_peers.write();
+            let mut peer_priorities = self.peer_priorities.write();
+            *peer_priorities = new_prioritized_peers
+                .iter()
+                .enumerate()
+                .map(|(priority, peer)| (*peer, priority))
+                .collect();
+            *prioritized_peers = new_prioritized_peers;
+        }
 
         // Check if we've now observed ping latencies for all peers
         if !self.observed_all_ping_latencies {
-            self.observed_all_ping_latencies = peers_and_metadata
+            self.observed_all_ping_latencies = observed_peers_and_metadata
                 .iter()
                 .all(|(_, metadata)| get_peer_ping_latency(metadata).is_some());
         }
@@ -437,6 +1152,38 @@ impl PrioritizedPeersState {
             num_committed_txns_recieved_since_peers_updated,
         );
 
+        // Update the prioritized peers info (for debugging)
+        self.update_prioritized_peers_info(&peer_monitoring_data);
+
+        // Evaluate the shadow prioritization strategies (if any)
+        self.evaluate_shadow_prioritization_strategies(&peers_and_metadata);
+
+        // Once the ping latencies of all peers have been observed, the restored latencies
+        // are no longer required.
+        if self.observed_all_ping_latencies {
+            self.restored_peer_latencies.clear();
+        }
+
+        // Save the snapshot. Peers whose latencies haven't been observed (yet) keep
+        // their restored latencies, so that a single unobserved peer doesn't block saves.
+        self.save_prioritized_peers_snapshot(&peers_and_metadata);
+
+        // Save the monitoring metadata used by the update
+        self.prioritized_peers_monitoring = peers_and_metadata
+            .iter()
+            .map(|(peer, metadata)| {
+                let distance = get_distance_from_validators(metadata);
+                (*peer, (distance, get_peer_ping_latency(metadata)))
+            })
+            .collect();
+
+        // Update the peer priority churn metrics
+        self.update_priority_churn_metrics(
+            &peers_and_metadata,
+            current_top_peer,
+            current_top_peers,
+        );
+
         // Set the last peer priority update time
         self.last_peer_priority_update = Some(self.time_service.now());
         info!(
@@ -454,6 +1201,129 @@ impl PrioritizedPeersState {
         );
     }
 
+    /// Updates the prioritized peers info using the current prioritization
+    /// and the metadata that produced it.
+    fn update_prioritized_peers_info(
+        &self,
+        peer_monitoring_data: &HashMap<PeerNetworkId, Option<&PeerMonitoringMetadata>>,
+    ) {
+        let prioritized_peers_info = self
+            .prioritized_peers
+            .read()
+            .iter()
+            .enumerate()
+            .map(|(priority, peer)| {
+                let monitoring_metadata = peer_monitoring_data.get(peer).copied().flatten();
+                PrioritizedPeerInfo {
+                    peer: peer.to_string(),
+                    priority,
+                    network_id: peer.network_id(),
+                    distance_from_validators: get_distance_from_validators(&monitoring_metadata),
+                    average_ping_latency_secs: get_peer_ping_latency(&monitoring_metadata),
+                    broadcast_ack_latency_secs: self.broadcast_ack_latencies.get(peer).copied(),
+                    mempool_utilization: get_mempool_utilization(&monitoring_metadata),
+                    network_group: self.peer_network_groups.get(peer).cloned(),
+                    failed_broadcasts: self.failed_peers.contains(peer),
+                    penalized: self.penalized_peers.contains(peer),
+                    pinned: self.is_pinned_peer(peer),
+                    is_top_peer: self.is_top_peer(peer),
+                }
+            })
+            .collect();
+        *self.prioritized_peers_info.write() = prioritized_peers_info;
+    }
+
+    /// Computes the rankings of the shadow prioritization strategies, and logs and
+    /// reports (via metrics) how they diverge from the active prioritization. Note:
+    /// shadow rankings are never used for broadcasting.
+    fn evaluate_shadow_prioritization_strategies(
+        &self,
+        peers_and_metadata: &[(PeerNetworkId, Option<&PeerMonitoringMetadata>)],
+    ) {
+        let prioritized_peers = self.prioritized_peers.read().clone();
+        let num_top_peers = self.get_top_peers().len();
+        let mut shadow_rankings = vec![];
+        for shadow_strategy in &self.mempool_config.shadow_prioritization_strategies {
+            // Compute the shadow ranking and compare it against the active ranking
+            let shadow_prioritized_peers =
+                self.sort_peers_by_strategy(peers_and_metadata, *shadow_strategy);
+            let divergence = PrioritizationDivergence::new(
+                &prioritized_peers,
+                &shadow_prioritized_peers,
+                num_top_peers,
+            );
+
+            // Update the divergence metrics
+            let strategy_label = shadow_strategy.get_label();
+            counters::shared_mempool_shadow_priority_divergence(
+                strategy_label,
+                counters::TOP_1_PEER_LABEL,
+                divergence.top_peer_changed as usize,
+            );
+            counters::shared_mempool_shadow_priority_divergence(
+                strategy_label,
+                counters::TOP_K_PEERS_LABEL,
+                divergence.num_top_peers_changed,
+            );
+            counters::shared_mempool_shadow_priority_divergence(
+                strategy_label,
+                counters::ALL_PEERS_LABEL,
+                divergence.num_peers_changed,
+            );
+
+            shadow_rankings.push((shadow_strategy, divergence, shadow_prioritized_peers));
+        }
+
+        // Periodically log the shadow rankings (the full rankings are too verbose to
+        // log on every update).
+        if !shadow_rankings.is_empty() {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(SHADOW_RANKING_LOG_INTERVAL_SECS)),
+                info!(
+                    "Evaluated shadow prioritization strategies (strategy, divergence, shadow prioritized peers): {:?}",
+                    shadow_rankings
+                )
+            );
+        }
+    }
+
+    /// Returns the set of top (i.e., primary) peers
+    pub(crate) fn get_top_peers(&self) -> HashSet<PeerNetworkId> {
+        self.peer_to_sender_buckets
+            .keys()
+            .filter(|peer| self.is_top_peer(peer))
+            .cloned()
+            .collect()
+    }
+
+    /// Updates the metrics for peer priority churn (i.e., changes to the top-1
+    /// and top-k peers), and the peers that lack ping latencies.
+    fn update_priority_churn_metrics(
+        &self,
+        peers_and_metadata: &[(PeerNetworkId, Option<&PeerMonitoringMetadata>)],
+        previous_top_peer: Option<PeerNetworkId>,
+        previous_top_peers: HashSet<PeerNetworkId>,
+    ) {
+        // Update the metrics for the top-1 and top-k peer changes
+        if self.prioritized_peers.read().first() != previous_top_peer.as_ref() {
+            counters::shared_mempool_priority_set_changed(counters::TOP_1_PEER_LABEL);
+        }
+        if self.get_top_peers() != previous_top_peers {
+            counters::shared_mempool_priority_set_changed(counters::TOP_K_PEERS_LABEL);
+        }
+
+        // Update the metric for the number of peers without ping latencies
+        let num_peers_without_ping_latency = peers_and_metadata
+            .iter()
+            .filter(|(_, metadata)| get_peer_ping_latency(metadata).is_none())
+            .count();
+        counters::SHARED_MEMPOOL_PEERS_WITHOUT_PING_LATENCY
+            .set(num_peers_without_ping_latency as i64);
+
+        // Reset the time since the last update
+        counters::SHARED_MEMPOOL_SECS_SINCE_PRIORITY_UPDATE.set(0);
+    }
+
     /// Updates the prioritized peer metrics based on the new prioritization
     fn update_prioritized_peer_metrics(&mut self, new_prioritized_peers: &Vec<PeerNetworkId>) {
         // Calculate the number of peers that changed priorities
@@ -475,10 +1345,151 @@ impl PrioritizedPeersState {
     }
 }
 
-/// Returns the distance from the validators for the
-/// given monitoring metadata (if one exists).
-fn get_distance_from_validators(
-    monitoring_metadata: &Option<&PeerMonitoringMetadata>,
+/// The divergence of a shadow peer prioritization from the active prioritization
+#[derive(Debug, Default, Eq, PartialEq)]
+struct PrioritizationDivergence {
+    top_peer_changed: bool,       // Whether the top-1 peer differs
+    num_top_peers_changed: usize, // The number of active top-k peers not in the shadow top-k
+    num_peers_changed: usize,     // The number of peers with a different priority
+}
+
+impl PrioritizationDivergence {
+    fn new(
+        prioritized_peers: &[PeerNetworkId],
+        shadow_prioritized_peers: &[PeerNetworkId],
+        num_top_peers: usize,
+    ) -> Self {
+        let shadow_top_peers: HashSet<_> = shadow_prioritized_peers
+            .iter()
+            .take(num_top_peers)
+            .collect();
+        Self {
+            top_peer_changed: prioritized_peers.first() != shadow_prioritized_peers.first(),
+            num_top_peers_changed: prioritized_peers
+                .iter()
+                .take(num_top_peers)
+                .filter(|peer| !shadow_top_peers.contains(peer))
+                .count(),
+            num_peers_changed: prioritized_peers
+                .iter()
+                .zip(shadow_prioritized_peers.iter())
+                .filter(|(peer, shadow_peer)| peer != shadow_peer)
+                .count(),
+        }
+    }
+}
+
+/// Samples (without replacement) up to `num_peers` of the given prioritized peers, where
+/// each peer is sampled with probability proportional to its priority score. The score
+/// of a peer is derived from its position in the prioritized list, i.e., the highest
+/// priority peer has a score of `n` (the number of peers) and the lowest has a score of 1.
+fn sample_peers_by_priority_score<R: Rng>(
+    prioritized_peers: &[PeerNetworkId],
+    num_peers: usize,
+    rng: &mut R,
+) -> Vec<PeerNetworkId> {
+    let num_prioritized_peers = prioritized_peers.len();
+    let mut candidate_peers: Vec<(PeerNetworkId, usize)> = prioritized_peers
+        .iter()
+        .enumerate()
+        .map(|(position, peer)| (*peer, num_prioritized_peers - position))
+        .collect();
+
+    let mut sampled_peers = vec![];
+    while sampled_peers.len() < num_peers && !candidate_peers.is_empty() {
+        // Pick a random point in the total score, and find the peer it falls on
+        let total_score: usize = candidate_peers.iter().map(|(_, score)| score).sum();
+        let mut target_score = rng.gen_range(0, total_score);
+        let sampled_index = candidate_peers
+            .iter()
+            .position(|(_, score)| {
+                if target_score < *score {
+                    true
+                } else {
+                    target_score -= score;
+                    false
+                }
+            })
+            .expect("The target score must fall on a candidate peer!");
+        sampled_peers.push(candidate_peers.remove(sampled_index).0);
+    }
+    sampled_peers
+}
+
+/// Selects the top peers (in priority order) on each network, up to the fanout of the
+/// network and the given total number of peers. Networks without a fanout are skipped.
+fn select_top_peers_per_network(
+    prioritized_peers: &[PeerNetworkId],
+    broadcast_fanout_per_network: &[NetworkFanoutConfig],
+    max_num_top_peers: usize,
+) -> Vec<PeerNetworkId> {
+    let mut remaining_fanouts: HashMap<NetworkId, usize> = broadcast_fanout_per_network
+        .iter()
+        .map(|network_fanout| (network_fanout.network_id, network_fanout.fanout))
+        .collect();
+
+    let mut top_peers = vec![];
+    for peer in prioritized_peers {
+        if top_peers.len() >= max_num_top_peers {
+            break;
+        }
+        if let Some(remaining_fanout) = remaining_fanouts.get_mut(&peer.network_id()) {
+            if *remaining_fanout > 0 {
+                *remaining_fanout -= 1;
+                top_peers.push(*peer);
+            }
+        }
+    }
+    top_peers
+}
+
+/// Returns the network group of the peer with the given connection metadata. For
+/// connections forwarded by a proxy (e.g., a TCP load balancer), the group is derived
+/// from the client address reported by the proxy, as the proxy address is shared by
+/// all proxied peers. The proxy address is only used if the client address has no group.
+fn get_peer_network_group(connection_metadata: &ConnectionMetadata) -> Option<String> {
+    get_network_group(&connection_metadata.addr).or_else(|| {
+        connection_metadata
+            .proxy_addr
+            .as_ref()
+            .and_then(get_network_group)
+    })
+}
+
+/// Returns the network group of the given address, i.e., the /24 subnet for IPv4
+/// addresses, the /48 prefix for IPv6 addresses, and the parent domain (e.g., the
+/// rack or datacenter) for DNS names. Returns None if no group can be derived.
+fn get_network_group(network_address: &NetworkAddress) -> Option<String> {
+    network_address
+        .as_slice()
+        .iter()
+        .find_map(|protocol| match protocol {
+            Protocol::Ip4(ip_addr) => {
+                let [a, b, c, _] = ip_addr.octets();
+                Some(format!("ip4/{}.{}.{}.0/24", a, b, c))
+            },
+            Protocol::Ip6(ip_addr) => {
+                let segments = ip_addr.segments();
+                Some(format!(
+                    "ip6/{:x}:{:x}:{:x}::/48",
+                    segments[0], segments[1], segments[2]
+                ))
+            },
+            Protocol::Dns(dns_name) | Protocol::Dns4(dns_name) | Protocol::Dns6(dns_name) => {
+                // Strip the host name, and use the parent domain as the group
+                dns_name
+                    .as_ref()
+                    .split_once('.')
+                    .map(|(_, parent_domain)| format!("dns/{}", parent_domain))
+            },
+            _ => None,
+        })
+}
+
+/// Returns the distance from the validators for the
+/// given monitoring metadata (if one exists).
+fn get_distance_from_validators(
+    monitoring_metadata: &Option<&PeerMonitoringMetadata>,
 ) -> Option<u64> {
     monitoring_metadata.and_then(|metadata| {
         metadata
@@ -494,6 +1505,23 @@ fn get_peer_ping_latency(monitoring_metadata: &Option<&PeerMonitoringMetadata>)
     monitoring_metadata.and_then(|metadata| metadata.average_ping_latency_secs)
 }
 
+/// Returns the mempool utilization (as a fraction of capacity)
+/// for the given monitoring metadata (if one exists).
+fn get_mempool_utilization(monitoring_metadata: &Option<&PeerMonitoringMetadata>) -> Option<f64> {
+    monitoring_metadata.and_then(|metadata| {
+        metadata
+            .latest_mempool_info_response
+            .as_ref()
+            .map(|mempool_info_response| mempool_info_response.get_utilization())
+    })
+}
+
+/// Compares the misbehavior of the given pair of peers.
+/// The peer that isn't penalized for misbehavior is prioritized.
+fn compare_misbehavior(is_penalized_a: bool, is_penalized_b: bool) -> Ordering {
+    is_penalized_a.cmp(&is_penalized_b).reverse()
+}
+
 /// Compares the network ID for the given pair of peers.
 /// The peer with the highest network is prioritized.
 fn compare_network_id(network_id_a: &NetworkId, network_id_b: &NetworkId) -> Ordering {
@@ -529,6 +1557,81 @@ fn compare_ping_latency(
     }
 }
 
+/// Compares the ping latency for the given pair of monitoring metadata, where the ping
+/// latencies are first grouped into buckets of the given slack (in ms), and latencies in
+/// the same bucket are considered equal. Note: unlike comparing the latency difference
+/// against the slack, bucketing keeps the ordering transitive (as required for sorting).
+/// If the slack is 0, the ping latencies are compared directly.
+fn compare_ping_latency_buckets(
+    monitoring_metadata_a: &Option<&PeerMonitoringMetadata>,
+    monitoring_metadata_b: &Option<&PeerMonitoringMetadata>,
+    ping_latency_slack_ms: u64,
+) -> Ordering {
+    if ping_latency_slack_ms > 0 {
+        if let (Some(ping_latency_a), Some(ping_latency_b)) = (
+            get_peer_ping_latency(monitoring_metadata_a),
+            get_peer_ping_latency(monitoring_metadata_b),
+        ) {
+            // Prioritize the peer with the lowest ping latency bucket
+            let bucket_a = get_ping_latency_bucket(ping_latency_a, ping_latency_slack_ms);
+            let bucket_b = get_ping_latency_bucket(ping_latency_b, ping_latency_slack_ms);
+            return bucket_a.cmp(&bucket_b).reverse();
+        }
+    }
+    compare_ping_latency(monitoring_metadata_a, monitoring_metadata_b)
+}
+
+/// Returns the bucket of the given ping latency (in seconds), for buckets of the
+/// given size (in ms). The slack must be non-zero.
+fn get_ping_latency_bucket(ping_latency_secs: f64, ping_latency_slack_ms: u64) -> u64 {
+    let ping_latency_ms = (ping_latency_secs * 1000.0) as u64;
+    ping_latency_ms / ping_latency_slack_ms
+}
+
+/// Compares the given pair of average broadcast ACK latencies.
+/// The peer with the lowest broadcast ACK latency is prioritized.
+fn compare_broadcast_ack_latency(
+    ack_latency_a: Option<&f64>,
+    ack_latency_b: Option<&f64>,
+) -> Ordering {
+    match (ack_latency_a, ack_latency_b) {
+        (Some(ack_latency_a), Some(ack_latency_b)) => {
+            // Prioritize the peer with the lowest ACK latency
+            ack_latency_a.total_cmp(ack_latency_b).reverse()
+        },
+        (Some(_), None) => {
+            Ordering::Greater // Prioritize the peer with an ACK latency
+        },
+        (None, Some(_)) => {
+            Ordering::Less // Prioritize the peer with an ACK latency
+        },
+        (None, None) => {
+            Ordering::Equal // Neither peer has an ACK latency
+        },
+    }
+}
+
+/// Compares the mempool fullness for the given pair of monitoring metadata.
+/// Peers with a mempool utilization at (or above) the given threshold are
+/// deprioritized. Peers with an unknown utilization are not considered full.
+fn compare_mempool_fullness(
+    monitoring_metadata_a: &Option<&PeerMonitoringMetadata>,
+    monitoring_metadata_b: &Option<&PeerMonitoringMetadata>,
+    mempool_full_threshold: f64,
+) -> Ordering {
+    // Determine if each peer's mempool is (nearly) full
+    let is_mempool_full = |monitoring_metadata| {
+        get_mempool_utilization(monitoring_metadata)
+            .map(|mempool_utilization| mempool_utilization >= mempool_full_threshold)
+            .unwrap_or(false)
+    };
+    let mempool_full_a = is_mempool_full(monitoring_metadata_a);
+    let mempool_full_b = is_mempool_full(monitoring_metadata_b);
+
+    // Prioritize the peer whose mempool isn't full
+    mempool_full_a.cmp(&mempool_full_b).reverse()
+}
+
 /// Compares the validator distance for the given pair of monitoring metadata.
 /// The peer with the lowest validator distance is prioritized.
 fn compare_validator_distance(
@@ -565,10 +1668,13 @@ mod test {
         network_id::{NetworkId, PeerNetworkId},
     };
     use aptos_peer_monitoring_service_types::{
-        response::NetworkInformationResponse, PeerMonitoringMetadata,
+        response::{MempoolInformationResponse, NetworkInformationResponse},
+        PeerMonitoringMetadata,
     };
+    use aptos_temppath::TempPath;
     use aptos_types::PeerId;
     use core::cmp::Ordering;
+    use rand::{rngs::StdRng, SeedableRng};
     use std::collections::BTreeMap;
 
     #[test]
@@ -714,6 +1820,224 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_compare_ping_latency_buckets() {
+        // Create monitoring metadata with ping latencies that are close to each other
+        let monitoring_metadata_1 = create_metadata_with_latency(Some(0.500));
+        let monitoring_metadata_2 = create_metadata_with_latency(Some(0.515));
+        let monitoring_metadata_3 = create_metadata_with_latency(Some(0.530));
+
+        // Verify that the metadata is equal when the latencies are in the same bucket
+        assert_eq!(
+            Ordering::Equal,
+            compare_ping_latency_buckets(
+                &Some(&monitoring_metadata_1),
+                &Some(&monitoring_metadata_2),
+                20
+            )
+        );
+
+        // Verify that the metadata has different ordering when the latencies are in different
+        // buckets, even if they are within the slack of each other (i.e., it is transitive).
+        assert_eq!(
+            Ordering::Greater,
+            compare_ping_latency_buckets(
+                &Some(&monitoring_metadata_2),
+                &Some(&monitoring_metadata_3),
+                20
+            )
+        );
+        assert_eq!(
+            Ordering::Greater,
+            compare_ping_latency_buckets(
+                &Some(&monitoring_metadata_1),
+                &Some(&monitoring_metadata_3),
+                20
+            )
+        );
+
+        // Verify that the latencies are compared directly if the slack is 0
+        assert_eq!(
+            Ordering::Greater,
+            compare_ping_latency_buckets(
+                &Some(&monitoring_metadata_1),
+                &Some(&monitoring_metadata_2),
+                0
+            )
+        );
+
+        // Verify that metadata without a ping latency is still deprioritized
+        let monitoring_metadata_4 = create_metadata_with_latency(None);
+        assert_eq!(
+            Ordering::Less,
+            compare_ping_latency_buckets(
+                &Some(&monitoring_metadata_4),
+                &Some(&monitoring_metadata_1),
+                1000
+            )
+        );
+    }
+
+    #[test]
+    fn test_compare_mempool_fullness() {
+        // Create monitoring metadata with different mempool utilizations
+        let full_threshold = 0.9;
+        let empty_metadata = create_metadata_with_mempool_info(0, 100);
+        let nearly_full_metadata = create_metadata_with_mempool_info(95, 100);
+        let full_metadata = create_metadata_with_mempool_info(100, 100);
+        let unknown_metadata = create_metadata_with_distance(Some(1));
+
+        // Verify that peers with nearly full mempools are deprioritized
+        assert_eq!(
+            compare_mempool_fullness(
+                &Some(&empty_metadata),
+                &Some(&nearly_full_metadata),
+                full_threshold
+            ),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_mempool_fullness(
+                &Some(&full_metadata),
+                &Some(&empty_metadata),
+                full_threshold
+            ),
+            Ordering::Less
+        );
+
+        // Verify that peers above the threshold are considered equal
+        assert_eq!(
+            compare_mempool_fullness(
+                &Some(&nearly_full_metadata),
+                &Some(&full_metadata),
+                full_threshold
+            ),
+            Ordering::Equal
+        );
+
+        // Verify that peers with unknown utilization are not considered full
+        assert_eq!(
+            compare_mempool_fullness(
+                &Some(&unknown_metadata),
+                &Some(&full_metadata),
+                full_threshold
+            ),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_mempool_fullness(&None, &Some(&empty_metadata), full_threshold),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_sort_peers_by_mempool_fullness() {
+        // Create a prioritized peer state with intelligent peer prioritization enabled
+        let mempool_config = MempoolConfig {
+            enable_intelligent_peer_prioritization: true,
+            upstream_mempool_full_threshold_percent: 90,
+            ..MempoolConfig::default()
+        };
+        let prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config,
+            NodeType::PublicFullnode,
+            TimeService::mock(),
+        );
+
+        // Create a low latency peer with a nearly full mempool
+        let mut peer_metadata_1 = create_metadata_with_distance_and_latency(1, 0.1);
+        peer_metadata_1.latest_mempool_info_response =
+            create_metadata_with_mempool_info(950, 1000).latest_mempool_info_response;
+        let public_peer_1 = (create_public_peer(), Some(&peer_metadata_1));
+
+        // Create a high latency peer with a mostly empty mempool
+        let mut peer_metadata_2 = create_metadata_with_distance_and_latency(1, 0.5);
+        peer_metadata_2.latest_mempool_info_response =
+            create_metadata_with_mempool_info(100, 1000).latest_mempool_info_response;
+        let public_peer_2 = (create_public_peer(), Some(&peer_metadata_2));
+
+        // Verify that the peer with the nearly full mempool is deprioritized
+        let all_peers = vec![public_peer_1, public_peer_2];
+        let prioritized_peers = prioritized_peers_state.sort_peers_by_priority(&all_peers);
+        assert_eq!(prioritized_peers, vec![public_peer_2.0, public_peer_1.0]);
+    }
+
+    #[test]
+    fn test_compare_broadcast_ack_latency() {
+        // Verify that equal ACK latencies are equal
+        assert_eq!(
+            Ordering::Equal,
+            compare_broadcast_ack_latency(Some(&1.0), Some(&1.0))
+        );
+
+        // Verify that the lower ACK latency is prioritized
+        assert_eq!(
+            Ordering::Greater,
+            compare_broadcast_ack_latency(Some(&0.5), Some(&2.0))
+        );
+        assert_eq!(
+            Ordering::Less,
+            compare_broadcast_ack_latency(Some(&2.0), Some(&0.5))
+        );
+
+        // Verify that a peer with an ACK latency is prioritized
+        assert_eq!(
+            Ordering::Greater,
+            compare_broadcast_ack_latency(Some(&2.0), None)
+        );
+        assert_eq!(
+            Ordering::Less,
+            compare_broadcast_ack_latency(None, Some(&2.0))
+        );
+        assert_eq!(Ordering::Equal, compare_broadcast_ack_latency(None, None));
+    }
+
+    #[test]
+    fn test_sort_peers_by_broadcast_ack_latency() {
+        // Create a prioritized peer state with intelligent peer prioritization enabled
+        let mempool_config = MempoolConfig {
+            enable_intelligent_peer_prioritization: true,
+            peer_ping_latency_slack_ms: 20,
+            ..MempoolConfig::default()
+        };
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config,
+            NodeType::PublicFullnode,
+            TimeService::mock(),
+        );
+
+        // Create peers with similar ping latencies (in the same bucket)
+        let peer_metadata_1 = create_metadata_with_distance_and_latency(1, 0.100);
+        let public_peer_1 = (create_public_peer(), Some(&peer_metadata_1));
+        let peer_metadata_2 = create_metadata_with_distance_and_latency(1, 0.110);
+        let public_peer_2 = (create_public_peer(), Some(&peer_metadata_2));
+        let peer_metadata_3 = create_metadata_with_distance_and_latency(1, 0.105);
+        let public_peer_3 = (create_public_peer(), Some(&peer_metadata_3));
+
+        // Create a peer with a higher ping latency (in a higher bucket)
+        let peer_metadata_4 = create_metadata_with_distance_and_latency(1, 0.500);
+        let public_peer_4 = (create_public_peer(), Some(&peer_metadata_4));
+
+        // Update the broadcast ACK latencies (peer 1 is overloaded, peer 3 has no ACKs yet)
+        let broadcast_ack_latencies = HashMap::from([
+            (public_peer_1.0, 2.0),
+            (public_peer_2.0, 0.2),
+            (public_peer_4.0, 0.1),
+        ]);
+        prioritized_peers_state.update_broadcast_ack_latencies(broadcast_ack_latencies);
+
+        // Verify that peers with similar ping latencies are prioritized by ACK latency
+        let all_peers = vec![public_peer_1, public_peer_2, public_peer_3, public_peer_4];
+        let prioritized_peers = prioritized_peers_state.sort_peers_by_priority(&all_peers);
+        let expected_peers = vec![
+            public_peer_2.0,
+            public_peer_1.0,
+            public_peer_3.0,
+            public_peer_4.0,
+        ];
+        assert_eq!(prioritized_peers, expected_peers);
+    }
+
     #[test]
     fn test_get_peer_priority() {
         // Create a prioritized peer state
@@ -884,73 +2208,416 @@ mod test {
     }
 
     #[test]
-    fn test_ready_for_update_intelligent() {
-        // Create a mempool configuration with intelligent peer prioritization enabled
-        let shared_mempool_priority_update_interval_secs = 10;
-        let enable_intelligent_peer_prioritization = true;
+    fn test_broadcast_fanout_per_network() {
+        // Create a prioritized peer state with a fanout of 1 VFN peer and 2 public peers
         let mempool_config = MempoolConfig {
-            enable_intelligent_peer_prioritization,
-            shared_mempool_priority_update_interval_secs,
+            broadcast_fanout_per_network: vec![
+                NetworkFanoutConfig {
+                    network_id: NetworkId::Vfn,
+                    fanout: 1,
+                },
+                NetworkFanoutConfig {
+                    network_id: NetworkId::Public,
+                    fanout: 2,
+                },
+            ],
             ..MempoolConfig::default()
         };
-
-        // Create a prioritized peer state
-        let time_service = TimeService::mock();
         let mut prioritized_peers_state = PrioritizedPeersState::new(
             mempool_config.clone(),
-            NodeType::PublicFullnode,
-            time_service.clone(),
+            NodeType::ValidatorFullnode,
+            TimeService::mock(),
         );
 
-        // Verify that the prioritized peers should be updated (no prior update time)
-        let peers_changed = false;
-        assert!(prioritized_peers_state.ready_for_update(peers_changed));
-
-        // Set the last peer priority update time
-        prioritized_peers_state.last_peer_priority_update = Some(Instant::now());
-
-        // Verify that the prioritized peers should still be updated (not all ping latencies were observed)
-        assert!(prioritized_peers_state.ready_for_update(peers_changed));
-
-        // Set the ping latencies observed flag
-        prioritized_peers_state.observed_all_ping_latencies = true;
-
-        // Verify that the prioritized peers should not be updated (not enough time has passed)
-        assert!(!prioritized_peers_state.ready_for_update(peers_changed));
-
-        // Emulate a change in peers and verify the prioritized peers should be updated
-        assert!(prioritized_peers_state.ready_for_update(true));
-
-        // Elapse some time (but not enough for the prioritized peers to be updated)
-        let time_service = time_service.into_mock();
-        time_service.advance_secs(shared_mempool_priority_update_interval_secs / 2);
-
-        // Verify that the prioritized peers should not be updated (not enough time has passed)
-        assert!(!prioritized_peers_state.ready_for_update(peers_changed));
-
-        // Elapse enough time for the prioritized peers to be updated
-        time_service.advance_secs(shared_mempool_priority_update_interval_secs + 1);
+        // Create VFN and public peers with increasing ping latencies
+        let peer_metadata_1 = create_metadata_with_distance_and_latency(1, 0.1);
+        let vfn_peer_1 = (create_vfn_peer(), Some(&peer_metadata_1));
+        let peer_metadata_2 = create_metadata_with_distance_and_latency(1, 0.2);
+        let vfn_peer_2 = (create_vfn_peer(), Some(&peer_metadata_2));
+        let peer_metadata_3 = create_metadata_with_distance_and_latency(1, 0.3);
+        let public_peer_1 = (create_public_peer(), Some(&peer_metadata_3));
+        let peer_metadata_4 = create_metadata_with_distance_and_latency(1, 0.4);
+        let public_peer_2 = (create_public_peer(), Some(&peer_metadata_4));
+        let peer_metadata_5 = create_metadata_with_distance_and_latency(1, 0.5);
+        let public_peer_3 = (create_public_peer(), Some(&peer_metadata_5));
+        let all_peers = vec![
+            vfn_peer_1,
+            vfn_peer_2,
+            public_peer_1,
+            public_peer_2,
+            public_peer_3,
+        ];
 
-        // Verify that the prioritized peers should be updated (enough time has passed)
-        assert!(prioritized_peers_state.ready_for_update(peers_changed));
+        // Verify that the top peers respect the fanout of each network
+        prioritized_peers_state.update_prioritized_peers(all_peers.clone(), 0, 0);
+        prioritized_peer_state_well_formed(
+            &prioritized_peers_state,
+            mempool_config.num_sender_buckets,
+        );
+        let top_peers: Vec<_> = all_peers
+            .iter()
+            .map(|(peer, _)| prioritized_peers_state.is_top_peer(peer))
+            .collect();
+        assert_eq!(top_peers, vec![true, false, true, true, false]);
     }
 
     #[test]
-    fn test_ready_for_update_simple() {
-        // Create a mempool configuration with intelligent peer prioritization disabled
-        let enable_intelligent_peer_prioritization = false;
+    fn test_broadcast_fanout_per_network_no_peers() {
+        // Create a prioritized peer state with a fanout of 2 VFN peers (only)
         let mempool_config = MempoolConfig {
-            enable_intelligent_peer_prioritization,
+            broadcast_fanout_per_network: vec![NetworkFanoutConfig {
+                network_id: NetworkId::Vfn,
+                fanout: 2,
+            }],
             ..MempoolConfig::default()
         };
-
-        // Create a prioritized peers state
-        let time_service = TimeService::mock();
-        let prioritized_peers_state = PrioritizedPeersState::new(
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
             mempool_config.clone(),
-            NodeType::PublicFullnode,
-            time_service.clone(),
-        );
+            NodeType::ValidatorFullnode,
+            TimeService::mock(),
+        );
+
+        // Create public peers (only) with increasing ping latencies
+        let peer_metadata_1 = create_metadata_with_distance_and_latency(1, 0.1);
+        let peer_metadata_2 = create_metadata_with_distance_and_latency(1, 0.2);
+        let all_peers = vec![
+            (create_public_peer(), Some(&peer_metadata_1)),
+            (create_public_peer(), Some(&peer_metadata_2)),
+        ];
+
+        // Verify that no public peer is a top peer (there are no peers on the VFN network)
+        prioritized_peers_state.update_prioritized_peers(all_peers.clone(), 0, 0);
+        for (peer, _) in &all_peers {
+            assert!(!prioritized_peers_state.is_top_peer(peer));
+        }
+
+        // Verify that all sender buckets are still assigned to the public peers for failover
+        for bucket in 0..mempool_config.num_sender_buckets {
+            assert!(prioritized_peers_state.peer_to_sender_buckets.values().any(
+                |sender_buckets| {
+                    sender_buckets.get(&bucket) == Some(&BroadcastPeerPriority::Failover)
+                }
+            ));
+        }
+    }
+
+    #[test]
+    fn test_select_top_peers_per_network() {
+        let vfn_peer = create_vfn_peer();
+        let public_peers: Vec<_> = (0..3).map(|_| create_public_peer()).collect();
+        let prioritized_peers =
+            [vec![public_peers[0], vfn_peer], public_peers[1..].to_vec()].concat();
+
+        // Verify that networks without a fanout are skipped
+        let public_fanout = NetworkFanoutConfig {
+            network_id: NetworkId::Public,
+            fanout: 2,
+        };
+        let top_peers =
+            select_top_peers_per_network(&prioritized_peers, &[public_fanout.clone()], 10);
+        assert_eq!(top_peers, public_peers[0..2].to_vec());
+
+        // Verify that the total number of top peers is bounded
+        let vfn_fanout = NetworkFanoutConfig {
+            network_id: NetworkId::Vfn,
+            fanout: 1,
+        };
+        let top_peers =
+            select_top_peers_per_network(&prioritized_peers, &[public_fanout, vfn_fanout], 2);
+        assert_eq!(top_peers, vec![public_peers[0], vfn_peer]);
+    }
+
+    #[test]
+    fn test_broadcast_fanout_with_failover() {
+        // Create a prioritized peer state with a broadcast fanout of 2
+        let mempool_config = MempoolConfig {
+            broadcast_fanout: Some(2),
+            ..MempoolConfig::default()
+        };
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config.clone(),
+            NodeType::PublicFullnode,
+            TimeService::mock(),
+        );
+
+        // Create public peers with increasing ping latencies
+        let peer_metadata_1 = create_metadata_with_distance_and_latency(1, 0.1);
+        let peer_1 = (create_public_peer(), Some(&peer_metadata_1));
+        let peer_metadata_2 = create_metadata_with_distance_and_latency(1, 0.2);
+        let peer_2 = (create_public_peer(), Some(&peer_metadata_2));
+        let peer_metadata_3 = create_metadata_with_distance_and_latency(1, 0.3);
+        let peer_3 = (create_public_peer(), Some(&peer_metadata_3));
+        let peer_metadata_4 = create_metadata_with_distance_and_latency(1, 0.4);
+        let peer_4 = (create_public_peer(), Some(&peer_metadata_4));
+        let all_peers = vec![peer_1, peer_2, peer_3, peer_4];
+
+        // Verify that the top 2 peers are selected
+        prioritized_peers_state.update_prioritized_peers(all_peers.clone(), 0, 0);
+        prioritized_peer_state_well_formed(
+            &prioritized_peers_state,
+            mempool_config.num_sender_buckets,
+        );
+        let top_peers: Vec<_> = all_peers
+            .iter()
+            .map(|(peer, _)| prioritized_peers_state.is_top_peer(peer))
+            .collect();
+        assert_eq!(top_peers, vec![true, true, false, false]);
+
+        // Verify that failures of non-top peers don't require an update
+        assert!(!prioritized_peers_state.update_failed_peers(HashSet::from([peer_4.0])));
+
+        // Fail the first peer and verify that the next peer is promoted
+        assert!(prioritized_peers_state.update_failed_peers(HashSet::from([peer_1.0])));
+        prioritized_peers_state.update_prioritized_peers(all_peers.clone(), 0, 0);
+        let top_peers: Vec<_> = all_peers
+            .iter()
+            .map(|(peer, _)| prioritized_peers_state.is_top_peer(peer))
+            .collect();
+        assert_eq!(top_peers, vec![false, true, true, false]);
+
+        // Verify that the same failure doesn't require another update
+        assert!(!prioritized_peers_state.update_failed_peers(HashSet::from([peer_1.0])));
+
+        // Fail all peers and verify that top peers are still selected
+        let all_peer_ids = all_peers.iter().map(|(peer, _)| *peer).collect();
+        assert!(prioritized_peers_state.update_failed_peers(all_peer_ids));
+        prioritized_peers_state.update_prioritized_peers(all_peers.clone(), 0, 0);
+        prioritized_peer_state_well_formed(
+            &prioritized_peers_state,
+            mempool_config.num_sender_buckets,
+        );
+        assert!(prioritized_peers_state.is_top_peer(&peer_1.0));
+    }
+
+    #[test]
+    fn test_peer_misbehavior_counts() {
+        let scoring_config = PeerMisbehaviorScoringConfig {
+            transactions_per_scoring_window: 10,
+            misbehavior_threshold_percent: 50,
+            disconnect_penalized_peers: false,
+        };
+
+        // Record several accepted transactions and verify the peer isn't misbehaving
+        let mut misbehavior_counts = PeerMisbehaviorCounts::default();
+        for _ in 0..5 {
+            misbehavior_counts
+                .record_transaction(&MempoolStatus::new(MempoolStatusCode::Accepted), None);
+        }
+        assert!(!misbehavior_counts.is_window_complete(&scoring_config));
+        assert!(!misbehavior_counts.is_misbehaving(&scoring_config));
+
+        // Record invalid, expired and already committed transactions
+        let vm_error = MempoolStatus::new(MempoolStatusCode::VmError);
+        misbehavior_counts.record_transaction(&vm_error, Some(StatusCode::INVALID_SIGNATURE));
+        misbehavior_counts.record_transaction(&vm_error, Some(StatusCode::TRANSACTION_EXPIRED));
+        misbehavior_counts.record_transaction(&vm_error, Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD));
+        misbehavior_counts
+            .record_transaction(&MempoolStatus::new(MempoolStatusCode::InvalidUpdate), None);
+        assert_eq!(misbehavior_counts.num_invalid, 1);
+        assert_eq!(misbehavior_counts.num_expired, 1);
+        assert!(!misbehavior_counts.is_misbehaving(&scoring_config));
+
+        // Verify that transactions rejected due to local limits aren't misbehavior
+        misbehavior_counts
+            .record_transaction(&MempoolStatus::new(MempoolStatusCode::MempoolIsFull), None);
+        assert!(misbehavior_counts.is_window_complete(&scoring_config));
+        assert!(!misbehavior_counts.is_misbehaving(&scoring_config));
+
+        // Verify that transactions with invalid sequence numbers (e.g., due to a
+        // local commit race) aren't misbehavior
+        misbehavior_counts.record_transaction(
+            &MempoolStatus::new(MempoolStatusCode::InvalidSeqNumber),
+            None,
+        );
+        assert_eq!(
+            misbehavior_counts.num_invalid + misbehavior_counts.num_expired,
+            2
+        );
+        assert!(!misbehavior_counts.is_misbehaving(&scoring_config));
+
+        // Record more invalid transactions and verify the peer is now misbehaving
+        // (i.e., 9 of the 18 transactions are invalid or expired)
+        for _ in 0..6 {
+            misbehavior_counts.record_transaction(&vm_error, None);
+        }
+        assert!(!misbehavior_counts.is_misbehaving(&scoring_config));
+        misbehavior_counts.record_transaction(&vm_error, None);
+        assert!(misbehavior_counts.is_misbehaving(&scoring_config));
+    }
+
+    #[test]
+    fn test_peer_resending_committed_transactions() {
+        let scoring_config = PeerMisbehaviorScoringConfig {
+            transactions_per_scoring_window: 10,
+            misbehavior_threshold_percent: 10,
+            disconnect_penalized_peers: true,
+        };
+
+        // Record a full window of transactions that were just committed (i.e., resent
+        // by a peer that hasn't seen the commit), as well as conflicting updates.
+        let mut misbehavior_counts = PeerMisbehaviorCounts::default();
+        for _ in 0..8 {
+            misbehavior_counts.record_transaction(
+                &MempoolStatus::new(MempoolStatusCode::VmError),
+                Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD),
+            );
+        }
+        for _ in 0..2 {
+            misbehavior_counts
+                .record_transaction(&MempoolStatus::new(MempoolStatusCode::InvalidUpdate), None);
+        }
+
+        // Verify that the window is complete, but the peer isn't penalized
+        assert!(misbehavior_counts.is_window_complete(&scoring_config));
+        assert!(!misbehavior_counts.is_misbehaving(&scoring_config));
+    }
+
+    #[test]
+    fn test_penalized_peers() {
+        // Create a prioritized peer state with intelligent peer prioritization enabled
+        let mempool_config = MempoolConfig {
+            enable_intelligent_peer_prioritization: true,
+            ..MempoolConfig::default()
+        };
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config.clone(),
+            NodeType::PublicFullnode,
+            TimeService::mock(),
+        );
+
+        // Create peers with increasing ping latencies (the first is a VFN peer)
+        let peer_metadata_1 = create_metadata_with_distance_and_latency(1, 0.1);
+        let peer_1 = (create_vfn_peer(), Some(&peer_metadata_1));
+        let peer_metadata_2 = create_metadata_with_distance_and_latency(1, 0.2);
+        let peer_2 = (create_public_peer(), Some(&peer_metadata_2));
+        let peer_metadata_3 = create_metadata_with_distance_and_latency(1, 0.3);
+        let peer_3 = (create_public_peer(), Some(&peer_metadata_3));
+        let all_peers = vec![peer_1, peer_2, peer_3];
+
+        // Verify that the peers are prioritized by network and latency
+        prioritized_peers_state.update_prioritized_peers(all_peers.clone(), 0, 0);
+        let expected_peers = vec![peer_1.0, peer_2.0, peer_3.0];
+        assert_eq!(
+            *prioritized_peers_state.prioritized_peers.read(),
+            expected_peers
+        );
+
+        // Penalize the first two peers and verify they are newly penalized
+        let peer_misbehavior_scores =
+            HashMap::from([(peer_1.0, true), (peer_2.0, true), (peer_3.0, false)]);
+        let mut newly_penalized_peers = prioritized_peers_state
+            .update_penalized_peers(peer_misbehavior_scores.clone(), |_| true);
+        newly_penalized_peers.sort();
+        let mut expected_penalized_peers = vec![peer_1.0, peer_2.0];
+        expected_penalized_peers.sort();
+        assert_eq!(newly_penalized_peers, expected_penalized_peers);
+
+        // Verify that the penalized peers are pushed to the bottom (and aren't top peers)
+        prioritized_peers_state.update_prioritized_peers(all_peers.clone(), 0, 0);
+        let expected_peers = vec![peer_3.0, peer_1.0, peer_2.0];
+        assert_eq!(
+            *prioritized_peers_state.prioritized_peers.read(),
+            expected_peers
+        );
+        assert!(prioritized_peers_state.is_top_peer(&peer_3.0));
+        assert!(!prioritized_peers_state.is_top_peer(&peer_1.0));
+        let prioritized_peers_info = prioritized_peers_state.get_prioritized_peers_info();
+        assert!(prioritized_peers_info.read()[1].penalized);
+
+        // Verify that the same scores don't newly penalize any peers
+        assert!(prioritized_peers_state
+            .update_penalized_peers(peer_misbehavior_scores, |_| true)
+            .is_empty());
+
+        // Forgive the first peer and verify it regains its priority
+        prioritized_peers_state
+            .update_penalized_peers(HashMap::from([(peer_1.0, false)]), |_| true);
+        prioritized_peers_state.update_prioritized_peers(all_peers.clone(), 0, 0);
+        let expected_peers = vec![peer_1.0, peer_3.0, peer_2.0];
+        assert_eq!(
+            *prioritized_peers_state.prioritized_peers.read(),
+            expected_peers
+        );
+
+        // Disconnect the second peer and verify that its penalty is dropped
+        prioritized_peers_state.update_penalized_peers(HashMap::new(), |peer| *peer != peer_2.0);
+        assert!(prioritized_peers_state.penalized_peers.is_empty());
+        prioritized_peers_state.update_prioritized_peers(all_peers, 0, 0);
+        let expected_peers = vec![peer_1.0, peer_2.0, peer_3.0];
+        assert_eq!(
+            *prioritized_peers_state.prioritized_peers.read(),
+            expected_peers
+        );
+    }
+
+    #[test]
+    fn test_ready_for_update_intelligent() {
+        // Create a mempool configuration with intelligent peer prioritization enabled
+        let shared_mempool_priority_update_interval_secs = 10;
+        let enable_intelligent_peer_prioritization = true;
+        let mempool_config = MempoolConfig {
+            enable_intelligent_peer_prioritization,
+            shared_mempool_priority_update_interval_secs,
+            ..MempoolConfig::default()
+        };
+
+        // Create a prioritized peer state
+        let time_service = TimeService::mock();
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config.clone(),
+            NodeType::PublicFullnode,
+            time_service.clone(),
+        );
+
+        // Verify that the prioritized peers should be updated (no prior update time)
+        let peers_changed = false;
+        assert!(prioritized_peers_state.ready_for_update(peers_changed));
+
+        // Set the last peer priority update time
+        prioritized_peers_state.last_peer_priority_update = Some(Instant::now());
+
+        // Verify that the prioritized peers should still be updated (not all ping latencies were observed)
+        assert!(prioritized_peers_state.ready_for_update(peers_changed));
+
+        // Set the ping latencies observed flag
+        prioritized_peers_state.observed_all_ping_latencies = true;
+
+        // Verify that the prioritized peers should not be updated (not enough time has passed)
+        assert!(!prioritized_peers_state.ready_for_update(peers_changed));
+
+        // Emulate a change in peers and verify the prioritized peers should be updated
+        assert!(prioritized_peers_state.ready_for_update(true));
+
+        // Elapse some time (but not enough for the prioritized peers to be updated)
+        let time_service = time_service.into_mock();
+        time_service.advance_secs(shared_mempool_priority_update_interval_secs / 2);
+
+        // Verify that the prioritized peers should not be updated (not enough time has passed)
+        assert!(!prioritized_peers_state.ready_for_update(peers_changed));
+
+        // Elapse enough time for the prioritized peers to be updated
+        time_service.advance_secs(shared_mempool_priority_update_interval_secs + 1);
+
+        // Verify that the prioritized peers should be updated (enough time has passed)
+        assert!(prioritized_peers_state.ready_for_update(peers_changed));
+    }
+
+    #[test]
+    fn test_ready_for_update_simple() {
+        // Create a mempool configuration with intelligent peer prioritization disabled
+        let enable_intelligent_peer_prioritization = false;
+        let mempool_config = MempoolConfig {
+            enable_intelligent_peer_prioritization,
+            ..MempoolConfig::default()
+        };
+
+        // Create a prioritized peers state
+        let time_service = TimeService::mock();
+        let prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config.clone(),
+            NodeType::PublicFullnode,
+            time_service.clone(),
+        );
 
         // Verify that the prioritized peers is updated when the peers change
         for _ in 0..10 {
@@ -971,6 +2638,113 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_update_prioritization_params() {
+        // Create a prioritized peers state with a broadcast fanout of 3 (and
+        // intelligent peer prioritization disabled)
+        let mempool_config = MempoolConfig {
+            enable_intelligent_peer_prioritization: false,
+            broadcast_fanout: Some(3),
+            ..MempoolConfig::default()
+        };
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config,
+            NodeType::PublicFullnode,
+            TimeService::mock(),
+        );
+
+        // Update the prioritized peers and verify no further update is required
+        let peers: Vec<_> = (0..3).map(|_| create_public_peer()).collect();
+        let all_peers: Vec<_> = peers.iter().map(|peer| (*peer, None)).collect();
+        prioritized_peers_state.update_prioritized_peers(all_peers.clone(), 0, 0);
+        assert_eq!(prioritized_peers_state.get_top_peers().len(), 3);
+        assert!(!prioritized_peers_state.ready_for_update(false));
+
+        // Set new prioritization params and verify they're pending
+        let params = PeerPrioritizationParams {
+            shared_mempool_priority_update_interval_secs: 60,
+            broadcast_fanout: Some(1),
+            broadcast_fanout_per_network: vec![],
+            prioritization_strategy: PeerPrioritizationStrategy::Simple,
+        };
+        prioritized_peers_state.set_prioritization_params(params.clone());
+        assert_eq!(prioritized_peers_state.get_prioritization_params(), params);
+        assert_eq!(prioritized_peers_state.get_top_peers().len(), 3);
+
+        // Verify the params are applied on the next update cycle
+        assert!(prioritized_peers_state.ready_for_update(false));
+        prioritized_peers_state.update_prioritized_peers(all_peers, 0, 0);
+        assert_eq!(prioritized_peers_state.get_top_peers().len(), 1);
+        assert_eq!(
+            prioritized_peers_state
+                .mempool_config
+                .shared_mempool_priority_update_interval_secs,
+            60
+        );
+        assert!(!prioritized_peers_state.ready_for_update(false));
+    }
+
+    #[test]
+    fn test_requires_update_for_monitoring_change() {
+        // Create a prioritized peers state with event-driven updates enabled
+        let mempool_config = MempoolConfig {
+            enable_intelligent_peer_prioritization: true,
+            priority_update_latency_change_percent: Some(50),
+            ..MempoolConfig::default()
+        };
+        let time_service = TimeService::mock();
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config.clone(),
+            NodeType::PublicFullnode,
+            time_service.clone(),
+        );
+
+        // Verify that unknown peers don't require an update
+        let peer = create_public_peer();
+        let peer_metadata = create_metadata_with_distance_and_latency(1, 0.2);
+        assert!(!prioritized_peers_state
+            .requires_update_for_monitoring_change(&peer, Some(&peer_metadata)));
+
+        // Update the prioritized peers
+        prioritized_peers_state.update_prioritized_peers(vec![(peer, Some(&peer_metadata))], 0, 0);
+
+        // Elapse enough time for event-driven updates
+        let time_service = time_service.into_mock();
+        time_service.advance_ms(mempool_config.shared_mempool_peer_update_interval_ms);
+
+        // Verify that small latency changes don't require an update
+        for (distance, latency) in [(1, 0.2), (1, 0.25), (1, 0.15)] {
+            let peer_metadata = create_metadata_with_distance_and_latency(distance, latency);
+            assert!(!prioritized_peers_state
+                .requires_update_for_monitoring_change(&peer, Some(&peer_metadata)));
+        }
+
+        // Verify that distance and large latency changes require an update
+        for (distance, latency) in [(2, 0.2), (1, 0.35), (1, 0.05)] {
+            let peer_metadata = create_metadata_with_distance_and_latency(distance, latency);
+            assert!(prioritized_peers_state
+                .requires_update_for_monitoring_change(&peer, Some(&peer_metadata)));
+        }
+
+        // Update the prioritized peers and verify that material changes are
+        // ignored until the peer update interval has elapsed.
+        let peer_metadata = create_metadata_with_distance_and_latency(2, 0.2);
+        prioritized_peers_state.update_prioritized_peers(vec![(peer, Some(&peer_metadata))], 0, 0);
+        let updated_peer_metadata = create_metadata_with_distance_and_latency(1, 0.2);
+        assert!(!prioritized_peers_state
+            .requires_update_for_monitoring_change(&peer, Some(&updated_peer_metadata)));
+        time_service.advance_ms(mempool_config.shared_mempool_peer_update_interval_ms);
+        assert!(prioritized_peers_state
+            .requires_update_for_monitoring_change(&peer, Some(&updated_peer_metadata)));
+
+        // Verify that event-driven updates can be disabled
+        prioritized_peers_state
+            .mempool_config
+            .priority_update_latency_change_percent = None;
+        assert!(!prioritized_peers_state
+            .requires_update_for_monitoring_change(&peer, Some(&updated_peer_metadata)));
+    }
+
     #[test]
     fn test_sort_peers_by_priority_intelligent() {
         // Create a mempool configuration with intelligent peer prioritization enabled
@@ -1176,6 +2950,103 @@ mod test {
         assert!(prioritized_peers_state.observed_all_ping_latencies);
     }
 
+    #[test]
+    fn test_prioritized_peers_snapshot() {
+        // Create a mempool configuration with prioritized peer snapshots enabled
+        let snapshot_path = TempPath::new();
+        let mempool_config = MempoolConfig {
+            prioritized_peers_snapshot_path: Some(snapshot_path.path().to_path_buf()),
+            ..MempoolConfig::default()
+        };
+
+        // Create a prioritized peers state and update the peers (with ping latencies)
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config.clone(),
+            NodeType::PublicFullnode,
+            TimeService::mock(),
+        );
+        let peer_metadata_1 = create_metadata_with_latency(Some(0.5));
+        let peer_metadata_2 = create_metadata_with_latency(Some(0.1));
+        let peer_metadata_3 = create_metadata_with_latency(Some(0.3));
+        let peers = [
+            create_public_peer(),
+            create_public_peer(),
+            create_public_peer(),
+        ];
+        let all_peers = vec![
+            (peers[0], Some(&peer_metadata_1)),
+            (peers[1], Some(&peer_metadata_2)),
+            (peers[2], Some(&peer_metadata_3)),
+        ];
+        prioritized_peers_state.update_prioritized_peers(all_peers, 0, 0);
+
+        // Verify the snapshot was saved
+        let expected_peers = vec![peers[1], peers[2], peers[0]];
+        let snapshot = PrioritizedPeersSnapshot::load(snapshot_path.path())
+            .unwrap()
+            .unwrap();
+        let snapshot_peers: Vec<_> = snapshot.peers.iter().map(|peer| peer.peer).collect();
+        assert_eq!(snapshot_peers, expected_peers);
+        assert_eq!(snapshot.peers[0].average_ping_latency_secs, Some(0.1));
+
+        // Update the peers again (with a new latency), and verify the snapshot
+        // isn't saved again until the snapshot interval has elapsed.
+        let peer_metadata_4 = create_metadata_with_latency(Some(0.2));
+        let all_peers = vec![
+            (peers[0], Some(&peer_metadata_1)),
+            (peers[1], Some(&peer_metadata_4)),
+            (peers[2], Some(&peer_metadata_3)),
+        ];
+        prioritized_peers_state.update_prioritized_peers(all_peers.clone(), 0, 0);
+        let load_first_peer_latency = || {
+            PrioritizedPeersSnapshot::load(snapshot_path.path())
+                .unwrap()
+                .unwrap()
+                .peers[0]
+                .average_ping_latency_secs
+        };
+        assert_eq!(load_first_peer_latency(), Some(0.1));
+        prioritized_peers_state
+            .time_service
+            .clone()
+            .into_mock()
+            .advance_secs(mempool_config.prioritized_peers_snapshot_interval_secs);
+        prioritized_peers_state.update_prioritized_peers(all_peers, 0, 0);
+        assert_eq!(load_first_peer_latency(), Some(0.2));
+
+        // Create a new prioritized peers state (i.e., after a restart), and update
+        // the peers before any monitoring metadata is observed.
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config.clone(),
+            NodeType::PublicFullnode,
+            TimeService::mock(),
+        );
+        assert_eq!(prioritized_peers_state.restored_peer_latencies.len(), 3);
+        let all_peers: Vec<_> = peers.iter().rev().map(|peer| (*peer, None)).collect();
+        prioritized_peers_state.update_prioritized_peers(all_peers, 0, 0);
+
+        // Verify the restored latencies were used to prioritize the peers
+        let prioritized_peers = prioritized_peers_state.prioritized_peers.read().clone();
+        assert_eq!(prioritized_peers, expected_peers);
+        assert!(!prioritized_peers_state.observed_all_ping_latencies);
+        assert_eq!(prioritized_peers_state.restored_peer_latencies.len(), 3);
+
+        // Verify the snapshot was saved (with the restored latencies), even though
+        // the latencies of the peers haven't been observed yet.
+        assert_eq!(load_first_peer_latency(), Some(0.2));
+
+        // Create a new prioritized peers state long after the snapshot was
+        // taken, and verify that the (expired) snapshot is not restored.
+        let time_service = TimeService::mock();
+        time_service
+            .clone()
+            .into_mock()
+            .advance_secs(mempool_config.prioritized_peers_snapshot_max_age_secs + 1);
+        let prioritized_peers_state =
+            PrioritizedPeersState::new(mempool_config, NodeType::PublicFullnode, time_service);
+        assert!(prioritized_peers_state.restored_peer_latencies.is_empty());
+    }
+
     #[test]
     fn test_update_prioritized_peers_simple() {
         // Create a mempool configuration with intelligent peer prioritization disabled
@@ -1243,6 +3114,386 @@ mod test {
         assert_ne!(latency_sorted_peers, prioritized_peers);
     }
 
+    #[test]
+    fn test_shadow_prioritization_strategies() {
+        // Create a mempool configuration with simple peer prioritization,
+        // and intelligent peer prioritization running in shadow mode.
+        let mempool_config = MempoolConfig {
+            enable_intelligent_peer_prioritization: false,
+            shadow_prioritization_strategies: vec![PeerPrioritizationStrategy::Intelligent],
+            ..MempoolConfig::default()
+        };
+
+        // Create a prioritized peer state
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config,
+            NodeType::PublicFullnode,
+            TimeService::mock(),
+        );
+
+        // Create a list of peers with the same network ID but different metadata
+        let all_metadata: Vec<_> = (0..100)
+            .map(|i| create_metadata_with_distance_and_latency(i, i as f64))
+            .collect();
+        let all_peers: Vec<_> = all_metadata
+            .iter()
+            .map(|metadata| (create_public_peer(), Some(metadata)))
+            .collect();
+
+        // Update the prioritized peers and verify the active (simple) strategy is used
+        prioritized_peers_state.update_prioritized_peers(all_peers.clone(), 5000, 2000);
+        let prioritized_peers = prioritized_peers_state.prioritized_peers.read().clone();
+        let simple_sorted_peers = prioritized_peers_state
+            .sort_peers_by_strategy(&all_peers, PeerPrioritizationStrategy::Simple);
+        assert_eq!(prioritized_peers, simple_sorted_peers);
+
+        // Verify that the shadow (intelligent) strategy sorts by validator distance
+        let shadow_prioritized_peers = prioritized_peers_state
+            .sort_peers_by_strategy(&all_peers, PeerPrioritizationStrategy::Intelligent);
+        let distance_sorted_peers: Vec<_> = all_peers.iter().map(|(peer, _)| *peer).collect();
+        assert_eq!(shadow_prioritized_peers, distance_sorted_peers);
+        assert_ne!(shadow_prioritized_peers, prioritized_peers);
+    }
+
+    #[test]
+    fn test_prioritization_divergence() {
+        // Create a prioritized list of peers
+        let prioritized_peers: Vec<_> = (0..5).map(|_| create_public_peer()).collect();
+
+        // Verify that identical prioritizations don't diverge
+        let divergence = PrioritizationDivergence::new(&prioritized_peers, &prioritized_peers, 2);
+        assert_eq!(divergence, PrioritizationDivergence::default());
+
+        // Verify that swapping the top peers only changes the top-1 peer
+        let mut shadow_prioritized_peers = prioritized_peers.clone();
+        shadow_prioritized_peers.swap(0, 1);
+        let divergence =
+            PrioritizationDivergence::new(&prioritized_peers, &shadow_prioritized_peers, 2);
+        let expected_divergence = PrioritizationDivergence {
+            top_peer_changed: true,
+            num_top_peers_changed: 0,
+            num_peers_changed: 2,
+        };
+        assert_eq!(divergence, expected_divergence);
+
+        // Verify that reversing the peers changes the top-k peers
+        let shadow_prioritized_peers: Vec<_> = prioritized_peers.iter().rev().cloned().collect();
+        let divergence =
+            PrioritizationDivergence::new(&prioritized_peers, &shadow_prioritized_peers, 2);
+        let expected_divergence = PrioritizationDivergence {
+            top_peer_changed: true,
+            num_top_peers_changed: 2,
+            num_peers_changed: 4,
+        };
+        assert_eq!(divergence, expected_divergence);
+    }
+
+    #[test]
+    fn test_update_prioritized_peers_info() {
+        // Create a prioritized peer state with intelligent peer prioritization enabled
+        let mempool_config = MempoolConfig {
+            enable_intelligent_peer_prioritization: true,
+            ..MempoolConfig::default()
+        };
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config,
+            NodeType::PublicFullnode,
+            TimeService::mock(),
+        );
+
+        // Verify that the prioritized peers info is empty
+        let prioritized_peers_info = prioritized_peers_state.get_prioritized_peers_info();
+        assert!(prioritized_peers_info.read().is_empty());
+
+        // Create a list of peers with different distances and ping latencies
+        let peer_metadata_1 = create_metadata_with_distance_and_latency(2, 0.1);
+        let public_peer_1 = (create_public_peer(), Some(&peer_metadata_1));
+        let peer_metadata_2 = create_metadata_with_distance_and_latency(1, 0.5);
+        let public_peer_2 = (create_public_peer(), Some(&peer_metadata_2));
+
+        // Update the broadcast ACK latencies and the failed peers
+        let broadcast_ack_latencies = HashMap::from([(public_peer_2.0, 0.2)]);
+        prioritized_peers_state.update_broadcast_ack_latencies(broadcast_ack_latencies);
+        prioritized_peers_state.update_failed_peers(HashSet::from([public_peer_1.0]));
+
+        // Update the prioritized peers
+        let all_peers = vec![public_peer_1, public_peer_2];
+        prioritized_peers_state.update_prioritized_peers(all_peers, 0, 0);
+
+        // Verify that the prioritized peers info reflects the prioritization and metadata
+        let prioritized_peers_info = prioritized_peers_info.read().clone();
+        assert_eq!(prioritized_peers_info.len(), 2);
+
+        let peer_info_1 = &prioritized_peers_info[0];
+        assert_eq!(peer_info_1.peer, public_peer_2.0.to_string());
+        assert_eq!(peer_info_1.priority, 0);
+        assert_eq!(peer_info_1.network_id, NetworkId::Public);
+        assert_eq!(peer_info_1.distance_from_validators, Some(1));
+        assert_eq!(peer_info_1.average_ping_latency_secs, Some(0.5));
+        assert_eq!(peer_info_1.broadcast_ack_latency_secs, Some(0.2));
+        assert!(!peer_info_1.failed_broadcasts);
+
+        let peer_info_2 = &prioritized_peers_info[1];
+        assert_eq!(peer_info_2.peer, public_peer_1.0.to_string());
+        assert_eq!(peer_info_2.priority, 1);
+        assert_eq!(peer_info_2.distance_from_validators, Some(2));
+        assert_eq!(peer_info_2.broadcast_ack_latency_secs, None);
+        assert!(peer_info_2.failed_broadcasts);
+    }
+
+    #[test]
+    fn test_sample_peers_by_priority_score() {
+        // Create a list of prioritized peers
+        let prioritized_peers: Vec<_> = (0..5).map(|_| create_public_peer()).collect();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Verify that the sampled peers are unique and capped by the number of peers
+        for num_peers in 0..10 {
+            let sampled_peers =
+                sample_peers_by_priority_score(&prioritized_peers, num_peers, &mut rng);
+            assert_eq!(sampled_peers.len(), min(num_peers, prioritized_peers.len()));
+            assert_eq!(sampled_peers.iter().unique().count(), sampled_peers.len());
+        }
+
+        // Sample a single peer many times and count how often each peer is selected
+        let mut num_times_sampled = vec![0; prioritized_peers.len()];
+        for _ in 0..10_000 {
+            let sampled_peers = sample_peers_by_priority_score(&prioritized_peers, 1, &mut rng);
+            let (position, _) = prioritized_peers
+                .iter()
+                .find_position(|peer| **peer == sampled_peers[0])
+                .unwrap();
+            num_times_sampled[position] += 1;
+        }
+
+        // Verify that all peers are sampled, and that higher priority peers are favored
+        assert!(num_times_sampled.iter().all(|count| *count > 0));
+        assert!(num_times_sampled
+            .windows(2)
+            .all(|counts| counts[0] > counts[1]));
+
+        // Verify that sampling from an empty list returns no peers
+        assert!(sample_peers_by_priority_score(&[], 3, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn test_get_network_group() {
+        // Verify that IPv4 addresses are grouped by /24 subnet
+        assert_eq!(
+            get_network_group(&create_network_address("/ip4/10.0.1.5/tcp/6182")),
+            Some("ip4/10.0.1.0/24".into())
+        );
+        assert_eq!(
+            get_network_group(&create_network_address("/ip4/10.0.1.200/tcp/6182")),
+            get_network_group(&create_network_address("/ip4/10.0.1.5/tcp/6182"))
+        );
+
+        // Verify that IPv6 addresses are grouped by /48 prefix
+        assert_eq!(
+            get_network_group(&create_network_address("/ip6/2001:db8:1:2::1/tcp/6182")),
+            Some("ip6/2001:db8:1::/48".into())
+        );
+
+        // Verify that DNS names are grouped by parent domain
+        assert_eq!(
+            get_network_group(&create_network_address(
+                "/dns/node-3.rack-7.example.com/tcp/6182"
+            )),
+            Some("dns/rack-7.example.com".into())
+        );
+        assert_eq!(
+            get_network_group(&create_network_address("/dns4/localhost/tcp/6182")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_get_peer_network_group() {
+        // Verify that direct connections are grouped by their address
+        let peer = create_public_peer();
+        let connection_metadata = create_connection_metadata(
+            peer,
+            create_network_address("/ip4/10.0.1.5/tcp/6182"),
+            None,
+        );
+        assert_eq!(
+            get_peer_network_group(&connection_metadata),
+            Some("ip4/10.0.1.0/24".into())
+        );
+
+        // Verify that proxied connections are grouped by the client address (not the proxy)
+        let connection_metadata = create_connection_metadata(
+            peer,
+            create_network_address("/ip4/10.0.2.5/tcp/6182"),
+            Some(create_network_address("/ip4/192.168.0.1/tcp/6182")),
+        );
+        assert_eq!(
+            get_peer_network_group(&connection_metadata),
+            Some("ip4/10.0.2.0/24".into())
+        );
+
+        // Verify that the proxy address is used if the client address has no group
+        let connection_metadata = create_connection_metadata(
+            peer,
+            create_network_address("/dns4/localhost/tcp/6182"),
+            Some(create_network_address("/ip4/192.168.0.1/tcp/6182")),
+        );
+        assert_eq!(
+            get_peer_network_group(&connection_metadata),
+            Some("ip4/192.168.0.0/24".into())
+        );
+    }
+
+    #[test]
+    fn test_sort_peers_by_network_diversity() {
+        // Create a prioritized peer state with network diversity enabled
+        let mempool_config = MempoolConfig {
+            enable_intelligent_peer_prioritization: true,
+            enable_peer_network_diversity: true,
+            peer_ping_latency_slack_ms: 0,
+            ..MempoolConfig::default()
+        };
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config,
+            NodeType::PublicFullnode,
+            TimeService::mock(),
+        );
+
+        // Create validator peers in the same subnet
+        let validator_peer_1 = (create_validator_peer(), None);
+        let validator_peer_2 = (create_validator_peer(), None);
+
+        // Create public peers (with increasing ping latencies)
+        let peer_metadata_1 = create_metadata_with_distance_and_latency(1, 0.1);
+        let public_peer_1 = (create_public_peer(), Some(&peer_metadata_1));
+        let peer_metadata_2 = create_metadata_with_distance_and_latency(1, 0.2);
+        let public_peer_2 = (create_public_peer(), Some(&peer_metadata_2));
+        let peer_metadata_3 = create_metadata_with_distance_and_latency(1, 0.3);
+        let public_peer_3 = (create_public_peer(), Some(&peer_metadata_3));
+        let peer_metadata_4 = create_metadata_with_distance_and_latency(1, 0.4);
+        let public_peer_4 = (create_public_peer(), Some(&peer_metadata_4));
+
+        // Update the connection metadata (public peers 1 and 2 share a subnet,
+        // and public peer 4 has no network group).
+        let peer_network_addresses = [
+            (
+                validator_peer_1.0,
+                create_network_address("/ip4/192.168.0.1/tcp/6180"),
+            ),
+            (
+                validator_peer_2.0,
+                create_network_address("/ip4/192.168.0.2/tcp/6180"),
+            ),
+            (
+                public_peer_1.0,
+                create_network_address("/ip4/10.0.1.5/tcp/6182"),
+            ),
+            (
+                public_peer_2.0,
+                create_network_address("/ip4/10.0.1.200/tcp/6182"),
+            ),
+            (
+                public_peer_3.0,
+                create_network_address("/dns/node-3.rack-7.example.com/tcp/6182"),
+            ),
+            (
+                public_peer_4.0,
+                create_network_address("/dns4/localhost/tcp/6182"),
+            ),
+        ];
+        let peer_connection_metadata = peer_network_addresses
+            .into_iter()
+            .map(|(peer, network_address)| {
+                (
+                    peer,
+                    create_connection_metadata(peer, network_address, None),
+                )
+            })
+            .collect();
+        prioritized_peers_state.update_peer_connection_metadata(peer_connection_metadata);
+
+        // Verify that the second peer in a subnet is deprioritized (within its network)
+        let all_peers = vec![
+            public_peer_4,
+            public_peer_3,
+            public_peer_2,
+            public_peer_1,
+            validator_peer_2,
+            validator_peer_1,
+        ];
+        let prioritized_peers = prioritized_peers_state.sort_peers_by_priority(&all_peers);
+        let expected_public_peers = vec![
+            public_peer_1.0,
+            public_peer_3.0,
+            public_peer_4.0,
+            public_peer_2.0,
+        ];
+        assert_eq!(prioritized_peers[2..], expected_public_peers);
+        assert!(prioritized_peers[..2]
+            .iter()
+            .all(|peer| peer.network_id() == NetworkId::Validator));
+
+        // Disable network diversity and verify that peers are sorted by ping latency
+        prioritized_peers_state
+            .mempool_config
+            .enable_peer_network_diversity = false;
+        let prioritized_peers = prioritized_peers_state.sort_peers_by_priority(&all_peers);
+        let expected_public_peers = vec![
+            public_peer_1.0,
+            public_peer_2.0,
+            public_peer_3.0,
+            public_peer_4.0,
+        ];
+        assert_eq!(prioritized_peers[2..], expected_public_peers);
+    }
+
+    #[test]
+    fn test_pinned_upstream_peers() {
+        // Create several VFN peers, with the pinned (slowest) peer being further from the validators
+        let pinned_peer = create_vfn_peer();
+        let vfn_peer_1 = create_vfn_peer();
+        let vfn_peer_2 = create_vfn_peer();
+        let pinned_metadata = create_metadata_with_distance_and_latency(1, 2.0);
+        let peer_metadata_1 = create_metadata_with_distance_and_latency(0, 0.5);
+        let peer_metadata_2 = create_metadata_with_distance_and_latency(0, 0.1);
+        let all_peers = vec![
+            (vfn_peer_1, Some(&peer_metadata_1)),
+            (pinned_peer, Some(&pinned_metadata)),
+            (vfn_peer_2, Some(&peer_metadata_2)),
+        ];
+
+        // Verify that the peers are prioritized by distance and latency (without pinning)
+        let prioritized_peers_state = PrioritizedPeersState::new(
+            MempoolConfig::default(),
+            NodeType::ValidatorFullnode,
+            TimeService::mock(),
+        );
+        let sorted_peers = prioritized_peers_state.sort_peers_by_priority(&all_peers);
+        assert_eq!(sorted_peers, vec![vfn_peer_2, vfn_peer_1, pinned_peer]);
+
+        // Pin the peer and verify that it is prioritized above the other peers
+        let mempool_config = MempoolConfig {
+            pinned_upstream_peers: vec![pinned_peer.peer_id()],
+            ..MempoolConfig::default()
+        };
+        let mut prioritized_peers_state = PrioritizedPeersState::new(
+            mempool_config,
+            NodeType::ValidatorFullnode,
+            TimeService::mock(),
+        );
+        let sorted_peers = prioritized_peers_state.sort_peers_by_priority(&all_peers);
+        assert_eq!(sorted_peers, vec![pinned_peer, vfn_peer_2, vfn_peer_1]);
+
+        // Update the prioritized peers and verify that the pinned peer is the top peer
+        prioritized_peers_state.update_prioritized_peers(all_peers, 0, 0);
+        assert_eq!(prioritized_peers_state.get_peer_priority(&pinned_peer), 0);
+        assert_eq!(
+            prioritized_peers_state.get_top_peers(),
+            HashSet::from([pinned_peer])
+        );
+        assert!(prioritized_peers_state.get_prioritized_peers_info().read()[0].pinned);
+    }
+
     /// Creates a peer monitoring metadata with the given distance
     fn create_metadata_with_distance(
         distance_from_validators: Option<u64>,
@@ -1268,6 +3519,19 @@ mod test {
         monitoring_metadata
     }
 
+    /// Creates a peer monitoring metadata with the given mempool information
+    fn create_metadata_with_mempool_info(
+        num_transactions: u64,
+        capacity: u64,
+    ) -> PeerMonitoringMetadata {
+        let mut monitoring_metadata = PeerMonitoringMetadata::default();
+        monitoring_metadata.latest_mempool_info_response = Some(MempoolInformationResponse {
+            num_transactions,
+            capacity,
+        });
+        monitoring_metadata
+    }
+
     /// Creates a peer monitoring metadata with the given ping latency
     fn create_metadata_with_latency(
         average_ping_latency_secs: Option<f64>,
@@ -1290,4 +3554,21 @@ mod test {
     fn create_public_peer() -> PeerNetworkId {
         PeerNetworkId::new(NetworkId::Public, PeerId::random())
     }
+
+    /// Creates a network address from the given string
+    fn create_network_address(network_address: &str) -> NetworkAddress {
+        network_address.parse().unwrap()
+    }
+
+    /// Creates connection metadata for the peer with the given addresses
+    fn create_connection_metadata(
+        peer: PeerNetworkId,
+        network_address: NetworkAddress,
+        proxy_address: Option<NetworkAddress>,
+    ) -> ConnectionMetadata {
+        let mut connection_metadata = ConnectionMetadata::mock(peer.peer_id());
+        connection_metadata.addr = network_address;
+        connection_metadata.proxy_addr = proxy_address;
+        connection_metadata
+    }
 }
@@ -0,0 +1,127 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! An on-disk snapshot of the prioritized peers (and the latencies observed for them).
+//! The snapshot is written after priority updates, and restored on startup, so that a
+//! restarting node can immediately prioritize known-good peers, instead of waiting for
+//! the monitoring metadata of each peer to be observed again. The snapshot is a single
+//! JSON file, and it is replaced atomically (by writing a temporary file and renaming it).
+
+use anyhow::{Context, Result};
+use aptos_config::network_id::PeerNetworkId;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The latencies observed for a single prioritized peer
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct PrioritizedPeerSnapshot {
+    /// The peer (and the network it belongs to)
+    pub peer: PeerNetworkId,
+    /// The average ping latency (in seconds) to the peer (if known)
+    pub average_ping_latency_secs: Option<f64>,
+    /// The average broadcast ACK latency (in seconds) of the peer (if known)
+    pub broadcast_ack_latency_secs: Option<f64>,
+}
+
+/// A snapshot of the prioritized peers
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub(crate) struct PrioritizedPeersSnapshot {
+    /// The time at which the snapshot was taken (in seconds since the Unix epoch)
+    pub timestamp_secs: u64,
+    /// The prioritized peers (ordered from highest to lowest priority)
+    pub peers: Vec<PrioritizedPeerSnapshot>,
+}
+
+impl PrioritizedPeersSnapshot {
+    /// Loads the snapshot at the given path. Returns None if no snapshot exists.
+    pub(crate) fn load(snapshot_path: &Path) -> Result<Option<Self>> {
+        if !snapshot_path.exists() {
+            return Ok(None);
+        }
+
+        let contents = std::fs::read(snapshot_path).with_context(|| {
+            format!(
+                "Failed to read the prioritized peers snapshot {:?}",
+                snapshot_path
+            )
+        })?;
+        let snapshot = serde_json::from_slice(&contents).with_context(|| {
+            format!(
+                "Failed to parse the prioritized peers snapshot {:?}",
+                snapshot_path
+            )
+        })?;
+        Ok(Some(snapshot))
+    }
+
+    /// Atomically writes the snapshot to the given path
+    pub(crate) fn save(&self, snapshot_path: &Path) -> Result<()> {
+        let contents = serde_json::to_vec(self)?;
+        let temp_path = snapshot_path.with_extension("tmp");
+        std::fs::write(&temp_path, contents).with_context(|| {
+            format!(
+                "Failed to write the prioritized peers snapshot {:?}",
+                temp_path
+            )
+        })?;
+        std::fs::rename(&temp_path, snapshot_path).with_context(|| {
+            format!(
+                "Failed to move the prioritized peers snapshot {:?} to {:?}",
+                temp_path, snapshot_path
+            )
+        })
+    }
+
+    /// Returns true iff the snapshot is older than the given maximum age
+    pub(crate) fn is_expired(&self, now_secs: u64, max_age_secs: u64) -> bool {
+        now_secs.saturating_sub(self.timestamp_secs) > max_age_secs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PrioritizedPeerSnapshot, PrioritizedPeersSnapshot};
+    use aptos_config::network_id::{NetworkId, PeerNetworkId};
+    use aptos_temppath::TempPath;
+    use aptos_types::PeerId;
+
+    #[test]
+    fn test_save_and_load_snapshot() {
+        // Verify that loading a missing snapshot returns None
+        let snapshot_path = TempPath::new();
+        assert_eq!(
+            PrioritizedPeersSnapshot::load(snapshot_path.path()).unwrap(),
+            None
+        );
+
+        // Save a snapshot and verify it can be loaded
+        let snapshot = PrioritizedPeersSnapshot {
+            timestamp_secs: 1_000,
+            peers: vec![
+                PrioritizedPeerSnapshot {
+                    peer: PeerNetworkId::new(NetworkId::Vfn, PeerId::random()),
+                    average_ping_latency_secs: Some(0.05),
+                    broadcast_ack_latency_secs: Some(0.2),
+                },
+                PrioritizedPeerSnapshot {
+                    peer: PeerNetworkId::new(NetworkId::Public, PeerId::random()),
+                    average_ping_latency_secs: None,
+                    broadcast_ack_latency_secs: None,
+                },
+            ],
+        };
+        snapshot.save(snapshot_path.path()).unwrap();
+        assert_eq!(
+            PrioritizedPeersSnapshot::load(snapshot_path.path()).unwrap(),
+            Some(snapshot.clone())
+        );
+
+        // Verify the snapshot expiration
+        assert!(!snapshot.is_expired(1_500, 600));
+        assert!(snapshot.is_expired(1_601, 600));
+
+        // Verify that a corrupt snapshot fails to load
+        std::fs::write(snapshot_path.path(), b"corrupt").unwrap();
+        assert!(PrioritizedPeersSnapshot::load(snapshot_path.path()).is_err());
+    }
+}
@@ -3,23 +3,31 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::CoreMempool,
+    core_mempool::ShardedMempool,
     network::MempoolSyncMsg,
     shared_mempool::{
-        coordinator::{coordinator, gc_coordinator, snapshot_job},
+        client_quotas::ClientQuotaManager,
+        coordinator::{
+            coordinator, gc_coordinator, mempool_load_job, mempool_utilization_job, snapshot_job,
+            transaction_filter_job,
+        },
+        load_state::MempoolLoadMonitor,
+        priority::PrioritizedPeerInfo,
+        tasks::process_journaled_transactions,
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     QuorumStoreRequest,
 };
 use aptos_config::config::{NodeConfig, NodeType};
 use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
-use aptos_infallible::{Mutex, RwLock};
+use aptos_infallible::RwLock;
 use aptos_logger::Level;
 use aptos_mempool_notifications::MempoolNotificationListener;
 use aptos_network::application::{
     interface::{NetworkClient, NetworkServiceEvents},
     storage::PeersAndMetadata,
 };
+use aptos_peer_monitoring_service_types::MempoolUtilization;
 use aptos_storage_interface::DbReader;
 use aptos_types::on_chain_config::OnChainConfigProvider;
 use aptos_vm_validator::vm_validator::{PooledVMValidator, TransactionValidation};
@@ -32,10 +40,16 @@ use tokio::runtime::{Handle, Runtime};
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers).
 ///   - inbound_network_task (task that handles inbound mempool messages and network events).
 ///   - gc_task (task that performs GC of all expired transactions by SystemTTL).
+///   - mempool_utilization_task (task that reports the mempool utilization to peers).
+///   - mempool_load_task (task that publishes the mempool load state for submission backpressure).
+///   - journal_replay_task (task that re-validates transactions reloaded from the journal).
+///   - transaction_filter_task (task that periodically sends transaction filters to peers).
+/// The list of prioritized peers is published to `prioritized_peers_info` (for debugging).
+/// Returns the manager of the per-client submission quotas.
 pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
     executor: &Handle,
     config: &NodeConfig,
-    mempool: Arc<Mutex<CoreMempool>>,
+    mempool: Arc<ShardedMempool>,
     network_client: NetworkClient<MempoolSyncMsg>,
     network_service_events: NetworkServiceEvents<MempoolSyncMsg>,
     client_events: MempoolEventsReceiver,
@@ -46,12 +60,16 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
     validator: Arc<RwLock<TransactionValidator>>,
     subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
     peers_and_metadata: Arc<PeersAndMetadata>,
-) where
+    mempool_utilization: MempoolUtilization,
+    mempool_load_monitor: MempoolLoadMonitor,
+    prioritized_peers_info: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+) -> Arc<ClientQuotaManager>
+where
     TransactionValidator: TransactionValidation + 'static,
     ConfigProvider: OnChainConfigProvider,
 {
     let node_type = NodeType::extract_from_config(config);
-    let smp: SharedMempool<NetworkClient<MempoolSyncMsg>, TransactionValidator> =
+    let mut smp: SharedMempool<NetworkClient<MempoolSyncMsg>, TransactionValidator> =
         SharedMempool::new(
             mempool.clone(),
             config.mempool.clone(),
@@ -61,6 +79,25 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
             subscribers,
             node_type,
         );
+    let client_quota_manager = smp.client_quota_manager.clone();
+    smp.network_interface
+        .set_prioritized_peers_info(prioritized_peers_info);
+
+    if let Some(broadcast_filter_config) = &config.mempool.broadcast_filter {
+        executor.spawn(transaction_filter_job(
+            smp.network_interface.clone(),
+            mempool.clone(),
+            broadcast_filter_config.filter_update_interval_ms,
+        ));
+    }
+
+    let journaled_transactions = mempool.take_journaled_transactions();
+    if !journaled_transactions.is_empty() {
+        executor.spawn(process_journaled_transactions(
+            smp.clone(),
+            journaled_transactions,
+        ));
+    }
 
     executor.spawn(coordinator(
         smp,
@@ -79,12 +116,29 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
         config.mempool.system_transaction_gc_interval_ms,
     ));
 
+    executor.spawn(mempool_utilization_job(
+        mempool.clone(),
+        mempool_utilization,
+        config.mempool.capacity,
+        config.mempool.shared_mempool_peer_update_interval_ms,
+    ));
+
+    if let Some(submission_backpressure_config) = &config.mempool.submission_backpressure {
+        executor.spawn(mempool_load_job(
+            mempool.clone(),
+            mempool_load_monitor,
+            submission_backpressure_config.clone(),
+        ));
+    }
+
     if aptos_logger::enabled!(Level::Trace) {
         executor.spawn(snapshot_job(
             mempool,
             config.mempool.mempool_snapshot_interval_secs,
         ));
     }
+
+    client_quota_manager
 }
 
 pub fn bootstrap(
@@ -97,14 +151,17 @@ pub fn bootstrap(
     mempool_listener: MempoolNotificationListener,
     mempool_reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
     peers_and_metadata: Arc<PeersAndMetadata>,
-) -> Runtime {
+    mempool_utilization: MempoolUtilization,
+    mempool_load_monitor: MempoolLoadMonitor,
+    prioritized_peers_info: Arc<RwLock<Vec<PrioritizedPeerInfo>>>,
+) -> (Runtime, Arc<ClientQuotaManager>) {
     let runtime = aptos_runtimes::spawn_named_runtime("shared-mem".into(), None);
-    let mempool = Arc::new(Mutex::new(CoreMempool::new(config)));
+    let mempool = Arc::new(ShardedMempool::new(config));
     let vm_validator = Arc::new(RwLock::new(PooledVMValidator::new(
         Arc::clone(&db),
         num_cpus::get(),
     )));
-    start_shared_mempool(
+    let client_quota_manager = start_shared_mempool(
         runtime.handle(),
         config,
         mempool,
@@ -118,6 +175,9 @@ pub fn bootstrap(
         vm_validator,
         vec![],
         peers_and_metadata,
+        mempool_utilization,
+        mempool_load_monitor,
+        prioritized_peers_info,
     );
-    runtime
+    (runtime, client_quota_manager)
 }
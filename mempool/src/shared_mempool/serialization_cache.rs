@@ -0,0 +1,194 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A bounded-memory cache of the serialized bytes of recently broadcast batches, so
+//! that retries and broadcasts of the same batch to multiple peers reuse the same
+//! serialization (instead of re-encoding the batch for each peer).
+//!
+//! The contents of a broadcast batch may differ across peers for the same message id
+//! (e.g., due to transaction filters or sender bucket priorities), so batches are keyed
+//! by both the message id and a digest of their contents. Note: mempool only broadcasts
+//! over a single direct send protocol, so the serialization doesn't depend on the peer.
+
+use crate::{
+    counters,
+    shared_mempool::{
+        network::{BroadcastPeerPriority, BroadcastRequestType},
+        types::MempoolMessageId,
+    },
+};
+use aptos_crypto::HashValue;
+use aptos_types::transaction::SignedTransaction;
+use bytes::Bytes;
+use std::collections::{HashMap, VecDeque};
+
+/// The key of a serialized broadcast batch
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BroadcastBatchKey {
+    message_id: MempoolMessageId,
+    contents_digest: HashValue,
+}
+
+impl BroadcastBatchKey {
+    /// Creates the key of the given broadcast batch. The digest covers everything
+    /// in the broadcast message (i.e., the request type, transactions, ready times
+    /// and priorities).
+    pub fn new(
+        message_id: &MempoolMessageId,
+        transactions: &[(SignedTransaction, u64, BroadcastPeerPriority)],
+        request_type: BroadcastRequestType,
+    ) -> Self {
+        let mut contents = Vec::with_capacity(1 + transactions.len() * (HashValue::LENGTH + 9));
+        contents.push(match request_type {
+            BroadcastRequestType::Plain => 0,
+            BroadcastRequestType::WithReadyTime => 1,
+            BroadcastRequestType::WithReceipt => 2,
+        });
+        for (transaction, ready_time, priority) in transactions {
+            contents.extend_from_slice(transaction.committed_hash().as_ref());
+            contents.extend_from_slice(&ready_time.to_le_bytes());
+            contents.push(match priority {
+                BroadcastPeerPriority::Primary => 0,
+                BroadcastPeerPriority::Failover => 1,
+            });
+        }
+
+        Self {
+            message_id: message_id.clone(),
+            contents_digest: HashValue::sha3_256_of(&contents),
+        }
+    }
+}
+
+/// A cache of serialized broadcast batches, with a bounded memory budget. When the
+/// budget is exceeded, the oldest batches are evicted first.
+#[derive(Debug)]
+pub struct BroadcastSerializationCache {
+    max_bytes: usize,
+    num_bytes: usize,
+    serialized_batches: HashMap<BroadcastBatchKey, Bytes>,
+    insertion_order: VecDeque<BroadcastBatchKey>,
+}
+
+impl BroadcastSerializationCache {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            num_bytes: 0,
+            serialized_batches: HashMap::new(),
+            insertion_order: VecDeque::new(),
+        }
+    }
+
+    /// Returns the serialized bytes of the given batch (if cached)
+    pub fn get(&self, batch_key: &BroadcastBatchKey) -> Option<Bytes> {
+        let serialized_batch = self.serialized_batches.get(batch_key).cloned();
+        let label = if serialized_batch.is_some() {
+            counters::SERIALIZATION_CACHE_HIT_LABEL
+        } else {
+            counters::SERIALIZATION_CACHE_MISS_LABEL
+        };
+        counters::shared_mempool_serialization_cache_inc(label);
+        serialized_batch
+    }
+
+    /// Caches the serialized bytes of the given batch, evicting the oldest
+    /// batches if required. Batches larger than the budget are not cached.
+    pub fn insert(&mut self, batch_key: BroadcastBatchKey, serialized_batch: Bytes) {
+        let num_batch_bytes = serialized_batch.len();
+        if num_batch_bytes > self.max_bytes || self.serialized_batches.contains_key(&batch_key) {
+            return;
+        }
+
+        // Evict the oldest batches until the new batch fits
+        while self.num_bytes + num_batch_bytes > self.max_bytes {
+            let Some(oldest_batch_key) = self.insertion_order.pop_front() else {
+                break;
+            };
+            if let Some(evicted_batch) = self.serialized_batches.remove(&oldest_batch_key) {
+                self.num_bytes -= evicted_batch.len();
+                counters::shared_mempool_serialization_cache_inc(
+                    counters::SERIALIZATION_CACHE_EVICTED_LABEL,
+                );
+            }
+        }
+
+        // Insert the new batch
+        self.num_bytes += num_batch_bytes;
+        self.insertion_order.push_back(batch_key.clone());
+        self.serialized_batches.insert(batch_key, serialized_batch);
+        counters::SHARED_MEMPOOL_SERIALIZATION_CACHE_BYTES.set(self.num_bytes as i64);
+    }
+
+    /// Returns the number of bytes held by the cache
+    pub fn num_bytes(&self) -> usize {
+        self.num_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BroadcastBatchKey, BroadcastSerializationCache};
+    use crate::shared_mempool::{network::BroadcastRequestType, types::MempoolMessageId};
+    use bytes::Bytes;
+
+    #[test]
+    fn test_batch_key() {
+        // Verify that the same batch produces the same key
+        let message_id = MempoolMessageId(vec![(0, 10)]);
+        let batch_key = BroadcastBatchKey::new(&message_id, &[], BroadcastRequestType::Plain);
+        assert_eq!(
+            batch_key,
+            BroadcastBatchKey::new(&message_id, &[], BroadcastRequestType::Plain)
+        );
+
+        // Verify that the key depends on the message id and contents
+        let other_message_id = MempoolMessageId(vec![(0, 20)]);
+        assert_ne!(
+            batch_key,
+            BroadcastBatchKey::new(&other_message_id, &[], BroadcastRequestType::Plain)
+        );
+
+        // Verify that the key depends on the request type
+        assert_ne!(
+            batch_key,
+            BroadcastBatchKey::new(&message_id, &[], BroadcastRequestType::WithReadyTime)
+        );
+        assert_ne!(
+            BroadcastBatchKey::new(&message_id, &[], BroadcastRequestType::WithReadyTime),
+            BroadcastBatchKey::new(&message_id, &[], BroadcastRequestType::WithReceipt)
+        );
+    }
+
+    #[test]
+    fn test_bounded_memory() {
+        // Create a cache with a budget of 100 bytes
+        let mut serialization_cache = BroadcastSerializationCache::new(100);
+
+        // Insert several batches and verify they're all cached
+        let batch_keys: Vec<_> = (0..4).map(create_batch_key).collect();
+        for batch_key in &batch_keys[0..2] {
+            serialization_cache.insert(batch_key.clone(), Bytes::from(vec![0; 40]));
+        }
+        assert_eq!(serialization_cache.num_bytes(), 80);
+        assert!(serialization_cache.get(&batch_keys[0]).is_some());
+        assert!(serialization_cache.get(&batch_keys[1]).is_some());
+
+        // Insert another batch and verify the oldest batch is evicted
+        serialization_cache.insert(batch_keys[2].clone(), Bytes::from(vec![0; 40]));
+        assert_eq!(serialization_cache.num_bytes(), 80);
+        assert!(serialization_cache.get(&batch_keys[0]).is_none());
+        assert!(serialization_cache.get(&batch_keys[2]).is_some());
+
+        // Verify that batches exceeding the budget are never cached
+        serialization_cache.insert(batch_keys[3].clone(), Bytes::from(vec![0; 101]));
+        assert_eq!(serialization_cache.num_bytes(), 80);
+        assert!(serialization_cache.get(&batch_keys[3]).is_none());
+    }
+
+    /// Creates an (empty) batch key for the given message index
+    fn create_batch_key(index: u64) -> BroadcastBatchKey {
+        let message_id = MempoolMessageId(vec![(index, index + 1)]);
+        BroadcastBatchKey::new(&message_id, &[], BroadcastRequestType::Plain)
+    }
+}
@@ -5,14 +5,15 @@
 //! Tasks that are executed by coordinators (short-lived compared to coordinators)
 use super::types::MempoolMessageId;
 use crate::{
-    core_mempool::{CoreMempool, TimelineState},
+    core_mempool::{ShardedMempool, TimelineState, IDEMPOTENT_TRANSACTION_MESSAGE},
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
-    network::{BroadcastError, BroadcastPeerPriority, MempoolSyncMsg},
+    network::{BroadcastDeliveryReceipt, BroadcastError, BroadcastPeerPriority, MempoolSyncMsg},
     shared_mempool::{
         types::{
-            notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification,
-            SubmissionStatusBundle,
+            notify_subscribers, MempoolTransactionStatus, PendingTransactionsPage,
+            PendingTransactionsRequest, ScheduledBroadcast, SharedMempool,
+            SharedMempoolNotification, SubmissionStatusBundle,
         },
         use_case_history::UseCaseHistory,
     },
@@ -21,13 +22,15 @@ use crate::{
 };
 use anyhow::Result;
 use aptos_config::network_id::PeerNetworkId;
-use aptos_consensus_types::common::RejectedTransactionSummary;
+use aptos_consensus_types::common::{RejectedTransactionSummary, TransactionRejectionReason};
 use aptos_crypto::HashValue;
 use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_mempool_notifications::CommittedTransaction;
 use aptos_metrics_core::HistogramTimer;
-use aptos_network::application::interface::NetworkClientInterface;
+use aptos_network::{
+    application::interface::NetworkClientInterface, protocols::network::RpcError, ProtocolId,
+};
 use aptos_storage_interface::state_view::LatestDbStateCheckpointView;
 use aptos_types::{
     mempool_status::{MempoolStatus, MempoolStatusCode},
@@ -36,6 +39,7 @@ use aptos_types::{
     vm_status::{DiscardedVMStatus, StatusCode},
 };
 use aptos_vm_validator::vm_validator::{get_account_sequence_number, TransactionValidation};
+use bytes::Bytes;
 use futures::{channel::oneshot, stream::FuturesUnordered};
 use rayon::prelude::*;
 use std::{
@@ -62,11 +66,12 @@ pub(crate) async fn execute_broadcast<NetworkClient, TransactionValidator>(
 {
     let network_interface = &smp.network_interface.clone();
     // If there's no connection, don't bother to broadcast
-    if network_interface.sync_states_exists(&peer) {
-        if let Err(err) = network_interface
+    let catch_up = if network_interface.sync_states_exists(&peer) {
+        let result = network_interface
             .execute_broadcast(peer, backoff, smp)
-            .await
-        {
+            .await;
+        let catch_up = network_interface.take_catch_up_broadcast(&peer, result.is_ok());
+        if let Err(err) = result {
             match err {
                 BroadcastError::NetworkError(peer, error) => warn!(LogSchema::event_log(
                     LogEntry::BroadcastTransaction,
@@ -88,14 +93,17 @@ pub(crate) async fn execute_broadcast<NetworkClient, TransactionValidator>(
                 },
             }
         }
+        catch_up
     } else {
         // Drop the scheduled broadcast, we're not connected anymore
         return;
-    }
+    };
     let schedule_backoff = network_interface.is_backoff_mode(&peer);
 
     let interval_ms = if schedule_backoff {
         smp.config.shared_mempool_backoff_interval_ms
+    } else if catch_up {
+        0 // Send the next catch-up broadcast immediately
     } else {
         smp.config.shared_mempool_tick_interval_ms
     };
@@ -116,6 +124,7 @@ pub(crate) async fn execute_broadcast<NetworkClient, TransactionValidator>(
 pub(crate) async fn process_client_transaction_submission<NetworkClient, TransactionValidator>(
     smp: SharedMempool<NetworkClient, TransactionValidator>,
     transaction: SignedTransaction,
+    client: Option<String>,
     callback: oneshot::Sender<Result<SubmissionStatus>>,
     timer: HistogramTimer,
 ) where
@@ -124,22 +133,39 @@ pub(crate) async fn process_client_transaction_submission<NetworkClient, Transac
 {
     timer.stop_and_record();
     let _timer = counters::process_txn_submit_latency_timer_client();
-    let ineligible_for_broadcast =
-        smp.network_interface.is_validator() && !smp.broadcast_within_validator_network();
-    let timeline_state = if ineligible_for_broadcast {
-        TimelineState::NonQualified
-    } else {
-        TimelineState::NotReady
-    };
+
+    // Enforce the quotas of the authenticated API client (if any)
+    let committed_hash = transaction.committed_hash();
+    if let Some(client) = &client {
+        if let Err(mempool_status) = smp
+            .client_quota_manager
+            .check_quotas(client, committed_hash)
+        {
+            if callback.send(Ok((mempool_status, None))).is_err() {
+                counters::CLIENT_CALLBACK_FAIL.inc();
+            }
+            return;
+        }
+    }
+
     let statuses: Vec<(SignedTransaction, (MempoolStatus, Option<StatusCode>))> =
         process_incoming_transactions(
             &smp,
             vec![(transaction, None, Some(BroadcastPeerPriority::Primary))],
-            timeline_state,
+            get_local_timeline_state(&smp),
             true,
         );
     log_txn_process_results(&statuses, None);
 
+    // Release the occupancy slot reserved for the transaction (regardless of the outcome)
+    if let Some(client) = &client {
+        let accepted = statuses.first().map_or(false, |status| {
+            status.1 .0.code == MempoolStatusCode::Accepted
+        });
+        smp.client_quota_manager
+            .complete_submission(client, committed_hash, accepted);
+    }
+
     if let Some(status) = statuses.first() {
         if callback.send(Ok(status.1.clone())).is_err() {
             warn!(LogSchema::event_log(
@@ -151,6 +177,61 @@ pub(crate) async fn process_client_transaction_submission<NetworkClient, Transac
     }
 }
 
+/// Re-validates the transactions reloaded from the mempool transaction journal
+/// on startup, and adds those that are still valid back into mempool.
+pub(crate) async fn process_journaled_transactions<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    transactions: Vec<SignedTransaction>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let num_journaled_transactions = transactions.len();
+    let timeline_state = get_local_timeline_state(&smp);
+    let batch_size = cmp::max(smp.config.shared_mempool_batch_size, 1);
+
+    let mut num_accepted_transactions = 0;
+    for batch in transactions.chunks(batch_size) {
+        let batch = batch
+            .iter()
+            .map(|transaction| (transaction.clone(), None, None))
+            .collect();
+        let statuses = process_incoming_transactions(&smp, batch, timeline_state, false);
+        for (_, (mempool_status, _)) in statuses {
+            if mempool_status.code == MempoolStatusCode::Accepted {
+                num_accepted_transactions += 1;
+            }
+            counters::CORE_MEMPOOL_JOURNAL_REPLAYED_TXNS
+                .with_label_values(&[mempool_status.code.to_string().as_str()])
+                .inc();
+        }
+    }
+
+    info!(
+        LogSchema::new(LogEntry::TransactionJournal).num_txns(num_journaled_transactions),
+        "Replayed the mempool transaction journal. Transactions accepted: {}",
+        num_accepted_transactions
+    );
+}
+
+/// Returns the timeline state for transactions submitted to this node (i.e.,
+/// transactions that were not received from a peer).
+fn get_local_timeline_state<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+) -> TimelineState
+where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let ineligible_for_broadcast =
+        smp.network_interface.is_validator() && !smp.broadcast_within_validator_network();
+    if ineligible_for_broadcast {
+        TimelineState::NonQualified
+    } else {
+        TimelineState::NotReady
+    }
+}
+
 /// Processes get transaction by hash request by client.
 pub(crate) async fn process_client_get_transaction<NetworkClient, TransactionValidator>(
     smp: SharedMempool<NetworkClient, TransactionValidator>,
@@ -163,7 +244,7 @@ pub(crate) async fn process_client_get_transaction<NetworkClient, TransactionVal
 {
     timer.stop_and_record();
     let _timer = counters::process_get_txn_latency_timer_client();
-    let txn = smp.mempool.lock().get_by_hash(hash);
+    let txn = smp.mempool.get_by_hash(hash);
 
     if callback.send(txn).is_err() {
         warn!(LogSchema::event_log(
@@ -174,6 +255,52 @@ pub(crate) async fn process_client_get_transaction<NetworkClient, TransactionVal
     }
 }
 
+/// Processes get transaction statuses by hash request by client.
+pub(crate) async fn process_client_get_transaction_statuses<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    hashes: Vec<HashValue>,
+    callback: oneshot::Sender<Vec<MempoolTransactionStatus>>,
+    timer: HistogramTimer,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    timer.stop_and_record();
+    let _timer = counters::process_get_txn_statuses_latency_timer_client();
+    let statuses = smp.mempool.get_statuses_by_hash(&hashes);
+
+    if callback.send(statuses).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::GetTransactionStatuses,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes pending transaction listing request by client.
+pub(crate) async fn process_client_get_pending_transactions<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    request: PendingTransactionsRequest,
+    callback: oneshot::Sender<PendingTransactionsPage>,
+    timer: HistogramTimer,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    timer.stop_and_record();
+    let _timer = counters::process_get_pending_txns_latency_timer_client();
+    let page = smp.mempool.get_pending_transactions(&request);
+
+    if callback.send(page).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::GetPendingTransactions,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes transactions from other nodes.
 pub(crate) async fn process_transaction_broadcast<NetworkClient, TransactionValidator>(
     smp: SharedMempool<NetworkClient, TransactionValidator>,
@@ -186,6 +313,8 @@ pub(crate) async fn process_transaction_broadcast<NetworkClient, TransactionVali
         Option<BroadcastPeerPriority>,
     )>,
     message_id: MempoolMessageId,
+    // If set, the sender requested a delivery receipt in the ack
+    request_receipt: bool,
     timeline_state: TimelineState,
     peer: PeerNetworkId,
     timer: HistogramTimer,
@@ -195,10 +324,14 @@ pub(crate) async fn process_transaction_broadcast<NetworkClient, TransactionVali
 {
     timer.stop_and_record();
     let _timer = counters::process_txn_submit_latency_timer(peer.network_id());
+
     let results = process_incoming_transactions(&smp, transactions, timeline_state, false);
     log_txn_process_results(&results, Some(peer));
+    smp.network_interface
+        .record_received_transactions(&peer, &results);
 
-    let ack_response = gen_ack_response(message_id, results, &peer);
+    let receipt = request_receipt.then(|| gen_delivery_receipt(&results));
+    let ack_response = gen_ack_response(message_id, results, receipt, &peer);
 
     // Respond to the peer with an ack. Note: ack response messages should be
     // small enough that they always fit within the maximum network message
@@ -218,10 +351,142 @@ pub(crate) async fn process_transaction_broadcast<NetworkClient, TransactionVali
     notify_subscribers(SharedMempoolNotification::ACK, &smp.subscribers);
 }
 
+/// Processes a request for transactions (by hash) from another node. The RPC response
+/// holds the requested transactions in mempool, up to the broadcast batch limits.
+pub(crate) async fn process_transaction_request<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    hashes: Vec<HashValue>,
+    peer: PeerNetworkId,
+    protocol_id: ProtocolId,
+    response_sender: oneshot::Sender<Result<Bytes, RpcError>>,
+    timer: HistogramTimer,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    timer.stop_and_record();
+    counters::shared_mempool_transaction_fetch_inc(
+        peer.network_id(),
+        counters::FETCH_REQUEST_RECEIVED_LABEL,
+    );
+
+    // Collect the requested transactions (ignoring any hashes beyond the batch size)
+    let max_batch_bytes = smp.config.shared_mempool_max_batch_bytes;
+    let mut batch_bytes = 0;
+    let mut transactions = vec![];
+    for hash in hashes
+        .into_iter()
+        .take(smp.config.shared_mempool_batch_size.max(1))
+    {
+        if let Some(txn) = smp.mempool.get_by_hash(hash) {
+            batch_bytes += txn.txn_bytes_len() as u64;
+            if batch_bytes > max_batch_bytes {
+                break;
+            }
+            transactions.push(txn);
+        }
+    }
+
+    // Respond to the peer
+    let response = MempoolSyncMsg::RequestTransactionsResponse { transactions };
+    let response_bytes = protocol_id
+        .to_bytes(&response)
+        .map(Bytes::from)
+        .map_err(RpcError::Error);
+    if response_sender.send(response_bytes).is_err() {
+        counters::network_send_fail_inc(counters::TRANSACTION_FETCH);
+        warn!(
+            LogSchema::event_log(LogEntry::TransactionFetch, LogEvent::NetworkSendFail).peer(&peer)
+        );
+        return;
+    }
+    counters::shared_mempool_transaction_fetch_inc(
+        peer.network_id(),
+        counters::FETCH_RESPONSE_SENT_LABEL,
+    );
+}
+
+/// Fetches the given transactions from another node, and processes the fetched
+/// transactions. Unlike broadcasts, fetched transactions are not acked. The callback
+/// is notified with the number of fetched transactions accepted into mempool.
+pub(crate) async fn fetch_transactions<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    hashes: Vec<HashValue>,
+    timeline_state: TimelineState,
+    peer: PeerNetworkId,
+    callback: oneshot::Sender<Result<usize>>,
+    timer: HistogramTimer,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    timer.stop_and_record();
+
+    let result = match smp.network_interface.fetch_transactions(peer, hashes).await {
+        Ok(transactions) => {
+            let _timer = counters::process_txn_submit_latency_timer(peer.network_id());
+            let transactions = transactions
+                .into_iter()
+                .map(|txn| (txn, None, None))
+                .collect();
+            let results = process_incoming_transactions(&smp, transactions, timeline_state, false);
+            log_txn_process_results(&results, Some(peer));
+            smp.network_interface
+                .record_received_transactions(&peer, &results);
+            Ok(results
+                .iter()
+                .filter(|(_, (mempool_status, _))| {
+                    mempool_status.code == MempoolStatusCode::Accepted
+                })
+                .count())
+        },
+        Err(error) => {
+            let error = anyhow::Error::from(error);
+            warn!(
+                LogSchema::event_log(LogEntry::TransactionFetch, LogEvent::NetworkSendFail)
+                    .peer(&peer)
+                    .error(&error)
+            );
+            Err(error)
+        },
+    };
+    if callback.send(result).is_err() {
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Generates the delivery receipt for a broadcast, given the results of processing
+/// the transactions. Transactions that were already in mempool (i.e., idempotent
+/// inserts) or already committed are duplicates, and all other transactions that
+/// were accepted into mempool are new. All remaining transactions (e.g., invalid
+/// transactions) are neither.
+pub(crate) fn gen_delivery_receipt(results: &[SubmissionStatusBundle]) -> BroadcastDeliveryReceipt {
+    let mut receipt = BroadcastDeliveryReceipt::default();
+    for (_, (mempool_status, vm_status)) in results {
+        match (mempool_status.code, vm_status) {
+            (MempoolStatusCode::Accepted, _)
+                if mempool_status.message == IDEMPOTENT_TRANSACTION_MESSAGE =>
+            {
+                receipt.num_duplicate_transactions += 1;
+            },
+            (MempoolStatusCode::Accepted, _) => {
+                receipt.num_new_transactions += 1;
+            },
+            (MempoolStatusCode::VmError, Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD))
+            | (MempoolStatusCode::InvalidSeqNumber, _) => {
+                receipt.num_duplicate_transactions += 1;
+            },
+            _ => {}, // The transaction was rejected
+        }
+    }
+    receipt
+}
+
 /// If `MempoolIsFull` on any of the transactions, provide backpressure to the downstream peer.
 fn gen_ack_response(
     message_id: MempoolMessageId,
     results: Vec<SubmissionStatusBundle>,
+    receipt: Option<BroadcastDeliveryReceipt>,
     peer: &PeerNetworkId,
 ) -> MempoolSyncMsg {
     let mut backoff_and_retry = false;
@@ -238,10 +503,18 @@ fn gen_ack_response(
         backoff_and_retry,
         backoff_and_retry,
     );
-    MempoolSyncMsg::BroadcastTransactionsResponse {
-        message_id,
-        retry: backoff_and_retry,
-        backoff: backoff_and_retry,
+    match receipt {
+        Some(receipt) => MempoolSyncMsg::BroadcastTransactionsResponseWithReceipt {
+            message_id,
+            retry: backoff_and_retry,
+            backoff: backoff_and_retry,
+            receipt,
+        },
+        None => MempoolSyncMsg::BroadcastTransactionsResponse {
+            message_id,
+            retry: backoff_and_retry,
+            backoff: backoff_and_retry,
+        },
     }
 }
 
@@ -379,7 +652,7 @@ fn validate_and_add_transactions<NetworkClient, TransactionValidator>(
         .collect::<Vec<_>>();
     vm_validation_timer.stop_and_record();
     {
-        let mut mempool = smp.mempool.lock();
+        let mempool = &smp.mempool;
         for (idx, (transaction, sequence_info, ready_time_at_sender, priority)) in
             transactions.into_iter().enumerate()
         {
@@ -448,7 +721,7 @@ fn validate_and_add_transactions<NetworkClient, TransactionValidator>(
 {
     use super::priority;
 
-    let mut mempool = smp.mempool.lock();
+    let mempool = &smp.mempool;
     for (transaction, sequence_info, ready_time_at_sender, priority) in transactions.into_iter() {
         let mempool_status = mempool.add_txn(
             transaction.clone(),
@@ -521,11 +794,12 @@ pub(crate) fn process_quorum_store_request<NetworkClient, TransactionValidator>(
         ) => {
             let txns;
             {
+                // Lock all shards, so that the gc and the consensus pull are atomic
                 let lock_timer = counters::mempool_service_start_latency_timer(
                     counters::GET_BLOCK_LOCK_LABEL,
                     counters::REQUEST_SUCCESS_LABEL,
                 );
-                let mut mempool = smp.mempool.lock();
+                let mut mempool = smp.mempool.lock_all();
                 lock_timer.observe_duration();
 
                 {
@@ -585,12 +859,11 @@ pub(crate) fn process_quorum_store_request<NetworkClient, TransactionValidator>(
 
 /// Remove transactions that are committed (or rejected) so that we can stop broadcasting them.
 pub(crate) fn process_committed_transactions(
-    mempool: &Mutex<CoreMempool>,
+    pool: &ShardedMempool,
     use_case_history: &Mutex<UseCaseHistory>,
     transactions: Vec<CommittedTransaction>,
     block_timestamp_usecs: u64,
 ) {
-    let mut pool = mempool.lock();
     let block_timestamp = Duration::from_micros(block_timestamp_usecs);
 
     let tracking_usecases = {
@@ -599,6 +872,7 @@ pub(crate) fn process_committed_transactions(
         history.compute_tracking_set()
     };
 
+    let mut committed_transactions = Vec::with_capacity(transactions.len());
     for transaction in transactions {
         pool.log_commit_transaction(
             &transaction.sender,
@@ -608,27 +882,35 @@ pub(crate) fn process_committed_transactions(
                 .map(|name| (transaction.use_case.clone(), name)),
             block_timestamp,
         );
-        pool.commit_transaction(&transaction.sender, transaction.sequence_number);
+        committed_transactions.push((transaction.sender, transaction.sequence_number));
     }
 
+    // Commit the transactions, and promote the parked transactions of the
+    // senders touched by the block (that are now ready)
+    let num_promoted_txns = pool.commit_transactions(&committed_transactions);
+    counters::CORE_MEMPOOL_PARKED_TXNS_PROMOTED_PER_BLOCK.observe(num_promoted_txns as f64);
+
     if block_timestamp_usecs > 0 {
         pool.gc_by_expiration_time(block_timestamp);
     }
 }
 
 pub(crate) fn process_rejected_transactions(
-    mempool: &Mutex<CoreMempool>,
+    pool: &ShardedMempool,
     transactions: Vec<RejectedTransactionSummary>,
 ) {
-    let mut pool = mempool.lock();
-
     for transaction in transactions {
-        pool.reject_transaction(
-            &transaction.sender,
-            transaction.sequence_number,
-            &transaction.hash,
-            &transaction.reason,
-        );
+        match transaction.reason {
+            TransactionRejectionReason::Discarded(reason) => pool.reject_transaction(
+                &transaction.sender,
+                transaction.sequence_number,
+                &transaction.hash,
+                &reason,
+            ),
+            TransactionRejectionReason::BlockCut => {
+                pool.retain_block_cut_transaction(&transaction.sender, transaction.sequence_number)
+            },
+        }
     }
 }
 
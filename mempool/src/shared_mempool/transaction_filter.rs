@@ -0,0 +1,138 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A compact (bloom) filter of transaction hashes, exchanged between peers so that
+//! shared mempool can skip broadcasting transactions the remote peer already holds.
+//!
+//! Each filter is built with a fresh random seed. This ensures that a false positive
+//! (i.e., a transaction that is wrongly reported as held by the peer) doesn't persist
+//! across filter updates: transactions skipped because of one filter are re-checked
+//! against the next filter, and are broadcast if they no longer match it.
+
+use aptos_crypto::HashValue;
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of hash functions accepted in a filter
+const MAX_NUM_HASH_FUNCTIONS: u32 = 32;
+
+/// The maximum size (in 64-bit words) of a filter accepted from a peer (i.e., 1 MiB)
+const MAX_FILTER_WORDS: usize = 128 * 1024;
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TransactionHashFilter {
+    seed: u64,               // The seed mixed into every hash (unique per filter)
+    num_hash_functions: u32, // The number of bits set per transaction
+    bits: Vec<u64>,          // The filter bit array
+}
+
+impl TransactionHashFilter {
+    /// Creates a filter holding the given transaction hashes, with the given number
+    /// of filter bits per transaction (which determines the false positive rate).
+    pub fn new(hashes: &[HashValue], bits_per_transaction: u64) -> Self {
+        let num_bits = (hashes.len() as u64 * bits_per_transaction).max(64);
+        let num_words = num_bits.div_ceil(64).min(MAX_FILTER_WORDS as u64) as usize;
+
+        // The optimal number of hash functions is (bits per transaction * ln(2))
+        let num_hash_functions = ((bits_per_transaction as f64 * std::f64::consts::LN_2).round()
+            as u32)
+            .clamp(1, MAX_NUM_HASH_FUNCTIONS);
+
+        let mut filter = Self {
+            seed: rand::random(),
+            num_hash_functions,
+            bits: vec![0; num_words],
+        };
+        for hash in hashes {
+            filter.insert(hash);
+        }
+        filter
+    }
+
+    /// Adds the given transaction hash to the filter
+    fn insert(&mut self, hash: &HashValue) {
+        for bit in self.bit_indices(hash) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Returns true iff the given transaction hash may be in the filter.
+    /// False positives are possible, but false negatives are not.
+    pub fn contains(&self, hash: &HashValue) -> bool {
+        self.bit_indices(hash)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Returns true iff the filter is well formed (i.e., it can be safely queried)
+    pub fn is_valid(&self) -> bool {
+        !self.bits.is_empty()
+            && self.bits.len() <= MAX_FILTER_WORDS
+            && (1..=MAX_NUM_HASH_FUNCTIONS).contains(&self.num_hash_functions)
+    }
+
+    /// Returns the indices of the filter bits for the given hash (using double hashing)
+    fn bit_indices(&self, hash: &HashValue) -> impl Iterator<Item = usize> {
+        let hash_bytes = hash.as_ref();
+        let first_hash = mix(read_u64(&hash_bytes[0..8]) ^ self.seed);
+        let second_hash = mix(read_u64(&hash_bytes[8..16]) ^ self.seed) | 1;
+        let num_bits = self.bits.len() as u64 * 64;
+        (0..self.num_hash_functions as u64).map(move |i| {
+            (first_hash.wrapping_add(i.wrapping_mul(second_hash)) % num_bits) as usize
+        })
+    }
+}
+
+/// Reads a little-endian u64 from the given 8 bytes
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().expect("Expected exactly 8 bytes!"))
+}
+
+/// Mixes the bits of the given value (i.e., the splitmix64 finalizer)
+fn mix(mut value: u64) -> u64 {
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+    value ^ (value >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TransactionHashFilter, MAX_FILTER_WORDS};
+    use aptos_crypto::HashValue;
+
+    #[test]
+    fn test_filter_contains_inserted_hashes() {
+        // Create a filter holding a set of hashes
+        let hashes: Vec<_> = (0..1_000).map(|_| HashValue::random()).collect();
+        let filter = TransactionHashFilter::new(&hashes, 10);
+        assert!(filter.is_valid());
+
+        // Verify there are no false negatives
+        for hash in &hashes {
+            assert!(filter.contains(hash));
+        }
+
+        // Verify the false positive rate is low (it should be ~1% for 10 bits per transaction)
+        let num_false_positives = (0..10_000)
+            .filter(|_| filter.contains(&HashValue::random()))
+            .count();
+        assert!(num_false_positives < 500);
+    }
+
+    #[test]
+    fn test_filter_validity() {
+        // Verify an empty filter is valid (and holds nothing)
+        let filter = TransactionHashFilter::new(&[], 10);
+        assert!(filter.is_valid());
+        assert!(!filter.contains(&HashValue::random()));
+
+        // Verify malformed filters are rejected
+        let mut filter = TransactionHashFilter::new(&[HashValue::random()], 10);
+        filter.num_hash_functions = 0;
+        assert!(!filter.is_valid());
+        let mut filter = TransactionHashFilter::new(&[HashValue::random()], 10);
+        filter.bits = vec![];
+        assert!(!filter.is_valid());
+        let mut filter = TransactionHashFilter::new(&[HashValue::random()], 10);
+        filter.bits = vec![0; MAX_FILTER_WORDS + 1];
+        assert!(!filter.is_valid());
+    }
+}
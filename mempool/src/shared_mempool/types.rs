@@ -4,13 +4,19 @@
 
 //! Objects used by/related to shared mempool
 use crate::{
-    core_mempool::CoreMempool,
-    network::{MempoolNetworkInterface, MempoolSyncMsg},
-    shared_mempool::use_case_history::UseCaseHistory,
+    core_mempool::ShardedMempool,
+    network::{BroadcastPeerPriority, MempoolNetworkInterface, MempoolSyncMsg},
+    shared_mempool::{
+        client_quotas::ClientQuotaManager, transaction_filter::TransactionHashFilter,
+        use_case_history::UseCaseHistory,
+    },
 };
 use anyhow::Result;
 use aptos_config::{
-    config::{MempoolConfig, NodeType},
+    config::{
+        AdaptiveBatchSizingConfig, EvictionPolicyConfig, MempoolConfig, NodeType,
+        PeerPrioritizationParams,
+    },
     network_id::PeerNetworkId,
 };
 use aptos_consensus_types::common::{
@@ -20,8 +26,12 @@ use aptos_crypto::HashValue;
 use aptos_infallible::{Mutex, RwLock};
 use aptos_network::application::interface::NetworkClientInterface;
 use aptos_storage_interface::DbReader;
+use aptos_time_service::TimeService;
 use aptos_types::{
-    mempool_status::MempoolStatus, transaction::SignedTransaction, vm_status::DiscardedVMStatus,
+    account_address::AccountAddress,
+    mempool_status::MempoolStatus,
+    transaction::{SignedTransaction, TransactionPayload},
+    vm_status::DiscardedVMStatus,
 };
 use aptos_vm_validator::vm_validator::TransactionValidation;
 use futures::{
@@ -35,19 +45,23 @@ use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
     fmt,
     pin::Pin,
+    str::FromStr,
     sync::Arc,
     task::Waker,
-    time::{Instant, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 use tokio::runtime::Handle;
 
 pub type MempoolSenderBucket = u8;
 pub type TimelineIndexIdentifier = u8;
 
+/// The weight of each new broadcast ACK latency in the moving average of ACK latencies
+const ACK_LATENCY_AVERAGE_WEIGHT: f64 = 0.2;
+
 /// Struct that owns all dependencies required by shared mempool routines.
 #[derive(Clone)]
 pub(crate) struct SharedMempool<NetworkClient, TransactionValidator> {
-    pub mempool: Arc<Mutex<CoreMempool>>,
+    pub mempool: Arc<ShardedMempool>,
     pub config: MempoolConfig,
     pub network_interface: MempoolNetworkInterface<NetworkClient>,
     pub db: Arc<dyn DbReader>,
@@ -55,6 +69,7 @@ pub(crate) struct SharedMempool<NetworkClient, TransactionValidator> {
     pub subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
     pub broadcast_within_validator_network: Arc<RwLock<bool>>,
     pub use_case_history: Arc<Mutex<UseCaseHistory>>,
+    pub client_quota_manager: Arc<ClientQuotaManager>,
 }
 
 impl<
@@ -63,7 +78,7 @@ impl<
     > SharedMempool<NetworkClient, TransactionValidator>
 {
     pub fn new(
-        mempool: Arc<Mutex<CoreMempool>>,
+        mempool: Arc<ShardedMempool>,
         config: MempoolConfig,
         network_client: NetworkClient,
         db: Arc<dyn DbReader>,
@@ -77,6 +92,11 @@ impl<
             config.usecase_stats_num_blocks_to_track,
             config.usecase_stats_num_top_to_track,
         );
+        let client_quota_manager = ClientQuotaManager::new(
+            config.client_quotas.clone(),
+            mempool.clone(),
+            TimeService::real(),
+        );
         SharedMempool {
             mempool,
             config,
@@ -86,6 +106,7 @@ impl<
             subscribers,
             broadcast_within_validator_network: Arc::new(RwLock::new(true)),
             use_case_history: Arc::new(Mutex::new(use_case_history)),
+            client_quota_manager: Arc::new(client_quota_manager),
         }
     }
 
@@ -236,7 +257,149 @@ pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 
 pub enum MempoolClientRequest {
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
+    /// Submits a transaction on behalf of the authenticated API client with the
+    /// given identity. The submission is subject to the quotas of the client.
+    SubmitTransactionFromClient(
+        SignedTransaction,
+        String,
+        oneshot::Sender<Result<SubmissionStatus>>,
+    ),
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
+    /// Returns the mempool status of each of the given transaction
+    /// hashes (in the same order as the hashes were requested).
+    GetTransactionStatusesByHash(
+        Vec<HashValue>,
+        oneshot::Sender<Vec<MempoolTransactionStatus>>,
+    ),
+    /// Returns a page of the transactions in mempool (in insertion order)
+    GetPendingTransactions(
+        PendingTransactionsRequest,
+        oneshot::Sender<PendingTransactionsPage>,
+    ),
+    /// Returns the current eviction policy (i.e., the system TTL overrides)
+    GetEvictionPolicy(oneshot::Sender<EvictionPolicyConfig>),
+    /// Replaces the eviction policy. The new policy only applies to transactions
+    /// inserted from now on.
+    SetEvictionPolicy(EvictionPolicyConfig, oneshot::Sender<Result<()>>),
+    /// Returns the current peer prioritization parameters
+    GetPrioritizationParams(oneshot::Sender<PeerPrioritizationParams>),
+    /// Replaces the peer prioritization parameters (e.g., the priority update interval
+    /// and broadcast fanouts). These are applied on the next peer priority update.
+    SetPrioritizationParams(PeerPrioritizationParams, oneshot::Sender<Result<()>>),
+    /// Fetches the given transactions (by hash) from the given peer (via RPC), e.g.,
+    /// when transactions referenced by a batch digest are missing. Transactions already
+    /// in mempool are not requested. Once the peer responds, the fetched transactions
+    /// are inserted into mempool, and the number of accepted transactions is returned.
+    FetchTransactions(
+        PeerNetworkId,
+        Vec<HashValue>,
+        oneshot::Sender<Result<usize>>,
+    ),
+}
+
+/// The status of a single transaction in mempool, as reported to clients
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MempoolTransactionStatus {
+    /// The transaction is ready to be pulled into a block
+    Pending(MempoolTransactionInfo),
+    /// The transaction is parked (e.g., waiting for a sequence number gap to be filled)
+    Parked(MempoolTransactionInfo),
+    /// The transaction was recently removed from mempool, because it was rejected
+    /// during block execution (with the given reason)
+    Rejected(DiscardedVMStatus),
+    /// The transaction is not in mempool
+    Unknown,
+}
+
+/// Details about a transaction that is currently held in mempool
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MempoolTransactionInfo {
+    pub ranking_score: u64,
+    pub time_in_mempool: Duration,
+}
+
+/// The position of a transaction in the mempool insertion order. This is used
+/// as a cursor when paging through the transactions in mempool.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct PendingTransactionsCursor {
+    pub insertion_time_usecs: u64,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+}
+
+impl fmt::Display for PendingTransactionsCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.insertion_time_usecs, self.sender, self.sequence_number
+        )
+    }
+}
+
+impl FromStr for PendingTransactionsCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(cursor: &str) -> Result<Self> {
+        match cursor.split(':').collect::<Vec<_>>().as_slice() {
+            [insertion_time_usecs, sender, sequence_number] => Ok(PendingTransactionsCursor {
+                insertion_time_usecs: insertion_time_usecs.parse()?,
+                sender: AccountAddress::from_str(sender)?,
+                sequence_number: sequence_number.parse()?,
+            }),
+            _ => Err(anyhow::anyhow!(
+                "Invalid cursor: {}. Expected <insertion_time_usecs>:<sender>:<sequence_number>",
+                cursor
+            )),
+        }
+    }
+}
+
+/// The criteria that listed transactions must match
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PendingTransactionsFilter {
+    /// Only list the transactions of this sender (if set)
+    pub sender: Option<AccountAddress>,
+    /// Only list the transactions with at least this gas unit price (if set)
+    pub min_gas_unit_price: Option<u64>,
+    /// Only list the transactions that are parked (if true)
+    pub parked_only: bool,
+}
+
+/// A request for a page of the transactions in mempool (in insertion order)
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PendingTransactionsRequest {
+    /// The position to start listing from (inclusive). If not set, listing
+    /// starts with the oldest transaction in mempool.
+    pub start: Option<PendingTransactionsCursor>,
+    /// The maximum number of transactions to return
+    pub limit: usize,
+    /// The criteria that listed transactions must match
+    pub filter: PendingTransactionsFilter,
+    /// Whether to include the transaction payloads (they are redacted by default)
+    pub include_payloads: bool,
+}
+
+/// A summary of a transaction that is currently held in mempool
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PendingTransaction {
+    pub cursor: PendingTransactionsCursor,
+    pub hash: HashValue,
+    pub gas_unit_price: u64,
+    pub max_gas_amount: u64,
+    pub expiration_timestamp_secs: u64,
+    pub parked: bool,
+    pub payload_size_bytes: usize,
+    /// The transaction payload (only included if requested)
+    pub payload: Option<TransactionPayload>,
+}
+
+/// A page of the transactions in mempool (in insertion order)
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct PendingTransactionsPage {
+    pub transactions: Vec<PendingTransaction>,
+    /// The cursor to start the next page from (if there are more transactions)
+    pub next_start: Option<PendingTransactionsCursor>,
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
@@ -249,8 +412,20 @@ pub type MempoolEventsReceiver = mpsc::Receiver<MempoolClientRequest>;
 pub(crate) struct PeerSyncState {
     pub timelines: HashMap<MempoolSenderBucket, MultiBucketTimelineIndexIds>,
     pub broadcast_info: BroadcastInfo,
+    /// The latest transaction filter received from the peer (and the time it was received)
+    pub transaction_filter: Option<(TransactionHashFilter, Instant)>,
+    /// The transactions skipped by fresh broadcasts because they matched the peer's filter.
+    /// These are not considered delivered: they are re-checked against the peer's next filter.
+    pub filtered_transactions: Vec<FilteredTransaction>,
+    /// The filtered transactions that didn't match the peer's next filter (i.e., that were
+    /// false positives), and must still be broadcast to the peer.
+    pub unfiltered_transactions: Vec<FilteredTransaction>,
 }
 
+/// A transaction skipped by a broadcast because it matched the peer's transaction
+/// filter, i.e., (committed hash, ready time, broadcast priority).
+pub(crate) type FilteredTransaction = (HashValue, u64, BroadcastPeerPriority);
+
 impl PeerSyncState {
     pub fn new(num_broadcast_buckets: usize, num_sender_buckets: MempoolSenderBucket) -> Self {
         let mut timelines = HashMap::new();
@@ -263,6 +438,9 @@ impl PeerSyncState {
         PeerSyncState {
             timelines,
             broadcast_info: BroadcastInfo::new(),
+            transaction_filter: None,
+            filtered_transactions: vec![],
+            unfiltered_transactions: vec![],
         }
     }
 
@@ -417,8 +595,9 @@ impl Ord for MempoolMessageId {
 #[cfg(test)]
 mod test {
     use crate::shared_mempool::types::{
-        MempoolMessageId, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+        BroadcastInfo, MempoolMessageId, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
     };
+    use aptos_config::config::AdaptiveBatchSizingConfig;
     use std::collections::HashMap;
 
     #[test]
@@ -457,6 +636,65 @@ mod test {
         let right = MempoolMessageId(vec![(2 | sender, 5), (1 | sender, 4), (2, 3)]);
         assert!(right > left);
     }
+
+    #[test]
+    fn test_consecutive_broadcast_failures() {
+        let mut broadcast_info = BroadcastInfo::new();
+        let message_id_1 = MempoolMessageId(vec![(0, 1)]);
+        let message_id_2 = MempoolMessageId(vec![(0, 2)]);
+
+        // Verify that retried failures of the same broadcast are only counted once
+        for _ in 0..3 {
+            broadcast_info.record_broadcast_failure(&message_id_1);
+        }
+        assert_eq!(broadcast_info.consecutive_broadcast_failures, 1);
+
+        // Verify that failures of different broadcasts are counted separately
+        broadcast_info.record_broadcast_failure(&message_id_2);
+        assert_eq!(broadcast_info.consecutive_broadcast_failures, 2);
+
+        // Verify that the failures are reset (and counted again afterwards)
+        broadcast_info.reset_broadcast_failures();
+        assert_eq!(broadcast_info.consecutive_broadcast_failures, 0);
+        broadcast_info.record_broadcast_failure(&message_id_1);
+        assert_eq!(broadcast_info.consecutive_broadcast_failures, 1);
+    }
+
+    #[test]
+    fn test_adaptive_batch_size() {
+        let config = AdaptiveBatchSizingConfig {
+            min_batch_size: 10,
+            max_batch_size: 200,
+            additive_increase: 20,
+            multiplicative_decrease_percent: 50,
+            target_ack_latency_ms: 500,
+        };
+
+        // Verify the default batch size is used (within bounds) before any adaptation
+        let mut broadcast_info = BroadcastInfo::new();
+        assert_eq!(broadcast_info.get_batch_size(100, None), 100);
+        assert_eq!(broadcast_info.get_batch_size(100, Some(&config)), 100);
+        assert_eq!(broadcast_info.get_batch_size(300, Some(&config)), 200);
+
+        // Verify the batch size increases additively (up to the maximum)
+        assert_eq!(broadcast_info.adapt_batch_size(&config, 100, true), 120);
+        assert_eq!(broadcast_info.adapt_batch_size(&config, 100, true), 140);
+        for _ in 0..10 {
+            broadcast_info.adapt_batch_size(&config, 100, true);
+        }
+        assert_eq!(broadcast_info.get_batch_size(100, Some(&config)), 200);
+
+        // Verify the batch size decreases multiplicatively (down to the minimum)
+        assert_eq!(broadcast_info.adapt_batch_size(&config, 100, false), 100);
+        assert_eq!(broadcast_info.adapt_batch_size(&config, 100, false), 50);
+        for _ in 0..10 {
+            broadcast_info.adapt_batch_size(&config, 100, false);
+        }
+        assert_eq!(broadcast_info.get_batch_size(100, Some(&config)), 10);
+
+        // Verify the adapted batch size is ignored if adaptive batch sizing is disabled
+        assert_eq!(broadcast_info.get_batch_size(100, None), 100);
+    }
 }
 
 /// Txn broadcast-related info for a given remote peer.
@@ -468,6 +706,18 @@ pub struct BroadcastInfo {
     pub retry_messages: BTreeSet<MempoolMessageId>,
     // Whether broadcasting to this peer is in backoff mode, e.g. broadcasting at longer intervals.
     pub backoff_mode: bool,
+    // The (exponentially weighted) moving average of the broadcast ACK latencies (in seconds).
+    pub average_ack_latency_secs: Option<f64>,
+    // The number of consecutive broadcasts that failed to send or timed out waiting for an ACK.
+    // Each broadcast (i.e., message ID) is counted once, regardless of how often it is retried.
+    pub consecutive_broadcast_failures: u32,
+    // The broadcasts counted in the consecutive failures (cleared on the next ACK).
+    pub failed_messages: BTreeSet<MempoolMessageId>,
+    // The adapted broadcast batch size (only set if adaptive batch sizing is enabled).
+    pub batch_size: Option<usize>,
+    // The number of broadcasts left to send back-to-back (i.e., without waiting for the
+    // tick interval), e.g., to catch up a newly promoted peer with the previous targets.
+    pub catch_up_broadcasts: usize,
 }
 
 impl BroadcastInfo {
@@ -476,6 +726,73 @@ impl BroadcastInfo {
             sent_messages: BTreeMap::new(),
             retry_messages: BTreeSet::new(),
             backoff_mode: false,
+            average_ack_latency_secs: None,
+            consecutive_broadcast_failures: 0,
+            failed_messages: BTreeSet::new(),
+            batch_size: None,
+            catch_up_broadcasts: 0,
+        }
+    }
+
+    /// Returns the broadcast batch size for this peer. If adaptive batch sizing is enabled,
+    /// this is the adapted size (which starts at the default size, within the bounds).
+    pub fn get_batch_size(
+        &self,
+        default_batch_size: usize,
+        adaptive_batch_sizing: Option<&AdaptiveBatchSizingConfig>,
+    ) -> usize {
+        match adaptive_batch_sizing {
+            Some(config) => self.batch_size.unwrap_or_else(|| {
+                default_batch_size.clamp(config.min_batch_size, config.max_batch_size)
+            }),
+            None => default_batch_size,
         }
     }
+
+    /// Adapts the broadcast batch size (AIMD-style) and returns the new size. The size
+    /// is increased additively after a fast ACK, and decreased multiplicatively otherwise.
+    pub fn adapt_batch_size(
+        &mut self,
+        config: &AdaptiveBatchSizingConfig,
+        default_batch_size: usize,
+        fast_ack: bool,
+    ) -> usize {
+        let batch_size = self.get_batch_size(default_batch_size, Some(config));
+        let batch_size = if fast_ack {
+            batch_size.saturating_add(config.additive_increase)
+        } else {
+            let retained_percent = 100u64.saturating_sub(config.multiplicative_decrease_percent);
+            (batch_size as u64 * retained_percent / 100) as usize
+        };
+
+        let batch_size = batch_size.clamp(config.min_batch_size, config.max_batch_size);
+        self.batch_size = Some(batch_size);
+        batch_size
+    }
+
+    /// Updates the moving average of the broadcast ACK latencies with the given latency
+    /// Records a failure of the broadcast with the given message ID (e.g., the send
+    /// failed or the ACK timed out). Failures of the same broadcast are only counted once.
+    pub fn record_broadcast_failure(&mut self, message_id: &MempoolMessageId) {
+        if self.failed_messages.insert(message_id.clone()) {
+            self.consecutive_broadcast_failures += 1;
+        }
+    }
+
+    /// Resets the consecutive broadcast failures (e.g., after receiving an ACK)
+    pub fn reset_broadcast_failures(&mut self) {
+        self.consecutive_broadcast_failures = 0;
+        self.failed_messages.clear();
+    }
+
+    pub fn record_ack_latency(&mut self, ack_latency: Duration) {
+        let ack_latency_secs = ack_latency.as_secs_f64();
+        self.average_ack_latency_secs = Some(match self.average_ack_latency_secs {
+            Some(average_ack_latency_secs) => {
+                ACK_LATENCY_AVERAGE_WEIGHT * ack_latency_secs
+                    + (1.0 - ACK_LATENCY_AVERAGE_WEIGHT) * average_ack_latency_secs
+            },
+            None => ack_latency_secs,
+        });
+    }
 }
@@ -0,0 +1,150 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    core_mempool::{ShardedMempool, TimelineState},
+    network::BroadcastPeerPriority,
+    shared_mempool::client_quotas::ClientQuotaManager,
+    tests::common::TestTransaction,
+};
+use aptos_config::config::{ClientQuotaConfig, NodeConfig};
+use aptos_crypto::HashValue;
+use aptos_time_service::TimeService;
+use aptos_types::mempool_status::MempoolStatusCode;
+use std::sync::Arc;
+
+#[test]
+fn test_client_rate_quota() {
+    let (client_quota_manager, _, time_service) = create_client_quota_manager(2, 100);
+
+    // Verify that the client can submit up to its rate quota
+    for _ in 0..2 {
+        client_quota_manager
+            .check_quotas("client_a", HashValue::random())
+            .unwrap();
+    }
+    let mempool_status = client_quota_manager
+        .check_quotas("client_a", HashValue::random())
+        .unwrap_err();
+    assert_eq!(mempool_status.code, MempoolStatusCode::TooManyTransactions);
+
+    // Verify that the quotas of other clients are independent
+    client_quota_manager
+        .check_quotas("client_b", HashValue::random())
+        .unwrap();
+
+    // Elapse half a second and verify that the client can submit again
+    time_service.into_mock().advance_ms(500);
+    client_quota_manager
+        .check_quotas("client_a", HashValue::random())
+        .unwrap();
+    client_quota_manager
+        .check_quotas("client_a", HashValue::random())
+        .unwrap_err();
+
+    // Verify the usage counters
+    let usage = client_quota_manager.get_usage();
+    assert_eq!(usage.len(), 2);
+    let client_a_usage = usage.get("client_a").unwrap();
+    assert_eq!(client_a_usage.num_submitted, 5);
+    assert_eq!(client_a_usage.num_rate_limited, 2);
+    assert_eq!(client_a_usage.num_occupancy_limited, 0);
+    assert_eq!(usage.get("client_b").unwrap().num_submitted, 1);
+}
+
+#[test]
+fn test_client_occupancy_quota() {
+    let (client_quota_manager, mempool, time_service) = create_client_quota_manager(100, 2);
+
+    // Add two transactions from the client to mempool
+    let txns: Vec<_> = (0..2)
+        .map(|sequence_number| {
+            TestTransaction::new(0, sequence_number, 1).make_signed_transaction()
+        })
+        .collect();
+    for txn in &txns {
+        client_quota_manager
+            .check_quotas("client", txn.committed_hash())
+            .unwrap();
+        let mempool_status = mempool.add_txn(
+            txn.clone(),
+            txn.gas_unit_price(),
+            0,
+            TimelineState::NotReady,
+            true,
+            None,
+            Some(BroadcastPeerPriority::Primary),
+        );
+        assert_eq!(mempool_status.code, MempoolStatusCode::Accepted);
+        client_quota_manager.complete_submission("client", txn.committed_hash(), true);
+    }
+
+    // Verify that the client can't submit more transactions (even with an available rate)
+    time_service.into_mock().advance_secs(1);
+    let mempool_status = client_quota_manager
+        .check_quotas("client", HashValue::random())
+        .unwrap_err();
+    assert_eq!(mempool_status.code, MempoolStatusCode::TooManyTransactions);
+
+    // Commit a transaction and verify that the client can submit again
+    mempool.commit_transaction(&txns[0].sender(), 0);
+    client_quota_manager
+        .check_quotas("client", HashValue::random())
+        .unwrap();
+
+    // Verify the usage counters
+    let usage = client_quota_manager.get_usage();
+    let client_usage = usage.get("client").unwrap();
+    assert_eq!(client_usage.num_submitted, 4);
+    assert_eq!(client_usage.num_accepted, 2);
+    assert_eq!(client_usage.num_occupancy_limited, 1);
+    assert_eq!(client_usage.num_pending_transactions, 1);
+}
+
+#[test]
+fn test_client_occupancy_quota_reservations() {
+    let (client_quota_manager, _, _) = create_client_quota_manager(100, 2);
+
+    // Reserve the occupancy quota with two in-flight submissions (that
+    // haven't been added to mempool yet), and verify that a concurrent
+    // submission is rejected.
+    let reserved_hashes = [HashValue::random(), HashValue::random()];
+    for hash in reserved_hashes {
+        client_quota_manager.check_quotas("client", hash).unwrap();
+    }
+    let mempool_status = client_quota_manager
+        .check_quotas("client", HashValue::random())
+        .unwrap_err();
+    assert_eq!(mempool_status.code, MempoolStatusCode::TooManyTransactions);
+
+    // Reject one of the submissions and verify that its slot is released
+    client_quota_manager.complete_submission("client", reserved_hashes[0], false);
+    client_quota_manager
+        .check_quotas("client", HashValue::random())
+        .unwrap();
+
+    // Verify the usage counters
+    let usage = client_quota_manager.get_usage();
+    let client_usage = usage.get("client").unwrap();
+    assert_eq!(client_usage.num_submitted, 4);
+    assert_eq!(client_usage.num_accepted, 0);
+    assert_eq!(client_usage.num_occupancy_limited, 1);
+}
+
+/// Creates a client quota manager (with a mock time service) for an empty mempool
+fn create_client_quota_manager(
+    max_submissions_per_sec: u64,
+    max_pending_transactions: usize,
+) -> (ClientQuotaManager, Arc<ShardedMempool>, TimeService) {
+    let mempool = Arc::new(ShardedMempool::new(&NodeConfig::generate_random_config()));
+    let time_service = TimeService::mock();
+    let client_quota_manager = ClientQuotaManager::new(
+        ClientQuotaConfig {
+            max_submissions_per_sec,
+            max_pending_transactions,
+        },
+        mempool.clone(),
+        time_service.clone(),
+    );
+    (client_quota_manager, mempool, time_service)
+}
@@ -3,14 +3,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{sender_bucket, CoreMempool, MempoolTransaction, SubmittedBy, TimelineState},
+    core_mempool::{
+        sender_bucket, CoreMempool, MempoolTransaction, SubmittedBy, TimelineState,
+        IDEMPOTENT_TRANSACTION_MESSAGE,
+    },
     network::BroadcastPeerPriority,
+    shared_mempool::types::MempoolTransactionStatus,
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, setup_mempool,
         setup_mempool_with_broadcast_buckets, txn_bytes_len, TestTransaction,
     },
 };
-use aptos_config::config::{MempoolConfig, NodeConfig};
+use aptos_config::config::{BroadcastLanesConfig, EvictionPolicyConfig, MempoolConfig, NodeConfig};
 use aptos_consensus_types::common::{TransactionInProgress, TransactionSummary};
 use aptos_crypto::HashValue;
 use aptos_types::{
@@ -18,7 +22,10 @@ use aptos_types::{
 };
 use itertools::Itertools;
 use maplit::btreemap;
-use std::time::{Duration, Instant, SystemTime};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant, SystemTime},
+};
 
 #[test]
 fn test_transaction_ordering_only_seqnos() {
@@ -147,6 +154,33 @@ fn test_ignore_same_transaction_submitted_to_mempool() {
     assert!(ret.is_ok())
 }
 
+#[test]
+fn test_same_transaction_submitted_to_mempool_is_idempotent() {
+    let (mut mempool, _) = setup_mempool();
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let mut insert_txn = || {
+        mempool.add_txn(
+            txn.clone(),
+            txn.gas_unit_price(),
+            0,
+            TimelineState::NotReady,
+            false,
+            None,
+            Some(BroadcastPeerPriority::Primary),
+        )
+    };
+
+    // Verify that the first insert isn't marked as idempotent
+    let mempool_status = insert_txn();
+    assert_eq!(mempool_status.code, MempoolStatusCode::Accepted);
+    assert_ne!(mempool_status.message, IDEMPOTENT_TRANSACTION_MESSAGE);
+
+    // Verify that re-inserting the same transaction is marked as idempotent
+    let mempool_status = insert_txn();
+    assert_eq!(mempool_status.code, MempoolStatusCode::Accepted);
+    assert_eq!(mempool_status.message, IDEMPOTENT_TRANSACTION_MESSAGE);
+}
+
 #[test]
 fn test_fail_for_same_gas_amount_and_not_same_expiration_time() {
     let (mut mempool, _) = setup_mempool();
@@ -207,6 +241,31 @@ fn test_commit_transaction() {
     );
 }
 
+#[test]
+fn test_commit_transactions_promotes_parked_transactions() {
+    let (mut pool, mut consensus) = setup_mempool();
+
+    // Add transactions for two senders, with sequence number gaps (i.e., parked)
+    add_txns_to_mempool(&mut pool, vec![
+        TestTransaction::new(0, 2, 1),
+        TestTransaction::new(0, 3, 1),
+        TestTransaction::new(1, 1, 1),
+    ]);
+    assert_eq!(3, pool.get_parking_lot_size());
+    assert!(consensus.get_block(&mut pool, 3, 1024).is_empty());
+
+    // Commit a block that fills the gap of the first sender only
+    let num_promoted_txns = pool.commit_transactions(&[
+        (TestTransaction::get_address(0), 0),
+        (TestTransaction::get_address(0), 1),
+    ]);
+
+    // Verify that the parked transactions of the first sender were promoted
+    assert_eq!(num_promoted_txns, 2);
+    assert_eq!(1, pool.get_parking_lot_size());
+    assert_eq!(consensus.get_block(&mut pool, 3, 1024).len(), 2);
+}
+
 #[test]
 fn test_reject_transaction() {
     let (mut pool, _) = setup_mempool();
@@ -306,6 +365,156 @@ fn test_system_ttl() {
     assert_eq!(vec![transaction.make_signed_transaction()], batch);
 }
 
+#[test]
+fn test_eviction_policy_ttl_overrides() {
+    // Create a mempool with a system TTL of 10 seconds
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.system_transaction_timeout_secs = 10;
+    let mut mempool = CoreMempool::new(&config);
+
+    // Set an eviction policy that evicts zero gas price transactions after 5 seconds,
+    // unless they are sent by the allow-listed sender (which has a TTL of 20 seconds).
+    let eviction_policy = EvictionPolicyConfig {
+        zero_gas_price_ttl_secs: Some(5),
+        sender_ttl_secs: BTreeMap::from([(TestTransaction::get_address(2), 20)]),
+    };
+    assert!(eviction_policy.verify().is_ok());
+    mempool.set_eviction_policy(eviction_policy.clone());
+    assert_eq!(mempool.get_eviction_policy(), &eviction_policy);
+
+    // Verify the system TTL of each transaction
+    let paid_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let zero_gas_txn = TestTransaction::new(1, 0, 0).make_signed_transaction();
+    let allowlisted_txn = TestTransaction::new(2, 0, 0).make_signed_transaction();
+    assert_eq!(mempool.get_system_ttl(&paid_txn), Duration::from_secs(10));
+    assert_eq!(
+        mempool.get_system_ttl(&zero_gas_txn),
+        Duration::from_secs(5)
+    );
+    assert_eq!(
+        mempool.get_system_ttl(&allowlisted_txn),
+        Duration::from_secs(20)
+    );
+
+    // Add the transactions
+    let now = aptos_infallible::duration_since_epoch();
+    for txn in [&paid_txn, &zero_gas_txn, &allowlisted_txn] {
+        add_signed_txn(&mut mempool, txn.clone()).unwrap();
+    }
+
+    // Verify that only the zero gas price transaction is evicted after 6 seconds
+    mempool.gc_at(now + Duration::from_secs(6));
+    let batch = mempool.get_batch(3, 1024, true, btreemap![]);
+    assert_eq!(batch.len(), 2);
+    assert!(batch.contains(&paid_txn));
+    assert!(batch.contains(&allowlisted_txn));
+
+    // Verify that only the allow-listed transaction is kept after 15 seconds
+    mempool.gc_at(now + Duration::from_secs(15));
+    let batch = mempool.get_batch(3, 1024, true, btreemap![]);
+    assert_eq!(batch, vec![allowlisted_txn]);
+}
+
+#[test]
+fn test_transaction_hashes_with_ttl_overrides() {
+    // Create a mempool with a system TTL of 10 seconds, and an eviction policy that
+    // evicts zero gas price transactions after 5 seconds (and sender 2 after 20 seconds).
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.system_transaction_timeout_secs = 10;
+    let mut mempool = CoreMempool::new(&config);
+    mempool.set_eviction_policy(EvictionPolicyConfig {
+        zero_gas_price_ttl_secs: Some(5),
+        sender_ttl_secs: BTreeMap::from([(TestTransaction::get_address(2), 20)]),
+    });
+
+    // Add the transactions in the reverse order of their system TTLs
+    let allowlisted_txn = TestTransaction::new(2, 0, 0).make_signed_transaction();
+    let paid_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let zero_gas_txn = TestTransaction::new(1, 0, 0).make_signed_transaction();
+    for txn in [&allowlisted_txn, &paid_txn, &zero_gas_txn] {
+        add_signed_txn(&mut mempool, txn.clone()).unwrap();
+        std::thread::sleep(Duration::from_millis(1)); // Ensure distinct insertion times
+    }
+
+    // Verify that the hashes are returned in insertion order (newest first),
+    // regardless of the system TTL of each transaction.
+    assert_eq!(mempool.get_transaction_hashes(2), vec![
+        zero_gas_txn.committed_hash(),
+        paid_txn.committed_hash(),
+    ]);
+    assert_eq!(mempool.get_transaction_hashes(10), vec![
+        zero_gas_txn.committed_hash(),
+        paid_txn.committed_hash(),
+        allowlisted_txn.committed_hash(),
+    ]);
+}
+
+#[test]
+fn test_shadow_eviction_policy() {
+    // Create a mempool with a system TTL of 100 seconds, an active eviction policy that
+    // evicts the transactions of sender 1 after 10 seconds, and a shadow eviction policy
+    // that evicts zero gas price transactions after 20 seconds.
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.system_transaction_timeout_secs = 100;
+    config.mempool.eviction_policy = EvictionPolicyConfig {
+        sender_ttl_secs: BTreeMap::from([(TestTransaction::get_address(1), 10)]),
+        ..Default::default()
+    };
+    config.mempool.shadow_eviction_policy = Some(EvictionPolicyConfig {
+        zero_gas_price_ttl_secs: Some(20),
+        ..Default::default()
+    });
+    assert!(config.mempool.eviction_policy.verify().is_ok());
+    assert!(config
+        .mempool
+        .shadow_eviction_policy
+        .as_ref()
+        .unwrap()
+        .verify()
+        .is_ok());
+    let mut mempool = CoreMempool::new(&config);
+
+    // Add the transactions
+    let paid_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let evicted_txn = TestTransaction::new(1, 0, 1).make_signed_transaction();
+    let zero_gas_txn = TestTransaction::new(2, 0, 0).make_signed_transaction();
+    for txn in [&paid_txn, &evicted_txn, &zero_gas_txn] {
+        add_signed_txn(&mut mempool, txn.clone()).unwrap();
+    }
+    let now = aptos_infallible::duration_since_epoch();
+
+    // Verify that the shadow policy would have kept the transaction of sender 1
+    // (after 15 seconds), but wouldn't have evicted anything yet.
+    let divergence = mempool
+        .evaluate_shadow_eviction_policy(now + Duration::from_secs(15))
+        .unwrap();
+    assert_eq!(divergence.num_would_have_evicted, 0);
+    assert_eq!(divergence.num_would_have_kept, 1);
+
+    // Verify that only the active policy evicts transactions
+    mempool.gc_at(now + Duration::from_secs(15));
+    let batch = mempool.get_batch(3, 1024, true, btreemap![]);
+    assert_eq!(batch.len(), 2);
+    assert!(batch.contains(&paid_txn));
+    assert!(batch.contains(&zero_gas_txn));
+
+    // Verify that the shadow policy would have evicted the zero gas price transaction
+    // (after 25 seconds), and that it is still kept by the active policy.
+    let divergence = mempool
+        .evaluate_shadow_eviction_policy(now + Duration::from_secs(25))
+        .unwrap();
+    assert_eq!(divergence.num_would_have_evicted, 1);
+    assert_eq!(divergence.num_would_have_kept, 0);
+    assert_eq!(mempool.get_batch(3, 1024, true, btreemap![]).len(), 2);
+
+    // Verify that transactions are only counted once by the shadow policy
+    let divergence = mempool
+        .evaluate_shadow_eviction_policy(now + Duration::from_secs(30))
+        .unwrap();
+    assert_eq!(divergence.num_would_have_evicted, 0);
+    assert_eq!(divergence.num_would_have_kept, 0);
+}
+
 #[test]
 fn test_commit_callback() {
     // Consensus commit callback should unlock txns in parking lot.
@@ -423,6 +632,35 @@ fn test_timeline() {
     assert_eq!(0, pool.get_parking_lot_size());
 }
 
+#[test]
+fn test_timeline_range_min_expiration_secs() {
+    // Add transactions with different expiration times (to timeline ids 1, 2 and 3)
+    let mut pool = setup_mempool().0;
+    for (sequence_number, expiration_secs) in [(0, 5000), (1, 3000), (2, 4000)] {
+        let txn = TestTransaction::new(1, sequence_number, 1)
+            .make_signed_transaction_with_expiration_time(expiration_secs);
+        add_signed_txn(&mut pool, txn).unwrap();
+    }
+    let sender_bucket = sender_bucket(
+        &TestTransaction::get_address(1),
+        MempoolConfig::default().num_sender_buckets,
+    );
+    let min_expiration_secs = |pool: &CoreMempool, start, end| {
+        pool.timeline_range_min_expiration_secs(sender_bucket, HashMap::from([(0, (start, end))]))
+    };
+
+    // Verify that the earliest expiration time of the range is returned
+    assert_eq!(min_expiration_secs(&pool, 0, 1), Some(5000));
+    assert_eq!(min_expiration_secs(&pool, 0, 3), Some(3000));
+    assert_eq!(min_expiration_secs(&pool, 2, 3), Some(3000));
+    assert_eq!(min_expiration_secs(&pool, 2, 2), None);
+
+    // Verify that transactions that are no longer in mempool are ignored
+    pool.commit_transaction(&TestTransaction::get_address(1), 1);
+    assert_eq!(min_expiration_secs(&pool, 0, 3), Some(4000));
+    assert_eq!(min_expiration_secs(&pool, 0, 2), None);
+}
+
 #[test]
 fn test_timeline_before() {
     let mut pool = setup_mempool().0;
@@ -724,6 +962,69 @@ fn test_multi_bucket_removal() {
     assert!(view(timeline).is_empty());
 }
 
+#[test]
+fn test_multi_bucket_timeline_with_broadcast_lanes() {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.broadcast_buckets = vec![0, 101, 201];
+    config.mempool.broadcast_lanes = Some(BroadcastLanesConfig {
+        high_fee_threshold: 201,
+        high_fee_batch_percent: 50,
+    });
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(&mut pool, vec![
+        TestTransaction::new(1, 0, 1),   // normal lane
+        TestTransaction::new(1, 1, 1),   // normal lane
+        TestTransaction::new(1, 2, 1),   // normal lane
+        TestTransaction::new(1, 3, 1),   // normal lane
+        TestTransaction::new(1, 4, 300), // high-fee lane
+        TestTransaction::new(1, 5, 300), // high-fee lane
+        TestTransaction::new(1, 6, 300), // high-fee lane
+        TestTransaction::new(1, 7, 300), // high-fee lane
+    ]);
+    let sender_bucket = sender_bucket(&txns[0].sender(), config.mempool.num_sender_buckets);
+
+    // Verify the batch is split evenly between the lanes (with high-fee transactions first)
+    let (timeline, _) = pool.read_timeline(
+        sender_bucket,
+        &vec![0, 0, 0].into(),
+        4,
+        None,
+        BroadcastPeerPriority::Primary,
+    );
+    assert_eq!(timeline[0].0.gas_unit_price(), 300);
+    assert_eq!(view(timeline), vec![0, 1, 4, 5]);
+
+    // Verify the normal lane fills the batch if the high-fee lane is short
+    let (timeline, _) = pool.read_timeline(
+        sender_bucket,
+        &vec![0, 0, 3].into(),
+        4,
+        None,
+        BroadcastPeerPriority::Primary,
+    );
+    assert_eq!(view(timeline), vec![0, 1, 6, 7]);
+
+    // Verify the high-fee lane fills the batch if the normal lane is short
+    let (timeline, _) = pool.read_timeline(
+        sender_bucket,
+        &vec![3, 0, 0].into(),
+        4,
+        None,
+        BroadcastPeerPriority::Primary,
+    );
+    assert_eq!(view(timeline), vec![3, 4, 5, 6]);
+
+    // Verify all transactions are returned if the batch is large enough
+    let (timeline, _) = pool.read_timeline(
+        sender_bucket,
+        &vec![0, 0, 0].into(),
+        10,
+        None,
+        BroadcastPeerPriority::Primary,
+    );
+    assert_eq!(view(timeline), vec![0, 1, 2, 3, 4, 5, 6, 7]);
+}
+
 #[test]
 fn test_capacity() {
     let mut config = NodeConfig::generate_random_config();
@@ -993,6 +1294,77 @@ fn test_get_transaction_by_hash() {
     assert!(ret.is_none());
 }
 
+#[test]
+fn test_get_transaction_statuses_by_hash() {
+    let mut pool = setup_mempool().0;
+    let ready_txn = add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    // Sequence number gap, so the transaction is parked
+    let parked_txn = add_txn(&mut pool, TestTransaction::new(1, 2, 1)).unwrap();
+    let unknown_hash = HashValue::random();
+
+    let statuses = pool.get_statuses_by_hash(&[
+        parked_txn.committed_hash(),
+        unknown_hash,
+        ready_txn.committed_hash(),
+    ]);
+    assert_eq!(statuses.len(), 3);
+    assert!(matches!(statuses[0], MempoolTransactionStatus::Parked(_)));
+    assert_eq!(statuses[1], MempoolTransactionStatus::Unknown);
+    assert!(matches!(statuses[2], MempoolTransactionStatus::Pending(_)));
+}
+
+#[test]
+fn test_get_rejected_transaction_statuses() {
+    // Create a mempool that retains the reasons of the last 2 rejected transactions
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.broadcast_buckets = vec![0];
+    config.mempool.max_rejected_transactions_to_retain = 2;
+    let mut pool = CoreMempool::new(&config);
+
+    // Add and reject 3 transactions (from different senders)
+    let txns: Vec<_> = (0..3)
+        .map(|sender| add_txn(&mut pool, TestTransaction::new(sender, 0, 1)).unwrap())
+        .collect();
+    let hashes: Vec<_> = txns.iter().map(|txn| txn.committed_hash()).collect();
+    for txn in &txns {
+        pool.reject_transaction(
+            &txn.sender(),
+            0,
+            &txn.committed_hash(),
+            &DiscardedVMStatus::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE,
+        );
+    }
+
+    // Verify the oldest rejection is evicted, and the others are reported
+    let rejected_status = MempoolTransactionStatus::Rejected(
+        DiscardedVMStatus::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE,
+    );
+    assert_eq!(pool.get_statuses_by_hash(&hashes), vec![
+        MempoolTransactionStatus::Unknown,
+        rejected_status.clone(),
+        rejected_status.clone(),
+    ]);
+
+    // Resubmit the second transaction and verify its rejection is removed
+    add_signed_txn(&mut pool, txns[1].clone()).unwrap();
+    let statuses = pool.get_statuses_by_hash(&hashes);
+    assert!(matches!(statuses[1], MempoolTransactionStatus::Pending(_)));
+    assert_eq!(statuses[2], rejected_status);
+
+    // Reject a new transaction and verify the remaining rejections are retained
+    let new_txn = add_txn(&mut pool, TestTransaction::new(3, 0, 1)).unwrap();
+    pool.reject_transaction(
+        &new_txn.sender(),
+        0,
+        &new_txn.committed_hash(),
+        &DiscardedVMStatus::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE,
+    );
+    assert_eq!(
+        pool.get_statuses_by_hash(&[hashes[2], new_txn.committed_hash()]),
+        vec![rejected_status.clone(), rejected_status]
+    );
+}
+
 #[test]
 fn test_get_transaction_by_hash_after_the_txn_is_updated() {
     let mut pool = setup_mempool().0;
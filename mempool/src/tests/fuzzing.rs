@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState},
+    core_mempool::{ShardedMempool, TimelineState},
     network::{BroadcastPeerPriority, MempoolSyncMsg},
     shared_mempool::{tasks, types::SharedMempool},
 };
@@ -11,7 +11,7 @@ use aptos_config::{
     config::{NodeConfig, NodeType},
     network_id::NetworkId,
 };
-use aptos_infallible::{Mutex, RwLock};
+use aptos_infallible::RwLock;
 use aptos_network::{
     application::{interface::NetworkClient, storage::PeersAndMetadata},
     protocols::wire::handshake::v1::ProtocolId::MempoolDirectSend,
@@ -83,7 +83,7 @@ pub fn test_mempool_process_incoming_transactions_impl(
         PeersAndMetadata::new(&[NetworkId::Validator]),
     );
     let smp: SharedMempool<NetworkClient<MempoolSyncMsg>, MockVMValidator> = SharedMempool::new(
-        Arc::new(Mutex::new(CoreMempool::new(&config))),
+        Arc::new(ShardedMempool::new(&config)),
         config.mempool.clone(),
         network_client,
         Arc::new(mock_db),
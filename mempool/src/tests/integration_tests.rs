@@ -4,9 +4,15 @@
 
 use crate::tests::{
     common::TestTransaction,
-    test_framework::{test_transaction, MempoolNode, MempoolTestFrameworkBuilder},
+    test_framework::{
+        block_only_contains_transactions, sign_transactions, single_vfn_with_config,
+        test_transaction, MempoolNode, MempoolTestFrameworkBuilder,
+    },
+};
+use aptos_config::{
+    config::{BroadcastFilterConfig, NodeConfig},
+    network_id::PeerNetworkId,
 };
-use aptos_config::network_id::PeerNetworkId;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_network::{
     testutils::{
@@ -21,7 +27,7 @@ use aptos_network::{
 };
 use std::time::Duration;
 
-const ALL_PROTOCOLS: [ProtocolId; 1] = [ProtocolId::MempoolDirectSend];
+const ALL_PROTOCOLS: [ProtocolId; 2] = [ProtocolId::MempoolDirectSend, ProtocolId::MempoolRpc];
 static ALL_TXNS: &[TestTransaction] = &[test_transaction(0), test_transaction(1)];
 static TXN_1: &[TestTransaction] = &[test_transaction(0)];
 static TXN_2: &[TestTransaction] = &[test_transaction(1)];
@@ -317,6 +323,85 @@ async fn test_rebroadcast_retry_is_empty() {
         .await;
 }
 
+/// Tests that a newly connected peer that is promoted above the current broadcast targets
+/// (e.g., a VFN's validator) is sent the in-flight window back-to-back, instead of a single
+/// broadcast per tick interval.
+#[tokio::test]
+async fn test_rebroadcast_on_peer_promotion() {
+    // Create a VFN that broadcasts a single transaction per tick
+    let tick_interval = Duration::from_millis(1000);
+    let mut config = NodeConfig::get_default_vfn_config();
+    config.mempool.shared_mempool_batch_size = 1;
+    config.mempool.shared_mempool_tick_interval_ms = tick_interval.as_millis() as u64;
+    config.mempool.shared_mempool_ack_timeout_ms = 60_000;
+    let mut node = single_vfn_with_config(config);
+    let (public_peer_network_id, public_metadata) =
+        vfn_vfn_mock_connection(ConnectionOrigin::Outbound, &ALL_PROTOCOLS);
+    let (validator_peer_network_id, validator_metadata) =
+        vfn_validator_mock_connection(ConnectionOrigin::Outbound, &ALL_PROTOCOLS);
+
+    // Connect to the public upstream peer (while the validator is disconnected)
+    let txns: Vec<_> = (0..4).map(test_transaction).collect();
+    node.add_txns_via_client(&txns).await;
+    node.connect_self(public_peer_network_id.network_id(), public_metadata);
+
+    // Broadcast two transactions to the public peer (one per tick), but never acknowledge them
+    for _ in 0..2 {
+        node.drop_next_network_msg(public_peer_network_id.network_id())
+            .await;
+    }
+
+    // Connect to the validator, and verify it is sent two broadcasts back-to-back
+    let validator_network_id = validator_peer_network_id.network_id();
+    node.connect_self(validator_network_id, validator_metadata);
+    for _ in 0..2 {
+        let (peer_id, _, _) = tokio::time::timeout(
+            tick_interval / 2,
+            node.drop_next_network_msg(validator_network_id),
+        )
+        .await
+        .expect("The catch-up broadcasts should be sent without waiting for a tick");
+        assert_eq!(peer_id, validator_peer_network_id.peer_id());
+    }
+
+    // Verify the following broadcasts wait for the tick interval again
+    node.wait_for_no_msg(validator_network_id, tick_interval / 2)
+        .await;
+}
+
+/// Tests that transactions skipped because they match a peer's transaction filter are not
+/// considered delivered, and are broadcast once the peer's next filter doesn't hold them
+/// (i.e., if they were false positives).
+#[tokio::test]
+async fn test_broadcast_filter_false_positives() {
+    let mut config = NodeConfig::get_default_vfn_config();
+    config.mempool.broadcast_filter = Some(BroadcastFilterConfig::default());
+    let mut node = single_vfn_with_config(config);
+    let (validator_peer_network_id, validator_metadata) =
+        vfn_validator_mock_connection(ConnectionOrigin::Outbound, &ALL_PROTOCOLS);
+    let txns: Vec<_> = (0..3).map(test_transaction).collect();
+
+    // Connect to the validator, and wait for the first broadcast (so the peer is known)
+    node.add_txns_via_client(&txns[..1]).await;
+    node.connect_self(validator_peer_network_id.network_id(), validator_metadata);
+    node.send_broadcast_and_receive_ack(validator_peer_network_id, &txns[..1])
+        .await;
+
+    // Send a filter from the validator that holds the second transaction
+    node.send_transaction_filter_to_node(validator_peer_network_id, &txns[1..2]);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Verify the filtered transaction is skipped
+    node.add_txns_via_client(&txns[1..]).await;
+    node.send_broadcast_and_receive_ack(validator_peer_network_id, &txns[2..])
+        .await;
+
+    // Send a filter that doesn't hold the skipped transaction, and verify it is broadcast
+    node.send_transaction_filter_to_node(validator_peer_network_id, &[]);
+    node.send_broadcast_and_receive_ack(validator_peer_network_id, &txns[1..2])
+        .await;
+}
+
 // -- Multi node tests below here --
 
 /// Tests if the node is a VFN, and it's getting forwarded messages from a PFN.  It should forward
@@ -380,3 +465,38 @@ async fn fn_to_val_test() {
         val.assert_only_txns_in_mempool(ALL_TXNS);
     }
 }
+
+/// Tests that transactions can be pulled from (and by) a node
+#[tokio::test]
+async fn transaction_fetch_test() {
+    for (mut node, (other_peer_network_id, other_metadata)) in inbound_node_combinations() {
+        node.connect_self(other_peer_network_id.network_id(), other_metadata);
+        node.add_txns_via_client(TXN_1).await;
+
+        // Verify that only the transactions held by the node are served
+        let transactions = node
+            .request_transactions_from_node(other_peer_network_id, ALL_TXNS)
+            .await;
+        assert!(block_only_contains_transactions(&transactions, TXN_1));
+
+        // Verify that transaction responses outside of an RPC are ignored. The node
+        // handles the unsolicited response before serving the next request.
+        node.send_unsolicited_transaction_response_to_node(other_peer_network_id, TXN_2);
+        let transactions = node
+            .request_transactions_from_node(other_peer_network_id, TXN_2)
+            .await;
+        assert!(transactions.is_empty());
+        node.assert_only_txns_in_mempool(TXN_1);
+
+        // Verify that only the transactions missing from the node are requested, and
+        // that only the requested transactions are inserted into mempool
+        let unrequested_txns: &[TestTransaction] = &[TestTransaction::new(2, 0, 1)];
+        let served_txns = [TXN_2, unrequested_txns].concat();
+        let (hashes, num_accepted_txns) = node
+            .fetch_transactions_via_client(other_peer_network_id, ALL_TXNS, &served_txns)
+            .await;
+        assert_eq!(hashes, vec![sign_transactions(TXN_2)[0].committed_hash()]);
+        assert_eq!(num_accepted_txns, 1);
+        node.assert_only_txns_in_mempool(ALL_TXNS);
+    }
+}
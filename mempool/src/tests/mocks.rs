@@ -3,10 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState},
+    core_mempool::{ShardedMempool, TimelineState},
     network::BroadcastPeerPriority,
     shared_mempool::start_shared_mempool,
-    MempoolClientSender, QuorumStoreRequest,
+    MempoolClientSender, MempoolLoadMonitor, QuorumStoreRequest,
 };
 use anyhow::{format_err, Result};
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
@@ -15,7 +15,7 @@ use aptos_config::{
     network_id::NetworkId,
 };
 use aptos_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
-use aptos_infallible::{Mutex, RwLock};
+use aptos_infallible::RwLock;
 use aptos_mempool_notifications::{self, MempoolNotifier};
 use aptos_network::{
     application::{
@@ -25,9 +25,10 @@ use aptos_network::{
     peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
     protocols::{
         network::{NetworkEvents, NetworkSender, NewNetworkEvents, NewNetworkSender},
-        wire::handshake::v1::ProtocolId::MempoolDirectSend,
+        wire::handshake::v1::ProtocolId::{MempoolDirectSend, MempoolRpc},
     },
 };
+use aptos_peer_monitoring_service_types::MempoolUtilization;
 use aptos_storage_interface::{mock::MockDbReaderWriter, DbReaderWriter};
 use aptos_types::{
     mempool_status::MempoolStatusCode,
@@ -50,7 +51,7 @@ pub struct MockSharedMempool {
     _runtime: Option<Handle>,
     _handle: Option<Handle>,
     pub ac_client: MempoolClientSender,
-    pub mempool: Arc<Mutex<CoreMempool>>,
+    pub mempool: Arc<ShardedMempool>,
     pub consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
     pub mempool_notifier: MempoolNotifier,
 }
@@ -111,14 +112,14 @@ impl MockSharedMempool {
         validator: V,
     ) -> (
         MempoolClientSender,
-        Arc<Mutex<CoreMempool>>,
+        Arc<ShardedMempool>,
         mpsc::Sender<QuorumStoreRequest>,
         MempoolNotifier,
     ) {
         let mut config = NodeConfig::generate_random_config();
         config.validator_network = Some(NetworkConfig::network_with_id(NetworkId::Validator));
 
-        let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
+        let mempool = Arc::new(ShardedMempool::new(&config));
         let (network_reqs_tx, _network_reqs_rx) = aptos_channel::new(QueueStyle::FIFO, 8, None);
         let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
         let (_network_notifs_tx, network_notifs_rx) = aptos_channel::new(QueueStyle::FIFO, 8, None);
@@ -148,7 +149,7 @@ impl MockSharedMempool {
         let network_senders = hashmap! {NetworkId::Validator => network_sender};
         let network_client = NetworkClient::new(
             vec![MempoolDirectSend],
-            vec![],
+            vec![MempoolRpc],
             network_senders,
             peers_and_metadata.clone(),
         );
@@ -169,44 +170,45 @@ impl MockSharedMempool {
             Arc::new(RwLock::new(validator)),
             vec![],
             peers_and_metadata,
+            MempoolUtilization::default(),
+            MempoolLoadMonitor::default(),
+            Arc::new(RwLock::new(vec![])),
         );
 
         (ac_client, mempool, quorum_store_sender, mempool_notifier)
     }
 
     pub fn add_txns(&self, txns: Vec<SignedTransaction>) -> Result<()> {
-        {
-            let mut pool = self.mempool.lock();
-            for txn in txns {
-                if pool
-                    .add_txn(
-                        txn.clone(),
-                        txn.gas_unit_price(),
-                        0,
-                        TimelineState::NotReady,
-                        false,
-                        None,
-                        Some(BroadcastPeerPriority::Primary),
-                    )
-                    .code
-                    != MempoolStatusCode::Accepted
-                {
-                    return Err(format_err!("failed to insert into mock mempool"));
-                };
-            }
+        for txn in txns {
+            if self
+                .mempool
+                .add_txn(
+                    txn.clone(),
+                    txn.gas_unit_price(),
+                    0,
+                    TimelineState::NotReady,
+                    false,
+                    None,
+                    Some(BroadcastPeerPriority::Primary),
+                )
+                .code
+                != MempoolStatusCode::Accepted
+            {
+                return Err(format_err!("failed to insert into mock mempool"));
+            };
         }
         Ok(())
     }
 
     pub fn get_txns(&self, size: u64) -> Vec<SignedTransaction> {
-        let pool = self.mempool.lock();
         // assume txn size is less than 100kb
-        pool.get_batch(size, size * 102400, true, BTreeMap::new())
+        self.mempool
+            .get_batch(size, size * 102400, true, BTreeMap::new())
     }
 
     pub fn remove_txn(&self, txn: &SignedTransaction) {
-        let mut pool = self.mempool.lock();
-        pool.commit_transaction(&txn.sender(), txn.sequence_number())
+        self.mempool
+            .commit_transaction(&txn.sender(), txn.sequence_number())
     }
 }
 
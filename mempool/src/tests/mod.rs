@@ -2,6 +2,8 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(test)]
+mod client_quotas_test;
 #[cfg(test)]
 mod common;
 #[cfg(test)]
@@ -14,9 +16,13 @@ mod multi_node_test;
 mod node;
 #[cfg(test)]
 mod shared_mempool_test;
+#[cfg(test)]
+mod sharded_mempool_test;
 
 pub mod fuzzing;
 #[cfg(any(feature = "fuzzing", test))]
 pub mod mocks;
+#[cfg(any(feature = "fuzzing", test))]
+pub mod prioritization_replay;
 #[cfg(test)]
 mod test_framework;
@@ -3,10 +3,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState},
+    core_mempool::{ShardedMempool, TimelineState},
     network::{BroadcastPeerPriority, MempoolSyncMsg},
     shared_mempool::{start_shared_mempool, types::SharedMempoolNotification},
     tests::common::TestTransaction,
+    MempoolLoadMonitor,
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
@@ -15,7 +16,7 @@ use aptos_config::{
 };
 use aptos_crypto::{x25519::PrivateKey, Uniform};
 use aptos_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
-use aptos_infallible::{Mutex, MutexGuard, RwLock};
+use aptos_infallible::RwLock;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_network::{
     application::{
@@ -32,6 +33,7 @@ use aptos_network::{
     transport::ConnectionMetadata,
     ProtocolId,
 };
+use aptos_peer_monitoring_service_types::MempoolUtilization;
 use aptos_storage_interface::mock::MockDbReaderWriter;
 use aptos_types::{
     on_chain_config::{InMemoryOnChainConfig, OnChainConfigPayload},
@@ -313,8 +315,8 @@ pub fn public_full_node_config(
 pub struct Node {
     /// The identifying Node
     node_info: NodeInfo,
-    /// `CoreMempool` for this node
-    mempool: Arc<Mutex<CoreMempool>>,
+    /// `ShardedMempool` for this node
+    mempool: Arc<ShardedMempool>,
     /// Network interfaces for a node
     network_interfaces: HashMap<NetworkId, NodeNetworkInterface>,
     /// Tokio runtime
@@ -366,14 +368,14 @@ impl Node {
         }
     }
 
-    /// Retrieves a `CoreMempool`
-    pub fn mempool(&self) -> MutexGuard<CoreMempool> {
-        self.mempool.lock()
+    /// Retrieves a `ShardedMempool`
+    pub fn mempool(&self) -> &ShardedMempool {
+        &self.mempool
     }
 
     /// Queues transactions for sending on a node.  Must use `broadcast_txns` to send to other nodes
     pub fn add_txns(&self, txns: Vec<TestTransaction>) {
-        let mut mempool = self.mempool();
+        let mempool = self.mempool();
         for txn in txns {
             let transaction = txn.make_signed_transaction_with_max_gas_amount(5);
             mempool.add_txn(
@@ -563,11 +565,11 @@ fn start_node_mempool(
     network_service_events: NetworkServiceEvents<MempoolSyncMsg>,
     peers_and_metadata: Arc<PeersAndMetadata>,
 ) -> (
-    Arc<Mutex<CoreMempool>>,
+    Arc<ShardedMempool>,
     Runtime,
     UnboundedReceiver<SharedMempoolNotification>,
 ) {
-    let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
+    let mempool = Arc::new(ShardedMempool::new(&config));
     let (sender, subscriber) = unbounded();
     let (_ac_endpoint_sender, ac_endpoint_receiver) = mpsc::channel(1_024);
     let (_quorum_store_sender, quorum_store_receiver) = mpsc::channel(1_024);
@@ -602,6 +604,9 @@ fn start_node_mempool(
         Arc::new(RwLock::new(MockVMValidator)),
         vec![sender],
         peers_and_metadata,
+        MempoolUtilization::default(),
+        MempoolLoadMonitor::default(),
+        Arc::new(RwLock::new(vec![])),
     );
 
     (mempool, runtime, subscriber)
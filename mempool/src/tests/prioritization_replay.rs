@@ -0,0 +1,235 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A utility for replaying a recorded stream of peer monitoring metadata through the
+//! mempool peer prioritization (i.e., `PrioritizedPeersState`). This allows changes to
+//! the prioritization strategies (and configs) to be evaluated offline against data
+//! captured from production nodes, by comparing the ranking stability and the coverage
+//! of the broadcast targets reported for each strategy.
+
+use crate::{
+    network::BroadcastPeerPriority,
+    shared_mempool::{types::MempoolSenderBucket, PrioritizedPeersState},
+};
+use aptos_config::{
+    config::{MempoolConfig, NodeType},
+    network_id::PeerNetworkId,
+};
+use aptos_peer_monitoring_service_types::PeerMonitoringMetadata;
+use aptos_time_service::TimeService;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+/// A single recorded peer monitoring metadata event
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MonitoringMetadataEvent {
+    /// The time at which the metadata was observed (in microseconds)
+    pub timestamp_usecs: u64,
+    /// The peer that the metadata belongs to
+    pub peer: PeerNetworkId,
+    /// The monitoring metadata of the peer
+    pub monitoring_metadata: PeerMonitoringMetadata,
+}
+
+/// The ranking stability and broadcast target coverage observed during a replay
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PrioritizationReplayReport {
+    /// The number of events replayed
+    pub num_events: usize,
+    /// The number of peers observed across all events
+    pub num_peers: usize,
+    /// The number of times the prioritized peers were updated
+    pub num_priority_updates: usize,
+    /// The number of updates that changed the top (i.e., highest priority) peer
+    pub num_top_peer_changes: usize,
+    /// The average fraction of the top peers replaced by each update (after the first)
+    pub average_top_peers_churn: f64,
+    /// The average number of top (i.e., primary broadcast) peers after each update
+    pub average_num_top_peers: f64,
+    /// The fraction of observed peers that were selected as top peers at least once
+    pub peer_coverage: f64,
+    /// The average fraction of sender buckets with a primary broadcast peer after each update
+    pub sender_bucket_coverage: f64,
+}
+
+/// Replays the given (recorded) events through a new prioritized peers state, and
+/// reports the resulting ranking stability and broadcast target coverage. Events are
+/// replayed in timestamp order, and each event updates the metadata of a single peer.
+/// Peers are considered connected from their first event until the end of the replay.
+pub fn replay_monitoring_metadata_events(
+    mempool_config: MempoolConfig,
+    node_type: NodeType,
+    mut events: Vec<MonitoringMetadataEvent>,
+) -> PrioritizationReplayReport {
+    // Create the prioritized peers state (driven by the event timestamps)
+    let time_service = TimeService::mock();
+    let mock_time_service = time_service.clone().into_mock();
+    let mut prioritized_peers_state =
+        PrioritizedPeersState::new(mempool_config.clone(), node_type, time_service);
+
+    // Replay the events (in timestamp order)
+    events.sort_by_key(|event| event.timestamp_usecs);
+    let mut last_timestamp_usecs = events.first().map_or(0, |event| event.timestamp_usecs);
+    let mut peers_and_metadata: HashMap<PeerNetworkId, PeerMonitoringMetadata> = HashMap::new();
+    let mut previous_top_peer = None;
+    let mut previous_top_peers: HashSet<PeerNetworkId> = HashSet::new();
+    let mut ever_top_peers = HashSet::new();
+    let mut total_top_peers_churn = 0.0;
+    let mut total_num_top_peers = 0;
+    let mut total_sender_bucket_coverage = 0.0;
+    let mut report = PrioritizationReplayReport {
+        num_events: events.len(),
+        ..PrioritizationReplayReport::default()
+    };
+    for event in events {
+        // Advance the time to the event timestamp
+        mock_time_service.advance(Duration::from_micros(
+            event.timestamp_usecs - last_timestamp_usecs,
+        ));
+        last_timestamp_usecs = event.timestamp_usecs;
+
+        // Update the metadata of the peer, and check if an update is required
+        let monitoring_changed = prioritized_peers_state
+            .requires_update_for_monitoring_change(&event.peer, Some(&event.monitoring_metadata));
+        let peers_changed = peers_and_metadata
+            .insert(event.peer, event.monitoring_metadata)
+            .is_none();
+        if !prioritized_peers_state.ready_for_update(peers_changed || monitoring_changed) {
+            continue;
+        }
+
+        // Update the prioritized peers
+        let all_peers_and_metadata: Vec<_> = peers_and_metadata
+            .iter()
+            .map(|(peer, monitoring_metadata)| (*peer, Some(monitoring_metadata)))
+            .collect();
+        prioritized_peers_state.update_prioritized_peers(all_peers_and_metadata.clone(), 0, 0);
+        report.num_priority_updates += 1;
+
+        // Update the ranking stability stats
+        let top_peer = all_peers_and_metadata
+            .iter()
+            .map(|(peer, _)| *peer)
+            .min_by_key(|peer| prioritized_peers_state.get_peer_priority(peer));
+        if report.num_priority_updates > 1 {
+            if top_peer != previous_top_peer {
+                report.num_top_peer_changes += 1;
+            }
+            let top_peers = prioritized_peers_state.get_top_peers();
+            let num_replaced_top_peers = previous_top_peers.difference(&top_peers).count();
+            total_top_peers_churn +=
+                num_replaced_top_peers as f64 / previous_top_peers.len().max(1) as f64;
+        }
+        previous_top_peer = top_peer;
+        previous_top_peers = prioritized_peers_state.get_top_peers();
+
+        // Update the broadcast target coverage stats
+        total_num_top_peers += previous_top_peers.len();
+        ever_top_peers.extend(previous_top_peers.iter().cloned());
+        total_sender_bucket_coverage += get_sender_bucket_coverage(
+            &prioritized_peers_state,
+            &previous_top_peers,
+            mempool_config.num_sender_buckets,
+        );
+    }
+
+    // Calculate the report averages
+    report.num_peers = peers_and_metadata.len();
+    if report.num_priority_updates > 0 {
+        let num_priority_updates = report.num_priority_updates as f64;
+        report.average_num_top_peers = total_num_top_peers as f64 / num_priority_updates;
+        report.sender_bucket_coverage = total_sender_bucket_coverage / num_priority_updates;
+        if report.num_priority_updates > 1 {
+            report.average_top_peers_churn = total_top_peers_churn / (num_priority_updates - 1.0);
+        }
+    }
+    if report.num_peers > 0 {
+        report.peer_coverage = ever_top_peers.len() as f64 / report.num_peers as f64;
+    }
+    report
+}
+
+/// Returns the fraction of sender buckets that have a primary broadcast peer
+fn get_sender_bucket_coverage(
+    prioritized_peers_state: &PrioritizedPeersState,
+    top_peers: &HashSet<PeerNetworkId>,
+    num_sender_buckets: MempoolSenderBucket,
+) -> f64 {
+    if num_sender_buckets == 0 {
+        return 0.0;
+    }
+    let num_covered_sender_buckets = (0..num_sender_buckets)
+        .filter(|sender_bucket| {
+            top_peers.iter().any(|peer| {
+                prioritized_peers_state
+                    .get_sender_bucket_priority_for_peer(peer, *sender_bucket)
+                    .is_some_and(|priority| priority == BroadcastPeerPriority::Primary)
+            })
+        })
+        .count();
+    num_covered_sender_buckets as f64 / num_sender_buckets as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replay_monitoring_metadata_events, MonitoringMetadataEvent};
+    use aptos_config::{
+        config::{MempoolConfig, NodeType},
+        network_id::{NetworkId, PeerNetworkId},
+    };
+    use aptos_peer_monitoring_service_types::PeerMonitoringMetadata;
+    use aptos_types::PeerId;
+
+    #[test]
+    fn test_replay_monitoring_metadata_events() {
+        // Create several public peers
+        let peers: Vec<_> = (0..3)
+            .map(|_| PeerNetworkId::new(NetworkId::Public, PeerId::random()))
+            .collect();
+
+        // Record the metadata of each peer, with the first peer becoming slower over time
+        let mut events = vec![];
+        for (index, peer) in peers.iter().enumerate() {
+            events.push(create_event(index as u64, *peer, 0.1 * (index + 1) as f64));
+        }
+        events.push(create_event(60_000_000, peers[0], 1.0));
+        events.push(create_event(120_000_000, peers[0], 1.0));
+
+        // Replay the events and verify the report
+        let mempool_config = MempoolConfig {
+            broadcast_fanout: Some(1),
+            ..MempoolConfig::default()
+        };
+        let report =
+            replay_monitoring_metadata_events(mempool_config, NodeType::PublicFullnode, events);
+        assert_eq!(report.num_events, 5);
+        assert_eq!(report.num_peers, 3);
+        assert!(report.num_priority_updates >= 3);
+        assert!(report.num_top_peer_changes >= 1);
+        assert_eq!(report.average_num_top_peers, 1.0);
+        assert_eq!(report.sender_bucket_coverage, 1.0);
+        assert!(report.peer_coverage > 0.0 && report.peer_coverage <= 1.0);
+    }
+
+    /// Creates a monitoring metadata event with the given ping latency
+    fn create_event(
+        timestamp_usecs: u64,
+        peer: PeerNetworkId,
+        average_ping_latency_secs: f64,
+    ) -> MonitoringMetadataEvent {
+        MonitoringMetadataEvent {
+            timestamp_usecs,
+            peer,
+            monitoring_metadata: PeerMonitoringMetadata::new(
+                Some(average_ping_latency_secs),
+                None,
+                None,
+                None,
+                None,
+            ),
+        }
+    }
+}
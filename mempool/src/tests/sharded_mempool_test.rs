@@ -0,0 +1,399 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    core_mempool::{ShardedMempool, TimelineState, TransactionJournal},
+    network::BroadcastPeerPriority,
+    shared_mempool::types::{
+        MempoolTransactionStatus, PendingTransactionsFilter, PendingTransactionsRequest,
+    },
+};
+use aptos_config::config::NodeConfig;
+use aptos_consensus_types::common::{TransactionInProgress, TransactionSummary};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    mempool_status::MempoolStatusCode,
+    transaction::{RawTransaction, Script, SignedTransaction},
+};
+use maplit::btreemap;
+use rand::{rngs::StdRng, SeedableRng};
+use std::{fs::OpenOptions, io::Write, time::Duration};
+
+#[test]
+fn test_sharded_mempool_routing() {
+    let mempool = create_sharded_mempool(4, 4, 100);
+
+    // Add a transaction for a sender in each shard
+    let txns: Vec<_> = (0..4)
+        .map(|bucket| create_signed_txn(bucket, 0, 1))
+        .collect();
+    for (bucket, txn) in txns.iter().enumerate() {
+        assert_eq!(mempool.shard_id_of_sender(&txn.sender()), bucket);
+        assert_eq!(
+            add_signed_txn(&mempool, txn.clone()),
+            MempoolStatusCode::Accepted
+        );
+    }
+
+    // Verify that each transaction can be found by hash
+    for txn in &txns {
+        assert_eq!(mempool.get_by_hash(txn.committed_hash()), Some(txn.clone()));
+    }
+    let statuses = mempool.get_statuses_by_hash(&[
+        txns[3].committed_hash(),
+        HashValue::random(),
+        txns[0].committed_hash(),
+    ]);
+    assert!(matches!(statuses[0], MempoolTransactionStatus::Pending(_)));
+    assert_eq!(statuses[1], MempoolTransactionStatus::Unknown);
+    assert!(matches!(statuses[2], MempoolTransactionStatus::Pending(_)));
+
+    // Verify that each sender bucket timeline only contains the sender of that bucket
+    for (bucket, txn) in txns.iter().enumerate() {
+        let (timeline, _) = mempool.read_timeline(
+            bucket as u8,
+            &vec![0; 1].into(),
+            10,
+            None,
+            BroadcastPeerPriority::Primary,
+        );
+        assert_eq!(timeline.len(), 1);
+        assert_eq!(&timeline[0].0, txn);
+    }
+
+    // Commit a transaction and verify it is removed from its shard
+    mempool.commit_transaction(&txns[2].sender(), 0);
+    assert_eq!(mempool.get_by_hash(txns[2].committed_hash()), None);
+}
+
+#[test]
+fn test_sharded_mempool_get_batch() {
+    let mempool = create_sharded_mempool(4, 4, 100);
+
+    // The second transaction of sender 0 has the highest gas price, but it
+    // can only be included after the first transaction of sender 0.
+    let sender_0_txn_0 = create_signed_txn(0, 0, 1);
+    let sender_0_txn_1 = create_signed_txn(0, 1, 10);
+    let sender_1_txn_0 = create_signed_txn(1, 0, 5);
+    let sender_2_txn_0 = create_signed_txn(2, 0, 3);
+    for txn in [
+        &sender_0_txn_1,
+        &sender_0_txn_0,
+        &sender_1_txn_0,
+        &sender_2_txn_0,
+    ] {
+        assert_eq!(
+            add_signed_txn(&mempool, txn.clone()),
+            MempoolStatusCode::Accepted
+        );
+    }
+
+    // Verify that the shards are merged by gas price, in sequence number order for each sender
+    let block = mempool.get_batch(100, 102400, true, btreemap![]);
+    assert_eq!(block, vec![
+        sender_1_txn_0.clone(),
+        sender_2_txn_0.clone(),
+        sender_0_txn_0.clone(),
+        sender_0_txn_1.clone(),
+    ]);
+
+    // Verify that the transaction limit is respected
+    let block = mempool.get_batch(2, 102400, true, btreemap![]);
+    assert_eq!(block, vec![sender_1_txn_0.clone(), sender_2_txn_0.clone()]);
+
+    // Verify that the byte limit is respected
+    let max_bytes = sender_1_txn_0.txn_bytes_len() as u64;
+    let block = mempool.get_batch(100, max_bytes, true, btreemap![]);
+    assert_eq!(block, vec![sender_1_txn_0.clone()]);
+
+    // Verify that transactions already in consensus are excluded
+    let exclude_transactions = btreemap! {
+        TransactionSummary::new(
+            sender_1_txn_0.sender(),
+            sender_1_txn_0.sequence_number(),
+            sender_1_txn_0.committed_hash(),
+        ) => TransactionInProgress::new(sender_1_txn_0.gas_unit_price()),
+    };
+    let block = mempool.get_batch(100, 102400, true, exclude_transactions);
+    assert_eq!(block, vec![sender_2_txn_0, sender_0_txn_0, sender_0_txn_1]);
+
+    // Verify that non-full blocks are not returned if requested
+    let block = mempool.get_batch(100, 102400, false, btreemap![]);
+    assert!(block.is_empty());
+}
+
+#[test]
+fn test_sharded_mempool_lock_all() {
+    let mempool = create_sharded_mempool(4, 4, 100);
+
+    // Add a transaction for a sender in each shard (with increasing gas prices)
+    let txns: Vec<_> = (0..4)
+        .map(|bucket| create_signed_txn(bucket, 0, bucket as u64 + 1))
+        .collect();
+    for txn in &txns {
+        assert_eq!(
+            add_signed_txn(&mempool, txn.clone()),
+            MempoolStatusCode::Accepted
+        );
+    }
+
+    // Verify that the gc and the consensus pull can be performed while holding all shards
+    let mut locked_shards = mempool.lock_all();
+    locked_shards.gc_by_expiration_time(Duration::from_secs(1));
+    let block = locked_shards.get_batch(100, 102400, true, btreemap![]);
+    assert_eq!(block, txns.iter().rev().cloned().collect::<Vec<_>>());
+
+    // Verify that the shards are unlocked once the guard is dropped
+    drop(locked_shards);
+    assert_eq!(mempool.get_num_transactions(), 4);
+}
+
+#[test]
+fn test_sharded_mempool_capacity() {
+    // Create a mempool with 2 shards, each with a capacity of 2 transactions
+    let mempool = create_sharded_mempool(2, 2, 4);
+
+    // Fill up the shard of sender 0
+    for sequence_number in 0..2 {
+        let txn = create_signed_txn(0, sequence_number, 1);
+        assert_eq!(add_signed_txn(&mempool, txn), MempoolStatusCode::Accepted);
+    }
+    let txn = create_signed_txn(0, 2, 1);
+    assert_eq!(
+        add_signed_txn(&mempool, txn),
+        MempoolStatusCode::MempoolIsFull
+    );
+
+    // Verify that the shard of sender 1 still has capacity
+    let txn = create_signed_txn(1, 0, 1);
+    assert_eq!(add_signed_txn(&mempool, txn), MempoolStatusCode::Accepted);
+}
+
+#[test]
+fn test_sharded_mempool_transaction_journal() {
+    // Create a mempool with a transaction journal
+    let journal_path = TempPath::new();
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.broadcast_buckets = vec![0];
+    config.mempool.transaction_journal_path = Some(journal_path.path().to_path_buf());
+    let mempool = ShardedMempool::new(&config);
+    assert!(mempool.take_journaled_transactions().is_empty());
+
+    // Add several transactions (and a transaction that is rejected)
+    let txns: Vec<_> = (0..3)
+        .map(|sequence_number| create_signed_txn(0, sequence_number, 1))
+        .collect();
+    for txn in &txns {
+        assert_eq!(
+            add_signed_txn(&mempool, txn.clone()),
+            MempoolStatusCode::Accepted
+        );
+    }
+    let rejected_txn = create_signed_txn(0, 100, 1);
+    assert_eq!(
+        mempool
+            .add_txn(
+                rejected_txn,
+                1,
+                101,
+                TimelineState::NotReady,
+                false,
+                None,
+                None
+            )
+            .code,
+        MempoolStatusCode::InvalidSeqNumber
+    );
+
+    // Simulate a crash during a write by appending a truncated record
+    drop(mempool);
+    let mut journal_file = OpenOptions::new()
+        .append(true)
+        .open(journal_path.path())
+        .unwrap();
+    journal_file.write_all(&[100, 0, 0, 0, 1, 2, 3]).unwrap();
+
+    // Restart the mempool and verify that the accepted transactions are reloaded
+    let mempool = ShardedMempool::new(&config);
+    assert_eq!(mempool.take_journaled_transactions(), txns);
+    assert!(mempool.take_journaled_transactions().is_empty());
+}
+
+#[test]
+fn test_transaction_journal_compaction() {
+    // Open a journal and append several transactions
+    let journal_path = TempPath::new();
+    let (journal, journaled_txns) = TransactionJournal::open(journal_path.path()).unwrap();
+    assert!(journaled_txns.is_empty());
+    let txns: Vec<_> = (0..4)
+        .map(|sequence_number| create_signed_txn(0, sequence_number, 1))
+        .collect();
+    for txn in &txns[..3] {
+        journal.append(txn.clone()).unwrap();
+    }
+
+    // Compact the journal, keeping only the first transaction. A transaction is
+    // accepted concurrently (i.e., after the mempool hashes are collected), so it
+    // must be retained.
+    journal
+        .compact(|| {
+            journal.append(txns[3].clone()).unwrap();
+            [txns[0].committed_hash()].into_iter().collect()
+        })
+        .unwrap();
+
+    // Reopen the journal and verify the retained transactions
+    drop(journal);
+    let (_, journaled_txns) = TransactionJournal::open(journal_path.path()).unwrap();
+    assert_eq!(journaled_txns, vec![txns[0].clone(), txns[3].clone()]);
+}
+
+#[test]
+fn test_sharded_mempool_get_pending_transactions() {
+    let mempool = create_sharded_mempool(4, 4, 100);
+
+    // Add transactions for several senders (the last transaction is parked)
+    let txns = vec![
+        create_signed_txn(0, 0, 1),
+        create_signed_txn(1, 0, 5),
+        create_signed_txn(2, 0, 3),
+        create_signed_txn(0, 1, 2),
+        create_signed_txn(3, 2, 4),
+    ];
+    for txn in &txns {
+        assert_eq!(
+            add_signed_txn(&mempool, txn.clone()),
+            MempoolStatusCode::Accepted
+        );
+    }
+
+    // Page through all transactions and verify each is listed exactly once
+    let mut request = PendingTransactionsRequest {
+        limit: 2,
+        ..Default::default()
+    };
+    let mut listed_txns = vec![];
+    loop {
+        let page = mempool.get_pending_transactions(&request);
+        assert!(page.transactions.len() <= 2);
+        listed_txns.extend(page.transactions);
+        match page.next_start {
+            Some(next_start) => request.start = Some(next_start),
+            None => break,
+        }
+    }
+    assert_eq!(listed_txns.len(), txns.len());
+    for txn in &txns {
+        assert_eq!(
+            listed_txns
+                .iter()
+                .filter(|listed_txn| listed_txn.hash == txn.committed_hash())
+                .count(),
+            1
+        );
+    }
+
+    // Verify the listed transactions are in insertion order, and payloads are redacted
+    assert!(listed_txns
+        .windows(2)
+        .all(|pair| pair[0].cursor < pair[1].cursor));
+    assert!(listed_txns.iter().all(|txn| txn.payload.is_none()));
+
+    // Verify the filters are applied
+    let page = mempool.get_pending_transactions(&PendingTransactionsRequest {
+        limit: 10,
+        filter: PendingTransactionsFilter {
+            sender: Some(txns[0].sender()),
+            ..Default::default()
+        },
+        include_payloads: true,
+        ..Default::default()
+    });
+    assert_eq!(page.next_start, None);
+    assert_eq!(page.transactions.len(), 2);
+    for (listed_txn, txn) in page.transactions.iter().zip([&txns[0], &txns[3]]) {
+        assert_eq!(listed_txn.hash, txn.committed_hash());
+        assert_eq!(listed_txn.payload.as_ref(), Some(txn.payload()));
+    }
+
+    let page = mempool.get_pending_transactions(&PendingTransactionsRequest {
+        limit: 10,
+        filter: PendingTransactionsFilter {
+            min_gas_unit_price: Some(3),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    assert_eq!(page.transactions.len(), 3);
+    assert!(page.transactions.iter().all(|txn| txn.gas_unit_price >= 3));
+
+    let page = mempool.get_pending_transactions(&PendingTransactionsRequest {
+        limit: 10,
+        filter: PendingTransactionsFilter {
+            parked_only: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    assert_eq!(page.transactions.len(), 1);
+    assert_eq!(page.transactions[0].hash, txns[4].committed_hash());
+    assert!(page.transactions[0].parked);
+}
+
+/// Creates a sharded mempool with the given number of sender buckets, shards and total capacity
+fn create_sharded_mempool(
+    num_sender_buckets: u8,
+    num_mempool_shards: u8,
+    capacity: usize,
+) -> ShardedMempool {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.broadcast_buckets = vec![0];
+    config.mempool.num_sender_buckets = num_sender_buckets;
+    config.mempool.num_mempool_shards = num_mempool_shards;
+    config.mempool.capacity = capacity;
+    ShardedMempool::new(&config)
+}
+
+/// Creates a signed transaction for a sender that falls into the given sender bucket
+fn create_signed_txn(
+    sender_bucket: u8,
+    sequence_number: u64,
+    gas_unit_price: u64,
+) -> SignedTransaction {
+    let mut sender = [1u8; AccountAddress::LENGTH];
+    sender[AccountAddress::LENGTH - 1] = sender_bucket;
+    let raw_txn = RawTransaction::new_script(
+        AccountAddress::new(sender),
+        sequence_number,
+        Script::new(vec![], vec![], vec![]),
+        100,
+        gas_unit_price,
+        u64::MAX,
+        ChainId::test(),
+    );
+
+    let mut rng: StdRng = StdRng::from_seed([0u8; 32]);
+    let private_key = Ed25519PrivateKey::generate(&mut rng);
+    raw_txn
+        .sign(&private_key, private_key.public_key())
+        .expect("Failed to sign raw transaction.")
+        .into_inner()
+}
+
+/// Adds the transaction to the mempool and returns the resulting status code
+fn add_signed_txn(mempool: &ShardedMempool, txn: SignedTransaction) -> MempoolStatusCode {
+    mempool
+        .add_txn(
+            txn.clone(),
+            txn.gas_unit_price(),
+            0,
+            TimelineState::NotReady,
+            false,
+            None,
+            Some(BroadcastPeerPriority::Primary),
+        )
+        .code
+}
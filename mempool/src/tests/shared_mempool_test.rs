@@ -3,16 +3,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::sender_bucket,
+    core_mempool::{sender_bucket, IDEMPOTENT_TRANSACTION_MESSAGE},
     mocks::MockSharedMempool,
-    network::BroadcastPeerPriority,
-    tests::common::{batch_add_signed_txn, TestTransaction},
-    QuorumStoreRequest,
+    network::{
+        expiration_urgency_band, most_urgent_message, BroadcastDeliveryReceipt,
+        BroadcastPeerPriority,
+    },
+    shared_mempool::{tasks::gen_delivery_receipt, types::MempoolMessageId},
+    tests::common::TestTransaction,
+    MempoolTransactionStatus, QuorumStoreRequest,
 };
 use aptos_config::config::MempoolConfig;
-use aptos_consensus_types::common::RejectedTransactionSummary;
+use aptos_consensus_types::common::{RejectedTransactionSummary, TransactionRejectionReason};
 use aptos_mempool_notifications::MempoolNotificationSender;
-use aptos_types::{transaction::Transaction, vm_status::DiscardedVMStatus};
+use aptos_types::{
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    transaction::Transaction,
+    vm_status::DiscardedVMStatus,
+};
 use futures::{channel::oneshot, sink::SinkExt};
 use tokio::time::timeout;
 
@@ -37,16 +45,13 @@ async fn test_consensus_events_rejected_txns() {
         MempoolConfig::default().num_sender_buckets,
     );
     // Add txns to mempool
-    {
-        let mut pool = smp.mempool.lock();
-        assert!(batch_add_signed_txn(&mut pool, txns).is_ok());
-    }
+    assert!(smp.add_txns(txns).is_ok());
 
     let transactions = vec![RejectedTransactionSummary {
         sender: rejected_txn.sender(),
         sequence_number: rejected_txn.sequence_number(),
         hash: rejected_txn.committed_hash(),
-        reason: DiscardedVMStatus::MALFORMED,
+        reason: TransactionRejectionReason::Discarded(DiscardedVMStatus::MALFORMED),
     }];
     let (callback, callback_rcv) = oneshot::channel();
     let req = QuorumStoreRequest::RejectNotification(transactions, callback);
@@ -54,7 +59,7 @@ async fn test_consensus_events_rejected_txns() {
     assert!(consensus_sender.send(req).await.is_ok());
     assert!(callback_rcv.await.is_ok());
 
-    let pool = smp.mempool.lock();
+    let pool = &smp.mempool;
     // TODO: make less brittle to broadcast buckets changes
     if sender_bucket_1 != sender_bucket_2 {
         let (timeline, _) = pool.read_timeline(
@@ -90,7 +95,60 @@ async fn test_consensus_events_rejected_txns() {
     }
 }
 
-#[allow(clippy::await_holding_lock)] // This appears to be a false positive!
+#[tokio::test]
+async fn test_consensus_events_rejection_reasons() {
+    let smp = MockSharedMempool::new();
+
+    // Add txns 1 and 2
+    // Txn 1: rejected during execution
+    // Txn 2: dropped from the block by the block cut
+    let rejected_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let block_cut_txn = TestTransaction::new(1, 0, 1).make_signed_transaction();
+    assert!(smp
+        .add_txns(vec![rejected_txn.clone(), block_cut_txn.clone()])
+        .is_ok());
+
+    let transactions = vec![
+        RejectedTransactionSummary {
+            sender: rejected_txn.sender(),
+            sequence_number: rejected_txn.sequence_number(),
+            hash: rejected_txn.committed_hash(),
+            reason: TransactionRejectionReason::Discarded(
+                DiscardedVMStatus::GAS_UNIT_PRICE_BELOW_MIN_BOUND,
+            ),
+        },
+        RejectedTransactionSummary {
+            sender: block_cut_txn.sender(),
+            sequence_number: block_cut_txn.sequence_number(),
+            hash: block_cut_txn.committed_hash(),
+            reason: TransactionRejectionReason::BlockCut,
+        },
+    ];
+    let (callback, callback_rcv) = oneshot::channel();
+    let req = QuorumStoreRequest::RejectNotification(transactions, callback);
+    let mut consensus_sender = smp.consensus_to_mempool_sender.clone();
+    assert!(consensus_sender.send(req).await.is_ok());
+    assert!(callback_rcv.await.is_ok());
+
+    // Verify the rejected txn was removed (with its reason retained),
+    // and that the block cut txn was kept in mempool.
+    let pool = &smp.mempool;
+    assert!(pool.get_by_hash(rejected_txn.committed_hash()).is_none());
+    assert_eq!(
+        pool.get_by_hash(block_cut_txn.committed_hash()),
+        Some(block_cut_txn.clone())
+    );
+    let statuses = pool.get_statuses_by_hash(&[
+        rejected_txn.committed_hash(),
+        block_cut_txn.committed_hash(),
+    ]);
+    assert_eq!(
+        statuses[0],
+        MempoolTransactionStatus::Rejected(DiscardedVMStatus::GAS_UNIT_PRICE_BELOW_MIN_BOUND)
+    );
+    assert!(matches!(statuses[1], MempoolTransactionStatus::Pending(_)));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_mempool_notify_committed_txns() {
     // Create a new mempool notifier, listener and shared mempool
@@ -113,10 +171,7 @@ async fn test_mempool_notify_committed_txns() {
         MempoolConfig::default().num_sender_buckets,
     );
     // Add txns to mempool
-    {
-        let mut pool = smp.mempool.lock();
-        assert!(batch_add_signed_txn(&mut pool, txns).is_ok());
-    }
+    assert!(smp.add_txns(txns).is_ok());
 
     // Notify mempool of the new commit
     let committed_txns = vec![Transaction::UserTransaction(committed_txn)];
@@ -128,7 +183,7 @@ async fn test_mempool_notify_committed_txns() {
 
     // Wait until mempool handles the commit notification
     let wait_for_commit = async {
-        let pool = smp.mempool.lock();
+        let pool = &smp.mempool;
         // TODO: make less brittle to broadcast buckets changes
         let (timeline, _) = pool.read_timeline(
             sender_bucket,
@@ -152,3 +207,112 @@ async fn test_mempool_notify_committed_txns() {
         );
     }
 }
+
+#[test]
+fn test_expiration_urgency_band() {
+    // Verify that all transactions are in the same band if no bands are configured
+    assert_eq!(expiration_urgency_band(&[], 1010, 1000), 0);
+    assert_eq!(expiration_urgency_band(&[], 5000, 1000), 0);
+
+    // Verify that transactions are assigned to the first band they fall into
+    let urgency_bands_secs = [30, 120];
+    assert_eq!(expiration_urgency_band(&urgency_bands_secs, 1010, 1000), 0);
+    assert_eq!(expiration_urgency_band(&urgency_bands_secs, 1030, 1000), 0);
+    assert_eq!(expiration_urgency_band(&urgency_bands_secs, 1100, 1000), 1);
+    assert_eq!(expiration_urgency_band(&urgency_bands_secs, 2000, 1000), 2);
+
+    // Verify that expired transactions are the most urgent
+    assert_eq!(expiration_urgency_band(&urgency_bands_secs, 900, 1000), 0);
+}
+
+#[test]
+fn test_most_urgent_message() {
+    let urgency_bands_secs = [30, 120];
+    let now_secs = 1000;
+    let old_message_id = MempoolMessageId(vec![(0, 10)]);
+    let new_message_id = MempoolMessageId(vec![(10, 20)]);
+    let select_message = |candidates: Vec<(&MempoolMessageId, Option<u64>)>| {
+        most_urgent_message(&urgency_bands_secs, candidates.into_iter(), now_secs).cloned()
+    };
+
+    // Verify that the message holding the transaction closest to expiration is picked
+    assert_eq!(
+        select_message(vec![
+            (&old_message_id, Some(1010)),
+            (&new_message_id, Some(2000)),
+        ]),
+        Some(old_message_id.clone())
+    );
+    assert_eq!(
+        select_message(vec![
+            (&old_message_id, Some(1100)),
+            (&new_message_id, Some(1020)),
+        ]),
+        Some(new_message_id.clone())
+    );
+
+    // Verify that ties (within the same urgency band) are broken in favor of the latest message
+    assert_eq!(
+        select_message(vec![
+            (&old_message_id, Some(1010)),
+            (&new_message_id, Some(1020)),
+        ]),
+        Some(new_message_id.clone())
+    );
+
+    // Verify that messages without any transactions left in mempool are skipped
+    assert_eq!(
+        select_message(vec![(&old_message_id, Some(2000)), (&new_message_id, None)]),
+        Some(old_message_id.clone())
+    );
+    assert_eq!(
+        select_message(vec![(&old_message_id, None), (&new_message_id, None)]),
+        None
+    );
+}
+
+#[test]
+fn test_gen_delivery_receipt() {
+    // Create the results of processing a broadcast
+    let held_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let new_txn = TestTransaction::new(0, 1, 1).make_signed_transaction();
+    let committed_txn = TestTransaction::new(1, 0, 1).make_signed_transaction();
+    let invalid_txn = TestTransaction::new(2, 0, 1).make_signed_transaction();
+    let results = vec![
+        (
+            held_txn,
+            (
+                MempoolStatus::new(MempoolStatusCode::Accepted)
+                    .with_message(IDEMPOTENT_TRANSACTION_MESSAGE.to_string()),
+                None,
+            ),
+        ),
+        (
+            new_txn,
+            (MempoolStatus::new(MempoolStatusCode::Accepted), None),
+        ),
+        (
+            committed_txn,
+            (
+                MempoolStatus::new(MempoolStatusCode::VmError),
+                Some(DiscardedVMStatus::SEQUENCE_NUMBER_TOO_OLD),
+            ),
+        ),
+        (
+            invalid_txn,
+            (
+                MempoolStatus::new(MempoolStatusCode::VmError),
+                Some(DiscardedVMStatus::INVALID_SIGNATURE),
+            ),
+        ),
+    ];
+
+    // Verify the held and committed transactions are duplicates (and the invalid one is ignored)
+    assert_eq!(
+        gen_delivery_receipt(&results),
+        BroadcastDeliveryReceipt {
+            num_new_transactions: 1,
+            num_duplicate_transactions: 2,
+        }
+    );
+}
@@ -3,22 +3,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::CoreMempool,
+    core_mempool::ShardedMempool,
     shared_mempool::{
         start_shared_mempool,
+        transaction_filter::TransactionHashFilter,
         types::{MempoolMessageId, MempoolSenderBucket},
     },
     tests::common::{self, TestTransaction},
-    MempoolClientRequest, MempoolClientSender, MempoolSyncMsg, QuorumStoreRequest,
+    MempoolClientRequest, MempoolClientSender, MempoolLoadMonitor, MempoolSyncMsg,
+    QuorumStoreRequest,
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
     config::NodeConfig,
     network_id::{NetworkId, PeerNetworkId},
 };
+use aptos_crypto::HashValue;
 use aptos_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
 use aptos_id_generator::U32IdGenerator;
-use aptos_infallible::{Mutex, RwLock};
+use aptos_infallible::RwLock;
 use aptos_mempool_notifications::MempoolNotifier;
 use aptos_network::{
     application::{
@@ -31,7 +34,7 @@ use aptos_network::{
             NetworkEvents, NetworkSender, NewNetworkEvents, NewNetworkSender, ReceivedMessage,
         },
         wire::{
-            handshake::v1::ProtocolId::MempoolDirectSend,
+            handshake::v1::ProtocolId::{MempoolDirectSend, MempoolRpc},
             messaging::v1::{DirectSendMsg, NetworkMessage, RpcRequest},
         },
     },
@@ -44,6 +47,7 @@ use aptos_network::{
     },
     ProtocolId,
 };
+use aptos_peer_monitoring_service_types::MempoolUtilization;
 use aptos_storage_interface::mock::MockDbReaderWriter;
 use aptos_types::{
     account_address::AccountAddress,
@@ -63,8 +67,8 @@ use tokio_stream::StreamExt;
 /// TODO: Add ability to mock StateSync updates to remove transactions
 /// TODO: Add ability to reject transactions via Consensus
 pub struct MempoolNode {
-    /// The [`CoreMempool`] storage of the node
-    pub mempool: Arc<Mutex<CoreMempool>>,
+    /// The [`ShardedMempool`] storage of the node
+    pub mempool: Arc<ShardedMempool>,
     /// A generator for [`MempoolSyncMsg`] request ids.
     pub request_id_generator: U32IdGenerator,
 
@@ -98,7 +102,7 @@ impl ApplicationNode for MempoolNode {
     }
 
     fn default_protocols(&self) -> &[ProtocolId] {
-        &[ProtocolId::MempoolDirectSend]
+        &[ProtocolId::MempoolDirectSend, ProtocolId::MempoolRpc]
     }
 
     fn get_inbound_handle(&self, network_id: NetworkId) -> InboundNetworkHandle {
@@ -163,7 +167,6 @@ impl MempoolNode {
     pub async fn commit_txns(&mut self, txns: &[TestTransaction]) {
         for txn in sign_transactions(txns) {
             self.mempool
-                .lock()
                 .commit_transaction(&txn.sender(), txn.sequence_number());
         }
     }
@@ -171,10 +174,7 @@ impl MempoolNode {
     /// Asynchronously waits for up to 1 second for txns to appear in mempool
     pub async fn wait_on_txns_in_mempool(&self, txns: &[TestTransaction]) {
         for _ in 0..10 {
-            let block = self
-                .mempool
-                .lock()
-                .get_batch(100, 102400, true, btreemap![]);
+            let block = self.mempool.get_batch(100, 102400, true, btreemap![]);
 
             if block_contains_all_transactions(&block, txns) {
                 break;
@@ -224,10 +224,7 @@ impl MempoolNode {
         txns: &[TestTransaction],
         condition: Condition,
     ) -> Result<(), (Vec<(AccountAddress, u64)>, Vec<(AccountAddress, u64)>)> {
-        let block = self
-            .mempool
-            .lock()
-            .get_batch(100, 102400, true, btreemap![]);
+        let block = self.mempool.get_batch(100, 102400, true, btreemap![]);
         if !condition(&block, txns) {
             let actual: Vec<_> = block
                 .iter()
@@ -329,6 +326,163 @@ impl MempoolNode {
         }
     }
 
+    /// Sends a message from the given remote peer to the node
+    fn send_message_from_peer(&self, remote_peer_network_id: PeerNetworkId, msg: MempoolSyncMsg) {
+        let network_id = remote_peer_network_id.network_id();
+        let remote_peer_id = remote_peer_network_id.peer_id();
+        let protocol_id = ProtocolId::MempoolDirectSend;
+        let notif = ReceivedMessage {
+            message: NetworkMessage::DirectSendMsg(DirectSendMsg {
+                protocol_id,
+                priority: 0,
+                raw_msg: protocol_id.to_bytes(&msg).unwrap(),
+            }),
+            sender: remote_peer_network_id,
+            receive_timestamp_micros: 0,
+            rpc_replier: None,
+        };
+        self.get_inbound_handle(network_id)
+            .inbound_message_sender
+            .push((remote_peer_id, protocol_id), notif)
+            .unwrap();
+    }
+
+    /// Sends an RPC request from the given remote peer to the node, and returns the response
+    async fn send_rpc_from_peer(
+        &self,
+        remote_peer_network_id: PeerNetworkId,
+        msg: MempoolSyncMsg,
+    ) -> MempoolSyncMsg {
+        let network_id = remote_peer_network_id.network_id();
+        let remote_peer_id = remote_peer_network_id.peer_id();
+        let protocol_id = ProtocolId::MempoolRpc;
+        let (res_tx, res_rx) = oneshot::channel();
+        let notif = ReceivedMessage {
+            message: NetworkMessage::RpcRequest(RpcRequest {
+                protocol_id,
+                request_id: 0,
+                priority: 0,
+                raw_request: protocol_id.to_bytes(&msg).unwrap(),
+            }),
+            sender: remote_peer_network_id,
+            receive_timestamp_micros: 0,
+            rpc_replier: Some(Arc::new(res_tx)),
+        };
+        self.get_inbound_handle(network_id)
+            .inbound_message_sender
+            .push((remote_peer_id, protocol_id), notif)
+            .unwrap();
+
+        let response = res_rx.await.unwrap().unwrap();
+        protocol_id.from_bytes(&response).unwrap()
+    }
+
+    /// Requests the given transactions from the node (on behalf of the given remote
+    /// peer), and returns the transactions in the response.
+    pub async fn request_transactions_from_node(
+        &mut self,
+        remote_peer_network_id: PeerNetworkId,
+        txns: &[TestTransaction],
+    ) -> Vec<SignedTransaction> {
+        let hashes = sign_transactions(txns)
+            .iter()
+            .map(|txn| txn.committed_hash())
+            .collect();
+        match self
+            .send_rpc_from_peer(
+                remote_peer_network_id,
+                MempoolSyncMsg::RequestTransactions { hashes },
+            )
+            .await
+        {
+            MempoolSyncMsg::RequestTransactionsResponse { transactions } => transactions,
+            message => panic!("Expected a transaction response, got {:?}", message),
+        }
+    }
+
+    /// Sends the given transactions to the node as a transaction response that
+    /// doesn't belong to any request (i.e., outside of an RPC).
+    pub fn send_unsolicited_transaction_response_to_node(
+        &self,
+        remote_peer_network_id: PeerNetworkId,
+        txns: &[TestTransaction],
+    ) {
+        self.send_message_from_peer(
+            remote_peer_network_id,
+            MempoolSyncMsg::RequestTransactionsResponse {
+                transactions: sign_transactions(txns),
+            },
+        );
+    }
+
+    /// Sends a transaction filter (holding the given transactions) to the node, on
+    /// behalf of the given remote peer
+    pub fn send_transaction_filter_to_node(
+        &self,
+        remote_peer_network_id: PeerNetworkId,
+        txns: &[TestTransaction],
+    ) {
+        let hashes: Vec<_> = sign_transactions(txns)
+            .iter()
+            .map(|txn| txn.committed_hash())
+            .collect();
+        self.send_message_from_peer(
+            remote_peer_network_id,
+            MempoolSyncMsg::TransactionFilterUpdate {
+                filter: TransactionHashFilter::new(&hashes, 10),
+            },
+        );
+    }
+
+    /// Asks the node (via the client) to fetch the requested transactions from the given
+    /// remote peer, and serves the request (on behalf of the peer) with the given served
+    /// transactions. Returns the hashes requested from the peer, and the number of
+    /// transactions accepted by the node.
+    pub async fn fetch_transactions_via_client(
+        &mut self,
+        remote_peer_network_id: PeerNetworkId,
+        requested_txns: &[TestTransaction],
+        served_txns: &[TestTransaction],
+    ) -> (Vec<HashValue>, usize) {
+        let hashes = sign_transactions(requested_txns)
+            .iter()
+            .map(|txn| txn.committed_hash())
+            .collect();
+        let (sender, receiver) = oneshot::channel();
+        self.mempool_client_sender
+            .send(MempoolClientRequest::FetchTransactions(
+                remote_peer_network_id,
+                hashes,
+                sender,
+            ))
+            .await
+            .unwrap();
+
+        // Wait for the transaction request (ignoring any broadcasts sent in the meantime)
+        let network_id = remote_peer_network_id.network_id();
+        let requested_hashes = loop {
+            match self.get_next_network_msg(network_id).await {
+                PeerManagerRequest::SendRpc(peer_id, msg) => {
+                    assert_eq!(peer_id, remote_peer_network_id.peer_id());
+                    let hashes = match msg.protocol_id.from_bytes(&msg.data).unwrap() {
+                        MempoolSyncMsg::RequestTransactions { hashes } => hashes,
+                        message => panic!("Expected a transaction request, got {:?}", message),
+                    };
+                    let response = MempoolSyncMsg::RequestTransactionsResponse {
+                        transactions: sign_transactions(served_txns),
+                    };
+                    let bytes = msg.protocol_id.to_bytes(&response).unwrap();
+                    msg.res_tx.send(Ok(bytes.into())).unwrap();
+                    break hashes;
+                },
+                PeerManagerRequest::SendDirectSend(..) => {},
+            }
+        };
+
+        let num_accepted_txns = receiver.await.unwrap().unwrap();
+        (requested_hashes, num_accepted_txns)
+    }
+
     pub async fn send_broadcast_and_receive_ack(
         &mut self,
         expected_peer_network_id: PeerNetworkId,
@@ -410,6 +564,10 @@ impl MempoolNode {
             MempoolSyncMsg::BroadcastTransactionsRequestWithReadyTime {
                 message_id,
                 transactions,
+            }
+            | MempoolSyncMsg::BroadcastTransactionsRequestWithReceipt {
+                message_id,
+                transactions,
             } => {
                 let transactions: Vec<_> =
                     transactions.iter().map(|(txn, _, _)| txn.clone()).collect();
@@ -435,9 +593,17 @@ impl MempoolNode {
                 }
                 message_id
             },
-            MempoolSyncMsg::BroadcastTransactionsResponse { .. } => {
+            MempoolSyncMsg::BroadcastTransactionsResponse { .. }
+            | MempoolSyncMsg::BroadcastTransactionsResponseWithReceipt { .. } => {
                 panic!("We aren't supposed to be getting as response here");
             },
+            MempoolSyncMsg::TransactionFilterUpdate { .. } => {
+                panic!("We aren't supposed to be getting a transaction filter here");
+            },
+            MempoolSyncMsg::RequestTransactions { .. }
+            | MempoolSyncMsg::RequestTransactionsResponse { .. } => {
+                panic!("We aren't supposed to be getting a transaction fetch message here");
+            },
         };
         let response = MempoolSyncMsg::BroadcastTransactionsResponse {
             message_id,
@@ -471,6 +637,15 @@ impl TestNode for MempoolNode {}
 
 pub type MempoolTestFrameworkBuilder = TestFrameworkBuilder<MempoolTestFramework, MempoolNode>;
 
+/// Builds a single validator fullnode [`MempoolNode`] with the given (e.g., mempool) config
+pub fn single_vfn_with_config(config: NodeConfig) -> MempoolNode {
+    let peer_id = AccountAddress::random();
+    MempoolTestFramework::build_node(NodeId::vfn(0), config, &[
+        PeerNetworkId::new(NetworkId::Vfn, peer_id),
+        PeerNetworkId::new(NetworkId::Public, peer_id),
+    ])
+}
+
 /// A [`TestFramework`] for [`MempoolNode`]s to test Mempool in a single and multi-node mock network
 /// environment.
 pub struct MempoolTestFramework {
@@ -565,7 +740,7 @@ pub fn setup_node_networks(
     // Create a network client and service events
     let network_client = NetworkClient::new(
         vec![MempoolDirectSend],
-        vec![],
+        vec![MempoolRpc],
         network_senders,
         peers_and_metadata.clone(),
     );
@@ -628,7 +803,7 @@ fn setup_mempool(
     MempoolClientSender,
     futures::channel::mpsc::Sender<QuorumStoreRequest>,
     MempoolNotifier,
-    Arc<Mutex<CoreMempool>>,
+    Arc<ShardedMempool>,
 ) {
     let (sender, _subscriber) = futures::channel::mpsc::unbounded();
     let (ac_endpoint_sender, ac_endpoint_receiver) = mpsc_channel();
@@ -636,7 +811,7 @@ fn setup_mempool(
     let (mempool_notifier, mempool_listener) =
         aptos_mempool_notifications::new_mempool_notifier_listener_pair(100);
 
-    let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
+    let mempool = Arc::new(ShardedMempool::new(&config));
     let vm_validator = Arc::new(RwLock::new(MockVMValidator));
     let db_ro = Arc::new(MockDbReaderWriter);
 
@@ -668,6 +843,9 @@ fn setup_mempool(
         vm_validator,
         vec![sender],
         peers_and_metadata,
+        MempoolUtilization::default(),
+        MempoolLoadMonitor::default(),
+        Arc::new(RwLock::new(vec![])),
     );
 
     (
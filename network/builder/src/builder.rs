@@ -13,16 +13,19 @@
 use aptos_config::{
     config::{
         DiscoveryMethod, NetworkConfig, Peer, PeerRole, PeerSet, RoleType, CONNECTION_BACKOFF_BASE,
-        CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONNECTION_DELAY_MS, MAX_FRAME_SIZE,
-        MAX_FULLNODE_OUTBOUND_CONNECTIONS, MAX_INBOUND_CONNECTIONS, NETWORK_CHANNEL_SIZE,
+        CONNECTIVITY_CHECK_INTERVAL_MS, HALF_OPEN_CONNECTION_TIMEOUT_MS, MAX_CONNECTION_DELAY_MS,
+        MAX_FRAME_SIZE, MAX_FULLNODE_OUTBOUND_CONNECTIONS, MAX_INBOUND_CONNECTIONS,
+        NETWORK_CHANNEL_SIZE,
     },
     network_id::NetworkContext,
 };
-use aptos_event_notifications::{DbBackedOnChainConfig, EventSubscriptionService};
+use aptos_event_notifications::{
+    DbBackedOnChainConfig, EventSubscriptionService, ReconfigNotificationListener,
+};
 use aptos_logger::prelude::*;
 use aptos_netcore::transport::tcp::TCPBufferCfg;
 use aptos_network::{
-    application::storage::PeersAndMetadata,
+    application::{rate_limit::ProtocolRateLimits, storage::PeersAndMetadata},
     connectivity_manager::{builder::ConnectivityManagerBuilder, ConnectivityRequest},
     constants::MAX_MESSAGE_SIZE,
     logging::NetworkSchema,
@@ -41,6 +44,7 @@ use aptos_network::{
 use aptos_network_discovery::DiscoveryChangeListener;
 use aptos_time_service::TimeService;
 use aptos_types::{chain_id::ChainId, network_address::NetworkAddress};
+use futures::StreamExt;
 use std::{clone::Clone, collections::HashSet, sync::Arc, time::Duration};
 use tokio::runtime::Handle;
 
@@ -66,6 +70,7 @@ pub struct NetworkBuilder {
     health_checker_builder: Option<HealthCheckerBuilder>,
     peer_manager_builder: PeerManagerBuilder,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    deprecation_epoch_listener: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
 }
 
 impl NetworkBuilder {
@@ -85,6 +90,8 @@ impl NetworkBuilder {
         network_channel_size: usize,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
+        half_open_connection_timeout: Option<Duration>,
+        protocol_rate_limits: ProtocolRateLimits,
     ) -> Self {
         // A network cannot exist without a PeerManager
         // TODO:  construct this in create and pass it to new() as a parameter. The complication is manual construction of NetworkBuilder in various tests.
@@ -101,6 +108,8 @@ impl NetworkBuilder {
             enable_proxy_protocol,
             inbound_connection_limit,
             tcp_buffer_cfg,
+            half_open_connection_timeout,
+            protocol_rate_limits,
         );
 
         NetworkBuilder {
@@ -113,6 +122,7 @@ impl NetworkBuilder {
             health_checker_builder: None,
             peer_manager_builder,
             peers_and_metadata,
+            deprecation_epoch_listener: None,
         }
     }
 
@@ -140,6 +150,8 @@ impl NetworkBuilder {
             NETWORK_CHANNEL_SIZE,
             MAX_INBOUND_CONNECTIONS,
             TCPBufferCfg::default(),
+            Some(Duration::from_millis(HALF_OPEN_CONNECTION_TIMEOUT_MS)),
+            ProtocolRateLimits::default(),
         );
 
         builder.add_connectivity_manager(
@@ -163,7 +175,7 @@ impl NetworkBuilder {
         role: RoleType,
         config: &NetworkConfig,
         time_service: TimeService,
-        reconfig_subscription_service: Option<&mut EventSubscriptionService>,
+        mut reconfig_subscription_service: Option<&mut EventSubscriptionService>,
         peers_and_metadata: Arc<PeersAndMetadata>,
     ) -> NetworkBuilder {
         let peer_id = config.peer_id();
@@ -195,6 +207,10 @@ impl NetworkBuilder {
                 config.outbound_rx_buffer_size_bytes,
                 config.outbound_tx_buffer_size_bytes,
             ),
+            config
+                .half_open_connection_timeout_ms
+                .map(Duration::from_millis),
+            ProtocolRateLimits::from_config(&network_context, &config.protocol_rate_limits),
         );
 
         network_builder.add_connection_monitoring(
@@ -219,6 +235,9 @@ impl NetworkBuilder {
             config.enable_latency_aware_dialing,
         );
 
+        network_builder
+            .setup_protocol_deprecations(config, reconfig_subscription_service.as_deref_mut());
+
         network_builder.discovery_listeners = Some(Vec::new());
         network_builder.setup_discovery(config, reconfig_subscription_service);
 
@@ -281,6 +300,19 @@ impl NetworkBuilder {
                 .into_iter()
                 .for_each(|listener| listener.start(executor))
         }
+
+        if let Some(mut reconfig_events) = self.deprecation_epoch_listener.take() {
+            let protocol_deprecations = self.peers_and_metadata.get_protocol_deprecations();
+            executor.spawn(async move {
+                while let Some(notification) = reconfig_events.next().await {
+                    protocol_deprecations.update_epoch(notification.on_chain_configs.epoch());
+                }
+            });
+            debug!(
+                NetworkSchema::new(&self.network_context),
+                "{} Started protocol deprecation epoch tracking", self.network_context
+            );
+        }
         self
     }
 
@@ -345,6 +377,38 @@ impl NetworkBuilder {
         self
     }
 
+    /// Deprecates the protocols configured for this network, and subscribes to
+    /// reconfigurations so that the protocols are sunset at the configured epochs.
+    fn setup_protocol_deprecations(
+        &mut self,
+        config: &NetworkConfig,
+        reconfig_subscription_service: Option<&mut EventSubscriptionService>,
+    ) {
+        if config.deprecated_protocols.is_empty() {
+            return;
+        }
+
+        self.peers_and_metadata
+            .get_protocol_deprecations()
+            .deprecate_protocols_from_config(&self.network_context, &config.deprecated_protocols);
+        match reconfig_subscription_service {
+            Some(reconfig_subscription_service) => {
+                let reconfig_events = reconfig_subscription_service
+                    .subscribe_to_reconfigurations()
+                    .expect("Protocol deprecations are unable to subscribe to reconfigurations!");
+                self.deprecation_epoch_listener = Some(reconfig_events);
+            },
+            None => {
+                warn!(
+                    NetworkSchema::new(&self.network_context),
+                    "{} No event subscription service is available, so deprecated protocols \
+                     will not be sunset!",
+                    self.network_context
+                );
+            },
+        }
+    }
+
     fn setup_discovery(
         &mut self,
         config: &NetworkConfig,
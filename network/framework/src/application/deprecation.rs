@@ -0,0 +1,255 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{logging::NetworkSchema, protocols::wire::handshake::v1::ProtocolIdSet, ProtocolId};
+use aptos_config::network_id::{NetworkContext, NetworkId};
+use aptos_infallible::RwLock;
+use aptos_logger::error;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+/// The deprecation status of a protocol at the current epoch
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProtocolStatus {
+    Supported,                        // The protocol is not deprecated
+    Deprecated { sunset_epoch: u64 }, // The protocol still works, but will be rejected at the sunset epoch
+    Sunset { sunset_epoch: u64 },     // The protocol is no longer accepted (inbound or outbound)
+}
+
+impl ProtocolStatus {
+    /// Returns true iff the protocol has been deprecated (but not yet sunset)
+    pub fn is_deprecated(&self) -> bool {
+        matches!(self, ProtocolStatus::Deprecated { .. })
+    }
+
+    /// Returns true iff the protocol has been sunset
+    pub fn is_sunset(&self) -> bool {
+        matches!(self, ProtocolStatus::Sunset { .. })
+    }
+}
+
+/// Tracks the protocols that have been deprecated by this node (per network),
+/// along with the epoch at which each protocol will be sunset (i.e., rejected).
+/// This is shared between the network framework (which enforces the deprecations
+/// on all inbound and outbound messages, and stops advertising sunset protocols
+/// in the handshake) and the network builder (which updates the epoch).
+#[derive(Clone, Debug, Default)]
+pub struct ProtocolDeprecations {
+    current_epoch: Arc<AtomicU64>,
+    sunset_epochs: Arc<RwLock<HashMap<(NetworkId, ProtocolId), u64>>>,
+}
+
+impl ProtocolDeprecations {
+    /// Deprecates the protocols in the given config (keyed by protocol name, and
+    /// mapped to the sunset epochs) on the given network. Unknown protocol names
+    /// are rejected by the config sanitizer (and are otherwise ignored here).
+    pub fn deprecate_protocols_from_config(
+        &self,
+        network_context: &NetworkContext,
+        config: &HashMap<String, u64>,
+    ) {
+        for (protocol_name, sunset_epoch) in config {
+            let protocol_id = ProtocolId::all()
+                .iter()
+                .find(|protocol_id| protocol_id.as_str() == protocol_name);
+            match protocol_id {
+                Some(protocol_id) => self.deprecate_protocol(
+                    network_context.network_id(),
+                    *protocol_id,
+                    *sunset_epoch,
+                ),
+                None => {
+                    error!(
+                        NetworkSchema::new(network_context),
+                        "{} Ignoring deprecation of unknown protocol: {}",
+                        network_context,
+                        protocol_name
+                    );
+                },
+            }
+        }
+    }
+
+    /// Marks the given protocol as deprecated on the given network, to be sunset at the given epoch
+    pub fn deprecate_protocol(
+        &self,
+        network_id: NetworkId,
+        protocol_id: ProtocolId,
+        sunset_epoch: u64,
+    ) {
+        self.sunset_epochs
+            .write()
+            .insert((network_id, protocol_id), sunset_epoch);
+    }
+
+    /// Removes any deprecation for the given protocol on the given network
+    pub fn remove_deprecation(&self, network_id: NetworkId, protocol_id: ProtocolId) {
+        self.sunset_epochs
+            .write()
+            .remove(&(network_id, protocol_id));
+    }
+
+    /// Returns all deprecated protocols (and their sunset epochs) on the given network
+    pub fn get_deprecated_protocols(&self, network_id: NetworkId) -> HashMap<ProtocolId, u64> {
+        self.sunset_epochs
+            .read()
+            .iter()
+            .filter(|((deprecated_network_id, _), _)| *deprecated_network_id == network_id)
+            .map(|((_, protocol_id), sunset_epoch)| (*protocol_id, *sunset_epoch))
+            .collect()
+    }
+
+    /// Returns the current epoch known to the deprecation tracker
+    pub fn get_current_epoch(&self) -> u64 {
+        self.current_epoch.load(Ordering::Relaxed)
+    }
+
+    /// Updates the current epoch. Epochs only move forward, so stale
+    /// updates are ignored.
+    pub fn update_epoch(&self, epoch: u64) {
+        self.current_epoch.fetch_max(epoch, Ordering::Relaxed);
+    }
+
+    /// Returns the given protocols, excluding those sunset on the given network.
+    /// This is used to stop advertising sunset protocols in the handshake, so
+    /// that peers negotiate the remaining protocols instead.
+    pub fn remove_sunset_protocols(
+        &self,
+        network_id: NetworkId,
+        protocols: &ProtocolIdSet,
+    ) -> ProtocolIdSet {
+        protocols
+            .iter()
+            .filter(|protocol_id| {
+                !self
+                    .get_protocol_status(network_id, *protocol_id)
+                    .is_sunset()
+            })
+            .collect()
+    }
+
+    /// Returns the status of the given protocol (on the given network) at the current epoch
+    pub fn get_protocol_status(
+        &self,
+        network_id: NetworkId,
+        protocol_id: ProtocolId,
+    ) -> ProtocolStatus {
+        match self.sunset_epochs.read().get(&(network_id, protocol_id)) {
+            None => ProtocolStatus::Supported,
+            Some(sunset_epoch) => {
+                let sunset_epoch = *sunset_epoch;
+                if self.get_current_epoch() >= sunset_epoch {
+                    ProtocolStatus::Sunset { sunset_epoch }
+                } else {
+                    ProtocolStatus::Deprecated { sunset_epoch }
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProtocolDeprecations, ProtocolStatus};
+    use crate::{protocols::wire::handshake::v1::ProtocolIdSet, ProtocolId};
+    use aptos_config::{
+        config::RoleType,
+        network_id::{NetworkContext, NetworkId},
+    };
+    use aptos_types::PeerId;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_protocol_status_transitions() {
+        // Create the deprecations tracker and deprecate a protocol
+        let protocol_deprecations = ProtocolDeprecations::default();
+        let network_id = NetworkId::Validator;
+        let protocol_id = ProtocolId::MempoolDirectSend;
+        protocol_deprecations.deprecate_protocol(network_id, protocol_id, 10);
+
+        // Verify the protocol is deprecated (but not sunset) before the sunset epoch
+        protocol_deprecations.update_epoch(9);
+        assert_eq!(
+            protocol_deprecations.get_protocol_status(network_id, protocol_id),
+            ProtocolStatus::Deprecated { sunset_epoch: 10 }
+        );
+
+        // Verify other protocols (and other networks) are unaffected
+        assert_eq!(
+            protocol_deprecations.get_protocol_status(network_id, ProtocolId::ConsensusRpcBcs),
+            ProtocolStatus::Supported
+        );
+        assert_eq!(
+            protocol_deprecations.get_protocol_status(NetworkId::Public, protocol_id),
+            ProtocolStatus::Supported
+        );
+
+        // Verify the protocol is sunset at the sunset epoch
+        protocol_deprecations.update_epoch(10);
+        assert!(protocol_deprecations
+            .get_protocol_status(network_id, protocol_id)
+            .is_sunset());
+
+        // Verify stale epoch updates are ignored
+        protocol_deprecations.update_epoch(5);
+        assert_eq!(protocol_deprecations.get_current_epoch(), 10);
+        assert!(protocol_deprecations
+            .get_protocol_status(network_id, protocol_id)
+            .is_sunset());
+
+        // Remove the deprecation and verify the protocol is supported again
+        protocol_deprecations.remove_deprecation(network_id, protocol_id);
+        assert_eq!(
+            protocol_deprecations.get_protocol_status(network_id, protocol_id),
+            ProtocolStatus::Supported
+        );
+    }
+
+    #[test]
+    fn test_deprecations_from_config() {
+        // Deprecate a known and an unknown protocol using the config
+        let protocol_deprecations = ProtocolDeprecations::default();
+        let network_context =
+            NetworkContext::new(RoleType::Validator, NetworkId::Vfn, PeerId::random());
+        let config = HashMap::from([
+            (ProtocolId::MempoolDirectSend.as_str().to_string(), 3),
+            ("UnknownProtocol".to_string(), 3),
+        ]);
+        protocol_deprecations.deprecate_protocols_from_config(&network_context, &config);
+
+        // Verify only the known protocol is deprecated (on the configured network)
+        assert_eq!(
+            protocol_deprecations.get_deprecated_protocols(NetworkId::Vfn),
+            HashMap::from([(ProtocolId::MempoolDirectSend, 3)])
+        );
+        assert!(protocol_deprecations
+            .get_deprecated_protocols(NetworkId::Public)
+            .is_empty());
+
+        // Verify the protocol is still advertised before the sunset epoch
+        let protocols = ProtocolIdSet::from_iter([
+            ProtocolId::MempoolDirectSend,
+            ProtocolId::StorageServiceRpc,
+        ]);
+        assert_eq!(
+            protocol_deprecations.remove_sunset_protocols(NetworkId::Vfn, &protocols),
+            protocols
+        );
+
+        // Verify the protocol is no longer advertised once it is sunset
+        protocol_deprecations.update_epoch(3);
+        assert_eq!(
+            protocol_deprecations.remove_sunset_protocols(NetworkId::Vfn, &protocols),
+            ProtocolIdSet::from_iter([ProtocolId::StorageServiceRpc])
+        );
+        assert_eq!(
+            protocol_deprecations.remove_sunset_protocols(NetworkId::Public, &protocols),
+            protocols
+        );
+    }
+}
@@ -11,6 +11,12 @@ pub enum Error {
     NetworkError(String),
     #[error("Rpc error encountered: {0}")]
     RpcError(String),
+    #[error("Protocol {0} was sunset at epoch {1}")]
+    ProtocolSunset(String, u64),
+    #[error("No common protocol with the peer: {0}")]
+    NoCommonProtocol(String),
+    #[error("Send queue is full for the peer: {0}")]
+    SendQueueFull(String),
     #[error("Unexpected error encountered: {0}")]
     UnexpectedError(String),
 }
@@ -3,9 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    application::{error::Error, storage::PeersAndMetadata},
+    application::{
+        deprecation::ProtocolStatus, error::Error, storage::PeersAndMetadata,
+        traffic::ProtocolTrafficStats,
+    },
+    counters,
     protocols::{
         network::{Message, NetworkEvents, NetworkSender},
+        rpc::error::RpcError,
         wire::handshake::v1::{ProtocolId, ProtocolIdSet},
     },
 };
@@ -15,13 +20,77 @@ use aptos_types::{network_address::NetworkAddress, PeerId};
 use async_trait::async_trait;
 use bytes::Bytes;
 use itertools::Itertools;
-use std::{collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio_retry::strategy::jitter;
 
 /// A simple definition to handle all the trait bounds for messages.
 // TODO: we should remove the duplication across the different files
 pub trait NetworkMessageTrait: Clone + Message + Send + Sync + 'static {}
 impl<T: Clone + Message + Send + Sync + 'static> NetworkMessageTrait for T {}
 
+/// The policy used to retry failed RPCs (e.g., if the preferred protocol fails,
+/// or if the peer disconnects mid-request). Retries are attempted on the next
+/// supported protocol of the peer (if the protocol failed), or on the next
+/// available peer (if the peer failed), using a jittered exponential backoff.
+#[derive(Clone, Debug)]
+pub struct RpcRetryPolicy {
+    /// The maximum number of retries (after the initial attempt)
+    pub max_retries: u64,
+    /// Whether to retry on the next supported protocol of the peer
+    pub retry_on_next_protocol: bool,
+    /// Whether to retry on the next available peer (on the same network)
+    pub retry_on_next_peer: bool,
+    /// The backoff before the first retry (doubled for each subsequent retry)
+    pub initial_backoff: Duration,
+    /// The maximum backoff between retries
+    pub max_backoff: Duration,
+    /// The total deadline for all attempts (including backoffs)
+    pub total_deadline: Duration,
+}
+
+impl RpcRetryPolicy {
+    /// Returns the (un-jittered) backoff before the given retry
+    fn get_backoff(&self, num_retries: u64) -> Duration {
+        let multiplier = 2u32.saturating_pow(num_retries.min(u32::MAX as u64) as u32);
+        self.initial_backoff
+            .saturating_mul(multiplier)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RpcRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            retry_on_next_protocol: true,
+            retry_on_next_peer: true,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            total_deadline: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Returns true iff the given RPC error was caused by the peer (e.g., the peer
+/// disconnected or failed to respond in time), rather than by the protocol.
+fn is_peer_failure(error: &RpcError) -> bool {
+    matches!(
+        error,
+        RpcError::NotConnected(_)
+            | RpcError::TimedOut
+            | RpcError::TooManyPending(_)
+            | RpcError::RateLimited(_)
+            | RpcError::MpscSendError(_)
+            | RpcError::IoError(_)
+            | RpcError::UnexpectedResponseChannelCancel
+    )
+}
+
 /// A simple interface offered by the networking stack to each client application (e.g., consensus,
 /// state sync, mempool, etc.). This interface provides basic support for sending messages,
 /// disconnecting from peers, notifying the network stack of new peers and managing application
@@ -42,13 +111,21 @@ pub trait NetworkClientInterface<Message: NetworkMessageTrait>: Clone + Send + S
     async fn disconnect_from_peer(&self, _peer: PeerNetworkId) -> Result<(), Error>;
 
     /// Returns a list of available peers (i.e., those that are
-    /// currently connected and support the relevant protocols
-    /// for the client).
+    /// currently connected, support the relevant protocols for
+    /// the client, and are not banned or greylisted).
     fn get_available_peers(&self) -> Result<Vec<PeerNetworkId>, Error>;
 
     /// Returns a handle to the global `PeersAndMetadata` container
     fn get_peers_and_metadata(&self) -> Arc<PeersAndMetadata>;
 
+    /// Returns the traffic stats (i.e., messages and bytes sent and received)
+    /// for each protocol used with the specified peer. This can be used to
+    /// detect one-sided relationships (e.g., peers that never respond).
+    fn get_peer_traffic_stats(
+        &self,
+        _peer: &PeerNetworkId,
+    ) -> HashMap<ProtocolId, ProtocolTrafficStats>;
+
     /// Sends the given message to the specified peer. Note: this
     /// method does not guarantee message delivery or handle responses.
     fn send_to_peer(&self, _message: Message, _peer: PeerNetworkId) -> Result<(), Error>;
@@ -61,6 +138,16 @@ pub trait NetworkClientInterface<Message: NetworkMessageTrait>: Clone + Send + S
     /// Note: this method does not guarantee message delivery or handle responses.
     fn send_to_peers(&self, _message: Message, _peers: Vec<PeerNetworkId>) -> Result<(), Error>;
 
+    /// Sends the given message to each peer in the specified peer list, and returns
+    /// the result of the send for each peer (e.g., to identify peers without a common
+    /// protocol, or whose send queue is full). Note: this method does not guarantee
+    /// message delivery or handle responses.
+    fn send_to_peers_with_results(
+        &self,
+        _message: Message,
+        _peers: Vec<PeerNetworkId>,
+    ) -> Vec<(PeerNetworkId, Result<(), Error>)>;
+
     /// Sends the given message to the specified peer with the corresponding
     /// timeout. Awaits a response from the peer, or hits the timeout
     /// (whichever occurs first).
@@ -71,6 +158,17 @@ pub trait NetworkClientInterface<Message: NetworkMessageTrait>: Clone + Send + S
         _peer: PeerNetworkId,
     ) -> Result<Message, Error>;
 
+    /// Sends the given message to the specified peer with the corresponding
+    /// timeout, and retries failed RPCs according to the given retry policy.
+    /// Returns the peer that responded, along with the response.
+    async fn send_to_peer_rpc_with_retry_policy(
+        &self,
+        _message: Message,
+        _rpc_timeout: Duration,
+        _peer: PeerNetworkId,
+        _retry_policy: RpcRetryPolicy,
+    ) -> Result<(PeerNetworkId, Message), Error>;
+
     async fn send_to_peer_rpc_raw(
         &self,
         _message: Bytes,
@@ -141,18 +239,109 @@ impl<Message: NetworkMessageTrait + Clone> NetworkClient<Message> {
         preferred_protocols: &[ProtocolId],
     ) -> Result<ProtocolId, Error> {
         let protocols_supported_by_peer = self.get_supported_protocols(peer)?;
+        let protocol_deprecations = self.get_peers_and_metadata().get_protocol_deprecations();
+        let mut sunset_protocol = None;
         for protocol in preferred_protocols {
             if protocols_supported_by_peer.contains(*protocol) {
-                return Ok(*protocol);
+                match protocol_deprecations.get_protocol_status(peer.network_id(), *protocol) {
+                    ProtocolStatus::Sunset { sunset_epoch } => {
+                        // Skip the sunset protocol and try the next preferred protocol
+                        sunset_protocol.get_or_insert((*protocol, sunset_epoch));
+                    },
+                    ProtocolStatus::Deprecated { .. } => {
+                        counters::deprecated_protocol_message(
+                            peer.network_id(),
+                            &peer.peer_id(),
+                            *protocol,
+                            counters::OUTBOUND_LABEL,
+                            counters::DEPRECATED_LABEL,
+                        );
+                        return Ok(*protocol);
+                    },
+                    ProtocolStatus::Supported => return Ok(*protocol),
+                }
             }
         }
-        Err(Error::NetworkError(format!(
+        if let Some((protocol, sunset_epoch)) = sunset_protocol {
+            return Err(Error::ProtocolSunset(protocol.to_string(), sunset_epoch));
+        }
+        Err(Error::NoCommonProtocol(format!(
             "None of the preferred protocols are supported by this peer! \
             Peer: {:?}, supported protocols: {:?}",
             peer, protocols_supported_by_peer
         )))
     }
 
+    /// Returns the RPC protocols to use for the specified peer (sorted from most to
+    /// least preferable). The preferred protocol is always first, followed by the
+    /// remaining (non-sunset) protocols supported by the peer.
+    fn get_rpc_protocols_for_peer(&self, peer: &PeerNetworkId) -> Result<Vec<ProtocolId>, Error> {
+        let preferred_protocol =
+            self.get_preferred_protocol_for_peer(peer, &self.rpc_protocols_and_preferences)?;
+        let protocols_supported_by_peer = self.get_supported_protocols(peer)?;
+        let protocol_deprecations = self.get_peers_and_metadata().get_protocol_deprecations();
+
+        let mut rpc_protocols = vec![preferred_protocol];
+        for protocol in &self.rpc_protocols_and_preferences {
+            if *protocol != preferred_protocol
+                && protocols_supported_by_peer.contains(*protocol)
+                && !matches!(
+                    protocol_deprecations.get_protocol_status(peer.network_id(), *protocol),
+                    ProtocolStatus::Sunset { .. }
+                )
+            {
+                rpc_protocols.push(*protocol);
+            }
+        }
+        Ok(rpc_protocols)
+    }
+
+    /// Returns the next peer (on the specified network) to retry an RPC on, along
+    /// with the RPC protocols to use for the peer. Peers that were already attempted
+    /// are skipped.
+    fn get_next_rpc_peer(
+        &self,
+        network_id: NetworkId,
+        attempted_peers: &[PeerNetworkId],
+    ) -> Option<(PeerNetworkId, Vec<ProtocolId>)> {
+        let connected_peers = self
+            .get_peers_and_metadata()
+            .get_connected_supported_peers(&self.rpc_protocols_and_preferences)
+            .ok()?;
+        connected_peers
+            .into_iter()
+            .filter(|peer| {
+                peer.network_id() == network_id
+                    && !attempted_peers.contains(peer)
+                    && !self.get_peers_and_metadata().is_peer_restricted(peer)
+            })
+            .find_map(|peer| {
+                self.get_rpc_protocols_for_peer(&peer)
+                    .ok()
+                    .map(|rpc_protocols| (peer, rpc_protocols))
+            })
+    }
+
+    /// Sends the given message bytes to the specified peer using the given protocol.
+    /// Returns an error if the message was dropped because the send queue is full.
+    fn try_send_to_peer_raw(
+        &self,
+        peer: PeerNetworkId,
+        protocol_id: ProtocolId,
+        message: Bytes,
+    ) -> Result<(), Error> {
+        let network_sender = self.get_sender_for_network_id(&peer.network_id())?;
+        network_sender
+            .try_send_to_raw(peer.peer_id(), protocol_id, message)
+            .map_err(|error| {
+                if error.is_send_queue_full() {
+                    Error::SendQueueFull(peer.to_string())
+                } else {
+                    error.into()
+                }
+            })
+    }
+
     fn group_peers_by_protocol(
         &self,
         peers: Vec<PeerNetworkId>,
@@ -208,14 +397,26 @@ impl<Message: NetworkMessageTrait> NetworkClientInterface<Message> for NetworkCl
             .chain(self.rpc_protocols_and_preferences.iter())
             .cloned()
             .collect();
-        self.peers_and_metadata
-            .get_connected_supported_peers(&supported_protocol_ids)
+        let mut available_peers = self
+            .peers_and_metadata
+            .get_connected_supported_peers(&supported_protocol_ids)?;
+        available_peers.retain(|peer| !self.peers_and_metadata.is_peer_restricted(peer));
+        Ok(available_peers)
     }
 
     fn get_peers_and_metadata(&self) -> Arc<PeersAndMetadata> {
         self.peers_and_metadata.clone()
     }
 
+    fn get_peer_traffic_stats(
+        &self,
+        peer: &PeerNetworkId,
+    ) -> HashMap<ProtocolId, ProtocolTrafficStats> {
+        self.peers_and_metadata
+            .get_peer_traffic_stats()
+            .get_peer_traffic_stats(peer)
+    }
+
     fn send_to_peer(&self, message: Message, peer: PeerNetworkId) -> Result<(), Error> {
         let network_sender = self.get_sender_for_network_id(&peer.network_id())?;
         let direct_send_protocol_id = self
@@ -247,6 +448,43 @@ impl<Message: NetworkMessageTrait> NetworkClientInterface<Message> for NetworkCl
         Ok(())
     }
 
+    fn send_to_peers_with_results(
+        &self,
+        message: Message,
+        peers: Vec<PeerNetworkId>,
+    ) -> Vec<(PeerNetworkId, Result<(), Error>)> {
+        let mut peer_results = vec![];
+
+        // Identify the preferred protocol for each peer
+        let mut peers_per_protocol: HashMap<ProtocolId, Vec<PeerNetworkId>> = HashMap::new();
+        for peer in peers {
+            match self
+                .get_preferred_protocol_for_peer(&peer, &self.direct_send_protocols_and_preferences)
+            {
+                Ok(protocol) => peers_per_protocol.entry(protocol).or_default().push(peer),
+                Err(error) => peer_results.push((peer, Err(error))),
+            }
+        }
+
+        // Serialize the message once per protocol, and send it to each peer
+        for (protocol_id, peers) in peers_per_protocol {
+            let message_bytes: Bytes = match protocol_id.to_bytes(&message) {
+                Ok(message_bytes) => message_bytes.into(),
+                Err(error) => {
+                    let error = Error::from(error);
+                    peer_results.extend(peers.into_iter().map(|peer| (peer, Err(error.clone()))));
+                    continue;
+                },
+            };
+            for peer in peers {
+                let result = self.try_send_to_peer_raw(peer, protocol_id, message_bytes.clone());
+                peer_results.push((peer, result));
+            }
+        }
+
+        peer_results
+    }
+
     async fn send_to_peer_rpc(
         &self,
         message: Message,
@@ -261,6 +499,87 @@ impl<Message: NetworkMessageTrait> NetworkClientInterface<Message> for NetworkCl
             .await?)
     }
 
+    async fn send_to_peer_rpc_with_retry_policy(
+        &self,
+        message: Message,
+        rpc_timeout: Duration,
+        peer: PeerNetworkId,
+        retry_policy: RpcRetryPolicy,
+    ) -> Result<(PeerNetworkId, Message), Error> {
+        let deadline = Instant::now() + retry_policy.total_deadline;
+        let network_sender = self.get_sender_for_network_id(&peer.network_id())?;
+
+        let mut rpc_peer = peer;
+        let mut rpc_protocols = VecDeque::from(self.get_rpc_protocols_for_peer(&rpc_peer)?);
+        let mut attempted_peers = vec![rpc_peer];
+        let mut num_retries = 0;
+        loop {
+            let Some(protocol_id) = rpc_protocols.pop_front() else {
+                return Err(Error::UnexpectedError(format!(
+                    "No RPC protocols remain for peer: {:?}",
+                    rpc_peer
+                )));
+            };
+
+            // Send the RPC (the timeout is bounded by the remaining deadline)
+            let remaining_time = deadline.saturating_duration_since(Instant::now());
+            let error = match network_sender
+                .send_rpc(
+                    rpc_peer.peer_id(),
+                    protocol_id,
+                    message.clone(),
+                    rpc_timeout.min(remaining_time),
+                )
+                .await
+            {
+                Ok(response) => return Ok((rpc_peer, response)),
+                Err(error) => error,
+            };
+            if num_retries >= retry_policy.max_retries {
+                return Err(error.into());
+            }
+
+            // Identify the retry target (the next protocol or the next peer)
+            let failed_peer = rpc_peer;
+            let retry_target_label = if retry_policy.retry_on_next_protocol
+                && !is_peer_failure(&error)
+                && !rpc_protocols.is_empty()
+            {
+                counters::NEXT_PROTOCOL_LABEL
+            } else if retry_policy.retry_on_next_peer {
+                match self.get_next_rpc_peer(rpc_peer.network_id(), &attempted_peers) {
+                    Some((next_peer, next_rpc_protocols)) => {
+                        rpc_peer = next_peer;
+                        rpc_protocols = VecDeque::from(next_rpc_protocols);
+                        attempted_peers.push(next_peer);
+                        counters::NEXT_PEER_LABEL
+                    },
+                    None => return Err(error.into()),
+                }
+            } else {
+                return Err(error.into());
+            };
+
+            // Backoff before retrying (unless the deadline would be exceeded)
+            let backoff = jitter(retry_policy.get_backoff(num_retries));
+            if Instant::now() + backoff >= deadline {
+                return Err(error.into());
+            }
+            // We only periodically log the retries (to prevent log spamming). All
+            // retries are still counted by the metrics.
+            sample!(
+                SampleRate::Duration(Duration::from_secs(10)),
+                warn!(
+                    "RPC to peer {:?} failed on protocol {:?}: {:?}. Retrying on the {} in {:?}.",
+                    failed_peer, protocol_id, error, retry_target_label, backoff
+                )
+            );
+            counters::rpc_retry(rpc_peer.network_id(), protocol_id, retry_target_label);
+            tokio::time::sleep(backoff).await;
+            num_retries += 1;
+        }
+    }
+
     async fn send_to_peer_rpc_raw(
         &self,
         message: Bytes,
@@ -17,6 +17,23 @@ pub enum ConnectionState {
     Disconnected, // Currently unused (TODO: fix this!)
 }
 
+/// A restriction placed on a peer by an application (e.g., for misbehaviour)
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum PeerRestriction {
+    Banned,     // The peer is not dialed or selected, and existing connections are closed
+    Greylisted, // The peer is not dialed or selected, but existing connections are kept
+}
+
+impl PeerRestriction {
+    /// Returns a summary label for the restriction
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            PeerRestriction::Banned => "banned",
+            PeerRestriction::Greylisted => "greylisted",
+        }
+    }
+}
+
 /// A container holding all relevant metadata for the peer.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct PeerMetadata {
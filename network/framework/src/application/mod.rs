@@ -2,10 +2,13 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod deprecation;
 pub mod error;
 pub mod interface;
 pub mod metadata;
+pub mod rate_limit;
 pub mod storage;
+pub mod traffic;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,242 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    counters::{self, INBOUND_LABEL, OUTBOUND_LABEL},
+    logging::NetworkSchema,
+    ProtocolId,
+};
+use aptos_config::{config::ProtocolRateLimitConfig, network_id::NetworkContext};
+use aptos_logger::prelude::*;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The rate limits (per protocol) applied to every connection on a network.
+/// These are shared between the peer manager and all peer actors.
+#[derive(Clone, Debug, Default)]
+pub struct ProtocolRateLimits {
+    rate_limits: Arc<HashMap<ProtocolId, ProtocolRateLimitConfig>>,
+}
+
+impl ProtocolRateLimits {
+    pub fn new(rate_limits: HashMap<ProtocolId, ProtocolRateLimitConfig>) -> Self {
+        Self {
+            rate_limits: Arc::new(rate_limits),
+        }
+    }
+
+    /// Creates the rate limits from the given config (keyed by protocol name)
+    /// and exports them as metrics. Unknown protocol names are rejected by the
+    /// config sanitizer (and are otherwise ignored here).
+    pub fn from_config(
+        network_context: &NetworkContext,
+        config: &HashMap<String, ProtocolRateLimitConfig>,
+    ) -> Self {
+        let mut rate_limits = HashMap::new();
+        for (protocol_name, rate_limit_config) in config {
+            let protocol_id = ProtocolId::all()
+                .iter()
+                .find(|protocol_id| protocol_id.as_str() == protocol_name);
+            match protocol_id {
+                Some(protocol_id) => {
+                    rate_limits.insert(*protocol_id, *rate_limit_config);
+                },
+                None => {
+                    error!(
+                        NetworkSchema::new(network_context),
+                        "{} Ignoring rate limit for unknown protocol: {}",
+                        network_context,
+                        protocol_name
+                    );
+                },
+            }
+        }
+
+        // Export the configured rate limits
+        for (protocol_id, rate_limit_config) in rate_limits.iter() {
+            for (limited, direction_label) in [
+                (rate_limit_config.limit_inbound, INBOUND_LABEL),
+                (rate_limit_config.limit_outbound, OUTBOUND_LABEL),
+            ] {
+                if limited {
+                    counters::set_protocol_rate_limit(
+                        network_context.network_id(),
+                        *protocol_id,
+                        direction_label,
+                        rate_limit_config.byte_bucket_rate,
+                        rate_limit_config.byte_bucket_size,
+                    );
+                }
+            }
+        }
+
+        Self::new(rate_limits)
+    }
+
+    /// Returns the rate limit config for the given protocol (if any)
+    pub fn get_rate_limit(&self, protocol_id: ProtocolId) -> Option<ProtocolRateLimitConfig> {
+        self.rate_limits.get(&protocol_id).copied()
+    }
+}
+
+/// A simple token bucket of bytes. A message is admitted as long as the
+/// bucket is not empty, and its size is then deducted from the bucket (which
+/// may go into debt). This ensures that messages larger than the bucket size
+/// are never starved, while still enforcing the rate over time.
+#[derive(Debug)]
+struct TokenBucket {
+    byte_bucket_rate: u64,
+    byte_bucket_size: u64,
+    num_tokens: i128,
+    last_refill_time: Instant,
+}
+
+impl TokenBucket {
+    fn new(byte_bucket_rate: u64, byte_bucket_size: u64, now: Instant) -> Self {
+        Self {
+            byte_bucket_rate,
+            byte_bucket_size,
+            num_tokens: byte_bucket_size as i128,
+            last_refill_time: now,
+        }
+    }
+
+    /// Refills the bucket (based on the elapsed time) and attempts to
+    /// consume the given number of bytes. Returns true iff the bytes
+    /// were admitted.
+    fn try_consume(&mut self, num_bytes: u64, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill_time);
+        let new_tokens = elapsed.as_micros() * self.byte_bucket_rate as u128
+            / Duration::from_secs(1).as_micros();
+        if new_tokens > 0 {
+            self.num_tokens = self
+                .num_tokens
+                .saturating_add(new_tokens as i128)
+                .min(self.byte_bucket_size as i128);
+            self.last_refill_time = now;
+        }
+
+        if self.num_tokens > 0 {
+            self.num_tokens -= num_bytes as i128;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Enforces the protocol rate limits on a single connection. Each
+/// protocol has an independent bucket for each direction of traffic.
+pub struct ProtocolRateLimiter {
+    protocol_rate_limits: ProtocolRateLimits,
+    time_service: TimeService,
+    inbound_buckets: HashMap<ProtocolId, TokenBucket>,
+    outbound_buckets: HashMap<ProtocolId, TokenBucket>,
+}
+
+impl ProtocolRateLimiter {
+    pub fn new(protocol_rate_limits: ProtocolRateLimits, time_service: TimeService) -> Self {
+        Self {
+            protocol_rate_limits,
+            time_service,
+            inbound_buckets: HashMap::new(),
+            outbound_buckets: HashMap::new(),
+        }
+    }
+
+    /// Returns true iff an inbound message (of the given size) on the protocol
+    /// is within the rate limit. If so, the message is counted against the limit.
+    pub fn allow_inbound(&mut self, protocol_id: ProtocolId, num_bytes: u64) -> bool {
+        match self.protocol_rate_limits.get_rate_limit(protocol_id) {
+            Some(rate_limit) if rate_limit.limit_inbound => {
+                let now = self.time_service.now();
+                self.inbound_buckets
+                    .entry(protocol_id)
+                    .or_insert_with(|| {
+                        TokenBucket::new(
+                            rate_limit.byte_bucket_rate,
+                            rate_limit.byte_bucket_size,
+                            now,
+                        )
+                    })
+                    .try_consume(num_bytes, now)
+            },
+            _ => true,
+        }
+    }
+
+    /// Returns true iff an outbound message (of the given size) on the protocol
+    /// is within the rate limit. If so, the message is counted against the limit.
+    pub fn allow_outbound(&mut self, protocol_id: ProtocolId, num_bytes: u64) -> bool {
+        match self.protocol_rate_limits.get_rate_limit(protocol_id) {
+            Some(rate_limit) if rate_limit.limit_outbound => {
+                let now = self.time_service.now();
+                self.outbound_buckets
+                    .entry(protocol_id)
+                    .or_insert_with(|| {
+                        TokenBucket::new(
+                            rate_limit.byte_bucket_rate,
+                            rate_limit.byte_bucket_size,
+                            now,
+                        )
+                    })
+                    .try_consume(num_bytes, now)
+            },
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProtocolRateLimiter, ProtocolRateLimits};
+    use crate::ProtocolId;
+    use aptos_config::config::ProtocolRateLimitConfig;
+    use aptos_time_service::TimeService;
+    use std::{collections::HashMap, time::Duration};
+
+    #[test]
+    fn test_protocol_rate_limiter() {
+        // Rate limit inbound storage service requests (1 KiB/s, with 2 KiB bursts)
+        let rate_limit_config = ProtocolRateLimitConfig {
+            byte_bucket_rate: 1024,
+            byte_bucket_size: 2048,
+            limit_inbound: true,
+            limit_outbound: false,
+        };
+        let protocol_id = ProtocolId::StorageServiceRpc;
+        let protocol_rate_limits =
+            ProtocolRateLimits::new(HashMap::from([(protocol_id, rate_limit_config)]));
+
+        // Create the rate limiter
+        let time_service = TimeService::mock();
+        let mut rate_limiter = ProtocolRateLimiter::new(protocol_rate_limits, time_service.clone());
+
+        // Verify the burst is admitted, and the following message is rejected
+        assert!(rate_limiter.allow_inbound(protocol_id, 1024));
+        assert!(rate_limiter.allow_inbound(protocol_id, 1024));
+        assert!(!rate_limiter.allow_inbound(protocol_id, 1));
+
+        // Verify other protocols and outbound messages are not rate limited
+        assert!(rate_limiter.allow_inbound(ProtocolId::ConsensusRpcBcs, 1_000_000));
+        assert!(rate_limiter.allow_outbound(protocol_id, 1_000_000));
+
+        // Elapse some time and verify the bucket is refilled
+        let mock_time = time_service.into_mock();
+        mock_time.advance(Duration::from_millis(500));
+        assert!(rate_limiter.allow_inbound(protocol_id, 512));
+        assert!(!rate_limiter.allow_inbound(protocol_id, 1));
+
+        // Verify large messages are admitted (but put the bucket into debt)
+        mock_time.advance(Duration::from_millis(1));
+        assert!(rate_limiter.allow_inbound(protocol_id, 10 * 1024));
+        mock_time.advance(Duration::from_secs(5));
+        assert!(!rate_limiter.allow_inbound(protocol_id, 1));
+        mock_time.advance(Duration::from_secs(6));
+        assert!(rate_limiter.allow_inbound(protocol_id, 1));
+    }
+}
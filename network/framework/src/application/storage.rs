@@ -4,8 +4,10 @@
 
 use crate::{
     application::{
+        deprecation::ProtocolDeprecations,
         error::Error,
-        metadata::{ConnectionState, PeerMetadata},
+        metadata::{ConnectionState, PeerMetadata, PeerRestriction},
+        traffic::PeerTrafficStats,
     },
     counters,
     peer_manager::ConnectionNotification,
@@ -19,13 +21,14 @@ use aptos_config::{
 use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::{sample, sample::SampleRate, warn};
 use aptos_peer_monitoring_service_types::PeerMonitoringMetadata;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{account_address::AccountAddress, PeerId};
 use arc_swap::ArcSwap;
 use std::{
     collections::{hash_map::Entry, HashMap},
     ops::Deref,
     sync::{Arc, RwLockWriteGuard},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio::sync::mpsc::error::TrySendError;
 
@@ -52,16 +55,43 @@ pub struct PeersAndMetadata {
     cached_peers_and_metadata: Arc<ArcSwap<HashMap<NetworkId, HashMap<PeerId, PeerMetadata>>>>,
 
     subscribers: Mutex<Vec<tokio::sync::mpsc::Sender<ConnectionNotification>>>,
+
+    // The subscribers notified of peer monitoring metadata updates
+    monitoring_subscribers: Mutex<Vec<tokio::sync::mpsc::Sender<PeerNetworkId>>>,
+
+    // The protocols deprecated by this node (and their sunset epochs)
+    protocol_deprecations: ProtocolDeprecations,
+
+    // The traffic stats of each connected peer (per protocol)
+    peer_traffic_stats: PeerTrafficStats,
+
+    // The peers banned or greylisted by applications (and the restriction expiration times)
+    peer_restrictions: RwLock<HashMap<PeerNetworkId, (PeerRestriction, Instant)>>,
+    // The time service used to expire peer restrictions
+    time_service: TimeService,
 }
 
 impl PeersAndMetadata {
     pub fn new(network_ids: &[NetworkId]) -> Arc<PeersAndMetadata> {
+        Self::new_with_time_service(network_ids, TimeService::real())
+    }
+
+    /// Creates a new container that uses the given time service (e.g., for tests)
+    pub fn new_with_time_service(
+        network_ids: &[NetworkId],
+        time_service: TimeService,
+    ) -> Arc<PeersAndMetadata> {
         // Create the container
         let mut peers_and_metadata = PeersAndMetadata {
             peers_and_metadata: RwLock::new(HashMap::new()),
             trusted_peers: HashMap::new(),
             cached_peers_and_metadata: Arc::new(ArcSwap::from(Arc::new(HashMap::new()))),
             subscribers: Mutex::new(vec![]),
+            monitoring_subscribers: Mutex::new(vec![]),
+            protocol_deprecations: ProtocolDeprecations::default(),
+            peer_traffic_stats: PeerTrafficStats::default(),
+            peer_restrictions: RwLock::new(HashMap::new()),
+            time_service,
         };
 
         // Initialize each network mapping and trusted peer set
@@ -239,6 +269,7 @@ impl PeersAndMetadata {
             let active_connection_id = entry.get().connection_metadata.connection_id;
             if active_connection_id == connection_id {
                 let peer_metadata = entry.remove();
+                self.peer_traffic_stats.remove_peer(&peer_network_id);
                 let event = ConnectionNotification::LostPeer(
                     peer_metadata.connection_metadata.clone(),
                     peer_network_id.network_id(),
@@ -314,6 +345,9 @@ impl PeersAndMetadata {
         // Update the cached peers and metadata
         self.set_cached_peers_and_metadata(peers_and_metadata.clone());
 
+        // Notify the subscribers of the update
+        self.broadcast_monitoring_update(peer_network_id);
+
         Ok(())
     }
 
@@ -326,6 +360,80 @@ impl PeersAndMetadata {
             .store(Arc::new(cached_peers_and_metadata));
     }
 
+    /// Returns a handle to the protocol deprecations tracked by this node
+    pub fn get_protocol_deprecations(&self) -> ProtocolDeprecations {
+        self.protocol_deprecations.clone()
+    }
+
+    /// Returns a handle to the traffic stats of the connected peers
+    pub fn get_peer_traffic_stats(&self) -> PeerTrafficStats {
+        self.peer_traffic_stats.clone()
+    }
+
+    /// Bans the given peer for the specified duration. Banned peers are not
+    /// dialed or returned as available peers, and any existing connections
+    /// to them are closed (by the connectivity manager).
+    pub fn ban_peer(&self, peer_network_id: PeerNetworkId, duration: Duration) {
+        self.restrict_peer(peer_network_id, PeerRestriction::Banned, duration);
+    }
+
+    /// Greylists the given peer for the specified duration. Greylisted peers
+    /// are not dialed or returned as available peers, but existing connections
+    /// are kept (e.g., so the peer can still send us requests). Note: greylisting
+    /// a peer does not override an active ban.
+    pub fn greylist_peer(&self, peer_network_id: PeerNetworkId, duration: Duration) {
+        self.restrict_peer(peer_network_id, PeerRestriction::Greylisted, duration);
+    }
+
+    /// Restricts the given peer for the specified duration
+    fn restrict_peer(
+        &self,
+        peer_network_id: PeerNetworkId,
+        peer_restriction: PeerRestriction,
+        duration: Duration,
+    ) {
+        // Grab the write lock for the peer restrictions
+        let mut peer_restrictions = self.peer_restrictions.write();
+
+        // Remove any expired restrictions
+        let time_now = self.time_service.now();
+        peer_restrictions.retain(|_, (_, expiration_time)| *expiration_time > time_now);
+
+        // Don't downgrade an active ban to a greylist
+        if peer_restriction == PeerRestriction::Greylisted
+            && matches!(
+                peer_restrictions.get(&peer_network_id),
+                Some((PeerRestriction::Banned, _))
+            )
+        {
+            return;
+        }
+
+        // Update the restriction for the peer
+        let expiration_time = time_now + duration;
+        peer_restrictions.insert(peer_network_id, (peer_restriction, expiration_time));
+        counters::peer_restricted(peer_network_id.network_id(), peer_restriction.get_label());
+    }
+
+    /// Removes any restriction (i.e., ban or greylist) for the given peer
+    pub fn remove_peer_restriction(&self, peer_network_id: &PeerNetworkId) {
+        self.peer_restrictions.write().remove(peer_network_id);
+    }
+
+    /// Returns the active restriction for the given peer (if any)
+    pub fn get_peer_restriction(&self, peer_network_id: &PeerNetworkId) -> Option<PeerRestriction> {
+        self.peer_restrictions
+            .read()
+            .get(peer_network_id)
+            .filter(|(_, expiration_time)| *expiration_time > self.time_service.now())
+            .map(|(peer_restriction, _)| *peer_restriction)
+    }
+
+    /// Returns true iff the given peer is currently banned or greylisted
+    pub fn is_peer_restricted(&self, peer_network_id: &PeerNetworkId) -> bool {
+        self.get_peer_restriction(peer_network_id).is_some()
+    }
+
     /// Returns a clone of the trusted peer set for the given network ID
     pub fn get_trusted_peers(&self, network_id: &NetworkId) -> Result<PeerSet, Error> {
         let trusted_peers = self.get_trusted_peer_set_for_network(network_id)?;
@@ -395,6 +503,26 @@ impl PeersAndMetadata {
         }
     }
 
+    /// Notifies the monitoring subscribers that the monitoring metadata of the given
+    /// peer was updated. Notifications are dropped for subscribers that are lagging.
+    fn broadcast_monitoring_update(&self, peer_network_id: PeerNetworkId) {
+        self.monitoring_subscribers.lock().retain(|subscriber| {
+            !matches!(
+                subscriber.try_send(peer_network_id),
+                Err(TrySendError::Closed(_))
+            )
+        });
+    }
+
+    /// Returns a channel for receiving the peers whose monitoring metadata was updated
+    /// (e.g., new ping latencies or distances from the validators). The updated metadata
+    /// can be fetched using get_metadata_for_peer().
+    pub fn subscribe_to_monitoring_updates(&self) -> tokio::sync::mpsc::Receiver<PeerNetworkId> {
+        let (sender, receiver) = tokio::sync::mpsc::channel(NOTIFICATION_BACKLOG);
+        self.monitoring_subscribers.lock().push(sender);
+        receiver
+    }
+
     /// subscribe() returns a channel for receiving NewPeer/LostPeer events.
     /// subscribe() immediately sends all* current connections as NewPeer events.
     /// (* capped at NOTIFICATION_BACKLOG, currently 1000, use get_connected_peers() to be sure)
@@ -5,8 +5,8 @@
 use crate::{
     application::{
         error::Error,
-        interface::{NetworkClient, NetworkClientInterface, NetworkServiceEvents},
-        metadata::{ConnectionState, PeerMetadata},
+        interface::{NetworkClient, NetworkClientInterface, NetworkServiceEvents, RpcRetryPolicy},
+        metadata::{ConnectionState, PeerMetadata, PeerRestriction},
         storage::PeersAndMetadata,
     },
     peer_manager::{
@@ -18,6 +18,7 @@ use crate::{
             Event, NetworkEvents, NetworkSender, NewNetworkEvents, NewNetworkSender,
             ReceivedMessage,
         },
+        rpc::{error::RpcError, OutboundRpcRequest},
         wire::{
             handshake::v1::{ProtocolId, ProtocolIdSet},
             messaging::v1::{DirectSendMsg, NetworkMessage, RpcRequest},
@@ -31,7 +32,9 @@ use aptos_config::{
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_peer_monitoring_service_types::PeerMonitoringMetadata;
+use aptos_time_service::TimeService;
 use aptos_types::{account_address::AccountAddress, PeerId};
+use bytes::Bytes;
 use futures_util::StreamExt;
 use maplit::hashmap;
 use serde::{Deserialize, Serialize};
@@ -540,6 +543,37 @@ async fn test_peers_and_metadata_subscriptions() {
     }
 }
 
+#[tokio::test]
+async fn test_peers_and_metadata_monitoring_subscriptions() {
+    // Create the peers and metadata container
+    let network_ids = vec![NetworkId::Validator, NetworkId::Vfn];
+    let peers_and_metadata = PeersAndMetadata::new(&network_ids);
+    let mut monitoring_updates = peers_and_metadata.subscribe_to_monitoring_updates();
+
+    // Create a peer and verify that connecting doesn't notify the subscriber
+    let (peer_network_id_1, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![ProtocolId::MempoolDirectSend],
+        peers_and_metadata.clone(),
+    );
+    assert_eq!(monitoring_updates.try_recv(), Err(TryRecvError::Empty));
+
+    // Update the monitoring metadata and verify the subscriber is notified
+    let peer_monitoring_metadata = PeerMonitoringMetadata::new(Some(0.5), None, None, None, None);
+    peers_and_metadata
+        .update_peer_monitoring_metadata(peer_network_id_1, peer_monitoring_metadata)
+        .unwrap();
+    assert_eq!(monitoring_updates.try_recv(), Ok(peer_network_id_1));
+    assert_eq!(monitoring_updates.try_recv(), Err(TryRecvError::Empty));
+
+    // Verify that failed updates (i.e., for missing peers) don't notify the subscriber
+    let missing_peer = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+    peers_and_metadata
+        .update_peer_monitoring_metadata(missing_peer, PeerMonitoringMetadata::default())
+        .unwrap_err();
+    assert_eq!(monitoring_updates.try_recv(), Err(TryRecvError::Empty));
+}
+
 #[test]
 fn test_network_client_available_peers() {
     // Create the peers and metadata container
@@ -640,6 +674,84 @@ fn test_network_client_available_peers() {
     ]);
 }
 
+#[test]
+fn test_network_client_restricted_peers() {
+    // Create the peers and metadata container (with a mock time service)
+    let network_ids = vec![NetworkId::Validator, NetworkId::Public];
+    let time_service = TimeService::mock();
+    let peers_and_metadata =
+        PeersAndMetadata::new_with_time_service(&network_ids, time_service.clone());
+
+    // Create the network client
+    let network_client: NetworkClient<DummyMessage> = NetworkClient::new(
+        vec![ProtocolId::MempoolDirectSend],
+        vec![],
+        HashMap::new(),
+        peers_and_metadata.clone(),
+    );
+
+    // Create three peers and initialize the connection metadata
+    let (peer_network_id_1, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![ProtocolId::MempoolDirectSend],
+        peers_and_metadata.clone(),
+    );
+    let (peer_network_id_2, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![ProtocolId::MempoolDirectSend],
+        peers_and_metadata.clone(),
+    );
+    let (peer_network_id_3, _) = create_peer_and_connection(
+        NetworkId::Public,
+        vec![ProtocolId::MempoolDirectSend],
+        peers_and_metadata.clone(),
+    );
+    let peer_network_ids = vec![peer_network_id_1, peer_network_id_2, peer_network_id_3];
+    check_available_peers(&network_client, peer_network_ids.clone());
+
+    // Ban peer 1 and greylist peer 2, and verify they are no longer available
+    let restriction_duration = Duration::from_secs(3600);
+    peers_and_metadata.ban_peer(peer_network_id_1, restriction_duration);
+    peers_and_metadata.greylist_peer(peer_network_id_2, restriction_duration);
+    check_available_peers(&network_client, vec![peer_network_id_3]);
+    assert_eq!(
+        peers_and_metadata.get_peer_restriction(&peer_network_id_1),
+        Some(PeerRestriction::Banned)
+    );
+    assert_eq!(
+        peers_and_metadata.get_peer_restriction(&peer_network_id_2),
+        Some(PeerRestriction::Greylisted)
+    );
+
+    // Verify the restricted peers are still connected
+    check_connected_peers_and_metadata(&peers_and_metadata, peer_network_ids.clone());
+
+    // Verify that greylisting a banned peer does not override the ban
+    peers_and_metadata.greylist_peer(peer_network_id_1, restriction_duration);
+    assert_eq!(
+        peers_and_metadata.get_peer_restriction(&peer_network_id_1),
+        Some(PeerRestriction::Banned)
+    );
+
+    // Remove the ban on peer 1 and verify it is available again
+    peers_and_metadata.remove_peer_restriction(&peer_network_id_1);
+    assert!(!peers_and_metadata.is_peer_restricted(&peer_network_id_1));
+    check_available_peers(&network_client, vec![peer_network_id_1, peer_network_id_3]);
+
+    // Greylist peer 3 with an expired restriction and verify it remains available
+    peers_and_metadata.greylist_peer(peer_network_id_3, Duration::from_secs(0));
+    assert!(!peers_and_metadata.is_peer_restricted(&peer_network_id_3));
+    check_available_peers(&network_client, vec![peer_network_id_1, peer_network_id_3]);
+
+    // Elapse the restriction duration and verify peer 2 is available again
+    let mock_time = time_service.into_mock();
+    mock_time.advance(restriction_duration - Duration::from_secs(1));
+    assert!(peers_and_metadata.is_peer_restricted(&peer_network_id_2));
+    mock_time.advance(Duration::from_secs(1));
+    assert!(!peers_and_metadata.is_peer_restricted(&peer_network_id_2));
+    check_available_peers(&network_client, peer_network_ids);
+}
+
 #[tokio::test]
 async fn test_network_client_missing_network_sender() {
     // Create the peers and metadata container
@@ -745,6 +857,69 @@ async fn test_network_client_senders_no_matching_protocols() {
         .unwrap_err();
 }
 
+#[tokio::test]
+async fn test_network_client_sunset_protocols() {
+    // Create the peers and metadata container
+    let network_ids = vec![NetworkId::Validator];
+    let peers_and_metadata = PeersAndMetadata::new(&network_ids);
+
+    // Create a network client with network senders
+    let (network_senders, _network_events, _outbound_request_receivers, _inbound_request_senders) =
+        create_network_sender_and_events(&network_ids);
+    let network_client: NetworkClient<DummyMessage> = NetworkClient::new(
+        vec![
+            ProtocolId::MempoolDirectSend,
+            ProtocolId::ConsensusDirectSendBcs,
+        ],
+        vec![],
+        network_senders,
+        peers_and_metadata.clone(),
+    );
+
+    // Create two peers: one supporting both protocols, and one supporting only the first
+    let (peer_network_id_1, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![
+            ProtocolId::MempoolDirectSend,
+            ProtocolId::ConsensusDirectSendBcs,
+        ],
+        peers_and_metadata.clone(),
+    );
+    let (peer_network_id_2, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![ProtocolId::MempoolDirectSend],
+        peers_and_metadata.clone(),
+    );
+
+    // Deprecate the first protocol and verify it is still used (before the sunset epoch)
+    let protocol_deprecations = peers_and_metadata.get_protocol_deprecations();
+    protocol_deprecations.deprecate_protocol(
+        NetworkId::Validator,
+        ProtocolId::MempoolDirectSend,
+        5,
+    );
+    for peer_network_id in [peer_network_id_1, peer_network_id_2] {
+        network_client
+            .send_to_peer(DummyMessage::new_empty(), peer_network_id)
+            .unwrap();
+    }
+
+    // Move to the sunset epoch and verify the first peer falls back to the second protocol
+    protocol_deprecations.update_epoch(5);
+    network_client
+        .send_to_peer(DummyMessage::new_empty(), peer_network_id_1)
+        .unwrap();
+
+    // Verify the second peer fails with a sunset error
+    let error = network_client
+        .send_to_peer(DummyMessage::new_empty(), peer_network_id_2)
+        .unwrap_err();
+    assert_eq!(
+        error,
+        Error::ProtocolSunset(ProtocolId::MempoolDirectSend.to_string(), 5)
+    );
+}
+
 #[tokio::test]
 async fn test_network_client_network_senders_direct_send() {
     // Create the peers and metadata container
@@ -853,6 +1028,62 @@ async fn test_network_client_network_senders_direct_send() {
     .await;
 }
 
+#[tokio::test]
+async fn test_network_client_send_to_peers_with_results() {
+    // Create the peers and metadata container
+    let network_ids = vec![NetworkId::Validator];
+    let peers_and_metadata = PeersAndMetadata::new(&network_ids);
+
+    // Create a network client with network senders
+    let (network_senders, _network_events, _outbound_request_receivers, _inbound_request_senders) =
+        create_network_sender_and_events(&network_ids);
+    let network_client: NetworkClient<DummyMessage> = NetworkClient::new(
+        vec![ProtocolId::MempoolDirectSend],
+        vec![],
+        network_senders,
+        peers_and_metadata.clone(),
+    );
+
+    // Create two peers: one with a common protocol, and one without
+    let (peer_network_id_1, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![ProtocolId::MempoolDirectSend],
+        peers_and_metadata.clone(),
+    );
+    let (peer_network_id_2, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![ProtocolId::StorageServiceRpc],
+        peers_and_metadata.clone(),
+    );
+
+    // Send messages until the send queue of the first peer is full (the queue is never drained)
+    for _ in 0..10 {
+        let peer_results = network_client.send_to_peers_with_results(
+            DummyMessage::new_empty(),
+            vec![peer_network_id_1, peer_network_id_2],
+        );
+        let peer_results: HashMap<_, _> = peer_results.into_iter().collect();
+        assert_eq!(peer_results.len(), 2);
+        assert_eq!(peer_results.get(&peer_network_id_1).unwrap(), &Ok(()));
+        assert!(matches!(
+            peer_results.get(&peer_network_id_2).unwrap(),
+            Err(Error::NoCommonProtocol(_))
+        ));
+    }
+
+    // Verify that the next message to the first peer is reported as dropped
+    let peer_results = network_client
+        .send_to_peers_with_results(DummyMessage::new_empty(), vec![peer_network_id_1]);
+    assert_eq!(peer_results.len(), 1);
+    assert_eq!(
+        peer_results[0],
+        (
+            peer_network_id_1,
+            Err(Error::SendQueueFull(peer_network_id_1.to_string()))
+        )
+    );
+}
+
 #[tokio::test]
 async fn test_network_client_network_senders_rpc() {
     // Create the peers and metadata container
@@ -938,6 +1169,131 @@ async fn test_network_client_network_senders_rpc() {
     .await;
 }
 
+#[tokio::test]
+async fn test_network_client_rpc_retry_policy() {
+    // Create the peers and metadata container
+    let network_ids = [NetworkId::Validator];
+    let peers_and_metadata = PeersAndMetadata::new(&network_ids);
+
+    // Create two peers and initialize the connection metadata
+    let (peer_network_id_1, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![ProtocolId::ConsensusRpcJson, ProtocolId::ConsensusRpcBcs],
+        peers_and_metadata.clone(),
+    );
+    let (peer_network_id_2, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![ProtocolId::ConsensusRpcBcs],
+        peers_and_metadata.clone(),
+    );
+
+    // Create a network client with network senders
+    let (network_senders, _network_events, mut outbound_request_receivers, _) =
+        create_network_sender_and_events(&network_ids);
+    let network_client: NetworkClient<DummyMessage> = NetworkClient::new(
+        vec![],
+        vec![ProtocolId::ConsensusRpcJson, ProtocolId::ConsensusRpcBcs],
+        network_senders,
+        peers_and_metadata.clone(),
+    );
+
+    // Send an rpc to the first peer (with retries on the next protocol and peer)
+    let dummy_message = DummyMessage::new(999);
+    let rpc_timeout = Duration::from_secs(MAX_MESSAGE_TIMEOUT_SECS);
+    let retry_policy = RpcRetryPolicy {
+        max_retries: 2,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(10),
+        ..Default::default()
+    };
+    let rpc_handle = tokio::spawn({
+        let network_client = network_client.clone();
+        let dummy_message = dummy_message.clone();
+        async move {
+            network_client
+                .send_to_peer_rpc_with_retry_policy(
+                    dummy_message,
+                    rpc_timeout,
+                    peer_network_id_1,
+                    retry_policy,
+                )
+                .await
+        }
+    });
+
+    // Fail the rpc on the preferred protocol (by responding with an invalid message)
+    let outbound_request_receiver = outbound_request_receivers
+        .get_mut(&NetworkId::Validator)
+        .unwrap();
+    let (peer_id, outbound_rpc_request) = wait_for_rpc_request(outbound_request_receiver).await;
+    assert_eq!(peer_id, peer_network_id_1.peer_id());
+    assert_eq!(
+        outbound_rpc_request.protocol_id,
+        ProtocolId::ConsensusRpcJson
+    );
+    outbound_rpc_request
+        .res_tx
+        .send(Ok(Bytes::from_static(b"invalid")))
+        .unwrap();
+
+    // Verify the rpc is retried on the next protocol, and fail it (as if the peer disconnected)
+    let (peer_id, outbound_rpc_request) = wait_for_rpc_request(outbound_request_receiver).await;
+    assert_eq!(peer_id, peer_network_id_1.peer_id());
+    assert_eq!(
+        outbound_rpc_request.protocol_id,
+        ProtocolId::ConsensusRpcBcs
+    );
+    outbound_rpc_request
+        .res_tx
+        .send(Err(RpcError::NotConnected(peer_id)))
+        .unwrap();
+
+    // Verify the rpc is retried on the next peer, and respond successfully
+    let (peer_id, outbound_rpc_request) = wait_for_rpc_request(outbound_request_receiver).await;
+    assert_eq!(peer_id, peer_network_id_2.peer_id());
+    assert_eq!(
+        outbound_rpc_request.protocol_id,
+        ProtocolId::ConsensusRpcBcs
+    );
+    let response_bytes = ProtocolId::ConsensusRpcBcs
+        .to_bytes(&dummy_message)
+        .unwrap();
+    outbound_rpc_request
+        .res_tx
+        .send(Ok(response_bytes.into()))
+        .unwrap();
+
+    // Verify the response is returned from the second peer
+    let (responding_peer, response) = rpc_handle.await.unwrap().unwrap();
+    assert_eq!(responding_peer, peer_network_id_2);
+    assert_eq!(response, dummy_message);
+
+    // Verify that rpcs aren't retried when the retries are disabled
+    let rpc_handle = tokio::spawn({
+        let network_client = network_client.clone();
+        let dummy_message = dummy_message.clone();
+        async move {
+            network_client
+                .send_to_peer_rpc_with_retry_policy(
+                    dummy_message,
+                    rpc_timeout,
+                    peer_network_id_1,
+                    RpcRetryPolicy {
+                        max_retries: 0,
+                        ..Default::default()
+                    },
+                )
+                .await
+        }
+    });
+    let (_, outbound_rpc_request) = wait_for_rpc_request(outbound_request_receiver).await;
+    outbound_rpc_request
+        .res_tx
+        .send(Err(RpcError::NotConnected(peer_network_id_1.peer_id())))
+        .unwrap();
+    assert!(matches!(rpc_handle.await.unwrap(), Err(Error::RpcError(_))));
+}
+
 /// Verifies that the available peers are correct
 fn check_available_peers(
     network_client: &NetworkClient<DummyMessage>,
@@ -1172,6 +1528,31 @@ fn verify_internal_map_states(
     }
 }
 
+/// Waits for an outbound rpc request on the given channel
+async fn wait_for_rpc_request(
+    outbound_request_receiver: &mut aptos_channel::Receiver<
+        (PeerId, ProtocolId),
+        PeerManagerRequest,
+    >,
+) -> (PeerId, OutboundRpcRequest) {
+    let channel_wait_time = Duration::from_secs(MAX_CHANNEL_TIMEOUT_SECS);
+    match timeout(
+        channel_wait_time,
+        outbound_request_receiver.select_next_some(),
+    )
+    .await
+    {
+        Ok(PeerManagerRequest::SendRpc(peer_id, outbound_rpc_request)) => {
+            (peer_id, outbound_rpc_request)
+        },
+        Ok(request) => panic!("Expected an rpc request, but got: {:?}", request),
+        Err(elapsed) => panic!(
+            "Timed out while waiting to receive an rpc request. Elapsed: {:?}",
+            elapsed
+        ),
+    }
+}
+
 /// Waits for a network event on the expected channels and
 /// verifies the message contents.
 async fn wait_for_network_event(
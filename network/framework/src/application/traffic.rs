@@ -0,0 +1,169 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ProtocolId;
+use aptos_config::network_id::PeerNetworkId;
+use aptos_infallible::{Mutex, RwLock};
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+
+/// The message and byte counters for a single direction of traffic
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct TrafficCounters {
+    pub num_messages: u64,
+    pub num_bytes: u64,
+}
+
+impl TrafficCounters {
+    /// Records a single message with the given number of bytes
+    fn record_message(&mut self, num_bytes: u64) {
+        self.num_messages = self.num_messages.saturating_add(1);
+        self.num_bytes = self.num_bytes.saturating_add(num_bytes);
+    }
+}
+
+/// The traffic sent to and received from a peer on a single protocol
+/// (including direct send messages, rpc requests and rpc responses).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct ProtocolTrafficStats {
+    pub sent: TrafficCounters,
+    pub received: TrafficCounters,
+}
+
+impl ProtocolTrafficStats {
+    /// Returns the ratio of messages received to messages sent. This can be
+    /// used to detect one-sided relationships (i.e., peers that we send a
+    /// lot of messages to, but that never respond). Returns None if no
+    /// messages have been sent.
+    pub fn get_response_ratio(&self) -> Option<f64> {
+        if self.sent.num_messages == 0 {
+            None
+        } else {
+            Some(self.received.num_messages as f64 / self.sent.num_messages as f64)
+        }
+    }
+}
+
+/// The traffic stats of a single peer, grouped by protocol. This is shared
+/// between the peer actor (which records all inbound and outbound messages)
+/// and the peer traffic stats container (which is queried by applications).
+#[derive(Clone, Debug, Default)]
+pub struct PeerTrafficCounters {
+    protocol_stats: Arc<Mutex<HashMap<ProtocolId, ProtocolTrafficStats>>>,
+}
+
+impl PeerTrafficCounters {
+    /// Records a message (of the given size) sent to the peer on the protocol
+    pub fn record_sent(&self, protocol_id: ProtocolId, num_bytes: u64) {
+        self.protocol_stats
+            .lock()
+            .entry(protocol_id)
+            .or_default()
+            .sent
+            .record_message(num_bytes);
+    }
+
+    /// Records a message (of the given size) received from the peer on the protocol
+    pub fn record_received(&self, protocol_id: ProtocolId, num_bytes: u64) {
+        self.protocol_stats
+            .lock()
+            .entry(protocol_id)
+            .or_default()
+            .received
+            .record_message(num_bytes);
+    }
+
+    /// Returns a snapshot of the traffic stats (for each protocol)
+    pub fn get_protocol_stats(&self) -> HashMap<ProtocolId, ProtocolTrafficStats> {
+        self.protocol_stats.lock().clone()
+    }
+}
+
+/// Tracks the traffic stats of all connected peers (per protocol). Peers
+/// are registered when they connect, and removed when they disconnect.
+#[derive(Clone, Debug, Default)]
+pub struct PeerTrafficStats {
+    peer_counters: Arc<RwLock<HashMap<PeerNetworkId, PeerTrafficCounters>>>,
+}
+
+impl PeerTrafficStats {
+    /// Returns the traffic counters for the given peer, registering the
+    /// peer if it is not already tracked (e.g., for a new connection).
+    pub fn register_peer(&self, peer_network_id: PeerNetworkId) -> PeerTrafficCounters {
+        self.peer_counters
+            .write()
+            .entry(peer_network_id)
+            .or_default()
+            .clone()
+    }
+
+    /// Removes the traffic counters for the given peer
+    pub fn remove_peer(&self, peer_network_id: &PeerNetworkId) {
+        self.peer_counters.write().remove(peer_network_id);
+    }
+
+    /// Returns the traffic stats (for each protocol) of the given peer.
+    /// If the peer is not tracked, an empty map is returned.
+    pub fn get_peer_traffic_stats(
+        &self,
+        peer_network_id: &PeerNetworkId,
+    ) -> HashMap<ProtocolId, ProtocolTrafficStats> {
+        self.peer_counters
+            .read()
+            .get(peer_network_id)
+            .map(|peer_counters| peer_counters.get_protocol_stats())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PeerTrafficStats;
+    use crate::ProtocolId;
+    use aptos_config::network_id::{NetworkId, PeerNetworkId};
+    use aptos_types::PeerId;
+
+    #[test]
+    fn test_peer_traffic_stats() {
+        // Register a peer and record traffic on several protocols
+        let peer_traffic_stats = PeerTrafficStats::default();
+        let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        let peer_counters = peer_traffic_stats.register_peer(peer_network_id);
+        for _ in 0..4 {
+            peer_counters.record_sent(ProtocolId::MempoolDirectSend, 100);
+        }
+        peer_counters.record_received(ProtocolId::MempoolDirectSend, 10);
+        peer_counters.record_received(ProtocolId::StorageServiceRpc, 50);
+
+        // Verify the traffic stats of the peer
+        let protocol_stats = peer_traffic_stats.get_peer_traffic_stats(&peer_network_id);
+        let mempool_stats = protocol_stats.get(&ProtocolId::MempoolDirectSend).unwrap();
+        assert_eq!(mempool_stats.sent.num_messages, 4);
+        assert_eq!(mempool_stats.sent.num_bytes, 400);
+        assert_eq!(mempool_stats.received.num_bytes, 10);
+        assert_eq!(mempool_stats.get_response_ratio(), Some(0.25));
+        let storage_stats = protocol_stats.get(&ProtocolId::StorageServiceRpc).unwrap();
+        assert_eq!(storage_stats.received.num_messages, 1);
+        assert_eq!(storage_stats.get_response_ratio(), None);
+
+        // Verify re-registering the peer keeps the existing counters
+        peer_traffic_stats
+            .register_peer(peer_network_id)
+            .record_sent(ProtocolId::MempoolDirectSend, 100);
+        let protocol_stats = peer_traffic_stats.get_peer_traffic_stats(&peer_network_id);
+        assert_eq!(
+            protocol_stats
+                .get(&ProtocolId::MempoolDirectSend)
+                .unwrap()
+                .sent
+                .num_messages,
+            5
+        );
+
+        // Remove the peer and verify the stats are empty
+        peer_traffic_stats.remove_peer(&peer_network_id);
+        assert!(peer_traffic_stats
+            .get_peer_traffic_stats(&peer_network_id)
+            .is_empty());
+    }
+}
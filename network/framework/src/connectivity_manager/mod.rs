@@ -28,7 +28,7 @@
 //! using a relay protocol.
 
 use crate::{
-    application::storage::PeersAndMetadata,
+    application::{metadata::PeerRestriction, storage::PeersAndMetadata},
     counters,
     logging::NetworkSchema,
     peer_manager::{self, conn_notifs_channel, ConnectionRequestSender, PeerManagerError},
@@ -36,13 +36,14 @@ use crate::{
 };
 use aptos_config::{
     config::{Peer, PeerRole, PeerSet},
-    network_id::NetworkContext,
+    network_id::{NetworkContext, PeerNetworkId},
 };
 use aptos_crypto::x25519;
 use aptos_infallible::RwLock;
 use aptos_logger::prelude::*;
 use aptos_netcore::transport::ConnectionOrigin;
 use aptos_num_variants::NumVariants;
+use aptos_peer_monitoring_service_types::PeerMonitoringMetadata;
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{account_address::AccountAddress, network_address::NetworkAddress, PeerId};
@@ -92,6 +93,11 @@ const MAX_SOCKET_ADDRESSES_TO_PING: usize = 2;
 /// It's currently set to 5 minutes to ensure rotation through all (or most) peers
 const TRY_DIAL_BACKOFF_TIME: Duration = Duration::from_secs(300);
 
+/// The amount of time for which the peer monitoring metadata (i.e., the monitored
+/// ping latency and distance) of a peer is used after it was last observed. Stale
+/// metadata is ignored, so that old observations don't prioritize dials forever.
+const PEER_MONITORING_METADATA_TTL: Duration = Duration::from_secs(30 * 60);
+
 /// The ConnectivityManager actor.
 pub struct ConnectivityManager<TBackoff> {
     network_context: NetworkContext,
@@ -226,6 +232,17 @@ impl DiscoveredPeerSet {
             discovered_peer.set_ping_latency_secs(latency_secs)
         }
     }
+
+    /// Updates the monitoring metadata for the specified peer (if one was found)
+    fn update_peer_monitoring_metadata(
+        &mut self,
+        peer_id: &PeerId,
+        peer_monitoring_metadata: &PeerMonitoringMetadata,
+    ) {
+        if let Some(discovered_peer) = self.peer_set.get_mut(peer_id) {
+            discovered_peer.set_peer_monitoring_metadata(peer_monitoring_metadata)
+        }
+    }
 }
 
 /// Represents all the information for a discovered peer
@@ -238,6 +255,14 @@ struct DiscoveredPeer {
     last_dial_time: SystemTime,
     /// The calculated peer ping latency (secs)
     ping_latency_secs: Option<f64>,
+    /// The average ping latency (secs) observed by peer monitoring (the last time
+    /// the peer was connected)
+    monitored_ping_latency_secs: Option<f64>,
+    /// The distance of the peer from the validators (as reported to peer
+    /// monitoring the last time the peer was connected)
+    distance_from_validators: Option<u64>,
+    /// The last time the peer monitoring metadata was observed
+    last_peer_monitoring_metadata_time: SystemTime,
 }
 
 impl DiscoveredPeer {
@@ -248,6 +273,9 @@ impl DiscoveredPeer {
             keys: PublicKeys::default(),
             last_dial_time: SystemTime::UNIX_EPOCH,
             ping_latency_secs: None,
+            monitored_ping_latency_secs: None,
+            distance_from_validators: None,
+            last_peer_monitoring_metadata_time: SystemTime::UNIX_EPOCH,
         }
     }
 
@@ -276,6 +304,45 @@ impl DiscoveredPeer {
         self.ping_latency_secs = Some(latency_secs);
     }
 
+    /// Updates the monitored ping latency and distance for this peer. Note: missing
+    /// metadata doesn't overwrite the previously observed metadata.
+    pub fn set_peer_monitoring_metadata(
+        &mut self,
+        peer_monitoring_metadata: &PeerMonitoringMetadata,
+    ) {
+        if let Some(average_ping_latency_secs) = peer_monitoring_metadata.average_ping_latency_secs
+        {
+            self.monitored_ping_latency_secs = Some(average_ping_latency_secs);
+            self.last_peer_monitoring_metadata_time = SystemTime::now();
+        }
+        if let Some(network_info_response) = &peer_monitoring_metadata.latest_network_info_response
+        {
+            self.distance_from_validators = Some(network_info_response.distance_from_validators);
+            self.last_peer_monitoring_metadata_time = SystemTime::now();
+        }
+    }
+
+    /// Returns the monitored ping latency for this peer (if it was observed recently)
+    pub fn get_monitored_ping_latency_secs(&self) -> Option<f64> {
+        self.monitored_ping_latency_secs
+            .filter(|_| self.has_recent_peer_monitoring_metadata())
+    }
+
+    /// Returns the distance from the validators for this peer (if it was observed recently)
+    pub fn get_distance_from_validators(&self) -> Option<u64> {
+        self.distance_from_validators
+            .filter(|_| self.has_recent_peer_monitoring_metadata())
+    }
+
+    /// Returns true iff the peer monitoring metadata was observed recently
+    fn has_recent_peer_monitoring_metadata(&self) -> bool {
+        if let Ok(duration_since_last_update) = self.last_peer_monitoring_metadata_time.elapsed() {
+            duration_since_last_update < PEER_MONITORING_METADATA_TTL
+        } else {
+            false
+        }
+    }
+
     /// Based on input, backoff on amount of time to dial a peer again
     pub fn has_dialed_recently(&self) -> bool {
         if let Ok(duration_since_last_dial) = self.last_dial_time.elapsed() {
@@ -476,31 +543,45 @@ where
         }
     }
 
+    /// Returns the restriction placed on the given peer by an application (if any)
+    fn get_peer_restriction(&self, peer_id: &PeerId) -> Option<PeerRestriction> {
+        let peer_network_id = PeerNetworkId::new(self.network_context.network_id(), *peer_id);
+        self.peers_and_metadata
+            .get_peer_restriction(&peer_network_id)
+    }
+
     /// Disconnect from all peers that are no longer eligible.
     ///
     /// For instance, a validator might leave the validator set after a
     /// reconfiguration. If we are currently connected to this validator, calling
-    /// this function will close our connection to it.
+    /// this function will close our connection to it. Likewise, connections to
+    /// peers that have been banned by an application are closed.
     async fn close_stale_connections(&mut self) {
         if let Some(trusted_peers) = self.get_trusted_peers() {
             // Identify stale peer connections
-            let stale_peers = self
-                .connected
-                .iter()
-                .filter(|(peer_id, _)| !trusted_peers.contains_key(peer_id))
-                .filter_map(|(peer_id, metadata)| {
-                    // If we're using server only auth, we need to not evict unknown peers
-                    // TODO: We should prevent `Unknown` from discovery sources
-                    if !self.mutual_authentication
-                        && metadata.origin == ConnectionOrigin::Inbound
-                        && (metadata.role == PeerRole::ValidatorFullNode
-                            || metadata.role == PeerRole::Unknown)
-                    {
-                        None
-                    } else {
-                        Some(*peer_id) // The peer is stale
-                    }
-                });
+            let stale_peers = self.connected.iter().filter_map(|(peer_id, metadata)| {
+                // Banned peers are always stale (regardless of how they connected)
+                if self.get_peer_restriction(peer_id) == Some(PeerRestriction::Banned) {
+                    return Some(*peer_id);
+                }
+
+                // Trusted peers are never stale
+                if trusted_peers.contains_key(peer_id) {
+                    return None;
+                }
+
+                // If we're using server only auth, we need to not evict unknown peers
+                // TODO: We should prevent `Unknown` from discovery sources
+                if !self.mutual_authentication
+                    && metadata.origin == ConnectionOrigin::Inbound
+                    && (metadata.role == PeerRole::ValidatorFullNode
+                        || metadata.role == PeerRole::Unknown)
+                {
+                    None
+                } else {
+                    Some(*peer_id) // The peer is stale
+                }
+            });
 
             // Close existing connections to stale peers
             for stale_peer in stale_peers {
@@ -532,14 +613,18 @@ where
     ///
     /// For instance, a validator might leave the validator set after a
     /// reconfiguration. If there is a pending dial to this validator, calling
-    /// this function will remove it from the dial queue.
+    /// this function will remove it from the dial queue. Likewise, pending dials
+    /// to peers that have been banned or greylisted by an application are removed.
     async fn cancel_stale_dials(&mut self) {
         if let Some(trusted_peers) = self.get_trusted_peers() {
             // Identify stale peer dials
             let stale_peer_dials: Vec<AccountAddress> = self
                 .dial_queue
                 .keys()
-                .filter(|peer_id| !trusted_peers.contains_key(peer_id))
+                .filter(|peer_id| {
+                    !trusted_peers.contains_key(peer_id)
+                        || self.get_peer_restriction(peer_id).is_some()
+                })
                 .cloned()
                 .collect();
 
@@ -580,6 +665,7 @@ where
                     && !self.connected.contains_key(peer_id) // The node is not already connected
                     && !self.dial_queue.contains_key(peer_id) // There is no pending dial to this node
                     && roles_to_dial.contains(&peer.role) // We can dial this role
+                    && self.get_peer_restriction(peer_id).is_none() // The node is not banned or greylisted
             })
             .collect();
 
@@ -821,6 +907,9 @@ where
             )
         });
 
+        // Record the monitoring metadata of the connected peers (to prioritize future dials)
+        self.update_peer_monitoring_metadata();
+
         // Cancel dials to peers that are no longer eligible.
         self.cancel_stale_dials().await;
         // Disconnect from connected peers that are no longer eligible.
@@ -833,6 +922,34 @@ where
         self.update_ping_latency_metrics();
     }
 
+    /// Records the monitoring metadata (e.g., the ping latencies and distances) of the
+    /// connected peers. This metadata outlives the connections, so that reconnection
+    /// attempts can prefer the peers that were historically good.
+    fn update_peer_monitoring_metadata(&self) {
+        // Peer monitoring metadata is only used when selecting peers by latency
+        if !selection::should_select_peers_by_latency(
+            &self.network_context,
+            self.enable_latency_aware_dialing,
+        ) {
+            return;
+        }
+
+        let network_id = self.network_context.network_id();
+        let mut discovered_peers = self.discovered_peers.write();
+        for peer_id in self.connected.keys() {
+            let peer_network_id = PeerNetworkId::new(network_id, *peer_id);
+            if let Ok(peer_metadata) = self
+                .peers_and_metadata
+                .get_metadata_for_peer(peer_network_id)
+            {
+                discovered_peers.update_peer_monitoring_metadata(
+                    peer_id,
+                    peer_metadata.get_peer_monitoring_metadata(),
+                );
+            }
+        }
+    }
+
     /// Updates the metrics for tracking pre-dial and connected peer ping latencies
     fn update_ping_latency_metrics(&self) {
         // Update the pre-dial peer ping latencies
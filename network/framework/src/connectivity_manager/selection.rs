@@ -14,6 +14,9 @@ use ordered_float::OrderedFloat;
 use rand_latest::prelude::*;
 use std::{cmp::Ordering, collections::HashSet, sync::Arc};
 
+/// The maximum number of weight reductions applied to distant peers
+const MAX_DISTANCE_WEIGHT_REDUCTIONS: u64 = 10;
+
 /// Chooses peers to dial randomly from the given list of eligible
 /// peers. We take last dial times into account to ensure that we
 /// don't dial the same peers too frequently.
@@ -99,7 +102,8 @@ pub fn should_select_peers_by_latency(
 
 /// Selects the specified number of peers from the list of potential
 /// peers. Peer selection is weighted by peer latencies (i.e., the
-/// lower the ping latency, the higher the probability of selection).
+/// lower the ping latency, the higher the probability of selection)
+/// and distances from the validators (see `calculate_dial_weight`).
 fn choose_peers_by_ping_latency(
     network_context: &NetworkContext,
     peer_ids: &HashSet<PeerId>,
@@ -114,8 +118,12 @@ fn choose_peers_by_ping_latency(
     // Gather the latency weights for all peers
     let mut peer_ids_and_latency_weights = vec![];
     for peer_id in peer_ids {
-        if let Some(ping_latency_secs) = discovered_peers.read().get_ping_latency_secs(peer_id) {
-            let latency_weight = convert_latency_to_weight(ping_latency_secs);
+        if let Some(latency_weight) = discovered_peers
+            .read()
+            .peer_set
+            .get(peer_id)
+            .and_then(calculate_dial_weight)
+        {
             peer_ids_and_latency_weights.push((peer_id, OrderedFloat(latency_weight)));
         }
     }
@@ -145,6 +153,32 @@ fn choose_peers_by_ping_latency(
         .collect::<HashSet<_>>()
 }
 
+/// Calculates the dial weight of the given peer. The weight is derived from the
+/// latency of the peer, preferring the average latency observed by peer monitoring
+/// (if the peer was connected recently) over the pre-dial ping latency. The weight
+/// is then reduced if the peer is far from the validators. Returns None if the
+/// latency of the peer is unknown.
+fn calculate_dial_weight(peer: &DiscoveredPeer) -> Option<f64> {
+    let latency_secs = peer
+        .get_monitored_ping_latency_secs()
+        .or(peer.ping_latency_secs)?;
+    let latency_weight = convert_latency_to_weight(latency_secs);
+    let distance_multiplier =
+        convert_distance_to_weight_multiplier(peer.get_distance_from_validators());
+    Some(latency_weight * distance_multiplier)
+}
+
+/// Converts the given distance from the validators to a weight multiplier. For
+/// every hop beyond the closest fullnodes (i.e., distance 1), the weight is
+/// reduced by 1/2. Peers with unknown distances are not penalized.
+fn convert_distance_to_weight_multiplier(distance_from_validators: Option<u64>) -> f64 {
+    let num_reductions = distance_from_validators
+        .map(|distance| distance.saturating_sub(1))
+        .unwrap_or(0)
+        .min(MAX_DISTANCE_WEIGHT_REDUCTIONS);
+    0.5_f64.powi(num_reductions as i32)
+}
+
 /// Converts the given latency measurement to a weight. The weight
 /// is calculated as the inverse of the latency, with a scaling
 /// factor to ensure that low latency peers are highly weighted.
@@ -224,13 +258,20 @@ fn get_unselected_peer_ids(
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::connectivity_manager::PEER_MONITORING_METADATA_TTL;
     use aptos_config::{
         config::{PeerRole, RoleType},
         network_id::NetworkId,
     };
+    use aptos_peer_monitoring_service_types::{
+        response::NetworkInformationResponse, PeerMonitoringMetadata,
+    };
     use aptos_types::account_address::AccountAddress;
     use rand::Rng;
-    use std::collections::{BinaryHeap, HashMap};
+    use std::{
+        collections::{BTreeMap, BinaryHeap, HashMap},
+        time::{Duration, SystemTime},
+    };
 
     #[test]
     fn test_choose_random_peers() {
@@ -596,6 +637,56 @@ mod test {
         assert_eq!(convert_latency_to_weight(0.2), 0.01953125);
     }
 
+    #[test]
+    fn test_dial_weights() {
+        // Verify that peers without latencies have no weight
+        let mut peer = DiscoveredPeer::new(PeerRole::Unknown);
+        assert_eq!(calculate_dial_weight(&peer), None);
+
+        // Verify that the pre-dial ping latency is used (if no monitoring metadata exists)
+        peer.set_ping_latency_secs(0.01);
+        assert_eq!(calculate_dial_weight(&peer), Some(100.0));
+
+        // Verify that the monitored ping latency is preferred over the pre-dial ping latency
+        let peer_monitoring_metadata = PeerMonitoringMetadata {
+            average_ping_latency_secs: Some(0.02),
+            ..Default::default()
+        };
+        peer.set_peer_monitoring_metadata(&peer_monitoring_metadata);
+        assert_eq!(calculate_dial_weight(&peer), Some(50.0));
+
+        // Verify that the weight is reduced for distant peers
+        let peer_monitoring_metadata = PeerMonitoringMetadata {
+            latest_network_info_response: Some(NetworkInformationResponse {
+                connected_peers: BTreeMap::new(),
+                distance_from_validators: 3,
+            }),
+            ..Default::default()
+        };
+        peer.set_peer_monitoring_metadata(&peer_monitoring_metadata);
+        assert_eq!(calculate_dial_weight(&peer), Some(12.5));
+
+        // Verify that stale monitoring metadata is ignored
+        peer.last_peer_monitoring_metadata_time = SystemTime::now()
+            .checked_sub(PEER_MONITORING_METADATA_TTL + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(calculate_dial_weight(&peer), Some(100.0));
+
+        // Verify that new monitoring metadata is used again
+        peer.set_peer_monitoring_metadata(&peer_monitoring_metadata);
+        assert_eq!(calculate_dial_weight(&peer), Some(12.5));
+
+        // Verify the distance weight multipliers
+        assert_eq!(convert_distance_to_weight_multiplier(None), 1.0);
+        assert_eq!(convert_distance_to_weight_multiplier(Some(0)), 1.0);
+        assert_eq!(convert_distance_to_weight_multiplier(Some(1)), 1.0);
+        assert_eq!(convert_distance_to_weight_multiplier(Some(2)), 0.5);
+        assert_eq!(
+            convert_distance_to_weight_multiplier(Some(100)),
+            0.5_f64.powi(MAX_DISTANCE_WEIGHT_REDUCTIONS as i32)
+        );
+    }
+
     #[test]
     fn test_should_select_peers_by_latency() {
         // Create a validator network context
@@ -90,7 +90,10 @@ impl TestHarness {
             aptos_channel::new(QueueStyle::FIFO, 1, None);
         let (connection_notifs_tx, connection_notifs_rx) = conn_notifs_channel::new();
         let (conn_mgr_reqs_tx, conn_mgr_reqs_rx) = aptos_channels::new_test(0);
-        let peers_and_metadata = PeersAndMetadata::new(&[network_context.network_id()]);
+        let peers_and_metadata = PeersAndMetadata::new_with_time_service(
+            &[network_context.network_id()],
+            time_service.clone(),
+        );
 
         let conn_mgr = ConnectivityManager::new(
             network_context,
@@ -466,6 +469,82 @@ fn disconnect() {
     block_on(future::join(conn_mgr.start(), test));
 }
 
+// Tests that connectivity manager disconnects from banned peers and doesn't redial them.
+#[test]
+fn ban_peer() {
+    let (other_peer_id, other_peer, _, other_addr) = test_peer(AccountAddress::ZERO);
+    let (mut mock, conn_mgr) = TestHarness::new(HashMap::new());
+
+    let test = async move {
+        // Sending pubkey & address of other peer
+        let peers = hashmap! {other_peer_id => other_peer};
+        mock.send_update_discovered_peers(DiscoverySource::OnChainValidatorSet, peers)
+            .await;
+
+        // Waiting to receive dial request
+        mock.trigger_connectivity_check().await;
+        mock.trigger_pending_dials().await;
+        mock.expect_one_dial_success(other_peer_id, other_addr.clone())
+            .await;
+
+        // Ban the other peer and verify that we disconnect from them
+        let peer_network_id = PeerNetworkId::new(mock.network_context.network_id(), other_peer_id);
+        mock.peers_and_metadata
+            .ban_peer(peer_network_id, Duration::from_secs(3600));
+        mock.trigger_connectivity_check().await;
+        mock.expect_disconnect_success(other_peer_id, other_addr.clone())
+            .await;
+
+        // Verify that we don't redial the banned peer
+        mock.trigger_connectivity_check().await;
+        assert_eq!(0, mock.get_dial_queue_size().await);
+
+        // Remove the ban and verify that we dial the peer again
+        mock.peers_and_metadata
+            .remove_peer_restriction(&peer_network_id);
+        mock.trigger_connectivity_check().await;
+        mock.trigger_pending_dials().await;
+        mock.expect_one_dial_success(other_peer_id, other_addr)
+            .await;
+    };
+    block_on(future::join(conn_mgr.start(), test));
+}
+
+// Tests that connectivity manager keeps connections to greylisted peers but doesn't redial them.
+#[test]
+fn greylist_peer() {
+    let (other_peer_id, other_peer, _, other_addr) = test_peer(AccountAddress::ZERO);
+    let (mut mock, conn_mgr) = TestHarness::new(HashMap::new());
+
+    let test = async move {
+        // Sending pubkey & address of other peer
+        let peers = hashmap! {other_peer_id => other_peer};
+        mock.send_update_discovered_peers(DiscoverySource::OnChainValidatorSet, peers)
+            .await;
+
+        // Waiting to receive dial request
+        mock.trigger_connectivity_check().await;
+        mock.trigger_pending_dials().await;
+        mock.expect_one_dial_success(other_peer_id, other_addr.clone())
+            .await;
+
+        // Greylist the other peer and verify that we remain connected
+        let peer_network_id = PeerNetworkId::new(mock.network_context.network_id(), other_peer_id);
+        mock.peers_and_metadata
+            .greylist_peer(peer_network_id, Duration::from_secs(3600));
+        mock.trigger_connectivity_check().await;
+        assert_eq!(1, mock.get_connected_size().await);
+
+        // Lose the connection and verify that we don't redial the greylisted peer
+        mock.send_lost_peer_await_delivery(other_peer_id, other_addr)
+            .await;
+        mock.trigger_connectivity_check().await;
+        assert_eq!(0, mock.get_connected_size().await);
+        assert_eq!(0, mock.get_dial_queue_size().await);
+    };
+    block_on(future::join(conn_mgr.start(), test));
+}
+
 // Tests that connectivity manager retries dials and disconnects on failure.
 #[test]
 fn retry_on_failure() {
@@ -908,6 +987,52 @@ async fn test_stale_peers_vfn_inbound() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stale_peers_banned_vfn_inbound() {
+    // Create a connectivity manager with mutual authentication disabled
+    let (mut mock, mut connectivity_manager) = TestHarness::new(HashMap::new());
+    connectivity_manager.mutual_authentication = false;
+
+    // Create and connect peer 1 (a vfn inbound connection)
+    let network_context = mock.network_context;
+    let peer_id_1 = PeerId::random();
+    let connection_metadata_1 = ConnectionMetadata::mock_with_role_and_origin(
+        peer_id_1,
+        PeerRole::ValidatorFullNode,
+        ConnectionOrigin::Inbound,
+    );
+    let connection_notification = ConnectionNotification::NewPeer(
+        connection_metadata_1.clone(),
+        network_context.network_id(),
+    );
+    connectivity_manager.handle_control_notification(connection_notification);
+
+    // Create and connect peer 2 (an unknown inbound connection)
+    let peer_id_2 = PeerId::random();
+    let connection_metadata = ConnectionMetadata::mock_with_role_and_origin(
+        peer_id_2,
+        PeerRole::Unknown,
+        ConnectionOrigin::Inbound,
+    );
+    let connection_notification =
+        ConnectionNotification::NewPeer(connection_metadata, network_context.network_id());
+    connectivity_manager.handle_control_notification(connection_notification);
+
+    // Verify we have 2 peers
+    assert_eq!(connectivity_manager.get_connected_peers().len(), 2);
+
+    // Ban peer 1
+    let peer_network_id = PeerNetworkId::new(network_context.network_id(), peer_id_1);
+    mock.peers_and_metadata
+        .ban_peer(peer_network_id, Duration::from_secs(3600));
+
+    // Close the stale connections and verify that only peer 1 is disconnected
+    tokio::join!(
+        connectivity_manager.close_stale_connections(),
+        mock.expect_disconnect_fail(peer_id_1, connection_metadata_1.addr)
+    );
+}
+
 /// Verifies that the trusted peers match the expected set
 fn verify_trusted_peers(
     peers_and_metadata: &Arc<PeersAndMetadata>,
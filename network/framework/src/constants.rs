@@ -14,6 +14,8 @@ pub const INBOUND_RPC_TIMEOUT_MS: u64 = 10_000;
 pub const MAX_CONCURRENT_OUTBOUND_RPCS: u32 = 100;
 /// Limit on concurrent Inbound RPC requests before backpressure is applied
 pub const MAX_CONCURRENT_INBOUND_RPCS: u32 = 100;
+/// The interval at which each connection is checked for being half-open
+pub const HALF_OPEN_CONNECTION_CHECK_INTERVAL_MS: u64 = 1_000;
 
 // These are only used in tests
 // TODO: Fix this so the tests and the defaults in config are the same
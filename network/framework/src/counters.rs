@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::protocols::wire::handshake::v1::ProtocolId;
-use aptos_config::network_id::NetworkContext;
+use aptos_config::network_id::{NetworkContext, NetworkId};
 use aptos_metrics_core::{
     exponential_buckets, register_histogram_vec, register_int_counter_vec, register_int_gauge,
     register_int_gauge_vec, Histogram, HistogramTimer, HistogramVec, IntCounter, IntCounterVec,
@@ -27,6 +27,12 @@ pub const SENT_LABEL: &str = "sent";
 pub const SUCCEEDED_LABEL: &str = "succeeded";
 pub const FAILED_LABEL: &str = "failed";
 pub const UNKNOWN_LABEL: &str = "unknown";
+pub const DEPRECATED_LABEL: &str = "deprecated";
+pub const SUNSET_LABEL: &str = "sunset";
+
+// RPC retry labels
+pub const NEXT_PROTOCOL_LABEL: &str = "next_protocol";
+pub const NEXT_PEER_LABEL: &str = "next_peer";
 
 // Direction labels
 pub const INBOUND_LABEL: &str = "inbound";
@@ -40,6 +46,11 @@ const PRE_DIAL_LABEL: &str = "pre_dial";
 pub const SERIALIZATION_LABEL: &str = "serialization";
 pub const DESERIALIZATION_LABEL: &str = "deserialization";
 
+// Handshake failure cause labels (noise handshake causes are defined by the noise errors)
+pub const PROXY_PROTOCOL_FAILURE_LABEL: &str = "proxy_protocol";
+pub const HANDSHAKE_EXCHANGE_FAILURE_LABEL: &str = "handshake_exchange";
+pub const PROTOCOL_NEGOTIATION_FAILURE_LABEL: &str = "protocol_negotiation";
+
 pub static APTOS_CONNECTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "aptos_connections",
@@ -79,6 +90,44 @@ pub fn connections_rejected(
     ])
 }
 
+pub static APTOS_NETWORK_HALF_OPEN_CONNECTIONS_REAPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_half_open_connections_reaped",
+        "Number of half-open connections closed because the remote peer was unresponsive",
+        &["role_type", "network_id", "peer_id", "direction"]
+    )
+    .unwrap()
+});
+
+pub fn half_open_connections_reaped(
+    network_context: &NetworkContext,
+    origin: ConnectionOrigin,
+) -> IntCounter {
+    APTOS_NETWORK_HALF_OPEN_CONNECTIONS_REAPED.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        origin.as_str(),
+    ])
+}
+
+pub static APTOS_NETWORK_HALF_OPEN_CONNECTION_DROPPED_RPCS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_half_open_connection_dropped_rpcs",
+        "Number of pending outbound rpcs dropped when closing half-open connections",
+        &["role_type", "network_id", "peer_id"]
+    )
+    .unwrap()
+});
+
+pub fn half_open_connection_dropped_rpcs(network_context: &NetworkContext) -> IntCounter {
+    APTOS_NETWORK_HALF_OPEN_CONNECTION_DROPPED_RPCS.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+    ])
+}
+
 pub static APTOS_NETWORK_PEER_CONNECTED: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "aptos_network_peer_connected",
@@ -162,6 +211,29 @@ pub fn connection_upgrade_time(
     ])
 }
 
+pub static APTOS_NETWORK_HANDSHAKE_FAILURES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_handshake_failures",
+        "Number of failed inbound or outbound connection handshakes, by failure cause",
+        &["role_type", "network_id", "peer_id", "direction", "cause"]
+    )
+    .unwrap()
+});
+
+pub fn handshake_failure(
+    network_context: &NetworkContext,
+    direction: ConnectionOrigin,
+    cause: &str,
+) -> IntCounter {
+    APTOS_NETWORK_HANDSHAKE_FAILURES.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        direction.as_str(),
+        cause,
+    ])
+}
+
 pub static APTOS_NETWORK_DISCOVERY_NOTES: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "aptos_network_discovery_notes",
@@ -586,6 +658,148 @@ pub fn network_application_outbound_traffic(
         .observe(size as f64);
 }
 
+/// Counter of messages sent or received on deprecated (or sunset) protocols
+pub static NETWORK_DEPRECATED_PROTOCOL_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_deprecated_protocol_messages",
+        "Number of messages sent or received on deprecated protocols",
+        &[
+            "network_id",
+            "remote_peer_id",
+            "protocol_id",
+            "direction",
+            "state"
+        ]
+    )
+    .unwrap()
+});
+
+/// Increments the deprecated protocol counter for the given peer and protocol
+pub fn deprecated_protocol_message(
+    network_id: NetworkId,
+    remote_peer_id: &PeerId,
+    protocol_id: ProtocolId,
+    direction_label: &'static str,
+    state_label: &'static str,
+) {
+    NETWORK_DEPRECATED_PROTOCOL_MESSAGES
+        .with_label_values(&[
+            network_id.as_str(),
+            remote_peer_id.short_str().as_str(),
+            protocol_id.as_str(),
+            direction_label,
+            state_label,
+        ])
+        .inc();
+}
+
+/// Counter of outbound RPCs that were retried (on the next protocol or peer)
+pub static NETWORK_RPC_RETRIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_rpc_retries",
+        "Number of outbound RPCs retried on the next protocol or peer",
+        &["network_id", "protocol_id", "retry_target"]
+    )
+    .unwrap()
+});
+
+/// Increments the RPC retry counter for the given (failed) protocol
+pub fn rpc_retry(network_id: NetworkId, protocol_id: ProtocolId, retry_target_label: &'static str) {
+    NETWORK_RPC_RETRIES
+        .with_label_values(&[
+            network_id.as_str(),
+            protocol_id.as_str(),
+            retry_target_label,
+        ])
+        .inc();
+}
+
+/// Counter of peers banned or greylisted by applications
+pub static NETWORK_PEER_RESTRICTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_peer_restrictions",
+        "Number of times peers were banned or greylisted by applications",
+        &["network_id", "restriction"]
+    )
+    .unwrap()
+});
+
+/// Increments the peer restriction counter for the given network and restriction
+pub fn peer_restricted(network_id: NetworkId, restriction_label: &'static str) {
+    NETWORK_PEER_RESTRICTIONS
+        .with_label_values(&[network_id.as_str(), restriction_label])
+        .inc();
+}
+
+/// Gauge of the configured rate limits (per connection) for each protocol
+pub static NETWORK_PROTOCOL_RATE_LIMITS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_network_protocol_rate_limits",
+        "Configured per-connection rate limits (in bytes) for each protocol",
+        &["network_id", "protocol_id", "direction", "limit"]
+    )
+    .unwrap()
+});
+
+/// Sets the configured rate limit gauges for the given protocol and direction
+pub fn set_protocol_rate_limit(
+    network_id: NetworkId,
+    protocol_id: ProtocolId,
+    direction_label: &'static str,
+    byte_bucket_rate: u64,
+    byte_bucket_size: u64,
+) {
+    for (limit_label, value) in [
+        ("byte_bucket_rate", byte_bucket_rate),
+        ("byte_bucket_size", byte_bucket_size),
+    ] {
+        NETWORK_PROTOCOL_RATE_LIMITS
+            .with_label_values(&[
+                network_id.as_str(),
+                protocol_id.as_str(),
+                direction_label,
+                limit_label,
+            ])
+            .set(value as i64);
+    }
+}
+
+/// Counter of messages dropped because a protocol exceeded its rate limit
+pub static NETWORK_RATE_LIMITED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_rate_limited_messages",
+        "Number of messages dropped because the protocol exceeded its rate limit",
+        &["network_id", "protocol_id", "direction"]
+    )
+    .unwrap()
+});
+
+/// Counter of bytes dropped because a protocol exceeded its rate limit
+pub static NETWORK_RATE_LIMITED_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_rate_limited_bytes",
+        "Number of bytes dropped because the protocol exceeded its rate limit",
+        &["network_id", "protocol_id", "direction"]
+    )
+    .unwrap()
+});
+
+/// Increments the rate limited counters for the given protocol and direction
+pub fn rate_limited_message(
+    network_id: NetworkId,
+    protocol_id: ProtocolId,
+    direction_label: &'static str,
+    num_bytes: u64,
+) {
+    let label_values = [network_id.as_str(), protocol_id.as_str(), direction_label];
+    NETWORK_RATE_LIMITED_MESSAGES
+        .with_label_values(&label_values)
+        .inc();
+    NETWORK_RATE_LIMITED_BYTES
+        .with_label_values(&label_values)
+        .inc_by(num_bytes);
+}
+
 /// Time it takes to perform message serialization and deserialization
 pub static NETWORK_APPLICATION_SERIALIZATION_METRIC: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
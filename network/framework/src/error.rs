@@ -24,6 +24,19 @@ pub enum NetworkErrorKind {
 
     #[error("Peer not connected")]
     NotConnected,
+
+    #[error("Send queue full")]
+    SendQueueFull,
+}
+
+impl NetworkError {
+    /// Returns true iff the message was dropped because the send queue was full
+    pub fn is_send_queue_full(&self) -> bool {
+        matches!(
+            self.0.downcast_ref::<NetworkErrorKind>(),
+            Some(NetworkErrorKind::SendQueueFull)
+        )
+    }
 }
 
 impl From<NetworkErrorKind> for NetworkError {
@@ -63,6 +76,9 @@ impl From<PeerManagerError> for NetworkError {
             PeerManagerError::NotConnected(_) => anyhow::Error::new(err)
                 .context(NetworkErrorKind::NotConnected)
                 .into(),
+            PeerManagerError::SendQueueFull(_) => anyhow::Error::new(err)
+                .context(NetworkErrorKind::SendQueueFull)
+                .into(),
             err => anyhow::Error::new(err)
                 .context(NetworkErrorKind::PeerManagerError)
                 .into(),
@@ -42,6 +42,8 @@ pub struct NetworkSchema<'a> {
     network_address: Option<&'a NetworkAddress>,
     network_context: &'a NetworkContext,
     #[schema(display)]
+    proxy_address: Option<&'a NetworkAddress>,
+    #[schema(display)]
     remote_peer: Option<&'a PeerId>,
 }
 
@@ -54,6 +56,7 @@ impl<'a> NetworkSchema<'a> {
             message: None,
             network_address: None,
             network_context,
+            proxy_address: None,
             remote_peer: None,
         }
     }
@@ -65,9 +68,14 @@ impl<'a> NetworkSchema<'a> {
     }
 
     pub fn connection_metadata_with_address(self, metadata: &'a ConnectionMetadata) -> Self {
-        self.connection_id(&metadata.connection_id)
+        let schema = self
+            .connection_id(&metadata.connection_id)
             .connection_origin(&metadata.origin)
             .remote_peer(&metadata.remote_peer_id)
-            .network_address(&metadata.addr)
+            .network_address(&metadata.addr);
+        match &metadata.proxy_addr {
+            Some(proxy_addr) => schema.proxy_address(proxy_addr),
+            None => schema,
+        }
     }
 }
@@ -92,6 +92,31 @@ impl NoiseHandshakeError {
         use NoiseHandshakeError::*;
         matches!(self, ServerReplayDetected(_, _))
     }
+
+    /// Returns the cause of the handshake failure (used as a metric label)
+    pub fn failure_cause(&self) -> &'static str {
+        use NoiseHandshakeError::*;
+        match self {
+            ClientWriteFailed(_)
+            | ClientReadFailed(_)
+            | ClientFlushFailed(_)
+            | ServerReadFailed(_)
+            | ServerWriteFailed(_, _) => "noise_io",
+            ClientExpectingDifferentPubkey(_, _, _)
+            | UnauthenticatedClientPubkey(_, _)
+            | UnauthenticatedClient(_, _)
+            | ClientPeerIdMismatch(_, _, _) => "noise_unauthenticated",
+            MissingAntiReplayTimestamp(_) | ServerReplayDetected(_, _) => "noise_replay",
+            SelfDialDetected => "noise_self_dial",
+            MissingServerPublicKey
+            | BuildClientHandshakeMessageFailed(_)
+            | ClientFinalizeFailed(_)
+            | InvalidClientPeerId(_)
+            | ServerParseClient(_, _)
+            | BuildServerHandshakeMessageFailed(_, _) => "noise_invalid_message",
+            UnexpectedError(_) => "noise_unexpected",
+        }
+    }
 }
 
 impl From<application::error::Error> for NoiseHandshakeError {
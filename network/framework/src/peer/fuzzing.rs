@@ -3,6 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    application::{
+        deprecation::ProtocolDeprecations, rate_limit::ProtocolRateLimits,
+        traffic::PeerTrafficCounters,
+    },
     constants,
     peer::Peer,
     protocols::wire::{
@@ -108,6 +112,10 @@ pub fn fuzz(data: &[u8]) {
         constants::MAX_CONCURRENT_OUTBOUND_RPCS,
         constants::MAX_FRAME_SIZE,
         constants::MAX_MESSAGE_SIZE,
+        ProtocolDeprecations::default(),
+        ProtocolRateLimits::default(),
+        PeerTrafficCounters::default(),
+        None,
     );
     executor.spawn(peer.start());
 
@@ -16,9 +16,16 @@
 //! [`PeerManager`]: crate::peer_manager::PeerManager
 
 use crate::{
+    application::{
+        deprecation::{ProtocolDeprecations, ProtocolStatus},
+        rate_limit::{ProtocolRateLimiter, ProtocolRateLimits},
+        traffic::PeerTrafficCounters,
+    },
+    constants,
     counters::{
         self, network_application_inbound_traffic, network_application_outbound_traffic,
-        DECLINED_LABEL, FAILED_LABEL, RECEIVED_LABEL, SENT_LABEL, UNKNOWN_LABEL,
+        DECLINED_LABEL, DEPRECATED_LABEL, FAILED_LABEL, INBOUND_LABEL, OUTBOUND_LABEL,
+        RECEIVED_LABEL, SENT_LABEL, SUNSET_LABEL, UNKNOWN_LABEL,
     },
     logging::NetworkSchema,
     peer_manager::{PeerManagerError, TransportNotification},
@@ -29,7 +36,8 @@ use crate::{
         stream::{InboundStreamBuffer, OutboundStream, StreamMessage},
         wire::messaging::v1::{
             DirectSendMsg, ErrorCode, MultiplexMessage, MultiplexMessageSink,
-            MultiplexMessageStream, NetworkMessage, Priority, ReadError, WriteError,
+            MultiplexMessageStream, NetworkMessage, NotSupportedType, Priority, ReadError,
+            WriteError,
         },
     },
     transport::{self, Connection, ConnectionMetadata},
@@ -45,12 +53,17 @@ use futures::{
     self,
     channel::oneshot,
     io::{AsyncRead, AsyncWrite},
-    stream::StreamExt,
+    stream::{self, StreamExt},
     SinkExt,
 };
 use futures_util::stream::select;
 use serde::Serialize;
-use std::{collections::HashMap, fmt, panic, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    fmt, panic,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::{runtime::Handle, time::timeout};
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
@@ -81,6 +94,7 @@ pub enum PeerRequest {
 pub enum DisconnectReason {
     Requested,
     ConnectionLost,
+    HalfOpen, // The remote peer stopped responding (see `Peer::check_half_open_connection`)
 }
 
 impl fmt::Display for DisconnectReason {
@@ -88,6 +102,7 @@ impl fmt::Display for DisconnectReason {
         let s = match self {
             DisconnectReason::Requested => "Requested",
             DisconnectReason::ConnectionLost => "ConnectionLost",
+            DisconnectReason::HalfOpen => "HalfOpen",
         };
         write!(f, "{}", s)
     }
@@ -130,6 +145,16 @@ pub struct Peer<TSocket> {
     max_message_size: usize,
     /// Inbound stream buffer
     inbound_stream: InboundStreamBuffer,
+    /// The protocols deprecated by this node (and their sunset epochs)
+    protocol_deprecations: ProtocolDeprecations,
+    /// The rate limiter for the protocols on this connection
+    protocol_rate_limiter: ProtocolRateLimiter,
+    /// The traffic counters of the remote peer (per protocol)
+    peer_traffic_counters: PeerTrafficCounters,
+    /// The time after which the connection is considered half-open (if enabled)
+    half_open_connection_timeout: Option<Duration>,
+    /// The time of the oldest outbound rpc request sent since the last inbound message
+    awaiting_inbound_since: Option<Instant>,
 }
 
 impl<TSocket> Peer<TSocket>
@@ -152,6 +177,10 @@ where
         max_concurrent_outbound_rpcs: u32,
         max_frame_size: usize,
         max_message_size: usize,
+        protocol_deprecations: ProtocolDeprecations,
+        protocol_rate_limits: ProtocolRateLimits,
+        peer_traffic_counters: PeerTrafficCounters,
+        half_open_connection_timeout: Option<Duration>,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
@@ -174,17 +203,24 @@ where
                 remote_peer_id,
                 inbound_rpc_timeout,
                 max_concurrent_inbound_rpcs,
+                peer_traffic_counters.clone(),
             ),
             outbound_rpcs: OutboundRpcs::new(
                 network_context,
-                time_service,
+                time_service.clone(),
                 remote_peer_id,
                 max_concurrent_outbound_rpcs,
+                peer_traffic_counters.clone(),
             ),
             state: State::Connected,
             max_frame_size,
             max_message_size,
             inbound_stream: InboundStreamBuffer::new(max_fragments),
+            protocol_deprecations,
+            protocol_rate_limiter: ProtocolRateLimiter::new(protocol_rate_limits, time_service),
+            peer_traffic_counters,
+            half_open_connection_timeout,
+            awaiting_inbound_since: None,
         }
     }
 
@@ -224,6 +260,18 @@ where
             self.max_message_size,
         );
 
+        // If enabled, periodically check whether the connection has become half-open
+        let mut half_open_check_interval = match self.half_open_connection_timeout {
+            Some(_) => self
+                .time_service
+                .interval(Duration::from_millis(
+                    constants::HALF_OPEN_CONNECTION_CHECK_INTERVAL_MS,
+                ))
+                .boxed(),
+            None => stream::pending().boxed(),
+        }
+        .fuse();
+
         // Start main Peer event loop.
         let reason = loop {
             if let State::ShuttingDown(reason) = self.state {
@@ -300,6 +348,10 @@ where
                 // successfully or unsuccessfully completed request.
                 (request_id, maybe_completed_request) = self.outbound_rpcs.next_completed_request() => {
                     self.outbound_rpcs.handle_completed_request(request_id, maybe_completed_request);
+                },
+                // Reap the connection if the remote peer has stopped responding
+                _ = half_open_check_interval.select_next_some() => {
+                    self.check_half_open_connection();
                 }
             }
         };
@@ -440,6 +492,7 @@ where
     fn handle_inbound_network_message(
         &mut self,
         message: NetworkMessage,
+        write_reqs_tx: &mut aptos_channel::Sender<(), NetworkMessage>,
     ) -> Result<(), PeerManagerError> {
         match &message {
             NetworkMessage::DirectSendMsg(direct) => {
@@ -449,6 +502,16 @@ where
                     direct.protocol_id,
                     data_len as u64,
                 );
+                self.peer_traffic_counters
+                    .record_received(direct.protocol_id, data_len as u64);
+                if !self.is_protocol_accepted(direct.protocol_id)
+                    || !self.is_inbound_within_rate_limit(direct.protocol_id, data_len as u64)
+                {
+                    counters::direct_send_messages(&self.network_context, DECLINED_LABEL).inc();
+                    counters::direct_send_bytes(&self.network_context, DECLINED_LABEL)
+                        .inc_by(data_len as u64);
+                    return Ok(());
+                }
                 match self.upstream_handlers.get(&direct.protocol_id) {
                     None => {
                         counters::direct_send_messages(&self.network_context, UNKNOWN_LABEL).inc();
@@ -494,8 +557,44 @@ where
                     self.remote_peer_id().short_str(),
                     error_msg,
                 );
+
+                // Fail any pending requests on an RPC protocol the peer no longer supports
+                if let ErrorCode::NotSupported(NotSupportedType::RpcRequest(protocol_id)) =
+                    error_msg
+                {
+                    self.outbound_rpcs.fail_pending_requests(*protocol_id);
+                }
             },
             NetworkMessage::RpcRequest(request) => {
+                if !self.is_protocol_accepted(request.protocol_id) {
+                    // Reply with an error, so the remote peer fails the request (and
+                    // any other pending requests on the protocol) without a timeout.
+                    counters::rpc_messages(
+                        &self.network_context,
+                        counters::REQUEST_LABEL,
+                        INBOUND_LABEL,
+                        DECLINED_LABEL,
+                    )
+                    .inc();
+                    let error_code =
+                        ErrorCode::NotSupported(NotSupportedType::RpcRequest(request.protocol_id));
+                    write_reqs_tx.push((), NetworkMessage::Error(error_code))?;
+                    return Ok(());
+                }
+                if !self.is_inbound_within_rate_limit(
+                    request.protocol_id,
+                    request.raw_request.len() as u64,
+                ) {
+                    // The request is dropped, so the remote peer will time out
+                    counters::rpc_messages(
+                        &self.network_context,
+                        counters::REQUEST_LABEL,
+                        INBOUND_LABEL,
+                        DECLINED_LABEL,
+                    )
+                    .inc();
+                    return Ok(());
+                }
                 match self.upstream_handlers.get(&request.protocol_id) {
                     None => {
                         counters::direct_send_messages(&self.network_context, UNKNOWN_LABEL).inc();
@@ -533,9 +632,96 @@ where
         Ok(())
     }
 
+    /// Checks the deprecation status of the given protocol for an inbound
+    /// message. Usage of deprecated protocols is logged and counted, and
+    /// messages on sunset protocols are rejected (i.e., false is returned).
+    fn is_protocol_accepted(&self, protocol_id: ProtocolId) -> bool {
+        let protocol_status = self
+            .protocol_deprecations
+            .get_protocol_status(self.network_context.network_id(), protocol_id);
+        match protocol_status {
+            ProtocolStatus::Supported => return true,
+            ProtocolStatus::Deprecated { sunset_epoch } => {
+                counters::deprecated_protocol_message(
+                    self.network_context.network_id(),
+                    &self.remote_peer_id(),
+                    protocol_id,
+                    INBOUND_LABEL,
+                    DEPRECATED_LABEL,
+                );
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(60)),
+                    warn!(
+                        NetworkSchema::new(&self.network_context)
+                            .connection_metadata(&self.connection_metadata),
+                        "{} Peer {} is using deprecated protocol {}, which will be sunset at epoch {}",
+                        self.network_context,
+                        self.remote_peer_id().short_str(),
+                        protocol_id,
+                        sunset_epoch,
+                    )
+                );
+            },
+            ProtocolStatus::Sunset { sunset_epoch } => {
+                counters::deprecated_protocol_message(
+                    self.network_context.network_id(),
+                    &self.remote_peer_id(),
+                    protocol_id,
+                    INBOUND_LABEL,
+                    SUNSET_LABEL,
+                );
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(60)),
+                    warn!(
+                        NetworkSchema::new(&self.network_context)
+                            .connection_metadata(&self.connection_metadata),
+                        "{} Rejecting message from peer {} on protocol {}, which was sunset at epoch {}",
+                        self.network_context,
+                        self.remote_peer_id().short_str(),
+                        protocol_id,
+                        sunset_epoch,
+                    )
+                );
+            },
+        }
+        !protocol_status.is_sunset()
+    }
+
+    /// Checks the rate limit of the given protocol for an inbound message.
+    /// Messages exceeding the rate limit are counted and rejected (i.e.,
+    /// false is returned).
+    fn is_inbound_within_rate_limit(&mut self, protocol_id: ProtocolId, num_bytes: u64) -> bool {
+        if self
+            .protocol_rate_limiter
+            .allow_inbound(protocol_id, num_bytes)
+        {
+            return true;
+        }
+
+        counters::rate_limited_message(
+            self.network_context.network_id(),
+            protocol_id,
+            INBOUND_LABEL,
+            num_bytes,
+        );
+        sample!(
+            SampleRate::Duration(Duration::from_secs(10)),
+            warn!(
+                NetworkSchema::new(&self.network_context)
+                    .connection_metadata(&self.connection_metadata),
+                "{} Dropping message from peer {} on protocol {}, which exceeded its rate limit",
+                self.network_context,
+                self.remote_peer_id().short_str(),
+                protocol_id,
+            )
+        );
+        false
+    }
+
     fn handle_inbound_stream_message(
         &mut self,
         message: StreamMessage,
+        write_reqs_tx: &mut aptos_channel::Sender<(), NetworkMessage>,
     ) -> Result<(), PeerManagerError> {
         match message {
             StreamMessage::Header(header) => {
@@ -543,7 +729,7 @@ where
             },
             StreamMessage::Fragment(fragment) => {
                 if let Some(message) = self.inbound_stream.append_fragment(fragment)? {
-                    self.handle_inbound_network_message(message)?;
+                    self.handle_inbound_network_message(message, write_reqs_tx)?;
                 }
             },
         }
@@ -563,6 +749,9 @@ where
             self.remote_peer_id().short_str()
         );
 
+        // Any message (even a malformed one) shows that the remote peer is alive
+        self.awaiting_inbound_since = None;
+
         let message = match message {
             Ok(message) => message,
             Err(err) => match err {
@@ -587,8 +776,12 @@ where
         };
 
         match message {
-            MultiplexMessage::Message(message) => self.handle_inbound_network_message(message),
-            MultiplexMessage::Stream(message) => self.handle_inbound_stream_message(message),
+            MultiplexMessage::Message(message) => {
+                self.handle_inbound_network_message(message, write_reqs_tx)
+            },
+            MultiplexMessage::Stream(message) => {
+                self.handle_inbound_stream_message(message, write_reqs_tx)
+            },
         }
     }
 
@@ -609,6 +802,19 @@ where
                 // Create the direct send message
                 let message_len = message.mdata.len();
                 let protocol_id = message.protocol_id;
+                if !self
+                    .protocol_rate_limiter
+                    .allow_outbound(protocol_id, message_len as u64)
+                {
+                    counters::direct_send_messages(&self.network_context, DECLINED_LABEL).inc();
+                    counters::rate_limited_message(
+                        self.network_context.network_id(),
+                        protocol_id,
+                        OUTBOUND_LABEL,
+                        message_len as u64,
+                    );
+                    return;
+                }
                 let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
                     protocol_id,
                     priority: Priority::default(),
@@ -635,24 +841,99 @@ where
             },
             PeerRequest::SendRpc(request) => {
                 let protocol_id = request.protocol_id;
-                if let Err(e) = self
-                    .outbound_rpcs
-                    .handle_outbound_request(request, write_reqs_tx)
+                let request_len = request.data.len() as u64;
+                if !self
+                    .protocol_rate_limiter
+                    .allow_outbound(protocol_id, request_len)
                 {
-                    warn!(
-                        NetworkSchema::new(&self.network_context)
-                            .connection_metadata(&self.connection_metadata),
-                        error = %e,
-                        "Failed to send outbound rpc request for protocol {} to peer: {}. Error: {}",
+                    // Notify the application that the request was dropped
+                    counters::rpc_messages(
+                        &self.network_context,
+                        counters::REQUEST_LABEL,
+                        OUTBOUND_LABEL,
+                        DECLINED_LABEL,
+                    )
+                    .inc();
+                    counters::rate_limited_message(
+                        self.network_context.network_id(),
                         protocol_id,
-                        self.remote_peer_id().short_str(),
-                        e,
+                        OUTBOUND_LABEL,
+                        request_len,
                     );
+                    let _ = request.res_tx.send(Err(RpcError::RateLimited(protocol_id)));
+                    return;
+                }
+                match self
+                    .outbound_rpcs
+                    .handle_outbound_request(request, write_reqs_tx)
+                {
+                    Ok(()) => {
+                        // We now expect to hear back from the remote peer
+                        if self.awaiting_inbound_since.is_none() {
+                            self.awaiting_inbound_since = Some(self.time_service.now());
+                        }
+                    },
+                    Err(e) => {
+                        warn!(
+                            NetworkSchema::new(&self.network_context)
+                                .connection_metadata(&self.connection_metadata),
+                            error = %e,
+                            "Failed to send outbound rpc request for protocol {} to peer: {}. Error: {}",
+                            protocol_id,
+                            self.remote_peer_id().short_str(),
+                            e,
+                        );
+                    },
                 }
             },
         }
     }
 
+    /// Checks if the connection is half-open, i.e., the remote peer has sent nothing
+    /// since our oldest unanswered rpc request (e.g., a health checker ping) for
+    /// longer than the half-open connection timeout. This typically happens after an
+    /// asymmetric network failure, where our writes still succeed but the remote peer
+    /// is gone. If so, all pending requests are dropped and the connection is closed
+    /// (which removes the peer from the PeerManager and PeersAndMetadata).
+    fn check_half_open_connection(&mut self) {
+        let (Some(half_open_connection_timeout), Some(awaiting_inbound_since)) = (
+            self.half_open_connection_timeout,
+            self.awaiting_inbound_since,
+        ) else {
+            return; // Detection is disabled, or we're not expecting anything
+        };
+        let unresponsive_duration = self
+            .time_service
+            .now()
+            .saturating_duration_since(awaiting_inbound_since);
+        if unresponsive_duration < half_open_connection_timeout {
+            return;
+        }
+
+        // Drop all pending outbound rpcs and queued requests for the peer
+        let num_pending_rpcs = self.outbound_rpcs.clear_pending_requests();
+        self.peer_reqs_rx.clear();
+
+        counters::half_open_connections_reaped(
+            &self.network_context,
+            self.connection_metadata.origin,
+        )
+        .inc();
+        counters::half_open_connection_dropped_rpcs(&self.network_context)
+            .inc_by(num_pending_rpcs as u64);
+        warn!(
+            NetworkSchema::new(&self.network_context)
+                .connection_metadata(&self.connection_metadata),
+            "{} Closing half-open connection to peer: {}. The peer has been unresponsive for {:?} (dropped {} pending rpcs)",
+            self.network_context,
+            self.remote_peer_id().short_str(),
+            unresponsive_duration,
+            num_pending_rpcs,
+        );
+
+        self.shutdown(DisconnectReason::HalfOpen);
+    }
+
     /// Updates the outbound direct send metrics (e.g., messages and bytes sent)
     fn update_outbound_direct_send_metrics(&mut self, protocol_id: ProtocolId, data_len: u64) {
         // Update the metrics for the sent direct send message
@@ -661,6 +942,8 @@ where
 
         // Update the general network traffic metrics
         network_application_outbound_traffic(self.network_context, protocol_id, data_len);
+        self.peer_traffic_counters
+            .record_sent(protocol_id, data_len);
     }
 
     fn shutdown(&mut self, reason: DisconnectReason) {
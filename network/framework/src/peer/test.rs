@@ -3,6 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    application::{
+        deprecation::ProtocolDeprecations, rate_limit::ProtocolRateLimits,
+        traffic::PeerTrafficCounters,
+    },
     constants::{
         INBOUND_RPC_TIMEOUT_MS, MAX_CONCURRENT_INBOUND_RPCS, MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE, MAX_MESSAGE_SIZE, NETWORK_CHANNEL_SIZE,
@@ -16,8 +20,8 @@ use crate::{
         wire::{
             handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
             messaging::v1::{
-                DirectSendMsg, MultiplexMessage, MultiplexMessageSink, MultiplexMessageStream,
-                NetworkMessage, RpcRequest, RpcResponse,
+                DirectSendMsg, ErrorCode, MultiplexMessage, MultiplexMessageSink,
+                MultiplexMessageStream, NetworkMessage, NotSupportedType, RpcRequest, RpcResponse,
             },
         },
     },
@@ -97,6 +101,10 @@ fn build_test_peer(
         MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE,
         MAX_MESSAGE_SIZE,
+        ProtocolDeprecations::default(),
+        ProtocolRateLimits::default(),
+        PeerTrafficCounters::default(),
+        None,
     );
     let peer_handle = PeerHandle(peer_reqs_tx);
 
@@ -888,6 +896,87 @@ fn peer_send_rpc_timeout() {
 }
 
 // PeerManager can request a Peer to shutdown.
+#[test]
+fn peer_recv_sunset_rpc() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (upstream_handlers, _prot_rx) = test_upstream_handlers();
+    let (mut peer, _peer_handle, mut connection, _connection_notifs_rx) = build_test_peer(
+        rt.handle().clone(),
+        TimeService::mock(),
+        ConnectionOrigin::Inbound,
+        upstream_handlers,
+    );
+
+    // Sunset the protocol on the peer's network
+    let protocol_deprecations = ProtocolDeprecations::default();
+    protocol_deprecations.deprecate_protocol(peer.network_context.network_id(), PROTOCOL, 1);
+    protocol_deprecations.update_epoch(1);
+    peer.protocol_deprecations = protocol_deprecations;
+    let (mut client_sink, mut client_stream) = build_network_sink_stream(&mut connection);
+
+    let send_msg = MultiplexMessage::Message(NetworkMessage::RpcRequest(RpcRequest {
+        request_id: 123,
+        protocol_id: PROTOCOL,
+        priority: 0,
+        raw_request: Vec::from("hello world"),
+    }));
+    let error_msg = MultiplexMessage::Message(NetworkMessage::Error(ErrorCode::NotSupported(
+        NotSupportedType::RpcRequest(PROTOCOL),
+    )));
+
+    let client = async move {
+        // Client should send the rpc request.
+        client_sink.send(&send_msg).await.unwrap();
+        // Client should then receive an error (instead of a response).
+        let received = client_stream.next().await.unwrap().unwrap();
+        assert_eq!(received, error_msg);
+        // Client then closes connection.
+        client_sink.close().await.unwrap();
+    };
+    rt.block_on(future::join(peer.start(), client));
+}
+
+#[test]
+fn peer_send_rpc_not_supported() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let upstream_handlers = Arc::new(HashMap::new());
+    let (peer, mut peer_handle, mut connection, _connection_notifs_rx) = build_test_peer(
+        rt.handle().clone(),
+        TimeService::mock(),
+        ConnectionOrigin::Inbound,
+        upstream_handlers,
+    );
+    let (mut server_sink, mut server_stream) = build_network_sink_stream(&mut connection);
+    let timeout = Duration::from_millis(10_000);
+
+    let client = async move {
+        // Send RpcRequest to server, and verify it fails (without timing out)
+        let response = peer_handle
+            .send_rpc_request(PROTOCOL, Bytes::from(&b"hello world"[..]), timeout)
+            .await;
+        assert!(matches!(response, Err(RpcError::NotSupported(PROTOCOL))));
+        // Client then closes connection.
+    };
+    let server = async move {
+        // Server should receive the rpc request.
+        let received = server_stream.next().await.unwrap().unwrap();
+        assert!(matches!(
+            received,
+            MultiplexMessage::Message(NetworkMessage::RpcRequest(_))
+        ));
+
+        // Server replies that the protocol is not supported
+        let error = MultiplexMessage::Message(NetworkMessage::Error(ErrorCode::NotSupported(
+            NotSupportedType::RpcRequest(PROTOCOL),
+        )));
+        server_sink.send(&error).await.unwrap();
+        assert!(server_stream.next().await.is_none());
+    };
+    rt.block_on(future::join3(peer.start(), server, client));
+}
+
 #[test]
 fn peer_disconnect_request() {
     ::aptos_logger::Logger::init_for_testing();
@@ -940,6 +1029,59 @@ fn peer_disconnect_connection_lost() {
     rt.block_on(future::join(peer.start(), test));
 }
 
+// Peer will shutdown if the connection is half-open (i.e., the remote peer stops responding).
+#[test]
+fn peer_disconnect_half_open() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let mock_time = MockTimeService::new();
+    let upstream_handlers = Arc::new(HashMap::new());
+    let (mut peer, peer_handle, mut connection, mut connection_notifs_rx) = build_test_peer(
+        rt.handle().clone(),
+        mock_time.clone().into(),
+        ConnectionOrigin::Inbound,
+        upstream_handlers,
+    );
+    let half_open_connection_timeout = Duration::from_secs(30);
+    peer.half_open_connection_timeout = Some(half_open_connection_timeout);
+    let remote_peer_id = peer.remote_peer_id();
+    let (_server_sink, mut server_stream) = build_network_sink_stream(&mut connection);
+
+    let test = async move {
+        // Client sends rpc request (with a timeout longer than the half-open timeout).
+        let (response_tx, response_rx) = oneshot::channel();
+        let request = PeerRequest::SendRpc(OutboundRpcRequest {
+            protocol_id: PROTOCOL,
+            data: Bytes::from(&b"hello world"[..]),
+            res_tx: response_tx,
+            timeout: half_open_connection_timeout * 10,
+        });
+        peer_handle.0.push(PROTOCOL, request).unwrap();
+
+        // Server receives the rpc request from client, but never responds.
+        let received = server_stream.next().await.unwrap().unwrap();
+        assert!(matches!(
+            received,
+            MultiplexMessage::Message(NetworkMessage::RpcRequest(_))
+        ));
+
+        // Advancing time past the half-open timeout should close the connection.
+        mock_time.advance_async(half_open_connection_timeout).await;
+        assert_disconnected_event(
+            remote_peer_id,
+            DisconnectReason::HalfOpen,
+            &mut connection_notifs_rx,
+        )
+        .await;
+
+        // The pending rpc request should have been dropped.
+        assert!(response_rx.await.is_err());
+
+        drop(peer_handle);
+    };
+    rt.block_on(future::join(peer.start(), test));
+}
+
 #[test]
 fn peer_terminates_when_request_tx_has_dropped() {
     ::aptos_logger::Logger::init_for_testing();
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    application::storage::PeersAndMetadata,
+    application::{rate_limit::ProtocolRateLimits, storage::PeersAndMetadata},
     counters,
     noise::{stream::NoiseStream, HandshakeAuthMode},
     peer_manager::{
@@ -29,7 +29,7 @@ use aptos_netcore::transport::{
 };
 use aptos_time_service::TimeService;
 use aptos_types::{chain_id::ChainId, network_address::NetworkAddress, PeerId};
-use std::{clone::Clone, collections::HashMap, fmt::Debug, sync::Arc};
+use std::{clone::Clone, collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
 use tokio::runtime::Handle;
 
 /// Inbound and Outbound connections are always secured with NoiseIK.  The dialer
@@ -78,6 +78,8 @@ struct PeerManagerContext {
     max_message_size: usize,
     inbound_connection_limit: usize,
     tcp_buffer_cfg: TCPBufferCfg,
+    half_open_connection_timeout: Option<Duration>,
+    protocol_rate_limits: ProtocolRateLimits,
 }
 
 impl PeerManagerContext {
@@ -100,6 +102,8 @@ impl PeerManagerContext {
         max_message_size: usize,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
+        half_open_connection_timeout: Option<Duration>,
+        protocol_rate_limits: ProtocolRateLimits,
     ) -> Self {
         Self {
             pm_reqs_tx,
@@ -116,6 +120,8 @@ impl PeerManagerContext {
             max_message_size,
             inbound_connection_limit,
             tcp_buffer_cfg,
+            half_open_connection_timeout,
+            protocol_rate_limits,
         }
     }
 
@@ -173,6 +179,8 @@ impl PeerManagerBuilder {
         enable_proxy_protocol: bool,
         inbound_connection_limit: usize,
         tcp_buffer_cfg: TCPBufferCfg,
+        half_open_connection_timeout: Option<Duration>,
+        protocol_rate_limits: ProtocolRateLimits,
     ) -> Self {
         // Setup channel to send requests to peer manager.
         let (pm_reqs_tx, pm_reqs_rx) = aptos_channel::new(
@@ -207,6 +215,8 @@ impl PeerManagerBuilder {
                 max_message_size,
                 inbound_connection_limit,
                 tcp_buffer_cfg,
+                half_open_connection_timeout,
+                protocol_rate_limits,
             )),
             peer_manager: None,
             listen_address,
@@ -250,6 +260,9 @@ impl PeerManagerBuilder {
         let protos = transport_context.supported_protocols;
         let chain_id = transport_context.chain_id;
         let enable_proxy_protocol = transport_context.enable_proxy_protocol;
+        let protocol_deprecations = transport_context
+            .peers_and_metadata
+            .get_protocol_deprecations();
 
         let (key, auth_mode) = match transport_context.authentication_mode {
             AuthenticationMode::MaybeMutual(key) => (
@@ -279,6 +292,7 @@ impl PeerManagerBuilder {
                         chain_id,
                         protos,
                         enable_proxy_protocol,
+                        protocol_deprecations,
                     ),
                     executor,
                 )))
@@ -295,6 +309,7 @@ impl PeerManagerBuilder {
                     chain_id,
                     protos,
                     enable_proxy_protocol,
+                    protocol_deprecations,
                 ),
                 executor,
             ))),
@@ -340,6 +355,8 @@ impl PeerManagerBuilder {
             pm_context.max_frame_size,
             pm_context.max_message_size,
             pm_context.inbound_connection_limit,
+            pm_context.half_open_connection_timeout,
+            pm_context.protocol_rate_limits,
         );
 
         // PeerManager constructor appends a public key to the listen_address.
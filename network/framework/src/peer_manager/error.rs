@@ -26,6 +26,9 @@ pub enum PeerManagerError {
     #[error("Not connected with Peer {0}")]
     NotConnected(PeerId),
 
+    #[error("Send queue is full for Peer {0}")]
+    SendQueueFull(PeerId),
+
     #[error("Already connected at {0}")]
     AlreadyConnected(NetworkAddress),
 
@@ -54,7 +54,10 @@ mod types;
 
 pub use self::error::PeerManagerError;
 use crate::{
-    application::{error::Error, storage::PeersAndMetadata},
+    application::{
+        error::Error, metadata::PeerRestriction, rate_limit::ProtocolRateLimits,
+        storage::PeersAndMetadata,
+    },
     peer_manager::transport::{TransportHandler, TransportRequest},
     protocols::network::{ReceivedMessage, SerializedRequest},
 };
@@ -117,6 +120,10 @@ where
     max_message_size: usize,
     /// Inbound connection limit separate of outbound connections
     inbound_connection_limit: usize,
+    /// The time after which unresponsive connections are considered half-open
+    half_open_connection_timeout: Option<Duration>,
+    /// The rate limits (per protocol) applied to each connection
+    protocol_rate_limits: ProtocolRateLimits,
 }
 
 impl<TTransport, TSocket> PeerManager<TTransport, TSocket>
@@ -144,6 +151,8 @@ where
         max_frame_size: usize,
         max_message_size: usize,
         inbound_connection_limit: usize,
+        half_open_connection_timeout: Option<Duration>,
+        protocol_rate_limits: ProtocolRateLimits,
     ) -> Self {
         let (transport_notifs_tx, transport_notifs_rx) = aptos_channels::new(
             channel_size,
@@ -185,6 +194,8 @@ where
             max_frame_size,
             max_message_size,
             inbound_connection_limit,
+            half_open_connection_timeout,
+            protocol_rate_limits,
         }
     }
 
@@ -351,6 +362,28 @@ where
 
         // Verify that we have not reached the max connection limit for unknown inbound peers
         if conn.metadata.origin == ConnectionOrigin::Inbound {
+            // Reject inbound connections from peers that have been banned by an application
+            let peer_network_id = PeerNetworkId::new(
+                self.network_context.network_id(),
+                conn.metadata.remote_peer_id,
+            );
+            if self
+                .peers_and_metadata
+                .get_peer_restriction(&peer_network_id)
+                == Some(PeerRestriction::Banned)
+            {
+                info!(
+                    NetworkSchema::new(&self.network_context)
+                        .connection_metadata_with_address(&conn.metadata),
+                    "{} Connection rejected from banned peer: {}",
+                    self.network_context,
+                    conn.metadata
+                );
+                counters::connections_rejected(&self.network_context, conn.metadata.origin).inc();
+                self.disconnect(conn);
+                return;
+            }
+
             // Everything below here is meant for unknown peers only. The role comes from
             // the Noise handshake and if it's not `Unknown` then it is trusted.
             if conn.metadata.role == PeerRole::Unknown {
@@ -661,6 +694,15 @@ where
             constants::MAX_CONCURRENT_OUTBOUND_RPCS,
             self.max_frame_size,
             self.max_message_size,
+            self.peers_and_metadata.get_protocol_deprecations(),
+            self.protocol_rate_limits.clone(),
+            self.peers_and_metadata
+                .get_peer_traffic_stats()
+                .register_peer(PeerNetworkId::new(
+                    self.network_context.network_id(),
+                    peer_id,
+                )),
+            self.half_open_connection_timeout,
         );
         self.executor.spawn(peer.start());
 
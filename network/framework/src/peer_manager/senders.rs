@@ -10,7 +10,7 @@ use crate::{
     },
     ProtocolId,
 };
-use aptos_channels::{self, aptos_channel};
+use aptos_channels::{self, aptos_channel, aptos_channel::ElementStatus};
 use aptos_types::{network_address::NetworkAddress, PeerId};
 use bytes::Bytes;
 use futures::channel::oneshot;
@@ -54,6 +54,28 @@ impl PeerManagerRequestSender {
         Ok(())
     }
 
+    /// Same as `send_to`, but an error is also returned if the message was dropped
+    /// because the queue for the remote peer (and protocol) is full.
+    pub fn try_send_to(
+        &self,
+        peer_id: PeerId,
+        protocol_id: ProtocolId,
+        mdata: Bytes,
+    ) -> Result<(), PeerManagerError> {
+        // Note: the queue drops the newest message when it is full, so the status
+        // of the message is available as soon as the message is pushed.
+        let (status_sender, mut status_receiver) = oneshot::channel();
+        self.inner.push_with_feedback(
+            (peer_id, protocol_id),
+            PeerManagerRequest::SendDirectSend(peer_id, Message { protocol_id, mdata }),
+            Some(status_sender),
+        )?;
+        if let Ok(Some(ElementStatus::Dropped(_))) = status_receiver.try_recv() {
+            return Err(PeerManagerError::SendQueueFull(peer_id));
+        }
+        Ok(())
+    }
+
     /// Send the _same_ message to many recipients using the direct-send protocol.
     ///
     /// This method is an optimization so that we can avoid serializing and
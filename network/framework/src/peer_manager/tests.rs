@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    application::storage::PeersAndMetadata,
+    application::{rate_limit::ProtocolRateLimits, storage::PeersAndMetadata},
     constants,
     peer::DisconnectReason,
     peer_manager::{
@@ -25,7 +25,7 @@ use anyhow::anyhow;
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
     config::{PeerRole, MAX_INBOUND_CONNECTIONS},
-    network_id::{NetworkContext, NetworkId},
+    network_id::{NetworkContext, NetworkId, PeerNetworkId},
 };
 use aptos_memsocket::MemorySocket;
 use aptos_netcore::transport::{
@@ -35,7 +35,7 @@ use aptos_time_service::TimeService;
 use aptos_types::{network_address::NetworkAddress, PeerId};
 use bytes::Bytes;
 use futures::{channel::oneshot, io::AsyncWriteExt, stream::StreamExt};
-use std::error::Error;
+use std::{error::Error, time::Duration};
 use tokio::runtime::Handle;
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
@@ -116,6 +116,8 @@ fn build_test_peer_manager(
         constants::MAX_FRAME_SIZE,
         constants::MAX_MESSAGE_SIZE,
         MAX_INBOUND_CONNECTIONS,
+        None,
+        ProtocolRateLimits::default(),
     );
 
     (
@@ -651,6 +653,65 @@ fn test_dial_disconnect() {
     runtime.block_on(test);
 }
 
+#[test]
+fn test_reject_banned_inbound_peer() {
+    ::aptos_logger::Logger::init_for_testing();
+    let runtime = ::tokio::runtime::Runtime::new().unwrap();
+
+    // Create a list of ordered PeerIds so we can ensure how PeerIds will be compared.
+    let ids = ordered_peer_ids(2);
+    let (mut peer_manager, _request_tx, _connection_reqs_tx, mut conn_status_rx) =
+        build_test_peer_manager(runtime.handle().clone(), ids[1]);
+
+    let test = async move {
+        // Ban the remote peer
+        let peer_network_id = PeerNetworkId::new(NetworkId::Validator, ids[0]);
+        peer_manager
+            .peers_and_metadata
+            .ban_peer(peer_network_id, Duration::from_secs(3600));
+
+        // Verify that an inbound connection from the banned peer is rejected
+        let (mut outbound, inbound) = build_test_connection();
+        peer_manager.handle_new_connection_event(create_connection(
+            inbound,
+            ids[0],
+            NetworkAddress::mock(),
+            ConnectionOrigin::Inbound,
+            ConnectionId::from(0),
+        ));
+        assert!(!peer_manager.active_peers.contains_key(&ids[0]));
+        assert!(ping_pong(&mut outbound).await.is_err());
+
+        // Remove the ban and verify that a new inbound connection is accepted
+        peer_manager
+            .peers_and_metadata
+            .remove_peer_restriction(&peer_network_id);
+        let (_outbound, inbound) = build_test_connection();
+        peer_manager.handle_new_connection_event(create_connection(
+            inbound,
+            ids[0],
+            NetworkAddress::mock(),
+            ConnectionOrigin::Inbound,
+            ConnectionId::from(1),
+        ));
+        assert!(peer_manager.active_peers.contains_key(&ids[0]));
+
+        // Expect a NewPeer notification for the accepted connection only
+        let conn_notif = conn_status_rx.next().await.unwrap();
+        match conn_notif {
+            ConnectionNotification::NewPeer(metadata, _) => {
+                assert_eq!(metadata.connection_id, ConnectionId::from(1));
+            },
+            notification => panic!(
+                "Expected a NewPeer notification, received: {:?}",
+                notification
+            ),
+        }
+    };
+
+    runtime.block_on(test);
+}
+
 fn add_peer_to_manager<TSocket: transport::TSocket>(
     peer_manager: &mut PeerManager<
         BoxedTransport<Connection<TSocket>, impl Error + Sync + Send + 'static>,
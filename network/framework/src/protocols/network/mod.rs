@@ -408,6 +408,20 @@ impl<TMessage: Message + Send + 'static> NetworkSender<TMessage> {
         Ok(())
     }
 
+    /// Sends a raw message to a single recipient. Unlike `send_to_raw`, an error is
+    /// also returned if the message was dropped because the send queue is full.
+    /// Provides a wrapper over `[peer_manager::PeerManagerRequestSender::try_send_to]`.
+    pub fn try_send_to_raw(
+        &self,
+        recipient: PeerId,
+        protocol: ProtocolId,
+        message: Bytes,
+    ) -> Result<(), NetworkError> {
+        self.peer_mgr_reqs_tx
+            .try_send_to(recipient, protocol, message)?;
+        Ok(())
+    }
+
     /// Send a protobuf message to a many recipients. Provides a wrapper over
     /// `[peer_manager::PeerManagerRequestSender::send_to_many]`.
     pub fn send_to_many(
@@ -4,7 +4,7 @@
 
 //! Rpc protocol errors
 
-use crate::peer_manager::PeerManagerError;
+use crate::{peer_manager::PeerManagerError, ProtocolId};
 use anyhow::anyhow;
 use aptos_types::PeerId;
 use futures::channel::{mpsc, oneshot};
@@ -42,6 +42,12 @@ pub enum RpcError {
 
     #[error("Rpc timed out")]
     TimedOut,
+
+    #[error("Rpc rate limited on protocol: {0}")]
+    RateLimited(ProtocolId),
+
+    #[error("Rpc protocol is not supported by the remote peer: {0}")]
+    NotSupported(ProtocolId),
 }
 
 impl From<PeerManagerError> for RpcError {
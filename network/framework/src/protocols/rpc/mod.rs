@@ -45,6 +45,7 @@
 //! [`Peer`]: crate::peer::Peer
 
 use crate::{
+    application::traffic::PeerTrafficCounters,
     counters::{
         self, network_application_inbound_traffic, network_application_outbound_traffic,
         CANCELED_LABEL, DECLINED_LABEL, EXPIRED_LABEL, FAILED_LABEL, INBOUND_LABEL, OUTBOUND_LABEL,
@@ -182,6 +183,8 @@ pub struct InboundRpcs {
     /// Only allow this many concurrent inbound rpcs at one time from this remote
     /// peer.  New inbound requests exceeding this limit will be dropped.
     max_concurrent_inbound_rpcs: u32,
+    /// The traffic counters of the remote peer (per protocol)
+    peer_traffic_counters: PeerTrafficCounters,
 }
 
 impl InboundRpcs {
@@ -191,6 +194,7 @@ impl InboundRpcs {
         remote_peer_id: PeerId,
         inbound_rpc_timeout: Duration,
         max_concurrent_inbound_rpcs: u32,
+        peer_traffic_counters: PeerTrafficCounters,
     ) -> Self {
         Self {
             network_context,
@@ -199,6 +203,7 @@ impl InboundRpcs {
             inbound_rpc_tasks: FuturesUnordered::new(),
             inbound_rpc_timeout,
             max_concurrent_inbound_rpcs,
+            peer_traffic_counters,
         }
     }
 
@@ -308,6 +313,8 @@ impl InboundRpcs {
 
         // Update the general network traffic metrics
         network_application_inbound_traffic(self.network_context, protocol_id, data_len);
+        self.peer_traffic_counters
+            .record_received(protocol_id, data_len);
     }
 
     /// Method for `Peer` actor to drive the pending inbound rpc tasks forward.
@@ -379,6 +386,8 @@ impl InboundRpcs {
 
         // Update the general network traffic metrics
         network_application_outbound_traffic(self.network_context, protocol_id, data_len);
+        self.peer_traffic_counters
+            .record_sent(protocol_id, data_len);
     }
 }
 
@@ -406,10 +415,13 @@ pub struct OutboundRpcs {
     /// Maps a `RequestId` into a handle to a task in the `outbound_rpc_tasks`
     /// completion queue. When a new `RpcResponse` message comes in, we will use
     /// this map to notify the corresponding task that its response has arrived.
-    pending_outbound_rpcs: HashMap<RequestId, (ProtocolId, oneshot::Sender<RpcResponse>)>,
+    pending_outbound_rpcs:
+        HashMap<RequestId, (ProtocolId, oneshot::Sender<Result<RpcResponse, RpcError>>)>,
     /// Only allow this many concurrent outbound rpcs at one time from this remote
     /// peer. New outbound requests exceeding this limit will be dropped.
     max_concurrent_outbound_rpcs: u32,
+    /// The traffic counters of the remote peer (per protocol)
+    peer_traffic_counters: PeerTrafficCounters,
 }
 
 impl OutboundRpcs {
@@ -418,6 +430,7 @@ impl OutboundRpcs {
         time_service: TimeService,
         remote_peer_id: PeerId,
         max_concurrent_outbound_rpcs: u32,
+        peer_traffic_counters: PeerTrafficCounters,
     ) -> Self {
         Self {
             network_context,
@@ -427,6 +440,7 @@ impl OutboundRpcs {
             outbound_rpc_tasks: FuturesUnordered::new(),
             pending_outbound_rpcs: HashMap::new(),
             max_concurrent_outbound_rpcs,
+            peer_traffic_counters,
         }
     }
 
@@ -503,7 +517,7 @@ impl OutboundRpcs {
         self.update_outbound_rpc_request_metrics(protocol_id, req_len);
 
         // Create channel over which response is delivered to outbound_rpc_task.
-        let (response_tx, response_rx) = oneshot::channel::<RpcResponse>();
+        let (response_tx, response_rx) = oneshot::channel::<Result<RpcResponse, RpcError>>();
 
         // Store send-side in the pending map so we can notify outbound_rpc_task
         // when the rpc response has arrived.
@@ -519,7 +533,8 @@ impl OutboundRpcs {
             .map(|result| {
                 // Flatten errors.
                 match result {
-                    Ok(Ok(response)) => Ok(Bytes::from(response.raw_response)),
+                    Ok(Ok(Ok(response))) => Ok(Bytes::from(response.raw_response)),
+                    Ok(Ok(Err(error))) => Err(error),
                     Ok(Err(oneshot::Canceled)) => Err(RpcError::UnexpectedResponseChannelCancel),
                     Err(timeout::Elapsed) => Err(RpcError::TimedOut),
                 }
@@ -589,6 +604,36 @@ impl OutboundRpcs {
 
         // Update the general network traffic metrics
         network_application_outbound_traffic(self.network_context, protocol_id, data_len);
+        self.peer_traffic_counters
+            .record_sent(protocol_id, data_len);
+    }
+
+    /// Drops all pending outbound rpc requests (e.g., because the connection is
+    /// being closed) and returns the number of requests dropped. The application
+    /// layer sees each dropped request as canceled.
+    pub fn clear_pending_requests(&mut self) -> usize {
+        let num_pending_requests = self.outbound_rpc_tasks.len();
+        self.pending_outbound_rpcs.clear();
+        self.outbound_rpc_tasks = FuturesUnordered::new();
+        num_pending_requests
+    }
+
+    /// Fails all pending outbound rpc requests on the given protocol (e.g., because
+    /// the remote peer replied that it no longer supports the protocol) and returns
+    /// the number of requests failed.
+    pub fn fail_pending_requests(&mut self, protocol_id: ProtocolId) -> usize {
+        let request_ids: Vec<_> = self
+            .pending_outbound_rpcs
+            .iter()
+            .filter(|(_, (pending_protocol_id, _))| *pending_protocol_id == protocol_id)
+            .map(|(request_id, _)| *request_id)
+            .collect();
+        for request_id in &request_ids {
+            if let Some((_, response_tx)) = self.pending_outbound_rpcs.remove(request_id) {
+                let _ = response_tx.send(Err(RpcError::NotSupported(protocol_id)));
+            }
+        }
+        request_ids.len()
     }
 
     /// Method for `Peer` actor to drive the pending outbound rpc tasks forward.
@@ -695,7 +740,7 @@ impl OutboundRpcs {
                 protocol_id,
                 response.raw_response.len() as u64,
             );
-            response_tx.send(response).is_err()
+            response_tx.send(Ok(response)).is_err()
         } else {
             true
         };
@@ -748,5 +793,7 @@ impl OutboundRpcs {
 
         // Update the general network traffic metrics
         network_application_inbound_traffic(self.network_context, protocol_id, data_len);
+        self.peer_traffic_counters
+            .record_received(protocol_id, data_len);
     }
 }
@@ -53,7 +53,7 @@ pub enum ProtocolId {
     ConsensusDirectSendJson = 6, // Json provides flexibility for backwards compatible upgrade
     ConsensusRpcJson = 7,
     StorageServiceRpc = 8,
-    MempoolRpc = 9, // Currently unused
+    MempoolRpc = 9, // Used for transaction fetches
     PeerMonitoringServiceRpc = 10,
     ConsensusRpcCompressed = 11,
     ConsensusDirectSendCompressed = 12,
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
+use aptos_config::config::NETWORK_PROTOCOL_NAMES;
 use std::iter::FromIterator;
 
 // Ensure serialization of MessagingProtocolVersion enum takes 1 byte.
@@ -23,6 +24,17 @@ fn protocols_to_from_iter() {
     );
 }
 
+// Ensure the protocol names known to the config (i.e., used to sanitize the
+// protocol rate limits and deprecations) match the protocol IDs.
+#[test]
+fn protocol_names_match_config() {
+    let protocol_names: Vec<_> = ProtocolId::all()
+        .iter()
+        .map(|protocol_id| protocol_id.as_str())
+        .collect();
+    assert_eq!(protocol_names, NETWORK_PROTOCOL_NAMES);
+}
+
 #[test]
 fn test_as_u8_serde_equiv() {
     for protocol in ProtocolId::all() {
@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    application::deprecation::ProtocolDeprecations,
+    counters,
     logging::NetworkSchema,
     noise::{stream::NoiseStream, AntiReplayTimestamps, HandshakeAuthMode, NoiseUpgrader},
     protocols::{
@@ -106,6 +108,11 @@ pub struct ConnectionMetadata {
     pub messaging_protocol: MessagingProtocolVersion,
     pub application_protocols: ProtocolIdSet,
     pub role: PeerRole,
+    /// The address of the proxy (e.g., a TCP load balancer) that forwarded the
+    /// inbound connection. This is only set if the proxy protocol is enabled and
+    /// the proxy reported the original client address (stored in `addr`).
+    #[serde(default)]
+    pub proxy_addr: Option<NetworkAddress>,
 }
 
 impl ConnectionMetadata {
@@ -126,6 +133,7 @@ impl ConnectionMetadata {
             messaging_protocol,
             application_protocols,
             role,
+            proxy_addr: None,
         }
     }
 
@@ -152,6 +160,7 @@ impl ConnectionMetadata {
             addr: NetworkAddress::mock(),
             messaging_protocol: MessagingProtocolVersion::V1,
             application_protocols: ProtocolIdSet::empty(),
+            proxy_addr: None,
         }
     }
 
@@ -159,6 +168,11 @@ impl ConnectionMetadata {
     pub fn is_outbound_connection(&self) -> bool {
         self.origin == ConnectionOrigin::Outbound
     }
+
+    /// Returns true iff the connection was forwarded by a proxy
+    pub fn is_proxied_connection(&self) -> bool {
+        self.proxy_addr.is_some()
+    }
 }
 
 impl fmt::Debug for ConnectionMetadata {
@@ -179,7 +193,11 @@ impl fmt::Display for ConnectionMetadata {
             self.messaging_protocol,
             self.application_protocols,
             self.role
-        )
+        )?;
+        if let Some(proxy_addr) = &self.proxy_addr {
+            write!(f, "(proxied via {})", proxy_addr)?;
+        }
+        Ok(())
     }
 }
 
@@ -210,6 +228,7 @@ pub struct UpgradeContext {
     supported_protocols: BTreeMap<MessagingProtocolVersion, ProtocolIdSet>,
     chain_id: ChainId,
     network_id: NetworkId,
+    protocol_deprecations: ProtocolDeprecations,
 }
 
 impl UpgradeContext {
@@ -219,6 +238,7 @@ impl UpgradeContext {
         supported_protocols: BTreeMap<MessagingProtocolVersion, ProtocolIdSet>,
         chain_id: ChainId,
         network_id: NetworkId,
+        protocol_deprecations: ProtocolDeprecations,
     ) -> Self {
         UpgradeContext {
             noise,
@@ -226,8 +246,23 @@ impl UpgradeContext {
             supported_protocols,
             chain_id,
             network_id,
+            protocol_deprecations,
         }
     }
+
+    /// Returns the protocols to advertise in the handshake, i.e., the supported
+    /// protocols, excluding any that have been sunset on this network.
+    fn get_advertised_protocols(&self) -> BTreeMap<MessagingProtocolVersion, ProtocolIdSet> {
+        self.supported_protocols
+            .iter()
+            .map(|(messaging_protocol, protocols)| {
+                let protocols = self
+                    .protocol_deprecations
+                    .remove_sunset_protocols(self.network_id, protocols);
+                (*messaging_protocol, protocols)
+            })
+            .collect()
+    }
 }
 
 /// If we have proxy protocol enabled, then prepend the un-proxied address to the error.
@@ -258,8 +293,8 @@ async fn upgrade_inbound<T: TSocket>(
 
     // If we have proxy protocol enabled, process the event, otherwise skip it
     // TODO: This would make more sense to build this in at instantiation so we don't need to put the if statement here
-    let addr = if proxy_protocol_enabled {
-        proxy_protocol::read_header(&addr, &mut socket)
+    let (addr, proxy_addr) = if proxy_protocol_enabled {
+        let source_addr = proxy_protocol::read_header(&addr, &mut socket)
             .await
             .map_err(|err| {
                 debug!(
@@ -268,10 +303,24 @@ async fn upgrade_inbound<T: TSocket>(
                     "ProxyProtocol: Failed to read header: {}",
                     err
                 );
+                counters::handshake_failure(
+                    &ctxt.noise.network_context,
+                    origin,
+                    counters::PROXY_PROTOCOL_FAILURE_LABEL,
+                )
+                .inc();
                 err
-            })?
+            })?;
+
+        // The proxy may not report the client address (e.g., for health checks),
+        // in which case the connection is attributed to the proxy itself.
+        if source_addr != addr {
+            (source_addr, Some(addr))
+        } else {
+            (addr, None)
+        }
     } else {
-        addr
+        (addr, None)
     };
 
     // try authenticating via noise handshake
@@ -289,6 +338,8 @@ async fn upgrade_inbound<T: TSocket>(
                     )
                 );
             }
+            counters::handshake_failure(&ctxt.noise.network_context, origin, err.failure_cause())
+                .inc();
             let err = io::Error::new(io::ErrorKind::Other, err);
             add_pp_addr(proxy_protocol_enabled, err, &addr)
         })?;
@@ -297,18 +348,32 @@ async fn upgrade_inbound<T: TSocket>(
 
     // exchange HandshakeMsg
     let handshake_msg = HandshakeMsg {
-        supported_protocols: ctxt.supported_protocols.clone(),
+        supported_protocols: ctxt.get_advertised_protocols(),
         chain_id: ctxt.chain_id,
         network_id: ctxt.network_id,
     };
     let remote_handshake = exchange_handshake(&handshake_msg, &mut socket)
         .await
-        .map_err(|err| add_pp_addr(proxy_protocol_enabled, err, &addr))?;
+        .map_err(|err| {
+            counters::handshake_failure(
+                &ctxt.noise.network_context,
+                origin,
+                counters::HANDSHAKE_EXCHANGE_FAILURE_LABEL,
+            )
+            .inc();
+            add_pp_addr(proxy_protocol_enabled, err, &addr)
+        })?;
 
     // try to negotiate common aptosnet version and supported application protocols
     let (messaging_protocol, application_protocols) = handshake_msg
         .perform_handshake(&remote_handshake)
         .map_err(|err| {
+            counters::handshake_failure(
+                &ctxt.noise.network_context,
+                origin,
+                counters::PROTOCOL_NEGOTIATION_FAILURE_LABEL,
+            )
+            .inc();
             let err = format!(
                 "handshake negotiation with peer {} failed: {}",
                 remote_peer_id.short_str(),
@@ -322,18 +387,17 @@ async fn upgrade_inbound<T: TSocket>(
         })?;
 
     // return successful connection
-    Ok(Connection {
-        socket,
-        metadata: ConnectionMetadata::new(
-            remote_peer_id,
-            CONNECTION_ID_GENERATOR.next(),
-            addr,
-            origin,
-            messaging_protocol,
-            application_protocols,
-            peer_role,
-        ),
-    })
+    let mut metadata = ConnectionMetadata::new(
+        remote_peer_id,
+        CONNECTION_ID_GENERATOR.next(),
+        addr,
+        origin,
+        messaging_protocol,
+        application_protocols,
+        peer_role,
+    );
+    metadata.proxy_addr = proxy_addr;
+    Ok(Connection { socket, metadata })
 }
 
 /// Upgrade an outbound connection. This means we run a Noise IK handshake for
@@ -371,6 +435,8 @@ pub async fn upgrade_outbound<T: TSocket>(
                     )
                 );
             }
+            counters::handshake_failure(&ctxt.noise.network_context, origin, err.failure_cause())
+                .inc();
             io::Error::new(io::ErrorKind::Other, err)
         })?;
 
@@ -379,16 +445,32 @@ pub async fn upgrade_outbound<T: TSocket>(
 
     // exchange HandshakeMsg
     let handshake_msg = HandshakeMsg {
-        supported_protocols: ctxt.supported_protocols.clone(),
+        supported_protocols: ctxt.get_advertised_protocols(),
         chain_id: ctxt.chain_id,
         network_id: ctxt.network_id,
     };
-    let remote_handshake = exchange_handshake(&handshake_msg, &mut socket).await?;
+    let remote_handshake = exchange_handshake(&handshake_msg, &mut socket)
+        .await
+        .map_err(|err| {
+            counters::handshake_failure(
+                &ctxt.noise.network_context,
+                origin,
+                counters::HANDSHAKE_EXCHANGE_FAILURE_LABEL,
+            )
+            .inc();
+            err
+        })?;
 
     // try to negotiate common aptosnet version and supported application protocols
     let (messaging_protocol, application_protocols) = handshake_msg
         .perform_handshake(&remote_handshake)
         .map_err(|e| {
+            counters::handshake_failure(
+                &ctxt.noise.network_context,
+                origin,
+                counters::PROTOCOL_NEGOTIATION_FAILURE_LABEL,
+            )
+            .inc();
             let e = format!(
                 "handshake negotiation with peer {} failed: {}",
                 remote_peer_id, e
@@ -449,6 +531,7 @@ where
         chain_id: ChainId,
         application_protocols: ProtocolIdSet,
         enable_proxy_protocol: bool,
+        protocol_deprecations: ProtocolDeprecations,
     ) -> Self {
         // build supported protocols
         let mut supported_protocols = BTreeMap::new();
@@ -462,6 +545,7 @@ where
             supported_protocols,
             chain_id,
             network_context.network_id(),
+            protocol_deprecations,
         );
 
         Self {
@@ -3,13 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    application::storage::PeersAndMetadata,
+    application::{deprecation::ProtocolDeprecations, storage::PeersAndMetadata},
     protocols::wire::handshake::v1::{MessagingProtocolVersion, ProtocolId, ProtocolIdSet},
     testutils,
     transport::*,
 };
 use aptos_config::config::{Peer, PeerRole, PeerSet, HANDSHAKE_VERSION};
 use aptos_crypto::{test_utils::TEST_SEED, traits::Uniform, x25519, x25519::PrivateKey};
+use aptos_memsocket::MemorySocket;
 use aptos_netcore::{
     framing::{read_u16frame, write_u16frame},
     transport::{memory, ConnectionOrigin, Transport},
@@ -22,7 +23,11 @@ use aptos_types::{
     PeerId,
 };
 use bytes::{Bytes, BytesMut};
-use futures::{future, io::AsyncWriteExt, stream::StreamExt};
+use futures::{
+    future::{self, BoxFuture, FutureExt},
+    io::AsyncWriteExt,
+    stream::StreamExt,
+};
 use rand::{rngs::StdRng, SeedableRng};
 use std::{io, iter::FromIterator, sync::Arc};
 use tokio::runtime::Runtime;
@@ -56,6 +61,28 @@ fn setup<TTransport>(
     Arc<PeersAndMetadata>,
     ProtocolIdSet,
 )
+where
+    TTransport: Transport<Error = io::Error> + Clone,
+    TTransport::Output: TSocket,
+    TTransport::Outbound: Send + 'static,
+    TTransport::Inbound: Send + 'static,
+    TTransport::Listener: Send + 'static,
+{
+    setup_with_proxy_protocol(base_transport, auth, false)
+}
+
+fn setup_with_proxy_protocol<TTransport>(
+    base_transport: TTransport,
+    auth: Auth,
+    enable_proxy_protocol: bool,
+) -> (
+    Runtime,
+    MockTimeService,
+    (PeerId, AptosNetTransport<TTransport>),
+    (PeerId, AptosNetTransport<TTransport>),
+    Arc<PeersAndMetadata>,
+    ProtocolIdSet,
+)
 where
     TTransport: Transport<Error = io::Error> + Clone,
     TTransport::Output: TSocket,
@@ -169,7 +196,8 @@ where
         HANDSHAKE_VERSION,
         chain_id,
         supported_protocols.clone(),
-        false, /* Disable proxy protocol */
+        enable_proxy_protocol,
+        ProtocolDeprecations::default(),
     );
 
     let dialer_transport = AptosNetTransport::new(
@@ -182,6 +210,7 @@ where
         chain_id,
         supported_protocols.clone(),
         false, /* Disable proxy protocol */
+        ProtocolDeprecations::default(),
     );
 
     (
@@ -554,6 +583,54 @@ fn test_memory_transport_maybe_mutual() {
     );
 }
 
+#[test]
+fn test_memory_transport_proxy_protocol() {
+    let (
+        rt,
+        _mock_time,
+        (listener_peer_id, listener_transport),
+        (dialer_peer_id, dialer_transport),
+        _,
+        _,
+    ) = setup_with_proxy_protocol(ProxiedMemoryTransport, Auth::Mutual, true);
+
+    let _guard = rt.enter();
+    let (mut inbounds, listener_addr) = listener_transport
+        .listen_on("/memory/0".parse().unwrap())
+        .unwrap();
+
+    // accept the proxied inbound connection and verify the connection
+    // metadata contains both the client address and the proxy address
+    let listener_task = async move {
+        let (inbound, proxy_addr) = inbounds.next().await.unwrap().unwrap();
+        let conn = inbound.await.unwrap();
+
+        assert_eq!(conn.metadata.remote_peer_id, dialer_peer_id);
+        expect_ip4_tcp_noise_addr(&conn.metadata.addr);
+        assert!(conn
+            .metadata
+            .addr
+            .to_string()
+            .starts_with("/ip4/10.0.0.1/tcp/8080/"));
+        assert_eq!(conn.metadata.proxy_addr, Some(proxy_addr));
+        assert!(conn.metadata.is_proxied_connection());
+    };
+
+    // dial the listener and verify the outbound connection is not proxied
+    let dialer_task = async move {
+        let conn = dialer_transport
+            .dial(listener_peer_id, listener_addr)
+            .unwrap()
+            .await
+            .unwrap();
+
+        assert_eq!(conn.metadata.remote_peer_id, listener_peer_id);
+        assert!(!conn.metadata.is_proxied_connection());
+    };
+
+    rt.block_on(future::join(listener_task, dialer_task));
+}
+
 /////////////////////////////////////
 // AptosNetTransport<TcpTransport> //
 /////////////////////////////////////
@@ -606,3 +683,42 @@ fn insert_trusted_peers(
         .set_trusted_peers(&network_id, trusted_peers)
         .unwrap();
 }
+
+/// A PROXY protocol v2 header for a TCP/IPv4 connection from 10.0.0.1:8080 to 10.0.0.2:6180
+const PROXY_PROTOCOL_V2_HEADER: [u8; 28] = [
+    0x0D, 0x0A, 0x0D, 0x0A, 0x00, 0x0D, 0x0A, 0x51, 0x55, 0x49, 0x54, 0x0A, // Signature
+    0x21, // Version 2, proxy command
+    0x11, // TCP over IPv4
+    0x00, 0x0C, // Address length
+    10, 0, 0, 1, // Source address
+    10, 0, 0, 2, // Destination address
+    0x1F, 0x90, // Source port
+    0x18, 0x24, // Destination port
+];
+
+/// A memory transport that prepends a PROXY protocol v2 header to all outbound
+/// connections (i.e., it behaves like a TCP load balancer in front of the listener).
+#[derive(Clone, Debug)]
+struct ProxiedMemoryTransport;
+
+impl Transport for ProxiedMemoryTransport {
+    type Error = io::Error;
+    type Inbound = <memory::MemoryTransport as Transport>::Inbound;
+    type Listener = <memory::MemoryTransport as Transport>::Listener;
+    type Outbound = BoxFuture<'static, io::Result<MemorySocket>>;
+    type Output = MemorySocket;
+
+    fn listen_on(&self, addr: NetworkAddress) -> io::Result<(Self::Listener, NetworkAddress)> {
+        memory::MemoryTransport.listen_on(addr)
+    }
+
+    fn dial(&self, peer_id: PeerId, addr: NetworkAddress) -> io::Result<Self::Outbound> {
+        let outbound = memory::MemoryTransport.dial(peer_id, addr)?;
+        Ok(async move {
+            let mut socket = outbound.await?;
+            socket.write_all(&PROXY_PROTOCOL_V2_HEADER).await?;
+            Ok(socket)
+        }
+        .boxed())
+    }
+}
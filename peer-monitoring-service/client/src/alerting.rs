@@ -0,0 +1,224 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metrics, peer_states, Error, LogEntry, LogEvent, LogSchema, PeerMonitorState, PeerState,
+};
+use aptos_config::{
+    config::{AlertingConfig, AlertingMetric, AlertingRule},
+    network_id::PeerNetworkId,
+};
+use aptos_logger::{info, warn};
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::time::{Duration, Instant};
+
+// Useful constants
+const LATENCY_PERCENTILE_P95: u64 = 95;
+
+/// Evaluates the configured alerting rules against the
+/// monitoring states of the connected peers.
+pub struct AlertEvaluator {
+    alerting_rule_states: Vec<AlertingRuleState>, // The states of each alerting rule
+    evaluation_interval: Duration,                // The interval between alerting rule evaluations
+    last_evaluation_time: Option<Instant>,        // The time of the last alerting rule evaluation
+    time_service: TimeService,                    // The time service used to track violations
+}
+
+impl AlertEvaluator {
+    pub fn new(alerting_config: &AlertingConfig, time_service: TimeService) -> Self {
+        let alerting_rule_states = alerting_config
+            .alerting_rules
+            .iter()
+            .cloned()
+            .map(AlertingRuleState::new)
+            .collect();
+
+        Self {
+            alerting_rule_states,
+            evaluation_interval: Duration::from_millis(
+                alerting_config.alert_evaluation_interval_ms,
+            ),
+            last_evaluation_time: None,
+            time_service,
+        }
+    }
+
+    /// Evaluates all alerting rules (if enough time has
+    /// elapsed since the last evaluation).
+    pub fn maybe_evaluate_alerting_rules(
+        &mut self,
+        peer_monitor_state: &PeerMonitorState,
+        connected_peers: &[PeerNetworkId],
+    ) -> Result<(), Error> {
+        // If there are no alerting rules, there's nothing to do
+        if self.alerting_rule_states.is_empty() {
+            return Ok(());
+        }
+
+        // Check if enough time has elapsed since the last evaluation
+        let time_now = self.time_service.now();
+        if let Some(last_evaluation_time) = self.last_evaluation_time {
+            if time_now.duration_since(last_evaluation_time) < self.evaluation_interval {
+                return Ok(());
+            }
+        }
+        self.last_evaluation_time = Some(time_now);
+
+        // Evaluate each alerting rule
+        for alerting_rule_state in self.alerting_rule_states.iter_mut() {
+            let breaching_peers_percentage = calculate_breaching_peers_percentage(
+                &alerting_rule_state.alerting_rule,
+                peer_monitor_state,
+                connected_peers,
+            )?;
+            alerting_rule_state.update_violation_state(time_now, breaching_peers_percentage);
+        }
+
+        Ok(())
+    }
+
+    /// Returns true iff the alert for the given rule is firing (only used for testing)
+    #[cfg(test)]
+    pub fn is_alert_firing(&self, rule_name: &str) -> bool {
+        self.alerting_rule_states
+            .iter()
+            .any(|state| state.alerting_rule.name == rule_name && state.alert_firing)
+    }
+}
+
+/// A simple container that holds the violation state of an alerting rule
+struct AlertingRuleState {
+    alerting_rule: AlertingRule,           // The alerting rule
+    alert_firing: bool,                    // Whether or not the alert is currently firing
+    violation_start_time: Option<Instant>, // The time the ongoing rule violation started
+}
+
+impl AlertingRuleState {
+    fn new(alerting_rule: AlertingRule) -> Self {
+        Self {
+            alerting_rule,
+            alert_firing: false,
+            violation_start_time: None,
+        }
+    }
+
+    /// Updates the violation state of the rule using the latest percentage of breaching
+    /// peers, and triggers (or resolves) the alert if the violation persists (or ends).
+    fn update_violation_state(
+        &mut self,
+        time_now: Instant,
+        breaching_peers_percentage: Option<u64>,
+    ) {
+        let rule_name = &self.alerting_rule.name;
+        metrics::set_gauge(
+            &metrics::ALERTING_RULE_BREACHING_PEERS_PERCENTAGE,
+            rule_name,
+            breaching_peers_percentage.unwrap_or(0),
+        );
+
+        // Check if the rule is violated (rules are never violated if no peers have data)
+        let rule_violated = breaching_peers_percentage.is_some_and(|breaching_peers_percentage| {
+            breaching_peers_percentage > self.alerting_rule.max_breaching_peers_percentage
+        });
+
+        if rule_violated {
+            // Trigger the alert if the violation has lasted long enough
+            let violation_start_time = *self.violation_start_time.get_or_insert(time_now);
+            let violation_duration = time_now.duration_since(violation_start_time);
+            let min_violation_duration =
+                Duration::from_millis(self.alerting_rule.min_violation_duration_ms);
+            if !self.alert_firing && violation_duration >= min_violation_duration {
+                self.alert_firing = true;
+                warn!(LogSchema::new(LogEntry::AlertingRules)
+                    .event(LogEvent::AlertTriggered)
+                    .message(&format!(
+                        "Alerting rule {:?} triggered! {:?}% of peers have {} > {} (max allowed: {}%) for {:?}.",
+                        rule_name,
+                        breaching_peers_percentage.unwrap_or(0),
+                        self.alerting_rule.metric.get_label(),
+                        self.alerting_rule.threshold,
+                        self.alerting_rule.max_breaching_peers_percentage,
+                        violation_duration,
+                    )));
+                metrics::increment_alert_counter(rule_name, &self.alerting_rule.metric);
+            }
+        } else {
+            // Resolve the alert if it was firing
+            if self.alert_firing {
+                info!(LogSchema::new(LogEntry::AlertingRules)
+                    .event(LogEvent::AlertResolved)
+                    .message(&format!("Alerting rule {:?} resolved!", rule_name)));
+            }
+            self.alert_firing = false;
+            self.violation_start_time = None;
+        }
+
+        metrics::set_gauge(
+            &metrics::ALERTING_RULE_FIRING,
+            rule_name,
+            self.alert_firing as u64,
+        );
+    }
+}
+
+/// Calculates the percentage of peers (on the network of the rule) that breach the
+/// rule threshold. Peers without data for the metric are ignored. If no peers have
+/// data for the metric, None is returned.
+fn calculate_breaching_peers_percentage(
+    alerting_rule: &AlertingRule,
+    peer_monitor_state: &PeerMonitorState,
+    connected_peers: &[PeerNetworkId],
+) -> Result<Option<u64>, Error> {
+    let mut num_peers_with_data: u64 = 0;
+    let mut num_breaching_peers: u64 = 0;
+    for peer_network_id in connected_peers {
+        // Only consider peers on the network of the rule
+        if let Some(network_id) = alerting_rule.network_id {
+            if peer_network_id.network_id() != network_id {
+                continue;
+            }
+        }
+
+        // Get the metric value for the peer and compare it against the threshold
+        let peer_state = peer_states::get_peer_state(peer_monitor_state, peer_network_id)?;
+        if let Some(metric_value) = get_peer_metric_value(&alerting_rule.metric, &peer_state)? {
+            num_peers_with_data += 1;
+            if metric_value > alerting_rule.threshold {
+                num_breaching_peers += 1;
+            }
+        }
+    }
+
+    if num_peers_with_data == 0 {
+        Ok(None)
+    } else {
+        Ok(Some(num_breaching_peers * 100 / num_peers_with_data))
+    }
+}
+
+/// Returns the value of the given metric for the peer (if data is available)
+fn get_peer_metric_value(
+    alerting_metric: &AlertingMetric,
+    peer_state: &PeerState,
+) -> Result<Option<u64>, Error> {
+    let metric_value = match alerting_metric {
+        AlertingMetric::AverageLatencyMs => peer_state
+            .get_latency_info_state()?
+            .get_average_latency_ping_secs()
+            .map(secs_to_ms),
+        AlertingMetric::DistanceFromValidators => peer_state
+            .get_network_info_state()?
+            .get_latest_network_info_response()
+            .map(|network_info_response| network_info_response.distance_from_validators),
+        AlertingMetric::LatencyP95Ms => peer_state
+            .get_latency_info_state()?
+            .get_latency_ping_percentile_secs(LATENCY_PERCENTILE_P95)
+            .map(secs_to_ms),
+    };
+    Ok(metric_value)
+}
+
+/// Converts the given duration in seconds to milliseconds
+fn secs_to_ms(duration_secs: f64) -> u64 {
+    (duration_secs * 1000.0) as u64
+}
@@ -3,7 +3,10 @@
 
 #![forbid(unsafe_code)]
 
-use crate::logging::{LogEntry, LogEvent, LogSchema};
+use crate::{
+    alerting::AlertEvaluator,
+    logging::{LogEntry, LogEvent, LogSchema},
+};
 use aptos_config::{
     config::{NodeConfig, PeerMonitoringServiceConfig},
     network_id::PeerNetworkId,
@@ -24,6 +27,7 @@ use std::{collections::HashMap, sync::Arc, time::Duration};
 use thiserror::Error;
 use tokio::{runtime::Handle, task::JoinHandle};
 
+mod alerting;
 mod error;
 mod logging;
 mod metrics;
@@ -68,7 +72,7 @@ pub async fn start_peer_monitor(
     // Spawn the peer metadata updater
     let time_service = TimeService::real();
     spawn_peer_metadata_updater(
-        node_config.peer_monitoring_service,
+        node_config.peer_monitoring_service.clone(),
         peer_monitor_state.clone(),
         peer_monitoring_client.get_peers_and_metadata(),
         time_service.clone(),
@@ -101,12 +105,16 @@ async fn start_peer_monitor_with_state(
     let peers_and_metadata = peer_monitoring_client.get_peers_and_metadata();
 
     // Create an interval ticker for the monitor loop
-    let monitoring_service_config = node_config.peer_monitoring_service;
+    let monitoring_service_config = node_config.peer_monitoring_service.clone();
     let peer_monitor_duration =
         Duration::from_micros(monitoring_service_config.peer_monitor_interval_usec);
     let peer_monitor_ticker = time_service.interval(peer_monitor_duration);
     futures::pin_mut!(peer_monitor_ticker);
 
+    // Create the evaluator for the alerting rules
+    let mut alert_evaluator =
+        AlertEvaluator::new(&monitoring_service_config.alerting, time_service.clone());
+
     // Start the peer monitoring loop
     info!(LogSchema::new(LogEntry::PeerMonitorLoop)
         .event(LogEvent::StartedPeerMonitorLoop)
@@ -139,6 +147,18 @@ async fn start_peer_monitor_with_state(
             &connected_peers_and_metadata,
         );
 
+        // Evaluate the alerting rules against the peer states
+        let connected_peers: Vec<PeerNetworkId> =
+            connected_peers_and_metadata.keys().cloned().collect();
+        if let Err(error) =
+            alert_evaluator.maybe_evaluate_alerting_rules(&peer_monitor_state, &connected_peers)
+        {
+            warn!(LogSchema::new(LogEntry::AlertingRules)
+                .event(LogEvent::UnexpectedErrorEncountered)
+                .error(&error)
+                .message("Failed to evaluate the alerting rules!"));
+        }
+
         // Refresh the peer states
         if let Err(error) = peer_states::refresh_peer_states(
             &monitoring_service_config,
@@ -40,7 +40,9 @@ impl<'a> LogSchema<'a> {
 #[derive(Clone, Copy, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum LogEntry {
+    AlertingRules,
     LatencyPing,
+    MempoolInfoRequest,
     MetadataUpdateLoop,
     NetworkInfoRequest,
     NodeInfoRequest,
@@ -51,6 +53,8 @@ pub enum LogEntry {
 #[derive(Clone, Copy, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum LogEvent {
+    AlertResolved,
+    AlertTriggered,
     InvalidResponse,
     LogAllPeerStates,
     PeerPingError,
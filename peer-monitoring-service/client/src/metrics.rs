@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::network_id::PeerNetworkId;
+use aptos_config::{config::AlertingMetric, network_id::PeerNetworkId};
 use aptos_metrics_core::{
     exponential_buckets, histogram_opts, register_histogram_vec, register_int_counter_vec,
     register_int_gauge_vec, HistogramVec, IntCounterVec, IntGaugeVec,
@@ -11,6 +11,36 @@ use once_cell::sync::Lazy;
 /// The special label TOTAL_COUNT stores the sum of all values in the counter
 pub const TOTAL_COUNT_LABEL: &str = "TOTAL_COUNT";
 
+/// Gauge for tracking the percentage of peers breaching each alerting rule
+pub static ALERTING_RULE_BREACHING_PEERS_PERCENTAGE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "peer_monitoring_client_alerting_rule_breaching_peers_percentage",
+        "Gauge related to the percentage of peers breaching each alerting rule",
+        &["rule_name"]
+    )
+    .unwrap()
+});
+
+/// Gauge for tracking the alerting rules that are currently firing
+pub static ALERTING_RULE_FIRING: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "peer_monitoring_client_alerting_rule_firing",
+        "Gauge related to the alerting rules that are currently firing",
+        &["rule_name"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the number of triggered alerts
+pub static TRIGGERED_ALERTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "peer_monitoring_client_triggered_alerts",
+        "Counters related to triggered alerts",
+        &["rule_name", "metric"]
+    )
+    .unwrap()
+});
+
 /// Counter for tracking the average ping latencies
 pub static AVERAGE_PING_LATENCIES: Lazy<HistogramVec> = Lazy::new(|| {
     let histogram_opts = histogram_opts!(
@@ -37,6 +67,21 @@ pub static DISTANCE_FROM_VALIDATORS: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(histogram_opts, &["network_id"]).unwrap()
 });
 
+// Histogram buckets for tracking the mempool utilization (percentage)
+const MEMPOOL_UTILIZATION_BUCKETS: &[f64] = &[
+    0.0, 5.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0, 90.0, 95.0, 99.0, 100.0,
+];
+
+/// Counter for tracking the reported mempool utilization of peers
+pub static MEMPOOL_UTILIZATION: Lazy<HistogramVec> = Lazy::new(|| {
+    let histogram_opts = histogram_opts!(
+        "peer_monitoring_client_mempool_utilization",
+        "Counters related to the reported mempool utilization of peers (percentage)",
+        MEMPOOL_UTILIZATION_BUCKETS.to_vec()
+    );
+    register_histogram_vec!(histogram_opts, &["network_id"]).unwrap()
+});
+
 // Histogram buckets for tracking the node uptime (hours)
 const NODE_UPTIME_BUCKETS: &[f64] = &[
     0.5, 1.0, 6.0, 12.0, 24.0, 48.0, 96.0, 192.0, 384.0, 768.0, 1536.0, 3072.0, 6144.0,
@@ -124,6 +169,13 @@ pub fn update_in_flight_requests(request_label: &str, num_in_flight_requests: u6
     set_gauge(&IN_FLIGHT_REQUESTS, request_label, num_in_flight_requests);
 }
 
+/// Increments the triggered alerts counter for the given rule
+pub fn increment_alert_counter(rule_name: &str, alerting_metric: &AlertingMetric) {
+    TRIGGERED_ALERTS
+        .with_label_values(&[rule_name, alerting_metric.get_label()])
+        .inc();
+}
+
 /// Increments the given request counter with the provided values
 pub fn increment_request_counter(
     counter: &Lazy<IntCounterVec>,
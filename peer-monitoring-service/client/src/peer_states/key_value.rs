@@ -3,12 +3,15 @@
 
 use crate::{
     peer_states::{
-        latency_info::LatencyInfoState, network_info::NetworkInfoState, node_info::NodeInfoState,
-        request_tracker::RequestTracker,
+        latency_info::LatencyInfoState, mempool_info::MempoolInfoState,
+        network_info::NetworkInfoState, node_info::NodeInfoState, request_tracker::RequestTracker,
     },
     Error,
 };
-use aptos_config::{config::NodeConfig, network_id::PeerNetworkId};
+use aptos_config::{
+    config::{NodeConfig, PeerMonitoringServiceConfig},
+    network_id::PeerNetworkId,
+};
 use aptos_infallible::RwLock;
 use aptos_network::application::metadata::PeerMetadata;
 use aptos_peer_monitoring_service_types::{
@@ -24,6 +27,7 @@ use std::{fmt::Display, sync::Arc};
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum PeerStateKey {
     LatencyInfo,
+    MempoolInfo,
     NetworkInfo,
     NodeInfo,
 }
@@ -33,15 +37,29 @@ impl PeerStateKey {
     pub fn get_all_keys() -> Vec<PeerStateKey> {
         vec![
             PeerStateKey::LatencyInfo,
+            PeerStateKey::MempoolInfo,
             PeerStateKey::NetworkInfo,
             PeerStateKey::NodeInfo,
         ]
     }
 
+    /// A utility function for getting all peer state keys that are
+    /// enabled by the given config (and should be refreshed).
+    pub fn get_enabled_keys(config: &PeerMonitoringServiceConfig) -> Vec<PeerStateKey> {
+        Self::get_all_keys()
+            .into_iter()
+            .filter(|peer_state_key| match peer_state_key {
+                PeerStateKey::MempoolInfo => config.mempool_monitoring.enable_mempool_monitoring,
+                _ => true,
+            })
+            .collect()
+    }
+
     /// Returns the label for the peer state key
     pub fn get_label(&self) -> &str {
         match self {
             PeerStateKey::LatencyInfo => "latency_info",
+            PeerStateKey::MempoolInfo => "mempool_info",
             PeerStateKey::NetworkInfo => "network_info",
             PeerStateKey::NodeInfo => "node_info",
         }
@@ -55,6 +73,9 @@ impl PeerStateKey {
                 PeerMonitoringServiceRequest::LatencyPing(LatencyPingRequest { ping_counter: 0 })
                     .get_label()
             },
+            PeerStateKey::MempoolInfo => {
+                PeerMonitoringServiceRequest::GetMempoolInformation.get_label()
+            },
             PeerStateKey::NetworkInfo => {
                 PeerMonitoringServiceRequest::GetNetworkInformation.get_label()
             },
@@ -102,6 +123,7 @@ pub trait StateValueInterface {
 #[derive(Clone, Debug)]
 pub enum PeerStateValue {
     LatencyInfoState,
+    MempoolInfoState,
     NetworkInfoState,
     NodeInfoState,
 }
@@ -118,6 +140,11 @@ impl PeerStateValue {
                     node_config.peer_monitoring_service.latency_monitoring;
                 LatencyInfoState::new(latency_monitoring_config, time_service).into()
             },
+            PeerStateKey::MempoolInfo => {
+                let mempool_monitoring_config =
+                    node_config.peer_monitoring_service.mempool_monitoring;
+                MempoolInfoState::new(mempool_monitoring_config, time_service).into()
+            },
             PeerStateKey::NetworkInfo => NetworkInfoState::new(node_config, time_service).into(),
             PeerStateKey::NodeInfo => {
                 let node_monitoring_config = node_config.peer_monitoring_service.node_monitoring;
@@ -132,6 +159,7 @@ impl Display for PeerStateValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             PeerStateValue::LatencyInfoState(state) => write!(f, "LatencyInfoState: {}", state),
+            PeerStateValue::MempoolInfoState(state) => write!(f, "MempoolInfoState: {}", state),
             PeerStateValue::NetworkInfoState(state) => write!(f, "NetworkInfoState: {}", state),
             PeerStateValue::NodeInfoState(state) => write!(f, "NodeInfoState: {}", state),
         }
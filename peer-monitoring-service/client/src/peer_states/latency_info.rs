@@ -109,6 +109,27 @@ impl LatencyInfoState {
         }
     }
 
+    /// Returns the given percentile (e.g., 95) of the recorded latency pings
+    /// in seconds. If no latency pings have been recorded, None is returned.
+    pub fn get_latency_ping_percentile_secs(&self, percentile: u64) -> Option<f64> {
+        let mut latency_pings_secs: Vec<f64> = self
+            .recorded_latency_ping_durations_secs
+            .values()
+            .cloned()
+            .collect();
+        if latency_pings_secs.is_empty() {
+            return None;
+        }
+
+        // Use the nearest-rank method to select the percentile
+        latency_pings_secs.sort_by(|a, b| a.total_cmp(b));
+        let num_latency_pings = latency_pings_secs.len() as u64;
+        let rank = (percentile.min(100) * num_latency_pings)
+            .div_ceil(100)
+            .max(1);
+        latency_pings_secs.get((rank - 1) as usize).cloned()
+    }
+
     /// Returns the latest latency ping in seconds. If no latency
     /// pings have been recorded, None is returned.
     pub fn get_latest_latency_ping_secs(&self) -> Option<f64> {
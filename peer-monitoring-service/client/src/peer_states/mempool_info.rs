@@ -0,0 +1,253 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metrics,
+    peer_states::{key_value::StateValueInterface, request_tracker::RequestTracker},
+    Error, LogEntry, LogEvent, LogSchema,
+};
+use aptos_config::{config::MempoolMonitoringConfig, network_id::PeerNetworkId};
+use aptos_infallible::RwLock;
+use aptos_logger::warn;
+use aptos_network::application::metadata::PeerMetadata;
+use aptos_peer_monitoring_service_types::{
+    request::PeerMonitoringServiceRequest,
+    response::{MempoolInformationResponse, PeerMonitoringServiceResponse},
+};
+use aptos_time_service::TimeService;
+use std::{
+    fmt,
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
+
+/// A simple container that holds a single peer's mempool info
+#[derive(Clone, Debug)]
+pub struct MempoolInfoState {
+    mempool_monitoring_config: MempoolMonitoringConfig, // The config for mempool monitoring
+    recorded_mempool_info_response: Option<MempoolInformationResponse>, // The last mempool info response
+    request_tracker: Arc<RwLock<RequestTracker>>, // The request tracker for mempool info requests
+}
+
+impl MempoolInfoState {
+    pub fn new(
+        mempool_monitoring_config: MempoolMonitoringConfig,
+        time_service: TimeService,
+    ) -> Self {
+        let request_tracker = RequestTracker::new(
+            mempool_monitoring_config.mempool_info_request_interval_ms,
+            time_service,
+        );
+
+        Self {
+            mempool_monitoring_config,
+            recorded_mempool_info_response: None,
+            request_tracker: Arc::new(RwLock::new(request_tracker)),
+        }
+    }
+
+    /// Records the new mempool info response for the peer
+    pub fn record_mempool_info_response(
+        &mut self,
+        mempool_info_response: MempoolInformationResponse,
+    ) {
+        // Update the request tracker with a successful response
+        self.request_tracker.write().record_response_success();
+
+        // Save the mempool info
+        self.recorded_mempool_info_response = Some(mempool_info_response);
+    }
+
+    /// Handles a request failure for the specified peer
+    fn handle_request_failure(&self) {
+        self.request_tracker.write().record_response_failure();
+    }
+
+    /// Returns the latest mempool info response
+    pub fn get_latest_mempool_info_response(&self) -> Option<MempoolInformationResponse> {
+        self.recorded_mempool_info_response.clone()
+    }
+}
+
+impl StateValueInterface for MempoolInfoState {
+    fn create_monitoring_service_request(&mut self) -> PeerMonitoringServiceRequest {
+        PeerMonitoringServiceRequest::GetMempoolInformation
+    }
+
+    fn get_request_timeout_ms(&self) -> u64 {
+        self.mempool_monitoring_config
+            .mempool_info_request_timeout_ms
+    }
+
+    fn get_request_tracker(&self) -> Arc<RwLock<RequestTracker>> {
+        self.request_tracker.clone()
+    }
+
+    fn handle_monitoring_service_response(
+        &mut self,
+        peer_network_id: &PeerNetworkId,
+        _peer_metadata: PeerMetadata,
+        _monitoring_service_request: PeerMonitoringServiceRequest,
+        monitoring_service_response: PeerMonitoringServiceResponse,
+        _response_time_secs: f64,
+    ) {
+        // Verify the response type is valid
+        let mempool_info_response = match monitoring_service_response {
+            PeerMonitoringServiceResponse::MempoolInformation(mempool_information_response) => {
+                mempool_information_response
+            },
+            _ => {
+                warn!(LogSchema::new(LogEntry::MempoolInfoRequest)
+                    .event(LogEvent::ResponseError)
+                    .peer(peer_network_id)
+                    .message(
+                        "An unexpected response was received instead of a mempool info response!"
+                    ));
+                self.handle_request_failure();
+                return;
+            },
+        };
+
+        // Store the new mempool info
+        self.record_mempool_info_response(mempool_info_response);
+    }
+
+    fn handle_monitoring_service_response_error(
+        &mut self,
+        peer_network_id: &PeerNetworkId,
+        error: Error,
+    ) {
+        // Handle the failure
+        self.handle_request_failure();
+
+        // Log the error
+        warn!(LogSchema::new(LogEntry::MempoolInfoRequest)
+            .event(LogEvent::ResponseError)
+            .message("Error encountered when requesting mempool information from the peer!")
+            .peer(peer_network_id)
+            .error(&error));
+    }
+
+    fn update_peer_state_metrics(&self, peer_network_id: &PeerNetworkId) {
+        if let Some(mempool_info_response) = self.get_latest_mempool_info_response() {
+            // Update the mempool utilization metric
+            let utilization_percentage = mempool_info_response.get_utilization() * 100.0;
+            metrics::observe_value(
+                &metrics::MEMPOOL_UTILIZATION,
+                peer_network_id,
+                utilization_percentage,
+            );
+        }
+    }
+}
+
+impl Display for MempoolInfoState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MempoolInfoState {{ recorded_mempool_info_response: {:?} }}",
+            self.recorded_mempool_info_response
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::peer_states::{key_value::StateValueInterface, mempool_info::MempoolInfoState};
+    use aptos_config::{
+        config::{MempoolMonitoringConfig, PeerRole},
+        network_id::PeerNetworkId,
+    };
+    use aptos_netcore::transport::ConnectionOrigin;
+    use aptos_network::{
+        application::metadata::PeerMetadata,
+        protocols::wire::handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
+        transport::{ConnectionId, ConnectionMetadata},
+    };
+    use aptos_peer_monitoring_service_types::{
+        request::PeerMonitoringServiceRequest,
+        response::{
+            LatencyPingResponse, MempoolInformationResponse, PeerMonitoringServiceResponse,
+        },
+    };
+    use aptos_time_service::TimeService;
+    use aptos_types::network_address::NetworkAddress;
+    use std::str::FromStr;
+
+    // Useful test constants
+    const TEST_NETWORK_ADDRESS: &str = "/ip4/127.0.0.1/tcp/8081";
+
+    #[test]
+    fn test_verify_mempool_info_state() {
+        // Create the mempool info state
+        let mempool_monitoring_config = MempoolMonitoringConfig::default();
+        let time_service = TimeService::mock();
+        let mut mempool_info_state = MempoolInfoState::new(mempool_monitoring_config, time_service);
+
+        // Verify the initial mempool info state
+        assert!(mempool_info_state
+            .get_latest_mempool_info_response()
+            .is_none());
+
+        // Handle several valid mempool info responses and verify the state
+        for i in 0..10 {
+            // Create the service response
+            let mempool_information_response = MempoolInformationResponse {
+                num_transactions: i * 100,
+                capacity: 1000,
+            };
+
+            // Handle the mempool info response
+            handle_monitoring_service_response(
+                &mut mempool_info_state,
+                PeerMonitoringServiceResponse::MempoolInformation(
+                    mempool_information_response.clone(),
+                ),
+            );
+
+            // Verify the latest mempool info state
+            let latest_mempool_info_response = mempool_info_state
+                .get_latest_mempool_info_response()
+                .unwrap();
+            assert_eq!(latest_mempool_info_response, mempool_information_response);
+        }
+
+        // Handle an invalid response and verify the latest mempool info is unchanged
+        handle_monitoring_service_response(
+            &mut mempool_info_state,
+            PeerMonitoringServiceResponse::LatencyPing(LatencyPingResponse { ping_counter: 0 }),
+        );
+        let latest_mempool_info_response = mempool_info_state
+            .get_latest_mempool_info_response()
+            .unwrap();
+        assert_eq!(latest_mempool_info_response.num_transactions, 900);
+    }
+
+    /// Handles a monitoring service response from a peer
+    fn handle_monitoring_service_response(
+        mempool_info_state: &mut MempoolInfoState,
+        peer_monitoring_service_response: PeerMonitoringServiceResponse,
+    ) {
+        // Create a new peer metadata entry
+        let peer_network_id = PeerNetworkId::random();
+        let connection_metadata = ConnectionMetadata::new(
+            peer_network_id.peer_id(),
+            ConnectionId::default(),
+            NetworkAddress::from_str(TEST_NETWORK_ADDRESS).unwrap(),
+            ConnectionOrigin::Outbound,
+            MessagingProtocolVersion::V1,
+            ProtocolIdSet::empty(),
+            PeerRole::Validator,
+        );
+        let peer_metadata = PeerMetadata::new(connection_metadata);
+
+        // Handle the response
+        mempool_info_state.handle_monitoring_service_response(
+            &peer_network_id,
+            peer_metadata,
+            PeerMonitoringServiceRequest::GetMempoolInformation,
+            peer_monitoring_service_response,
+            0.0,
+        );
+    }
+}
@@ -16,6 +16,7 @@ use tokio::runtime::Handle;
 
 pub mod key_value;
 pub mod latency_info;
+pub mod mempool_info;
 pub mod network_info;
 pub mod node_info;
 pub mod peer_state;
@@ -36,9 +37,9 @@ pub fn refresh_peer_states(
     time_service: TimeService,
     runtime: Option<Handle>,
 ) -> Result<(), Error> {
-    // Process all state entries (in order) and update the ones that
-    // need to be refreshed for each peer.
-    for peer_state_key in PeerStateKey::get_all_keys() {
+    // Process all enabled state entries (in order) and update the
+    // ones that need to be refreshed for each peer.
+    for peer_state_key in PeerStateKey::get_enabled_keys(monitoring_service_config) {
         let mut num_in_flight_requests = 0;
 
         // Go through all connected peers and see if we should refresh the state
@@ -88,7 +89,7 @@ pub fn refresh_peer_states(
 }
 
 /// Returns the peer state for the given peer
-fn get_peer_state(
+pub(crate) fn get_peer_state(
     peer_monitor_state: &PeerMonitorState,
     peer_network_id: &PeerNetworkId,
 ) -> Result<PeerState, Error> {
@@ -6,6 +6,7 @@ use crate::{
     peer_states::{
         key_value::{PeerStateKey, PeerStateValue, StateValueInterface},
         latency_info::LatencyInfoState,
+        mempool_info::MempoolInfoState,
         network_info::NetworkInfoState,
         node_info::NodeInfoState,
         request_tracker::RequestTracker,
@@ -210,6 +211,11 @@ impl PeerState {
         let node_info_response = node_info_state.get_latest_node_info_response();
         peer_monitoring_metadata.latest_node_info_response = node_info_response;
 
+        // Get and store the latest mempool info response
+        let mempool_info_state = self.get_mempool_info_state()?;
+        let mempool_info_response = mempool_info_state.get_latest_mempool_info_response();
+        peer_monitoring_metadata.latest_mempool_info_response = mempool_info_response;
+
         Ok(peer_monitoring_metadata)
     }
 
@@ -242,6 +248,21 @@ impl PeerState {
         }
     }
 
+    /// Returns a copy of the mempool info state
+    pub(crate) fn get_mempool_info_state(&self) -> Result<MempoolInfoState, Error> {
+        let peer_state_value = self
+            .get_peer_state_value(&PeerStateKey::MempoolInfo)?
+            .read()
+            .clone();
+        match peer_state_value {
+            PeerStateValue::MempoolInfoState(mempool_info_state) => Ok(mempool_info_state),
+            peer_state_value => Err(Error::UnexpectedError(format!(
+                "Invalid peer state value found! Expected mempool_info_state but got: {:?}",
+                peer_state_value
+            ))),
+        }
+    }
+
     /// Returns a copy of the network info state
     pub(crate) fn get_network_info_state(&self) -> Result<NetworkInfoState, Error> {
         let peer_state_value = self
@@ -0,0 +1,167 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    alerting::AlertEvaluator,
+    peer_states::key_value::{PeerStateKey, PeerStateValue},
+    PeerMonitorState, PeerState,
+};
+use aptos_config::{
+    config::{AlertingConfig, AlertingMetric, AlertingRule, NodeConfig},
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_time_service::TimeService;
+use aptos_types::PeerId;
+
+// Useful test constants
+const ALERT_EVALUATION_INTERVAL_MS: u64 = 1000;
+const HIGH_LATENCY_SECS: f64 = 1.0;
+const LOW_LATENCY_SECS: f64 = 0.1;
+const MIN_VIOLATION_DURATION_MS: u64 = 5000;
+const RULE_NAME: &str = "high_validator_latency";
+
+#[test]
+fn test_latency_alerting_rule() {
+    // Create an alert evaluator with a single latency rule for validator peers
+    let time_service = TimeService::mock();
+    let mock_time = time_service.clone().into_mock();
+    let alerting_config = AlertingConfig {
+        alert_evaluation_interval_ms: ALERT_EVALUATION_INTERVAL_MS,
+        alerting_rules: vec![AlertingRule {
+            name: RULE_NAME.into(),
+            network_id: Some(NetworkId::Validator),
+            metric: AlertingMetric::LatencyP95Ms,
+            threshold: 500,
+            max_breaching_peers_percentage: 50,
+            min_violation_duration_ms: MIN_VIOLATION_DURATION_MS,
+        }],
+    };
+    let mut alert_evaluator = AlertEvaluator::new(&alerting_config, time_service.clone());
+
+    // Create several validator peers and a public peer
+    let peer_monitor_state = PeerMonitorState::new();
+    let validator_peers: Vec<_> = (0..4)
+        .map(|_| create_peer_state(&peer_monitor_state, NetworkId::Validator, &time_service))
+        .collect();
+    let public_peer = create_peer_state(&peer_monitor_state, NetworkId::Public, &time_service);
+    let mut connected_peers = validator_peers.clone();
+    connected_peers.push(public_peer);
+
+    // Half of the validator peers (and the public peer) have high latencies
+    for (index, peer_network_id) in validator_peers.iter().enumerate() {
+        let latency_secs = if index < 2 {
+            HIGH_LATENCY_SECS
+        } else {
+            LOW_LATENCY_SECS
+        };
+        record_latency_ping(&peer_monitor_state, peer_network_id, latency_secs);
+    }
+    record_latency_ping(&peer_monitor_state, &public_peer, HIGH_LATENCY_SECS);
+
+    // Verify that the rule is not violated (public peers are ignored)
+    alert_evaluator
+        .maybe_evaluate_alerting_rules(&peer_monitor_state, &connected_peers)
+        .unwrap();
+    mock_time.advance_ms(MIN_VIOLATION_DURATION_MS);
+    alert_evaluator
+        .maybe_evaluate_alerting_rules(&peer_monitor_state, &connected_peers)
+        .unwrap();
+    assert!(!alert_evaluator.is_alert_firing(RULE_NAME));
+
+    // Add a high latency ping for another validator peer and verify that the
+    // rule is violated, but the alert doesn't fire until the duration elapses.
+    record_latency_ping(&peer_monitor_state, &validator_peers[2], HIGH_LATENCY_SECS);
+    mock_time.advance_ms(ALERT_EVALUATION_INTERVAL_MS);
+    alert_evaluator
+        .maybe_evaluate_alerting_rules(&peer_monitor_state, &connected_peers)
+        .unwrap();
+    assert!(!alert_evaluator.is_alert_firing(RULE_NAME));
+
+    // Elapse the violation duration and verify that the alert fires
+    mock_time.advance_ms(MIN_VIOLATION_DURATION_MS);
+    alert_evaluator
+        .maybe_evaluate_alerting_rules(&peer_monitor_state, &connected_peers)
+        .unwrap();
+    assert!(alert_evaluator.is_alert_firing(RULE_NAME));
+
+    // Disconnect two of the high latency peers and verify that the alert is resolved
+    // (but only after the evaluation interval has elapsed).
+    let connected_peers = &connected_peers[2..];
+    alert_evaluator
+        .maybe_evaluate_alerting_rules(&peer_monitor_state, connected_peers)
+        .unwrap();
+    assert!(alert_evaluator.is_alert_firing(RULE_NAME));
+    mock_time.advance_ms(ALERT_EVALUATION_INTERVAL_MS);
+    alert_evaluator
+        .maybe_evaluate_alerting_rules(&peer_monitor_state, connected_peers)
+        .unwrap();
+    assert!(!alert_evaluator.is_alert_firing(RULE_NAME));
+}
+
+#[test]
+fn test_alerting_rule_without_data() {
+    // Create an alert evaluator with a rule that fires immediately
+    let time_service = TimeService::mock();
+    let alerting_config = AlertingConfig {
+        alerting_rules: vec![AlertingRule {
+            name: RULE_NAME.into(),
+            network_id: None,
+            metric: AlertingMetric::AverageLatencyMs,
+            threshold: 0,
+            max_breaching_peers_percentage: 0,
+            min_violation_duration_ms: 0,
+        }],
+        ..Default::default()
+    };
+    let mut alert_evaluator = AlertEvaluator::new(&alerting_config, time_service.clone());
+
+    // Create a peer without any latency pings and verify that the alert doesn't fire
+    let peer_monitor_state = PeerMonitorState::new();
+    let peer_network_id = create_peer_state(&peer_monitor_state, NetworkId::Vfn, &time_service);
+    alert_evaluator
+        .maybe_evaluate_alerting_rules(&peer_monitor_state, &[peer_network_id])
+        .unwrap();
+    assert!(!alert_evaluator.is_alert_firing(RULE_NAME));
+
+    // Verify that an error is returned for peers without a state
+    let mut alert_evaluator = AlertEvaluator::new(&alerting_config, time_service);
+    let unknown_peer = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+    assert!(alert_evaluator
+        .maybe_evaluate_alerting_rules(&peer_monitor_state, &[unknown_peer])
+        .is_err());
+}
+
+/// Creates a new peer state for a random peer on the given network
+fn create_peer_state(
+    peer_monitor_state: &PeerMonitorState,
+    network_id: NetworkId,
+    time_service: &TimeService,
+) -> PeerNetworkId {
+    let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+    let peer_state = PeerState::new(NodeConfig::default(), time_service.clone());
+    peer_monitor_state
+        .peer_states
+        .write()
+        .insert(peer_network_id, peer_state);
+    peer_network_id
+}
+
+/// Records a new latency ping for the given peer
+fn record_latency_ping(
+    peer_monitor_state: &PeerMonitorState,
+    peer_network_id: &PeerNetworkId,
+    latency_secs: f64,
+) {
+    let peer_state = peer_monitor_state.get_peer_state(peer_network_id).unwrap();
+    let peer_state_value = peer_state
+        .get_peer_state_value(&PeerStateKey::LatencyInfo)
+        .unwrap();
+    match &mut *peer_state_value.write() {
+        PeerStateValue::LatencyInfoState(latency_info_state) => {
+            let latency_ping_counter = latency_info_state.get_and_increment_latency_ping_counter();
+            latency_info_state
+                .record_new_latency_and_reset_failures(latency_ping_counter, latency_secs);
+        },
+        peer_state_value => panic!("Unexpected peer state value: {:?}", peer_state_value),
+    }
+}
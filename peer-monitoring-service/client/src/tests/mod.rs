@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+mod alerting;
 mod mock;
 mod multiple_peers;
 mod single_peer;
@@ -277,7 +277,9 @@ pub async fn initialize_and_verify_peer_states(
     elapse_peer_monitor_interval(node_config.clone(), mock_time.clone()).await;
 
     // Verify the initial client requests and send responses
-    let num_expected_requests = PeerStateKey::get_all_keys().len() as u64;
+    let enabled_peer_state_keys =
+        PeerStateKey::get_enabled_keys(&node_config.peer_monitoring_service);
+    let num_expected_requests = enabled_peer_state_keys.len() as u64;
     verify_all_requests_and_respond(
         network_id,
         mock_monitoring_server,
@@ -292,7 +294,7 @@ pub async fn initialize_and_verify_peer_states(
         time_before_update,
         peer_monitor_state,
         peer_network_id,
-        PeerStateKey::get_all_keys(),
+        enabled_peer_state_keys,
     )
     .await;
 
@@ -356,7 +358,7 @@ pub async fn start_peer_metadata_updater(
 ) {
     // Spawn the peer metadata updater
     tokio::spawn(spawn_peer_metadata_updater(
-        node_config.peer_monitoring_service,
+        node_config.peer_monitoring_service.clone(),
         peer_monitor_state.clone(),
         peers_and_metadata,
         time_service.clone(),
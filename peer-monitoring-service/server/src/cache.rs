@@ -0,0 +1,112 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{error::Error, metrics};
+use aptos_config::config::PeerMonitoringServiceConfig;
+use aptos_infallible::Mutex;
+use aptos_peer_monitoring_service_types::{
+    request::PeerMonitoringServiceRequest, response::PeerMonitoringServiceResponse,
+};
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::time::{Duration, Instant};
+
+// Useful cache lookup labels
+pub const CACHE_HIT_LABEL: &str = "hit";
+pub const CACHE_MISS_LABEL: &str = "miss";
+
+/// A response that was computed and cached at a specific time
+#[derive(Clone, Debug)]
+struct CachedResponse {
+    cached_at: Instant,
+    response: PeerMonitoringServiceResponse,
+}
+
+/// A single-entry cache for a request type whose response does
+/// not depend on the requesting peer. Entries older than the max
+/// staleness are recomputed (a max staleness of zero disables caching).
+#[derive(Debug)]
+struct ResponseCache {
+    cached_response: Mutex<Option<CachedResponse>>,
+    max_staleness: Duration,
+}
+
+impl ResponseCache {
+    fn new(max_staleness_ms: u64) -> Self {
+        Self {
+            cached_response: Mutex::new(None),
+            max_staleness: Duration::from_millis(max_staleness_ms),
+        }
+    }
+
+    /// Returns the cached response if it is fresh enough, otherwise computes,
+    /// caches and returns a new response. Errors are never cached.
+    fn get_or_compute(
+        &self,
+        request_label: &str,
+        time_service: &TimeService,
+        compute_response: impl FnOnce() -> Result<PeerMonitoringServiceResponse, Error>,
+    ) -> Result<PeerMonitoringServiceResponse, Error> {
+        // If caching is disabled, compute the response directly
+        if self.max_staleness.is_zero() {
+            return compute_response();
+        }
+
+        // Hold the lock while computing so that a burst of
+        // requests only computes the response once.
+        let mut cached_response = self.cached_response.lock();
+        let now = time_service.now();
+        if let Some(cached_response) = cached_response.as_ref() {
+            if now.duration_since(cached_response.cached_at) <= self.max_staleness {
+                metrics::increment_cache_lookup(request_label, CACHE_HIT_LABEL);
+                return Ok(cached_response.response.clone());
+            }
+        }
+        metrics::increment_cache_lookup(request_label, CACHE_MISS_LABEL);
+
+        // Compute the response and update the cache
+        let response = compute_response()?;
+        *cached_response = Some(CachedResponse {
+            cached_at: now,
+            response: response.clone(),
+        });
+        Ok(response)
+    }
+}
+
+/// The response caches for the peer monitoring server. Only requests whose
+/// responses are identical for all peers (and are relatively expensive to
+/// compute) are cached.
+#[derive(Debug)]
+pub struct ResponseCaches {
+    network_information: ResponseCache,
+    node_information: ResponseCache,
+    time_service: TimeService,
+}
+
+impl ResponseCaches {
+    pub fn new(config: &PeerMonitoringServiceConfig, time_service: TimeService) -> Self {
+        Self {
+            network_information: ResponseCache::new(config.max_network_info_staleness_ms),
+            node_information: ResponseCache::new(config.max_node_info_staleness_ms),
+            time_service,
+        }
+    }
+
+    /// Returns the response for the given request, using the cache
+    /// for the request type (if one exists).
+    pub fn get_or_compute(
+        &self,
+        request: &PeerMonitoringServiceRequest,
+        compute_response: impl FnOnce() -> Result<PeerMonitoringServiceResponse, Error>,
+    ) -> Result<PeerMonitoringServiceResponse, Error> {
+        let response_cache = match request {
+            PeerMonitoringServiceRequest::GetNetworkInformation => &self.network_information,
+            PeerMonitoringServiceRequest::GetNodeInformation => &self.node_information,
+            PeerMonitoringServiceRequest::GetMempoolInformation
+            | PeerMonitoringServiceRequest::GetServerProtocolVersion
+            | PeerMonitoringServiceRequest::HealthCheck(_)
+            | PeerMonitoringServiceRequest::LatencyPing(_) => return compute_response(),
+        };
+        response_cache.get_or_compute(request.get_label(), &self.time_service, compute_response)
+    }
+}
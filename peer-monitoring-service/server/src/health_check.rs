@@ -0,0 +1,88 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::Error;
+use aptos_config::{config::PeerMonitoringServiceConfig, network_id::PeerNetworkId};
+use aptos_infallible::Mutex;
+use aptos_peer_monitoring_service_types::response::PeerMonitoringServiceResponse;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// The number of tracked peers above which stale entries are garbage collected
+const MAX_TRACKED_PEERS_BEFORE_GC: usize = 10_000;
+
+/// Enforces the limits on health check requests. Health checks require no
+/// peer state (so they can be sent by external tools, e.g., load balancers),
+/// so each peer is aggressively rate limited and the responses are capped.
+#[derive(Debug)]
+pub struct HealthCheckLimiter {
+    last_served_health_checks: Mutex<HashMap<PeerNetworkId, Instant>>,
+    max_response_bytes: u64,
+    min_interval: Duration,
+    time_service: TimeService,
+}
+
+impl HealthCheckLimiter {
+    pub fn new(config: &PeerMonitoringServiceConfig, time_service: TimeService) -> Self {
+        Self {
+            last_served_health_checks: Mutex::new(HashMap::new()),
+            max_response_bytes: config.max_health_check_response_bytes,
+            min_interval: Duration::from_millis(config.health_check_rate_limit_interval_ms),
+            time_service,
+        }
+    }
+
+    /// Verifies that a health check can be served to the given peer (i.e., the
+    /// peer is not rate limited) and records the health check if so.
+    pub fn check_rate_limit(&self, peer_network_id: &PeerNetworkId) -> Result<(), Error> {
+        // If rate limiting is disabled, there's nothing to check
+        if self.min_interval.is_zero() {
+            return Ok(());
+        }
+
+        // Verify enough time has elapsed since the last health check served to the peer
+        let now = self.time_service.now();
+        let mut last_served_health_checks = self.last_served_health_checks.lock();
+        if let Some(last_served_time) = last_served_health_checks.get(peer_network_id) {
+            let time_since_last_served = now.duration_since(*last_served_time);
+            if time_since_last_served < self.min_interval {
+                return Err(Error::TooManyRequests(format!(
+                    "Health check rate limit exceeded for peer: {:?}. Time since last health check: {:?}, \
+                    min interval: {:?}",
+                    peer_network_id, time_since_last_served, self.min_interval
+                )));
+            }
+        }
+
+        // Garbage collect the stale entries (to bound the number of tracked peers)
+        if last_served_health_checks.len() >= MAX_TRACKED_PEERS_BEFORE_GC {
+            let min_interval = self.min_interval;
+            last_served_health_checks
+                .retain(|_, last_served_time| now.duration_since(*last_served_time) < min_interval);
+        }
+
+        // Record the health check
+        last_served_health_checks.insert(*peer_network_id, now);
+        Ok(())
+    }
+
+    /// Verifies that the given health check response respects the size cap
+    pub fn check_response_size(
+        &self,
+        response: &PeerMonitoringServiceResponse,
+    ) -> Result<(), Error> {
+        let num_response_bytes = response
+            .get_num_bytes()
+            .map_err(|error| Error::UnexpectedErrorEncountered(error.to_string()))?;
+        if num_response_bytes > self.max_response_bytes {
+            return Err(Error::UnexpectedErrorEncountered(format!(
+                "The health check response is too large! Num bytes: {}, max bytes: {}",
+                num_response_bytes, self.max_response_bytes
+            )));
+        }
+        Ok(())
+    }
+}
@@ -4,6 +4,8 @@
 #![forbid(unsafe_code)]
 
 use crate::{
+    cache::ResponseCaches,
+    health_check::HealthCheckLimiter,
     logging::{LogEntry, LogSchema},
     metrics::{increment_counter, start_timer},
     network::PeerMonitoringServiceNetworkEvents,
@@ -12,25 +14,31 @@ use crate::{
 use aptos_bounded_executor::BoundedExecutor;
 use aptos_config::{
     config::{BaseConfig, NodeConfig},
-    network_id::NetworkId,
+    network_id::PeerNetworkId,
 };
-use aptos_logger::prelude::*;
+use aptos_logger::{prelude::*, sample, sample::SampleRate};
 use aptos_network::application::storage::PeersAndMetadata;
 use aptos_peer_monitoring_service_types::{
     request::{LatencyPingRequest, PeerMonitoringServiceRequest},
     response::{
-        ConnectionMetadata, LatencyPingResponse, NetworkInformationResponse,
+        ConnectionMetadata, HealthCheckResponse, LatencyPingResponse, NetworkInformationResponse,
         NodeInformationResponse, PeerMonitoringServiceResponse, ServerProtocolVersionResponse,
     },
-    PeerMonitoringServiceError, Result, MAX_DISTANCE_FROM_VALIDATORS,
+    MempoolUtilization, PeerMonitoringServiceError, Result, MAX_DISTANCE_FROM_VALIDATORS,
 };
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use error::Error;
 use futures::stream::StreamExt;
-use std::{cmp::min, sync::Arc, time::Instant};
+use std::{
+    cmp::min,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::runtime::Handle;
 
+mod cache;
 mod error;
+mod health_check;
 mod logging;
 pub mod metrics;
 pub mod network;
@@ -41,13 +49,17 @@ mod tests;
 
 /// Peer monitoring server constants
 pub const PEER_MONITORING_SERVER_VERSION: u64 = 1;
+const RATE_LIMITED_LOG_FREQUENCY_SECS: u64 = 5; // The frequency to log rate limited requests
 
 /// The server-side actor for the peer monitoring service
 pub struct PeerMonitoringServiceServer<T> {
     base_config: BaseConfig,
     bounded_executor: BoundedExecutor,
+    health_check_limiter: Arc<HealthCheckLimiter>,
+    mempool_utilization: MempoolUtilization,
     network_requests: PeerMonitoringServiceNetworkEvents,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    response_caches: Arc<ResponseCaches>,
     start_time: Instant,
     storage: T,
     time_service: TimeService,
@@ -59,6 +71,7 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
         executor: Handle,
         network_requests: PeerMonitoringServiceNetworkEvents,
         peers_and_metadata: Arc<PeersAndMetadata>,
+        mempool_utilization: MempoolUtilization,
         storage: T,
         time_service: TimeService,
     ) -> Self {
@@ -67,13 +80,24 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
             node_config.peer_monitoring_service.max_concurrent_requests as usize,
             executor,
         );
+        let health_check_limiter = Arc::new(HealthCheckLimiter::new(
+            &node_config.peer_monitoring_service,
+            time_service.clone(),
+        ));
+        let response_caches = Arc::new(ResponseCaches::new(
+            &node_config.peer_monitoring_service,
+            time_service.clone(),
+        ));
         let start_time = time_service.now();
 
         Self {
             base_config,
             bounded_executor,
+            health_check_limiter,
+            mempool_utilization,
             network_requests,
             peers_and_metadata,
+            response_caches,
             start_time,
             storage,
             time_service,
@@ -98,7 +122,10 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
             // All handler methods are currently CPU-bound so we want
             // to spawn on the blocking thread pool.
             let base_config = self.base_config.clone();
+            let health_check_limiter = self.health_check_limiter.clone();
+            let mempool_utilization = self.mempool_utilization.clone();
             let peers_and_metadata = self.peers_and_metadata.clone();
+            let response_caches = self.response_caches.clone();
             let start_time = self.start_time;
             let storage = self.storage.clone();
             let time_service = self.time_service.clone();
@@ -106,15 +133,15 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
                 .spawn_blocking(move || {
                     let response = Handler::new(
                         base_config,
+                        health_check_limiter,
+                        mempool_utilization,
                         peers_and_metadata,
+                        response_caches,
                         start_time,
                         storage,
                         time_service,
                     )
-                    .call(
-                        peer_network_id.network_id(),
-                        peer_monitoring_service_request,
-                    );
+                    .call(peer_network_id, peer_monitoring_service_request);
                     log_monitoring_service_response(&response);
                     response_sender.send(response);
                 })
@@ -129,7 +156,10 @@ impl<T: StorageReaderInterface> PeerMonitoringServiceServer<T> {
 #[derive(Clone)]
 pub struct Handler<T> {
     base_config: BaseConfig,
+    health_check_limiter: Arc<HealthCheckLimiter>,
+    mempool_utilization: MempoolUtilization,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    response_caches: Arc<ResponseCaches>,
     start_time: Instant,
     storage: T,
     time_service: TimeService,
@@ -138,14 +168,20 @@ pub struct Handler<T> {
 impl<T: StorageReaderInterface> Handler<T> {
     pub fn new(
         base_config: BaseConfig,
+        health_check_limiter: Arc<HealthCheckLimiter>,
+        mempool_utilization: MempoolUtilization,
         peers_and_metadata: Arc<PeersAndMetadata>,
+        response_caches: Arc<ResponseCaches>,
         start_time: Instant,
         storage: T,
         time_service: TimeService,
     ) -> Self {
         Self {
             base_config,
+            health_check_limiter,
+            mempool_utilization,
             peers_and_metadata,
+            response_caches,
             start_time,
             storage,
             time_service,
@@ -154,9 +190,11 @@ impl<T: StorageReaderInterface> Handler<T> {
 
     pub fn call(
         &self,
-        network_id: NetworkId,
+        peer_network_id: PeerNetworkId,
         request: PeerMonitoringServiceRequest,
     ) -> Result<PeerMonitoringServiceResponse> {
+        let network_id = peer_network_id.network_id();
+
         // Update the request count
         increment_counter(
             &metrics::PEER_MONITORING_REQUESTS_RECEIVED,
@@ -171,15 +209,12 @@ impl<T: StorageReaderInterface> Handler<T> {
             request.get_label(),
         );
 
-        // Process the request
-        let response = match &request {
-            PeerMonitoringServiceRequest::GetNetworkInformation => self.get_network_information(),
-            PeerMonitoringServiceRequest::GetServerProtocolVersion => {
-                self.get_server_protocol_version()
-            },
-            PeerMonitoringServiceRequest::GetNodeInformation => self.get_node_information(),
-            PeerMonitoringServiceRequest::LatencyPing(request) => self.handle_latency_ping(request),
-        };
+        // Process the request (using the response cache, if possible)
+        let response = self
+            .response_caches
+            .get_or_compute(&request, || {
+                self.process_request(&peer_network_id, &request)
+            });
 
         // Process the response and handle any errors
         match response {
@@ -190,13 +225,23 @@ impl<T: StorageReaderInterface> Handler<T> {
                     network_id,
                     error.get_label(),
                 );
-                error!(LogSchema::new(LogEntry::PeerMonitoringServiceError)
-                    .error(&error)
-                    .request(&request));
+                if matches!(error, Error::TooManyRequests(_)) {
+                    // Rate limited requests are expected (e.g., from load balancers)
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(RATE_LIMITED_LOG_FREQUENCY_SECS)),
+                        warn!(LogSchema::new(LogEntry::PeerMonitoringServiceError)
+                            .error(&error)
+                            .request(&request))
+                    );
+                } else {
+                    error!(LogSchema::new(LogEntry::PeerMonitoringServiceError)
+                        .error(&error)
+                        .request(&request));
+                }
 
                 // Return an appropriate response to the client
                 match error {
-                    Error::InvalidRequest(error) => {
+                    Error::InvalidRequest(error) | Error::TooManyRequests(error) => {
                         Err(PeerMonitoringServiceError::InvalidRequest(error))
                     },
                     error => Err(PeerMonitoringServiceError::InternalError(error.to_string())),
@@ -214,6 +259,31 @@ impl<T: StorageReaderInterface> Handler<T> {
         }
     }
 
+    /// Computes the response for the given request
+    fn process_request(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        request: &PeerMonitoringServiceRequest,
+    ) -> Result<PeerMonitoringServiceResponse, Error> {
+        match request {
+            PeerMonitoringServiceRequest::GetMempoolInformation => self.get_mempool_information(),
+            PeerMonitoringServiceRequest::GetNetworkInformation => self.get_network_information(),
+            PeerMonitoringServiceRequest::GetServerProtocolVersion => {
+                self.get_server_protocol_version()
+            },
+            PeerMonitoringServiceRequest::GetNodeInformation => self.get_node_information(),
+            PeerMonitoringServiceRequest::LatencyPing(request) => self.handle_latency_ping(request),
+            PeerMonitoringServiceRequest::HealthCheck(_) => self.handle_health_check(peer_network_id),
+        }
+    }
+
+    fn get_mempool_information(&self) -> Result<PeerMonitoringServiceResponse, Error> {
+        let mempool_information_response = self.mempool_utilization.get_mempool_information();
+        Ok(PeerMonitoringServiceResponse::MempoolInformation(
+            mempool_information_response,
+        ))
+    }
+
     fn get_network_information(&self) -> Result<PeerMonitoringServiceResponse, Error> {
         // Get the connected peers
         let connected_peers_and_metadata =
@@ -280,6 +350,31 @@ impl<T: StorageReaderInterface> Handler<T> {
         ))
     }
 
+    fn handle_health_check(
+        &self,
+        peer_network_id: &PeerNetworkId,
+    ) -> Result<PeerMonitoringServiceResponse, Error> {
+        // Verify the peer isn't rate limited
+        self.health_check_limiter.check_rate_limit(peer_network_id)?;
+
+        // Get the ledger version and calculate the sync lag
+        let (_, ledger_version) = self.storage.get_highest_synced_epoch_and_version()?;
+        let ledger_timestamp_usecs = self.storage.get_ledger_timestamp_usecs()?;
+        let current_time_usecs = self.time_service.now_unix_time().as_micros() as u64;
+        let sync_lag_usecs = current_time_usecs.saturating_sub(ledger_timestamp_usecs);
+
+        // Create the response and verify it respects the size cap
+        let health_check_response = HealthCheckResponse {
+            node_role: self.base_config.role,
+            ledger_version,
+            sync_lag_usecs,
+        };
+        let response = PeerMonitoringServiceResponse::HealthCheck(health_check_response);
+        self.health_check_limiter.check_response_size(&response)?;
+
+        Ok(response)
+    }
+
     fn handle_latency_ping(
         &self,
         latency_ping_request: &LatencyPingRequest,
@@ -47,6 +47,16 @@ pub static PEER_MONITORING_RESPONSES_SENT: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for peer monitoring service response cache lookups
+pub static PEER_MONITORING_RESPONSE_CACHE_LOOKUPS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_peer_monitoring_service_server_response_cache_lookups",
+        "Counters related to the peer monitoring server response cache lookups",
+        &["request_type", "result"]
+    )
+    .unwrap()
+});
+
 /// Time it takes to process a peer monitoring request
 pub static PEER_MONITORING_REQUEST_PROCESSING_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -64,6 +74,13 @@ pub fn increment_counter(counter: &Lazy<IntCounterVec>, network_id: NetworkId, l
         .inc();
 }
 
+/// Increments the response cache lookup counter for the given request and result
+pub fn increment_cache_lookup(request_label: &str, result_label: &str) {
+    PEER_MONITORING_RESPONSE_CACHE_LOOKUPS
+        .with_label_values(&[request_label, result_label])
+        .inc();
+}
+
 /// Starts the timer for the provided histogram and label values.
 pub fn start_timer(
     histogram: &Lazy<HistogramVec>,
@@ -30,12 +30,13 @@ use aptos_network::{
     transport::{ConnectionId, ConnectionMetadata},
 };
 use aptos_peer_monitoring_service_types::{
-    request::{LatencyPingRequest, PeerMonitoringServiceRequest},
+    request::{HealthCheckRequest, LatencyPingRequest, PeerMonitoringServiceRequest},
     response::{
-        NetworkInformationResponse, NodeInformationResponse, PeerMonitoringServiceResponse,
-        ServerProtocolVersionResponse,
+        HealthCheckResponse, MempoolInformationResponse, NetworkInformationResponse,
+        NodeInformationResponse, PeerMonitoringServiceResponse, ServerProtocolVersionResponse,
     },
-    PeerMonitoringMetadata, PeerMonitoringServiceError, PeerMonitoringServiceMessage,
+    MempoolUtilization, PeerMonitoringMetadata, PeerMonitoringServiceError,
+    PeerMonitoringServiceMessage,
 };
 use aptos_storage_interface::{DbReader, ExecutedTrees, Order};
 use aptos_time_service::{MockTimeService, TimeService};
@@ -77,7 +78,7 @@ const LOCAL_HOST_NET_ADDR: &str = "/ip4/127.0.0.1/tcp/8081";
 #[tokio::test]
 async fn test_get_server_protocol_version() {
     // Create the peer monitoring client and server
-    let (mut mock_client, service, _, _) = MockClient::new(None, None, None);
+    let (mut mock_client, service, _, _) = MockClient::new(None, None, None, None);
     tokio::spawn(service.start());
 
     // Process a request to fetch the protocol version
@@ -100,7 +101,7 @@ async fn test_get_network_information_fullnode() {
         ..Default::default()
     };
     let (mut mock_client, service, _, peers_and_metadata) =
-        MockClient::new(Some(base_config), None, None);
+        MockClient::new(Some(base_config), None, None, None);
     tokio::spawn(service.start());
 
     // Process a client request to fetch the network information and verify an empty response
@@ -221,7 +222,7 @@ async fn test_get_network_information_validator() {
         ..Default::default()
     };
     let (mut mock_client, service, _, peers_and_metadata) =
-        MockClient::new(Some(base_config), None, None);
+        MockClient::new(Some(base_config), None, None, None);
     tokio::spawn(service.start());
 
     // Process a client request to fetch the network information and verify
@@ -331,7 +332,7 @@ async fn test_get_node_information() {
     // Create the peer monitoring client and server
     let storage_reader = StorageReader::new(Arc::new(mock_db_reader));
     let (mut mock_client, service, time_service, _) =
-        MockClient::new(None, None, Some(storage_reader));
+        MockClient::new(None, None, Some(storage_reader), None);
     tokio::spawn(service.start());
 
     // Process a client request to fetch the node information and verify the response
@@ -366,10 +367,247 @@ async fn test_get_node_information() {
     }
 }
 
+#[tokio::test]
+async fn test_get_network_information_cached() {
+    // Create the peer monitoring client and server (with network info caching enabled)
+    let max_network_info_staleness_ms = 1000;
+    let peer_monitoring_config = PeerMonitoringServiceConfig {
+        max_network_info_staleness_ms,
+        ..Default::default()
+    };
+    let (mut mock_client, service, time_service, peers_and_metadata) =
+        MockClient::new(None, Some(peer_monitoring_config), None, None);
+    tokio::spawn(service.start());
+
+    // Process a client request to fetch the network information and verify an empty response
+    verify_network_information(
+        &mut mock_client,
+        BTreeMap::new(),
+        MAX_DISTANCE_FROM_VALIDATORS,
+    )
+    .await;
+
+    // Connect a new peer
+    let peer_id = PeerId::random();
+    let peer_network_id = PeerNetworkId::new(NetworkId::Public, peer_id);
+    let connection_metadata = create_connection_metadata(peer_id, PeerRole::Unknown);
+    peers_and_metadata
+        .insert_connection_metadata(peer_network_id, connection_metadata.clone())
+        .unwrap();
+
+    // Verify the cached (empty) response is returned while it is fresh
+    time_service.advance(Duration::from_millis(max_network_info_staleness_ms));
+    verify_network_information(
+        &mut mock_client,
+        BTreeMap::new(),
+        MAX_DISTANCE_FROM_VALIDATORS,
+    )
+    .await;
+
+    // Elapse enough time for the cached response to become stale
+    time_service.advance(Duration::from_millis(1));
+
+    // Verify the new peer is now returned
+    verify_network_information(
+        &mut mock_client,
+        btreemap! {peer_network_id => connection_metadata},
+        MAX_DISTANCE_FROM_VALIDATORS,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_get_node_information_cached() {
+    // Setup the mock data
+    let highest_synced_epoch = 10;
+    let highest_synced_version = 500;
+    let ledger_timestamp_usecs = 1234;
+    let block_info = BlockInfo::new(
+        highest_synced_epoch,
+        0,
+        HashValue::zero(),
+        HashValue::zero(),
+        highest_synced_version,
+        ledger_timestamp_usecs,
+        None,
+    );
+    let latest_ledger_info = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(block_info, HashValue::zero()),
+        AggregateSignature::empty(),
+    );
+    let lowest_available_version = 5;
+
+    // Create the mock storage reader (storage should only be read for two requests,
+    // and each node info request reads the latest ledger info twice).
+    let mut mock_db_reader = create_mock_db_reader();
+    mock_db_reader
+        .expect_get_latest_ledger_info()
+        .times(4)
+        .returning(move || Ok(latest_ledger_info.clone()));
+    mock_db_reader
+        .expect_get_first_txn_version()
+        .times(2)
+        .returning(move || Ok(Some(lowest_available_version)));
+
+    // Create the peer monitoring client and server (with node info caching enabled)
+    let max_node_info_staleness_ms = 500;
+    let peer_monitoring_config = PeerMonitoringServiceConfig {
+        max_node_info_staleness_ms,
+        ..Default::default()
+    };
+    let storage_reader = StorageReader::new(Arc::new(mock_db_reader));
+    let (mut mock_client, service, time_service, _) = MockClient::new(
+        None,
+        Some(peer_monitoring_config),
+        Some(storage_reader),
+        None,
+    );
+    tokio::spawn(service.start());
+
+    // Process several node information requests and verify the cached uptime is returned
+    for _ in 0..5 {
+        verify_node_information(
+            &mut mock_client,
+            highest_synced_epoch,
+            highest_synced_version,
+            ledger_timestamp_usecs,
+            lowest_available_version,
+            Duration::from_millis(0),
+        )
+        .await;
+        time_service.advance(Duration::from_millis(100));
+    }
+
+    // Elapse enough time for the cached response to become stale
+    time_service.advance(Duration::from_millis(max_node_info_staleness_ms));
+
+    // Verify a new response is computed (with the updated uptime)
+    verify_node_information(
+        &mut mock_client,
+        highest_synced_epoch,
+        highest_synced_version,
+        ledger_timestamp_usecs,
+        lowest_available_version,
+        Duration::from_millis(1000),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_get_mempool_information() {
+    // Create the peer monitoring client and server
+    let mempool_utilization = MempoolUtilization::default();
+    let (mut mock_client, service, _, _) =
+        MockClient::new(None, None, None, Some(mempool_utilization.clone()));
+    tokio::spawn(service.start());
+
+    // Verify that the mempool is initially reported as empty
+    verify_mempool_information(&mut mock_client, 0, 0).await;
+
+    // Update the mempool utilization several times and verify the responses
+    let capacity = 2_000_000;
+    for num_transactions in [0, 1000, 1_500_000, capacity] {
+        mempool_utilization.update(num_transactions, capacity);
+        verify_mempool_information(&mut mock_client, num_transactions, capacity).await;
+    }
+}
+
+#[tokio::test]
+async fn test_health_check() {
+    // Setup the mock data
+    let highest_synced_version = 1000;
+    let ledger_timestamp_usecs = 9_734_834;
+    let block_info = BlockInfo::new(
+        5,
+        0,
+        HashValue::zero(),
+        HashValue::zero(),
+        highest_synced_version,
+        ledger_timestamp_usecs,
+        None,
+    );
+    let latest_ledger_info = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(block_info, HashValue::zero()),
+        AggregateSignature::empty(),
+    );
+
+    // Create the mock storage reader
+    let mut mock_db_reader = create_mock_db_reader();
+    mock_db_reader
+        .expect_get_latest_ledger_info()
+        .returning(move || Ok(latest_ledger_info.clone()));
+
+    // Create the peer monitoring client and server (with health check rate limiting)
+    let base_config = BaseConfig {
+        role: RoleType::Validator,
+        ..Default::default()
+    };
+    let health_check_rate_limit_interval_ms = 1000;
+    let peer_monitoring_config = PeerMonitoringServiceConfig {
+        health_check_rate_limit_interval_ms,
+        ..Default::default()
+    };
+    let storage_reader = StorageReader::new(Arc::new(mock_db_reader));
+    let (mut mock_client, service, time_service, _) = MockClient::new(
+        Some(base_config),
+        Some(peer_monitoring_config),
+        Some(storage_reader),
+        None,
+    );
+    tokio::spawn(service.start());
+
+    // Elapse some time so that the node is lagging behind
+    time_service.advance(Duration::from_secs(10));
+
+    // Send a health check and verify the response
+    let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    let expected_sync_lag_usecs = 10_000_000 - ledger_timestamp_usecs;
+    verify_health_check(
+        &mut mock_client,
+        peer_network_id,
+        highest_synced_version,
+        expected_sync_lag_usecs,
+    )
+    .await;
+
+    // Send another health check from the same peer and verify it is rate limited
+    let response = mock_client
+        .send_request_from_peer(
+            peer_network_id,
+            PeerMonitoringServiceRequest::HealthCheck(HealthCheckRequest),
+        )
+        .await;
+    assert!(matches!(
+        response,
+        Err(PeerMonitoringServiceError::InvalidRequest(_))
+    ));
+
+    // Verify that health checks from other peers are still served
+    let other_peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    verify_health_check(
+        &mut mock_client,
+        other_peer_network_id,
+        highest_synced_version,
+        expected_sync_lag_usecs,
+    )
+    .await;
+
+    // Elapse the rate limit interval and verify the peer is served again
+    let rate_limit_interval = Duration::from_millis(health_check_rate_limit_interval_ms);
+    time_service.advance(rate_limit_interval);
+    verify_health_check(
+        &mut mock_client,
+        peer_network_id,
+        highest_synced_version,
+        expected_sync_lag_usecs + rate_limit_interval.as_micros() as u64,
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn test_latency_ping_request() {
     // Create the peer monitoring client and server
-    let (mut mock_client, service, _, _) = MockClient::new(None, None, None);
+    let (mut mock_client, service, _, _) = MockClient::new(None, None, None, None);
     tokio::spawn(service.start());
 
     // Process several requests to perform latency pings
@@ -399,6 +637,50 @@ fn create_connection_metadata(peer_id: AccountAddress, peer_role: PeerRole) -> C
     )
 }
 
+/// A simple utility function that sends a health check from the given peer
+/// using the given client, and verifies the response is correct.
+async fn verify_health_check(
+    client: &mut MockClient,
+    peer_network_id: PeerNetworkId,
+    expected_ledger_version: u64,
+    expected_sync_lag_usecs: u64,
+) {
+    // Send the health check request
+    let request = PeerMonitoringServiceRequest::HealthCheck(HealthCheckRequest);
+    let response = client
+        .send_request_from_peer(peer_network_id, request)
+        .await
+        .unwrap();
+
+    // Verify the response is correct
+    let expected_response = PeerMonitoringServiceResponse::HealthCheck(HealthCheckResponse {
+        node_role: RoleType::Validator,
+        ledger_version: expected_ledger_version,
+        sync_lag_usecs: expected_sync_lag_usecs,
+    });
+    assert_eq!(response, expected_response);
+}
+
+/// A simple utility function that sends a request for mempool info using the given
+/// client, and verifies the response is correct.
+async fn verify_mempool_information(
+    client: &mut MockClient,
+    expected_num_transactions: u64,
+    expected_capacity: u64,
+) {
+    // Send a request to fetch the mempool information
+    let request = PeerMonitoringServiceRequest::GetMempoolInformation;
+    let response = client.send_request(request).await.unwrap();
+
+    // Verify the response is correct
+    let expected_response =
+        PeerMonitoringServiceResponse::MempoolInformation(MempoolInformationResponse {
+            num_transactions: expected_num_transactions,
+            capacity: expected_capacity,
+        });
+    assert_eq!(response, expected_response);
+}
+
 /// A simple utility function that sends a request for network info using the given
 /// client, and verifies the response is correct.
 async fn verify_network_information(
@@ -477,6 +759,7 @@ impl MockClient {
         base_config: Option<BaseConfig>,
         peer_monitoring_config: Option<PeerMonitoringServiceConfig>,
         storage_reader: Option<StorageReader>,
+        mempool_utilization: Option<MempoolUtilization>,
     ) -> (
         Self,
         PeerMonitoringServiceServer<StorageReader>,
@@ -487,7 +770,13 @@ impl MockClient {
 
         // Create the node config
         let base_config = base_config.unwrap_or_default();
-        let peer_monitoring_config = peer_monitoring_config.unwrap_or_default();
+        // Response caching is disabled by default, so that tests observe updates immediately
+        let peer_monitoring_config =
+            peer_monitoring_config.unwrap_or_else(|| PeerMonitoringServiceConfig {
+                max_network_info_staleness_ms: 0,
+                max_node_info_staleness_ms: 0,
+                ..Default::default()
+            });
         let node_config = NodeConfig {
             base: base_config,
             peer_monitoring_service: peer_monitoring_config,
@@ -524,6 +813,7 @@ impl MockClient {
             executor,
             peer_monitoring_network_events,
             peers_and_metadata.clone(),
+            mempool_utilization.unwrap_or_default(),
             storage_reader,
             mock_time_service.clone(),
         );
@@ -546,9 +836,19 @@ impl MockClient {
         &mut self,
         request: PeerMonitoringServiceRequest,
     ) -> Result<PeerMonitoringServiceResponse, PeerMonitoringServiceError> {
-        let peer_id = PeerId::random();
+        let peer_network_id = PeerNetworkId::new(get_random_network_id(), PeerId::random());
+        self.send_request_from_peer(peer_network_id, request).await
+    }
+
+    /// Sends the specified request (from the given peer) and returns the response from the server
+    async fn send_request_from_peer(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        request: PeerMonitoringServiceRequest,
+    ) -> Result<PeerMonitoringServiceResponse, PeerMonitoringServiceError> {
+        let peer_id = peer_network_id.peer_id();
         let protocol_id = ProtocolId::PeerMonitoringServiceRpc;
-        let network_id = get_random_network_id();
+        let network_id = peer_network_id.network_id();
 
         // Create an inbound RPC request
         let request_data = protocol_id
@@ -3,13 +3,19 @@
 
 #![forbid(unsafe_code)]
 
-use crate::response::{NetworkInformationResponse, NodeInformationResponse};
+use crate::response::{
+    MempoolInformationResponse, NetworkInformationResponse, NodeInformationResponse,
+};
 use request::PeerMonitoringServiceRequest;
 use response::PeerMonitoringServiceResponse;
 use serde::{Deserialize, Serialize};
 use std::{
     fmt,
     fmt::{Debug, Display},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 use thiserror::Error;
 
@@ -47,6 +53,7 @@ pub struct PeerMonitoringMetadata {
     pub latest_ping_latency_secs: Option<f64>,  // The latest latency ping for the peer
     pub latest_network_info_response: Option<NetworkInformationResponse>, // The latest network info response
     pub latest_node_info_response: Option<NodeInformationResponse>, // The latest node info response
+    pub latest_mempool_info_response: Option<MempoolInformationResponse>, // The latest mempool info response
     pub internal_client_state: Option<String>, // A detailed client state string for debugging and logging
 }
 
@@ -67,6 +74,7 @@ impl PeerMonitoringMetadata {
             latest_ping_latency_secs,
             latest_network_info_response,
             latest_node_info_response,
+            latest_mempool_info_response: None,
             internal_client_state,
         }
     }
@@ -77,11 +85,12 @@ impl Display for PeerMonitoringMetadata {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{ average_ping_latency_secs: {}, latest_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {} }}",
+            "{{ average_ping_latency_secs: {}, latest_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {}, latest_mempool_info_response: {} }}",
             display_format_option(&self.average_ping_latency_secs),
             display_format_option(&self.latest_ping_latency_secs),
             display_format_option(&self.latest_network_info_response),
             display_format_option(&self.latest_node_info_response),
+            display_format_option(&self.latest_mempool_info_response),
         )
     }
 }
@@ -92,15 +101,41 @@ impl Debug for PeerMonitoringMetadata {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{{ average_ping_latency_secs: {}, latest_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {} }}",
+            "{{ average_ping_latency_secs: {}, latest_ping_latency_secs: {}, latest_network_info_response: {}, latest_node_info_response: {}, latest_mempool_info_response: {} }}",
             debug_format_option(&self.average_ping_latency_secs),
             debug_format_option(&self.latest_ping_latency_secs),
             debug_format_option(&self.latest_network_info_response),
             debug_format_option(&self.latest_node_info_response),
+            debug_format_option(&self.latest_mempool_info_response),
         )
     }
 }
 
+/// A shared handle to the mempool utilization of the node. This is
+/// updated by mempool, and served to peers by the monitoring service.
+#[derive(Clone, Debug, Default)]
+pub struct MempoolUtilization {
+    num_transactions: Arc<AtomicU64>, // The number of transactions in mempool
+    capacity: Arc<AtomicU64>,         // The maximum number of transactions in mempool
+}
+
+impl MempoolUtilization {
+    /// Updates the number of transactions in mempool, and the mempool capacity
+    pub fn update(&self, num_transactions: u64, capacity: u64) {
+        self.num_transactions
+            .store(num_transactions, Ordering::Relaxed);
+        self.capacity.store(capacity, Ordering::Relaxed);
+    }
+
+    /// Returns the current mempool information
+    pub fn get_mempool_information(&self) -> MempoolInformationResponse {
+        MempoolInformationResponse {
+            num_transactions: self.num_transactions.load(Ordering::Relaxed),
+            capacity: self.capacity.load(Ordering::Relaxed),
+        }
+    }
+}
+
 /// A simple utility function for debug formatting an optional value
 fn debug_format_option<T: Debug>(option: &Option<T>) -> String {
     option
@@ -10,6 +10,8 @@ pub enum PeerMonitoringServiceRequest {
     GetNodeInformation,       // Returns relevant node information about the peer
     GetServerProtocolVersion, // Fetches the protocol version run by the server
     LatencyPing(LatencyPingRequest), // A simple message used by the client to ensure liveness and measure latency
+    GetMempoolInformation, // Returns relevant mempool information (e.g., utilization) about the peer
+    HealthCheck(HealthCheckRequest), // A minimal (rate limited) health check, e.g., for load balancers
 }
 
 impl PeerMonitoringServiceRequest {
@@ -20,6 +22,8 @@ impl PeerMonitoringServiceRequest {
             Self::GetNodeInformation => "get_node_information",
             Self::GetServerProtocolVersion => "get_server_protocol_version",
             Self::LatencyPing(_) => "latency_ping",
+            Self::GetMempoolInformation => "get_mempool_information",
+            Self::HealthCheck(_) => "health_check",
         }
     }
 }
@@ -29,3 +33,8 @@ impl PeerMonitoringServiceRequest {
 pub struct LatencyPingRequest {
     pub ping_counter: u64, // A monotonically increasing counter to verify latency ping responses
 }
+
+/// The health check request. This is intentionally empty, so that the request
+/// can be sent by external tools (e.g., load balancers) without any peer state.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct HealthCheckRequest;
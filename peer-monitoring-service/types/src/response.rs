@@ -1,7 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::{config::PeerRole, network_id::PeerNetworkId};
+use aptos_config::{
+    config::{PeerRole, RoleType},
+    network_id::PeerNetworkId,
+};
 use aptos_types::{network_address::NetworkAddress, PeerId};
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, fmt, fmt::Display, time::Duration};
@@ -15,6 +18,8 @@ pub enum PeerMonitoringServiceResponse {
     NetworkInformation(NetworkInformationResponse), // Holds the response for network information
     NodeInformation(NodeInformationResponse), // Holds the response for node information
     ServerProtocolVersion(ServerProtocolVersionResponse), // Returns the current server protocol version
+    MempoolInformation(MempoolInformationResponse), // Holds the response for mempool information
+    HealthCheck(HealthCheckResponse),               // Holds the response for health checks
 }
 
 impl PeerMonitoringServiceResponse {
@@ -25,6 +30,8 @@ impl PeerMonitoringServiceResponse {
             Self::NetworkInformation(_) => "network_information",
             Self::NodeInformation(_) => "node_information",
             Self::ServerProtocolVersion(_) => "server_protocol_version",
+            Self::MempoolInformation(_) => "mempool_information",
+            Self::HealthCheck(_) => "health_check",
         }
     }
 
@@ -117,6 +124,53 @@ impl Display for NodeInformationResponse {
     }
 }
 
+/// A response for the mempool information request
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MempoolInformationResponse {
+    pub num_transactions: u64, // The number of transactions in the mempool of the node
+    pub capacity: u64,         // The maximum number of transactions in the mempool of the node
+}
+
+impl MempoolInformationResponse {
+    /// Returns the mempool utilization (i.e., the fraction of the capacity in use)
+    pub fn get_utilization(&self) -> f64 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+        self.num_transactions as f64 / self.capacity as f64
+    }
+}
+
+// Display formatting provides a high-level summary of the response
+impl Display for MempoolInformationResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{{ num_transactions: {:?}, capacity: {:?} }}",
+            self.num_transactions, self.capacity,
+        )
+    }
+}
+
+/// A response for the health check request
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HealthCheckResponse {
+    pub node_role: RoleType, // The role of the node (i.e., validator or fullnode)
+    pub ledger_version: u64, // The highest synced version of the node
+    pub sync_lag_usecs: u64, // The lag (in microseconds) between the ledger timestamp and the current time
+}
+
+// Display formatting provides a high-level summary of the response
+impl Display for HealthCheckResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{{ node_role: {:?}, ledger_version: {:?}, sync_lag_usecs: {:?} }}",
+            self.node_role, self.ledger_version, self.sync_lag_usecs,
+        )
+    }
+}
+
 #[derive(Clone, Debug, Error)]
 #[error("Unexpected response variant: {0}")]
 pub struct UnexpectedResponseError(pub String);
@@ -176,3 +230,31 @@ impl TryFrom<PeerMonitoringServiceResponse> for ServerProtocolVersionResponse {
         }
     }
 }
+
+impl TryFrom<PeerMonitoringServiceResponse> for MempoolInformationResponse {
+    type Error = UnexpectedResponseError;
+
+    fn try_from(response: PeerMonitoringServiceResponse) -> crate::Result<Self, Self::Error> {
+        match response {
+            PeerMonitoringServiceResponse::MempoolInformation(inner) => Ok(inner),
+            _ => Err(UnexpectedResponseError(format!(
+                "expected mempool_information_response, found {}",
+                response.get_label()
+            ))),
+        }
+    }
+}
+
+impl TryFrom<PeerMonitoringServiceResponse> for HealthCheckResponse {
+    type Error = UnexpectedResponseError;
+
+    fn try_from(response: PeerMonitoringServiceResponse) -> crate::Result<Self, Self::Error> {
+        match response {
+            PeerMonitoringServiceResponse::HealthCheck(inner) => Ok(inner),
+            _ => Err(UnexpectedResponseError(format!(
+                "expected health_check_response, found {}",
+                response.get_label()
+            ))),
+        }
+    }
+}
@@ -17,7 +17,7 @@ from aptos.transaction.v1 import (
 )
 
 DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(
-    b'\n\x1f\x61ptos/indexer/v1/raw_data.proto\x12\x10\x61ptos.indexer.v1\x1a&aptos/transaction/v1/transaction.proto"\x84\x01\n\x15TransactionsInStorage\x12\x37\n\x0ctransactions\x18\x01 \x03(\x0b\x32!.aptos.transaction.v1.Transaction\x12\x1d\n\x10starting_version\x18\x02 \x01(\x04H\x00\x88\x01\x01\x42\x13\n\x11_starting_version"\xb4\x01\n\x16GetTransactionsRequest\x12!\n\x10starting_version\x18\x01 \x01(\x04\x42\x02\x30\x01H\x00\x88\x01\x01\x12#\n\x12transactions_count\x18\x02 \x01(\x04\x42\x02\x30\x01H\x01\x88\x01\x01\x12\x17\n\nbatch_size\x18\x03 \x01(\x04H\x02\x88\x01\x01\x42\x13\n\x11_starting_versionB\x15\n\x13_transactions_countB\r\n\x0b_batch_size"w\n\x14TransactionsResponse\x12\x37\n\x0ctransactions\x18\x01 \x03(\x0b\x32!.aptos.transaction.v1.Transaction\x12\x19\n\x08\x63hain_id\x18\x02 \x01(\x04\x42\x02\x30\x01H\x00\x88\x01\x01\x42\x0b\n\t_chain_id2p\n\x07RawData\x12\x65\n\x0fGetTransactions\x12(.aptos.indexer.v1.GetTransactionsRequest\x1a&.aptos.indexer.v1.TransactionsResponse0\x01\x62\x06proto3'
+    b'\n\x1f\x61ptos/indexer/v1/raw_data.proto\x12\x10\x61ptos.indexer.v1\x1a&aptos/transaction/v1/transaction.proto"\x84\x01\n\x15TransactionsInStorage\x12\x37\n\x0ctransactions\x18\x01 \x03(\x0b\x32!.aptos.transaction.v1.Transaction\x12\x1d\n\x10starting_version\x18\x02 \x01(\x04H\x00\x88\x01\x01\x42\x13\n\x11_starting_version"\xde\x01\n\x16GetTransactionsRequest\x12!\n\x10starting_version\x18\x01 \x01(\x04\x42\x02\x30\x01H\x00\x88\x01\x01\x12#\n\x12transactions_count\x18\x02 \x01(\x04\x42\x02\x30\x01H\x01\x88\x01\x01\x12\x17\n\nbatch_size\x18\x03 \x01(\x04H\x02\x88\x01\x01\x12\x18\n\x0b\x63onsumer_id\x18\x04 \x01(\tH\x03\x88\x01\x01\x42\x13\n\x11_starting_versionB\x15\n\x13_transactions_countB\r\n\x0b_batch_sizeB\x0e\n\x0c_consumer_id"w\n\x14TransactionsResponse\x12\x37\n\x0ctransactions\x18\x01 \x03(\x0b\x32!.aptos.transaction.v1.Transaction\x12\x19\n\x08\x63hain_id\x18\x02 \x01(\x04\x42\x02\x30\x01H\x00\x88\x01\x01\x42\x0b\n\t_chain_id"g\n\x15\x43ommitProgressRequest\x12\x18\n\x0b\x63onsumer_id\x18\x01 \x01(\tH\x00\x88\x01\x01\x12\x18\n\x07version\x18\x02 \x01(\x04\x42\x02\x30\x01H\x01\x88\x01\x01\x42\x0e\n\x0c_consumer_idB\n\n\x08_version"R\n\x16\x43ommitProgressResponse\x12"\n\x11\x63ommitted_version\x18\x01 \x01(\x04\x42\x02\x30\x01H\x00\x88\x01\x01\x42\x14\n\x12_committed_version">\n\x12GetProgressRequest\x12\x18\n\x0b\x63onsumer_id\x18\x01 \x01(\tH\x00\x88\x01\x01\x42\x0e\n\x0c_consumer_id"O\n\x13GetProgressResponse\x12"\n\x11\x63ommitted_version\x18\x01 \x01(\x04\x42\x02\x30\x01H\x00\x88\x01\x01\x42\x14\n\x12_committed_version2\xb1\x02\n\x07RawData\x12\x65\n\x0fGetTransactions\x12(.aptos.indexer.v1.GetTransactionsRequest\x1a&.aptos.indexer.v1.TransactionsResponse0\x01\x12\x63\n\x0e\x43ommitProgress\x12\'.aptos.indexer.v1.CommitProgressRequest\x1a(.aptos.indexer.v1.CommitProgressResponse\x12Z\n\x0bGetProgress\x12$.aptos.indexer.v1.GetProgressRequest\x1a%.aptos.indexer.v1.GetProgressResponseb\x06proto3'
 )
 
 _globals = globals()
@@ -37,12 +37,30 @@ if _descriptor._USE_C_DESCRIPTORS == False:
     ]._serialized_options = b"0\001"
     _TRANSACTIONSRESPONSE.fields_by_name["chain_id"]._options = None
     _TRANSACTIONSRESPONSE.fields_by_name["chain_id"]._serialized_options = b"0\001"
+    _COMMITPROGRESSREQUEST.fields_by_name["version"]._options = None
+    _COMMITPROGRESSREQUEST.fields_by_name["version"]._serialized_options = b"0\001"
+    _COMMITPROGRESSRESPONSE.fields_by_name["committed_version"]._options = None
+    _COMMITPROGRESSRESPONSE.fields_by_name[
+        "committed_version"
+    ]._serialized_options = b"0\001"
+    _GETPROGRESSRESPONSE.fields_by_name["committed_version"]._options = None
+    _GETPROGRESSRESPONSE.fields_by_name[
+        "committed_version"
+    ]._serialized_options = b"0\001"
     _globals["_TRANSACTIONSINSTORAGE"]._serialized_start = 94
     _globals["_TRANSACTIONSINSTORAGE"]._serialized_end = 226
     _globals["_GETTRANSACTIONSREQUEST"]._serialized_start = 229
-    _globals["_GETTRANSACTIONSREQUEST"]._serialized_end = 409
-    _globals["_TRANSACTIONSRESPONSE"]._serialized_start = 411
-    _globals["_TRANSACTIONSRESPONSE"]._serialized_end = 530
-    _globals["_RAWDATA"]._serialized_start = 532
-    _globals["_RAWDATA"]._serialized_end = 644
+    _globals["_GETTRANSACTIONSREQUEST"]._serialized_end = 451
+    _globals["_TRANSACTIONSRESPONSE"]._serialized_start = 453
+    _globals["_TRANSACTIONSRESPONSE"]._serialized_end = 572
+    _globals["_COMMITPROGRESSREQUEST"]._serialized_start = 574
+    _globals["_COMMITPROGRESSREQUEST"]._serialized_end = 677
+    _globals["_COMMITPROGRESSRESPONSE"]._serialized_start = 679
+    _globals["_COMMITPROGRESSRESPONSE"]._serialized_end = 761
+    _globals["_GETPROGRESSREQUEST"]._serialized_start = 763
+    _globals["_GETPROGRESSREQUEST"]._serialized_end = 825
+    _globals["_GETPROGRESSRESPONSE"]._serialized_start = 827
+    _globals["_GETPROGRESSRESPONSE"]._serialized_end = 906
+    _globals["_RAWDATA"]._serialized_start = 909
+    _globals["_RAWDATA"]._serialized_end = 1214
 # @@protoc_insertion_point(module_scope)
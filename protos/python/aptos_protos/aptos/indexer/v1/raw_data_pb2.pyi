@@ -28,18 +28,21 @@ class TransactionsInStorage(_message.Message):
     ) -> None: ...
 
 class GetTransactionsRequest(_message.Message):
-    __slots__ = ["starting_version", "transactions_count", "batch_size"]
+    __slots__ = ["starting_version", "transactions_count", "batch_size", "consumer_id"]
     STARTING_VERSION_FIELD_NUMBER: _ClassVar[int]
     TRANSACTIONS_COUNT_FIELD_NUMBER: _ClassVar[int]
     BATCH_SIZE_FIELD_NUMBER: _ClassVar[int]
+    CONSUMER_ID_FIELD_NUMBER: _ClassVar[int]
     starting_version: int
     transactions_count: int
     batch_size: int
+    consumer_id: str
     def __init__(
         self,
         starting_version: _Optional[int] = ...,
         transactions_count: _Optional[int] = ...,
         batch_size: _Optional[int] = ...,
+        consumer_id: _Optional[str] = ...,
     ) -> None: ...
 
 class TransactionsResponse(_message.Message):
@@ -57,3 +60,31 @@ class TransactionsResponse(_message.Message):
         ] = ...,
         chain_id: _Optional[int] = ...,
     ) -> None: ...
+
+class CommitProgressRequest(_message.Message):
+    __slots__ = ["consumer_id", "version"]
+    CONSUMER_ID_FIELD_NUMBER: _ClassVar[int]
+    VERSION_FIELD_NUMBER: _ClassVar[int]
+    consumer_id: str
+    version: int
+    def __init__(
+        self, consumer_id: _Optional[str] = ..., version: _Optional[int] = ...
+    ) -> None: ...
+
+class CommitProgressResponse(_message.Message):
+    __slots__ = ["committed_version"]
+    COMMITTED_VERSION_FIELD_NUMBER: _ClassVar[int]
+    committed_version: int
+    def __init__(self, committed_version: _Optional[int] = ...) -> None: ...
+
+class GetProgressRequest(_message.Message):
+    __slots__ = ["consumer_id"]
+    CONSUMER_ID_FIELD_NUMBER: _ClassVar[int]
+    consumer_id: str
+    def __init__(self, consumer_id: _Optional[str] = ...) -> None: ...
+
+class GetProgressResponse(_message.Message):
+    __slots__ = ["committed_version"]
+    COMMITTED_VERSION_FIELD_NUMBER: _ClassVar[int]
+    committed_version: int
+    def __init__(self, committed_version: _Optional[int] = ...) -> None: ...
@@ -18,6 +18,16 @@ class RawDataStub(object):
             request_serializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.GetTransactionsRequest.SerializeToString,
             response_deserializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.TransactionsResponse.FromString,
         )
+        self.CommitProgress = channel.unary_unary(
+            "/aptos.indexer.v1.RawData/CommitProgress",
+            request_serializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.CommitProgressRequest.SerializeToString,
+            response_deserializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.CommitProgressResponse.FromString,
+        )
+        self.GetProgress = channel.unary_unary(
+            "/aptos.indexer.v1.RawData/GetProgress",
+            request_serializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.GetProgressRequest.SerializeToString,
+            response_deserializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.GetProgressResponse.FromString,
+        )
 
 
 class RawDataServicer(object):
@@ -29,6 +39,18 @@ class RawDataServicer(object):
         context.set_details("Method not implemented!")
         raise NotImplementedError("Method not implemented!")
 
+    def CommitProgress(self, request, context):
+        """Commit the last processed version for a consumer. Committed versions must not move backwards."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
+    def GetProgress(self, request, context):
+        """Get the last committed version for a consumer."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
 
 def add_RawDataServicer_to_server(servicer, server):
     rpc_method_handlers = {
@@ -37,6 +59,16 @@ def add_RawDataServicer_to_server(servicer, server):
             request_deserializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.GetTransactionsRequest.FromString,
             response_serializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.TransactionsResponse.SerializeToString,
         ),
+        "CommitProgress": grpc.unary_unary_rpc_method_handler(
+            servicer.CommitProgress,
+            request_deserializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.CommitProgressRequest.FromString,
+            response_serializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.CommitProgressResponse.SerializeToString,
+        ),
+        "GetProgress": grpc.unary_unary_rpc_method_handler(
+            servicer.GetProgress,
+            request_deserializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.GetProgressRequest.FromString,
+            response_serializer=aptos_dot_indexer_dot_v1_dot_raw__data__pb2.GetProgressResponse.SerializeToString,
+        ),
     }
     generic_handler = grpc.method_handlers_generic_handler(
         "aptos.indexer.v1.RawData", rpc_method_handlers
@@ -76,3 +108,61 @@ class RawData(object):
             timeout,
             metadata,
         )
+
+    @staticmethod
+    def CommitProgress(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/aptos.indexer.v1.RawData/CommitProgress",
+            aptos_dot_indexer_dot_v1_dot_raw__data__pb2.CommitProgressRequest.SerializeToString,
+            aptos_dot_indexer_dot_v1_dot_raw__data__pb2.CommitProgressResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+        )
+
+    @staticmethod
+    def GetProgress(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/aptos.indexer.v1.RawData/GetProgress",
+            aptos_dot_indexer_dot_v1_dot_raw__data__pb2.GetProgressRequest.SerializeToString,
+            aptos_dot_indexer_dot_v1_dot_raw__data__pb2.GetProgressResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+        )
@@ -2,6 +2,397 @@
 // SPDX-License-Identifier: Apache-2.0
 
 // @generated
+impl serde::Serialize for CommitProgressRequest {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.consumer_id.is_some() {
+            len += 1;
+        }
+        if self.version.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.CommitProgressRequest", len)?;
+        if let Some(v) = self.consumer_id.as_ref() {
+            struct_ser.serialize_field("consumerId", v)?;
+        }
+        if let Some(v) = self.version.as_ref() {
+            struct_ser.serialize_field("version", ToString::to_string(&v).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for CommitProgressRequest {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "consumer_id",
+            "consumerId",
+            "version",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            ConsumerId,
+            Version,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "consumerId" | "consumer_id" => Ok(GeneratedField::ConsumerId),
+                            "version" => Ok(GeneratedField::Version),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = CommitProgressRequest;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.indexer.v1.CommitProgressRequest")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<CommitProgressRequest, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut consumer_id__ = None;
+                let mut version__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::ConsumerId => {
+                            if consumer_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("consumerId"));
+                            }
+                            consumer_id__ = map.next_value()?;
+                        }
+                        GeneratedField::Version => {
+                            if version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("version"));
+                            }
+                            version__ =
+                                map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(CommitProgressRequest {
+                    consumer_id: consumer_id__,
+                    version: version__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.indexer.v1.CommitProgressRequest", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for CommitProgressResponse {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.committed_version.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.CommitProgressResponse", len)?;
+        if let Some(v) = self.committed_version.as_ref() {
+            struct_ser.serialize_field("committedVersion", ToString::to_string(&v).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for CommitProgressResponse {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "committed_version",
+            "committedVersion",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            CommittedVersion,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "committedVersion" | "committed_version" => Ok(GeneratedField::CommittedVersion),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = CommitProgressResponse;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.indexer.v1.CommitProgressResponse")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<CommitProgressResponse, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut committed_version__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::CommittedVersion => {
+                            if committed_version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("committedVersion"));
+                            }
+                            committed_version__ =
+                                map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(CommitProgressResponse {
+                    committed_version: committed_version__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.indexer.v1.CommitProgressResponse", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for GetProgressRequest {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.consumer_id.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.GetProgressRequest", len)?;
+        if let Some(v) = self.consumer_id.as_ref() {
+            struct_ser.serialize_field("consumerId", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for GetProgressRequest {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "consumer_id",
+            "consumerId",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            ConsumerId,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "consumerId" | "consumer_id" => Ok(GeneratedField::ConsumerId),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = GetProgressRequest;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.indexer.v1.GetProgressRequest")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<GetProgressRequest, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut consumer_id__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::ConsumerId => {
+                            if consumer_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("consumerId"));
+                            }
+                            consumer_id__ = map.next_value()?;
+                        }
+                    }
+                }
+                Ok(GetProgressRequest {
+                    consumer_id: consumer_id__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.indexer.v1.GetProgressRequest", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for GetProgressResponse {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.committed_version.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.GetProgressResponse", len)?;
+        if let Some(v) = self.committed_version.as_ref() {
+            struct_ser.serialize_field("committedVersion", ToString::to_string(&v).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for GetProgressResponse {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "committed_version",
+            "committedVersion",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            CommittedVersion,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "committedVersion" | "committed_version" => Ok(GeneratedField::CommittedVersion),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = GetProgressResponse;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.indexer.v1.GetProgressResponse")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<GetProgressResponse, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut committed_version__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::CommittedVersion => {
+                            if committed_version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("committedVersion"));
+                            }
+                            committed_version__ =
+                                map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(GetProgressResponse {
+                    committed_version: committed_version__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.indexer.v1.GetProgressResponse", FIELDS, GeneratedVisitor)
+    }
+}
 impl serde::Serialize for GetTransactionsRequest {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -19,6 +410,9 @@ impl serde::Serialize for GetTransactionsRequest {
         if self.batch_size.is_some() {
             len += 1;
         }
+        if self.consumer_id.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("aptos.indexer.v1.GetTransactionsRequest", len)?;
         if let Some(v) = self.starting_version.as_ref() {
             struct_ser.serialize_field("startingVersion", ToString::to_string(&v).as_str())?;
@@ -29,6 +423,9 @@ impl serde::Serialize for GetTransactionsRequest {
         if let Some(v) = self.batch_size.as_ref() {
             struct_ser.serialize_field("batchSize", ToString::to_string(&v).as_str())?;
         }
+        if let Some(v) = self.consumer_id.as_ref() {
+            struct_ser.serialize_field("consumerId", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -45,6 +442,8 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
             "transactionsCount",
             "batch_size",
             "batchSize",
+            "consumer_id",
+            "consumerId",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -52,6 +451,7 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
             StartingVersion,
             TransactionsCount,
             BatchSize,
+            ConsumerId,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -76,6 +476,7 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
                             "startingVersion" | "starting_version" => Ok(GeneratedField::StartingVersion),
                             "transactionsCount" | "transactions_count" => Ok(GeneratedField::TransactionsCount),
                             "batchSize" | "batch_size" => Ok(GeneratedField::BatchSize),
+                            "consumerId" | "consumer_id" => Ok(GeneratedField::ConsumerId),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -98,6 +499,7 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
                 let mut starting_version__ = None;
                 let mut transactions_count__ = None;
                 let mut batch_size__ = None;
+                let mut consumer_id__ = None;
                 while let Some(k) = map.next_key()? {
                     match k {
                         GeneratedField::StartingVersion => {
@@ -124,12 +526,19 @@ impl<'de> serde::Deserialize<'de> for GetTransactionsRequest {
                                 map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
                             ;
                         }
+                        GeneratedField::ConsumerId => {
+                            if consumer_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("consumerId"));
+                            }
+                            consumer_id__ = map.next_value()?;
+                        }
                     }
                 }
                 Ok(GetTransactionsRequest {
                     starting_version: starting_version__,
                     transactions_count: transactions_count__,
                     batch_size: batch_size__,
+                    consumer_id: consumer_id__,
                 })
             }
         }
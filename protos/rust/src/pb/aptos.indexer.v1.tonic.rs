@@ -115,6 +115,60 @@ pub mod raw_data_client {
                 .insert(GrpcMethod::new("aptos.indexer.v1.RawData", "GetTransactions"));
             self.inner.server_streaming(req, path, codec).await
         }
+        /** Commit the last processed version for a consumer. Committed versions must not move backwards.
+*/
+        pub async fn commit_progress(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CommitProgressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CommitProgressResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/aptos.indexer.v1.RawData/CommitProgress",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("aptos.indexer.v1.RawData", "CommitProgress"));
+            self.inner.unary(req, path, codec).await
+        }
+        /** Get the last committed version for a consumer.
+*/
+        pub async fn get_progress(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetProgressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetProgressResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/aptos.indexer.v1.RawData/GetProgress",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("aptos.indexer.v1.RawData", "GetProgress"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -139,6 +193,24 @@ pub mod raw_data_server {
             tonic::Response<Self::GetTransactionsStream>,
             tonic::Status,
         >;
+        /** Commit the last processed version for a consumer. Committed versions must not move backwards.
+*/
+        async fn commit_progress(
+            &self,
+            request: tonic::Request<super::CommitProgressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CommitProgressResponse>,
+            tonic::Status,
+        >;
+        /** Get the last committed version for a consumer.
+*/
+        async fn get_progress(
+            &self,
+            request: tonic::Request<super::GetProgressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetProgressResponse>,
+            tonic::Status,
+        >;
     }
     ///
     #[derive(Debug)]
@@ -268,6 +340,98 @@ pub mod raw_data_server {
                     };
                     Box::pin(fut)
                 }
+                "/aptos.indexer.v1.RawData/CommitProgress" => {
+                    #[allow(non_camel_case_types)]
+                    struct CommitProgressSvc<T: RawData>(pub Arc<T>);
+                    impl<
+                        T: RawData,
+                    > tonic::server::UnaryService<super::CommitProgressRequest>
+                    for CommitProgressSvc<T> {
+                        type Response = super::CommitProgressResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CommitProgressRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).commit_progress(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CommitProgressSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/aptos.indexer.v1.RawData/GetProgress" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetProgressSvc<T: RawData>(pub Arc<T>);
+                    impl<
+                        T: RawData,
+                    > tonic::server::UnaryService<super::GetProgressRequest>
+                    for GetProgressSvc<T> {
+                        type Response = super::GetProgressResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetProgressRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).get_progress(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetProgressSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
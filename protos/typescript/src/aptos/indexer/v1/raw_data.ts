@@ -7,7 +7,14 @@ import {
   makeGenericClientConstructor,
   Metadata,
 } from "@grpc/grpc-js";
-import type { CallOptions, ClientOptions, UntypedServiceImplementation } from "@grpc/grpc-js";
+import type {
+  CallOptions,
+  ClientOptions,
+  ClientUnaryCall,
+  handleUnaryCall,
+  ServiceError,
+  UntypedServiceImplementation,
+} from "@grpc/grpc-js";
 import Long from "long";
 import _m0 from "protobufjs/minimal";
 import { Transaction } from "../../transaction/v1/transaction";
@@ -38,7 +45,15 @@ export interface GetTransactionsRequest {
    * Optional; number of transactions in each `TransactionsResponse` for current stream.
    * If not present, default to 1000. If larger than 1000, request will be rejected.
    */
-  batchSize?: bigint | undefined;
+  batchSize?:
+    | bigint
+    | undefined;
+  /**
+   * Optional; name of a consumer whose committed progress is persisted by the server.
+   * If present and `starting_version` is not, the stream resumes after the last committed version.
+   * If both are present, `starting_version` must not skip past the last committed version.
+   */
+  consumerId?: string | undefined;
 }
 
 /** TransactionsResponse is a batch of transactions. */
@@ -51,6 +66,33 @@ export interface TransactionsResponse {
   chainId?: bigint | undefined;
 }
 
+export interface CommitProgressRequest {
+  /** Required; name of the consumer. */
+  consumerId?:
+    | string
+    | undefined;
+  /** Required; last version processed by the consumer. */
+  version?: bigint | undefined;
+}
+
+export interface CommitProgressResponse {
+  /** Required; last version committed for the consumer. */
+  committedVersion?: bigint | undefined;
+}
+
+export interface GetProgressRequest {
+  /** Required; name of the consumer. */
+  consumerId?: string | undefined;
+}
+
+export interface GetProgressResponse {
+  /**
+   * Optional; last version committed for the consumer.
+   * If not present, the consumer has not committed any progress.
+   */
+  committedVersion?: bigint | undefined;
+}
+
 function createBaseTransactionsInStorage(): TransactionsInStorage {
   return { transactions: [], startingVersion: undefined };
 }
@@ -167,7 +209,7 @@ export const TransactionsInStorage = {
 };
 
 function createBaseGetTransactionsRequest(): GetTransactionsRequest {
-  return { startingVersion: undefined, transactionsCount: undefined, batchSize: undefined };
+  return { startingVersion: undefined, transactionsCount: undefined, batchSize: undefined, consumerId: undefined };
 }
 
 export const GetTransactionsRequest = {
@@ -190,6 +232,9 @@ export const GetTransactionsRequest = {
       }
       writer.uint32(24).uint64(message.batchSize.toString());
     }
+    if (message.consumerId !== undefined) {
+      writer.uint32(34).string(message.consumerId);
+    }
     return writer;
   },
 
@@ -221,6 +266,13 @@ export const GetTransactionsRequest = {
 
           message.batchSize = longToBigint(reader.uint64() as Long);
           continue;
+        case 4:
+          if (tag !== 34) {
+            break;
+          }
+
+          message.consumerId = reader.string();
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -269,6 +321,7 @@ export const GetTransactionsRequest = {
       startingVersion: isSet(object.startingVersion) ? BigInt(object.startingVersion) : undefined,
       transactionsCount: isSet(object.transactionsCount) ? BigInt(object.transactionsCount) : undefined,
       batchSize: isSet(object.batchSize) ? BigInt(object.batchSize) : undefined,
+      consumerId: isSet(object.consumerId) ? globalThis.String(object.consumerId) : undefined,
     };
   },
 
@@ -283,6 +336,9 @@ export const GetTransactionsRequest = {
     if (message.batchSize !== undefined) {
       obj.batchSize = message.batchSize.toString();
     }
+    if (message.consumerId !== undefined) {
+      obj.consumerId = message.consumerId;
+    }
     return obj;
   },
 
@@ -294,6 +350,7 @@ export const GetTransactionsRequest = {
     message.startingVersion = object.startingVersion ?? undefined;
     message.transactionsCount = object.transactionsCount ?? undefined;
     message.batchSize = object.batchSize ?? undefined;
+    message.consumerId = object.consumerId ?? undefined;
     return message;
   },
 };
@@ -413,6 +470,402 @@ export const TransactionsResponse = {
   },
 };
 
+function createBaseCommitProgressRequest(): CommitProgressRequest {
+  return { consumerId: undefined, version: undefined };
+}
+
+export const CommitProgressRequest = {
+  encode(message: CommitProgressRequest, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.consumerId !== undefined) {
+      writer.uint32(10).string(message.consumerId);
+    }
+    if (message.version !== undefined) {
+      if (BigInt.asUintN(64, message.version) !== message.version) {
+        throw new globalThis.Error("value provided for field message.version of type uint64 too large");
+      }
+      writer.uint32(16).uint64(message.version.toString());
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): CommitProgressRequest {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseCommitProgressRequest();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 10) {
+            break;
+          }
+
+          message.consumerId = reader.string();
+          continue;
+        case 2:
+          if (tag !== 16) {
+            break;
+          }
+
+          message.version = longToBigint(reader.uint64() as Long);
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  // encodeTransform encodes a source of message objects.
+  // Transform<CommitProgressRequest, Uint8Array>
+  async *encodeTransform(
+    source:
+      | AsyncIterable<CommitProgressRequest | CommitProgressRequest[]>
+      | Iterable<CommitProgressRequest | CommitProgressRequest[]>,
+  ): AsyncIterable<Uint8Array> {
+    for await (const pkt of source) {
+      if (globalThis.Array.isArray(pkt)) {
+        for (const p of (pkt as any)) {
+          yield* [CommitProgressRequest.encode(p).finish()];
+        }
+      } else {
+        yield* [CommitProgressRequest.encode(pkt as any).finish()];
+      }
+    }
+  },
+
+  // decodeTransform decodes a source of encoded messages.
+  // Transform<Uint8Array, CommitProgressRequest>
+  async *decodeTransform(
+    source: AsyncIterable<Uint8Array | Uint8Array[]> | Iterable<Uint8Array | Uint8Array[]>,
+  ): AsyncIterable<CommitProgressRequest> {
+    for await (const pkt of source) {
+      if (globalThis.Array.isArray(pkt)) {
+        for (const p of (pkt as any)) {
+          yield* [CommitProgressRequest.decode(p)];
+        }
+      } else {
+        yield* [CommitProgressRequest.decode(pkt as any)];
+      }
+    }
+  },
+
+  fromJSON(object: any): CommitProgressRequest {
+    return {
+      consumerId: isSet(object.consumerId) ? globalThis.String(object.consumerId) : undefined,
+      version: isSet(object.version) ? BigInt(object.version) : undefined,
+    };
+  },
+
+  toJSON(message: CommitProgressRequest): unknown {
+    const obj: any = {};
+    if (message.consumerId !== undefined) {
+      obj.consumerId = message.consumerId;
+    }
+    if (message.version !== undefined) {
+      obj.version = message.version.toString();
+    }
+    return obj;
+  },
+
+  create(base?: DeepPartial<CommitProgressRequest>): CommitProgressRequest {
+    return CommitProgressRequest.fromPartial(base ?? {});
+  },
+  fromPartial(object: DeepPartial<CommitProgressRequest>): CommitProgressRequest {
+    const message = createBaseCommitProgressRequest();
+    message.consumerId = object.consumerId ?? undefined;
+    message.version = object.version ?? undefined;
+    return message;
+  },
+};
+
+function createBaseCommitProgressResponse(): CommitProgressResponse {
+  return { committedVersion: undefined };
+}
+
+export const CommitProgressResponse = {
+  encode(message: CommitProgressResponse, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.committedVersion !== undefined) {
+      if (BigInt.asUintN(64, message.committedVersion) !== message.committedVersion) {
+        throw new globalThis.Error("value provided for field message.committedVersion of type uint64 too large");
+      }
+      writer.uint32(8).uint64(message.committedVersion.toString());
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): CommitProgressResponse {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseCommitProgressResponse();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 8) {
+            break;
+          }
+
+          message.committedVersion = longToBigint(reader.uint64() as Long);
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  // encodeTransform encodes a source of message objects.
+  // Transform<CommitProgressResponse, Uint8Array>
+  async *encodeTransform(
+    source:
+      | AsyncIterable<CommitProgressResponse | CommitProgressResponse[]>
+      | Iterable<CommitProgressResponse | CommitProgressResponse[]>,
+  ): AsyncIterable<Uint8Array> {
+    for await (const pkt of source) {
+      if (globalThis.Array.isArray(pkt)) {
+        for (const p of (pkt as any)) {
+          yield* [CommitProgressResponse.encode(p).finish()];
+        }
+      } else {
+        yield* [CommitProgressResponse.encode(pkt as any).finish()];
+      }
+    }
+  },
+
+  // decodeTransform decodes a source of encoded messages.
+  // Transform<Uint8Array, CommitProgressResponse>
+  async *decodeTransform(
+    source: AsyncIterable<Uint8Array | Uint8Array[]> | Iterable<Uint8Array | Uint8Array[]>,
+  ): AsyncIterable<CommitProgressResponse> {
+    for await (const pkt of source) {
+      if (globalThis.Array.isArray(pkt)) {
+        for (const p of (pkt as any)) {
+          yield* [CommitProgressResponse.decode(p)];
+        }
+      } else {
+        yield* [CommitProgressResponse.decode(pkt as any)];
+      }
+    }
+  },
+
+  fromJSON(object: any): CommitProgressResponse {
+    return {
+      committedVersion: isSet(object.committedVersion) ? BigInt(object.committedVersion) : undefined,
+    };
+  },
+
+  toJSON(message: CommitProgressResponse): unknown {
+    const obj: any = {};
+    if (message.committedVersion !== undefined) {
+      obj.committedVersion = message.committedVersion.toString();
+    }
+    return obj;
+  },
+
+  create(base?: DeepPartial<CommitProgressResponse>): CommitProgressResponse {
+    return CommitProgressResponse.fromPartial(base ?? {});
+  },
+  fromPartial(object: DeepPartial<CommitProgressResponse>): CommitProgressResponse {
+    const message = createBaseCommitProgressResponse();
+    message.committedVersion = object.committedVersion ?? undefined;
+    return message;
+  },
+};
+
+function createBaseGetProgressRequest(): GetProgressRequest {
+  return { consumerId: undefined };
+}
+
+export const GetProgressRequest = {
+  encode(message: GetProgressRequest, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.consumerId !== undefined) {
+      writer.uint32(10).string(message.consumerId);
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): GetProgressRequest {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseGetProgressRequest();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 10) {
+            break;
+          }
+
+          message.consumerId = reader.string();
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  // encodeTransform encodes a source of message objects.
+  // Transform<GetProgressRequest, Uint8Array>
+  async *encodeTransform(
+    source:
+      | AsyncIterable<GetProgressRequest | GetProgressRequest[]>
+      | Iterable<GetProgressRequest | GetProgressRequest[]>,
+  ): AsyncIterable<Uint8Array> {
+    for await (const pkt of source) {
+      if (globalThis.Array.isArray(pkt)) {
+        for (const p of (pkt as any)) {
+          yield* [GetProgressRequest.encode(p).finish()];
+        }
+      } else {
+        yield* [GetProgressRequest.encode(pkt as any).finish()];
+      }
+    }
+  },
+
+  // decodeTransform decodes a source of encoded messages.
+  // Transform<Uint8Array, GetProgressRequest>
+  async *decodeTransform(
+    source: AsyncIterable<Uint8Array | Uint8Array[]> | Iterable<Uint8Array | Uint8Array[]>,
+  ): AsyncIterable<GetProgressRequest> {
+    for await (const pkt of source) {
+      if (globalThis.Array.isArray(pkt)) {
+        for (const p of (pkt as any)) {
+          yield* [GetProgressRequest.decode(p)];
+        }
+      } else {
+        yield* [GetProgressRequest.decode(pkt as any)];
+      }
+    }
+  },
+
+  fromJSON(object: any): GetProgressRequest {
+    return {
+      consumerId: isSet(object.consumerId) ? globalThis.String(object.consumerId) : undefined,
+    };
+  },
+
+  toJSON(message: GetProgressRequest): unknown {
+    const obj: any = {};
+    if (message.consumerId !== undefined) {
+      obj.consumerId = message.consumerId;
+    }
+    return obj;
+  },
+
+  create(base?: DeepPartial<GetProgressRequest>): GetProgressRequest {
+    return GetProgressRequest.fromPartial(base ?? {});
+  },
+  fromPartial(object: DeepPartial<GetProgressRequest>): GetProgressRequest {
+    const message = createBaseGetProgressRequest();
+    message.consumerId = object.consumerId ?? undefined;
+    return message;
+  },
+};
+
+function createBaseGetProgressResponse(): GetProgressResponse {
+  return { committedVersion: undefined };
+}
+
+export const GetProgressResponse = {
+  encode(message: GetProgressResponse, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.committedVersion !== undefined) {
+      if (BigInt.asUintN(64, message.committedVersion) !== message.committedVersion) {
+        throw new globalThis.Error("value provided for field message.committedVersion of type uint64 too large");
+      }
+      writer.uint32(8).uint64(message.committedVersion.toString());
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): GetProgressResponse {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseGetProgressResponse();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 8) {
+            break;
+          }
+
+          message.committedVersion = longToBigint(reader.uint64() as Long);
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  // encodeTransform encodes a source of message objects.
+  // Transform<GetProgressResponse, Uint8Array>
+  async *encodeTransform(
+    source:
+      | AsyncIterable<GetProgressResponse | GetProgressResponse[]>
+      | Iterable<GetProgressResponse | GetProgressResponse[]>,
+  ): AsyncIterable<Uint8Array> {
+    for await (const pkt of source) {
+      if (globalThis.Array.isArray(pkt)) {
+        for (const p of (pkt as any)) {
+          yield* [GetProgressResponse.encode(p).finish()];
+        }
+      } else {
+        yield* [GetProgressResponse.encode(pkt as any).finish()];
+      }
+    }
+  },
+
+  // decodeTransform decodes a source of encoded messages.
+  // Transform<Uint8Array, GetProgressResponse>
+  async *decodeTransform(
+    source: AsyncIterable<Uint8Array | Uint8Array[]> | Iterable<Uint8Array | Uint8Array[]>,
+  ): AsyncIterable<GetProgressResponse> {
+    for await (const pkt of source) {
+      if (globalThis.Array.isArray(pkt)) {
+        for (const p of (pkt as any)) {
+          yield* [GetProgressResponse.decode(p)];
+        }
+      } else {
+        yield* [GetProgressResponse.decode(pkt as any)];
+      }
+    }
+  },
+
+  fromJSON(object: any): GetProgressResponse {
+    return {
+      committedVersion: isSet(object.committedVersion) ? BigInt(object.committedVersion) : undefined,
+    };
+  },
+
+  toJSON(message: GetProgressResponse): unknown {
+    const obj: any = {};
+    if (message.committedVersion !== undefined) {
+      obj.committedVersion = message.committedVersion.toString();
+    }
+    return obj;
+  },
+
+  create(base?: DeepPartial<GetProgressResponse>): GetProgressResponse {
+    return GetProgressResponse.fromPartial(base ?? {});
+  },
+  fromPartial(object: DeepPartial<GetProgressResponse>): GetProgressResponse {
+    const message = createBaseGetProgressResponse();
+    message.committedVersion = object.committedVersion ?? undefined;
+    return message;
+  },
+};
+
 export type RawDataService = typeof RawDataService;
 export const RawDataService = {
   /** Get transactions batch without any filtering from starting version and end if transaction count is present. */
@@ -425,11 +878,35 @@ export const RawDataService = {
     responseSerialize: (value: TransactionsResponse) => Buffer.from(TransactionsResponse.encode(value).finish()),
     responseDeserialize: (value: Buffer) => TransactionsResponse.decode(value),
   },
+  /** Commit the last processed version for a consumer. Committed versions must not move backwards. */
+  commitProgress: {
+    path: "/aptos.indexer.v1.RawData/CommitProgress",
+    requestStream: false,
+    responseStream: false,
+    requestSerialize: (value: CommitProgressRequest) => Buffer.from(CommitProgressRequest.encode(value).finish()),
+    requestDeserialize: (value: Buffer) => CommitProgressRequest.decode(value),
+    responseSerialize: (value: CommitProgressResponse) => Buffer.from(CommitProgressResponse.encode(value).finish()),
+    responseDeserialize: (value: Buffer) => CommitProgressResponse.decode(value),
+  },
+  /** Get the last committed version for a consumer. */
+  getProgress: {
+    path: "/aptos.indexer.v1.RawData/GetProgress",
+    requestStream: false,
+    responseStream: false,
+    requestSerialize: (value: GetProgressRequest) => Buffer.from(GetProgressRequest.encode(value).finish()),
+    requestDeserialize: (value: Buffer) => GetProgressRequest.decode(value),
+    responseSerialize: (value: GetProgressResponse) => Buffer.from(GetProgressResponse.encode(value).finish()),
+    responseDeserialize: (value: Buffer) => GetProgressResponse.decode(value),
+  },
 } as const;
 
 export interface RawDataServer extends UntypedServiceImplementation {
   /** Get transactions batch without any filtering from starting version and end if transaction count is present. */
   getTransactions: handleServerStreamingCall<GetTransactionsRequest, TransactionsResponse>;
+  /** Commit the last processed version for a consumer. Committed versions must not move backwards. */
+  commitProgress: handleUnaryCall<CommitProgressRequest, CommitProgressResponse>;
+  /** Get the last committed version for a consumer. */
+  getProgress: handleUnaryCall<GetProgressRequest, GetProgressResponse>;
 }
 
 export interface RawDataClient extends Client {
@@ -443,6 +920,38 @@ export interface RawDataClient extends Client {
     metadata?: Metadata,
     options?: Partial<CallOptions>,
   ): ClientReadableStream<TransactionsResponse>;
+  /** Commit the last processed version for a consumer. Committed versions must not move backwards. */
+  commitProgress(
+    request: CommitProgressRequest,
+    callback: (error: ServiceError | null, response: CommitProgressResponse) => void,
+  ): ClientUnaryCall;
+  commitProgress(
+    request: CommitProgressRequest,
+    metadata: Metadata,
+    callback: (error: ServiceError | null, response: CommitProgressResponse) => void,
+  ): ClientUnaryCall;
+  commitProgress(
+    request: CommitProgressRequest,
+    metadata: Metadata,
+    options: Partial<CallOptions>,
+    callback: (error: ServiceError | null, response: CommitProgressResponse) => void,
+  ): ClientUnaryCall;
+  /** Get the last committed version for a consumer. */
+  getProgress(
+    request: GetProgressRequest,
+    callback: (error: ServiceError | null, response: GetProgressResponse) => void,
+  ): ClientUnaryCall;
+  getProgress(
+    request: GetProgressRequest,
+    metadata: Metadata,
+    callback: (error: ServiceError | null, response: GetProgressResponse) => void,
+  ): ClientUnaryCall;
+  getProgress(
+    request: GetProgressRequest,
+    metadata: Metadata,
+    options: Partial<CallOptions>,
+    callback: (error: ServiceError | null, response: GetProgressResponse) => void,
+  ): ClientUnaryCall;
 }
 
 export const RawDataClient = makeGenericClientConstructor(RawDataService, "aptos.indexer.v1.RawData") as unknown as {
@@ -110,7 +110,7 @@ def get_algebra_lines(gas_per_ns):
     quantity_types['ark_bn254_multi_pairing_per_pair'] = 'InternalGasPerArg'
 
     gas_param_entries = { k:(int(gas_per_ns*v), quantity_types[k]) for k,v in nanoseconds.items()}
-    lines = [f'        [algebra_{k}: {unt}, {{ {TARGET_GAS_VERSION}.. => "algebra.{k}" }}, {prettify_number(qty)}],' for k,(qty,unt) in sorted(gas_param_entries.items())]
+    lines = [f'            [algebra_{k}: {unt}, {{ {TARGET_GAS_VERSION}.. => "algebra.{k}" }}, {prettify_number(qty)}],' for k,(qty,unt) in sorted(gas_param_entries.items())]
     return lines
 
 def main(gas_per_ns):
@@ -119,7 +119,7 @@ def main(gas_per_ns):
     striped_lines = [line.strip() for line in lines]
     line_id_begin = striped_lines.index('// BN254 algebra gas parameters begin.')
     line_id_end = striped_lines.index('// BN254 algebra gas parameters end.')
-    generator_note_line = f'        // Generated at time {time()} by `scripts/algebra-gas/update_bn254_algebra_gas_params.py` with gas_per_ns={gas_per_ns}.'
+    generator_note_line = f'            // Generated at time {time()} by `scripts/algebra-gas/update_bn254_algebra_gas_params.py` with gas_per_ns={gas_per_ns}.'
     new_lines = lines[:line_id_begin+1] + [generator_note_line] + get_algebra_lines(gas_per_ns) + lines[line_id_end:]
     path.write_text('\n'.join(new_lines))
 
@@ -4,6 +4,7 @@
 use crate::{
     error::Error,
     global_summary::GlobalDataSummary,
+    hedging::RequestHedger,
     interface::{
         AptosDataClientInterface, Response, ResponseCallback, ResponseContext, ResponseError,
         ResponseId, SubscriptionRequestMetadata,
@@ -45,7 +46,7 @@ use aptos_storage_service_types::{
     responses::{StorageServerSummary, StorageServiceResponse, TransactionOrOutputListWithProof},
     Epoch, StorageServiceMessage,
 };
-use aptos_time_service::TimeService;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
     epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
@@ -54,7 +55,7 @@ use aptos_types::{
 };
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
-use futures::{stream::FuturesUnordered, StreamExt};
+use futures::{future, stream::FuturesUnordered, StreamExt};
 use maplit::hashset;
 use std::{
     cmp::min,
@@ -64,7 +65,7 @@ use std::{
     sync::Arc,
     time::Duration,
 };
-use tokio::runtime::Handle;
+use tokio::{runtime::Handle, task::JoinHandle};
 
 // Useful constants
 const PEER_METRICS_FREQ_SECS: u64 = 5; // The frequency to update peer metrics and logs
@@ -103,6 +104,8 @@ pub struct AptosDataClient {
     global_summary_cache: Arc<ArcSwap<GlobalDataSummary>>,
     /// Used for generating the next request/response id.
     response_id_generator: Arc<U64IdGenerator>,
+    /// The request hedger (used to hedge slow requests)
+    request_hedger: Arc<RequestHedger>,
     /// Time service used for calculating peer lag
     time_service: TimeService,
 }
@@ -129,6 +132,9 @@ impl AptosDataClient {
             peer_states: Arc::new(PeerStates::new(data_client_config.clone())),
             global_summary_cache: Arc::new(ArcSwap::from(Arc::new(GlobalDataSummary::empty()))),
             response_id_generator: Arc::new(U64IdGenerator::new()),
+            request_hedger: Arc::new(RequestHedger::new(
+                data_client_config.data_request_hedging_config,
+            )),
             time_service: time_service.clone(),
         };
 
@@ -642,41 +648,81 @@ impl AptosDataClient {
         // Send the requests to the peers (and gather abort handles for the tasks)
         let mut sent_requests = FuturesUnordered::new();
         let mut abort_handles = vec![];
+        let mut requested_peers = HashSet::new();
         for peer in peers {
             // Send the request to the peer
-            let aptos_data_client = self.clone();
-            let request = request.clone();
-            let sent_request = tokio::spawn(async move {
-                aptos_data_client
-                    .send_request_to_peer_and_decode(peer, request, request_timeout_ms)
-                    .await
-            });
+            let sent_request =
+                self.spawn_request_to_peer(peer, &request, request_timeout_ms, false);
             let abort_handle = sent_request.abort_handle();
 
             // Gather the tasks and abort handles
             sent_requests.push(sent_request);
             abort_handles.push(abort_handle);
+            requested_peers.insert(peer);
+        }
+
+        // Start the hedging timer (if the request can be hedged)
+        let hedging_delay = self.request_hedger.get_hedging_delay(&request);
+        if self.request_hedger.is_hedgeable(&request) {
+            self.request_hedger.refill_hedging_budget();
         }
+        let time_service = self.time_service.clone();
+        let hedging_timer = async move {
+            match hedging_delay {
+                Some(hedging_delay) => time_service.sleep(hedging_delay).await,
+                None => future::pending::<()>().await,
+            }
+        };
+        tokio::pin!(hedging_timer);
+        let mut hedging_timer_expired = false;
 
-        // Wait for the first successful response and abort all other tasks.
+        // Wait for the first successful response and abort all other tasks. If the
+        // hedging timer expires first, the request is also sent to another peer.
         // If all requests fail, gather the errors and return them.
-        let num_sent_requests = sent_requests.len();
+        let mut num_sent_requests = sent_requests.len();
         let mut sent_request_errors = vec![];
-        for _ in 0..num_sent_requests {
-            if let Ok(response_result) = sent_requests.select_next_some().await {
-                match response_result {
-                    Ok(response) => {
-                        // We received a valid response. Abort all pending tasks.
-                        for abort_handle in abort_handles {
-                            abort_handle.abort();
+        loop {
+            tokio::select! {
+                sent_request = sent_requests.next() => {
+                    let Some(sent_request) = sent_request else {
+                        break; // All requests have completed
+                    };
+                    if let Ok((is_hedged_request, response_result)) = sent_request {
+                        match response_result {
+                            Ok(response) => {
+                                // We received a valid response. Abort all pending tasks.
+                                for abort_handle in abort_handles {
+                                    abort_handle.abort();
+                                }
+                                if is_hedged_request {
+                                    metrics::increment_hedged_request_counter(
+                                        &request.get_label(),
+                                        metrics::HEDGE_WON_LABEL,
+                                    );
+                                }
+                                return Ok(response); // Return the response
+                            },
+                            Err(error) => {
+                                // Gather the error and continue waiting for a response
+                                sent_request_errors.push(error)
+                            },
                         }
-                        return Ok(response); // Return the response
-                    },
-                    Err(error) => {
-                        // Gather the error and continue waiting for a response
-                        sent_request_errors.push(error)
-                    },
-                }
+                    }
+                },
+                _ = &mut hedging_timer, if !hedging_timer_expired => {
+                    // Hedge the request by sending it to another peer (if possible)
+                    hedging_timer_expired = true;
+                    let hedging_peer =
+                        self.choose_peer_for_hedged_request(&request, &requested_peers);
+                    if let Some(peer) = hedging_peer {
+                        let sent_request =
+                            self.spawn_request_to_peer(peer, &request, request_timeout_ms, true);
+                        abort_handles.push(sent_request.abort_handle());
+                        sent_requests.push(sent_request);
+                        requested_peers.insert(peer);
+                        num_sent_requests += 1;
+                    }
+                },
             }
         }
 
@@ -687,6 +733,72 @@ impl AptosDataClient {
         )))
     }
 
+    /// Spawns a task that sends the request to the given peer and decodes the
+    /// response. The task returns the response along with whether the request
+    /// was hedged (i.e., sent after the original request was slow to respond).
+    fn spawn_request_to_peer<T, E>(
+        &self,
+        peer: PeerNetworkId,
+        request: &StorageServiceRequest,
+        request_timeout_ms: u64,
+        is_hedged_request: bool,
+    ) -> JoinHandle<(bool, crate::error::Result<Response<T>>)>
+    where
+        T: TryFrom<StorageServiceResponse, Error = E> + Send + Sync + 'static,
+        E: Into<Error>,
+    {
+        let aptos_data_client = self.clone();
+        let request = request.clone();
+        tokio::spawn(async move {
+            let response_result = aptos_data_client
+                .send_request_to_peer_and_decode(peer, request, request_timeout_ms)
+                .await;
+            (is_hedged_request, response_result)
+        })
+    }
+
+    /// Chooses a peer to service a hedged request. The peer is chosen from the
+    /// serviceable peers that were not already sent the request (first by
+    /// priority, and then by latency). Returns None if the request should not
+    /// be hedged (e.g., the hedging budget is exhausted or no peer is available).
+    fn choose_peer_for_hedged_request(
+        &self,
+        request: &StorageServiceRequest,
+        requested_peers: &HashSet<PeerNetworkId>,
+    ) -> Option<PeerNetworkId> {
+        // Identify the highest priority serviceable peer (that wasn't already requested)
+        let peers_by_priorities = self.get_peers_by_priorities().ok()?;
+        let hedging_peer = PeerPriority::get_all_ordered_priorities()
+            .into_iter()
+            .find_map(|priority| {
+                let serviceable_peers = self
+                    .identify_serviceable(&peers_by_priorities, priority, request)
+                    .into_iter()
+                    .filter(|peer| !requested_peers.contains(peer))
+                    .collect();
+                self.choose_random_peers_by_latency(serviceable_peers, 1)
+                    .into_iter()
+                    .next()
+            });
+
+        // Verify that a peer was found and that the hedging budget allows the request
+        let request_label = request.get_label();
+        let hedging_result = match hedging_peer {
+            None => metrics::HEDGE_NO_PEER_LABEL,
+            Some(_) if !self.request_hedger.try_acquire_hedge() => {
+                metrics::HEDGE_BUDGET_EXHAUSTED_LABEL
+            },
+            Some(_) => metrics::HEDGE_SENT_LABEL,
+        };
+        metrics::increment_hedged_request_counter(&request_label, hedging_result);
+
+        if hedging_result == metrics::HEDGE_SENT_LABEL {
+            hedging_peer
+        } else {
+            None
+        }
+    }
+
     /// Sends a request to a specific peer and decodes the response
     pub async fn send_request_to_peer_and_decode<T, E>(
         &self,
@@ -700,6 +812,7 @@ impl AptosDataClient {
     {
         // Start the timer for the request
         let timer = start_request_timer(&metrics::REQUEST_LATENCIES, &request.get_label(), peer);
+        let request_start_time = self.time_service.now();
 
         // Get the response from the peer
         let response = self
@@ -708,13 +821,22 @@ impl AptosDataClient {
 
         // If an error occurred, stop the timer (without updating the metrics)
         // and return the error. Otherwise, stop the timer and update the metrics.
+        // Timeouts are also sampled for hedging (so that the hedging delay isn't
+        // skewed towards the latencies of fast peers).
+        let response_latency = self.time_service.now().duration_since(request_start_time);
         let storage_response = match response {
             Ok(storage_response) => {
                 timer.stop_and_record(); // Update the latency metrics
+                self.request_hedger
+                    .record_response_latency(&request, response_latency);
                 storage_response
             },
             Err(error) => {
                 timer.stop_and_discard(); // Discard the timer without updating the metrics
+                if error.is_timeout() {
+                    self.request_hedger
+                        .record_response_latency(&request, response_latency);
+                }
                 return Err(error);
             },
         };
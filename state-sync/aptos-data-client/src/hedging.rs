@@ -0,0 +1,221 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::AptosDataRequestHedgingConfig;
+use aptos_infallible::Mutex;
+use aptos_storage_service_types::requests::StorageServiceRequest;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+/// Tracks the state required to hedge data client requests. If a response hasn't
+/// arrived within the hedging delay (i.e., a percentile of the recent response
+/// latencies for the request type), the request is also sent to another peer.
+/// The number of hedged requests is bounded by a global budget (i.e., a token
+/// bucket that is refilled by each hedgeable request).
+#[derive(Debug)]
+pub struct RequestHedger {
+    /// The request hedging config
+    hedging_config: AptosDataRequestHedgingConfig,
+    /// The recent response latencies (for each request type)
+    response_latencies: Mutex<HashMap<&'static str, VecDeque<Duration>>>,
+    /// The number of hedged requests that can currently be sent
+    hedging_budget: Mutex<f64>,
+}
+
+impl RequestHedger {
+    pub fn new(hedging_config: AptosDataRequestHedgingConfig) -> Self {
+        Self {
+            hedging_config,
+            response_latencies: Mutex::new(HashMap::new()),
+            hedging_budget: Mutex::new(hedging_config.max_hedged_requests_burst as f64),
+        }
+    }
+
+    /// Returns true iff the given request can be hedged. Optimistic fetch and
+    /// subscription requests are never hedged (they wait for new data to arrive).
+    pub fn is_hedgeable(&self, request: &StorageServiceRequest) -> bool {
+        self.hedging_config.enable_request_hedging
+            && !request.data_request.is_optimistic_fetch()
+            && !request.data_request.is_subscription_request()
+    }
+
+    /// Records the latency of a response to the given request (i.e., the time
+    /// until a successful response was received, or the request timed out).
+    pub fn record_response_latency(&self, request: &StorageServiceRequest, latency: Duration) {
+        if !self.is_hedgeable(request) {
+            return;
+        }
+
+        let mut response_latencies = self.response_latencies.lock();
+        let latencies = response_latencies
+            .entry(request.data_request.get_label())
+            .or_default();
+        latencies.push_back(latency);
+        while latencies.len() > self.hedging_config.max_latency_samples {
+            latencies.pop_front();
+        }
+    }
+
+    /// Returns the hedging delay for the given request. Returns None if the
+    /// request can't be hedged, or if not enough latencies have been observed.
+    pub fn get_hedging_delay(&self, request: &StorageServiceRequest) -> Option<Duration> {
+        if !self.is_hedgeable(request) {
+            return None;
+        }
+
+        // Get the recent latencies for the request type
+        let response_latencies = self.response_latencies.lock();
+        let latencies = response_latencies.get(request.data_request.get_label())?;
+        if latencies.is_empty() || latencies.len() < self.hedging_config.min_latency_samples {
+            return None;
+        }
+
+        // Calculate the latency percentile (using the nearest rank)
+        let mut sorted_latencies: Vec<_> = latencies.iter().copied().collect();
+        sorted_latencies.sort_unstable();
+        let percentile = self.hedging_config.hedging_latency_percentile as usize;
+        let rank = (sorted_latencies.len() * percentile).div_ceil(100);
+        let index = rank.clamp(1, sorted_latencies.len()) - 1;
+
+        // Bound the hedging delay
+        Some(sorted_latencies[index].clamp(
+            Duration::from_millis(self.hedging_config.min_hedging_delay_ms),
+            Duration::from_millis(self.hedging_config.max_hedging_delay_ms),
+        ))
+    }
+
+    /// Refills the hedging budget (for a new hedgeable request)
+    pub fn refill_hedging_budget(&self) {
+        let refill_amount = self.hedging_config.max_hedged_requests_percent as f64 / 100.0;
+        let max_budget = self.hedging_config.max_hedged_requests_burst as f64;
+
+        let mut hedging_budget = self.hedging_budget.lock();
+        *hedging_budget = (*hedging_budget + refill_amount).min(max_budget);
+    }
+
+    /// Attempts to take a single hedged request from the budget.
+    /// Returns true iff the budget allows the request to be hedged.
+    pub fn try_acquire_hedge(&self) -> bool {
+        let mut hedging_budget = self.hedging_budget.lock();
+        if *hedging_budget >= 1.0 {
+            *hedging_budget -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RequestHedger;
+    use aptos_config::config::AptosDataRequestHedgingConfig;
+    use aptos_storage_service_types::requests::{
+        DataRequest, NewTransactionsWithProofRequest, StorageServiceRequest,
+        TransactionsWithProofRequest,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn test_hedging_delay() {
+        // Create a request hedger
+        let hedging_config = AptosDataRequestHedgingConfig {
+            enable_request_hedging: true,
+            hedging_latency_percentile: 90,
+            min_hedging_delay_ms: 5,
+            max_hedging_delay_ms: 80,
+            min_latency_samples: 10,
+            max_latency_samples: 20,
+            ..Default::default()
+        };
+        let request_hedger = RequestHedger::new(hedging_config);
+
+        // Verify there is no hedging delay until enough latencies are observed
+        let request = create_transactions_request();
+        for latency_ms in 1..=9 {
+            request_hedger.record_response_latency(&request, Duration::from_millis(latency_ms));
+            assert_eq!(request_hedger.get_hedging_delay(&request), None);
+        }
+
+        // Verify the hedging delay is the latency percentile
+        request_hedger.record_response_latency(&request, Duration::from_millis(10));
+        assert_eq!(
+            request_hedger.get_hedging_delay(&request),
+            Some(Duration::from_millis(9))
+        );
+
+        // Verify the hedging delay is bounded (and only recent latencies are used)
+        for _ in 0..20 {
+            request_hedger.record_response_latency(&request, Duration::from_millis(100));
+        }
+        assert_eq!(
+            request_hedger.get_hedging_delay(&request),
+            Some(Duration::from_millis(80))
+        );
+
+        // Verify that optimistic fetch requests are never hedged
+        let optimistic_fetch_request = StorageServiceRequest::new(
+            DataRequest::GetNewTransactionsWithProof(NewTransactionsWithProofRequest {
+                known_version: 0,
+                known_epoch: 0,
+                include_events: false,
+            }),
+            false,
+        );
+        assert!(!request_hedger.is_hedgeable(&optimistic_fetch_request));
+        assert_eq!(
+            request_hedger.get_hedging_delay(&optimistic_fetch_request),
+            None
+        );
+    }
+
+    #[test]
+    fn test_hedging_budget() {
+        // Create a request hedger with a budget of 10% and a burst of 2
+        let hedging_config = AptosDataRequestHedgingConfig {
+            enable_request_hedging: true,
+            max_hedged_requests_percent: 10,
+            max_hedged_requests_burst: 2,
+            ..Default::default()
+        };
+        let request_hedger = RequestHedger::new(hedging_config);
+
+        // Verify the initial burst can be used
+        assert!(request_hedger.try_acquire_hedge());
+        assert!(request_hedger.try_acquire_hedge());
+        assert!(!request_hedger.try_acquire_hedge());
+
+        // Verify the budget is refilled by (roughly) 1 hedge per 10 requests
+        for _ in 0..9 {
+            request_hedger.refill_hedging_budget();
+            assert!(!request_hedger.try_acquire_hedge());
+        }
+        request_hedger.refill_hedging_budget();
+        request_hedger.refill_hedging_budget();
+        assert!(request_hedger.try_acquire_hedge());
+        assert!(!request_hedger.try_acquire_hedge());
+
+        // Verify the budget never exceeds the burst
+        for _ in 0..1_000 {
+            request_hedger.refill_hedging_budget();
+        }
+        assert!(request_hedger.try_acquire_hedge());
+        assert!(request_hedger.try_acquire_hedge());
+        assert!(!request_hedger.try_acquire_hedge());
+    }
+
+    /// Creates a transactions request (that can be hedged)
+    fn create_transactions_request() -> StorageServiceRequest {
+        StorageServiceRequest::new(
+            DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+                proof_version: 100,
+                start_version: 0,
+                end_version: 10,
+                include_events: false,
+            }),
+            false,
+        )
+    }
+}
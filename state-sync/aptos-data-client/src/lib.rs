@@ -6,6 +6,7 @@
 pub mod client;
 pub mod error;
 pub mod global_summary;
+mod hedging;
 pub mod interface;
 mod latency_monitor;
 mod logging;
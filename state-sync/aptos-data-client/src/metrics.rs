@@ -10,6 +10,10 @@ use aptos_metrics_core::{
 
 // Useful metric constants and labels
 pub const BLOCK_TIMESTAMP_LAG_LABEL: &str = "block_timestamp_lag";
+pub const HEDGE_BUDGET_EXHAUSTED_LABEL: &str = "budget_exhausted";
+pub const HEDGE_NO_PEER_LABEL: &str = "no_peer";
+pub const HEDGE_SENT_LABEL: &str = "sent";
+pub const HEDGE_WON_LABEL: &str = "won";
 pub const PRIORITIZED_PEER: &str = "prioritized_peer";
 pub const PROPOSE_TO_SEEN_LATENCY_LABEL: &str = "propose_to_seen_latency";
 pub const PROPOSE_TO_SYNC_LATENCY_LABEL: &str = "propose_to_sync_latency";
@@ -49,6 +53,16 @@ pub static ERROR_RESPONSES: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking hedged requests (and their results)
+pub static HEDGED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_data_client_hedged_requests",
+        "Counters related to hedged requests",
+        &["request_type", "result"]
+    )
+    .unwrap()
+});
+
 // Buckets for tracking the number of multi-fetches sent per request
 const MULTI_FETCH_BUCKETS: &[f64] = &[
     1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 15.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0,
@@ -231,6 +245,13 @@ pub fn increment_request_counter(
         .inc();
 }
 
+/// Increments the hedged request counter with the provided values
+pub fn increment_hedged_request_counter(request_label: &str, result_label: &str) {
+    HEDGED_REQUESTS
+        .with_label_values(&[request_label, result_label])
+        .inc();
+}
+
 /// Observes the value for the provided histogram and label
 pub fn observe_value_with_label(histogram: &Lazy<HistogramVec>, label: &str, value: f64) {
     histogram.with_label_values(&[label]).observe(value)
@@ -0,0 +1,94 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    interface::AptosDataClientInterface,
+    priority::PeerPriority,
+    tests::{mock::MockNetwork, utils},
+};
+use aptos_config::config::{
+    AptosDataClientConfig, AptosDataMultiFetchConfig, AptosDataRequestHedgingConfig,
+};
+use aptos_time_service::TimeServiceTrait;
+use std::time::Duration;
+
+#[tokio::test]
+async fn hedged_request_to_second_peer() {
+    // Create a base config for a validator
+    let base_config = utils::create_validator_base_config();
+
+    // Create a data client config with hedging enabled (and multi-fetch disabled)
+    let hedging_delay_ms = 100;
+    let data_client_config = AptosDataClientConfig {
+        data_multi_fetch_config: AptosDataMultiFetchConfig {
+            enable_multi_fetch: false,
+            ..Default::default()
+        },
+        data_request_hedging_config: AptosDataRequestHedgingConfig {
+            enable_request_hedging: true,
+            min_hedging_delay_ms: hedging_delay_ms,
+            max_hedging_delay_ms: hedging_delay_ms,
+            min_latency_samples: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // Create the mock network, mock time and client
+    let (mut mock_network, mut mock_time, client, _) =
+        MockNetwork::new(Some(base_config), Some(data_client_config), None);
+
+    // Add two high priority peers that advertise the data
+    let peers = utils::add_several_peers(&mut mock_network, 2, PeerPriority::HighPriority);
+    let network_id = peers.iter().next().unwrap().network_id();
+    let known_version = 100;
+    utils::update_storage_summaries_for_peers(
+        &client,
+        &peers,
+        known_version,
+        mock_time.now_unix_time().as_micros(),
+    );
+
+    // Send a transactions request and respond (so that a response latency is observed)
+    let request_timeout = data_client_config.response_timeout_ms;
+    let use_compression = data_client_config.use_compression;
+    let client_clone = client.clone();
+    let request_task = tokio::spawn(async move {
+        client_clone
+            .get_transactions_with_proof(known_version, 50, known_version, false, request_timeout)
+            .await
+    });
+    let network_request = utils::get_network_request(&mut mock_network, network_id).await;
+    utils::handle_transactions_request(network_request, use_compression);
+    request_task.await.unwrap().unwrap();
+
+    // Send another transactions request, but don't respond to it
+    let client_clone = client.clone();
+    let request_task = tokio::spawn(async move {
+        client_clone
+            .get_transactions_with_proof(known_version, 50, known_version, false, request_timeout)
+            .await
+    });
+    let slow_network_request = utils::get_network_request(&mut mock_network, network_id).await;
+
+    // Elapse the hedging delay, and verify the request is hedged to the other peer
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+        mock_time
+            .advance_async(Duration::from_millis(hedging_delay_ms))
+            .await;
+    }
+    let hedged_network_request = utils::get_network_request(&mut mock_network, network_id).await;
+    assert_ne!(
+        hedged_network_request.peer_network_id,
+        slow_network_request.peer_network_id
+    );
+    assert_eq!(
+        hedged_network_request.storage_service_request,
+        slow_network_request.storage_service_request
+    );
+
+    // Respond to the hedged request, and verify the client receives the response
+    utils::handle_transactions_request(hedged_network_request, use_compression);
+    request_task.await.unwrap().unwrap();
+}
@@ -48,6 +48,32 @@ pub enum DataPayload {
     TransactionsWithProof(TransactionListWithProof),
 }
 
+impl DataPayload {
+    /// Returns an estimate of the size (in bytes) of the data payload
+    pub fn get_size_estimate(&self) -> u64 {
+        let serialized_size = match self {
+            DataPayload::ContinuousTransactionOutputsWithProof(ledger_info, outputs_with_proof) => {
+                bcs::serialized_size(&(ledger_info, outputs_with_proof))
+            },
+            DataPayload::ContinuousTransactionsWithProof(ledger_info, transactions_with_proof) => {
+                bcs::serialized_size(&(ledger_info, transactions_with_proof))
+            },
+            DataPayload::EpochEndingLedgerInfos(ledger_infos) => bcs::serialized_size(ledger_infos),
+            DataPayload::EndOfStream => Ok(0),
+            DataPayload::StateValuesWithProof(state_values_with_proof) => {
+                bcs::serialized_size(state_values_with_proof)
+            },
+            DataPayload::TransactionOutputsWithProof(outputs_with_proof) => {
+                bcs::serialized_size(outputs_with_proof)
+            },
+            DataPayload::TransactionsWithProof(transactions_with_proof) => {
+                bcs::serialized_size(transactions_with_proof)
+            },
+        };
+        serialized_size.unwrap_or_default() as u64
+    }
+}
+
 /// A request that has been sent to the Aptos data client.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataClientRequest {
@@ -123,6 +123,14 @@ pub struct DataStream<T> {
 
     // The dynamic prefetching state (if enabled)
     dynamic_prefetching_state: DynamicPrefetchingState,
+
+    // The lookahead buffer of verified data notifications (and their estimated
+    // sizes) that are ready to be sent, but that the listener has not yet had
+    // room to receive. This is only used if data lookahead is enabled.
+    lookahead_buffer: VecDeque<(DataNotification, u64)>,
+
+    // The estimated number of bytes currently held in the lookahead buffer
+    lookahead_buffer_bytes: u64,
 }
 
 impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
@@ -168,6 +176,8 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
             subscription_stream_lag: None,
             time_service,
             dynamic_prefetching_state,
+            lookahead_buffer: VecDeque::new(),
+            lookahead_buffer_bytes: 0,
         };
 
         Ok((data_stream, data_stream_listener))
@@ -401,22 +411,87 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
         &mut self,
         data_notification: DataNotification,
     ) -> Result<(), Error> {
+        // If data lookahead is enabled, buffer the notification instead of blocking
+        if self.is_data_lookahead_enabled() {
+            let size_estimate = data_notification.data_payload.get_size_estimate();
+            self.lookahead_buffer
+                .push_back((data_notification, size_estimate));
+            self.lookahead_buffer_bytes += size_estimate;
+            return self.flush_lookahead_buffer();
+        }
+
         if let Err(error) = self.notification_sender.send(data_notification).await {
-            let error = Error::UnexpectedErrorEncountered(error.to_string());
-            warn!(
-                (LogSchema::new(LogEntry::StreamNotification)
-                    .stream_id(self.data_stream_id)
-                    .event(LogEvent::Error)
-                    .error(&error)
-                    .message("Failed to send data notification to listener!"))
-            );
-            self.send_failure = true;
-            Err(error)
+            self.handle_send_failure(Error::UnexpectedErrorEncountered(error.to_string()))
         } else {
             Ok(())
         }
     }
 
+    /// Sends as many buffered notifications to the listener as it has room for
+    /// (without blocking). The notifications are sent in order.
+    fn flush_lookahead_buffer(&mut self) -> Result<(), Error> {
+        while let Some((data_notification, size_estimate)) = self.lookahead_buffer.pop_front() {
+            if let Err(error) = self.notification_sender.try_send(data_notification) {
+                if error.is_full() {
+                    // The listener has no room, so keep the notification buffered
+                    self.lookahead_buffer
+                        .push_front((error.into_inner(), size_estimate));
+                    break;
+                }
+
+                // The listener has been dropped
+                self.lookahead_buffer.clear();
+                self.lookahead_buffer_bytes = 0;
+                metrics::set_lookahead_buffer_occupancy(0, 0);
+                return self.handle_send_failure(Error::UnexpectedErrorEncountered(
+                    error.into_send_error().to_string(),
+                ));
+            }
+            self.lookahead_buffer_bytes = self.lookahead_buffer_bytes.saturating_sub(size_estimate);
+        }
+
+        // Update the lookahead occupancy metrics
+        metrics::set_lookahead_buffer_occupancy(
+            self.lookahead_buffer.len(),
+            self.lookahead_buffer_bytes,
+        );
+
+        Ok(())
+    }
+
+    /// Handles a failure to send a data notification to the listener
+    fn handle_send_failure(&mut self, error: Error) -> Result<(), Error> {
+        warn!(
+            (LogSchema::new(LogEntry::StreamNotification)
+                .stream_id(self.data_stream_id)
+                .event(LogEvent::Error)
+                .error(&error)
+                .message("Failed to send data notification to listener!"))
+        );
+        self.send_failure = true;
+        Err(error)
+    }
+
+    /// Returns true iff data lookahead is enabled for the stream
+    fn is_data_lookahead_enabled(&self) -> bool {
+        self.streaming_service_config
+            .data_lookahead
+            .enable_data_lookahead
+    }
+
+    /// Returns true iff the lookahead buffer has reached its memory budget
+    /// (i.e., no further data should be fetched ahead of the listener).
+    fn is_lookahead_buffer_full(&self) -> bool {
+        let data_lookahead_config = &self.streaming_service_config.data_lookahead;
+        self.lookahead_buffer.len() as u64 >= data_lookahead_config.max_lookahead_notifications
+            || self.lookahead_buffer_bytes >= data_lookahead_config.max_lookahead_bytes
+    }
+
+    /// Returns the number of notifications held in the lookahead buffer
+    pub fn get_num_lookahead_notifications(&self) -> usize {
+        self.lookahead_buffer.len()
+    }
+
     /// Returns true iff there was a send failure
     pub fn send_failure(&self) -> bool {
         self.send_failure
@@ -444,6 +519,11 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
         &mut self,
         global_data_summary: GlobalDataSummary,
     ) -> Result<(), Error> {
+        // Send any buffered notifications that the listener now has room for
+        if self.is_data_lookahead_enabled() && !self.send_failure {
+            self.flush_lookahead_buffer()?;
+        }
+
         if self.stream_engine.is_stream_complete()
             || self.request_failure_count >= self.streaming_service_config.max_request_retry
             || self.send_failure
@@ -455,7 +535,19 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
         }
 
         // Continuously process any ready data responses
-        while let Some(pending_response) = self.pop_pending_response_queue()? {
+        loop {
+            // If the lookahead buffer is full, stall until the listener catches up
+            if self.is_data_lookahead_enabled() && self.is_lookahead_buffer_full() {
+                metrics::LOOKAHEAD_STALLS.inc();
+                break;
+            }
+
+            // Get the next ready data response (if any)
+            let pending_response = match self.pop_pending_response_queue()? {
+                Some(pending_response) => pending_response,
+                None => break,
+            };
+
             // Get the client request and response information
             let maybe_client_response = pending_response.lock().client_response.take();
             let client_response = maybe_client_response.ok_or_else(|| {
@@ -200,6 +200,33 @@ pub static SUBSCRIPTION_STREAM_LAG: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Gauge for the number of data notifications held in the lookahead buffer
+pub static LOOKAHEAD_BUFFERED_NOTIFICATIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_data_streaming_service_lookahead_buffered_notifications",
+        "Gauge for the number of data notifications held in the lookahead buffer",
+    )
+    .unwrap()
+});
+
+/// Gauge for the number of bytes (estimated) held in the lookahead buffer
+pub static LOOKAHEAD_BUFFERED_BYTES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_data_streaming_service_lookahead_buffered_bytes",
+        "Gauge for the number of bytes (estimated) held in the lookahead buffer",
+    )
+    .unwrap()
+});
+
+/// Counter for the number of times the stream stalled on a full lookahead buffer
+pub static LOOKAHEAD_STALLS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_data_streaming_service_lookahead_stalls",
+        "Counters related to stream stalls caused by a full lookahead buffer",
+    )
+    .unwrap()
+});
+
 /// Time it takes to process a data request
 pub static DATA_REQUEST_PROCESSING_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     let histogram_opts = histogram_opts!(
@@ -276,6 +303,12 @@ pub fn set_pending_data_responses(value: u64) {
     PENDING_DATA_RESPONSES.set(value as i64);
 }
 
+/// Sets the number of notifications and bytes held in the lookahead buffer
+pub fn set_lookahead_buffer_occupancy(num_notifications: usize, num_bytes: u64) {
+    LOOKAHEAD_BUFFERED_NOTIFICATIONS.set(num_notifications as i64);
+    LOOKAHEAD_BUFFERED_BYTES.set(num_bytes as i64);
+}
+
 /// Sets the subscription stream lag
 pub fn set_subscription_stream_lag(value: u64) {
     SUBSCRIPTION_STREAM_LAG.set(value as i64);
@@ -32,7 +32,8 @@ use crate::{
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::config::{
-    AptosDataClientConfig, DataStreamingServiceConfig, DynamicPrefetchingConfig,
+    AptosDataClientConfig, DataLookaheadConfig, DataStreamingServiceConfig,
+    DynamicPrefetchingConfig,
 };
 use aptos_data_client::{
     global_summary::{AdvertisedData, GlobalDataSummary, OptimalChunkSizes},
@@ -3195,6 +3196,72 @@ async fn test_stream_timeouts_dynamic() {
     }
 }
 
+#[tokio::test]
+async fn test_stream_data_lookahead() {
+    // Create an epoch ending data stream with data lookahead enabled
+    let max_concurrent_requests = 3;
+    let max_lookahead_notifications = 2;
+    let streaming_service_config = DataStreamingServiceConfig {
+        data_lookahead: DataLookaheadConfig {
+            enable_data_lookahead: true,
+            max_lookahead_notifications,
+            ..Default::default()
+        },
+        dynamic_prefetching: DynamicPrefetchingConfig {
+            enable_dynamic_prefetching: false,
+            ..Default::default()
+        },
+        max_concurrent_requests,
+        max_data_stream_channel_sizes: 1, // The channel capacity is 2 (1 + the number of senders)
+        max_pending_requests: max_concurrent_requests,
+        ..Default::default()
+    };
+    let (mut data_stream, mut stream_listener) = create_epoch_ending_stream(
+        AptosDataClientConfig::default(),
+        streaming_service_config,
+        MIN_ADVERTISED_EPOCH_END,
+    );
+
+    // Initialize the data stream
+    let global_data_summary = create_global_data_summary(1);
+    initialize_data_requests(&mut data_stream, &global_data_summary);
+    verify_num_sent_requests(&mut data_stream, max_concurrent_requests);
+
+    // Set valid responses for all requests and process them (without consuming
+    // any notifications). Verify the overflow is held in the lookahead buffer.
+    for index in 0..max_concurrent_requests {
+        set_epoch_ending_response_in_queue(&mut data_stream, index as usize, 0);
+    }
+    process_data_responses(&mut data_stream, &global_data_summary).await;
+    assert_eq!(data_stream.get_num_lookahead_notifications(), 1);
+
+    // Verify that more requests were sent (i.e., data is fetched ahead of the listener)
+    verify_num_sent_requests(&mut data_stream, max_concurrent_requests);
+
+    // Set valid responses for all new requests and process them. Verify
+    // that the stream stalls once the lookahead buffer is full.
+    for index in 0..max_concurrent_requests {
+        set_epoch_ending_response_in_queue(&mut data_stream, index as usize, 0);
+    }
+    process_data_responses(&mut data_stream, &global_data_summary).await;
+    assert_eq!(
+        data_stream.get_num_lookahead_notifications() as u64,
+        max_lookahead_notifications
+    );
+    verify_num_sent_requests(&mut data_stream, max_concurrent_requests);
+
+    // Consume notifications and process responses until all notifications are received
+    let mut num_received_notifications = 0;
+    for _ in 0..10 {
+        while stream_listener.select_next_some().now_or_never().is_some() {
+            num_received_notifications += 1;
+        }
+        process_data_responses(&mut data_stream, &global_data_summary).await;
+    }
+    assert_eq!(num_received_notifications, max_concurrent_requests * 2);
+    assert_eq!(data_stream.get_num_lookahead_notifications(), 0);
+}
+
 #[tokio::test]
 async fn test_stream_listener_dropped() {
     // Create an epoch ending data stream
@@ -35,6 +35,8 @@ pub enum Error {
     NotifyStorageServiceError(String),
     #[error("Received an old sync request for version {0}, but our committed version is: {1}")]
     OldSyncRequest(Version, Version),
+    #[error("The transaction output audit failed: {0}")]
+    OutputAuditMismatch(String),
     #[error("Received oneshot::canceled. The sender of a channel was dropped: {0}")]
     SenderDroppedError(String),
     #[error("Unexpected storage error: {0}")]
@@ -66,6 +68,7 @@ impl Error {
             Error::NotifyMempoolError(_) => "notify_mempool_error",
             Error::NotifyStorageServiceError(_) => "notify_storage_service_error",
             Error::OldSyncRequest(_, _) => "old_sync_request",
+            Error::OutputAuditMismatch(_) => "output_audit_mismatch",
             Error::SenderDroppedError(_) => "sender_dropped_error",
             Error::StorageError(_) => "storage_error",
             Error::SyncedBeyondTarget(_, _) => "synced_beyond_target",
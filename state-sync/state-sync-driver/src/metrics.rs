@@ -38,6 +38,10 @@ pub const STORAGE_SYNCHRONIZER_COMMITTER: &str = "committer";
 pub const STORAGE_SYNCHRONIZER_COMMIT_POST_PROCESSOR: &str = "commit_post_processor";
 pub const STORAGE_SYNCHRONIZER_STATE_SNAPSHOT_RECEIVER: &str = "state_snapshot_receiver";
 
+/// Transaction output audit result labels
+pub const OUTPUT_AUDIT_PASSED: &str = "passed";
+pub const OUTPUT_AUDIT_MISMATCH: &str = "mismatch";
+
 /// An enum representing the component currently executing
 pub enum ExecutingComponent {
     Bootstrapper,
@@ -187,6 +191,16 @@ pub static STORAGE_SYNCHRONIZER_LATENCIES: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counters for the audits of applied transaction output chunks (i.e., via re-execution)
+pub static STORAGE_SYNCHRONIZER_OUTPUT_AUDITS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_storage_synchronizer_output_audits",
+        "Counters for the audits of applied transaction output chunks",
+        &["result"]
+    )
+    .unwrap()
+});
+
 /// Gauges for the storage synchronizer operations
 pub static STORAGE_SYNCHRONIZER_OPERATIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
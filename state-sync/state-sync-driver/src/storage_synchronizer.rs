@@ -36,6 +36,7 @@ use aptos_types::{
 };
 use async_trait::async_trait;
 use futures::{channel::mpsc, SinkExt, StreamExt};
+use rand::Rng;
 use std::{
     future::Future,
     sync::{
@@ -234,6 +235,7 @@ impl<
         let runtime = runtime.map(|runtime| runtime.handle().clone());
         let executor_handle = spawn_executor(
             chunk_executor.clone(),
+            driver_config.output_audit_percentage,
             error_notification_sender.clone(),
             executor_listener,
             ledger_updater_notifier,
@@ -484,6 +486,7 @@ enum StorageDataChunk {
 /// Spawns a dedicated executor that executes/applies storage data chunks
 fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
     chunk_executor: Arc<ChunkExecutor>,
+    output_audit_percentage: u64,
     error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
     mut executor_listener: mpsc::Receiver<StorageDataChunk>,
     mut ledger_updater_notifier: mpsc::Sender<NotificationMetadata>,
@@ -520,12 +523,14 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
                     target_ledger_info,
                     end_of_epoch_ledger_info,
                 ) => {
-                    // Apply the storage data chunk
+                    // Apply the storage data chunk (and audit it, if sampled)
+                    let audit_outputs = should_audit_output_chunk(output_audit_percentage);
                     let result = apply_output_chunk(
                         chunk_executor.clone(),
                         outputs_with_proof,
                         target_ledger_info,
                         end_of_epoch_ledger_info,
+                        audit_outputs,
                     )
                     .await;
                     (notification_metadata, result, false)
@@ -977,23 +982,35 @@ fn spawn_state_snapshot_receiver<
     spawn(runtime, receiver)
 }
 
-/// Spawns a dedicated task that applies the given output chunk. We use
-/// `spawn_blocking` so that the heavy synchronous function doesn't
-/// block the async thread.
+/// Returns true iff the next output chunk should be audited (i.e., re-executed
+/// to verify the outputs), given the percentage of chunks to audit.
+fn should_audit_output_chunk(output_audit_percentage: u64) -> bool {
+    output_audit_percentage > 0 && rand::thread_rng().gen_range(0, 100) < output_audit_percentage
+}
+
+/// Spawns a dedicated task that applies the given output chunk (and audits
+/// the outputs first, if required). We use `spawn_blocking` so that the
+/// heavy synchronous function doesn't block the async thread.
 async fn apply_output_chunk<ChunkExecutor: ChunkExecutorTrait + 'static>(
     chunk_executor: Arc<ChunkExecutor>,
     outputs_with_proof: TransactionOutputListWithProof,
     target_ledger_info: LedgerInfoWithSignatures,
     end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
+    audit_outputs: bool,
 ) -> anyhow::Result<()> {
-    // Apply the output chunk
+    // Audit (if required) and apply the output chunk. The audit must happen
+    // before the chunk is enqueued, as it re-executes on top of the same state.
     let num_outputs = outputs_with_proof.transactions_and_outputs.len();
-    let result = tokio::task::spawn_blocking(move || {
-        chunk_executor.enqueue_chunk_by_transaction_outputs(
+    let first_output_version = outputs_with_proof.first_transaction_output_version;
+    let (audit_result, result) = tokio::task::spawn_blocking(move || {
+        let audit_result =
+            audit_outputs.then(|| chunk_executor.audit_chunk_by_execution(&outputs_with_proof));
+        let result = chunk_executor.enqueue_chunk_by_transaction_outputs(
             outputs_with_proof,
             &target_ledger_info,
             end_of_epoch_ledger_info.as_ref(),
-        )
+        );
+        (audit_result, result)
     })
     .await
     .expect("Spawn_blocking(apply_output_chunk) failed!");
@@ -1012,11 +1029,53 @@ async fn apply_output_chunk<ChunkExecutor: ChunkExecutorTrait + 'static>(
         let operation_label =
             metrics::StorageSynchronizerOperations::AppliedTransactionOutputs.get_label();
         update_synchronizer_chunk_metrics(num_outputs, operation_label);
+
+        // Handle the audit result. Note: we only do this once the chunk has been
+        // verified and applied, otherwise the outputs may simply be invalid.
+        if let Some(audit_result) = audit_result {
+            handle_output_audit_result(audit_result, first_output_version, num_outputs);
+        }
     }
 
     result
 }
 
+/// Logs and updates the metrics for the given output audit result. Mismatches
+/// don't prevent the (verified) outputs from being committed, but they signal
+/// that local execution diverges from the rest of the network.
+fn handle_output_audit_result(
+    audit_result: anyhow::Result<()>,
+    first_output_version: Option<Version>,
+    num_outputs: usize,
+) {
+    match audit_result {
+        Ok(()) => {
+            debug!(
+                LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                    "The output audit passed! First version: {:?}, num outputs: {:?}",
+                    first_output_version, num_outputs
+                ))
+            );
+            metrics::increment_counter(
+                &metrics::STORAGE_SYNCHRONIZER_OUTPUT_AUDITS,
+                metrics::OUTPUT_AUDIT_PASSED,
+            );
+        },
+        Err(error) => {
+            let error = Error::OutputAuditMismatch(format!(
+                "Local execution doesn't match the applied outputs! First version: {:?}, \
+                num outputs: {:?}, error: {:?}",
+                first_output_version, num_outputs, error
+            ));
+            error!(LogSchema::new(LogEntry::StorageSynchronizer).error(&error));
+            metrics::increment_counter(
+                &metrics::STORAGE_SYNCHRONIZER_OUTPUT_AUDITS,
+                metrics::OUTPUT_AUDIT_MISMATCH,
+            );
+        },
+    }
+}
+
 /// Spawns a dedicated task that executes the given transaction chunk.
 /// We use `spawn_blocking` so that the heavy synchronous function
 /// doesn't block the async thread.
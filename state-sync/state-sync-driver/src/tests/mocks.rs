@@ -166,6 +166,11 @@ mock! {
             epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
         ) -> AnyhowResult<()>;
 
+        fn audit_chunk_by_execution(
+            &self,
+            txn_output_list_with_proof: &TransactionOutputListWithProof,
+        ) -> AnyhowResult<()>;
+
         fn update_ledger(&self) -> AnyhowResult<()>;
 
         fn commit_chunk(&self) -> AnyhowResult<ChunkCommitNotification>;
@@ -334,6 +334,72 @@ async fn test_apply_outputs_commit_send_error() {
     verify_no_pending_data(&storage_synchronizer);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_apply_outputs_audit_mismatch() {
+    // Create test data
+    let transaction_to_commit = create_transaction();
+    let event_to_commit = create_event(None);
+
+    // Setup the mock executor (the audit fails, but the outputs are still applied)
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_audit_chunk_by_execution()
+        .with(always())
+        .times(1)
+        .returning(|_| Err(format_err!("Output mismatch!")));
+    chunk_executor
+        .expect_enqueue_chunk_by_transaction_outputs()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    chunk_executor.expect_update_ledger().returning(|| Ok(()));
+    let expected_commit_return = Ok(ChunkCommitNotification {
+        subscribable_events: vec![event_to_commit.clone()],
+        committed_transactions: vec![transaction_to_commit.clone()],
+        reconfiguration_occurred: false,
+    });
+    chunk_executor
+        .expect_commit_chunk()
+        .return_once(move || expected_commit_return);
+
+    // Create the mock DB reader/writer
+    let highest_synced_version = 1090;
+    let mock_reader_writer =
+        create_mock_reader_writer_with_version(None, None, highest_synced_version);
+
+    // Create the storage synchronizer (with all output chunks audited)
+    let driver_config = StateSyncDriverConfig {
+        output_audit_percentage: 100,
+        ..Default::default()
+    };
+    let (_, _, _, mut mempool_listener, mut storage_service_listener, mut storage_synchronizer, _) =
+        create_storage_synchronizer_with_config(driver_config, chunk_executor, mock_reader_writer);
+
+    // Attempt to apply a chunk of outputs
+    storage_synchronizer
+        .apply_transaction_outputs(
+            NotificationMetadata::new_for_test(0),
+            create_output_list_with_proof(),
+            create_epoch_ending_ledger_info(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Verify that the chunk is still committed
+    verify_commit_notification(
+        None,
+        &mut mempool_listener,
+        &mut storage_service_listener,
+        vec![transaction_to_commit],
+        vec![event_to_commit],
+        highest_synced_version,
+    )
+    .await;
+
+    // Verify there's no pending data
+    verify_no_pending_data(&storage_synchronizer);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_execute_transactions() {
     // Create test data
@@ -886,6 +952,27 @@ fn create_storage_synchronizer(
     StorageServiceNotificationListener,
     StorageSynchronizer<MockChunkExecutor, PersistentMetadataStorage>,
     StorageSynchronizerHandles,
+) {
+    create_storage_synchronizer_with_config(
+        StateSyncDriverConfig::default(),
+        mock_chunk_executor,
+        mock_reader_writer,
+    )
+}
+
+/// Creates a storage synchronizer for testing (using the given driver config)
+fn create_storage_synchronizer_with_config(
+    driver_config: StateSyncDriverConfig,
+    mock_chunk_executor: MockChunkExecutor,
+    mock_reader_writer: DbReaderWriter,
+) -> (
+    CommitNotificationListener,
+    ErrorNotificationListener,
+    Arc<Mutex<EventSubscriptionService>>,
+    MempoolNotificationListener,
+    StorageServiceNotificationListener,
+    StorageSynchronizer<MockChunkExecutor, PersistentMetadataStorage>,
+    StorageSynchronizerHandles,
 ) {
     aptos_logger::Logger::init_for_testing();
 
@@ -916,7 +1003,7 @@ fn create_storage_synchronizer(
 
     // Create the storage synchronizer
     let (storage_synchronizer, storage_synchronizer_handles) = StorageSynchronizer::new(
-        StateSyncDriverConfig::default(),
+        driver_config,
         Arc::new(mock_chunk_executor),
         commit_notification_sender,
         error_notification_sender,
@@ -84,7 +84,7 @@ impl AptosDB {
 
         let (ledger_db, state_merkle_db, state_kv_db) = Self::open_dbs(
             db_paths,
-            rocksdb_configs,
+            rocksdb_configs.clone(),
             readonly,
             max_num_nodes_per_lru_cache_shard,
         )?;
@@ -169,10 +169,14 @@ impl AptosDB {
         readonly: bool,
         max_num_nodes_per_lru_cache_shard: usize,
     ) -> Result<(LedgerDb, StateMerkleDb, StateKvDb)> {
-        let ledger_db = LedgerDb::new(db_paths.ledger_db_root_path(), rocksdb_configs, readonly)?;
+        let ledger_db = LedgerDb::new(
+            db_paths.ledger_db_root_path(),
+            rocksdb_configs.clone(),
+            readonly,
+        )?;
         let state_kv_db = StateKvDb::new(
             db_paths,
-            rocksdb_configs,
+            rocksdb_configs.clone(),
             readonly,
             ledger_db.metadata_db_arc(),
         )?;
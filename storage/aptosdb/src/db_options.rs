@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::schema::*;
-use aptos_config::config::RocksdbConfig;
+use aptos_config::config::{BloomFilterType, RocksdbConfig};
+use aptos_logger::warn;
 use aptos_schemadb::{
     BlockBasedOptions, Cache, ColumnFamilyDescriptor, ColumnFamilyName, DBCompressionType, Options,
     SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
@@ -145,24 +146,87 @@ fn gen_cfds<F>(
 where
     F: Fn(ColumnFamilyName, &mut Options),
 {
-    let mut table_options = BlockBasedOptions::default();
-    table_options.set_cache_index_and_filter_blocks(rocksdb_config.cache_index_and_filter_blocks);
-    table_options.set_block_size(rocksdb_config.block_size as usize);
     let cache = Cache::new_lru_cache(rocksdb_config.block_cache_size as usize);
-    table_options.set_block_cache(&cache);
     let mut cfds = Vec::with_capacity(cfs.len());
     for cf_name in cfs {
+        let mut table_options = BlockBasedOptions::default();
+        table_options
+            .set_cache_index_and_filter_blocks(rocksdb_config.cache_index_and_filter_blocks);
+        table_options.set_block_size(rocksdb_config.block_size as usize);
+        table_options.set_block_cache(&cache);
+        set_bloom_filter(rocksdb_config, cf_name, &mut table_options);
+
         let mut cf_opts = Options::default();
         cf_opts.set_compression_type(DBCompressionType::Lz4);
         cf_opts.set_block_based_table_factory(&table_options);
+        if let Some(prefix_length) = fixed_prefix_length(rocksdb_config, cf_name) {
+            cf_opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(prefix_length));
+        }
         cf_opts_post_processor(cf_name, &mut cf_opts);
         cfds.push(ColumnFamilyDescriptor::new((*cf_name).to_string(), cf_opts));
     }
     cfds
 }
 
+/// Returns the length of the fixed length prefix extractor for the column family
+/// (if one is configured). The state key extractor always takes precedence.
+fn fixed_prefix_length(rocksdb_config: &RocksdbConfig, cf_name: ColumnFamilyName) -> Option<usize> {
+    if uses_state_key_extractor(cf_name) {
+        return None;
+    }
+    rocksdb_config
+        .bloom_filter
+        .fixed_prefix_lengths
+        .get(cf_name)
+        .copied()
+}
+
+fn set_bloom_filter(
+    rocksdb_config: &RocksdbConfig,
+    cf_name: ColumnFamilyName,
+    table_options: &mut BlockBasedOptions,
+) {
+    let bloom_filter_config = &rocksdb_config.bloom_filter;
+    let bloom_filter_type = match bloom_filter_config.filter_types.get(cf_name) {
+        Some(bloom_filter_type) => *bloom_filter_type,
+        None => return, // The column family doesn't use a bloom filter
+    };
+
+    match bloom_filter_type {
+        BloomFilterType::WholeKey if bloom_filter_config.whole_key_bits_per_key > 0 => {
+            table_options.set_bloom_filter(
+                bloom_filter_config.whole_key_bits_per_key as f64,
+                /*block_based=*/ false,
+            );
+            table_options.set_whole_key_filtering(true);
+        },
+        BloomFilterType::Prefix if bloom_filter_config.prefix_bits_per_key > 0 => {
+            // Prefix filters can only be built for column families with a prefix extractor
+            if !uses_state_key_extractor(cf_name)
+                && fixed_prefix_length(rocksdb_config, cf_name).is_none()
+            {
+                warn!(
+                    "Ignoring the prefix bloom filter for column family {}: no prefix extractor is configured!",
+                    cf_name
+                );
+                return;
+            }
+            table_options.set_bloom_filter(
+                bloom_filter_config.prefix_bits_per_key as f64,
+                /*block_based=*/ false,
+            );
+            table_options.set_whole_key_filtering(false);
+        },
+        _ => {},
+    }
+}
+
+fn uses_state_key_extractor(cf_name: ColumnFamilyName) -> bool {
+    cf_name == STATE_VALUE_CF_NAME || cf_name == STATE_VALUE_BY_KEY_HASH_CF_NAME
+}
+
 fn with_state_key_extractor_processor(cf_name: ColumnFamilyName, cf_opts: &mut Options) {
-    if cf_name == STATE_VALUE_CF_NAME || cf_name == STATE_VALUE_BY_KEY_HASH_CF_NAME {
+    if uses_state_key_extractor(cf_name) {
         let prefix_extractor =
             SliceTransform::create("state_key_extractor", state_key_extractor, None);
         cf_opts.set_prefix_extractor(prefix_extractor);
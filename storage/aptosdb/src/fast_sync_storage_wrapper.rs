@@ -49,7 +49,7 @@ impl FastSyncStorageWrapper {
             config.storage.get_dir_paths(),
             /*readonly=*/ false,
             config.storage.storage_pruner_config,
-            config.storage.rocksdb_configs,
+            config.storage.rocksdb_configs.clone(),
             config.storage.enable_indexer,
             config.storage.buffered_state_target_items,
             config.storage.max_num_nodes_per_lru_cache_shard,
@@ -76,7 +76,7 @@ impl FastSyncStorageWrapper {
                 StorageDirPaths::from_path(db_dir.as_path()),
                 /*readonly=*/ false,
                 config.storage.storage_pruner_config,
-                config.storage.rocksdb_configs,
+                config.storage.rocksdb_configs.clone(),
                 config.storage.enable_indexer,
                 config.storage.buffered_state_target_items,
                 config.storage.max_num_nodes_per_lru_cache_shard,
@@ -0,0 +1,654 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fork genesis: creates a new network whose genesis state is derived from a snapshot of an
+//! existing network (e.g., for incident rehearsals). The DB is first restored to the chosen
+//! version (see `restore`), then a fork genesis transaction is created on top of it. The fork
+//! genesis overrides the chain ID and the validator set, and reconfigures into a new epoch.
+//! Every modification is recorded in a manifest, which can be verified against the snapshot
+//! by anyone holding it. The fork genesis is applied via `bootstrap` (or by the node itself,
+//! given the genesis transaction and waypoint).
+
+use anyhow::{ensure, format_err, Context, Result};
+use aptos_config::config::{
+    RocksdbConfigs, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_crypto::{bls12381, hash::CryptoHash, HashValue};
+use aptos_db::AptosDB;
+use aptos_storage_interface::{
+    async_proof_fetcher::AsyncProofFetcher, DbReaderWriter, ExecutedTrees,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::{ChainIdResource, NewEpochEvent},
+    chain_id::ChainId,
+    network_address::NetworkAddress,
+    on_chain_config::{ConfigurationResource, ValidatorSet},
+    stake_pool::StakePool,
+    state_store::{state_key::StateKey, state_value::StateValue, StateView, StateViewId},
+    timestamp::TimestampResource,
+    transaction::{ChangeSet, Transaction, Version, WriteSetPayload},
+    validator_config::ValidatorConfig,
+    validator_info::ValidatorInfo,
+    validator_performances::{ValidatorPerformance, ValidatorPerformances},
+    write_set::{WriteOp, WriteSetMut},
+};
+use clap::{Parser, Subcommand};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The file name of the fork genesis transaction
+const GENESIS_TXN_FILE_NAME: &str = "genesis.blob";
+/// The file name of the fork manifest
+const MANIFEST_FILE_NAME: &str = "fork_manifest.json";
+
+/// Creates or verifies a fork genesis (i.e., the genesis of a network forked from a snapshot)
+#[derive(Subcommand)]
+pub enum Command {
+    /// Creates a fork genesis transaction (and its manifest) on top of the snapshot in the DB
+    Create(CreateOpt),
+    /// Verifies a fork genesis transaction against its manifest and the snapshot in the DB
+    Verify(VerifyOpt),
+}
+
+#[derive(Parser)]
+pub struct CreateOpt {
+    /// The DB holding the state snapshot of the source network. The snapshot is taken at
+    /// the latest version in the DB (e.g., the target version of a restore).
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    /// If set, the version the snapshot is expected to be at
+    #[clap(long)]
+    source_version: Option<Version>,
+
+    /// The fork config file (YAML)
+    #[clap(long, value_parser)]
+    fork_config: PathBuf,
+
+    /// The directory to write the fork genesis transaction and manifest to
+    #[clap(long, value_parser)]
+    output_dir: PathBuf,
+}
+
+#[derive(Parser)]
+pub struct VerifyOpt {
+    /// The DB holding the state snapshot of the source network
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    /// The fork genesis transaction file
+    #[clap(long, value_parser)]
+    genesis_txn_file: PathBuf,
+
+    /// The fork manifest file
+    #[clap(long, value_parser)]
+    manifest_file: PathBuf,
+}
+
+impl Command {
+    pub fn run(self) -> Result<()> {
+        match self {
+            Command::Create(opt) => opt.run(),
+            Command::Verify(opt) => opt.run(),
+        }
+    }
+}
+
+impl CreateOpt {
+    fn run(self) -> Result<()> {
+        let fork_config: ForkConfig = serde_yaml::from_slice(&fs::read(&self.fork_config)?)
+            .with_context(|| format_err!("Failed to parse the fork config."))?;
+
+        let db = open_db(&self.db_dir)?;
+        let executed_trees = db.reader.get_latest_executed_trees()?;
+        if let Some(source_version) = self.source_version {
+            ensure!(
+                executed_trees.version() == Some(source_version),
+                "The snapshot is at version {:?}, but version {} was expected.",
+                executed_trees.version(),
+                source_version,
+            );
+        }
+
+        let (genesis_txn, manifest) = create_fork_genesis(&db, &executed_trees, fork_config)?;
+        fs::create_dir_all(&self.output_dir)?;
+        fs::write(
+            self.output_dir.join(GENESIS_TXN_FILE_NAME),
+            bcs::to_bytes(&genesis_txn)?,
+        )?;
+        fs::write(
+            self.output_dir.join(MANIFEST_FILE_NAME),
+            serde_json::to_vec_pretty(&manifest)?,
+        )?;
+        println!(
+            "Created the fork genesis at version {} with {} modifications. Genesis txn hash: {}",
+            manifest.source_version + 1,
+            manifest.modifications.len(),
+            manifest.genesis_txn_hash,
+        );
+
+        Ok(())
+    }
+}
+
+impl VerifyOpt {
+    fn run(self) -> Result<()> {
+        let genesis_txn: Transaction = bcs::from_bytes(&fs::read(&self.genesis_txn_file)?)?;
+        let manifest: ForkManifest = serde_json::from_slice(&fs::read(&self.manifest_file)?)
+            .with_context(|| format_err!("Failed to parse the fork manifest."))?;
+
+        let db = open_db(&self.db_dir)?;
+        let executed_trees = db.reader.get_latest_executed_trees()?;
+        manifest.verify(&db, &executed_trees, &genesis_txn)?;
+        println!("Fork genesis verified.");
+
+        Ok(())
+    }
+}
+
+/// A validator of the forked network. The validator must be a validator (i.e., have a
+/// stake pool and validator config) in the snapshot, as its stake pool is used to compute
+/// the validator set on subsequent reconfigurations.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForkValidator {
+    /// The address of the validator's stake pool
+    pub address: AccountAddress,
+    /// The new consensus public key of the validator
+    pub consensus_public_key: bls12381::PublicKey,
+    /// The new validator network addresses of the validator
+    pub validator_network_addresses: Vec<NetworkAddress>,
+    /// The new fullnode network addresses of the validator
+    #[serde(default)]
+    pub fullnode_network_addresses: Vec<NetworkAddress>,
+    /// The voting power of the validator in the first epoch of the fork
+    pub voting_power: u64,
+}
+
+/// The overrides applied to the snapshot to create a fork genesis
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForkConfig {
+    /// The chain ID of the forked network
+    pub chain_id: ChainId,
+    /// The validators of the forked network (replacing the source validator set)
+    pub validators: Vec<ForkValidator>,
+}
+
+/// A single state modification made by a fork genesis
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateModification {
+    /// A human readable description of the modification
+    pub description: String,
+    /// The modified state key
+    pub state_key: String,
+    /// The hash of the value in the snapshot
+    pub old_value_hash: HashValue,
+    /// The hash of the value written by the fork genesis
+    pub new_value_hash: HashValue,
+}
+
+/// The manifest of a fork genesis, i.e., the snapshot it was derived from and all the
+/// modifications it makes. The fork genesis can be re-derived from the snapshot and the
+/// fork config, to verify that it makes no other modifications.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ForkManifest {
+    /// The version of the snapshot
+    pub source_version: Version,
+    /// The state root hash of the snapshot
+    pub source_state_root_hash: HashValue,
+    /// The chain ID of the source network
+    pub source_chain_id: ChainId,
+    /// The epoch of the snapshot (the fork starts at the next epoch)
+    pub source_epoch: u64,
+    /// The fork config the fork genesis was created with
+    pub fork_config: ForkConfig,
+    /// The state modifications made by the fork genesis
+    pub modifications: Vec<StateModification>,
+    /// The hash of the fork genesis transaction
+    pub genesis_txn_hash: HashValue,
+}
+
+impl ForkManifest {
+    /// Verifies that the given genesis transaction and this manifest match the fork
+    /// genesis derived from the snapshot (i.e., the latest state in the DB).
+    pub fn verify(
+        &self,
+        db: &DbReaderWriter,
+        executed_trees: &ExecutedTrees,
+        genesis_txn: &Transaction,
+    ) -> Result<()> {
+        let (expected_genesis_txn, expected_manifest) =
+            create_fork_genesis(db, executed_trees, self.fork_config.clone())?;
+        ensure!(
+            self.source_version == expected_manifest.source_version
+                && self.source_state_root_hash == expected_manifest.source_state_root_hash,
+            "The manifest snapshot (version: {}, root hash: {}) doesn't match the DB (version: {}, root hash: {})!",
+            self.source_version,
+            self.source_state_root_hash,
+            expected_manifest.source_version,
+            expected_manifest.source_state_root_hash,
+        );
+        ensure!(
+            self == &expected_manifest,
+            "The manifest doesn't match the fork genesis derived from the snapshot!"
+        );
+        ensure!(
+            genesis_txn.hash() == expected_genesis_txn.hash(),
+            "The genesis transaction (hash: {}) doesn't match the fork genesis derived from the snapshot (hash: {})!",
+            genesis_txn.hash(),
+            expected_genesis_txn.hash(),
+        );
+        Ok(())
+    }
+}
+
+/// Creates the fork genesis transaction (and its manifest) on top of the latest state in the DB
+pub fn create_fork_genesis(
+    db: &DbReaderWriter,
+    executed_trees: &ExecutedTrees,
+    fork_config: ForkConfig,
+) -> Result<(Transaction, ForkManifest)> {
+    let source_version = executed_trees
+        .version()
+        .ok_or_else(|| format_err!("The DB is empty! A snapshot is required to fork."))?;
+    let state_view = executed_trees.verified_state_view(
+        StateViewId::Miscellaneous,
+        Arc::clone(&db.reader),
+        Arc::new(AsyncProofFetcher::new(db.reader.clone())),
+    )?;
+
+    let mut builder = ForkChangeSetBuilder::new(&state_view);
+
+    // Override the chain ID
+    let chain_id_key = StateKey::resource_typed::<ChainIdResource>(&AccountAddress::ONE)?;
+    let (source_chain_id, _) = builder.get_resource::<ChainIdResource>(&chain_id_key)?;
+    builder.modify(
+        "chain ID",
+        chain_id_key,
+        &ChainIdResource::new(fork_config.chain_id),
+    )?;
+
+    // Override the validator set (and the config of each validator, so that the new keys
+    // and addresses persist across reconfigurations). Each validator must have a stake pool,
+    // as the validator set is recomputed from the stake pools on the next reconfiguration.
+    ensure!(
+        !fork_config.validators.is_empty(),
+        "The fork must have at least one validator!"
+    );
+    let validator_set_key = StateKey::on_chain_config::<ValidatorSet>()?;
+    let (source_validator_set, _) = builder.get_resource::<ValidatorSet>(&validator_set_key)?;
+    let mut active_validators = vec![];
+    for (validator_index, validator) in fork_config.validators.iter().enumerate() {
+        let stake_pool_key = StateKey::resource_typed::<StakePool>(&validator.address)?;
+        builder
+            .get_resource::<StakePool>(&stake_pool_key)
+            .with_context(|| {
+                format_err!(
+                    "The fork validator {} has no stake pool in the snapshot!",
+                    validator.address
+                )
+            })?;
+        let validator_config = ValidatorConfig::new(
+            validator.consensus_public_key.clone(),
+            bcs::to_bytes(&validator.validator_network_addresses)?,
+            bcs::to_bytes(&validator.fullnode_network_addresses)?,
+            validator_index as u64,
+        );
+        builder.modify(
+            &format!("validator config of {}", validator.address),
+            StateKey::resource_typed::<ValidatorConfig>(&validator.address)?,
+            &validator_config,
+        )?;
+        active_validators.push(ValidatorInfo::new(
+            validator.address,
+            validator.voting_power,
+            validator_config,
+        ));
+    }
+    let total_voting_power = fork_config
+        .validators
+        .iter()
+        .map(|validator| validator.voting_power as u128)
+        .sum();
+    builder.modify(
+        "validator set",
+        validator_set_key,
+        &ValidatorSet {
+            scheme: source_validator_set.scheme,
+            active_validators,
+            pending_inactive: vec![],
+            pending_active: vec![],
+            total_voting_power,
+            total_joining_power: 0,
+        },
+    )?;
+
+    // Reset the performance of the validators (which is indexed by the validator index), so
+    // that the performance of the source validators isn't attributed to the fork validators.
+    let validator_performance_key =
+        StateKey::resource_typed::<ValidatorPerformances>(&AccountAddress::ONE)?;
+    builder.modify(
+        "validator performance",
+        validator_performance_key,
+        &ValidatorPerformances {
+            validators: vec![
+                ValidatorPerformance {
+                    successful_proposals: 0,
+                    failed_proposals: 0,
+                };
+                fork_config.validators.len()
+            ],
+        },
+    )?;
+
+    // Reconfigure into a new epoch (at the time of the snapshot)
+    let timestamp_key = StateKey::resource_typed::<TimestampResource>(&AccountAddress::ONE)?;
+    let (timestamp, _) = builder.get_resource::<TimestampResource>(&timestamp_key)?;
+    let configuration_key = StateKey::on_chain_config::<ConfigurationResource>()?;
+    let (configuration, _) = builder.get_resource::<ConfigurationResource>(&configuration_key)?;
+    let next_configuration = configuration.next_epoch(timestamp.timestamp.microseconds);
+    builder.modify("epoch", configuration_key, &next_configuration)?;
+    let new_epoch_event = NewEpochEvent::new(next_configuration.epoch())
+        .as_contract_event(configuration.events().count());
+
+    // Create the genesis transaction and the manifest
+    let (write_set, modifications) = builder.into_inner();
+    let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(ChangeSet::new(
+        write_set.freeze()?,
+        vec![new_epoch_event],
+    )));
+    let manifest = ForkManifest {
+        source_version,
+        source_state_root_hash: executed_trees.state().root_hash(),
+        source_chain_id: source_chain_id.chain_id(),
+        source_epoch: configuration.epoch(),
+        fork_config,
+        modifications,
+        genesis_txn_hash: genesis_txn.hash(),
+    };
+
+    Ok((genesis_txn, manifest))
+}
+
+/// Accumulates the modifications of a fork genesis (on top of the snapshot state)
+struct ForkChangeSetBuilder<'a, S> {
+    state_view: &'a S,
+    write_set: WriteSetMut,
+    modifications: Vec<StateModification>,
+}
+
+impl<'a, S: StateView> ForkChangeSetBuilder<'a, S> {
+    fn new(state_view: &'a S) -> Self {
+        Self {
+            state_view,
+            write_set: WriteSetMut::new(vec![]),
+            modifications: vec![],
+        }
+    }
+
+    /// Returns the (deserialized) resource at the given state key in the snapshot
+    fn get_resource<T: DeserializeOwned>(&self, state_key: &StateKey) -> Result<(T, StateValue)> {
+        let state_value = self
+            .state_view
+            .get_state_value(state_key)?
+            .ok_or_else(|| format_err!("{:?} doesn't exist in the snapshot!", state_key))?;
+        let resource = bcs::from_bytes(state_value.bytes())
+            .with_context(|| format_err!("Failed to deserialize {:?}.", state_key))?;
+        Ok((resource, state_value))
+    }
+
+    /// Overwrites the (existing) value at the given state key, and records the modification.
+    /// The metadata of the value (e.g., the storage deposit) is kept as is.
+    fn modify<T: Serialize>(
+        &mut self,
+        description: &str,
+        state_key: StateKey,
+        new_value: &T,
+    ) -> Result<()> {
+        let old_value = self
+            .state_view
+            .get_state_value(&state_key)?
+            .ok_or_else(|| {
+                format_err!(
+                    "Failed to modify the {}: {:?} doesn't exist in the snapshot!",
+                    description,
+                    state_key
+                )
+            })?;
+        let new_value_bytes = bcs::to_bytes(new_value)?;
+
+        self.modifications.push(StateModification {
+            description: description.to_string(),
+            state_key: format!("{:?}", state_key),
+            old_value_hash: HashValue::sha3_256_of(old_value.bytes()),
+            new_value_hash: HashValue::sha3_256_of(&new_value_bytes),
+        });
+        self.write_set.insert((
+            state_key,
+            WriteOp::Modification {
+                data: new_value_bytes.into(),
+                metadata: old_value.into_metadata(),
+            },
+        ));
+        Ok(())
+    }
+
+    fn into_inner(self) -> (WriteSetMut, Vec<StateModification>) {
+        (self.write_set, self.modifications)
+    }
+}
+
+/// Opens the DB (read-only) holding the snapshot
+fn open_db(db_dir: &Path) -> Result<DbReaderWriter> {
+    let db = AptosDB::open(
+        StorageDirPaths::from_path(db_dir),
+        true, /* readonly */
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfigs::default(),
+        false, /* indexer */
+        BUFFERED_STATE_TARGET_ITEMS,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        None,
+    )?;
+    Ok(DbReaderWriter::new(db))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create_fork_genesis, ForkConfig, ForkValidator};
+    use aptos_cached_packages::aptos_stdlib;
+    use aptos_crypto::{bls12381, PrivateKey, Uniform};
+    use aptos_db::AptosDB;
+    use aptos_executor::{
+        block_executor::BlockExecutor,
+        db_bootstrapper::{generate_waypoint, maybe_bootstrap},
+    };
+    use aptos_executor_test_helpers::{bootstrap_genesis, gen_block_id, gen_ledger_info_with_sigs};
+    use aptos_executor_types::BlockExecutorTrait;
+    use aptos_sdk::{
+        transaction_builder::TransactionFactory,
+        types::{AccountKey, LocalAccount},
+    };
+    use aptos_storage_interface::{state_view::LatestDbStateCheckpointView, DbReaderWriter};
+    use aptos_temppath::TempPath;
+    use aptos_types::{
+        account_address::AccountAddress,
+        account_config::{aptos_test_root_address, ChainIdResource},
+        block_metadata::BlockMetadata,
+        chain_id::ChainId,
+        on_chain_config::{ConfigurationResource, OnChainConfig, ValidatorSet},
+        state_store::MoveResourceExt,
+        test_helpers::transaction_test_helpers::TEST_BLOCK_EXECUTOR_ONCHAIN_CONFIG,
+        transaction::{
+            signature_verified_transaction::into_signature_verified_block, Transaction,
+            WriteSetPayload,
+        },
+        validator_performances::ValidatorPerformances,
+        validator_signer::ValidatorSigner,
+    };
+    use aptos_vm::AptosVM;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_fork_genesis() {
+        // Create a DB with a test genesis (and four validators)
+        let (genesis, _) = aptos_vm_genesis::test_genesis_change_set_and_validators(Some(4));
+        let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
+        let tmp_dir = TempPath::new();
+        let db = DbReaderWriter::new(AptosDB::new_for_test(&tmp_dir));
+        bootstrap_genesis::<AptosVM>(&db, &genesis_txn).unwrap();
+        let state_view = db.reader.latest_state_checkpoint_view().unwrap();
+        let source_validator_set = ValidatorSet::fetch_config(&state_view).unwrap();
+        let source_configuration = ConfigurationResource::fetch_config(&state_view).unwrap();
+        assert_eq!(source_validator_set.active_validators.len(), 4);
+
+        // Create a fork genesis with three of the validators (in reverse order, so that
+        // the validator indices change), and replace their consensus keys.
+        let consensus_private_keys: Vec<_> = (0..3)
+            .map(|_| bls12381::PrivateKey::generate_for_testing())
+            .collect();
+        let validators: Vec<_> = source_validator_set.active_validators[1..]
+            .iter()
+            .rev()
+            .zip(&consensus_private_keys)
+            .map(|(validator, consensus_private_key)| ForkValidator {
+                address: validator.account_address,
+                consensus_public_key: consensus_private_key.public_key(),
+                validator_network_addresses: vec![],
+                fullnode_network_addresses: vec![],
+                voting_power: 100,
+            })
+            .collect();
+        let fork_config = ForkConfig {
+            chain_id: ChainId::new(42),
+            validators: validators.clone(),
+        };
+        let executed_trees = db.reader.get_latest_executed_trees().unwrap();
+        let (fork_genesis_txn, manifest) =
+            create_fork_genesis(&db, &executed_trees, fork_config.clone()).unwrap();
+        assert_eq!(manifest.source_version, 0);
+        assert_eq!(manifest.modifications.len(), 7);
+
+        // Verify the manifest, and that a tampered manifest or genesis fails verification
+        manifest
+            .verify(&db, &executed_trees, &fork_genesis_txn)
+            .unwrap();
+        let mut tampered_manifest = manifest.clone();
+        tampered_manifest.modifications.pop();
+        assert!(tampered_manifest
+            .verify(&db, &executed_trees, &fork_genesis_txn)
+            .is_err());
+        assert!(manifest.verify(&db, &executed_trees, &genesis_txn).is_err());
+
+        // Verify that a fork validator without a stake pool is rejected
+        let mut invalid_fork_config = fork_config.clone();
+        invalid_fork_config.validators[0].address = aptos_test_root_address();
+        assert!(create_fork_genesis(&db, &executed_trees, invalid_fork_config).is_err());
+
+        // Apply the fork genesis, and verify the chain ID, validator set, validator
+        // performance and epoch.
+        let waypoint = generate_waypoint::<AptosVM>(&db, &fork_genesis_txn).unwrap();
+        assert!(maybe_bootstrap::<AptosVM>(&db, &fork_genesis_txn, waypoint)
+            .unwrap()
+            .is_some());
+        assert_eq!(waypoint.version(), 1);
+        let state_view = db.reader.latest_state_checkpoint_view().unwrap();
+        assert_eq!(
+            ChainIdResource::fetch_config(&state_view)
+                .unwrap()
+                .chain_id(),
+            ChainId::new(42)
+        );
+        verify_validator_set(
+            &ValidatorSet::fetch_config(&state_view).unwrap(),
+            &validators,
+        );
+        let validator_performances =
+            ValidatorPerformances::fetch_move_resource(&state_view, &AccountAddress::ONE)
+                .unwrap()
+                .unwrap();
+        assert_eq!(validator_performances.validators.len(), 3);
+        let fork_epoch = ConfigurationResource::fetch_config(&state_view)
+            .unwrap()
+            .epoch();
+        assert_eq!(fork_epoch, source_configuration.epoch() + 1);
+
+        // Reconfigure the forked network, proposed by one of the fork validators
+        let executor = BlockExecutor::<AptosVM>::new(db.clone());
+        let core_resources_account = LocalAccount::new(
+            aptos_test_root_address(),
+            AccountKey::from_private_key(aptos_vm_genesis::GENESIS_KEYPAIR.0.clone()),
+            0,
+        );
+        let txn_factory = TransactionFactory::new(ChainId::new(42));
+        let block_id = gen_block_id(1);
+        let block_metadata = Transaction::BlockMetadata(BlockMetadata::new(
+            block_id,
+            fork_epoch,
+            1,
+            validators[0].address,
+            vec![0],
+            vec![],
+            1,
+        ));
+        let reconfig_txn = core_resources_account.sign_with_transaction_builder(
+            txn_factory.payload(aptos_stdlib::aptos_governance_force_end_epoch_test_only()),
+        );
+        let block = into_signature_verified_block(vec![
+            block_metadata,
+            Transaction::UserTransaction(reconfig_txn),
+        ]);
+        let output = executor
+            .execute_block(
+                (block_id, block).into(),
+                executor.committed_block_id(),
+                TEST_BLOCK_EXECUTOR_ONCHAIN_CONFIG,
+            )
+            .unwrap();
+        let signers: Vec<_> = validators
+            .iter()
+            .zip(consensus_private_keys)
+            .map(|(validator, consensus_private_key)| {
+                ValidatorSigner::new(validator.address, Arc::new(consensus_private_key))
+            })
+            .collect();
+        let ledger_info = gen_ledger_info_with_sigs(fork_epoch, &output, block_id, &signers);
+        executor.commit_blocks(vec![block_id], ledger_info).unwrap();
+
+        // Verify that the validator set (recomputed from the stake pools) keeps the new keys
+        let state_view = db.reader.latest_state_checkpoint_view().unwrap();
+        assert_eq!(
+            ConfigurationResource::fetch_config(&state_view)
+                .unwrap()
+                .epoch(),
+            fork_epoch + 1
+        );
+        verify_validator_set(
+            &ValidatorSet::fetch_config(&state_view).unwrap(),
+            &validators,
+        );
+    }
+
+    /// Verifies that the validator set contains exactly the given fork validators
+    fn verify_validator_set(validator_set: &ValidatorSet, validators: &[ForkValidator]) {
+        assert_eq!(validator_set.active_validators.len(), validators.len());
+        for validator in validators {
+            let validator_info = validator_set
+                .active_validators
+                .iter()
+                .find(|validator_info| validator_info.account_address == validator.address)
+                .unwrap();
+            assert_eq!(
+                validator_info.consensus_public_key(),
+                &validator.consensus_public_key
+            );
+        }
+    }
+}
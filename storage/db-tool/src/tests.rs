@@ -55,6 +55,17 @@ fn test_various_cmd_parsing() {
         "--local-fs-dir",
         ".",
     ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "fork-genesis",
+        "create",
+        "--db-dir",
+        ".",
+        "--fork-config",
+        "fork.yaml",
+        "--output-dir",
+        ".",
+    ]);
     run_cmd(&[
         "aptos-db-tool",
         "backup",
@@ -6,9 +6,9 @@ use aptos_config::config::internal_indexer_db_config::InternalIndexerDBConfig;
 use aptos_db_indexer_schemas::{
     metadata::{MetadataKey, MetadataValue, StateSnapshotProgress},
     schema::{
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
-        indexer_metadata::InternalIndexerMetadataSchema, state_keys::StateKeysSchema,
-        transaction_by_account::TransactionByAccountSchema,
+        account_storage_usage::AccountStorageUsageSchema, event_by_key::EventByKeySchema,
+        event_by_version::EventByVersionSchema, indexer_metadata::InternalIndexerMetadataSchema,
+        state_keys::StateKeysSchema, transaction_by_account::TransactionByAccountSchema,
     },
     utils::{
         error_if_too_many_requested, get_first_seq_num_and_limit, AccountTransactionVersionIter,
@@ -25,14 +25,17 @@ use aptos_types::{
     event::EventKey,
     indexer::indexer_db_reader::Order,
     state_store::{
+        account_storage_usage::AccountStorageUsage,
         state_key::{prefix::StateKeyPrefix, StateKey},
         state_value::StateValue,
     },
     transaction::{AccountTransactionsWithProof, Transaction, Version},
     write_set::{TransactionWrite, WriteSet},
 };
+use dashmap::DashMap;
 use std::{
     cmp::min,
+    collections::{hash_map::Entry, HashMap, HashSet},
     sync::{
         mpsc::{self, Receiver, Sender},
         Arc,
@@ -114,6 +117,10 @@ impl InternalIndexerDB {
         self.get_version(&MetadataKey::TransactionVersion)
     }
 
+    pub fn get_storage_usage_version(&self) -> Result<Option<Version>> {
+        self.get_version(&MetadataKey::StorageUsageVersion)
+    }
+
     pub fn event_enabled(&self) -> bool {
         self.config.enable_event
     }
@@ -126,6 +133,10 @@ impl InternalIndexerDB {
         self.config.enable_statekeys
     }
 
+    pub fn storage_usage_enabled(&self) -> bool {
+        self.config.enable_storage_usage
+    }
+
     pub fn get_inner_db_ref(&self) -> &Arc<DB> {
         &self.db
     }
@@ -173,6 +184,29 @@ impl InternalIndexerDB {
         ))
     }
 
+    /// Returns the storage usage of the given account at the given version (i.e., the
+    /// latest usage recorded at or before the version). Returns None if no usage has
+    /// been recorded for the account.
+    pub fn get_account_storage_usage(
+        &self,
+        address: AccountAddress,
+        version: Version,
+    ) -> Result<Option<AccountStorageUsage>> {
+        let mut iter = self.db.iter::<AccountStorageUsageSchema>()?;
+        iter.seek_for_prev(&(address, version))?;
+
+        Ok(iter
+            .next()
+            .transpose()?
+            .and_then(|((key_address, _version), usage)| {
+                if key_address == address {
+                    Some(usage)
+                } else {
+                    None
+                }
+            }))
+    }
+
     pub fn get_latest_sequence_number(
         &self,
         ledger_version: Version,
@@ -280,6 +314,10 @@ pub struct DBIndexer {
     pub main_db_reader: Arc<dyn DbReader>,
     sender: Sender<Option<SchemaBatch>>,
     committer_handle: Option<thread::JoinHandle<()>>,
+    // The latest storage usages (and the versions they were recorded at) of the accounts
+    // updated by the batches that might not have been committed yet. The storage usage index
+    // is cumulative, so each batch builds on these (instead of waiting for the commits).
+    pending_storage_usages: DashMap<AccountAddress, (Version, AccountStorageUsage)>,
 }
 
 impl Drop for DBIndexer {
@@ -310,6 +348,7 @@ impl DBIndexer {
             main_db_reader: db_reader,
             sender,
             committer_handle: Some(committer_handle),
+            pending_storage_usages: DashMap::new(),
         }
     }
 
@@ -360,9 +399,80 @@ impl DBIndexer {
         Ok(num_of_transaction)
     }
 
+    /// Returns the storage usages recorded by the previous batches that might not have been
+    /// committed yet (the usages of all other accounts can be read from the indexer DB).
+    fn get_pending_storage_usages(&self) -> Result<HashMap<AccountAddress, AccountStorageUsage>> {
+        // Drop the usages that have been committed
+        if let Some(committed_version) = self.indexer_db.get_storage_usage_version()? {
+            self.pending_storage_usages
+                .retain(|_, (version, _)| *version > committed_version);
+        }
+        Ok(self
+            .pending_storage_usages
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().1))
+            .collect())
+    }
+
+    /// Updates the storage usage of the accounts whose state items are written by
+    /// the given write set, and records the updated usages at the given version.
+    fn index_storage_usage(
+        &self,
+        batch: &SchemaBatch,
+        account_storage_usages: &mut HashMap<AccountAddress, AccountStorageUsage>,
+        writeset: &WriteSet,
+        version: Version,
+    ) -> Result<()> {
+        let mut updated_accounts = HashSet::new();
+        for (state_key, write_op) in writeset.iter() {
+            let Some(address) = AccountStorageUsage::get_account(state_key) else {
+                continue;
+            };
+            let usage = match account_storage_usages.entry(address) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(
+                    self.indexer_db
+                        .get_account_storage_usage(address, version)?
+                        .unwrap_or_default(),
+                ),
+            };
+
+            // Replace the previous value of the state item (if any) with the new value
+            if !write_op.is_creation() && version > 0 {
+                if let Some(prev_state_value) = self
+                    .main_db_reader
+                    .get_state_value_by_version(state_key, version - 1)?
+                {
+                    usage.remove_item(state_key, &prev_state_value);
+                }
+            }
+            if let Some(state_value) = write_op.as_state_value() {
+                usage.add_item(state_key, &state_value);
+            }
+            updated_accounts.insert(address);
+        }
+
+        for address in updated_accounts {
+            batch.put::<AccountStorageUsageSchema>(
+                &(address, version),
+                &account_storage_usages[&address],
+            )?;
+        }
+        Ok(())
+    }
+
     pub fn process_a_batch(&self, start_version: Version) -> Result<Version> {
         let mut version = start_version;
         let num_transactions = self.get_num_of_transactions(version)?;
+
+        // The storage usage index is cumulative, so this batch updates the usages recorded
+        // by the previous batches (which might not have been committed yet).
+        let mut account_storage_usages = if self.indexer_db.storage_usage_enabled() {
+            self.get_pending_storage_usages()?
+        } else {
+            HashMap::new()
+        };
+
         let mut db_iter = self.get_main_db_iter(version, num_transactions)?;
         let batch = SchemaBatch::new();
         db_iter.try_for_each(|res| {
@@ -404,6 +514,10 @@ impl DBIndexer {
                     }
                 });
             }
+
+            if self.indexer_db.storage_usage_enabled() {
+                self.index_storage_usage(&batch, &mut account_storage_usages, &writeset, version)?;
+            }
             version += 1;
             Ok::<(), AptosDbError>(())
         })?;
@@ -426,6 +540,16 @@ impl DBIndexer {
                 &MetadataValue::Version(version - 1),
             )?;
         }
+        if self.indexer_db.storage_usage_enabled() {
+            batch.put::<InternalIndexerMetadataSchema>(
+                &MetadataKey::StorageUsageVersion,
+                &MetadataValue::Version(version - 1),
+            )?;
+            for (address, usage) in account_storage_usages {
+                self.pending_storage_usages
+                    .insert(address, (version - 1, usage));
+            }
+        }
         batch.put::<InternalIndexerMetadataSchema>(
             &MetadataKey::LatestVersion,
             &MetadataValue::Version(version - 1),
@@ -481,6 +605,19 @@ impl DBIndexer {
         )
     }
 
+    pub fn get_account_storage_usage(
+        &self,
+        address: AccountAddress,
+        ledger_version: Version,
+    ) -> Result<AccountStorageUsage> {
+        self.indexer_db
+            .ensure_cover_ledger_version(ledger_version)?;
+        Ok(self
+            .indexer_db
+            .get_account_storage_usage(address, ledger_version)?
+            .unwrap_or_default())
+    }
+
     pub fn get_events(
         &self,
         event_key: &EventKey,
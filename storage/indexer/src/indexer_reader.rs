@@ -9,6 +9,7 @@ use aptos_types::{
     event::EventKey,
     indexer::indexer_db_reader::{IndexerReader, Order},
     state_store::{
+        account_storage_usage::AccountStorageUsage,
         state_key::{prefix::StateKeyPrefix, StateKey},
         state_value::StateValue,
         table::{TableHandle, TableInfo},
@@ -153,4 +154,19 @@ impl IndexerReader for IndexerReaders {
         }
         anyhow::bail!("DB indexer reader is not available")
     }
+
+    fn get_account_storage_usage(
+        &self,
+        address: AccountAddress,
+        ledger_version: Version,
+    ) -> anyhow::Result<AccountStorageUsage> {
+        if let Some(db_indexer_reader) = &self.db_indexer_reader {
+            if db_indexer_reader.indexer_db.storage_usage_enabled() {
+                return Ok(db_indexer_reader.get_account_storage_usage(address, ledger_version)?);
+            } else {
+                anyhow::bail!("Internal storage usage index is not enabled")
+            }
+        }
+        anyhow::bail!("DB indexer reader is not available")
+    }
 }
@@ -0,0 +1,67 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an account storage usage index via which the
+//! storage used by the state items of an account (and the storage deposits paid for them) can be
+//! found at any version. A new record is written for each version that changes the usage, so the
+//! usage at a given version is the latest record at (or before) that version.
+//!
+//! ```text
+//! |<-------key------->|<-value->|
+//! | address | txn_ver |  usage  |
+//! ```
+
+use crate::{schema::ACCOUNT_STORAGE_USAGE_CF_NAME, utils::ensure_slice_len_eq};
+use anyhow::Result;
+use aptos_schemadb::{
+    define_pub_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use aptos_types::{
+    account_address::AccountAddress, state_store::account_storage_usage::AccountStorageUsage,
+    transaction::Version,
+};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::{convert::TryFrom, mem::size_of};
+
+define_pub_schema!(
+    AccountStorageUsageSchema,
+    Key,
+    AccountStorageUsage,
+    ACCOUNT_STORAGE_USAGE_CF_NAME
+);
+
+type Key = (AccountAddress, Version);
+
+impl KeyCodec<AccountStorageUsageSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref account_address, version) = *self;
+
+        let mut encoded = account_address.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let version = (&data[AccountAddress::LENGTH..]).read_u64::<BigEndian>()?;
+
+        Ok((address, version))
+    }
+}
+
+impl ValueCodec<AccountStorageUsageSchema> for AccountStorageUsage {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,19 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use aptos_schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        address in any::<AccountAddress>(),
+        version in any::<Version>(),
+        usage in any::<AccountStorageUsage>(),
+    ) {
+        assert_encode_decode::<AccountStorageUsageSchema>(&(address, version), &usage);
+    }
+}
+
+test_no_panic_decoding!(AccountStorageUsageSchema);
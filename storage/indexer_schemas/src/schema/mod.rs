@@ -6,6 +6,7 @@
 //!
 //! All schemas are `pub(crate)` so not shown in rustdoc, refer to the source code to see details.
 
+pub mod account_storage_usage;
 pub mod event_by_key;
 pub mod event_by_version;
 pub mod indexer_metadata;
@@ -22,6 +23,7 @@ pub const EVENT_BY_KEY_CF_NAME: ColumnFamilyName = "event_by_key";
 pub const EVENT_BY_VERSION_CF_NAME: ColumnFamilyName = "event_by_version";
 pub const TRANSACTION_BY_ACCOUNT_CF_NAME: ColumnFamilyName = "transaction_by_account";
 pub const STATE_KEYS_CF_NAME: ColumnFamilyName = "state_keys";
+pub const ACCOUNT_STORAGE_USAGE_CF_NAME: ColumnFamilyName = "account_storage_usage";
 
 pub fn column_families() -> Vec<ColumnFamilyName> {
     vec![
@@ -39,5 +41,6 @@ pub fn internal_indexer_column_families() -> Vec<ColumnFamilyName> {
         EVENT_BY_VERSION_CF_NAME,
         TRANSACTION_BY_ACCOUNT_CF_NAME,
         STATE_KEYS_CF_NAME,
+        ACCOUNT_STORAGE_USAGE_CF_NAME,
     ]
 }
@@ -18,6 +18,10 @@ pub struct NewEpochEvent {
 }
 
 impl NewEpochEvent {
+    pub fn new(epoch: u64) -> Self {
+        Self { epoch }
+    }
+
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn dummy() -> Self {
         Self { epoch: 0 }
@@ -8,14 +8,20 @@ use move_core_types::{
     identifier::IdentStr,
     move_resource::{MoveResource, MoveStructType},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub struct ChainIdResource {
     chain_id: u8,
 }
 
 impl ChainIdResource {
+    pub fn new(chain_id: ChainId) -> Self {
+        Self {
+            chain_id: chain_id.id(),
+        }
+    }
+
     pub fn chain_id(&self) -> ChainId {
         ChainId::new(self.chain_id)
     }
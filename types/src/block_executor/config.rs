@@ -14,6 +14,10 @@ pub struct BlockExecutorLocalConfig {
     // If true, we will discard the failed blocks and continue with the next block.
     // (allow_fallback needs to be set)
     pub discard_failed_blocks: bool,
+    // If specified, the total size (in bytes) of the speculative writes buffered during
+    // parallel execution is bounded. Once exceeded, the block falls back to sequential
+    // execution, which applies the writes directly (and buffers no speculative state).
+    pub speculative_write_budget_bytes: Option<u64>,
 }
 
 /// Configuration from on-chain configuration, that is
@@ -73,6 +77,7 @@ impl BlockExecutorConfig {
                 concurrency_level,
                 allow_fallback: true,
                 discard_failed_blocks: false,
+                speculative_write_budget_bytes: None,
             },
             onchain: BlockExecutorConfigFromOnchain::new_no_block_limit(),
         }
@@ -87,6 +92,7 @@ impl BlockExecutorConfig {
                 concurrency_level,
                 allow_fallback: true,
                 discard_failed_blocks: false,
+                speculative_write_budget_bytes: None,
             },
             onchain: BlockExecutorConfigFromOnchain::new_maybe_block_limit(maybe_block_gas_limit),
         }
@@ -6,6 +6,7 @@ use crate::{
     contract_event::EventWithVersion,
     event::EventKey,
     state_store::{
+        account_storage_usage::AccountStorageUsage,
         state_key::{prefix::StateKeyPrefix, StateKey},
         state_value::StateValue,
         table::{TableHandle, TableInfo},
@@ -57,6 +58,12 @@ pub trait IndexerReader: Send + Sync {
         version: Version,
     ) -> Result<Box<dyn Iterator<Item = Result<(StateKey, StateValue)>> + '_>>;
 
+    fn get_account_storage_usage(
+        &self,
+        address: AccountAddress,
+        ledger_version: Version,
+    ) -> Result<AccountStorageUsage>;
+
     fn get_latest_internal_indexer_ledger_version(&self) -> Result<Option<Version>>;
 
     #[cfg(any(test, feature = "fuzzing"))]
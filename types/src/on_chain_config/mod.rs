@@ -241,6 +241,16 @@ impl ConfigurationResource {
         &self.events
     }
 
+    /// Returns the configuration of the next epoch (i.e., after a reconfiguration at the
+    /// given time). Note: the caller is responsible for emitting the new epoch event.
+    pub fn next_epoch(&self, reconfiguration_time_usecs: u64) -> Self {
+        Self {
+            epoch: self.epoch + 1,
+            last_reconfiguration_time: reconfiguration_time_usecs,
+            events: EventHandle::new(*self.events.key(), self.events.count() + 1),
+        }
+    }
+
     #[cfg(feature = "fuzzing")]
     pub fn bump_epoch_for_test(&self) -> Self {
         let epoch = self.epoch + 1;
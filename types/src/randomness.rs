@@ -101,6 +101,49 @@ impl OnChainConfig for PerBlockRandomness {
     const TYPE_IDENTIFIER: &'static str = "PerBlockRandomness";
 }
 
+/// The randomness seed used by a transaction, as recorded in the (debug-only) randomness
+/// seed sidecar.
+///
+/// The randomness natives derive the randomness of a transaction from the seed of its block,
+/// the transaction hash and a per-transaction counter, so the seed (together with the
+/// transaction itself) is enough to reproduce the randomness consumed by the transaction.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RandomnessSeedRecord {
+    pub txn_hash: HashValue,
+    pub epoch: u64,
+    pub round: Round,
+    pub block_id: HashValue,
+    #[serde(with = "hex")]
+    pub seed: Vec<u8>,
+}
+
+impl RandomnessSeedRecord {
+    pub fn new(
+        txn_hash: HashValue,
+        epoch: u64,
+        round: Round,
+        block_id: HashValue,
+        seed: Vec<u8>,
+    ) -> Self {
+        Self {
+            txn_hash,
+            epoch,
+            round,
+            block_id,
+            seed,
+        }
+    }
+
+    /// Returns the per-block randomness (i.e., the on-chain resource) for the recorded seed
+    pub fn per_block_randomness(&self) -> PerBlockRandomness {
+        PerBlockRandomness {
+            epoch: self.epoch,
+            round: self.round,
+            seed: Some(self.seed.clone()),
+        }
+    }
+}
+
 #[derive(Clone, SilentDebug)]
 pub struct RandKeys {
     // augmented secret / public key share of this validator, obtained from the DKG transcript of last epoch
@@ -2,6 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{account_address::AccountAddress, event::EventHandle};
+use move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    move_resource::{MoveResource, MoveStructType},
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,6 +33,13 @@ pub struct StakePool {
     pub leave_validator_set_events: EventHandle,
 }
 
+impl MoveStructType for StakePool {
+    const MODULE_NAME: &'static IdentStr = ident_str!("stake");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("StakePool");
+}
+
+impl MoveResource for StakePool {}
+
 impl StakePool {
     pub fn get_total_staked_amount(&self) -> u64 {
         self.active + self.inactive + self.pending_active + self.pending_inactive
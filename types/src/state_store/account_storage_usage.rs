@@ -0,0 +1,142 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::AccountAddress,
+    state_store::{
+        state_key::{inner::StateKeyInner, StateKey},
+        state_value::StateValue,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// The storage used by the state items (i.e., resources, resource groups and modules)
+/// stored under a single account, along with the storage deposits paid for the items.
+/// Table items are not attributed to any account.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+pub struct AccountStorageUsage {
+    /// The number of resources (and resource groups) stored under the account
+    pub resource_count: u64,
+    /// The number of modules stored under the account
+    pub module_count: u64,
+    /// The total size of the state items (keys and values) stored under the account
+    pub bytes: u64,
+    /// The total slot deposit paid for the state items
+    pub slot_deposit: u64,
+    /// The total bytes deposit paid for the state items
+    pub bytes_deposit: u64,
+}
+
+impl AccountStorageUsage {
+    /// Returns the account the given state item is attributed to (if any)
+    pub fn get_account(state_key: &StateKey) -> Option<AccountAddress> {
+        match state_key.inner() {
+            StateKeyInner::AccessPath(access_path) => Some(access_path.address),
+            StateKeyInner::TableItem { .. } | StateKeyInner::Raw(_) => None,
+        }
+    }
+
+    pub fn total_deposit(&self) -> u64 {
+        self.slot_deposit + self.bytes_deposit
+    }
+
+    pub fn add_item(&mut self, state_key: &StateKey, state_value: &StateValue) {
+        if Self::is_module(state_key) {
+            self.module_count += 1;
+        } else {
+            self.resource_count += 1;
+        }
+        self.bytes += (state_key.size() + state_value.size()) as u64;
+        self.slot_deposit += state_value.metadata().slot_deposit();
+        self.bytes_deposit += state_value.metadata().bytes_deposit();
+    }
+
+    pub fn remove_item(&mut self, state_key: &StateKey, state_value: &StateValue) {
+        if Self::is_module(state_key) {
+            self.module_count = self.module_count.saturating_sub(1);
+        } else {
+            self.resource_count = self.resource_count.saturating_sub(1);
+        }
+        self.bytes = self
+            .bytes
+            .saturating_sub((state_key.size() + state_value.size()) as u64);
+        self.slot_deposit = self
+            .slot_deposit
+            .saturating_sub(state_value.metadata().slot_deposit());
+        self.bytes_deposit = self
+            .bytes_deposit
+            .saturating_sub(state_value.metadata().bytes_deposit());
+    }
+
+    fn is_module(state_key: &StateKey) -> bool {
+        matches!(state_key.inner(), StateKeyInner::AccessPath(access_path) if access_path.is_code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AccountStorageUsage;
+    use crate::{
+        on_chain_config::CurrentTimeMicroseconds,
+        state_store::{
+            state_key::StateKey,
+            state_value::{StateValue, StateValueMetadata},
+            table::TableHandle,
+        },
+    };
+    use move_core_types::{
+        account_address::AccountAddress, ident_str, language_storage::StructTag,
+    };
+
+    #[test]
+    fn test_account_storage_usage() {
+        // Verify that only access paths are attributed to accounts
+        let address = AccountAddress::random();
+        let struct_tag = StructTag {
+            address,
+            module: ident_str!("object").to_owned(),
+            name: ident_str!("ObjectGroup").to_owned(),
+            type_args: vec![],
+        };
+        let resource_key = StateKey::resource_group(&address, &struct_tag);
+        let module_key = StateKey::module(&address, ident_str!("coin"));
+        let table_item_key = StateKey::table_item(&TableHandle(address), &[1, 2, 3]);
+        assert_eq!(
+            AccountStorageUsage::get_account(&resource_key),
+            Some(address)
+        );
+        assert_eq!(AccountStorageUsage::get_account(&module_key), Some(address));
+        assert_eq!(AccountStorageUsage::get_account(&table_item_key), None);
+
+        // Add a resource and a module, and verify the usage
+        let mut metadata =
+            StateValueMetadata::new(50, 10, &CurrentTimeMicroseconds { microseconds: 0 });
+        let resource_value = StateValue::new_with_metadata(vec![0; 100].into(), metadata.clone());
+        metadata.set_bytes_deposit(20);
+        let module_value = StateValue::new_with_metadata(vec![0; 200].into(), metadata);
+        let mut usage = AccountStorageUsage::default();
+        usage.add_item(&resource_key, &resource_value);
+        usage.add_item(&module_key, &module_value);
+        let expected_usage = AccountStorageUsage {
+            resource_count: 1,
+            module_count: 1,
+            bytes: (resource_key.size() + module_key.size() + 300) as u64,
+            slot_deposit: 100,
+            bytes_deposit: 30,
+        };
+        assert_eq!(usage, expected_usage);
+        assert_eq!(usage.total_deposit(), 130);
+
+        // Remove the module, and verify the usage
+        usage.remove_item(&module_key, &module_value);
+        let expected_usage = AccountStorageUsage {
+            resource_count: 1,
+            module_count: 0,
+            bytes: (resource_key.size() + 100) as u64,
+            slot_deposit: 50,
+            bytes_deposit: 10,
+        };
+        assert_eq!(usage, expected_usage);
+    }
+}
@@ -16,6 +16,7 @@ use bytes::Bytes;
 use move_core_types::move_resource::MoveResource;
 use std::{collections::HashMap, ops::Deref};
 
+pub mod account_storage_usage;
 pub mod errors;
 pub mod in_memory_state_view;
 pub mod state_key;